@@ -0,0 +1,285 @@
+// Generated by build.rs from the gonhanh_core C ABI; do not edit
+package org.gonhanh.core
+
+import com.sun.jna.Callback
+import com.sun.jna.Library
+import com.sun.jna.Native
+import com.sun.jna.Pointer
+import com.sun.jna.Structure
+
+const val GONHANH_ABI_VERSION = 2
+const val GONHANH_MAX_CHARS = 256
+const val GONHANH_ACTION_NONE = 0
+const val GONHANH_ACTION_SEND = 1
+const val GONHANH_ACTION_RESTORE = 2
+const val GONHANH_ACTION_REPLACE = 3 // select backspace chars, type chars over them
+const val GONHANH_FLAG_KEY_CONSUMED = 0x01
+const val GONHANH_FLAG_CURSOR_MOVE = 0x02 // press Left ime_cursor_offset() times
+const val GONHANH_FLAG_SNIPPET = 0x04 // call ime_snippet_next()
+const val GONHANH_FLAG_DECOMPOSED = 0x08 // backspace counts NFD code points
+const val GONHANH_WORD_VALID = 0
+const val GONHANH_WORD_INVALID_INITIAL = 1
+const val GONHANH_WORD_INVALID_FINAL = 2
+const val GONHANH_WORD_INVALID_SPELLING = 3
+const val GONHANH_WORD_INVALID_VOWEL_PATTERN = 4
+const val GONHANH_WORD_NO_VOWEL = 5
+const val GONHANH_WORD_INVALID_TONE = 6 // huyền/hỏi/ngã before p, t, c, ch
+const val GONHANH_METHOD_TELEX = 0
+const val GONHANH_METHOD_VNI = 1
+const val GONHANH_METHOD_SIMPLE_TELEX = 2 // w only as horn/breve, ] [ → ư ơ
+const val GONHANH_METHOD_TELEX_VNI = 3 // Telex letters and VNI digits together
+const val GONHANH_KEY_NONE = 0xFFFF
+const val GONHANH_STRICTNESS_STANDARD = 0
+const val GONHANH_STRICTNESS_STRICT = 1 // no foreign initials or final k
+const val GONHANH_STRICTNESS_RELAXED = 2 // chat: "ko", foreign initials, loan rhymes
+const val GONHANH_VARIANT_SOUTHERN = 0x01 // "dzô", "zậy", "bển"
+const val GONHANH_VARIANT_NORTHERN = 0x02 // "chẩy", "dầy", "quí"
+const val GONHANH_VARIANT_LOANWORDS = 0x04 // "pốt", "sịp", "okê"
+const val GONHANH_CAPITALIZE_CANCEL = 0 // drop a pending capital (default)
+const val GONHANH_CAPITALIZE_KEEP = 1 // keep it: quotes, brackets
+const val GONHANH_CAPITALIZE_END_SENTENCE = 2 // . ! ?
+const val GONHANH_EVENT_REPEATED_WORD = 1 // text = the repeated word ("của")
+const val GONHANH_EVENT_METHOD_CHANGED = 2 // text = "telex", "vni", "simple-telex", "telex-vni"
+const val GONHANH_EVENT_PROFILE_CHANGED = 3 // text = the new profile's name
+const val GONHANH_EVENT_ENABLED_CHANGED = 4 // text = "on", "off" (per-app state, ime_per_app_enabled)
+const val GONHANH_EVENT_TONE_STYLE_CHANGED = 5 // text = "new", "traditional" (ime_auto_tone_style)
+const val GONHANH_EVENT_TITLE_CASE_CHANGED = 6 // text = "on", "off" (GONHANH_HOTKEY_TITLE_CASE)
+const val GONHANH_HOTKEY_CYCLE_METHOD = 0 // Telex → VNI → Telex
+const val GONHANH_HOTKEY_NEXT_PROFILE = 1 // next profile, in name order
+const val GONHANH_HOTKEY_UNDO_LAST = 2 // as ime_undo_last, result with the key
+const val GONHANH_HOTKEY_TITLE_CASE = 3 // flip Title Case mode
+const val GONHANH_TONE_TRADITIONAL = 0 // hòa, khỏe, thủy
+const val GONHANH_TONE_NEW = 1 // hoà, khoẻ, thuỷ (default)
+const val GONHANH_ENCODING_TCVN3 = 0 // ABC, .VnTime fonts
+const val GONHANH_ENCODING_VNI = 1 // VNI-Windows fonts
+const val GONHANH_CONVERT_KEYSTROKES = 0 // "Vieetj" → "Việt" (current method)
+const val GONHANH_CONVERT_STRIP = 1 // "Việt" → "Viet"
+const val GONHANH_CONVERT_TONE_STYLE = 2 // hòa ↔ hoà, as set by ime_tone_style
+const val GONHANH_CONVERT_FROM_TCVN3 = 3 // TCVN3 pasted as Latin-1 → Unicode
+const val GONHANH_CONVERT_FROM_VNI = 4 // VNI pasted as Latin-1 → Unicode
+const val GONHANH_TERMINAL_OFF = 0 // backspaces + new text (default)
+const val GONHANH_TERMINAL_COMPOSE = 1 // send each word once, finished
+const val GONHANH_TERMINAL_PASSTHROUGH = 2 // engine off for the app
+const val GONHANH_SEND_FAST = 0 // backspaces and text as separate injections
+const val GONHANH_SEND_SLOW = 1 // one event per character, delay_ms apart
+const val GONHANH_SEND_ATOMIC = 2 // backspaces and text in one injection
+const val GONHANH_SEND_PASTE = 3 // clipboard + Ctrl+V
+const val GONHANH_SEND_PASSTHROUGH = 4 // leave keys alone (remote desktop)
+const val GONHANH_BACKSPACE_KEY = 0
+const val GONHANH_BACKSPACE_UNICODE = 1 // U+0008 as a Unicode character
+const val GONHANH_QUIRK_KEYUP_RACE = 0x01 // swallow key-up of consumed keys
+const val GONHANH_QUIRK_BATCH = 0x02 // send the whole replacement at once
+const val GONHANH_QUIRK_COALESCE = 0x04 // merge quick successive replacements
+
+@Structure.FieldOrder("chars", "action", "backspace", "count", "flags")
+class ImeResult(p: Pointer? = null) : Structure(p) {
+    @JvmField var chars: IntArray = IntArray(GONHANH_MAX_CHARS)
+    @JvmField var action: Byte = 0
+    @JvmField var backspace: Byte = 0
+    @JvmField var count: Byte = 0
+    @JvmField var flags: Byte = 0
+
+    init {
+        if (p != null) read()
+    }
+}
+
+@Structure.FieldOrder("method", "backspace", "flags", "delay_ms", "settle_ms")
+class ImeAppQuirk(p: Pointer? = null) : Structure(p) {
+    @JvmField var method: Byte = 0
+    @JvmField var backspace: Byte = 0
+    @JvmField var flags: Byte = 0
+    @JvmField var delay_ms: Short = 0
+    @JvmField var settle_ms: Short = 0
+
+    init {
+        if (p != null) read()
+    }
+}
+
+/**
+ * The gonhanh_core C ABI. C `bool` is a Byte (0 or 1), strings go in as
+ * UTF-8, and allocated strings come back as a Pointer to free with
+ * ime_free_string. Read an ImeResult with `ImeResult(pointer)`.
+ */
+interface GonhanhCore : Library {
+    fun interface ImeEventCallback : Callback {
+        fun invoke(event: Byte, text: String?)
+    }
+
+    /** The returned text must stay valid until the hook returns */
+    fun interface ImeWordHook : Callback {
+        fun invoke(word: String?): Pointer?
+    }
+
+    fun ime_abi_version(): Int
+    fun ime_add_english_word(word: String?)
+    fun ime_add_proper_noun(name: String?): Byte
+    fun ime_add_shortcut(trigger: String?, replacement: String?)
+    fun ime_add_teencode(word: String?): Byte
+    fun ime_allow_foreign_consonants(enabled: Byte)
+    fun ime_app_quirk(app: String?, out: ImeAppQuirk?): Byte
+    fun ime_auto_capitalize(enabled: Byte)
+    fun ime_auto_detect_method(enabled: Byte)
+    fun ime_auto_english_switch(words: Byte)
+    fun ime_auto_restore_triggers(triggers: Byte)
+    fun ime_auto_tone_style(enabled: Byte)
+    fun ime_backspace_undo_restore(enabled: Byte)
+    fun ime_bracket_shortcut(enabled: Byte)
+    fun ime_candidates(): Pointer?
+    fun ime_capitalize_rule(ch: Int, rule: Byte)
+    fun ime_caps_lock_recase(enabled: Byte)
+    fun ime_clear()
+    fun ime_clear_all()
+    fun ime_clear_english_words()
+    fun ime_clear_shortcuts()
+    fun ime_clear_teencode()
+    fun ime_code_context(enabled: Byte)
+    fun ime_config_dir(): Pointer?
+    fun ime_config_get(key: String?): Pointer?
+    fun ime_config_keys(): Pointer?
+    fun ime_config_set(key: String?, value: String?): Byte
+    fun ime_convert_text(text: String?, command: Byte): Pointer?
+    fun ime_cursor_offset(): Byte
+    fun ime_decomposed_backspace(enabled: Byte)
+    fun ime_dictionary_load(list: Byte, path: String?): Byte
+    fun ime_dictionary_reset(list: Byte)
+    fun ime_dictionary_version(list: Byte): Int
+    fun ime_digit_policy(policy: Byte)
+    fun ime_dump_state(): Pointer?
+    fun ime_enabled(enabled: Byte)
+    fun ime_english_auto_restore(enabled: Byte)
+    fun ime_english_capital_i(enabled: Byte)
+    fun ime_english_hold(held: Byte)
+    fun ime_english_suspended(): Byte
+    fun ime_esc_restore(enabled: Byte)
+    fun ime_free(r: Pointer?)
+    fun ime_free_string(s: Pointer?)
+    fun ime_free_tone(enabled: Byte)
+    fun ime_from_legacy(bytes: String?, encoding: Byte): Pointer?
+    fun ime_get_buffer(out: Pointer?, max_len: Long): Long
+    fun ime_hotkey(action: Byte, key: Short, ctrl: Byte, shift: Byte)
+    fun ime_hyphen_soft_boundary(enabled: Byte)
+    fun ime_init()
+    fun ime_initial_w_literal(enabled: Byte)
+    fun ime_key(key: Short, caps: Byte, ctrl: Byte): Pointer?
+    fun ime_key_ext(key: Short, caps: Byte, ctrl: Byte, shift: Byte): Pointer?
+    fun ime_key_repeat(key: Short, caps: Byte, ctrl: Byte, shift: Byte): Pointer?
+    fun ime_key_with_char(key: Short, caps: Byte, ctrl: Byte, shift: Byte, char_code: Int): Pointer?
+    fun ime_latency_enabled(enabled: Byte)
+    fun ime_latency_json(): Pointer?
+    fun ime_latency_reset()
+    fun ime_learning_enabled(enabled: Byte)
+    fun ime_learning_load(path: String?): Byte
+    fun ime_learning_reset()
+    fun ime_learning_save(path: String?): Byte
+    fun ime_load_config_json(): Pointer?
+    fun ime_log_file(path: String?): Byte
+    fun ime_log_level(level: Byte)
+    fun ime_macro_save(trigger: String?): Byte
+    fun ime_macro_start()
+    fun ime_macro_stop(): Pointer?
+    fun ime_managed_load(): Byte
+    fun ime_managed_locked(key: String?): Byte
+    fun ime_method(method: Byte)
+    fun ime_modern(modern: Byte)
+    fun ime_network_allowed(): Byte
+    fun ime_normalize_tone(text: String?, style: Byte): Pointer?
+    fun ime_notify_paste(text: String?)
+    fun ime_notify_select_all()
+    fun ime_notify_text_changed()
+    fun ime_per_app_enabled(enabled: Byte)
+    fun ime_profile_active(): Pointer?
+    fun ime_profile_delete(name: String?): Byte
+    fun ime_profile_list(): Pointer?
+    fun ime_profile_switch(name: String?): Byte
+    fun ime_profiles_load(dir: String?): Byte
+    fun ime_profiles_save(dir: String?): Byte
+    fun ime_proper_noun_capitalize(enabled: Byte)
+    fun ime_quirks_load(path: String?): Byte
+    fun ime_quirks_reset()
+    fun ime_quirks_version(): Int
+    fun ime_reasons(): Pointer?
+    fun ime_remove_english_word(word: String?): Byte
+    fun ime_remove_key(method: Byte, key: Short)
+    fun ime_remove_shortcut(trigger: String?)
+    fun ime_repeat_policy(policy: Byte)
+    fun ime_repeated_word_check(enabled: Byte)
+    fun ime_reset_capitalize_rules()
+    fun ime_restore_key(key: Short)
+    fun ime_restore_state(app: String?): Byte
+    fun ime_restore_word(word: String?)
+    fun ime_result_action(r: Pointer?): Byte
+    fun ime_result_backspace(r: Pointer?): Byte
+    fun ime_result_chars(r: Pointer?): Pointer?
+    fun ime_result_count(r: Pointer?): Byte
+    fun ime_result_flags(r: Pointer?): Byte
+    fun ime_revert_policy(policy: Byte)
+    fun ime_save_config_json(json: String?): Byte
+    fun ime_save_state(app: String?): Byte
+    fun ime_select_candidate(index: Int): Pointer?
+    fun ime_selection_replace(enabled: Byte)
+    fun ime_self_test(): Pointer?
+    fun ime_set_active_app(app: String?)
+    fun ime_set_app_enabled(app: String?, state: Byte)
+    fun ime_set_app_terminal_mode(app: String?, mode: Byte)
+    fun ime_set_clipboard(text: String?)
+    fun ime_set_event_callback(callback: ImeEventCallback?)
+    fun ime_set_shortcut_apps(trigger: String?, only: String?, never: String?)
+    fun ime_set_utc_offset(minutes: Int)
+    fun ime_set_word_hook(hook: ImeWordHook?)
+    fun ime_settings_export(path: String?): Byte
+    fun ime_settings_import(path: String?): Byte
+    fun ime_skip_w_shortcut(skip: Byte)
+    fun ime_snippet_active(): Byte
+    fun ime_snippet_next(): Int
+    fun ime_snippet_select(): Byte
+    fun ime_spell_check(enabled: Byte)
+    fun ime_spelling_autofix(enabled: Byte)
+    fun ime_spelling_variants(variants: Byte)
+    fun ime_stats_enabled(enabled: Byte)
+    fun ime_stats_json(): Pointer?
+    fun ime_stats_load(path: String?): Byte
+    fun ime_stats_reset()
+    fun ime_stats_save(path: String?): Byte
+    fun ime_strip_diacritics(text: String?): Pointer?
+    fun ime_symbol_pack(enabled: Byte)
+    fun ime_teencode_passthrough(enabled: Byte)
+    fun ime_terminal_mode(mode: Byte)
+    fun ime_terminal_take(): Pointer?
+    fun ime_title_case(enabled: Byte)
+    fun ime_to_legacy(text: String?, encoding: Byte): Pointer?
+    fun ime_toggle_title_case(): Byte
+    fun ime_tone_renderings(word: String?): Pointer?
+    fun ime_tone_style(style: Byte)
+    fun ime_trace_start()
+    fun ime_trace_stop(): Pointer?
+    fun ime_undo_last(): Pointer?
+    fun ime_unicode_input(enabled: Byte)
+    fun ime_url_passthrough(enabled: Byte)
+    fun ime_user_dictionaries_reload(dir: String?): Byte
+    fun ime_user_dictionary_words(): Int
+    fun ime_validate_word(word: String?): Byte
+    fun ime_validation_strictness(profile: Byte)
+    fun ime_word_hooks(enabled: Byte)
+    fun update_check(current: String?, repo: String?): Pointer?
+    fun update_proxy(url: String?): Byte
+    fun update_record_check(): Byte
+    fun update_schedule(path: String?, interval_secs: Long, jitter_secs: Long): Byte
+    fun update_should_check_now(): Byte
+    fun version_compare(v1: String?, v2: String?): Int
+    fun version_has_update(current: String?, latest: String?): Int
+
+    companion object {
+        /** Load the library; fails if it was built for another ABI version */
+        fun load(name: String = "gonhanh_core"): GonhanhCore {
+            val options = mapOf(Library.OPTION_STRING_ENCODING to "UTF-8")
+            val core = Native.load(name, GonhanhCore::class.java, options)
+            val version = core.ime_abi_version()
+            check(version == GONHANH_ABI_VERSION) {
+                "$name: ABI version $version, expected $GONHANH_ABI_VERSION"
+            }
+            return core
+        }
+    }
+}
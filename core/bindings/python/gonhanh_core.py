@@ -0,0 +1,305 @@
+# Generated by build.rs from the gonhanh_core C ABI; do not edit
+"""ctypes bindings for gonhanh_core
+
+    import gonhanh_core
+    core = gonhanh_core.load("./libgonhanh_core.so")
+    core.ime_init()
+    r = core.ime_key(0, False, False)  # macOS keycode of A
+    ...
+    core.ime_free(r)
+
+Strings go in as UTF-8 bytes. Functions returning an allocated string
+return a raw pointer: read it with take_string, which also frees it.
+"""
+
+import ctypes
+from ctypes import (
+    POINTER,
+    c_bool,
+    c_char_p,
+    c_int32,
+    c_int64,
+    c_uint8,
+    c_uint16,
+    c_uint32,
+    c_uint64,
+    c_void_p,
+)
+
+GONHANH_ABI_VERSION = 2
+GONHANH_MAX_CHARS = 256
+GONHANH_ACTION_NONE = 0
+GONHANH_ACTION_SEND = 1
+GONHANH_ACTION_RESTORE = 2
+GONHANH_ACTION_REPLACE = 3  # select backspace chars, type chars over them
+GONHANH_FLAG_KEY_CONSUMED = 0x01
+GONHANH_FLAG_CURSOR_MOVE = 0x02  # press Left ime_cursor_offset() times
+GONHANH_FLAG_SNIPPET = 0x04  # call ime_snippet_next()
+GONHANH_FLAG_DECOMPOSED = 0x08  # backspace counts NFD code points
+GONHANH_WORD_VALID = 0
+GONHANH_WORD_INVALID_INITIAL = 1
+GONHANH_WORD_INVALID_FINAL = 2
+GONHANH_WORD_INVALID_SPELLING = 3
+GONHANH_WORD_INVALID_VOWEL_PATTERN = 4
+GONHANH_WORD_NO_VOWEL = 5
+GONHANH_WORD_INVALID_TONE = 6  # huyền/hỏi/ngã before p, t, c, ch
+GONHANH_METHOD_TELEX = 0
+GONHANH_METHOD_VNI = 1
+GONHANH_METHOD_SIMPLE_TELEX = 2  # w only as horn/breve, ] [ → ư ơ
+GONHANH_METHOD_TELEX_VNI = 3  # Telex letters and VNI digits together
+GONHANH_KEY_NONE = 0xFFFF
+GONHANH_STRICTNESS_STANDARD = 0
+GONHANH_STRICTNESS_STRICT = 1  # no foreign initials or final k
+GONHANH_STRICTNESS_RELAXED = 2  # chat: "ko", foreign initials, loan rhymes
+GONHANH_VARIANT_SOUTHERN = 0x01  # "dzô", "zậy", "bển"
+GONHANH_VARIANT_NORTHERN = 0x02  # "chẩy", "dầy", "quí"
+GONHANH_VARIANT_LOANWORDS = 0x04  # "pốt", "sịp", "okê"
+GONHANH_CAPITALIZE_CANCEL = 0  # drop a pending capital (default)
+GONHANH_CAPITALIZE_KEEP = 1  # keep it: quotes, brackets
+GONHANH_CAPITALIZE_END_SENTENCE = 2  # . ! ?
+GONHANH_EVENT_REPEATED_WORD = 1  # text = the repeated word ("của")
+GONHANH_EVENT_METHOD_CHANGED = 2  # text = "telex", "vni", "simple-telex", "telex-vni"
+GONHANH_EVENT_PROFILE_CHANGED = 3  # text = the new profile's name
+GONHANH_EVENT_ENABLED_CHANGED = 4  # text = "on", "off" (per-app state, ime_per_app_enabled)
+GONHANH_EVENT_TONE_STYLE_CHANGED = 5  # text = "new", "traditional" (ime_auto_tone_style)
+GONHANH_EVENT_TITLE_CASE_CHANGED = 6  # text = "on", "off" (GONHANH_HOTKEY_TITLE_CASE)
+GONHANH_HOTKEY_CYCLE_METHOD = 0  # Telex → VNI → Telex
+GONHANH_HOTKEY_NEXT_PROFILE = 1  # next profile, in name order
+GONHANH_HOTKEY_UNDO_LAST = 2  # as ime_undo_last, result with the key
+GONHANH_HOTKEY_TITLE_CASE = 3  # flip Title Case mode
+GONHANH_TONE_TRADITIONAL = 0  # hòa, khỏe, thủy
+GONHANH_TONE_NEW = 1  # hoà, khoẻ, thuỷ (default)
+GONHANH_ENCODING_TCVN3 = 0  # ABC, .VnTime fonts
+GONHANH_ENCODING_VNI = 1  # VNI-Windows fonts
+GONHANH_CONVERT_KEYSTROKES = 0  # "Vieetj" → "Việt" (current method)
+GONHANH_CONVERT_STRIP = 1  # "Việt" → "Viet"
+GONHANH_CONVERT_TONE_STYLE = 2  # hòa ↔ hoà, as set by ime_tone_style
+GONHANH_CONVERT_FROM_TCVN3 = 3  # TCVN3 pasted as Latin-1 → Unicode
+GONHANH_CONVERT_FROM_VNI = 4  # VNI pasted as Latin-1 → Unicode
+GONHANH_TERMINAL_OFF = 0  # backspaces + new text (default)
+GONHANH_TERMINAL_COMPOSE = 1  # send each word once, finished
+GONHANH_TERMINAL_PASSTHROUGH = 2  # engine off for the app
+GONHANH_SEND_FAST = 0  # backspaces and text as separate injections
+GONHANH_SEND_SLOW = 1  # one event per character, delay_ms apart
+GONHANH_SEND_ATOMIC = 2  # backspaces and text in one injection
+GONHANH_SEND_PASTE = 3  # clipboard + Ctrl+V
+GONHANH_SEND_PASSTHROUGH = 4  # leave keys alone (remote desktop)
+GONHANH_BACKSPACE_KEY = 0
+GONHANH_BACKSPACE_UNICODE = 1  # U+0008 as a Unicode character
+GONHANH_QUIRK_KEYUP_RACE = 0x01  # swallow key-up of consumed keys
+GONHANH_QUIRK_BATCH = 0x02  # send the whole replacement at once
+GONHANH_QUIRK_COALESCE = 0x04  # merge quick successive replacements
+
+
+class ImeResult(ctypes.Structure):
+    _fields_ = [
+        ("chars", c_uint32 * GONHANH_MAX_CHARS),
+        ("action", c_uint8),
+        ("backspace", c_uint8),
+        ("count", c_uint8),
+        ("flags", c_uint8),
+    ]
+
+
+class ImeAppQuirk(ctypes.Structure):
+    _fields_ = [
+        ("method", c_uint8),
+        ("backspace", c_uint8),
+        ("flags", c_uint8),
+        ("delay_ms", c_uint16),
+        ("settle_ms", c_uint16),
+    ]
+
+
+ImeEventCallback = ctypes.CFUNCTYPE(None, c_uint8, c_char_p)
+# The returned text must stay valid until the hook returns
+ImeWordHook = ctypes.CFUNCTYPE(c_void_p, c_char_p)
+
+# name: (argument types, return type)
+_SIGNATURES = {
+    "ime_abi_version": ([], c_uint32),
+    "ime_add_english_word": ([c_char_p], None),
+    "ime_add_proper_noun": ([c_char_p], c_bool),
+    "ime_add_shortcut": ([c_char_p, c_char_p], None),
+    "ime_add_teencode": ([c_char_p], c_bool),
+    "ime_allow_foreign_consonants": ([c_bool], None),
+    "ime_app_quirk": ([c_char_p, POINTER(ImeAppQuirk)], c_bool),
+    "ime_auto_capitalize": ([c_bool], None),
+    "ime_auto_detect_method": ([c_bool], None),
+    "ime_auto_english_switch": ([c_uint8], None),
+    "ime_auto_restore_triggers": ([c_uint8], None),
+    "ime_auto_tone_style": ([c_bool], None),
+    "ime_backspace_undo_restore": ([c_bool], None),
+    "ime_bracket_shortcut": ([c_bool], None),
+    "ime_candidates": ([], c_void_p),
+    "ime_capitalize_rule": ([c_uint32, c_uint8], None),
+    "ime_caps_lock_recase": ([c_bool], None),
+    "ime_clear": ([], None),
+    "ime_clear_all": ([], None),
+    "ime_clear_english_words": ([], None),
+    "ime_clear_shortcuts": ([], None),
+    "ime_clear_teencode": ([], None),
+    "ime_code_context": ([c_bool], None),
+    "ime_config_dir": ([], c_void_p),
+    "ime_config_get": ([c_char_p], c_void_p),
+    "ime_config_keys": ([], c_void_p),
+    "ime_config_set": ([c_char_p, c_char_p], c_bool),
+    "ime_convert_text": ([c_char_p, c_uint8], c_void_p),
+    "ime_cursor_offset": ([], c_uint8),
+    "ime_decomposed_backspace": ([c_bool], None),
+    "ime_dictionary_load": ([c_uint8, c_char_p], c_bool),
+    "ime_dictionary_reset": ([c_uint8], None),
+    "ime_dictionary_version": ([c_uint8], c_uint32),
+    "ime_digit_policy": ([c_uint8], None),
+    "ime_dump_state": ([], c_void_p),
+    "ime_enabled": ([c_bool], None),
+    "ime_english_auto_restore": ([c_bool], None),
+    "ime_english_capital_i": ([c_bool], None),
+    "ime_english_hold": ([c_bool], None),
+    "ime_english_suspended": ([], c_bool),
+    "ime_esc_restore": ([c_bool], None),
+    "ime_free": ([POINTER(ImeResult)], None),
+    "ime_free_string": ([c_void_p], None),
+    "ime_free_tone": ([c_bool], None),
+    "ime_from_legacy": ([c_char_p, c_uint8], c_void_p),
+    "ime_get_buffer": ([POINTER(c_uint32), c_int64], c_int64),
+    "ime_hotkey": ([c_uint8, c_uint16, c_bool, c_bool], None),
+    "ime_hyphen_soft_boundary": ([c_bool], None),
+    "ime_init": ([], None),
+    "ime_initial_w_literal": ([c_bool], None),
+    "ime_key": ([c_uint16, c_bool, c_bool], POINTER(ImeResult)),
+    "ime_key_ext": ([c_uint16, c_bool, c_bool, c_bool], POINTER(ImeResult)),
+    "ime_key_repeat": ([c_uint16, c_bool, c_bool, c_bool], POINTER(ImeResult)),
+    "ime_key_with_char": ([c_uint16, c_bool, c_bool, c_bool, c_uint32], POINTER(ImeResult)),
+    "ime_latency_enabled": ([c_bool], None),
+    "ime_latency_json": ([], c_void_p),
+    "ime_latency_reset": ([], None),
+    "ime_learning_enabled": ([c_bool], None),
+    "ime_learning_load": ([c_char_p], c_bool),
+    "ime_learning_reset": ([], None),
+    "ime_learning_save": ([c_char_p], c_bool),
+    "ime_load_config_json": ([], c_void_p),
+    "ime_log_file": ([c_char_p], c_bool),
+    "ime_log_level": ([c_uint8], None),
+    "ime_macro_save": ([c_char_p], c_bool),
+    "ime_macro_start": ([], None),
+    "ime_macro_stop": ([], c_void_p),
+    "ime_managed_load": ([], c_bool),
+    "ime_managed_locked": ([c_char_p], c_bool),
+    "ime_method": ([c_uint8], None),
+    "ime_modern": ([c_bool], None),
+    "ime_network_allowed": ([], c_bool),
+    "ime_normalize_tone": ([c_char_p, c_uint8], c_void_p),
+    "ime_notify_paste": ([c_char_p], None),
+    "ime_notify_select_all": ([], None),
+    "ime_notify_text_changed": ([], None),
+    "ime_per_app_enabled": ([c_bool], None),
+    "ime_profile_active": ([], c_void_p),
+    "ime_profile_delete": ([c_char_p], c_bool),
+    "ime_profile_list": ([], c_void_p),
+    "ime_profile_switch": ([c_char_p], c_bool),
+    "ime_profiles_load": ([c_char_p], c_bool),
+    "ime_profiles_save": ([c_char_p], c_bool),
+    "ime_proper_noun_capitalize": ([c_bool], None),
+    "ime_quirks_load": ([c_char_p], c_bool),
+    "ime_quirks_reset": ([], None),
+    "ime_quirks_version": ([], c_uint32),
+    "ime_reasons": ([], c_void_p),
+    "ime_remove_english_word": ([c_char_p], c_bool),
+    "ime_remove_key": ([c_uint8, c_uint16], None),
+    "ime_remove_shortcut": ([c_char_p], None),
+    "ime_repeat_policy": ([c_uint8], None),
+    "ime_repeated_word_check": ([c_bool], None),
+    "ime_reset_capitalize_rules": ([], None),
+    "ime_restore_key": ([c_uint16], None),
+    "ime_restore_state": ([c_char_p], c_bool),
+    "ime_restore_word": ([c_char_p], None),
+    "ime_result_action": ([POINTER(ImeResult)], c_uint8),
+    "ime_result_backspace": ([POINTER(ImeResult)], c_uint8),
+    "ime_result_chars": ([POINTER(ImeResult)], POINTER(c_uint32)),
+    "ime_result_count": ([POINTER(ImeResult)], c_uint8),
+    "ime_result_flags": ([POINTER(ImeResult)], c_uint8),
+    "ime_revert_policy": ([c_uint8], None),
+    "ime_save_config_json": ([c_char_p], c_bool),
+    "ime_save_state": ([c_char_p], c_bool),
+    "ime_select_candidate": ([c_uint32], POINTER(ImeResult)),
+    "ime_selection_replace": ([c_bool], None),
+    "ime_self_test": ([], c_void_p),
+    "ime_set_active_app": ([c_char_p], None),
+    "ime_set_app_enabled": ([c_char_p, c_uint8], None),
+    "ime_set_app_terminal_mode": ([c_char_p, c_uint8], None),
+    "ime_set_clipboard": ([c_char_p], None),
+    "ime_set_event_callback": ([ImeEventCallback], None),
+    "ime_set_shortcut_apps": ([c_char_p, c_char_p, c_char_p], None),
+    "ime_set_utc_offset": ([c_int32], None),
+    "ime_set_word_hook": ([ImeWordHook], None),
+    "ime_settings_export": ([c_char_p], c_bool),
+    "ime_settings_import": ([c_char_p], c_bool),
+    "ime_skip_w_shortcut": ([c_bool], None),
+    "ime_snippet_active": ([], c_bool),
+    "ime_snippet_next": ([], c_int32),
+    "ime_snippet_select": ([], c_uint8),
+    "ime_spell_check": ([c_bool], None),
+    "ime_spelling_autofix": ([c_bool], None),
+    "ime_spelling_variants": ([c_uint8], None),
+    "ime_stats_enabled": ([c_bool], None),
+    "ime_stats_json": ([], c_void_p),
+    "ime_stats_load": ([c_char_p], c_bool),
+    "ime_stats_reset": ([], None),
+    "ime_stats_save": ([c_char_p], c_bool),
+    "ime_strip_diacritics": ([c_char_p], c_void_p),
+    "ime_symbol_pack": ([c_bool], None),
+    "ime_teencode_passthrough": ([c_bool], None),
+    "ime_terminal_mode": ([c_uint8], None),
+    "ime_terminal_take": ([], c_void_p),
+    "ime_title_case": ([c_bool], None),
+    "ime_to_legacy": ([c_char_p, c_uint8], c_void_p),
+    "ime_toggle_title_case": ([], c_bool),
+    "ime_tone_renderings": ([c_char_p], c_void_p),
+    "ime_tone_style": ([c_uint8], None),
+    "ime_trace_start": ([], None),
+    "ime_trace_stop": ([], c_void_p),
+    "ime_undo_last": ([], POINTER(ImeResult)),
+    "ime_unicode_input": ([c_bool], None),
+    "ime_url_passthrough": ([c_bool], None),
+    "ime_user_dictionaries_reload": ([c_char_p], c_bool),
+    "ime_user_dictionary_words": ([], c_uint32),
+    "ime_validate_word": ([c_char_p], c_uint8),
+    "ime_validation_strictness": ([c_uint8], None),
+    "ime_word_hooks": ([c_bool], None),
+    "update_check": ([c_char_p, c_char_p], c_void_p),
+    "update_proxy": ([c_char_p], c_bool),
+    "update_record_check": ([], c_bool),
+    "update_schedule": ([c_char_p, c_uint64, c_uint64], c_bool),
+    "update_should_check_now": ([], c_bool),
+    "version_compare": ([c_char_p, c_char_p], c_int32),
+    "version_has_update": ([c_char_p, c_char_p], c_int32),
+}
+
+
+def load(path):
+    """Load the library at path and declare every export
+
+    Raises OSError if it was built for another ABI version.
+    """
+    lib = ctypes.CDLL(path)
+    for name, (argtypes, restype) in _SIGNATURES.items():
+        function = getattr(lib, name)
+        function.argtypes = argtypes
+        function.restype = restype
+    version = lib.ime_abi_version()
+    if version != GONHANH_ABI_VERSION:
+        raise OSError(
+            "%s: ABI version %d, expected %d" % (path, version, GONHANH_ABI_VERSION)
+        )
+    return lib
+
+
+def take_string(lib, pointer):
+    """Text of a string the library allocated, which is then freed (None for NULL)"""
+    if not pointer:
+        return None
+    try:
+        return ctypes.string_at(pointer).decode("utf-8")
+    finally:
+        lib.ime_free_string(pointer)
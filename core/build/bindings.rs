@@ -0,0 +1,365 @@
+//! Language bindings generated from the C ABI
+//!
+//! One file per language, all from the same exports and header, so a new
+//! export or a changed signature reaches every host with the next build:
+//!
+//! - Python: `bindings/python/gonhanh_core.py`, `ctypes` declarations
+//! - Kotlin: `bindings/kotlin/GonhanhCore.kt`, a JNA `Library` interface
+//! - Swift: `include/module.modulemap`, so Swift imports the header itself
+//!
+//! The engine is one process-wide instance behind `ime_init`, so the
+//! bindings stay as flat as the C functions; hosts wrap them as they like.
+//! Constants and structs are read back from the generated header.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use super::header::{Export, Ty};
+
+const PYTHON: &str = "bindings/python/gonhanh_core.py";
+const KOTLIN: &str = "bindings/kotlin/GonhanhCore.kt";
+const SWIFT: &str = "include/module.modulemap";
+
+/// (checked-in path, contents) of every binding
+pub fn all(header: &str, exports: &BTreeMap<String, Export>) -> Vec<(&'static str, String)> {
+    let defines = defines(header);
+    let structs = structs(header);
+    vec![
+        (PYTHON, python(&defines, &structs, exports)),
+        (KOTLIN, kotlin(&defines, &structs, exports)),
+        (SWIFT, swift()),
+    ]
+}
+
+/// `#define GONHANH_NAME value /* note */` in the header
+struct Define {
+    name: String,
+    value: String,
+    note: Option<String>,
+}
+
+/// `typedef struct Name { ... } Name;` in the header
+struct Struct {
+    name: String,
+    /// (C type, field, array length)
+    fields: Vec<(String, String, Option<String>)>,
+}
+
+fn defines(header: &str) -> Vec<Define> {
+    header
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("#define GONHANH_")?;
+            let (code, note) = match rest.split_once("/*") {
+                Some((code, note)) => (code, Some(note.trim_end_matches("*/").trim().to_string())),
+                None => (rest, None),
+            };
+            let mut parts = code.split_whitespace();
+            let name = format!("GONHANH_{}", parts.next()?);
+            let value = parts.next()?.to_string();
+            Some(Define { name, value, note })
+        })
+        .collect()
+}
+
+fn structs(header: &str) -> Vec<Struct> {
+    let mut structs = Vec::new();
+    let mut lines = header.lines();
+    while let Some(line) = lines.next() {
+        let Some(name) = line
+            .strip_prefix("typedef struct ")
+            .and_then(|rest| rest.strip_suffix(" {"))
+        else {
+            continue;
+        };
+        let mut fields = Vec::new();
+        for line in lines.by_ref() {
+            if line.starts_with('}') {
+                break;
+            }
+            let declaration = line.split(';').next().unwrap_or_default().trim();
+            let Some((ty, field)) = declaration.rsplit_once(' ') else {
+                continue;
+            };
+            let (field, len) = match field.split_once('[') {
+                Some((field, len)) => (field, Some(len.trim_end_matches(']').to_string())),
+                None => (field, None),
+            };
+            fields.push((ty.to_string(), field.to_string(), len));
+        }
+        structs.push(Struct {
+            name: name.to_string(),
+            fields,
+        });
+    }
+    structs
+}
+
+fn python_type(ty: Ty) -> &'static str {
+    match ty {
+        Ty::Bool => "c_bool",
+        Ty::U8 => "c_uint8",
+        Ty::U16 => "c_uint16",
+        Ty::U32 => "c_uint32",
+        Ty::U64 => "c_uint64",
+        Ty::I32 => "c_int32",
+        Ty::I64 => "c_int64",
+        Ty::Str => "c_char_p",
+        // Raw pointer: c_char_p would copy the text and lose the pointer
+        // to free
+        Ty::OwnedStr => "c_void_p",
+        Ty::ConstU32Ptr | Ty::U32Ptr => "POINTER(c_uint32)",
+        Ty::ConstResultPtr | Ty::ResultPtr => "POINTER(ImeResult)",
+        Ty::QuirkPtr => "POINTER(ImeAppQuirk)",
+        Ty::EventCallback => "ImeEventCallback",
+        Ty::WordHook => "ImeWordHook",
+    }
+}
+
+/// ctypes type of a struct field's C type
+fn python_field_type(c: &str) -> &'static str {
+    match c {
+        "uint8_t" => "c_uint8",
+        "uint16_t" => "c_uint16",
+        "uint32_t" => "c_uint32",
+        _ => panic!("no ctypes type for field type {:?}", c),
+    }
+}
+
+fn python(defines: &[Define], structs: &[Struct], exports: &BTreeMap<String, Export>) -> String {
+    let mut py = String::from(
+        r#"# Generated by build.rs from the gonhanh_core C ABI; do not edit
+"""ctypes bindings for gonhanh_core
+
+    import gonhanh_core
+    core = gonhanh_core.load("./libgonhanh_core.so")
+    core.ime_init()
+    r = core.ime_key(0, False, False)  # macOS keycode of A
+    ...
+    core.ime_free(r)
+
+Strings go in as UTF-8 bytes. Functions returning an allocated string
+return a raw pointer: read it with take_string, which also frees it.
+"""
+
+import ctypes
+from ctypes import (
+    POINTER,
+    c_bool,
+    c_char_p,
+    c_int32,
+    c_int64,
+    c_uint8,
+    c_uint16,
+    c_uint32,
+    c_uint64,
+    c_void_p,
+)
+
+"#,
+    );
+    for define in defines {
+        match &define.note {
+            Some(note) => writeln!(py, "{} = {}  # {}", define.name, define.value, note),
+            None => writeln!(py, "{} = {}", define.name, define.value),
+        }
+        .unwrap();
+    }
+    for s in structs {
+        write!(
+            py,
+            "\n\nclass {}(ctypes.Structure):\n    _fields_ = [\n",
+            s.name
+        )
+        .unwrap();
+        for (ty, field, len) in &s.fields {
+            let ty = python_field_type(ty);
+            match len {
+                Some(len) => writeln!(py, "        (\"{}\", {} * {}),", field, ty, len),
+                None => writeln!(py, "        (\"{}\", {}),", field, ty),
+            }
+            .unwrap();
+        }
+        py.push_str("    ]\n");
+    }
+    py.push_str(
+        "\n\nImeEventCallback = ctypes.CFUNCTYPE(None, c_uint8, c_char_p)\n\
+         # The returned text must stay valid until the hook returns\n\
+         ImeWordHook = ctypes.CFUNCTYPE(c_void_p, c_char_p)\n\n\
+         # name: (argument types, return type)\n\
+         _SIGNATURES = {\n",
+    );
+    for export in exports.values() {
+        let params: Vec<&str> = export
+            .params
+            .iter()
+            .map(|&(_, ty)| python_type(ty))
+            .collect();
+        let ret = export.ret.map_or("None", python_type);
+        writeln!(
+            py,
+            "    \"{}\": ([{}], {}),",
+            export.name,
+            params.join(", "),
+            ret
+        )
+        .unwrap();
+    }
+    py.push_str(
+        r#"}
+
+
+def load(path):
+    """Load the library at path and declare every export
+
+    Raises OSError if it was built for another ABI version.
+    """
+    lib = ctypes.CDLL(path)
+    for name, (argtypes, restype) in _SIGNATURES.items():
+        function = getattr(lib, name)
+        function.argtypes = argtypes
+        function.restype = restype
+    version = lib.ime_abi_version()
+    if version != GONHANH_ABI_VERSION:
+        raise OSError(
+            "%s: ABI version %d, expected %d" % (path, version, GONHANH_ABI_VERSION)
+        )
+    return lib
+
+
+def take_string(lib, pointer):
+    """Text of a string the library allocated, which is then freed (None for NULL)"""
+    if not pointer:
+        return None
+    try:
+        return ctypes.string_at(pointer).decode("utf-8")
+    finally:
+        lib.ime_free_string(pointer)
+"#,
+    );
+    py
+}
+
+fn kotlin_type(ty: Ty) -> &'static str {
+    match ty {
+        // JNA passes a Boolean as a 4-byte int; C bool is one byte
+        Ty::Bool | Ty::U8 => "Byte",
+        Ty::U16 => "Short",
+        Ty::U32 | Ty::I32 => "Int",
+        Ty::U64 | Ty::I64 => "Long",
+        Ty::Str => "String?",
+        Ty::OwnedStr | Ty::ConstU32Ptr | Ty::U32Ptr | Ty::ConstResultPtr | Ty::ResultPtr => {
+            "Pointer?"
+        }
+        Ty::QuirkPtr => "ImeAppQuirk?",
+        Ty::EventCallback => "ImeEventCallback?",
+        Ty::WordHook => "ImeWordHook?",
+    }
+}
+
+/// (Kotlin type, initial value) of a struct field's C type
+fn kotlin_field(c: &str, len: Option<&str>) -> (String, String) {
+    match (c, len) {
+        ("uint32_t", Some(len)) => ("IntArray".into(), format!("IntArray({})", len)),
+        ("uint8_t", None) => ("Byte".into(), "0".into()),
+        ("uint16_t", None) => ("Short".into(), "0".into()),
+        ("uint32_t", None) => ("Int".into(), "0".into()),
+        _ => panic!("no Kotlin type for field type {:?}", c),
+    }
+}
+
+fn kotlin(defines: &[Define], structs: &[Struct], exports: &BTreeMap<String, Export>) -> String {
+    let mut kt = String::from(
+        "// Generated by build.rs from the gonhanh_core C ABI; do not edit\n\
+         package org.gonhanh.core\n\n\
+         import com.sun.jna.Callback\n\
+         import com.sun.jna.Library\n\
+         import com.sun.jna.Native\n\
+         import com.sun.jna.Pointer\n\
+         import com.sun.jna.Structure\n\n",
+    );
+    for define in defines {
+        match &define.note {
+            Some(note) => writeln!(
+                kt,
+                "const val {} = {} // {}",
+                define.name, define.value, note
+            ),
+            None => writeln!(kt, "const val {} = {}", define.name, define.value),
+        }
+        .unwrap();
+    }
+    for s in structs {
+        let order: Vec<String> = s
+            .fields
+            .iter()
+            .map(|(_, f, _)| format!("\"{}\"", f))
+            .collect();
+        write!(
+            kt,
+            "\n@Structure.FieldOrder({})\nclass {}(p: Pointer? = null) : Structure(p) {{\n",
+            order.join(", "),
+            s.name
+        )
+        .unwrap();
+        for (ty, field, len) in &s.fields {
+            let (ty, init) = kotlin_field(ty, len.as_deref());
+            writeln!(kt, "    @JvmField var {}: {} = {}", field, ty, init).unwrap();
+        }
+        kt.push_str("\n    init {\n        if (p != null) read()\n    }\n}\n");
+    }
+    kt.push_str(
+        "\n/**\n \
+         * The gonhanh_core C ABI. C `bool` is a Byte (0 or 1), strings go in as\n \
+         * UTF-8, and allocated strings come back as a Pointer to free with\n \
+         * ime_free_string. Read an ImeResult with `ImeResult(pointer)`.\n \
+         */\n\
+         interface GonhanhCore : Library {\n    \
+         fun interface ImeEventCallback : Callback {\n        \
+         fun invoke(event: Byte, text: String?)\n    }\n\n    \
+         /** The returned text must stay valid until the hook returns */\n    \
+         fun interface ImeWordHook : Callback {\n        \
+         fun invoke(word: String?): Pointer?\n    }\n\n",
+    );
+    for export in exports.values() {
+        let params: Vec<String> = export
+            .params
+            .iter()
+            .map(|(name, ty)| format!("{}: {}", name, kotlin_type(*ty)))
+            .collect();
+        let ret = match export.ret {
+            Some(ty) => format!(": {}", kotlin_type(ty)),
+            None => String::new(),
+        };
+        writeln!(kt, "    fun {}({}){}", export.name, params.join(", "), ret).unwrap();
+    }
+    kt.push_str(
+        r#"
+    companion object {
+        /** Load the library; fails if it was built for another ABI version */
+        fun load(name: String = "gonhanh_core"): GonhanhCore {
+            val options = mapOf(Library.OPTION_STRING_ENCODING to "UTF-8")
+            val core = Native.load(name, GonhanhCore::class.java, options)
+            val version = core.ime_abi_version()
+            check(version == GONHANH_ABI_VERSION) {
+                "$name: ABI version $version, expected $GONHANH_ABI_VERSION"
+            }
+            return core
+        }
+    }
+}
+"#,
+    );
+    kt
+}
+
+fn swift() -> String {
+    "// Generated by build.rs; do not edit\n\
+     // Swift: add this directory to the import paths, then `import GonhanhCore`\n\
+     module GonhanhCore {\n    \
+     header \"gonhanh_core.h\"\n    \
+     link \"gonhanh_core\"\n    \
+     export *\n\
+     }\n"
+    .to_string()
+}
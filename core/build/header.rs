@@ -0,0 +1,253 @@
+//! The C ABI: exports read from the source, and the header built from them
+//!
+//! `include/gonhanh_core.h` is generated from `include/gonhanh_core.h.in`:
+//! each `@fn name` line becomes the prototype of export `name`, and
+//! `@NAME@` the value of a `CONSTANTS` entry. The build fails when an
+//! export has no line in the template, a line names no export, or a
+//! signature uses a type with no C mapping (`Ty::parse`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::read;
+
+/// C header and the template it is generated from
+pub const HEADER: &str = "include/gonhanh_core.h";
+pub const TEMPLATE: &str = "include/gonhanh_core.h.in";
+
+/// Rust constants the template refers to as `@NAME@`, and their files
+const CONSTANTS: &[(&str, &str)] = &[
+    ("ABI_VERSION", "src/lib.rs"),
+    ("KEY_NONE", "src/lib.rs"),
+    ("MAX", "src/engine/buffer.rs"),
+    ("FLAG_KEY_CONSUMED", "src/engine/mod.rs"),
+    ("FLAG_CURSOR_MOVE", "src/engine/mod.rs"),
+    ("FLAG_SNIPPET", "src/engine/mod.rs"),
+    ("FLAG_DECOMPOSED", "src/engine/mod.rs"),
+];
+
+/// Type of a parameter or return value in an export
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ty {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I32,
+    I64,
+    /// NUL-terminated UTF-8 passed in, borrowed for the call
+    Str,
+    /// NUL-terminated UTF-8 the library allocated (`ime_free_string`)
+    OwnedStr,
+    ConstU32Ptr,
+    U32Ptr,
+    ConstResultPtr,
+    ResultPtr,
+    /// `ImeAppQuirk` the library fills in
+    QuirkPtr,
+    EventCallback,
+    WordHook,
+}
+
+impl Ty {
+    /// Type of a Rust type written in an export
+    fn parse(rust: &str) -> Option<Ty> {
+        let rust = rust.replace("std::os::raw::", "").replace("quirks::", "");
+        Some(match rust.as_str() {
+            "bool" => Ty::Bool,
+            "u8" => Ty::U8,
+            "u16" => Ty::U16,
+            "u32" => Ty::U32,
+            "u64" => Ty::U64,
+            "i32" => Ty::I32,
+            "i64" => Ty::I64,
+            "*const c_char" | "*const i8" => Ty::Str,
+            "*mut c_char" | "*mut i8" => Ty::OwnedStr,
+            "*const u32" => Ty::ConstU32Ptr,
+            "*mut u32" => Ty::U32Ptr,
+            "*const Result" => Ty::ConstResultPtr,
+            "*mut Result" => Ty::ResultPtr,
+            "*mut Quirk" => Ty::QuirkPtr,
+            "Option<EventCallback>" => Ty::EventCallback,
+            "Option<WordHookCallback>" => Ty::WordHook,
+            _ => return None,
+        })
+    }
+
+    fn c(self) -> &'static str {
+        match self {
+            Ty::Bool => "bool",
+            Ty::U8 => "uint8_t",
+            Ty::U16 => "uint16_t",
+            Ty::U32 => "uint32_t",
+            Ty::U64 => "uint64_t",
+            Ty::I32 => "int32_t",
+            Ty::I64 => "int64_t",
+            Ty::Str => "const char *",
+            Ty::OwnedStr => "char *",
+            Ty::ConstU32Ptr => "const uint32_t *",
+            Ty::U32Ptr => "uint32_t *",
+            Ty::ConstResultPtr => "const ImeResult *",
+            Ty::ResultPtr => "ImeResult *",
+            Ty::QuirkPtr => "ImeAppQuirk *",
+            Ty::EventCallback => "ImeEventCallback",
+            Ty::WordHook => "ImeWordHook",
+        }
+    }
+}
+
+/// A `#[no_mangle]` function
+#[derive(Debug)]
+pub struct Export {
+    pub name: String,
+    pub params: Vec<(String, Ty)>,
+    /// None: returns nothing
+    pub ret: Option<Ty>,
+}
+
+impl Export {
+    /// C prototype, `ImeResult *ime_key(uint16_t key, bool caps, bool ctrl);`
+    fn prototype(&self) -> String {
+        let ret = self.ret.map_or("void", Ty::c);
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|(name, ty)| c_declaration(ty.c(), name))
+            .collect();
+        let params = if params.is_empty() {
+            "void".to_string()
+        } else {
+            params.join(", ")
+        };
+        format!("{}({});", c_declaration(ret, &self.name), params)
+    }
+}
+
+/// `ty name`, without a space after a pointer's `*`
+fn c_declaration(ty: &str, name: &str) -> String {
+    if ty.ends_with('*') {
+        format!("{}{}", ty, name)
+    } else {
+        format!("{} {}", ty, name)
+    }
+}
+
+/// The header for `template`
+pub fn c_header(template: &str, exports: &BTreeMap<String, Export>) -> String {
+    let mut unused: Vec<&String> = exports.keys().collect();
+    let mut header = String::new();
+    for line in template.lines() {
+        match line.strip_prefix("@fn ") {
+            Some(rest) => {
+                let name_len = rest.find(' ').unwrap_or(rest.len());
+                let (name, comment) = rest.split_at(name_len);
+                let export = exports
+                    .get(name)
+                    .unwrap_or_else(|| panic!("{}: {} is not exported", TEMPLATE, name));
+                unused.retain(|&n| n != name);
+                header.push_str(&export.prototype());
+                header.push_str(comment);
+            }
+            None => header.push_str(line),
+        }
+        header.push('\n');
+    }
+    assert!(
+        unused.is_empty(),
+        "{}: no @fn line for {:?}",
+        TEMPLATE,
+        unused
+    );
+    for &(name, file) in CONSTANTS {
+        header = header.replace(&format!("@{}@", name), &constant(file, name));
+    }
+    header
+}
+
+/// Literal value of `const name` in `file`
+fn constant(file: &str, name: &str) -> String {
+    let source = read(file);
+    source
+        .lines()
+        .map(|line| line.trim_start().trim_start_matches("pub "))
+        .find_map(|line| {
+            let rest = line.strip_prefix("const ")?.strip_prefix(name)?;
+            let value = rest.strip_prefix(':')?.split_once('=')?.1;
+            Some(value.trim().trim_end_matches(';').to_string())
+        })
+        .unwrap_or_else(|| panic!("{}: no const {}", file, name))
+}
+
+/// Every `#[no_mangle]` function under `src/`, by name
+pub fn exports() -> BTreeMap<String, Export> {
+    let mut files = Vec::new();
+    rust_sources(Path::new("src"), &mut files);
+    let mut exports = BTreeMap::new();
+    for file in files {
+        let source = read(&file.to_string_lossy());
+        let mut lines = source.lines().map(str::trim);
+        while let Some(line) = lines.next() {
+            if line != "#[no_mangle]" {
+                continue;
+            }
+            // Signature up to the body, rustfmt may split it over lines
+            let mut signature = String::new();
+            for line in lines.by_ref() {
+                signature.push_str(line);
+                signature.push(' ');
+                if line.ends_with('{') {
+                    break;
+                }
+            }
+            let export = parse_signature(&signature)
+                .unwrap_or_else(|| panic!("{}: cannot read {:?}", file.display(), signature));
+            exports.insert(export.name.clone(), export);
+        }
+    }
+    exports
+}
+
+/// Collect `.rs` files under `dir` recursively
+fn rust_sources(dir: &Path, out: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).expect("read src").flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            rust_sources(&path, out);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+/// Export of a Rust `extern "C" fn` signature
+fn parse_signature(signature: &str) -> Option<Export> {
+    let rest = signature.split_once("extern \"C\" fn ")?.1;
+    let (name, rest) = rest.split_once('(')?;
+    let (params, rest) = rest.split_once(')')?;
+    let ret = rest
+        .trim()
+        .trim_end_matches('{')
+        .trim()
+        .strip_prefix("->")
+        .map(ty);
+    let params = params
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (param, rust) = p.split_once(':')?;
+            Some((param.trim().to_string(), ty(rust)))
+        })
+        .collect::<Option<_>>()?;
+    Some(Export {
+        name: name.to_string(),
+        params,
+        ret,
+    })
+}
+
+fn ty(rust: &str) -> Ty {
+    Ty::parse(rust.trim()).unwrap_or_else(|| panic!("no C type for Rust type {:?}", rust.trim()))
+}
@@ -0,0 +1,130 @@
+//! Build script: frequency rank tables, the C header and language bindings
+//!
+//! `english_freq.txt` and `dictionaries/vi_freq.txt` list words most
+//! frequent first. The ranks are computed here into `$OUT_DIR`, so the
+//! engine does no work for them at startup, and each list is checked
+//! against its dictionary: a word the engine can never match (a typo, a
+//! word missing from the dictionary, a duplicate) fails the build instead
+//! of silently never ranking.
+//!
+//! The `#[no_mangle]` signatures are the one interface definition of the
+//! C ABI. The C header (`header`) and the Python, Kotlin and Swift
+//! bindings (`bindings`) are generated from them, so no host can drift
+//! from the library. They are checked in, for hosts that do not run cargo,
+//! but the build only generates them into `$OUT_DIR/abi/`:
+//! `tests/abi_header_test.rs` fails when a checked-in copy is stale.
+
+mod bindings;
+mod header;
+
+use std::collections::HashSet;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// (ranked list, dictionary it must be drawn from, header lines to skip,
+/// generated file)
+const LISTS: &[(&str, &str, usize, &str)] = &[
+    (
+        "src/data/english_freq.txt",
+        "src/data/english_dict_merged.txt",
+        0,
+        "english_freq.rs",
+    ),
+    (
+        "src/data/dictionaries/vi_freq.txt",
+        "src/data/dictionaries/vi.dic",
+        1,
+        "vi_freq.rs",
+    ),
+];
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    for &(list, dict, skip, out) in LISTS {
+        println!("cargo:rerun-if-changed={}", list);
+        println!("cargo:rerun-if-changed={}", dict);
+        let code = rank_table(list, &read(list), &read(dict), skip);
+        fs::write(Path::new(&out_dir).join(out), code).expect("write rank table");
+    }
+
+    println!("cargo:rerun-if-changed={}", header::TEMPLATE);
+    println!("cargo:rerun-if-changed=src");
+    let exports = header::exports();
+    let header = header::c_header(&read(header::TEMPLATE), &exports);
+    for (path, text) in bindings::all(&header, &exports) {
+        write_generated(&out_dir, path, &text);
+    }
+    write_generated(&out_dir, header::HEADER, &header);
+}
+
+/// Write `$OUT_DIR/abi/<path>`, the generated version of checked-in `path`
+///
+/// The source tree is never written: `tests/abi_header_test.rs` fails when
+/// the checked-in copy differs.
+fn write_generated(out_dir: &str, path: &str, text: &str) {
+    let out = Path::new(out_dir).join("abi").join(path);
+    if let Some(dir) = out.parent() {
+        fs::create_dir_all(dir).expect("create output directory");
+    }
+    fs::write(&out, text).unwrap_or_else(|err| panic!("{}: {}", out.display(), err));
+}
+
+fn read(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|err| panic!("{}: {}", path, err))
+}
+
+/// Rust source for the ranks of `text`, words most frequent first
+///
+/// `BY_RANK` keeps the file order; `RANKS` holds (word, rank) sorted by
+/// word for binary search.
+fn rank_table(path: &str, text: &str, dict: &str, skip: usize) -> String {
+    // Lists are lowercase; dictionaries keep proper nouns capitalized
+    let dict: HashSet<String> = dict.lines().skip(skip).map(str::to_lowercase).collect();
+    let mut seen = HashSet::new();
+    let words: Vec<&str> = text.lines().filter(|line| !line.is_empty()).collect();
+    for (i, &word) in words.iter().enumerate() {
+        let line = i + 1;
+        assert!(
+            word == word.to_lowercase() && word.trim() == word,
+            "{}:{}: {:?} is not a lowercase word",
+            path,
+            line,
+            word
+        );
+        assert!(
+            dict.contains(word),
+            "{}:{}: {:?} is not in the dictionary",
+            path,
+            line,
+            word
+        );
+        assert!(
+            seen.insert(word),
+            "{}:{}: {:?} is listed twice",
+            path,
+            line,
+            word
+        );
+    }
+    assert!(words.len() <= u16::MAX as usize, "{}: too many words", path);
+
+    let mut sorted: Vec<(&str, usize)> = words.iter().copied().zip(0..).collect();
+    sorted.sort_unstable();
+
+    let mut code = format!("// Generated by build.rs from {}; do not edit\n\n", path);
+    code.push_str("/// Words, most frequent first\n");
+    code.push_str("pub(crate) static BY_RANK: &[&str] = &[\n");
+    for word in &words {
+        let _ = writeln!(code, "    {:?},", word);
+    }
+    code.push_str("];\n\n");
+    code.push_str("/// (word, rank), sorted by word\n");
+    code.push_str("pub(crate) static RANKS: &[(&str, u16)] = &[\n");
+    for (word, rank) in sorted {
+        let _ = writeln!(code, "    ({:?}, {}),", word, rank);
+    }
+    code.push_str("];\n");
+    code
+}
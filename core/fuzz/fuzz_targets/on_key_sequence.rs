@@ -0,0 +1,19 @@
+//! Fuzz arbitrary key sequences through the engine
+//!
+//! The first bytes set every engine option (see `utils::engine_from_bytes`),
+//! the rest decodes into (key, caps, ctrl, shift) events. Panics on any
+//! engine panic or broken invariant (see `utils::assert_key_invariants`).
+//!
+//! ```text
+//! cd core && cargo +nightly fuzz run on_key_sequence
+//! ```
+
+#![no_main]
+
+use gonhanh_core::utils::{assert_key_invariants, engine_from_bytes, key_events_from_bytes};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let (mut e, keys) = engine_from_bytes(data);
+    assert_key_invariants(&mut e, &key_events_from_bytes(keys));
+});
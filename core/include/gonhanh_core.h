@@ -0,0 +1,376 @@
+/*
+ * Gõ Nhanh core - C ABI
+ *
+ * Generated by build.rs from include/gonhanh_core.h.in: edit the template,
+ * not gonhanh_core.h. Each `@fn name` line there becomes the prototype of
+ * the `#[no_mangle]` export `name` and `@NAME@` the value of that Rust
+ * constant, so the header cannot drift from the library. The build fails
+ * when an export has no `@fn` line or a line names no export.
+ *
+ * Bump ABI_VERSION in lib.rs whenever an existing signature or the layout
+ * of ImeResult changes. Adding new functions does not require a bump.
+ */
+
+#ifndef GONHANH_CORE_H
+#define GONHANH_CORE_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define GONHANH_ABI_VERSION 2
+
+/* Capacity of ImeResult.chars (engine buffer size). */
+#define GONHANH_MAX_CHARS 256
+
+/* ImeResult.action */
+#define GONHANH_ACTION_NONE 0
+#define GONHANH_ACTION_SEND 1
+#define GONHANH_ACTION_RESTORE 2
+#define GONHANH_ACTION_REPLACE 3 /* select backspace chars, type chars over them */
+
+/* ImeResult.flags */
+#define GONHANH_FLAG_KEY_CONSUMED 0x01
+#define GONHANH_FLAG_CURSOR_MOVE 0x02 /* press Left ime_cursor_offset() times */
+#define GONHANH_FLAG_SNIPPET 0x04     /* call ime_snippet_next() */
+#define GONHANH_FLAG_DECOMPOSED 0x08  /* backspace counts NFD code points */
+
+/* ime_validate_word() results */
+#define GONHANH_WORD_VALID 0
+#define GONHANH_WORD_INVALID_INITIAL 1
+#define GONHANH_WORD_INVALID_FINAL 2
+#define GONHANH_WORD_INVALID_SPELLING 3
+#define GONHANH_WORD_INVALID_VOWEL_PATTERN 4
+#define GONHANH_WORD_NO_VOWEL 5
+#define GONHANH_WORD_INVALID_TONE 6 /* huyền/hỏi/ngã before p, t, c, ch */
+
+/* ime_method() input methods */
+#define GONHANH_METHOD_TELEX 0
+#define GONHANH_METHOD_VNI 1
+#define GONHANH_METHOD_SIMPLE_TELEX 2 /* w only as horn/breve, ] [ → ư ơ */
+#define GONHANH_METHOD_TELEX_VNI 3    /* Telex letters and VNI digits together */
+
+/* ime_remove_key: no key removes diacritics. */
+#define GONHANH_KEY_NONE 0xFFFF
+
+/* ime_validation_strictness() profiles */
+#define GONHANH_STRICTNESS_STANDARD 0
+#define GONHANH_STRICTNESS_STRICT 1  /* no foreign initials or final k */
+#define GONHANH_STRICTNESS_RELAXED 2 /* chat: "ko", foreign initials, loan rhymes */
+
+/* ime_spelling_variants() bits */
+#define GONHANH_VARIANT_SOUTHERN 0x01  /* "dzô", "zậy", "bển" */
+#define GONHANH_VARIANT_NORTHERN 0x02  /* "chẩy", "dầy", "quí" */
+#define GONHANH_VARIANT_LOANWORDS 0x04 /* "pốt", "sịp", "okê" */
+
+/* ime_capitalize_rule() rules for a punctuation character */
+#define GONHANH_CAPITALIZE_CANCEL 0       /* drop a pending capital (default) */
+#define GONHANH_CAPITALIZE_KEEP 1         /* keep it: quotes, brackets */
+#define GONHANH_CAPITALIZE_END_SENTENCE 2 /* . ! ? */
+
+/* ime_set_event_callback() events */
+#define GONHANH_EVENT_REPEATED_WORD 1   /* text = the repeated word ("của") */
+#define GONHANH_EVENT_METHOD_CHANGED 2  /* text = "telex", "vni", "simple-telex", "telex-vni" */
+#define GONHANH_EVENT_PROFILE_CHANGED 3 /* text = the new profile's name */
+#define GONHANH_EVENT_ENABLED_CHANGED 4 /* text = "on", "off" (per-app state, ime_per_app_enabled) */
+#define GONHANH_EVENT_TONE_STYLE_CHANGED 5 /* text = "new", "traditional" (ime_auto_tone_style) */
+#define GONHANH_EVENT_TITLE_CASE_CHANGED 6 /* text = "on", "off" (GONHANH_HOTKEY_TITLE_CASE) */
+
+/* ime_hotkey() actions */
+#define GONHANH_HOTKEY_CYCLE_METHOD 0 /* Telex → VNI → Telex */
+#define GONHANH_HOTKEY_NEXT_PROFILE 1 /* next profile, in name order */
+#define GONHANH_HOTKEY_UNDO_LAST 2    /* as ime_undo_last, result with the key */
+#define GONHANH_HOTKEY_TITLE_CASE 3   /* flip Title Case mode */
+
+/* ime_tone_style() / ime_normalize_tone() styles (open oa, oe, uy only) */
+#define GONHANH_TONE_TRADITIONAL 0 /* hòa, khỏe, thủy */
+#define GONHANH_TONE_NEW 1         /* hoà, khoẻ, thuỷ (default) */
+
+/* ime_from_legacy() / ime_to_legacy() encodings */
+#define GONHANH_ENCODING_TCVN3 0 /* ABC, .VnTime fonts */
+#define GONHANH_ENCODING_VNI 1   /* VNI-Windows fonts */
+
+/* ime_convert_text() clipboard commands */
+#define GONHANH_CONVERT_KEYSTROKES 0 /* "Vieetj" → "Việt" (current method) */
+#define GONHANH_CONVERT_STRIP 1      /* "Việt" → "Viet" */
+#define GONHANH_CONVERT_TONE_STYLE 2 /* hòa ↔ hoà, as set by ime_tone_style */
+#define GONHANH_CONVERT_FROM_TCVN3 3 /* TCVN3 pasted as Latin-1 → Unicode */
+#define GONHANH_CONVERT_FROM_VNI 4   /* VNI pasted as Latin-1 → Unicode */
+
+/*
+ * Keystroke result. Layout is part of the ABI (1028 bytes, no padding).
+ * Prefer the ime_result_* accessors over reading fields directly.
+ */
+typedef struct ImeResult {
+    uint32_t chars[GONHANH_MAX_CHARS]; /* UTF-32 codepoints to insert */
+    uint8_t action;                    /* GONHANH_ACTION_* */
+    uint8_t backspace;                 /* characters to delete first */
+    uint8_t count;                     /* valid entries in chars */
+    uint8_t flags;                     /* GONHANH_FLAG_* */
+} ImeResult;
+
+/* ime_terminal_mode(): terminal-safe output */
+#define GONHANH_TERMINAL_OFF 0         /* backspaces + new text (default) */
+#define GONHANH_TERMINAL_COMPOSE 1     /* send each word once, finished */
+#define GONHANH_TERMINAL_PASSTHROUGH 2 /* engine off for the app */
+
+/* ImeAppQuirk.method: how to inject a replacement */
+#define GONHANH_SEND_FAST 0        /* backspaces and text as separate injections */
+#define GONHANH_SEND_SLOW 1        /* one event per character, delay_ms apart */
+#define GONHANH_SEND_ATOMIC 2      /* backspaces and text in one injection */
+#define GONHANH_SEND_PASTE 3       /* clipboard + Ctrl+V */
+#define GONHANH_SEND_PASSTHROUGH 4 /* leave keys alone (remote desktop) */
+
+/* ImeAppQuirk.backspace */
+#define GONHANH_BACKSPACE_KEY 0
+#define GONHANH_BACKSPACE_UNICODE 1 /* U+0008 as a Unicode character */
+
+/* ImeAppQuirk.flags */
+#define GONHANH_QUIRK_KEYUP_RACE 0x01 /* swallow key-up of consumed keys */
+#define GONHANH_QUIRK_BATCH 0x02      /* send the whole replacement at once */
+#define GONHANH_QUIRK_COALESCE 0x04   /* merge quick successive replacements */
+
+/* Send strategy for one app from the quirk table (8 bytes) */
+typedef struct ImeAppQuirk {
+    uint8_t method;     /* GONHANH_SEND_* */
+    uint8_t backspace;  /* GONHANH_BACKSPACE_* */
+    uint8_t flags;      /* GONHANH_QUIRK_* */
+    uint16_t delay_ms;  /* pause between characters */
+    uint16_t settle_ms; /* pause between backspaces and text */
+} ImeAppQuirk;
+
+/* Event callback: GONHANH_EVENT_* code and UTF-8 text, valid during the call */
+typedef void (*ImeEventCallback)(uint8_t event, const char *text);
+/* Word hook: committed word in (valid during the call), rewrite or NULL out;
+   the rewrite must stay valid until the hook returns. Must not call ime_* */
+typedef const char *(*ImeWordHook)(const char *word);
+
+/* ---- ABI ---- */
+uint32_t ime_abi_version(void);
+
+/* ---- Lifecycle ---- */
+void ime_init(void);
+void ime_clear(void);
+void ime_clear_all(void);
+void ime_notify_select_all(void);
+void ime_notify_paste(const char *text);
+void ime_notify_text_changed(void);
+
+/* ---- Keystrokes (free every non-null result with ime_free) ---- */
+ImeResult *ime_key(uint16_t key, bool caps, bool ctrl);
+ImeResult *ime_key_ext(uint16_t key, bool caps, bool ctrl, bool shift);
+ImeResult *ime_key_repeat(uint16_t key, bool caps, bool ctrl, bool shift);
+ImeResult *ime_key_with_char(uint16_t key, bool caps, bool ctrl, bool shift, uint32_t char_code);
+ImeResult *ime_undo_last(void); /* undo last auto-restore/shortcut/auto-capitalize */
+char *ime_candidates(void); /* JSON [{"text","kind"}] for the word in progress; free with ime_free_string */
+ImeResult *ime_select_candidate(uint32_t index);
+void ime_free(ImeResult *r);
+void ime_set_event_callback(ImeEventCallback callback); /* NULL to remove; fired after ime_key* */
+void ime_set_word_hook(ImeWordHook hook); /* NULL to remove; called unlocked in ime_key*, rewrite merged into its result */
+void ime_word_hooks(bool enabled); /* off by default; off again after a hook over 50 ms */
+
+/* ---- Result accessors (null-safe, return 0 / NULL for null input) ---- */
+uint8_t ime_result_action(const ImeResult *r);
+uint8_t ime_result_backspace(const ImeResult *r);
+uint8_t ime_result_count(const ImeResult *r);
+uint8_t ime_result_flags(const ImeResult *r);
+const uint32_t *ime_result_chars(const ImeResult *r);
+
+/* ---- Options ---- */
+void ime_method(uint8_t method);
+void ime_enabled(bool enabled);
+void ime_skip_w_shortcut(bool skip);
+void ime_initial_w_literal(bool enabled); /* "web" stays, "nhw" → "như" */
+void ime_bracket_shortcut(bool enabled);
+void ime_esc_restore(bool enabled);
+void ime_restore_key(uint16_t key); /* macOS keycode, default Esc (53) */
+void ime_remove_key(uint8_t method, uint16_t key); /* GONHANH_KEY_NONE: off */
+void ime_free_tone(bool enabled);
+void ime_spell_check(bool enabled); /* off: never refuse keys, restore at boundary */
+void ime_modern(bool modern); /* true = GONHANH_TONE_NEW */
+void ime_tone_style(uint8_t style); /* GONHANH_TONE_* */
+void ime_english_auto_restore(bool enabled);
+void ime_auto_restore_triggers(uint8_t triggers); /* 0x01 Space, 0x02 Tab, 0x04 Enter, 0x08 punctuation */
+void ime_backspace_undo_restore(bool enabled);
+void ime_auto_english_switch(uint8_t words); /* 0 = off */
+void ime_repeated_word_check(bool enabled); /* reported as GONHANH_EVENT_REPEATED_WORD */
+void ime_auto_detect_method(bool enabled); /* reported as GONHANH_EVENT_METHOD_CHANGED */
+void ime_auto_tone_style(bool enabled); /* reported as GONHANH_EVENT_TONE_STYLE_CHANGED */
+bool ime_english_suspended(void);
+void ime_english_hold(bool held); /* temporary English key down/up */
+void ime_english_capital_i(bool enabled); /* "i" → "I" in English text */
+void ime_hyphen_soft_boundary(bool enabled);
+void ime_url_passthrough(bool enabled); /* default on */
+void ime_code_context(bool enabled);
+void ime_selection_replace(bool enabled); /* results that delete come as GONHANH_ACTION_REPLACE */
+void ime_decomposed_backspace(bool enabled); /* app stores NFD: backspace per code point */
+void ime_digit_policy(uint8_t policy); /* 0 in word (default), 1 word boundary */
+void ime_revert_policy(uint8_t policy); /* 0 literal "aaaa" → "aaa" (default), 1 cycle → "â" */
+void ime_repeat_policy(uint8_t policy); /* 0 auto-repeats typed as-is (default), 1 each repeat is a press */
+void ime_symbol_pack(bool enabled);
+void ime_unicode_input(bool enabled);
+void ime_auto_capitalize(bool enabled);
+void ime_capitalize_rule(uint32_t ch, uint8_t rule); /* GONHANH_CAPITALIZE_* */
+void ime_reset_capitalize_rules(void);
+void ime_title_case(bool enabled); /* capitalize every word (headings) */
+bool ime_toggle_title_case(void); /* hotkey, returns new state */
+void ime_hotkey(uint8_t action, uint16_t key, bool ctrl, bool shift); /* GONHANH_KEY_NONE unbinds */
+void ime_caps_lock_recase(bool enabled); /* CapsLock mid-word re-cases the word */
+void ime_allow_foreign_consonants(bool enabled);
+void ime_validation_strictness(uint8_t profile); /* GONHANH_STRICTNESS_* */
+void ime_spelling_variants(uint8_t variants); /* GONHANH_VARIANT_* bits, default none */
+void ime_spelling_autofix(bool enabled); /* "nge" → "nghe" at word boundary */
+void ime_proper_noun_capitalize(bool enabled); /* "hà nội" → "Hà Nội" */
+void ime_teencode_passthrough(bool enabled); /* "ko", "wa" committed as typed */
+void ime_terminal_mode(uint8_t mode); /* GONHANH_TERMINAL_*, apps without their own */
+void ime_set_app_terminal_mode(const char *app, uint8_t mode); /* 255 = follow default */
+char *ime_terminal_take(void); /* held word; free with ime_free_string */
+
+/* ---- Buffer ---- */
+int64_t ime_get_buffer(uint32_t *out, int64_t max_len);
+void ime_restore_word(const char *word);
+uint8_t ime_validate_word(const char *word); /* GONHANH_WORD_* */
+
+/* ---- Shortcuts (UTF-8, NUL-terminated) ---- */
+void ime_add_shortcut(const char *trigger, const char *replacement);
+void ime_remove_shortcut(const char *trigger);
+void ime_clear_shortcuts(void);
+/* Macro recording: type the expansion, then pick its trigger */
+void ime_macro_start(void);
+char *ime_macro_stop(void); /* text recorded, NULL if not recording; free with ime_free_string */
+bool ime_macro_save(const char *trigger);
+void ime_set_utc_offset(int32_t minutes); /* %date%, %time% placeholders */
+void ime_set_clipboard(const char *text); /* %clipboard% placeholder, NULL clears */
+uint8_t ime_cursor_offset(void); /* after GONHANH_FLAG_CURSOR_MOVE */
+/* Per-app shortcuts: comma-separated app ids, NULL = none */
+void ime_set_shortcut_apps(const char *trigger, const char *only, const char *never);
+void ime_set_active_app(const char *app); /* frontmost app, NULL = unknown */
+void ime_per_app_enabled(bool enabled); /* reported as GONHANH_EVENT_ENABLED_CHANGED */
+void ime_set_app_enabled(const char *app, uint8_t state); /* 0 off, 1 on, 255 = follow default */
+/* word in progress per app across focus changes; app NULL = active app */
+bool ime_save_state(const char *app); /* app losing focus */
+bool ime_restore_state(const char *app); /* app gaining focus; false = nothing saved */
+/* Snippet fields (${1:text}): Tab jumps while ime_snippet_active() */
+int32_t ime_snippet_next(void); /* caret move, negative = Left */
+uint8_t ime_snippet_select(void); /* then Shift+Right this many */
+bool ime_snippet_active(void);
+
+/* ---- English exception words (always left as typed) ---- */
+void ime_add_english_word(const char *word);
+bool ime_remove_english_word(const char *word);
+void ime_clear_english_words(void);
+
+/* ---- Proper nouns (capitalized at word boundary) ---- */
+bool ime_add_proper_noun(const char *name); /* "Nguyễn Văn An", max 5 words */
+
+/* ---- Teencode (chat abbreviations kept as typed) ---- */
+bool ime_add_teencode(const char *word); /* "vcl", one word */
+void ime_clear_teencode(void);
+
+/* ---- Text conversion (free results with ime_free_string) ---- */
+char *ime_convert_text(const char *text, uint8_t command); /* GONHANH_CONVERT_* */
+char *ime_strip_diacritics(const char *text); /* "Nguyễn" → "Nguyen" */
+char *ime_normalize_tone(const char *text, uint8_t style); /* GONHANH_TONE_* */
+char *ime_tone_renderings(const char *word); /* JSON array, configured style first */
+char *ime_from_legacy(const char *bytes, uint8_t encoding); /* GONHANH_ENCODING_* → UTF-8 */
+char *ime_to_legacy(const char *text, uint8_t encoding); /* UTF-8 → GONHANH_ENCODING_* */
+
+/* ---- Diagnostics ---- */
+char *ime_dump_state(void); /* JSON, free with ime_free_string */
+void ime_free_string(char *s);
+char *ime_self_test(void); /* JSON report, free with ime_free_string */
+void ime_trace_start(void);
+char *ime_trace_stop(void); /* session trace, NULL if not recording; free with ime_free_string */
+char *ime_reasons(void); /* decisions on the last key, one per line (debug-trace builds); free with ime_free_string */
+
+/* ---- Logging ---- */
+void ime_log_level(uint8_t level); /* 0=Off 1=Error 2=Warn 3=Info 4=Debug 5=Trace */
+bool ime_log_file(const char *path); /* NULL or "" = stderr */
+
+/* ---- Statistics (local only, off by default) ---- */
+void ime_stats_enabled(bool enabled);
+char *ime_stats_json(void); /* JSON, free with ime_free_string */
+void ime_stats_reset(void);
+bool ime_stats_save(const char *path);
+bool ime_stats_load(const char *path);
+
+/* ---- Keystroke latency metrics (off by default) ---- */
+void ime_latency_enabled(bool enabled);
+char *ime_latency_json(void); /* JSON, free with ime_free_string */
+void ime_latency_reset(void);
+
+/* ---- Learned auto-restore corrections (off by default) ---- */
+void ime_learning_enabled(bool enabled);
+void ime_learning_reset(void);
+bool ime_learning_save(const char *path);
+bool ime_learning_load(const char *path);
+
+/* ---- Config directory: GONHANH_CONFIG_DIR, else the per-user default ---- */
+char *ime_config_dir(void); /* free with ime_free_string, NULL if unknown */
+
+/* ---- Single options by settings file key ("method", "free_tone") ---- */
+char *ime_config_get(const char *key); /* free with ime_free_string, NULL if unknown */
+bool ime_config_set(const char *key, const char *value); /* false if invalid or locked */
+char *ime_config_keys(void); /* JSON array; free with ime_free_string */
+char *ime_load_config_json(void); /* {"method":0,...}; free with ime_free_string */
+bool ime_save_config_json(const char *json); /* listed options only; nothing changes on failure */
+
+/* ---- Managed config: admin file that overrides and locks options ---- */
+/* locked options ignore their setters; always reads the system file */
+bool ime_managed_load(void);
+bool ime_managed_locked(const char *key); /* settings file key, e.g. "tone_style" */
+bool ime_network_allowed(void); /* false: skip downloads, update checks */
+
+/* ---- Settings bundle: options, shortcuts, exceptions, learned words ---- */
+/* path NULL = settings.txt in the config directory */
+bool ime_settings_export(const char *path);
+bool ime_settings_import(const char *path); /* nothing changes on failure */
+
+/* ---- Named profiles: own options and shortcuts each ---- */
+bool ime_profile_switch(const char *name);
+char *ime_profile_active(void); /* free with ime_free_string */
+char *ime_profile_list(void); /* JSON array of names; free with ime_free_string */
+bool ime_profile_delete(const char *name); /* not the active one */
+/* dir NULL = profiles/ in the config directory */
+bool ime_profiles_save(const char *dir);
+bool ime_profiles_load(const char *dir); /* nothing changes on failure */
+
+/* ---- Downloaded word lists (0 = vi.dic, 1 = keep.dic) ---- */
+/* stamped, newer than embedded; path NULL = vi.dic / keep.dic in the config directory */
+bool ime_dictionary_load(uint8_t list, const char *path);
+uint32_t ime_dictionary_version(uint8_t list); /* 0 = embedded */
+void ime_dictionary_reset(uint8_t list);
+/* user *.dic files, merged into vi.dic; dir NULL = dictionaries/ in the config directory */
+bool ime_user_dictionaries_reload(const char *dir); /* also run by ime_init */
+uint32_t ime_user_dictionary_words(void);
+
+/* ---- App quirk table (send strategies) ---- */
+/* app NULL = the app set by ime_set_active_app; false = nothing special */
+bool ime_app_quirk(const char *app, ImeAppQuirk *out);
+/* stamped like word lists; path NULL = quirks.txt in the config directory */
+bool ime_quirks_load(const char *path);
+uint32_t ime_quirks_version(void); /* 0 = embedded */
+void ime_quirks_reset(void);
+
+/* ---- Updater ---- */
+int32_t version_compare(const char *v1, const char *v2);
+int32_t version_has_update(const char *current, const char *latest);
+/* Latest GitHub release as JSON ({"available":...} or {"error":...,"code":
+ * "offline"|"proxy"|"network"|"status"|"invalid_response"|"unsupported"}),
+ * free with ime_free_string. repo NULL = default. Blocking; HTTPS needs the
+ * `update-check` cargo feature. */
+char *update_check(const char *current, const char *repo);
+bool update_proxy(const char *url); /* NULL = environment (default), "" = direct */
+/* Check scheduling: interval/jitter 0 = defaults (1 day, 1 hour), path NULL = in memory */
+bool update_schedule(const char *path, uint64_t interval_secs, uint64_t jitter_secs);
+bool update_should_check_now(void);
+bool update_record_check(void); /* after a check made by the host's own client */
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GONHANH_CORE_H */
@@ -0,0 +1,56 @@
+//! gonhanh-native-host - native messaging host for browser extensions
+//!
+//! Lets a Chrome / ChromeOS extension type Vietnamese through the real
+//! engine, e.g. in kiosk sessions where no system IME can be installed.
+//! The browser starts this binary and talks to it over stdin/stdout with
+//! length-prefixed JSON messages; the requests are those of `gonhanh
+//! serve` (see `gonhanh_core::daemon`).
+//!
+//! Register it with a host manifest named `org.gonhanh.engine.json` in
+//! the browser's NativeMessagingHosts directory:
+//!
+//! ```json
+//! {
+//!   "name": "org.gonhanh.engine",
+//!   "description": "Gõ Nhanh Vietnamese engine",
+//!   "path": "/usr/lib/fkey/gonhanh-native-host",
+//!   "type": "stdio",
+//!   "allowed_origins": ["chrome-extension://<extension id>/"]
+//! }
+//! ```
+//!
+//! The engine starts with the managed config and the settings bundle from
+//! the config directory, so the extension types like the desktop app.
+//! Browser arguments (the caller's origin, `--parent-window`) are ignored.
+
+use std::io;
+use std::process::ExitCode;
+use std::sync::Mutex;
+
+use gonhanh_core::config;
+use gonhanh_core::daemon::{self, Service};
+use gonhanh_core::engine::Engine;
+use gonhanh_core::managed;
+
+fn main() -> ExitCode {
+    // stdout carries the messages, so problems go to stderr (Chrome's log)
+    if let Err(err) = managed::load() {
+        eprintln!("gonhanh-native-host: managed config: {}", err);
+    }
+    let mut engine = Engine::new();
+    if let Some(path) = config::path(config::SETTINGS_FILE).filter(|p| p.is_file()) {
+        if let Err(err) = engine.load_settings(&path.to_string_lossy()) {
+            eprintln!("gonhanh-native-host: {}: {}", path.display(), err);
+        }
+    }
+    engine.apply_managed();
+
+    let service = Mutex::new(Service::new(engine));
+    match daemon::serve_native(&service, io::stdin().lock(), io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("gonhanh-native-host: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! gonhanh - command-line converter
+//!
+//! Replays raw Telex/VNI text through the real engine, e.g. to fix notes
+//! typed while the IME was off:
+//!
+//! ```text
+//! gonhanh convert --method telex < notes.txt
+//! gonhanh convert --method vni --traditional a.txt b.txt > out.txt
+//! gonhanh strip < names.txt
+//! gonhanh tone --traditional report.txt
+//! gonhanh decode --from tcvn3 old.txt > new.txt
+//! gonhanh convert --settings my.gonhanh < notes.txt
+//! gonhanh settings my.gonhanh
+//! gonhanh serve --socket /run/user/1000/gonhanh.sock
+//! ```
+//!
+//! Each line is typed as keystrokes. Characters without a key (Unicode,
+//! tabs) are copied as-is and end the current word. `strip` removes
+//! diacritics instead ("Nguyễn Văn A" → "Nguyen Van A"), `tone` moves tone
+//! marks to one placement style ("hòa" → "hoà"); `decode` and
+//! `encode` convert between UTF-8 and the legacy TCVN3/VNI encodings.
+//! `settings` checks a settings file exported by the app and prints it as
+//! the engine reads it (the defaults without a file); `--settings` types
+//! with its options and shortcuts. `serve` runs the engine as a service
+//! speaking JSON lines on stdin/stdout, a Unix socket or a Windows named
+//! pipe (see
+//! `gonhanh_core::daemon`).
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use gonhanh_core::convert::{normalize_tone, type_text};
+use gonhanh_core::daemon::{self, Service};
+use gonhanh_core::encoding::{self, Encoding};
+use gonhanh_core::engine::{Engine, ToneStyle};
+use gonhanh_core::utils::strip_diacritics;
+
+const USAGE: &str = "\
+Usage: gonhanh convert [OPTIONS] [FILE...]
+       gonhanh strip [FILE...]
+       gonhanh tone [--traditional] [FILE...]
+       gonhanh decode --from <tcvn3|vni> [FILE...]
+       gonhanh encode --to <tcvn3|vni> [FILE...]
+       gonhanh settings [FILE]
+       gonhanh serve [--socket PATH] [OPTIONS]
+
+convert: raw Telex/VNI keystrokes to Vietnamese.
+strip:   Vietnamese to ASCII without diacritics (Nguyễn → Nguyen).
+tone:    Rewrite tone placement to one style (hòa → hoà, or back with --traditional).
+decode:  legacy TCVN3 (ABC) / VNI-Windows text to UTF-8.
+encode:  UTF-8 to TCVN3 / VNI-Windows.
+settings: check an exported settings file and print it normalized
+          (default settings if no FILE).
+serve:   run the engine as a service: JSON requests and responses, one per
+         line, on stdin/stdout, a Unix socket or a named pipe.
+The others read stdin if no FILE.
+
+Options:
+  -m, --method <telex|vni|simple-telex|telex-vni>
+                            Input method (default: telex)
+      --traditional         Traditional tone placement (hòa instead of hoà)
+      --auto-restore        Restore English words (text, expect, ...)
+      --from, --to <enc>    Legacy encoding: tcvn3 (or abc), vni
+      --settings <file>     Options and shortcuts from an exported settings
+                            file (flags given after it still apply)
+      --socket <path>       serve: listen on this Unix socket, or on Windows
+                            this named pipe (\\\\.\\pipe\\gonhanh)
+  -h, --help                Show this help";
+
+struct Options {
+    /// Contents of the `--settings` file, already checked
+    settings: Option<String>,
+    method: Option<u8>,
+    traditional: bool,
+    auto_restore: bool,
+    encoding: Option<Encoding>,
+    socket: Option<String>,
+    files: Vec<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut opts = Options {
+        settings: None,
+        method: None,
+        traditional: false,
+        auto_restore: false,
+        encoding: None,
+        socket: None,
+        files: Vec::new(),
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-m" | "--method" => {
+                opts.method = match iter.next().map(|s| s.as_str()) {
+                    Some("telex") => Some(0),
+                    Some("vni") => Some(1),
+                    Some("simple-telex") => Some(2),
+                    Some("telex-vni") => Some(3),
+                    other => return Err(format!("unknown method: {}", other.unwrap_or(""))),
+                }
+            }
+            "--settings" => {
+                let path = iter.next().map_or("", |s| s.as_str());
+                let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+                Engine::new()
+                    .import_settings(&text)
+                    .map_err(|err| format!("{}: {}", path, err))?;
+                opts.settings = Some(text);
+            }
+            "--socket" => opts.socket = iter.next().cloned(),
+            "--traditional" => opts.traditional = true,
+            "--auto-restore" => opts.auto_restore = true,
+            "--from" | "--to" => {
+                let name = iter.next().map_or("", |s| s.as_str());
+                opts.encoding = Some(
+                    Encoding::from_name(name)
+                        .ok_or_else(|| format!("unknown encoding: {}", name))?,
+                );
+            }
+            s if s.starts_with('-') && s != "-" => return Err(format!("unknown option: {}", s)),
+            s => opts.files.push(s.to_string()),
+        }
+    }
+    Ok(opts)
+}
+
+/// Engine configured from the command line
+fn engine(opts: &Options) -> Engine {
+    let mut e = Engine::new();
+    if let Some(text) = &opts.settings {
+        // Checked in parse_args
+        let _ = e.import_settings(text);
+    }
+    if let Some(method) = opts.method {
+        e.set_method(method);
+    }
+    if opts.traditional {
+        e.set_modern_tone(false);
+    }
+    if opts.auto_restore {
+        e.set_english_auto_restore(true);
+    }
+    e
+}
+
+fn convert(opts: &Options, input: &str, out: &mut impl Write) -> io::Result<()> {
+    out.write_all(type_text(&mut engine(opts), input).as_bytes())
+}
+
+fn serve(opts: &Options) -> Result<(), String> {
+    let service = Service::new(engine(opts));
+    match &opts.socket {
+        #[cfg(unix)]
+        Some(path) => daemon::serve_unix(service, std::path::Path::new(path))
+            .map_err(|err| format!("{}: {}", path, err)),
+        #[cfg(windows)]
+        Some(name) => daemon::serve_pipe(service, name).map_err(|err| format!("{}: {}", name, err)),
+        #[cfg(not(any(unix, windows)))]
+        Some(_) => Err("--socket needs Unix or Windows; use stdin/stdout".to_string()),
+        None => daemon::serve(
+            &std::sync::Mutex::new(service),
+            io::stdin().lock(),
+            io::stdout().lock(),
+        )
+        .map_err(|err| err.to_string()),
+    }
+}
+
+fn settings(opts: &Options) -> Result<(), String> {
+    let mut e = Engine::new();
+    if let Some(path) = opts.files.first() {
+        e.load_settings(path)
+            .map_err(|err| format!("{}: {}", path, err))?;
+    }
+    print!("{}", e.export_settings());
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let command = match args.first().map(|s| s.as_str()) {
+        Some(
+            command @ ("convert" | "strip" | "tone" | "decode" | "encode" | "settings" | "serve"),
+        ) => command,
+        Some("-h") | Some("--help") | None => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Some(other) => return Err(format!("unknown command: {}", other)),
+    };
+    if args[1..].iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let opts = parse_args(&args[1..])?;
+    match command {
+        "settings" => return settings(&opts),
+        "serve" => return serve(&opts),
+        _ => {}
+    }
+    let legacy = match (command, opts.encoding) {
+        ("decode" | "encode", None) => return Err(format!("{} needs an encoding", command)),
+        (_, encoding) => encoding.unwrap_or(Encoding::Tcvn3),
+    };
+    let process = |path: &str, input: Vec<u8>, out: &mut io::StdoutLock| {
+        let written = if command == "decode" {
+            out.write_all(encoding::decode(&input, legacy).as_bytes())
+        } else {
+            let text = String::from_utf8(input).map_err(|_| format!("{}: not UTF-8", path))?;
+            match command {
+                "strip" => out.write_all(strip_diacritics(&text).as_bytes()),
+                "tone" => {
+                    let style = if opts.traditional {
+                        ToneStyle::TraditionalStyle
+                    } else {
+                        ToneStyle::NewStyle
+                    };
+                    out.write_all(normalize_tone(&text, style).as_bytes())
+                }
+                "encode" => out.write_all(&encoding::encode(&text, legacy)),
+                _ => convert(&opts, &text, out),
+            }
+        };
+        written.map_err(|err| err.to_string())
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let stdin = ["-".to_string()];
+    let files = if opts.files.is_empty() {
+        &stdin[..]
+    } else {
+        &opts.files[..]
+    };
+    for path in files {
+        let mut input = Vec::new();
+        if path == "-" {
+            io::stdin().read_to_end(&mut input).map(|_| ())
+        } else {
+            fs::read(path).map(|bytes| input = bytes)
+        }
+        .map_err(|err| format!("{}: {}", path, err))?;
+        process(path, input, &mut out)?;
+    }
+    out.flush().map_err(|err| err.to_string())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("gonhanh: {}\n\n{}", msg, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Small LRU cache for lookups repeated word after word
+//!
+//! Users retype the same words all day, and every key checks the word typed
+//! so far again ("v", "vi", "viê", ...), so a few dozen recent results answer
+//! most validation and word list lookups. Entries are scanned linearly: at
+//! this size that is cheaper than hashing a key.
+
+use std::borrow::Borrow;
+
+/// Most recently used entries, up to a fixed capacity
+#[derive(Debug)]
+pub struct Lru<K, V> {
+    /// Least recently used first
+    entries: Vec<(K, V)>,
+    capacity: usize,
+}
+
+impl<K, V: Clone> Lru<K, V> {
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Cached value for `key`, marking it most recently used
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        let i = self.entries.iter().position(|(k, _)| k.borrow() == key)?;
+        let entry = self.entries.remove(i);
+        let value = entry.1.clone();
+        self.entries.push(entry);
+        Some(value)
+    }
+
+    /// Cache `value` for `key`, evicting the least recently used entry when
+    /// full (the key must not be cached already)
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, value));
+    }
+
+    /// Take the value cached for `key` out of the cache
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        let i = self.entries.iter().position(|(k, _)| k.borrow() == key)?;
+        Some(self.entries.remove(i).1)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut lru: Lru<String, u8> = Lru::new(2);
+        lru.insert("viet".into(), 1);
+        lru.insert("nam".into(), 2);
+        assert_eq!(lru.get("viet"), Some(1));
+        // "nam" is now the oldest
+        lru.insert("xin".into(), 3);
+        assert_eq!(lru.get("nam"), None);
+        assert_eq!((lru.get("viet"), lru.get("xin")), (Some(1), Some(3)));
+        assert_eq!(lru.len(), 2);
+    }
+}
@@ -0,0 +1,144 @@
+//! Config directory
+//!
+//! Where the core keeps files of its own: the settings bundle, profiles,
+//! downloaded word lists and the user's own dictionaries.
+//! `GONHANH_CONFIG_DIR` overrides it, for portable installs, tests and
+//! side-by-side setups; otherwise the platform's per-user config location
+//! is used:
+//!
+//! - Windows: `%APPDATA%\FKey`
+//! - macOS: `~/Library/Application Support/FKey`
+//! - Linux and other Unix: `$XDG_CONFIG_HOME/fkey`, else `~/.config/fkey`
+//!   (a relative `XDG_CONFIG_HOME` is ignored, as the XDG spec requires)
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+
+/// Environment variable overriding the config directory
+pub const ENV_VAR: &str = "GONHANH_CONFIG_DIR";
+
+/// Settings bundle file name (see `engine::settings`)
+pub const SETTINGS_FILE: &str = "settings.txt";
+
+/// Directory of the named profiles (see `engine::profiles`)
+pub const PROFILES_DIR: &str = "profiles";
+
+/// Directory of the user's `*.dic` word lists (see `data::dictionary`)
+pub const DICTIONARIES_DIR: &str = "dictionaries";
+
+/// Platform family, for the default location
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Os {
+    Windows,
+    MacOs,
+    /// Linux, BSD and other XDG systems
+    Unix,
+}
+
+impl Os {
+    /// The platform this was built for
+    pub fn current() -> Self {
+        if cfg!(windows) {
+            Os::Windows
+        } else if cfg!(target_os = "macos") {
+            Os::MacOs
+        } else {
+            Os::Unix
+        }
+    }
+}
+
+/// Config directory, None if neither the override nor a home is known
+pub fn dir() -> Option<PathBuf> {
+    resolve(Os::current(), |name| std::env::var_os(name))
+}
+
+/// `dir()` joined with `name`
+pub fn path(name: &str) -> Option<PathBuf> {
+    dir().map(|d| d.join(name))
+}
+
+/// `path(name)`, creating the config directory first
+pub fn create_path(name: &str) -> Option<PathBuf> {
+    let dir = dir()?;
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(name))
+}
+
+/// `dir()` for `os` with the environment passed in
+pub fn resolve(os: Os, env: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let var = |name: &str| env(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    if let Some(dir) = var(ENV_VAR) {
+        return Some(dir);
+    }
+    match os {
+        Os::Windows => var("APPDATA").map(|d| d.join("FKey")),
+        Os::MacOs => var("HOME").map(|h| h.join("Library/Application Support/FKey")),
+        Os::Unix => var("XDG_CONFIG_HOME")
+            .filter(|d| d.is_absolute())
+            .or_else(|| var("HOME").map(|h| h.join(".config")))
+            .map(|d| d.join("fkey")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| OsString::from(v))
+        }
+    }
+
+    #[test]
+    fn test_override_wins() {
+        let vars = [(ENV_VAR, "/opt/fkey/config"), ("HOME", "/home/an")];
+        for os in [Os::Windows, Os::MacOs, Os::Unix] {
+            assert_eq!(
+                resolve(os, env(&vars)),
+                Some(PathBuf::from("/opt/fkey/config"))
+            );
+        }
+        // Empty means unset
+        let vars = [(ENV_VAR, ""), ("HOME", "/home/an")];
+        assert_eq!(
+            resolve(Os::Unix, env(&vars)),
+            Some(PathBuf::from("/home/an/.config/fkey"))
+        );
+    }
+
+    #[test]
+    fn test_platform_defaults() {
+        let vars = [
+            ("HOME", "/home/an"),
+            ("XDG_CONFIG_HOME", "/data/config"),
+            ("APPDATA", "C:\\Users\\an\\AppData\\Roaming"),
+        ];
+        assert_eq!(
+            resolve(Os::Unix, env(&vars)),
+            Some(PathBuf::from("/data/config/fkey"))
+        );
+        assert_eq!(
+            resolve(Os::MacOs, env(&vars)),
+            Some(PathBuf::from("/home/an/Library/Application Support/FKey"))
+        );
+        assert_eq!(
+            resolve(Os::Windows, env(&vars)),
+            Some(PathBuf::from("C:\\Users\\an\\AppData\\Roaming").join("FKey"))
+        );
+        assert_eq!(resolve(Os::Windows, env(&[("HOME", "/home/an")])), None);
+    }
+
+    #[test]
+    fn test_relative_xdg_ignored() {
+        let vars = [("HOME", "/home/an"), ("XDG_CONFIG_HOME", "config")];
+        assert_eq!(
+            resolve(Os::Unix, env(&vars)),
+            Some(PathBuf::from("/home/an/.config/fkey"))
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Text conversion commands - fixing text after it was written
+//!
+//! Hosts bind these to hotkeys that transform the clipboard: the text is
+//! read, passed to [`convert`] and written back. The logic lives here so
+//! every platform converts the same way.
+//!
+//! ```
+//! use gonhanh_core::convert::{convert, Command};
+//! use gonhanh_core::engine::Engine;
+//!
+//! let e = Engine::new();
+//! assert_eq!(convert(&e, "Vieetj Nam", Command::Keystrokes), "Việt Nam");
+//! assert_eq!(convert(&e, "Việt Nam", Command::StripDiacritics), "Viet Nam");
+//! assert_eq!(convert(&e, "hòa", Command::ToneStyle), "hoà");
+//! ```
+
+use crate::data::chars;
+use crate::data::keys;
+use crate::data::vowel::Phonology;
+use crate::encoding::{self, Encoding};
+use crate::engine::buffer::{Buffer, Char};
+use crate::engine::validation;
+use crate::engine::{Action, Engine, ToneStyle};
+use crate::utils;
+
+/// Clipboard conversion
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Raw keystrokes ("Vieetj") → Vietnamese, in the engine's input method
+    Keystrokes = 0,
+    /// Vietnamese → ASCII ("Việt" → "Viet")
+    StripDiacritics = 1,
+    /// Re-place tone marks in the engine's style (hòa ↔ hoà)
+    ToneStyle = 2,
+    /// TCVN3 (ABC) text pasted as Latin-1 → Unicode
+    FromTcvn3 = 3,
+    /// VNI-Windows text pasted as Latin-1 → Unicode
+    FromVni = 4,
+}
+
+impl Command {
+    /// From FFI value, None if unknown
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Keystrokes),
+            1 => Some(Self::StripDiacritics),
+            2 => Some(Self::ToneStyle),
+            3 => Some(Self::FromTcvn3),
+            4 => Some(Self::FromVni),
+            _ => None,
+        }
+    }
+}
+
+/// Run `command` on `text`
+///
+/// Keystroke replay and tone placement follow `engine`'s method and
+/// options; the engine itself is not touched.
+pub fn convert(engine: &Engine, text: &str, command: Command) -> String {
+    match command {
+        Command::Keystrokes => type_text(&mut engine.fresh_copy(), text),
+        Command::StripDiacritics => utils::strip_diacritics(text),
+        Command::ToneStyle => normalize_tone(text, engine.tone_style()),
+        Command::FromTcvn3 => from_legacy(text, Encoding::Tcvn3),
+        Command::FromVni => from_legacy(text, Encoding::Vni),
+    }
+}
+
+/// Key and shift state for a typed character, None if it has no key
+fn char_to_keystroke(c: char) -> Option<(u16, bool)> {
+    let shifted = match c {
+        '!' => keys::N1,
+        '@' => keys::N2,
+        '#' => keys::N3,
+        '$' => keys::N4,
+        '%' => keys::N5,
+        '^' => keys::N6,
+        '&' => keys::N7,
+        '*' => keys::N8,
+        '(' => keys::N9,
+        ')' => keys::N0,
+        '_' => keys::MINUS,
+        '+' => keys::EQUAL,
+        ':' => keys::SEMICOLON,
+        '"' => keys::QUOTE,
+        '<' => keys::COMMA,
+        '>' => keys::DOT,
+        '?' => keys::SLASH,
+        '|' => keys::BACKSLASH,
+        '{' => keys::LBRACKET,
+        '}' => keys::RBRACKET,
+        '~' => keys::BACKQUOTE,
+        _ => {
+            // Test mapping treats ESC as a key; here it is plain text
+            if !c.is_ascii() || c.is_ascii_control() {
+                return None;
+            }
+            let key = utils::char_to_key(c);
+            return (key != 255).then_some((key, false));
+        }
+    };
+    Some((shifted, true))
+}
+
+/// Type `text` into `e` as keystrokes and return the resulting screen text
+///
+/// Characters without a key (Unicode, tabs, newlines) are copied as-is and
+/// end the current word.
+pub fn type_text(e: &mut Engine, text: &str) -> String {
+    let mut screen: Vec<char> = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        let Some((key, shift)) = char_to_keystroke(c) else {
+            e.clear_all();
+            screen.push(c);
+            continue;
+        };
+        let r = e.on_key_ext(key, c.is_ascii_uppercase(), false, shift);
+        if r.action == Action::Send as u8 {
+            for _ in 0..r.backspace {
+                screen.pop();
+            }
+            screen.extend((0..r.count as usize).filter_map(|i| char::from_u32(r.chars[i])));
+            // Break chars are not part of an auto-restore replacement; space
+            // results already include it. Consumed keys are shortcut output.
+            if key != keys::SPACE && keys::is_break_ext(key, shift) && !r.key_consumed() {
+                screen.push(c);
+            }
+        } else {
+            screen.push(c);
+        }
+    }
+    e.clear_all();
+    screen.into_iter().collect()
+}
+
+/// Rewrite tone placement to one style ("Hòa, thủy" ↔ "Hoà, thuỷ")
+///
+/// Each syllable gets its mark where the engine would type it in `style`,
+/// so a document mixing both styles comes out uniform.
+/// Only valid syllables carrying a single tone mark are changed; other
+/// words, and words with decomposed marks, are kept as-is.
+///
+/// ```
+/// use gonhanh_core::convert::normalize_tone;
+/// use gonhanh_core::engine::ToneStyle;
+/// assert_eq!(normalize_tone("hòa và hoà", ToneStyle::NewStyle), "hoà và hoà");
+/// assert_eq!(normalize_tone("hòa và hoà", ToneStyle::TraditionalStyle), "hòa và hòa");
+/// ```
+pub fn normalize_tone(text: &str, style: ToneStyle) -> String {
+    // Decomposed marks stay with their word, which is then left alone
+    let in_word = |c: char| c.is_alphabetic() || ('\u{300}'..='\u{36f}').contains(&c);
+    let mut out: Vec<char> = text.chars().collect();
+    let mut start = 0;
+    while start < out.len() {
+        if !in_word(out[start]) {
+            start += 1;
+            continue;
+        }
+        let end = (start..out.len())
+            .find(|&i| !in_word(out[i]))
+            .unwrap_or(out.len());
+        normalize_word(&mut out[start..end], style.is_new());
+        start = end;
+    }
+    out.into_iter().collect()
+}
+
+fn normalize_word(word: &mut [char], modern: bool) {
+    if let Some(syllable) = MarkedSyllable::parse(word) {
+        let to = syllable.tone_position(modern);
+        if let Some(moved) = syllable.with_mark_at(word, to) {
+            word.copy_from_slice(&moved);
+        }
+    }
+}
+
+/// Acceptable spellings of one syllable, the `style` spelling first
+///
+/// Open oa, oe, uy syllables have both placements ("hoà", "hòa"); "quy"
+/// also has the older "qúy". Other words, and syllables without a tone
+/// mark, give just themselves. For candidate lists in IME frontends.
+///
+/// ```
+/// use gonhanh_core::convert::tone_renderings;
+/// use gonhanh_core::engine::ToneStyle;
+/// assert_eq!(tone_renderings("hòa", ToneStyle::NewStyle), ["hoà", "hòa"]);
+/// assert_eq!(tone_renderings("quý", ToneStyle::NewStyle), ["quý", "qúy"]);
+/// assert_eq!(tone_renderings("hoàn", ToneStyle::NewStyle), ["hoàn"]);
+/// ```
+pub fn tone_renderings(word: &str, style: ToneStyle) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let Some(syllable) = MarkedSyllable::parse(&chars) else {
+        return vec![word.to_string()];
+    };
+    let mut positions = vec![
+        syllable.tone_position(style.is_new()),
+        syllable.tone_position(!style.is_new()),
+    ];
+    if let Some(u) = syllable.open_quy_u() {
+        positions.push(u);
+    }
+    let mut out: Vec<String> = Vec::with_capacity(positions.len());
+    for pos in positions {
+        let rendering: String = syllable
+            .with_mark_at(&chars, pos)
+            .unwrap_or_else(|| chars.clone())
+            .into_iter()
+            .collect();
+        if !out.contains(&rendering) {
+            out.push(rendering);
+        }
+    }
+    out
+}
+
+/// A valid syllable carrying exactly one tone mark
+struct MarkedSyllable {
+    buf: Buffer,
+    /// Position of the marked vowel
+    mark_pos: usize,
+}
+
+impl MarkedSyllable {
+    fn parse(word: &[char]) -> Option<Self> {
+        let mut buf = Buffer::new();
+        for &c in word {
+            let p = chars::parse_char(c)?;
+            let mut ch = Char::new(p.key, p.caps);
+            ch.tone = p.tone;
+            ch.mark = p.mark;
+            ch.stroke = p.stroke;
+            buf.push(ch);
+        }
+        if buf.len() != word.len() {
+            return None;
+        }
+        let mut marked = buf.iter().enumerate().filter(|(_, c)| c.mark != 0);
+        let (Some((mark_pos, _)), None) = (marked.next(), marked.next()) else {
+            return None;
+        };
+        let keys: Vec<u16> = buf.iter().map(|c| c.key).collect();
+        let tones: Vec<u8> = buf.iter().map(|c| c.tone).collect();
+        validation::is_valid_with_tones(&keys, &tones).then_some(Self { buf, mark_pos })
+    }
+
+    /// Where the engine puts the mark
+    fn tone_position(&self, modern: bool) -> usize {
+        let vowels = utils::collect_vowels(&self.buf);
+        let last_vowel_pos = vowels.last().map(|v| v.pos).unwrap_or(0);
+        Phonology::find_tone_position(
+            &vowels,
+            utils::has_final_consonant(&self.buf, last_vowel_pos),
+            modern,
+            utils::has_qu_initial(&self.buf),
+            utils::has_gi_initial(&self.buf),
+        )
+    }
+
+    /// Position of the u in an open "quy" syllable (older spelling "qúy")
+    fn open_quy_u(&self) -> Option<usize> {
+        let keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
+        (keys == [keys::Q, keys::U, keys::Y]).then_some(1)
+    }
+
+    /// `word` with the mark moved to `to`, None if unchanged or not a vowel
+    fn with_mark_at(&self, word: &[char], to: usize) -> Option<Vec<char>> {
+        let from = self.mark_pos;
+        let (old, new) = (self.buf.get(from)?, self.buf.get(to)?);
+        if to == from || !keys::is_vowel(new.key) {
+            return None;
+        }
+        let mut out = word.to_vec();
+        out[from] = chars::to_char(old.key, old.caps, old.tone, 0)?;
+        out[to] = chars::to_char(new.key, new.caps, new.tone, old.mark)?;
+        Some(out)
+    }
+}
+
+/// Decode legacy bytes that were pasted as Latin-1 characters
+///
+/// Characters above U+00FF cannot be legacy bytes and are kept.
+fn from_legacy(text: &str, encoding: Encoding) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut bytes = Vec::new();
+    for c in text.chars() {
+        match u8::try_from(c) {
+            Ok(b) => bytes.push(b),
+            Err(_) => {
+                out.push_str(&encoding::decode(&bytes, encoding));
+                bytes.clear();
+                out.push(c);
+            }
+        }
+    }
+    out.push_str(&encoding::decode(&bytes, encoding));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tone() {
+        assert_eq!(
+            normalize_tone("Hòa, thủy, khỏe", ToneStyle::NewStyle),
+            "Hoà, thuỷ, khoẻ"
+        );
+        assert_eq!(
+            normalize_tone("Hoà, thuỷ, khoẻ", ToneStyle::TraditionalStyle),
+            "Hòa, thủy, khỏe"
+        );
+        // Final consonant or gi/qu initial: one placement in both styles
+        assert_eq!(
+            normalize_tone("hoàn quý già", ToneStyle::TraditionalStyle),
+            "hoàn quý già"
+        );
+        // Not Vietnamese, or more than one mark: left alone
+        assert_eq!(
+            normalize_tone("café \u{f2}\u{e1}", ToneStyle::NewStyle),
+            "café \u{f2}\u{e1}"
+        );
+        assert_eq!(
+            normalize_tone("ho\u{300}a", ToneStyle::NewStyle),
+            "ho\u{300}a"
+        );
+    }
+
+    #[test]
+    fn test_tone_renderings() {
+        let new = ToneStyle::NewStyle;
+        let old = ToneStyle::TraditionalStyle;
+        assert_eq!(tone_renderings("khoẻ", old), ["khỏe", "khoẻ"]);
+        assert_eq!(tone_renderings("Thủy", new), ["Thuỷ", "Thủy"]);
+        assert_eq!(tone_renderings("QÙY", old), ["QUỲ", "QÙY"]);
+        // One spelling: closed syllable, no mark, not Vietnamese
+        assert_eq!(tone_renderings("quýt", new), ["quýt"]);
+        assert_eq!(tone_renderings("hoa", new), ["hoa"]);
+        assert_eq!(tone_renderings("café", new), ["café"]);
+    }
+
+    #[test]
+    fn test_from_legacy() {
+        assert_eq!(from_legacy("H\u{b5} N\u{e9}i", Encoding::Tcvn3), "Hà Nội");
+        assert_eq!(from_legacy("Vie\u{e4}t → 日", Encoding::Vni), "Việt → 日");
+    }
+
+    #[test]
+    fn test_command_from_u8() {
+        assert_eq!(Command::from_u8(2), Some(Command::ToneStyle));
+        assert_eq!(Command::from_u8(5), None);
+    }
+}
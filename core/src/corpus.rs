@@ -0,0 +1,239 @@
+//! Golden typing corpora: input/expected pairs kept in TSV files
+//!
+//! Regression cases ("neeus" came out as "neếu" in Firefox) are one line of
+//! data, so contributors add them without writing Rust. The corpora ship
+//! inside the library: the integration tests run all of them and the
+//! self-test (`selftest::run`) replays the one for the user's method.
+//!
+//! - `telex.tsv`, `vni.tsv`: words, sentences and a paragraph that come out
+//!   the same under every option
+//! - `edge-cases.tsv`: reported bugs and option-dependent behavior
+//!
+//! Format, one line each:
+//!
+//! - `input<TAB>expected`: keys typed into a fresh engine (see `testkit`,
+//!   '<' is Backspace) and the text the app shows afterwards
+//! - `@option=value`: set an engine option (settings name) for the cases
+//!   below it; `@reset` goes back to the options the run started with
+//! - `# comment` and blank lines are skipped
+//!
+//! In both columns `\n`, `\t` and `\\` stand for a line break, a tab and a
+//! backslash. Trailing spaces count.
+
+use crate::engine::Engine;
+use crate::testkit;
+use crate::utils;
+
+/// Corpus for Telex
+pub const TELEX: &str = include_str!("data/corpus/telex.tsv");
+/// Corpus for VNI
+pub const VNI: &str = include_str!("data/corpus/vni.tsv");
+/// Reported bugs and option-dependent cases
+pub const EDGE_CASES: &str = include_str!("data/corpus/edge-cases.tsv");
+
+/// Built-in corpora by name
+pub const ALL: [(&str, &str); 3] = [("telex", TELEX), ("vni", VNI), ("edge-cases", EDGE_CASES)];
+
+/// One case: what to type, with which options, and the expected text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    /// Line in the file (1-based)
+    pub line: usize,
+    pub input: String,
+    pub expected: String,
+    /// Options set above the case, in file order
+    pub options: Vec<(String, String)>,
+}
+
+/// Line that is neither a case, an option nor a comment
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: cannot read {:?}", self.line, self.text)
+    }
+}
+
+/// Read the cases of a corpus file
+pub fn parse(content: &str) -> Result<Vec<Case>, ParseError> {
+    let mut options: Vec<(String, String)> = Vec::new();
+    let mut cases = Vec::new();
+    for (i, text) in content.lines().enumerate() {
+        let line = i + 1;
+        let error = || ParseError {
+            line,
+            text: text.to_string(),
+        };
+        if text.trim().is_empty() || text.starts_with('#') {
+            continue;
+        }
+        if text == "@reset" {
+            options.clear();
+        } else if let Some(option) = text.strip_prefix('@') {
+            let (key, value) = option.split_once('=').ok_or_else(error)?;
+            options.push((key.trim().to_string(), value.trim().to_string()));
+        } else {
+            let (input, expected) = text.split_once('\t').ok_or_else(error)?;
+            cases.push(Case {
+                line,
+                input: unescape(input),
+                expected: unescape(expected),
+                options: options.clone(),
+            });
+        }
+    }
+    Ok(cases)
+}
+
+/// Turn `\n`, `\t` and `\\` into the characters they stand for
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// A case whose output did not match
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub corpus: String,
+    pub line: usize,
+    pub input: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Result of running corpora
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub total: usize,
+    pub failures: Vec<Failure>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Type the cases of corpus `name`, each on a copy of `base` with the
+    /// case's options on top (`Engine::fresh_copy`: `base` is not touched)
+    ///
+    /// An option that cannot be set fails its case, naming the option.
+    pub fn run(&mut self, name: &str, cases: &[Case], base: &Engine) {
+        for case in cases {
+            self.total += 1;
+            let mut e = base.fresh_copy();
+            let actual = match case
+                .options
+                .iter()
+                .try_for_each(|(key, value)| e.set_option(key, value))
+            {
+                Ok(()) => testkit::type_text(&mut e, &case.input),
+                Err(err) => format!("<{}>", err),
+            };
+            if actual != case.expected {
+                self.failures.push(Failure {
+                    corpus: name.to_string(),
+                    line: case.line,
+                    input: case.input.clone(),
+                    expected: case.expected.clone(),
+                    actual,
+                });
+            }
+        }
+    }
+
+    /// Serialize as `{"total":N,"failed":M,"failures":[{"corpus","line",
+    /// "input","expected","actual"},...]}`
+    pub fn to_json(&self) -> String {
+        let esc = utils::json_escape;
+        let failures: Vec<String> = self
+            .failures
+            .iter()
+            .map(|f| {
+                format!(
+                    "{{\"corpus\":\"{}\",\"line\":{},\"input\":\"{}\",\"expected\":\"{}\",\"actual\":\"{}\"}}",
+                    esc(&f.corpus),
+                    f.line,
+                    esc(&f.input),
+                    esc(&f.expected),
+                    esc(&f.actual)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"total\":{},\"failed\":{},\"failures\":[{}]}}",
+            self.total,
+            self.failures.len(),
+            failures.join(",")
+        )
+    }
+}
+
+/// Run a built-in corpus (`ALL`) on copies of `base`
+pub fn run_builtin(name: &str, base: &Engine) -> Report {
+    let mut report = Report::default();
+    if let Some((name, content)) = ALL.iter().find(|(n, _)| *n == name) {
+        let cases = parse(content).expect("built-in corpus parses");
+        report.run(name, &cases, base);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cases_options_and_escapes() {
+        let cases =
+            parse("# comment\n\nas\tá\n@method=1\na1 \tá \n@reset\nx\\ny\ta\\tb\\\\\n").unwrap();
+        assert_eq!(cases.len(), 3);
+        assert_eq!((cases[0].line, cases[0].options.len()), (3, 0));
+        assert_eq!(
+            (cases[1].input.as_str(), cases[1].expected.as_str()),
+            ("a1 ", "á ")
+        );
+        assert_eq!(cases[1].options, [("method".to_string(), "1".to_string())]);
+        assert!(cases[2].options.is_empty());
+        assert_eq!(
+            (cases[2].input.as_str(), cases[2].expected.as_str()),
+            ("x\ny", "a\tb\\")
+        );
+        assert_eq!(parse("no tab").unwrap_err().line, 1);
+        assert_eq!(parse("@method").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn test_report_json() {
+        let report = Report {
+            total: 2,
+            failures: vec![Failure {
+                corpus: "telex".into(),
+                line: 9,
+                input: "as".into(),
+                expected: "á".into(),
+                actual: "a\"s".into(),
+            }],
+        };
+        assert_eq!(
+            report.to_json(),
+            r#"{"total":2,"failed":1,"failures":[{"corpus":"telex","line":9,"input":"as","expected":"á","actual":"a\"s"}]}"#
+        );
+    }
+}
@@ -0,0 +1,658 @@
+//! Service mode: one shared engine behind a JSON-lines protocol
+//!
+//! `gonhanh serve` keeps an engine in its own process, so thin platform
+//! shims and test harnesses can drive it without linking the library.
+//! Each request is one JSON object on one line and gets one line back,
+//! with the request's `id` echoed (a string, number or null; any other
+//! id is an error):
+//!
+//! ```text
+//! → {"id":1,"cmd":"key","key":0}
+//! ← {"id":1,"action":0,"backspace":0,"chars":"","flags":0,"events":[]}
+//! → {"id":2,"cmd":"key","key":1}
+//! ← {"id":2,"action":1,"backspace":1,"chars":"á","flags":0,"events":[]}
+//! → {"id":3,"cmd":"set","key":"method","value":"1"}
+//! ← {"id":3,"ok":true}
+//! → {"id":4,"cmd":"bogus"}
+//! ← {"id":4,"error":"unknown command: bogus"}
+//! ```
+//!
+//! Commands:
+//!
+//! - `key`: `key` (keycode) and optional `caps`, `ctrl`, `shift`, `char`
+//!   (the typed character, as for `ime_key_with_char`); answers the result
+//!   and the events it raised (`{"code":1,"text":"của"}`)
+//! - `clear`: end the word in progress (focus change, mouse click)
+//! - `get` / `set`: one option by settings file key (`ime_config_get`)
+//! - `config` / `set_config`: all options as a JSON object
+//!   (`ime_load_config_json`), `set_config` taking it in `config`
+//! - `ping`: answers the core version
+//! - `shutdown`: stop the server after answering
+//!
+//! The transport is stdin/stdout (`serve`), or one endpoint several
+//! clients share: a Unix socket (`serve_unix`) or, on Windows, a named
+//! pipe (`serve_pipe`). Either is only accessible to the user running the
+//! service, since keystrokes pass through it, and only from this machine.
+//!
+//! Browser extensions reach the same protocol through native messaging
+//! (`serve_native`, the `gonhanh-native-host` binary): each message is
+//! prefixed with its length as a 32-bit native-endian integer instead of
+//! ending with a newline.
+
+use crate::engine::{Engine, Result as KeyResult};
+use crate::json::{self, Value};
+use crate::utils::json_escape;
+use std::io::{self, BufRead, Read, Write};
+use std::sync::Mutex;
+
+/// The served engine
+pub struct Service {
+    engine: Engine,
+    shutdown: bool,
+}
+
+impl Service {
+    pub fn new(engine: Engine) -> Self {
+        Self {
+            engine,
+            shutdown: false,
+        }
+    }
+
+    /// The served engine, e.g. to configure it before serving
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    /// Whether a `shutdown` request was answered
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown
+    }
+
+    /// Answer one request line (without the trailing newline)
+    pub fn handle(&mut self, line: &str) -> String {
+        let Some(request @ Value::Object(_)) = json::parse(line) else {
+            return reply(None, "\"error\":\"invalid request\"");
+        };
+        let id = request.get("id");
+        if id.is_some_and(|id| !echoable(id)) {
+            // Without its id the client could not tell what the reply is for
+            return reply(None, "\"error\":\"invalid id: not a string or number\"");
+        }
+        let body = match self.dispatch(&request) {
+            Ok(body) => body,
+            Err(msg) => format!("\"error\":\"{}\"", json_escape(&msg)),
+        };
+        reply(id, &body)
+    }
+
+    fn dispatch(&mut self, request: &Value) -> Result<String, String> {
+        let string = |name: &str| {
+            request
+                .get(name)
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("missing {}", name))
+        };
+        let ok = "\"ok\":true".to_string();
+        match string("cmd")? {
+            "key" => {
+                let key = request
+                    .get("key")
+                    .and_then(Value::as_u64)
+                    .and_then(|k| u16::try_from(k).ok())
+                    .ok_or("missing key")?;
+                let flag = |name: &str| request.get(name).and_then(Value::as_bool) == Some(true);
+                let ch = request
+                    .get("char")
+                    .and_then(Value::as_str)
+                    .and_then(|s| s.chars().next());
+                let result = self.engine.on_key_with_char(
+                    key,
+                    flag("caps"),
+                    flag("ctrl"),
+                    flag("shift"),
+                    ch,
+                );
+                Ok(self.key_reply(&result))
+            }
+            "clear" => {
+                self.engine.clear();
+                Ok(ok)
+            }
+            "get" => {
+                let key = string("key")?;
+                let value = self
+                    .engine
+                    .option(key)
+                    .ok_or_else(|| format!("unknown option: {}", key))?;
+                Ok(format!("\"value\":\"{}\"", json_escape(&value)))
+            }
+            "set" => {
+                let value = match request.get("value") {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Bool(b)) => b.to_string(),
+                    Some(v @ Value::Number(_)) => v.as_u64().ok_or("invalid value")?.to_string(),
+                    _ => return Err("missing value".into()),
+                };
+                self.engine
+                    .set_option(string("key")?, &value)
+                    .map_err(|err| err.to_string())?;
+                Ok(ok)
+            }
+            "config" => Ok(format!("\"config\":{}", self.engine.config_json())),
+            "set_config" => {
+                let config = request.get("config").ok_or("missing config")?;
+                self.engine
+                    .set_config(config)
+                    .map_err(|err| err.to_string())?;
+                Ok(ok)
+            }
+            "ping" => Ok(format!("\"version\":\"{}\"", env!("CARGO_PKG_VERSION"))),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(ok)
+            }
+            other => Err(format!("unknown command: {}", other)),
+        }
+    }
+
+    fn key_reply(&mut self, r: &KeyResult) -> String {
+        let chars: String = r.chars[..r.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        let events: Vec<String> = self
+            .engine
+            .take_events()
+            .iter()
+            .map(|e| {
+                format!(
+                    "{{\"code\":{},\"text\":\"{}\"}}",
+                    e.code(),
+                    json_escape(e.text())
+                )
+            })
+            .collect();
+        format!(
+            "\"action\":{},\"backspace\":{},\"chars\":\"{}\",\"flags\":{},\"events\":[{}]",
+            r.action,
+            r.backspace,
+            json_escape(&chars),
+            r.flags,
+            events.join(",")
+        )
+    }
+}
+
+/// Whether `id` can be echoed back: null, a string or a number JSON can hold
+fn echoable(id: &Value) -> bool {
+    match id {
+        Value::Null | Value::String(_) => true,
+        Value::Number(n) => n.is_finite(),
+        _ => false,
+    }
+}
+
+/// One response object: the echoed id, then `body` members
+///
+/// Numbers are written back in their shortest form ("-1.5", "2" for "2.0").
+fn reply(id: Option<&Value>, body: &str) -> String {
+    let id = match id {
+        Some(Value::String(s)) => format!("\"id\":\"{}\",", json_escape(s)),
+        Some(Value::Number(n)) if n.is_finite() => format!("\"id\":{},", n),
+        Some(Value::Null) => "\"id\":null,".to_string(),
+        _ => String::new(),
+    };
+    format!("{{{}{}}}", id, body)
+}
+
+/// Answer requests from `input` until it ends or `shutdown` is requested
+pub fn serve(
+    service: &Mutex<Service>,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, done) = {
+            let mut service = service.lock().unwrap_or_else(|e| e.into_inner());
+            (service.handle(&line), service.is_shutdown())
+        };
+        writeln!(output, "{}", response)?;
+        output.flush()?;
+        if done {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Largest native message accepted, as Chrome allows the host to send
+pub const MAX_NATIVE_MESSAGE: usize = 1024 * 1024;
+
+/// Read one length-prefixed native message, None at end of input
+pub fn read_native_message(input: &mut impl Read) -> io::Result<Option<String>> {
+    let mut len = [0u8; 4];
+    match input.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u32::from_ne_bytes(len) as usize;
+    if len > MAX_NATIVE_MESSAGE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {} bytes", len),
+        ));
+    }
+    let mut body = vec![0; len];
+    input.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "message is not UTF-8"))
+}
+
+/// Write one length-prefixed native message
+pub fn write_native_message(output: &mut impl Write, message: &str) -> io::Result<()> {
+    output.write_all(&(message.len() as u32).to_ne_bytes())?;
+    output.write_all(message.as_bytes())?;
+    output.flush()
+}
+
+/// `serve` with native messaging framing (browser extensions)
+///
+/// Ends when the browser closes the pipe, which it does when the
+/// extension disconnects.
+pub fn serve_native(
+    service: &Mutex<Service>,
+    mut input: impl Read,
+    mut output: impl Write,
+) -> io::Result<()> {
+    while let Some(message) = read_native_message(&mut input)? {
+        let (response, done) = {
+            let mut service = service.lock().unwrap_or_else(|e| e.into_inner());
+            (service.handle(&message), service.is_shutdown())
+        };
+        write_native_message(&mut output, &response)?;
+        if done {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Listen on a Unix socket at `path`, one thread per client, all sharing
+/// `service`, until a client requests `shutdown`
+///
+/// A stale socket at `path` is replaced; anything else there is an error.
+/// The socket is only accessible to the current user from the start: it
+/// is bound in a directory only that user can enter, made private, then
+/// moved to `path`.
+#[cfg(unix)]
+pub fn serve_unix(service: Service, path: &std::path::Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::Arc;
+
+    if UnixStream::connect(path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "another service is listening",
+        ));
+    }
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if !meta.file_type().is_socket() => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "not a socket, left in place",
+            ));
+        }
+        Ok(_) | Err(_) => {}
+    }
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name"))?;
+    let private = path.with_file_name(format!(
+        ".{}.{}",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    std::fs::DirBuilder::new().mode(0o700).create(&private)?;
+    let bound = private.join("socket");
+    let listener = UnixListener::bind(&bound).and_then(|listener| {
+        std::fs::set_permissions(&bound, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&bound, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&bound);
+    let _ = std::fs::remove_dir(&private);
+    let listener = listener?;
+
+    let service = Arc::new(Mutex::new(service));
+    let is_shutdown = |s: &Mutex<Service>| s.lock().unwrap_or_else(|e| e.into_inner()).shutdown;
+    for stream in listener.incoming() {
+        if is_shutdown(&service) {
+            break;
+        }
+        let stream = stream?;
+        let service = Arc::clone(&service);
+        let path = path.to_path_buf();
+        std::thread::spawn(move || {
+            if let Ok(reader) = stream.try_clone() {
+                let _ = serve(&service, io::BufReader::new(reader), &stream);
+            }
+            if is_shutdown(&service) {
+                // Wake the accept loop so it sees the shutdown
+                let _ = UnixStream::connect(&path);
+            }
+        });
+    }
+    let _ = std::fs::remove_file(path);
+    Ok(())
+}
+
+/// Listen on a named pipe (`\\.\pipe\gonhanh`), one thread per client,
+/// all sharing `service`, until a client requests `shutdown`
+///
+/// Fails if another process owns the name. Only the current user can
+/// open the pipe, and only from this machine.
+#[cfg(windows)]
+pub fn serve_pipe(service: Service, name: &str) -> io::Result<()> {
+    use std::sync::Arc;
+
+    let pipe = pipe::Pipe::new(name)?;
+    let service = Arc::new(Mutex::new(service));
+    let is_shutdown = |s: &Mutex<Service>| s.lock().unwrap_or_else(|e| e.into_inner()).shutdown;
+    let mut first = true;
+    loop {
+        let stream = pipe.accept(first)?;
+        first = false;
+        if is_shutdown(&service) {
+            break;
+        }
+        let service = Arc::clone(&service);
+        let name = name.to_string();
+        std::thread::spawn(move || {
+            if let Ok(reader) = stream.try_clone() {
+                let _ = serve(&service, io::BufReader::new(reader), &stream);
+            }
+            if is_shutdown(&service) {
+                // Wake the accept loop so it sees the shutdown
+                let _ = pipe::connect(&name);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Named pipe server side, on the Win32 calls std does not wrap
+#[cfg(windows)]
+mod pipe {
+    use std::ffi::c_void;
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::io::{FromRawHandle, RawHandle};
+
+    const PIPE_ACCESS_DUPLEX: u32 = 0x3;
+    const FILE_FLAG_FIRST_PIPE_INSTANCE: u32 = 0x0008_0000;
+    /// Byte stream, blocking, local clients only
+    const PIPE_MODE: u32 = 0x8;
+    const PIPE_UNLIMITED_INSTANCES: u32 = 255;
+    const BUFFER_SIZE: u32 = 4096;
+    const ERROR_PIPE_CONNECTED: i32 = 535;
+    const SDDL_REVISION_1: u32 = 1;
+    /// Full access for the pipe's owner (the user running the service),
+    /// nobody else: not even read access for Everyone, the default
+    const OWNER_ONLY: &str = "D:P(A;;GA;;;OW)";
+
+    #[repr(C)]
+    struct SecurityAttributes {
+        length: u32,
+        descriptor: *mut c_void,
+        inherit: i32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateNamedPipeW(
+            name: *const u16,
+            open_mode: u32,
+            pipe_mode: u32,
+            max_instances: u32,
+            out_buffer_size: u32,
+            in_buffer_size: u32,
+            default_timeout: u32,
+            security: *const SecurityAttributes,
+        ) -> RawHandle;
+        fn ConnectNamedPipe(pipe: RawHandle, overlapped: *mut c_void) -> i32;
+        fn LocalFree(mem: *mut c_void) -> *mut c_void;
+    }
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            sddl: *const u16,
+            revision: u32,
+            descriptor: *mut *mut c_void,
+            size: *mut u32,
+        ) -> i32;
+    }
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain([0]).collect()
+    }
+
+    /// Pipe name and the owner-only security descriptor for its instances
+    pub(super) struct Pipe {
+        name: Vec<u16>,
+        descriptor: *mut c_void,
+    }
+
+    impl Pipe {
+        pub(super) fn new(name: &str) -> io::Result<Self> {
+            let sddl = wide(OWNER_ONLY);
+            let mut descriptor = std::ptr::null_mut();
+            // SAFETY: valid NUL-terminated SDDL; the descriptor is freed on drop
+            let ok = unsafe {
+                ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                    sddl.as_ptr(),
+                    SDDL_REVISION_1,
+                    &mut descriptor,
+                    std::ptr::null_mut(),
+                )
+            };
+            if ok == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self {
+                name: wide(name),
+                descriptor,
+            })
+        }
+
+        /// Create an instance and wait for a client to open it; `first`
+        /// fails if the name is already taken
+        pub(super) fn accept(&self, first: bool) -> io::Result<File> {
+            let security = SecurityAttributes {
+                length: std::mem::size_of::<SecurityAttributes>() as u32,
+                descriptor: self.descriptor,
+                inherit: 0,
+            };
+            let open_mode = if first {
+                PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE
+            } else {
+                PIPE_ACCESS_DUPLEX
+            };
+            // SAFETY: valid NUL-terminated name and security attributes
+            let handle = unsafe {
+                CreateNamedPipeW(
+                    self.name.as_ptr(),
+                    open_mode,
+                    PIPE_MODE,
+                    PIPE_UNLIMITED_INSTANCES,
+                    BUFFER_SIZE,
+                    BUFFER_SIZE,
+                    0,
+                    &security,
+                )
+            };
+            if handle as isize == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: a new handle owned by nothing else; the File closes it
+            let file = unsafe { File::from_raw_handle(handle) };
+            // SAFETY: `handle` is open (owned by `file`); no overlapped I/O
+            if unsafe { ConnectNamedPipe(handle, std::ptr::null_mut()) } == 0 {
+                let err = io::Error::last_os_error();
+                // A client that connected before the call is fine
+                if err.raw_os_error() != Some(ERROR_PIPE_CONNECTED) {
+                    return Err(err);
+                }
+            }
+            Ok(file)
+        }
+    }
+
+    impl Drop for Pipe {
+        fn drop(&mut self) {
+            // SAFETY: allocated by ConvertStringSecurityDescriptor..., freed once
+            unsafe { LocalFree(self.descriptor) };
+        }
+    }
+
+    /// Open the pipe as a client
+    pub(super) fn connect(name: &str) -> io::Result<File> {
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::keys;
+
+    #[test]
+    fn test_key_requests() {
+        let mut s = Service::new(Engine::new());
+        let key = |s: &mut Service, id: u32, key: u16| {
+            s.handle(&format!(
+                "{{\"id\":{},\"cmd\":\"key\",\"key\":{}}}",
+                id, key
+            ))
+        };
+        // Plain letters pass through
+        assert_eq!(
+            key(&mut s, 1, keys::A),
+            "{\"id\":1,\"action\":0,\"backspace\":0,\"chars\":\"\",\"flags\":0,\"events\":[]}"
+        );
+        assert_eq!(
+            key(&mut s, 2, keys::S),
+            "{\"id\":2,\"action\":1,\"backspace\":1,\"chars\":\"á\",\"flags\":0,\"events\":[]}"
+        );
+        assert_eq!(
+            s.handle(r#"{"id":"x","cmd":"clear"}"#),
+            r#"{"id":"x","ok":true}"#
+        );
+    }
+
+    #[test]
+    fn test_echoes_any_number_id() {
+        let mut s = Service::new(Engine::new());
+        for (id, echoed) in [
+            ("-3", "-3"),
+            ("1.5", "1.5"),
+            ("2.0", "2"),
+            ("1e3", "1000"),
+            ("null", "null"),
+        ] {
+            assert_eq!(
+                s.handle(&format!("{{\"id\":{},\"cmd\":\"clear\"}}", id)),
+                format!("{{\"id\":{},\"ok\":true}}", echoed)
+            );
+        }
+        for id in ["1e999", "true", "[1]"] {
+            assert_eq!(
+                s.handle(&format!("{{\"id\":{},\"cmd\":\"clear\"}}", id)),
+                r#"{"error":"invalid id: not a string or number"}"#
+            );
+        }
+    }
+
+    #[test]
+    fn test_config_requests() {
+        let mut s = Service::new(Engine::new());
+        assert_eq!(
+            s.handle(r#"{"id":1,"cmd":"set","key":"method","value":1}"#),
+            r#"{"id":1,"ok":true}"#
+        );
+        assert_eq!(
+            s.handle(r#"{"id":2,"cmd":"get","key":"method"}"#),
+            r#"{"id":2,"value":"1"}"#
+        );
+        assert_eq!(
+            s.handle(r#"{"id":3,"cmd":"set_config","config":{"method":0}}"#),
+            r#"{"id":3,"ok":true}"#
+        );
+        assert!(s
+            .handle(r#"{"cmd":"config"}"#)
+            .starts_with(r#"{"config":{"method":0,"#));
+        assert_eq!(
+            s.handle(r#"{"id":4,"cmd":"set","key":"method","value":"9"}"#),
+            r#"{"id":4,"error":"invalid value for method: \"9\""}"#
+        );
+    }
+
+    #[test]
+    fn test_bad_requests() {
+        let mut s = Service::new(Engine::new());
+        assert_eq!(s.handle("not json"), r#"{"error":"invalid request"}"#);
+        assert_eq!(s.handle(r#"{"id":1}"#), r#"{"id":1,"error":"missing cmd"}"#);
+        assert_eq!(
+            s.handle(r#"{"id":2,"cmd":"key"}"#),
+            r#"{"id":2,"error":"missing key"}"#
+        );
+        assert_eq!(
+            s.handle(r#"{"id":3,"cmd":"fly"}"#),
+            r#"{"id":3,"error":"unknown command: fly"}"#
+        );
+    }
+
+    #[test]
+    fn test_native_framing() {
+        let service = Mutex::new(Service::new(Engine::new()));
+        let mut input = Vec::new();
+        write_native_message(&mut input, r#"{"id":1,"cmd":"get","key":"method"}"#).unwrap();
+        let mut output = Vec::new();
+        serve_native(&service, &input[..], &mut output).unwrap();
+
+        let mut reader = &output[..];
+        assert_eq!(
+            read_native_message(&mut reader).unwrap().as_deref(),
+            Some(r#"{"id":1,"value":"0"}"#)
+        );
+        assert_eq!(read_native_message(&mut reader).unwrap(), None);
+
+        let huge = ((MAX_NATIVE_MESSAGE + 1) as u32).to_ne_bytes();
+        assert!(read_native_message(&mut &huge[..]).is_err());
+    }
+
+    #[test]
+    fn test_serve_until_shutdown() {
+        let service = Mutex::new(Service::new(Engine::new()));
+        let input = "{\"id\":1,\"cmd\":\"ping\"}\n\n{\"id\":2,\"cmd\":\"shutdown\"}\n{\"id\":3,\"cmd\":\"ping\"}\n";
+        let mut output = Vec::new();
+        serve(&service, input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("{\"id\":1,\"version\":"));
+        assert_eq!(lines[1], r#"{"id":2,"ok":true}"#);
+    }
+}
@@ -404,6 +404,55 @@ pub fn parse_char(c: char) -> Option<ParsedChar> {
     }
 }
 
+/// Tone or mark for a combining diacritic (U+0300 block)
+fn combining(c: char) -> Option<(u8, u8)> {
+    Some(match c {
+        '\u{0302}' => (tone::CIRCUMFLEX, mark::NONE),
+        '\u{0306}' | '\u{031B}' => (tone::HORN, mark::NONE),
+        '\u{0301}' => (tone::NONE, mark::SAC),
+        '\u{0300}' => (tone::NONE, mark::HUYEN),
+        '\u{0309}' => (tone::NONE, mark::HOI),
+        '\u{0303}' => (tone::NONE, mark::NGA),
+        '\u{0323}' => (tone::NONE, mark::NANG),
+        _ => return None,
+    })
+}
+
+/// Code points of `c` once decomposed (NFD): "ấ" → 3, "ư" → 2, "đ" → 1
+///
+/// Characters outside Vietnamese count as one.
+pub fn decomposed_len(c: char) -> usize {
+    parse_char(c).map_or(1, |p| {
+        1 + usize::from(p.tone != 0) + usize::from(p.mark != 0)
+    })
+}
+
+/// Compose decomposed Vietnamese (NFD: "o" + U+0302) into precomposed
+/// characters ("ô"), the form the engine produces
+///
+/// Text from macOS text fields and some web forms arrives decomposed.
+/// Combining marks that do not form a Vietnamese letter are kept as-is.
+pub fn compose(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        let composed = combining(c).and_then(|(t, m)| {
+            let p = parse_char(out.chars().last()?)?;
+            if !keys::is_vowel(p.key) || (t != 0 && p.tone != 0) || (m != 0 && p.mark != 0) {
+                return None;
+            }
+            to_char(p.key, p.caps, p.tone.max(t), p.mark.max(m))
+        });
+        match composed {
+            Some(ch) => {
+                out.pop();
+                out.push(ch);
+            }
+            None => out.push(c),
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -552,4 +601,15 @@ mod tests {
             assert_eq!((p.key, p.tone, p.mark), (key, t, m), "Failed for '{}'", ch);
         }
     }
+
+    #[test]
+    fn test_compose() {
+        assert_eq!(compose("ko\u{0302}"), "kô");
+        assert_eq!(compose("Vie\u{0302}\u{0323}t"), "Việt");
+        assert_eq!(compose("u\u{031B}o\u{031B}\u{0300}"), "ườ");
+        assert_eq!(compose("đu\u{031B}o\u{031B}\u{0323}c"), "được");
+        // Mark on a consonant, or a second tone, is not a Vietnamese letter
+        assert_eq!(compose("n\u{0301}"), "n\u{0301}");
+        assert_eq!(compose("ô\u{0306}"), "ô\u{0306}");
+    }
 }
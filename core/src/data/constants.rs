@@ -35,6 +35,9 @@ pub const VALID_INITIALS_1: &[u16] = &[
 /// these are treated as valid initial consonants.
 pub const FOREIGN_INITIALS: &[u16] = &[keys::Z, keys::W, keys::J, keys::F];
 
+/// Southern "dz" for v/d/gi ("dzô"), valid only with southern variants on
+pub const SOUTHERN_INITIAL_DZ: [u16; 2] = [keys::D, keys::Z];
+
 /// Valid double initial consonants (11 digraphs)
 /// Note: Kr is included for ethnic minority place names (Krông Búk)
 pub const VALID_INITIALS_2: &[[u16; 2]] = &[
@@ -182,39 +185,21 @@ pub const SPELLING_RULES: &[(&[u16], &[u16], &str)] = &[
     ),
 ];
 
+/// Correct spelling of each consonant in `SPELLING_RULES`: (wrong, right)
+pub const SPELLING_FIXES: &[(&[u16], &[u16])] = &[
+    (&[keys::C], &[keys::K]),                            // ce → ke
+    (&[keys::K], &[keys::C]),                            // ka → ca
+    (&[keys::G], &[keys::G, keys::H]),                   // ge → ghe
+    (&[keys::N, keys::G], &[keys::N, keys::G, keys::H]), // nge → nghe
+    (&[keys::G, keys::H], &[keys::G]),                   // gha → ga
+    (&[keys::N, keys::G, keys::H], &[keys::N, keys::G]), // ngha → nga
+];
+
 // =============================================================================
 // AUTO-RESTORE RULES
 // These patterns are used by auto-restore to detect invalid Vietnamese
 // =============================================================================
 
-/// Invalid rhyme patterns: -ing + tone mark is NOT valid Vietnamese
-/// Vietnamese uses -inh (tính, kính), not -ing with tone marks
-/// The rhyme "-ing" exists only in loanwords without tone marks
-pub const INVALID_RHYME_ING: &[[u16; 2]] = &[
-    [keys::N, keys::G], // -ng final after 'i' with tone = invalid
-];
-
-/// Open diphthongs (vần mở) - CANNOT take consonant finals (C/K/M/N/P/T/CH/NG/NH)
-/// These diphthongs end with semi-vowel I/O/U/Y that completes the rhyme.
-/// Examples:
-///   - "ai" (tài) ✓, "ain" ✗ (invalid)
-///   - "ao" (cào) ✓, "aon" ✗ (invalid)  ← catches "mason" → "máon"
-///   - "au" (đau) ✓, "aum" ✗ (invalid)
-///   - "oi" (tôi) ✓, "oin" ✗ (invalid)
-///
-/// Note: Diphthongs ia/iê, oa/oă, ua/ưa, uê, uô/ươ, uy CAN take consonant finals.
-pub const OPEN_DIPHTHONGS: &[[u16; 2]] = &[
-    [keys::A, keys::I], // ai - ends with semi-vowel I
-    [keys::A, keys::O], // ao - ends with semi-vowel O
-    [keys::A, keys::U], // au/âu - ends with semi-vowel U
-    [keys::A, keys::Y], // ay/ây - ends with semi-vowel Y
-    [keys::E, keys::O], // eo - ends with semi-vowel O
-    [keys::I, keys::U], // iu - ends with semi-vowel U
-    [keys::O, keys::I], // oi/ôi/ơi - ends with semi-vowel I
-    [keys::U, keys::I], // ui/ưi - ends with semi-vowel I
-    [keys::U, keys::U], // ưu - ends with semi-vowel U
-];
-
 /// Common Vietnamese single-vowel interjections (should NOT be restored)
 /// These standalone vowels with tone marks are valid Vietnamese words
 /// Example: à (ah), ồ (oh!), ừ (yeah)
@@ -2,13 +2,64 @@
 //!
 //! Uses HashSet-based word lookup for efficient Vietnamese word validation.
 //! Memory-efficient: ~0.5MB vs ~5.5MB with full Hunspell implementation.
+//!
+//! The word lists are embedded, but a newer copy downloaded next to the
+//! settings can replace either one at runtime (`load_override`), so word
+//! list fixes ship without an app release. A downloaded file stamps its
+//! first line with a version and an FNV-1a checksum of the rest:
+//!
+//! ```text
+//! 6712 20261016 5b1e0c9a7d3f2e41
+//! a
+//! ...
+//! ```
+//!
+//! The embedded files only carry the word count (version 0).
+//!
+//! Regional and loanword spellings ("dzô", "chẩy", "sịp") live in separate
+//! lists that only count when turned on (`VARIANT_*` bits), so writing
+//! colloquially stays opt-in.
+//!
+//! Users add domain vocabularies (y khoa, pháp lý) by dropping `*.dic`
+//! files into a folder (`load_user_dir`); their words count as valid
+//! Vietnamese on top of `vi.dic`. These are plain lists, not stamped:
+//!
+//! ```text
+//! # thuật ngữ y khoa
+//! insulin
+//! xét nghiệm
+//! ```
 
+use crate::cache::Lru;
+use crate::data::chars;
+use crate::latency::{self, Stage};
+use std::cell::RefCell;
 use std::collections::HashSet;
-use std::sync::LazyLock;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, RwLock};
 
 // Embed dictionary files into binary
 const DIC_VI: &str = include_str!("dictionaries/vi.dic");
 const DIC_KEEP: &str = include_str!("dictionaries/keep.dic");
+/// Common two-syllable words ("cảm ơn"), one per line
+const COMPOUNDS_VI: &str = include_str!("dictionaries/vi_compounds.txt");
+/// Optional spelling variants, indexed by bit position of `VARIANT_*`
+const DIC_VARIANTS: [&str; 3] = [
+    include_str!("dictionaries/south.dic"),
+    include_str!("dictionaries/north.dic"),
+    include_str!("dictionaries/loan.dic"),
+];
+
+/// Southern spellings: z/dz for v/d/gi ("dzô", "zậy"), "bển", "nghen"
+pub const VARIANT_SOUTHERN: u8 = 0x01;
+/// Northern spellings: "chẩy", "dầy", "quí", "lày"
+pub const VARIANT_NORTHERN: u8 = 0x02;
+/// Vietnamized loanwords: "pốt", "sịp", "okê"
+pub const VARIANT_LOANWORDS: u8 = 0x04;
+/// All variant lists
+pub const VARIANT_ALL: u8 = VARIANT_SOUTHERN | VARIANT_NORTHERN | VARIANT_LOANWORDS;
 
 /// Parse .dic file into HashSet (skip first line which is word count)
 fn parse_dic_to_hashset(dic_content: &'static str) -> HashSet<&'static str> {
@@ -22,6 +73,321 @@ static DICT_VI: LazyLock<HashSet<&'static str>> = LazyLock::new(|| parse_dic_to_
 static DICT_KEEP: LazyLock<HashSet<&'static str>> =
     LazyLock::new(|| parse_dic_to_hashset(DIC_KEEP));
 
+/// Rank tables generated by `build.rs` from `vi_freq.txt`, the most
+/// frequent syllables of `vi.dic`, most frequent first (not stamped: ranks
+/// ship with the app)
+#[allow(dead_code)] // only the order is used, not the ranks
+mod freq_vi {
+    include!(concat!(env!("OUT_DIR"), "/vi_freq.rs"));
+}
+
+/// Common two-syllable words, syllables separated by one space
+static COMPOUNDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    COMPOUNDS_VI
+        .lines()
+        .filter(|line| !line.is_empty())
+        .collect()
+});
+
+/// Lazy-loaded variant lists, same order as `DIC_VARIANTS`
+static DICT_VARIANTS: LazyLock<[HashSet<&'static str>; 3]> =
+    LazyLock::new(|| DIC_VARIANTS.map(parse_dic_to_hashset));
+
+/// Downloaded lists replacing the embedded ones, indexed by `WordList`
+static OVERRIDES: [RwLock<Option<Override>>; 2] = [RwLock::new(None), RwLock::new(None)];
+
+/// Words from the user's `*.dic` files, merged into the Vietnamese list
+static USER_WORDS: RwLock<Option<HashSet<String>>> = RwLock::new(None);
+
+/// Bumped when a list changes, so per-thread lookup caches start over
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Recent lookups kept per list
+const CACHE_SIZE: usize = 64;
+
+/// Recent lookups per list, made under one `GENERATION`
+struct Lookups {
+    generation: u64,
+    lists: [Lru<String, bool>; 2],
+}
+
+thread_local! {
+    static LOOKUPS: RefCell<Lookups> = const {
+        RefCell::new(Lookups {
+            generation: 0,
+            lists: [Lru::new(CACHE_SIZE), Lru::new(CACHE_SIZE)],
+        })
+    };
+}
+
+/// Which embedded word list
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordList {
+    /// Valid Vietnamese words (`vi.dic`)
+    Vietnamese = 0,
+    /// Words never auto-restored (`keep.dic`)
+    Keep = 1,
+}
+
+impl WordList {
+    /// From FFI value, None if unknown
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Vietnamese),
+            1 => Some(Self::Keep),
+            _ => None,
+        }
+    }
+
+    /// File name of this list ("vi.dic")
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Vietnamese => "vi.dic",
+            Self::Keep => "keep.dic",
+        }
+    }
+
+    fn embedded_text(self) -> &'static str {
+        match self {
+            Self::Vietnamese => DIC_VI,
+            Self::Keep => DIC_KEEP,
+        }
+    }
+
+    fn embedded(self) -> &'static HashSet<&'static str> {
+        match self {
+            Self::Vietnamese => &DICT_VI,
+            Self::Keep => &DICT_KEEP,
+        }
+    }
+}
+
+/// A downloaded word list in use
+struct Override {
+    version: u32,
+    words: HashSet<String>,
+}
+
+/// Why a downloaded list (word list, `quirks` table) was not used
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    /// File could not be read
+    Io(String),
+    /// No version stamp, or the line count does not match
+    Malformed,
+    /// Contents do not match the stamped checksum
+    Checksum,
+    /// Not newer than the embedded list or the one already loaded
+    NotNewer,
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(msg) => write!(f, "cannot read list: {}", msg),
+            LoadError::Malformed => write!(f, "malformed list"),
+            LoadError::Checksum => write!(f, "list checksum mismatch"),
+            LoadError::NotNewer => write!(f, "list is not newer"),
+        }
+    }
+}
+
+/// FNV-1a (64-bit) of `data`, the checksum stamped on list files
+pub fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A list file for `words` (one per line), stamped with `version` and
+/// its checksum
+pub fn stamp(words: &[&str], version: u32) -> String {
+    let body: String = words.iter().map(|w| format!("{}\n", w)).collect();
+    format!(
+        "{} {} {:016x}\n{}",
+        words.len(),
+        version,
+        checksum(body.as_bytes()),
+        body
+    )
+}
+
+/// Version of the list `list` now in use (0: embedded, unstamped)
+pub fn version(list: WordList) -> u32 {
+    match &*read_override(list) {
+        Some(o) => o.version,
+        None => embedded_version(list),
+    }
+}
+
+fn embedded_version(list: WordList) -> u32 {
+    let header = list.embedded_text().lines().next().unwrap_or("");
+    header
+        .split_whitespace()
+        .nth(1)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Use the stamped word list file at `path` instead of the embedded `list`
+///
+/// The file must be intact and newer than the embedded list, so a stale
+/// download left behind by an app update that ships newer lists is
+/// ignored. Reloading the version already in use is allowed; older ones
+/// are not. Returns the version now in use.
+pub fn load_override(list: WordList, path: &str) -> Result<u32, LoadError> {
+    let text = fs::read_to_string(path).map_err(|err| LoadError::Io(err.to_string()))?;
+    load_override_text(list, &text)
+}
+
+/// Check the stamp of a list file: its version and non-empty lines
+pub fn unstamp(text: &str) -> Result<(u32, Vec<&str>), LoadError> {
+    let (header, body) = text.split_once('\n').ok_or(LoadError::Malformed)?;
+    let fields: Vec<&str> = header.split_whitespace().collect();
+    let [count, version, sum] = fields[..] else {
+        return Err(LoadError::Malformed);
+    };
+    let count: usize = count.parse().map_err(|_| LoadError::Malformed)?;
+    let version: u32 = version.parse().map_err(|_| LoadError::Malformed)?;
+    let sum = u64::from_str_radix(sum, 16).map_err(|_| LoadError::Malformed)?;
+    if checksum(body.as_bytes()) != sum {
+        return Err(LoadError::Checksum);
+    }
+    let lines: Vec<&str> = body.lines().filter(|l| !l.is_empty()).collect();
+    if lines.len() != count {
+        return Err(LoadError::Malformed);
+    }
+    Ok((version, lines))
+}
+
+/// `load_override` with the file contents
+pub fn load_override_text(list: WordList, text: &str) -> Result<u32, LoadError> {
+    let (version, lines) = unstamp(text)?;
+    let words: HashSet<String> = lines.iter().map(|w| w.to_string()).collect();
+    // Duplicates would make the stamped count lie about the list
+    if words.len() != lines.len() {
+        return Err(LoadError::Malformed);
+    }
+
+    let mut guard = OVERRIDES[list as usize]
+        .write()
+        .unwrap_or_else(|e| e.into_inner());
+    let current = guard.as_ref().map_or(0, |o| o.version);
+    if version <= embedded_version(list) || version < current {
+        return Err(LoadError::NotNewer);
+    }
+    *guard = Some(Override { version, words });
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+    Ok(version)
+}
+
+/// Go back to the embedded `list`
+pub fn reset_override(list: WordList) {
+    *OVERRIDES[list as usize]
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = None;
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Words of a user word list file, lowercase and composed
+///
+/// One word per line; a line with several syllables ("xét nghiệm") adds
+/// each of them. Lines starting with `#` are comments, and a leading word
+/// count line (as in `vi.dic`) is skipped.
+pub fn parse_user_dic(text: &str) -> Vec<String> {
+    let mut lines = text.lines().peekable();
+    if lines
+        .peek()
+        .is_some_and(|l| !l.trim().is_empty() && l.trim().bytes().all(|b| b.is_ascii_digit()))
+    {
+        lines.next();
+    }
+    lines
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .flat_map(str::split_whitespace)
+        .map(|w| chars::compose(w).to_lowercase())
+        .collect()
+}
+
+/// Load every `*.dic` file in `dir` as user words, replacing the ones
+/// loaded before
+///
+/// A missing folder loads nothing and succeeds. If a file cannot be read,
+/// the words loaded before stay in use. Returns the number of words now
+/// loaded.
+pub fn load_user_dir(dir: &Path) -> Result<usize, LoadError> {
+    let io = |err: std::io::Error| LoadError::Io(err.to_string());
+    let mut words = HashSet::new();
+    if dir.is_dir() {
+        for entry in fs::read_dir(dir).map_err(io)? {
+            let path = entry.map_err(io)?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("dic") {
+                continue;
+            }
+            let text = fs::read_to_string(&path).map_err(io)?;
+            words.extend(parse_user_dic(&text));
+        }
+    }
+    let count = words.len();
+    *USER_WORDS.write().unwrap_or_else(|e| e.into_inner()) = (count > 0).then_some(words);
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+    Ok(count)
+}
+
+/// Whether `word` is in the user's word lists, case-insensitive
+pub fn is_user_word(word: &str) -> bool {
+    !word.is_empty()
+        && read_user()
+            .as_ref()
+            .is_some_and(|w| w.contains(word.to_lowercase().as_str()))
+}
+
+/// Number of user words loaded by `load_user_dir`
+pub fn user_word_count() -> usize {
+    read_user().as_ref().map_or(0, HashSet::len)
+}
+
+/// Drop the user words
+pub fn reset_user() {
+    *USER_WORDS.write().unwrap_or_else(|e| e.into_inner()) = None;
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+fn read_user() -> std::sync::RwLockReadGuard<'static, Option<HashSet<String>>> {
+    USER_WORDS.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn read_override(list: WordList) -> std::sync::RwLockReadGuard<'static, Option<Override>> {
+    OVERRIDES[list as usize]
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+fn contains(list: WordList, word: &str) -> bool {
+    let _timing = latency::span(Stage::Dictionary);
+    let generation = GENERATION.load(Ordering::Relaxed);
+    let cached = LOOKUPS.with_borrow_mut(|lookups| {
+        if lookups.generation != generation {
+            lookups.generation = generation;
+            lookups.lists.iter_mut().for_each(Lru::clear);
+        }
+        lookups.lists[list as usize].get(word)
+    });
+    latency::record_lookup(Stage::Dictionary, cached.is_some());
+    if let Some(found) = cached {
+        return found;
+    }
+    let found = match &*read_override(list) {
+        Some(o) => o.words.contains(word),
+        None => list.embedded().contains(word),
+    } || (list == WordList::Vietnamese
+        && read_user().as_ref().is_some_and(|w| w.contains(word)));
+    LOOKUPS.with_borrow_mut(|lookups| lookups.lists[list as usize].insert(word.to_string(), found));
+    found
+}
+
 /// Check if word starts with foreign consonant (z, w, j, f)
 fn starts_with_foreign_consonant(word: &str) -> bool {
     matches!(
@@ -46,7 +412,37 @@ pub fn is_vietnamese(word: &str, allow_foreign: bool) -> bool {
 
     // Case-insensitive lookup (dictionary stores lowercase)
     let word_lower = word.to_lowercase();
-    DICT_VI.contains(word_lower.as_str())
+    contains(WordList::Vietnamese, &word_lower)
+}
+
+/// Common Vietnamese syllables, most frequent first (lowercase)
+pub fn frequent_words() -> impl Iterator<Item = &'static str> {
+    freq_vi::BY_RANK.iter().copied()
+}
+
+/// Whether `first` followed by `second` is a common two-syllable word
+/// ("máy" + "tính"), case-insensitive
+pub fn is_compound(first: &str, second: &str) -> bool {
+    if first.is_empty() || second.is_empty() {
+        return false;
+    }
+    let _timing = latency::span(Stage::Dictionary);
+    let pair = format!("{} {}", first, second).to_lowercase();
+    COMPOUNDS.contains(pair.as_str())
+}
+
+/// Whether `word` is in one of the variant lists selected by `variants`
+/// (`VARIANT_*` bits), case-insensitive
+pub fn is_variant(word: &str, variants: u8) -> bool {
+    if word.is_empty() || variants & VARIANT_ALL == 0 {
+        return false;
+    }
+    let _timing = latency::span(Stage::Dictionary);
+    let word_lower = word.to_lowercase();
+    DICT_VARIANTS
+        .iter()
+        .enumerate()
+        .any(|(bit, list)| variants & (1 << bit) != 0 && list.contains(word_lower.as_str()))
 }
 
 /// Check if a word is in the keep list (should not be auto-restored)
@@ -55,7 +451,7 @@ pub fn should_keep(word: &str) -> bool {
         return false;
     }
     let word_lower = word.to_lowercase();
-    DICT_KEEP.contains(word_lower.as_str())
+    contains(WordList::Keep, &word_lower)
 }
 
 #[cfg(test)]
@@ -109,4 +505,79 @@ mod tests {
         // allow_foreign=true skips the foreign consonant check, but word must still be in dictionary
         assert!(!is_vietnamese("zá", true)); // Not in dict → false
     }
+
+    #[test]
+    fn test_compounds() {
+        assert!(is_compound("cảm", "ơn"));
+        assert!(is_compound("Máy", "tính"));
+        assert!(!is_compound("tính", "máy"));
+        assert!(!is_compound("máy", ""));
+    }
+
+    #[test]
+    fn test_variants_need_their_bit() {
+        assert!(!is_vietnamese("dzô", true));
+        assert!(!is_variant("dzô", 0));
+        assert!(is_variant("Dzô", VARIANT_SOUTHERN));
+        assert!(!is_variant("dzô", VARIANT_NORTHERN | VARIANT_LOANWORDS));
+        assert!(is_variant("chẩy", VARIANT_NORTHERN));
+        assert!(is_variant("sịp", VARIANT_ALL));
+    }
+
+    #[test]
+    fn test_parse_user_dic() {
+        let text = "3\n# y khoa\nInsulin\n\n  xét nghiệm \nno\u{0302}\n";
+        assert_eq!(parse_user_dic(text), ["insulin", "xét", "nghiệm", "nô"]);
+        assert_eq!(parse_user_dic("12a\nb"), ["12a", "b"]);
+    }
+
+    #[test]
+    fn test_override_rejects_bad_files() {
+        let good = stamp(&["ba", "ma"], 7);
+        assert_eq!(
+            load_override_text(WordList::Vietnamese, "2\nba\nma\n"),
+            Err(LoadError::Malformed)
+        );
+        let tampered = good.replace("ma", "mà");
+        assert_eq!(
+            load_override_text(WordList::Vietnamese, &tampered),
+            Err(LoadError::Checksum)
+        );
+        let miscounted = good.replacen('2', "3", 1);
+        assert_eq!(
+            load_override_text(WordList::Vietnamese, &miscounted),
+            Err(LoadError::Malformed)
+        );
+        assert_eq!(
+            load_override_text(WordList::Vietnamese, &stamp(&["ba"], 0)),
+            Err(LoadError::NotNewer)
+        );
+        assert!(matches!(
+            load_override(WordList::Vietnamese, "/nonexistent/vi.dic"),
+            Err(LoadError::Io(_))
+        ));
+        assert_eq!(version(WordList::Vietnamese), 0);
+    }
+
+    #[test]
+    fn test_override_replaces_embedded_list() {
+        // Embedded keep list plus one word, so other tests see no change
+        let mut words: Vec<&str> = DIC_KEEP.lines().skip(1).collect();
+        words.push("qoan");
+        assert!(!should_keep("qoan"));
+
+        assert_eq!(load_override_text(WordList::Keep, &stamp(&words, 5)), Ok(5));
+        assert!(should_keep("qoan"));
+        assert!(should_keep("Has"));
+        assert_eq!(version(WordList::Keep), 5);
+        assert_eq!(
+            load_override_text(WordList::Keep, &stamp(&words, 4)),
+            Err(LoadError::NotNewer)
+        );
+        assert_eq!(load_override_text(WordList::Keep, &stamp(&words, 5)), Ok(5));
+
+        reset_override(WordList::Keep);
+        assert!(!should_keep("qoan"));
+        assert_eq!(version(WordList::Keep), 0);
+    }
 }
@@ -2,7 +2,14 @@
 //!
 //! Uses merged dictionary: 10k common words + words with double telex chars.
 //! Only restores to English when raw_input is a known English word.
+//!
+//! A second, much shorter list (`english_freq.txt`, most frequent first)
+//! ranks the most frequent English words, so auto-restore can tell "down"
+//! (worth restoring over "dơn", which is no word) from a word nobody types
+//! often. `build.rs` turns it into a rank table and checks every word is in
+//! the dictionary.
 
+use crate::latency::{self, Stage};
 use std::collections::HashSet;
 use std::sync::LazyLock;
 
@@ -17,8 +24,25 @@ static DICT: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
         .collect()
 });
 
+/// Rank tables generated from `english_freq.txt`
+#[allow(dead_code)] // only the ranks are looked up, not the order
+mod freq {
+    include!(concat!(env!("OUT_DIR"), "/english_freq.rs"));
+}
+
+/// Frequency rank of a common English word (0 = most frequent), None for
+/// words outside the frequency list (case-insensitive)
+pub fn frequency_rank(word: &str) -> Option<usize> {
+    let lower = word.to_lowercase();
+    freq::RANKS
+        .binary_search_by(|&(w, _)| w.cmp(lower.as_str()))
+        .ok()
+        .map(|i| freq::RANKS[i].1 as usize)
+}
+
 /// Check if a word is in the English dictionary (case-insensitive)
 pub fn is_english_word(word: &str) -> bool {
+    let _timing = latency::span(Stage::Dictionary);
     let lower = word.to_lowercase();
     DICT.contains(lower.as_str())
 }
@@ -65,6 +65,133 @@ pub const MINUS: u16 = 27;
 pub const EQUAL: u16 = 24;
 pub const BACKQUOTE: u16 = 50;
 
+// Key classes, combined in `KEY_TABLE`
+const VOWEL: u8 = 0x01;
+const LETTER: u8 = 0x02;
+const NUMBER: u8 = 0x04;
+const BREAK: u8 = 0x08;
+
+/// Keycodes covered by the lookup table (all of the above are below it)
+const TABLE_SIZE: usize = 128;
+
+/// Every known key: keycode, classes, character without and with Shift
+///
+/// The only place keys are classified; add new keycodes (numpad, other
+/// layouts) here. `'\0'` means no character: punctuation is typed by the
+/// host, so only letters and digits have one without Shift.
+const KEY_DEFS: &[(u16, u8, char, char)] = &[
+    (A, LETTER | VOWEL, 'a', '\0'),
+    (B, LETTER, 'b', '\0'),
+    (C, LETTER, 'c', '\0'),
+    (D, LETTER, 'd', '\0'),
+    (E, LETTER | VOWEL, 'e', '\0'),
+    (F, LETTER, 'f', '\0'),
+    (G, LETTER, 'g', '\0'),
+    (H, LETTER, 'h', '\0'),
+    (I, LETTER | VOWEL, 'i', '\0'),
+    (J, LETTER, 'j', '\0'),
+    (K, LETTER, 'k', '\0'),
+    (L, LETTER, 'l', '\0'),
+    (M, LETTER, 'm', '\0'),
+    (N, LETTER, 'n', '\0'),
+    (O, LETTER | VOWEL, 'o', '\0'),
+    (P, LETTER, 'p', '\0'),
+    (Q, LETTER, 'q', '\0'),
+    (R, LETTER, 'r', '\0'),
+    (S, LETTER, 's', '\0'),
+    (T, LETTER, 't', '\0'),
+    (U, LETTER | VOWEL, 'u', '\0'),
+    (V, LETTER, 'v', '\0'),
+    (W, LETTER, 'w', '\0'),
+    (X, LETTER, 'x', '\0'),
+    (Y, LETTER | VOWEL, 'y', '\0'),
+    (Z, LETTER, 'z', '\0'),
+    (N1, NUMBER, '1', '!'),
+    (N2, NUMBER, '2', '@'),
+    (N3, NUMBER, '3', '#'),
+    (N4, NUMBER, '4', '$'),
+    (N5, NUMBER, '5', '%'),
+    (N6, NUMBER, '6', '^'),
+    (N7, NUMBER, '7', '&'),
+    (N8, NUMBER, '8', '*'),
+    (N9, NUMBER, '9', '('),
+    (N0, NUMBER, '0', ')'),
+    (SPACE, BREAK, '\0', '\0'),
+    (TAB, BREAK, '\0', '\0'),
+    (RETURN, BREAK, '\0', '\0'),
+    (ENTER, BREAK, '\0', '\0'),
+    (ESC, BREAK, '\0', '\0'),
+    (LEFT, BREAK, '\0', '\0'),
+    (RIGHT, BREAK, '\0', '\0'),
+    (UP, BREAK, '\0', '\0'),
+    (DOWN, BREAK, '\0', '\0'),
+    (DOT, BREAK, '\0', '>'),
+    (COMMA, BREAK, '\0', '<'),
+    (SLASH, BREAK, '\0', '?'),
+    (SEMICOLON, BREAK, '\0', ':'),
+    (QUOTE, BREAK, '\0', '"'),
+    (LBRACKET, BREAK, '\0', '{'),
+    (RBRACKET, BREAK, '\0', '}'),
+    (BACKSLASH, BREAK, '\0', '|'),
+    (MINUS, BREAK, '\0', '_'),
+    (EQUAL, BREAK, '\0', '+'),
+    (BACKQUOTE, BREAK, '\0', '~'),
+];
+
+/// One keycode's entry in `KEY_TABLE`
+#[derive(Clone, Copy)]
+struct KeyInfo {
+    class: u8,
+    base: char,
+    shifted: char,
+}
+
+/// `KEY_DEFS` indexed by keycode
+static KEY_TABLE: [KeyInfo; TABLE_SIZE] = build_table();
+
+const fn build_table() -> [KeyInfo; TABLE_SIZE] {
+    let mut table = [KeyInfo {
+        class: 0,
+        base: '\0',
+        shifted: '\0',
+    }; TABLE_SIZE];
+    let mut i = 0;
+    while i < KEY_DEFS.len() {
+        let (key, class, base, shifted) = KEY_DEFS[i];
+        assert!((key as usize) < TABLE_SIZE, "keycode outside the table");
+        assert!(table[key as usize].class == 0, "keycode defined twice");
+        table[key as usize] = KeyInfo {
+            class,
+            base,
+            shifted,
+        };
+        i += 1;
+    }
+    table
+}
+
+#[inline]
+fn info(key: u16) -> Option<&'static KeyInfo> {
+    KEY_TABLE.get(key as usize)
+}
+
+#[inline]
+fn class(key: u16) -> u8 {
+    info(key).map_or(0, |k| k.class)
+}
+
+/// Lowercase letter or digit typed by the key without Shift
+#[inline]
+pub fn base_char(key: u16) -> Option<char> {
+    info(key).map(|k| k.base).filter(|&c| c != '\0')
+}
+
+/// Symbol typed by a number or punctuation key with Shift ('@' for 2)
+#[inline]
+pub fn shifted_char(key: u16) -> Option<char> {
+    info(key).map(|k| k.shifted).filter(|&c| c != '\0')
+}
+
 /// Check if key breaks word (space, punctuation, arrows, etc.)
 /// When shift=true, also treat number keys as break (they produce !@#$%^&*())
 pub fn is_break(key: u16) -> bool {
@@ -73,81 +200,61 @@ pub fn is_break(key: u16) -> bool {
 
 /// Extended break check with shift parameter
 /// Shift+number keys produce symbols like @, !, #, etc. which are break chars
+#[inline]
 pub fn is_break_ext(key: u16, shift: bool) -> bool {
-    // Standard break keys
-    let standard_break = matches!(
-        key,
-        SPACE
-            | TAB
-            | RETURN
-            | ENTER
-            | ESC
-            | LEFT
-            | RIGHT
-            | UP
-            | DOWN
-            | DOT
-            | COMMA
-            | SLASH
-            | SEMICOLON
-            | QUOTE
-            | LBRACKET
-            | RBRACKET
-            | BACKSLASH
-            | MINUS
-            | EQUAL
-            | BACKQUOTE
-    );
-
-    // Shifted number keys produce symbols: !@#$%^&*()
-    let shifted_number = shift && matches!(key, N1 | N2 | N3 | N4 | N5 | N6 | N7 | N8 | N9 | N0);
-
-    standard_break || shifted_number
+    let breaks = if shift { BREAK | NUMBER } else { BREAK };
+    class(key) & breaks != 0
 }
 
 /// Check if key is a vowel (a, e, i, o, u, y)
+#[inline]
 pub fn is_vowel(key: u16) -> bool {
-    matches!(key, A | E | I | O | U | Y)
+    class(key) & VOWEL != 0
 }
 
 /// Check if key is a letter
+#[inline]
 pub fn is_letter(key: u16) -> bool {
-    matches!(
-        key,
-        A | B
-            | C
-            | D
-            | E
-            | F
-            | G
-            | H
-            | I
-            | J
-            | K
-            | L
-            | M
-            | N
-            | O
-            | P
-            | Q
-            | R
-            | S
-            | T
-            | U
-            | V
-            | W
-            | X
-            | Y
-            | Z
-    )
+    class(key) & LETTER != 0
 }
 
 /// Check if key is a consonant
+#[inline]
 pub fn is_consonant(key: u16) -> bool {
-    is_letter(key) && !is_vowel(key)
+    class(key) & (LETTER | VOWEL) == LETTER
 }
 
 /// Check if key is a number (0-9)
+#[inline]
 pub fn is_number(key: u16) -> bool {
-    matches!(key, N0 | N1 | N2 | N3 | N4 | N5 | N6 | N7 | N8 | N9)
+    class(key) & NUMBER != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_classes() {
+        let letters = (0..TABLE_SIZE as u16).filter(|&k| is_letter(k)).count();
+        let vowels: String = (0..TABLE_SIZE as u16)
+            .filter(|&k| is_vowel(k))
+            .filter_map(base_char)
+            .collect();
+        assert_eq!((letters, vowels.len()), (26, 6));
+        assert!(is_consonant(D) && !is_consonant(A) && !is_consonant(N1));
+
+        assert!(is_break(DOT) && !is_break(N2) && is_break_ext(N2, true));
+        assert!(!is_break(DELETE) && !is_break_ext(A, true));
+        assert!(!is_letter(u16::MAX) && !is_break(u16::MAX));
+
+        assert_eq!(
+            (base_char(Q), base_char(N7), base_char(DOT)),
+            (Some('q'), Some('7'), None)
+        );
+        assert_eq!(
+            (shifted_char(N2), shifted_char(QUOTE), shifted_char(A)),
+            (Some('@'), Some('"'), None)
+        );
+    }
 }
@@ -4,13 +4,17 @@
 //! - `keys`: Virtual keycode definitions (platform-specific)
 //! - `chars`: Unicode character conversion (includes tone/mark constants)
 //! - `vowel`: Vietnamese vowel phonology system
+//! - `rhymes`: Table of legal rhymes (vần) used by validation
 //! - `telex_doubles`: English words with Telex double patterns for auto-restore
+//! - `quirks`: Per-app send strategies for hosts (delays, known issues)
 
 pub mod chars;
 pub mod constants;
 pub mod dictionary;
 pub mod english_dict;
 pub mod keys;
+pub mod quirks;
+pub mod rhymes;
 pub mod telex_doubles;
 pub mod vowel;
 
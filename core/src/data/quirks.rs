@@ -0,0 +1,278 @@
+//! App quirk table: how to send text into apps that misbehave
+//!
+//! Hosts replace text by injecting backspaces and characters, and some
+//! apps drop or reorder those events. The table maps an app id (process
+//! name without `.exe`, bundle id, or a site host for browser extensions)
+//! to the send strategy that works there, so every host shares one list
+//! instead of hardcoding its own.
+//!
+//! One line per app: the id and a colon, the method, then optional
+//! attributes:
+//!
+//! ```text
+//! firefox: slow keyup_race
+//! auggie: atomic backspace=unicode
+//! docs.google.com: slow batch settle=30
+//! ```
+//!
+//! - methods: `fast`, `slow` (one event per character), `atomic` (one
+//!   injection), `paste` (clipboard + Ctrl+V), `passthrough` (no IME)
+//! - `backspace=unicode`: send backspace as a Unicode character, not a key
+//! - `delay=MS`: pause between characters; `settle=MS`: pause between the
+//!   backspaces and the text (defaults depend on the method)
+//! - `coalesce`: merge replacements typed in quick succession
+//! - `keyup_race`: swallow the key-up of consumed keys (Firefox address
+//!   bar autocompletes the first word from a stray key-up)
+//! - `batch`: send the whole replacement at once; the editor drops events
+//!   that arrive while it re-renders (Google Docs)
+//!
+//! The table is embedded, and a newer one is picked up through the same
+//! channel as word lists: a stamped file (see `dictionary`) loaded with
+//! `load_override` replaces it as a whole.
+
+use super::dictionary::{self, LoadError};
+use std::fs;
+use std::sync::{LazyLock, RwLock};
+
+const EMBEDDED: &str = include_str!("quirks.txt");
+
+/// Quirk table file name in the config directory
+pub const FILE_NAME: &str = "quirks.txt";
+
+/// Swallow key-up events of consumed keys
+pub const QUIRK_KEYUP_RACE: u8 = 0x01;
+/// Send the whole replacement in one go
+pub const QUIRK_BATCH: u8 = 0x02;
+/// Merge replacements typed in quick succession
+pub const QUIRK_COALESCE: u8 = 0x04;
+
+/// How a host injects a replacement
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendMethod {
+    /// Backspaces and text as separate injections
+    Fast = 0,
+    /// One event per character with pauses (Electron apps, browsers)
+    Slow = 1,
+    /// Backspaces and text in one injection
+    Atomic = 2,
+    /// Clipboard and Ctrl+V (apps that ignore Unicode key events)
+    Paste = 3,
+    /// Leave keys alone (remote desktop clients)
+    Passthrough = 4,
+}
+
+impl SendMethod {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Fast),
+            1 => Some(Self::Slow),
+            2 => Some(Self::Atomic),
+            3 => Some(Self::Paste),
+            4 => Some(Self::Passthrough),
+            _ => None,
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "fast" => Some(Self::Fast),
+            "slow" => Some(Self::Slow),
+            "atomic" => Some(Self::Atomic),
+            "paste" => Some(Self::Paste),
+            "passthrough" => Some(Self::Passthrough),
+            _ => None,
+        }
+    }
+
+    /// (delay, settle) in ms when the table does not say
+    fn default_delays(self) -> (u16, u16) {
+        match self {
+            Self::Fast => (0, 5),
+            Self::Slow => (5, 20),
+            _ => (0, 0),
+        }
+    }
+}
+
+/// How a host deletes characters
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackspaceMode {
+    /// Backspace key events
+    Key = 0,
+    /// U+0008 as a Unicode character (CLIs that read raw input)
+    Unicode = 1,
+}
+
+impl BackspaceMode {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Key),
+            1 => Some(Self::Unicode),
+            _ => None,
+        }
+    }
+}
+
+/// Send strategy for one app
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirk {
+    pub method: SendMethod,
+    pub backspace: BackspaceMode,
+    /// `QUIRK_*` bits
+    pub flags: u8,
+    /// Pause between characters (ms)
+    pub delay_ms: u16,
+    /// Pause between the backspaces and the text (ms)
+    pub settle_ms: u16,
+}
+
+impl Quirk {
+    /// Quirk with the method's default delays and nothing else
+    pub fn new(method: SendMethod) -> Self {
+        let (delay_ms, settle_ms) = method.default_delays();
+        Self {
+            method,
+            backspace: BackspaceMode::Key,
+            flags: 0,
+            delay_ms,
+            settle_ms,
+        }
+    }
+
+    pub fn has(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+}
+
+/// Parsed table: (app id, quirk) in file order
+type Table = Vec<(String, Quirk)>;
+
+/// A downloaded table in use
+struct Override {
+    version: u32,
+    table: Table,
+}
+
+static EMBEDDED_TABLE: LazyLock<Table> = LazyLock::new(|| {
+    let lines: Vec<&str> = EMBEDDED.lines().skip(1).filter(|l| !l.is_empty()).collect();
+    parse(&lines).expect("embedded quirk table")
+});
+
+static OVERRIDE: RwLock<Option<Override>> = RwLock::new(None);
+
+/// App id as the table keys it: lowercase, without `.exe`
+pub fn normalize_app(app: &str) -> String {
+    let app = app.trim().to_lowercase();
+    match app.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => app,
+    }
+}
+
+fn parse_line(line: &str) -> Option<(String, Quirk)> {
+    let (app, rest) = line.split_once(':')?;
+    let mut fields = rest.split_whitespace();
+    let mut quirk = Quirk::new(SendMethod::parse(fields.next()?)?);
+    for field in fields {
+        match field.split_once('=') {
+            Some(("backspace", "unicode")) => quirk.backspace = BackspaceMode::Unicode,
+            Some(("backspace", "key")) => quirk.backspace = BackspaceMode::Key,
+            Some(("delay", ms)) => quirk.delay_ms = ms.parse().ok()?,
+            Some(("settle", ms)) => quirk.settle_ms = ms.parse().ok()?,
+            None if field == "keyup_race" => quirk.flags |= QUIRK_KEYUP_RACE,
+            None if field == "batch" => quirk.flags |= QUIRK_BATCH,
+            None if field == "coalesce" => quirk.flags |= QUIRK_COALESCE,
+            _ => return None,
+        }
+    }
+    let app = normalize_app(app);
+    (!app.is_empty()).then_some((app, quirk))
+}
+
+/// Parse table lines; None if any line is invalid
+fn parse(lines: &[&str]) -> Option<Table> {
+    lines.iter().map(|line| parse_line(line)).collect()
+}
+
+/// Quirk for `app`, None for apps that need nothing special
+pub fn lookup(app: &str) -> Option<Quirk> {
+    let app = normalize_app(app);
+    let find = |table: &Table| table.iter().find(|(id, _)| *id == app).map(|(_, q)| *q);
+    match &*OVERRIDE.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(o) => find(&o.table),
+        None => find(&EMBEDDED_TABLE),
+    }
+}
+
+/// Version of the table in use (0: embedded)
+pub fn version() -> u32 {
+    OVERRIDE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map_or(0, |o| o.version)
+}
+
+/// Use the stamped table file at `path` instead of the embedded one
+///
+/// Same rules as word lists: intact, and not older than the table in use.
+/// Returns the version now in use.
+pub fn load_override(path: &str) -> Result<u32, LoadError> {
+    let text = fs::read_to_string(path).map_err(|err| LoadError::Io(err.to_string()))?;
+    load_override_text(&text)
+}
+
+/// `load_override` with the file contents
+pub fn load_override_text(text: &str) -> Result<u32, LoadError> {
+    let (version, lines) = dictionary::unstamp(text)?;
+    let table = parse(&lines).ok_or(LoadError::Malformed)?;
+
+    let mut guard = OVERRIDE.write().unwrap_or_else(|e| e.into_inner());
+    let current = guard.as_ref().map_or(0, |o| o.version);
+    if version == 0 || version < current {
+        return Err(LoadError::NotNewer);
+    }
+    *guard = Some(Override { version, table });
+    Ok(version)
+}
+
+/// Go back to the embedded table
+pub fn reset_override() {
+    *OVERRIDE.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_table() {
+        let firefox = lookup("Firefox.exe").unwrap();
+        assert_eq!(firefox.method, SendMethod::Slow);
+        assert_eq!((firefox.delay_ms, firefox.settle_ms), (5, 20));
+        assert!(firefox.has(QUIRK_KEYUP_RACE));
+
+        assert!(lookup("docs.google.com").unwrap().has(QUIRK_BATCH));
+        assert_eq!(lookup("auggie").unwrap().backspace, BackspaceMode::Unicode);
+        assert_eq!(lookup("Claude Code").unwrap().method, SendMethod::Slow);
+        assert_eq!(lookup("parsecd").unwrap().method, SendMethod::Passthrough);
+        assert_eq!(lookup("notepad"), None);
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let (app, quirk) = parse_line("App.EXE: fast delay=3 settle=40 coalesce").unwrap();
+        assert_eq!(app, "app");
+        assert_eq!(quirk.method, SendMethod::Fast);
+        assert_eq!((quirk.delay_ms, quirk.settle_ms), (3, 40));
+        assert!(quirk.has(QUIRK_COALESCE));
+
+        assert_eq!(parse_line("app slow"), None);
+        assert_eq!(parse_line("app: teleport"), None);
+        assert_eq!(parse_line("app: slow delay=soon"), None);
+        assert_eq!(parse_line("app: slow sparkle"), None);
+    }
+}
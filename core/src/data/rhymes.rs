@@ -0,0 +1,119 @@
+//! Vietnamese rhymes (vần)
+//!
+//! Every legal medial + nucleus + coda combination, written without tone
+//! marks: "oang" (hoàng), "uyêt" (tuyết), "ươu" (hươu). Validation checks
+//! typed syllables against this table, so it is the single place to add a
+//! rhyme that is missing or drop one that does not exist.
+//!
+//! Medials written into the initial are not part of the rhyme: "gi" + "ang",
+//! "qu" + "yêt". Validation also tries the medial forms for those initials
+//! (giếng = gi + "iêng", quạu = qu + "au").
+
+use super::chars;
+use super::keys;
+use std::sync::LazyLock;
+
+/// Legal rhymes, grouped by nucleus
+#[rustfmt::skip]
+pub const RHYMES: &[&str] = &[
+    // a, ă, â
+    "a", "ac", "ach", "ai", "am", "an", "ang", "anh", "ao", "ap", "at", "au", "ay",
+    "ăc", "ăm", "ăn", "ăng", "ăp", "ăt",
+    "âc", "âm", "ân", "âng", "âp", "ât", "âu", "ây",
+    // e, ê
+    "e", "ec", "em", "en", "eng", "eo", "ep", "et",
+    "ê", "êch", "êm", "ên", "ênh", "êp", "êt", "êu",
+    // i, iê, y, yê
+    "i", "ia", "ich", "im", "in", "inh", "ip", "it", "iu",
+    "iêc", "iêm", "iên", "iêng", "iêp", "iêt", "iêu",
+    "y", "yêm", "yên", "yêng", "yêt", "yêu",
+    // o, oo, ô, ơ
+    "o", "oc", "oi", "om", "on", "ong", "op", "ot",
+    "ooc", "oong",
+    "ô", "ôc", "ôi", "ôm", "ôn", "ông", "ôp", "ôt",
+    "ơ", "ơi", "ơm", "ơn", "ơp", "ơt",
+    // u, uô, ư, ươ
+    "u", "ua", "uc", "ui", "um", "un", "ung", "up", "ut",
+    "uôc", "uôi", "uôm", "uôn", "uông", "uôt",
+    "ư", "ưa", "ưc", "ưi", "ưm", "ưn", "ưng", "ưp", "ưt", "ưu",
+    "ươc", "ươi", "ươm", "ươn", "ương", "ươp", "ươt", "ươu",
+    // medial o
+    "oa", "oac", "oach", "oai", "oam", "oan", "oang", "oanh", "oao", "oap", "oat", "oay",
+    "oăc", "oăm", "oăn", "oăng", "oăt",
+    "oe", "oen", "oeo", "oet",
+    // medial u
+    "uân", "uâng", "uât", "uây",
+    "uê", "uêch", "uênh", "uêu",
+    "uơ",
+    "uy", "uya", "uych", "uyn", "uynh", "uyp", "uyt", "uyu",
+    "uyên", "uyêt",
+];
+
+/// Table rhymes as (key, tone) pairs
+static RHYME_KEYS: LazyLock<Vec<Vec<(u16, u8)>>> = LazyLock::new(|| {
+    RHYMES
+        .iter()
+        .map(|r| {
+            r.chars()
+                .filter_map(chars::parse_char)
+                .map(|p| (p.key, p.tone))
+                .collect()
+        })
+        .collect()
+});
+
+/// Whether `rhyme` ("uyêt", "ang") is in the table
+pub fn is_rhyme(rhyme: &str) -> bool {
+    RHYMES.contains(&rhyme)
+}
+
+/// Whether typed keys/tones form a rhyme from the table
+///
+/// A tone of 0 matches any modifier, since circumflex/horn/breve may still
+/// be typed ("uoc" fits "uôc"). Final k is read as c (Đắk Lắk). With
+/// `partial`, the start of a rhyme is enough ("iê" before "iên").
+pub fn fits(rhyme_keys: &[u16], rhyme_tones: &[u8], partial: bool) -> bool {
+    RHYME_KEYS.iter().any(|rhyme| {
+        let len_ok = if partial {
+            rhyme_keys.len() <= rhyme.len()
+        } else {
+            rhyme_keys.len() == rhyme.len()
+        };
+        len_ok
+            && rhyme_keys.iter().zip(rhyme_tones).zip(rhyme).all(
+                |((&key, &tone), &(r_key, r_tone))| {
+                    let key = if key == keys::K { keys::C } else { key };
+                    key == r_key && (tone == 0 || tone == r_tone)
+                },
+            )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::tone;
+
+    #[test]
+    fn test_table_parses() {
+        for (rhyme, parsed) in RHYMES.iter().zip(RHYME_KEYS.iter()) {
+            assert_eq!(rhyme.chars().count(), parsed.len(), "'{}'", rhyme);
+        }
+        assert!(is_rhyme("uyêt") && is_rhyme("oăng") && is_rhyme("ươu"));
+        assert!(!is_rhyme("ơc"));
+    }
+
+    #[test]
+    fn test_fits() {
+        let (u, o, c) = (keys::U, keys::O, keys::C);
+        let horn = tone::HORN;
+        assert!(fits(&[u, o, c], &[0, 0, 0], false));
+        assert!(fits(&[u, o, c], &[horn, horn, 0], false));
+        assert!(!fits(&[o, c], &[horn, 0], false));
+        // "iê" is only the start of a rhyme
+        let ie = [keys::I, keys::E];
+        assert!(!fits(&ie, &[0, tone::CIRCUMFLEX], false));
+        assert!(fits(&ie, &[0, tone::CIRCUMFLEX], true));
+        assert!(fits(&[keys::A, keys::K], &[tone::HORN, 0], false));
+    }
+}
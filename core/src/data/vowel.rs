@@ -22,10 +22,11 @@
 use super::keys;
 
 /// Vowel modifier type (dấu phụ)
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(u8)]
 pub enum Modifier {
-    None = 0,       // a, e, i, o, u, y
+    #[default]
+    None = 0, // a, e, i, o, u, y
     Circumflex = 1, // â, ê, ô (^)
     Horn = 2,       // ơ, ư (móc) / ă (trăng)
 }
@@ -39,7 +40,7 @@ pub enum Role {
 }
 
 /// Vowel information
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct Vowel {
     pub key: u16,
     pub modifier: Modifier,
@@ -0,0 +1,252 @@
+//! Legacy Vietnamese encodings (TCVN3, VNI) ↔ Unicode
+//!
+//! Old .txt/.doc files were written for 8-bit fonts: TCVN3 (ABC, ".VnTime")
+//! stores each Vietnamese letter in one byte, VNI (VNI-Windows fonts) stores
+//! a base letter followed by a diacritic byte ("Vieät" = Việt).
+//!
+//! ```
+//! use gonhanh_core::encoding::{decode, encode, Encoding};
+//! assert_eq!(decode(b"H\xb5 N\xe9i", Encoding::Tcvn3), "Hà Nội");
+//! assert_eq!(encode("Việt", Encoding::Vni), b"Vie\xe4t");
+//! ```
+//!
+//! TCVN3 has no capitals with tone marks (documents switched to the
+//! ".VnTimeH" font for those); they are encoded as the lowercase byte.
+//! Characters an encoding cannot hold become `?`.
+
+use crate::data::chars::{self, mark, tone};
+use crate::data::keys;
+
+/// Legacy encoding
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// TCVN 5712:1993 VN3 (ABC)
+    Tcvn3 = 0,
+    /// VNI-Windows
+    Vni = 1,
+}
+
+impl Encoding {
+    /// From FFI value, None if unknown
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Tcvn3),
+            1 => Some(Self::Vni),
+            _ => None,
+        }
+    }
+
+    /// From a name ("tcvn3", "abc", "vni"), case-insensitive
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "tcvn3" | "abc" => Some(Self::Tcvn3),
+            "vni" => Some(Self::Vni),
+            _ => None,
+        }
+    }
+}
+
+/// TCVN3 bytes above 0x7F
+#[rustfmt::skip]
+const TCVN3: &[(u8, char)] = &[
+    (0xA1, 'Ă'), (0xA2, 'Â'), (0xA3, 'Ê'), (0xA4, 'Ô'), (0xA5, 'Ơ'), (0xA6, 'Ư'), (0xA7, 'Đ'),
+    (0xA8, 'ă'), (0xA9, 'â'), (0xAA, 'ê'), (0xAB, 'ô'), (0xAC, 'ơ'), (0xAD, 'ư'), (0xAE, 'đ'),
+    (0xB5, 'à'), (0xB6, 'ả'), (0xB7, 'ã'), (0xB8, 'á'), (0xB9, 'ạ'),
+    (0xBB, 'ằ'), (0xBC, 'ẳ'), (0xBD, 'ẵ'), (0xBE, 'ắ'), (0xC6, 'ặ'),
+    (0xC7, 'ầ'), (0xC8, 'ẩ'), (0xC9, 'ẫ'), (0xCA, 'ấ'), (0xCB, 'ậ'),
+    (0xCC, 'è'), (0xCE, 'ẻ'), (0xCF, 'ẽ'), (0xD0, 'é'), (0xD1, 'ẹ'),
+    (0xD2, 'ề'), (0xD3, 'ể'), (0xD4, 'ễ'), (0xD5, 'ế'), (0xD6, 'ệ'),
+    (0xD7, 'ì'), (0xD8, 'ỉ'), (0xDC, 'ĩ'), (0xDD, 'í'), (0xDE, 'ị'),
+    (0xDF, 'ò'), (0xE1, 'ỏ'), (0xE2, 'õ'), (0xE3, 'ó'), (0xE4, 'ọ'),
+    (0xE5, 'ồ'), (0xE6, 'ổ'), (0xE7, 'ỗ'), (0xE8, 'ố'), (0xE9, 'ộ'),
+    (0xEA, 'ờ'), (0xEB, 'ở'), (0xEC, 'ỡ'), (0xED, 'ớ'), (0xEE, 'ợ'),
+    (0xEF, 'ù'), (0xF1, 'ủ'), (0xF2, 'ũ'), (0xF3, 'ú'), (0xF4, 'ụ'),
+    (0xF5, 'ừ'), (0xF6, 'ử'), (0xF7, 'ữ'), (0xF8, 'ứ'), (0xF9, 'ự'),
+    (0xFA, 'ỳ'), (0xFB, 'ỷ'), (0xFC, 'ỹ'), (0xFD, 'ý'), (0xFE, 'ỵ'),
+];
+
+/// VNI letters stored in one byte
+#[rustfmt::skip]
+const VNI_SINGLE: &[(u8, char)] = &[
+    (0xF1, 'đ'), (0xD1, 'Đ'), (0xF4, 'ơ'), (0xD4, 'Ơ'), (0xF6, 'ư'), (0xD6, 'Ư'),
+    (0xED, 'í'), (0xCD, 'Í'), (0xEC, 'ì'), (0xCC, 'Ì'), (0xE6, 'ỉ'), (0xC6, 'Ỉ'),
+    (0xF3, 'ĩ'), (0xD3, 'Ĩ'), (0xF2, 'ị'), (0xD2, 'Ị'), (0xEE, 'ỵ'), (0xCE, 'Ỵ'),
+];
+
+/// VNI diacritic bytes after a base letter: (byte, modifier, mark)
+///
+/// Lowercase forms; after a capital the byte is 0x20 lower ("AÙ" = Á).
+/// Breve is stored as `tone::HORN`, like the engine does for ă.
+#[rustfmt::skip]
+const VNI_DIACRITICS: &[(u8, u8, u8)] = &[
+    (0xF9, tone::NONE, mark::SAC), (0xF8, tone::NONE, mark::HUYEN),
+    (0xFB, tone::NONE, mark::HOI), (0xF5, tone::NONE, mark::NGA), (0xEF, tone::NONE, mark::NANG),
+    (0xE2, tone::CIRCUMFLEX, mark::NONE), (0xE1, tone::CIRCUMFLEX, mark::SAC),
+    (0xE0, tone::CIRCUMFLEX, mark::HUYEN), (0xE5, tone::CIRCUMFLEX, mark::HOI),
+    (0xE3, tone::CIRCUMFLEX, mark::NGA), (0xE4, tone::CIRCUMFLEX, mark::NANG),
+    (0xEA, tone::HORN, mark::NONE), (0xE9, tone::HORN, mark::SAC),
+    (0xE8, tone::HORN, mark::HUYEN), (0xFA, tone::HORN, mark::HOI),
+    (0xFC, tone::HORN, mark::NGA), (0xEB, tone::HORN, mark::NANG),
+];
+
+/// Convert legacy-encoded bytes to Unicode
+///
+/// ASCII is copied; bytes the encoding does not define are read as Latin-1.
+pub fn decode(bytes: &[u8], encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Tcvn3 => bytes
+            .iter()
+            .map(|&b| byte_char(TCVN3, b).unwrap_or(char::from(b)))
+            .collect(),
+        Encoding::Vni => decode_vni(bytes),
+    }
+}
+
+/// Convert Unicode text to a legacy encoding
+pub fn encode(text: &str, encoding: Encoding) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match encoding {
+            Encoding::Tcvn3 => out.push(encode_tcvn3(c)),
+            Encoding::Vni => encode_vni(c, &mut out),
+        }
+    }
+    out
+}
+
+fn byte_char(table: &[(u8, char)], b: u8) -> Option<char> {
+    table.iter().find(|&&(byte, _)| byte == b).map(|&(_, c)| c)
+}
+
+fn char_byte(table: &[(u8, char)], c: char) -> Option<u8> {
+    table.iter().find(|&&(_, ch)| ch == c).map(|&(b, _)| b)
+}
+
+fn encode_tcvn3(c: char) -> u8 {
+    if c.is_ascii() {
+        return c as u8;
+    }
+    char_byte(TCVN3, c)
+        .or_else(|| c.to_lowercase().next().and_then(|l| char_byte(TCVN3, l)))
+        .unwrap_or(b'?')
+}
+
+fn decode_vni(bytes: &[u8]) -> String {
+    let mut out: Vec<char> = Vec::with_capacity(bytes.len());
+    for &b in bytes {
+        if b.is_ascii() {
+            out.push(char::from(b));
+            continue;
+        }
+        if let Some(c) = byte_char(VNI_SINGLE, b) {
+            out.push(c);
+            continue;
+        }
+        let combined = vni_diacritic(b).and_then(|(t, m)| {
+            let last = chars::parse_char(*out.last()?)?;
+            apply_vni_diacritic(last, t, m)
+        });
+        match combined {
+            Some(c) => *out.last_mut().unwrap() = c,
+            None => out.push(char::from(b)),
+        }
+    }
+    out.into_iter().collect()
+}
+
+/// Modifier and mark of a VNI diacritic byte, either case
+fn vni_diacritic(b: u8) -> Option<(u8, u8)> {
+    VNI_DIACRITICS
+        .iter()
+        .find(|&&(byte, _, _)| byte == b || byte - 0x20 == b)
+        .map(|&(_, t, m)| (t, m))
+}
+
+/// `last` with a diacritic added, None if it cannot take it
+fn apply_vni_diacritic(last: chars::ParsedChar, t: u8, m: u8) -> Option<char> {
+    let fits = match t {
+        tone::CIRCUMFLEX => matches!(last.key, keys::A | keys::E | keys::O),
+        tone::HORN => last.key == keys::A,
+        _ => true,
+    };
+    if last.stroke || !keys::is_vowel(last.key) || !fits || last.mark != mark::NONE {
+        return None;
+    }
+    if t != tone::NONE && last.tone != tone::NONE {
+        return None;
+    }
+    let t = if t == tone::NONE { last.tone } else { t };
+    chars::to_char(last.key, last.caps, t, m)
+}
+
+fn encode_vni(c: char, out: &mut Vec<u8>) {
+    if c.is_ascii() {
+        out.push(c as u8);
+        return;
+    }
+    if let Some(b) = char_byte(VNI_SINGLE, c) {
+        out.push(b);
+        return;
+    }
+    let Some(p) = chars::parse_char(c).filter(|p| !p.stroke && keys::is_vowel(p.key)) else {
+        out.push(b'?');
+        return;
+    };
+    let case = |b: u8| if p.caps { b - 0x20 } else { b };
+    let horn_base = p.tone == tone::HORN && p.key != keys::A;
+    if horn_base {
+        // ơ, ư: one byte, then the mark
+        out.push(
+            char_byte(
+                VNI_SINGLE,
+                chars::to_char(p.key, p.caps, p.tone, 0).unwrap(),
+            )
+            .unwrap(),
+        );
+    } else {
+        out.push(chars::to_char(p.key, p.caps, 0, 0).unwrap() as u8);
+    }
+    let t = if horn_base { tone::NONE } else { p.tone };
+    if t == tone::NONE && p.mark == mark::NONE {
+        return;
+    }
+    let (b, _, _) = VNI_DIACRITICS
+        .iter()
+        .find(|&&(_, dt, dm)| dt == t && dm == p.mark)
+        .unwrap();
+    out.push(case(*b));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Thành phố Hồ Chí Minh, Đà Nẵng, Việt Nam; người ỷ lại, TRƯỜNG";
+
+    #[test]
+    fn test_vni_round_trip() {
+        let bytes = encode(SAMPLE, Encoding::Vni);
+        assert_eq!(decode(&bytes, Encoding::Vni), SAMPLE);
+        assert_eq!(encode("Đà Nẵng", Encoding::Vni), b"\xd1a\xf8 Na\xfcng");
+        assert_eq!(encode("ỵ Ị ớ", Encoding::Vni), b"\xee \xd2 \xf4\xf9");
+    }
+
+    #[test]
+    fn test_tcvn3_round_trip() {
+        let lower = SAMPLE.replace("TRƯỜNG", "trường");
+        assert_eq!(
+            decode(&encode(&lower, Encoding::Tcvn3), Encoding::Tcvn3),
+            lower
+        );
+        assert_eq!(encode("Ấ", Encoding::Tcvn3), encode("ấ", Encoding::Tcvn3));
+        assert_eq!(encode("日", Encoding::Tcvn3), b"?");
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(Encoding::from_name("ABC"), Some(Encoding::Tcvn3));
+        assert_eq!(Encoding::from_name("vni"), Some(Encoding::Vni));
+        assert_eq!(Encoding::from_u8(9), None);
+    }
+}
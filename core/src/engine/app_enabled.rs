@@ -0,0 +1,101 @@
+//! Per-app on/off: Vietnamese stays off in the apps the user turned it
+//! off in
+//!
+//! Turning the IME off in an IDE and back on in a chat app every time the
+//! focus moves is tedious. With `per_app_enabled` on, the toggle is kept
+//! for the app in front when it is used, and `set_active_app` brings it
+//! back when that app gets the focus again. Apps never toggled follow the
+//! state last set with no app in front (on by default).
+//!
+//! Only apps whose state differs from that default are kept; they are
+//! saved with the settings (`[app_enabled]`). A focus change that turns
+//! the engine on or off queues `Event::EnabledChanged` so the host can
+//! update its icon.
+
+use super::{session, Engine, Event};
+use crate::data::quirks;
+
+impl Engine {
+    /// Keep the on/off toggle per app
+    pub fn set_per_app_enabled(&mut self, enabled: bool) {
+        self.per_app_enabled = enabled;
+    }
+
+    pub fn per_app_enabled(&self) -> bool {
+        self.per_app_enabled
+    }
+
+    /// Whether the engine is on (in the app in front)
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Set the frontmost app (host calls this on focus change)
+    ///
+    /// Per-app shortcuts and terminal modes follow it, and with
+    /// `per_app_enabled` the engine turns on or off as last set in it.
+    pub fn set_active_app(&mut self, app: Option<&str>) {
+        self.traced(
+            || session::text_event("app", app),
+            |e| {
+                e.shortcuts.set_active_app(app);
+                e.resolve_app_enabled();
+            },
+        );
+    }
+
+    /// Set whether `app` starts on or off, None to follow the default
+    ///
+    /// `app` is an id as passed to `set_active_app`.
+    pub fn set_app_enabled(&mut self, app: &str, enabled: Option<bool>) {
+        let app = quirks::normalize_app(app);
+        match enabled {
+            Some(enabled) if !app.is_empty() => {
+                self.app_enabled.insert(app, enabled);
+            }
+            _ => {
+                self.app_enabled.remove(&app);
+            }
+        }
+        self.resolve_app_enabled();
+    }
+
+    /// Apps with their own on/off state, by app id
+    pub fn app_enabled_states(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.app_enabled
+            .iter()
+            .map(|(app, &enabled)| (app.as_str(), enabled))
+    }
+
+    /// Record a toggle for the app in front, or as the default
+    pub(super) fn remember_enabled(&mut self, enabled: bool) {
+        let app = self.shortcuts.active_app().map(quirks::normalize_app);
+        match app {
+            Some(app) if self.per_app_enabled && !app.is_empty() => {
+                if enabled == self.default_enabled {
+                    self.app_enabled.remove(&app);
+                } else {
+                    self.app_enabled.insert(app, enabled);
+                }
+            }
+            _ => self.default_enabled = enabled,
+        }
+    }
+
+    /// Turn on or off as set for the app in front
+    fn resolve_app_enabled(&mut self) {
+        if !self.per_app_enabled {
+            return;
+        }
+        let enabled = self
+            .shortcuts
+            .active_app()
+            .and_then(|app| self.app_enabled.get(&quirks::normalize_app(app)))
+            .copied()
+            .unwrap_or(self.default_enabled);
+        if enabled != self.enabled {
+            self.apply_enabled(enabled);
+            self.push_event(Event::EnabledChanged(enabled));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Per-app word state: the word in progress survives an app switch
+//!
+//! The engine follows one stream of keys, but the user types into many
+//! apps. Switching from Slack to Chrome mid-word used to either carry the
+//! Slack word into Chrome (a tone key there rewrote text that is not on
+//! screen) or drop it (back in Slack the word could no longer be edited).
+//!
+//! Hosts save the state under the app losing focus and restore the one of
+//! the app gaining it (`Engine::save_app_state` / `restore_app_state`).
+//! An app without a saved state starts with nothing known, as after a
+//! cursor move. The last `MAX_APPS` apps are kept.
+
+use super::buffer::Buffer;
+use super::{Engine, Transform, WordHistory};
+use crate::cache::Lru;
+use crate::data::quirks;
+
+/// Apps whose state is kept (least recently saved dropped first)
+pub const MAX_APPS: usize = 16;
+
+/// The word in progress and what the engine knows of the text before it
+///
+/// Options are not part of it: they apply to every app.
+#[derive(Clone)]
+pub struct WordState {
+    buf: Buffer,
+    raw_input: Vec<(u16, bool, bool)>,
+    last_transform: Option<Transform>,
+    has_non_letter_prefix: bool,
+    pending_breve_pos: Option<usize>,
+    pending_u_horn_pos: Option<usize>,
+    stroke_reverted: bool,
+    had_mark_revert: bool,
+    pending_mark_revert_pop: bool,
+    had_any_transform: bool,
+    word_transformed: bool,
+    had_vowel_triggered_circumflex: bool,
+    had_circumflex_revert: bool,
+    reverted_circumflex_key: Option<u16>,
+    last_revert: Option<(Transform, usize, usize)>,
+    had_telex_transform: bool,
+    telex_double_raw: Option<String>,
+    telex_double_raw_len: usize,
+    restored_pending_clear: bool,
+    restored_is_ascii: bool,
+    auto_restore_declined: bool,
+    word_caps_lock: Option<bool>,
+    shortcut_prefix: String,
+    passthrough_word: bool,
+    english_keys: Vec<(u16, bool, bool)>,
+    last_token_char: Option<char>,
+    word_history: WordHistory,
+    spaces_after_commit: u8,
+    english_context: bool,
+    pending_capitalize: bool,
+    auto_capitalize_used: bool,
+    saw_sentence_ending: bool,
+}
+
+/// Saved states by app id
+pub(super) struct AppStates {
+    states: Lru<String, WordState>,
+}
+
+impl Default for AppStates {
+    fn default() -> Self {
+        Self {
+            states: Lru::new(MAX_APPS),
+        }
+    }
+}
+
+impl Engine {
+    /// The word in progress, to bring back later with `restore_state`
+    pub fn save_state(&self) -> WordState {
+        WordState {
+            buf: self.buf.clone(),
+            raw_input: self.raw_input.clone(),
+            last_transform: self.last_transform,
+            has_non_letter_prefix: self.has_non_letter_prefix,
+            pending_breve_pos: self.pending_breve_pos,
+            pending_u_horn_pos: self.pending_u_horn_pos,
+            stroke_reverted: self.stroke_reverted,
+            had_mark_revert: self.had_mark_revert,
+            pending_mark_revert_pop: self.pending_mark_revert_pop,
+            had_any_transform: self.had_any_transform,
+            word_transformed: self.word_transformed,
+            had_vowel_triggered_circumflex: self.had_vowel_triggered_circumflex,
+            had_circumflex_revert: self.had_circumflex_revert,
+            reverted_circumflex_key: self.reverted_circumflex_key,
+            last_revert: self.last_revert,
+            had_telex_transform: self.had_telex_transform,
+            telex_double_raw: self.telex_double_raw.clone(),
+            telex_double_raw_len: self.telex_double_raw_len,
+            restored_pending_clear: self.restored_pending_clear,
+            restored_is_ascii: self.restored_is_ascii,
+            auto_restore_declined: self.auto_restore_declined,
+            word_caps_lock: self.word_caps_lock,
+            shortcut_prefix: self.shortcut_prefix.clone(),
+            passthrough_word: self.passthrough_word,
+            english_keys: self.english_keys.clone(),
+            last_token_char: self.last_token_char,
+            word_history: self.word_history.clone(),
+            spaces_after_commit: self.spaces_after_commit,
+            english_context: self.english_context,
+            pending_capitalize: self.pending_capitalize,
+            auto_capitalize_used: self.auto_capitalize_used,
+            saw_sentence_ending: self.saw_sentence_ending,
+        }
+    }
+
+    /// Continue the word saved by `save_state`
+    ///
+    /// Undo of the last automatic action is dropped: it was only valid
+    /// until the next key.
+    pub fn restore_state(&mut self, state: WordState) {
+        self.clear_all();
+        self.last_auto = None;
+        self.cursor_back = None;
+        self.buf = state.buf;
+        self.raw_input = state.raw_input;
+        self.last_transform = state.last_transform;
+        self.has_non_letter_prefix = state.has_non_letter_prefix;
+        self.pending_breve_pos = state.pending_breve_pos;
+        self.pending_u_horn_pos = state.pending_u_horn_pos;
+        self.stroke_reverted = state.stroke_reverted;
+        self.had_mark_revert = state.had_mark_revert;
+        self.pending_mark_revert_pop = state.pending_mark_revert_pop;
+        self.had_any_transform = state.had_any_transform;
+        self.word_transformed = state.word_transformed;
+        self.had_vowel_triggered_circumflex = state.had_vowel_triggered_circumflex;
+        self.had_circumflex_revert = state.had_circumflex_revert;
+        self.reverted_circumflex_key = state.reverted_circumflex_key;
+        self.last_revert = state.last_revert;
+        self.had_telex_transform = state.had_telex_transform;
+        self.telex_double_raw = state.telex_double_raw;
+        self.telex_double_raw_len = state.telex_double_raw_len;
+        self.restored_pending_clear = state.restored_pending_clear;
+        self.restored_is_ascii = state.restored_is_ascii;
+        self.auto_restore_declined = state.auto_restore_declined;
+        self.word_caps_lock = state.word_caps_lock;
+        self.shortcut_prefix = state.shortcut_prefix;
+        self.passthrough_word = state.passthrough_word;
+        self.english_keys = state.english_keys;
+        self.last_token_char = state.last_token_char;
+        self.word_history = state.word_history;
+        self.spaces_after_commit = state.spaces_after_commit;
+        self.english_context = state.english_context;
+        self.pending_capitalize = state.pending_capitalize;
+        self.auto_capitalize_used = state.auto_capitalize_used;
+        self.saw_sentence_ending = state.saw_sentence_ending;
+    }
+
+    /// Keep the word in progress for `app` (the app losing focus)
+    pub fn save_app_state(&mut self, app: &str) {
+        let app = quirks::normalize_app(app);
+        let state = self.save_state();
+        self.app_states.states.remove(&app);
+        self.app_states.states.insert(app, state);
+    }
+
+    /// Continue the word saved for `app` (the app gaining focus)
+    ///
+    /// Returns false if nothing was saved for it; the engine then starts
+    /// with nothing known, as after a cursor move.
+    pub fn restore_app_state(&mut self, app: &str) -> bool {
+        match self.app_states.states.remove(&quirks::normalize_app(app)) {
+            Some(state) => {
+                self.restore_state(state);
+                true
+            }
+            None => {
+                self.clear_all();
+                self.last_auto = None;
+                false
+            }
+        }
+    }
+}
@@ -124,7 +124,7 @@ impl Buffer {
     }
 
     /// Find indices of vowels in buffer
-    pub fn find_vowels(&self) -> Vec<usize> {
+    pub fn find_vowels(&self) -> StackVec<usize, MAX> {
         use crate::data::keys;
         (0..self.len)
             .filter(|&i| keys::is_vowel(self.data[i].key))
@@ -184,6 +184,75 @@ impl Buffer {
     }
 }
 
+/// List of at most `N` items kept on the stack
+///
+/// For per-keystroke scans of the buffer (vowel positions, vowels), which
+/// can never hold more than `MAX` items and should not allocate.
+#[derive(Clone, Copy)]
+pub struct StackVec<T, const N: usize> {
+    items: [T; N],
+    len: usize,
+}
+
+impl<T: Copy + Default, const N: usize> StackVec<T, N> {
+    pub fn new() -> Self {
+        Self {
+            items: [T::default(); N],
+            len: 0,
+        }
+    }
+
+    /// Append `item`; ignored when full, like `Buffer::push`
+    pub fn push(&mut self, item: T) {
+        if self.len < N {
+            self.items[self.len] = item;
+            self.len += 1;
+        }
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for StackVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> std::ops::Deref for StackVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items[..self.len]
+    }
+}
+
+impl<T: Copy + Default, const N: usize> FromIterator<T> for StackVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for item in iter.into_iter().take(N) {
+            list.push(item);
+        }
+        list
+    }
+}
+
+impl<T, const N: usize> IntoIterator for StackVec<T, N> {
+    type Item = T;
+    type IntoIter = std::iter::Take<std::array::IntoIter<T, N>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter().take(self.len)
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a StackVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,4 +273,20 @@ mod tests {
         buf.clear();
         assert!(buf.is_empty());
     }
+
+    #[test]
+    fn test_find_vowels() {
+        use crate::data::keys;
+        let mut buf = Buffer::new();
+        for key in [keys::T, keys::O, keys::A, keys::N] {
+            buf.push(Char::new(key, false));
+        }
+        let vowels = buf.find_vowels();
+        assert_eq!(&vowels[..], &[1, 2]);
+        assert_eq!(vowels.into_iter().rev().collect::<Vec<_>>(), [2, 1]);
+
+        // Items beyond the capacity are dropped
+        let list: StackVec<u8, 2> = (1..=3).collect();
+        assert_eq!(&list[..], &[1, 2]);
+    }
 }
@@ -0,0 +1,178 @@
+//! Candidate list for the word in progress
+//!
+//! Hosts that can draw a popup under the caret (IMKit, TSF, IBus) show
+//! what else the word could be and let the user pick one:
+//!
+//! - `Tone`: the other tone placement of an open oa/oe/uy syllable
+//!   ("hòa" for "hoà"), see `convert::tone_renderings`
+//! - `Spelling`: the word with its initial respelled ("nghe" for "nge"),
+//!   as spelling auto-fix would give at the word boundary
+//! - `Prediction`: common syllables the word can still become ("tiếp"
+//!   for "tie"), most frequent first
+//! - `Raw`: the keys as typed ("vieetj" for "việt")
+//!
+//! The word itself is never listed. Picking a candidate replaces the word
+//! on screen and keeps it as the word in progress, so tone keys still
+//! apply to it.
+
+use super::{Engine, Result};
+use crate::convert;
+use crate::data::chars;
+use crate::data::dictionary;
+use crate::utils;
+
+/// Predictions listed at most
+pub const MAX_PREDICTIONS: usize = 5;
+
+/// Where a candidate comes from
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateKind {
+    Tone = 0,
+    Spelling = 1,
+    Prediction = 2,
+    Raw = 3,
+}
+
+impl CandidateKind {
+    /// Name in `candidates_json()`
+    pub fn name(self) -> &'static str {
+        match self {
+            CandidateKind::Tone => "tone",
+            CandidateKind::Spelling => "spelling",
+            CandidateKind::Prediction => "prediction",
+            CandidateKind::Raw => "raw",
+        }
+    }
+}
+
+/// One entry of the candidate list
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub text: String,
+    pub kind: CandidateKind,
+}
+
+impl Engine {
+    /// What the word in progress could be instead, in display order
+    ///
+    /// Empty when no word is being typed.
+    pub fn candidates(&self) -> Vec<Candidate> {
+        if !self.enabled || self.buf.is_empty() {
+            return Vec::new();
+        }
+        let word = self.buf.to_full_string();
+        let mut list: Vec<Candidate> = Vec::new();
+        let mut add = |text: String, kind: CandidateKind| {
+            if text != word && !list.iter().any(|c| c.text == text) {
+                list.push(Candidate { text, kind });
+            }
+        };
+
+        for text in convert::tone_renderings(&word, self.tone_style) {
+            add(text, CandidateKind::Tone);
+        }
+        if !dictionary::is_vietnamese(&word, self.allow_foreign_consonants) {
+            if let Some(e) = self.respelled() {
+                add(e.buf.to_full_string(), CandidateKind::Spelling);
+            }
+        }
+        let typed = word.to_lowercase();
+        dictionary::frequent_words()
+            .filter(|w| can_become(&typed, w))
+            .take(MAX_PREDICTIONS)
+            .for_each(|w| add(match_case(w, &word), CandidateKind::Prediction));
+        add(self.raw_chars().into_iter().collect(), CandidateKind::Raw);
+        list
+    }
+
+    /// Replace the word in progress with candidate `index` of `candidates()`
+    ///
+    /// Returns `Result::none()` if there is no such candidate.
+    pub fn select_candidate(&mut self, index: usize) -> Result {
+        let Some(candidate) = self.candidates().into_iter().nth(index) else {
+            return Result::none();
+        };
+        let backspace = self.buf.len().min(u8::MAX as usize) as u8;
+        let chars: Vec<char> = candidate.text.chars().collect();
+        self.last_auto = None;
+        self.restore_word(&candidate.text);
+        // Picked on purpose: keep it when a consonant follows
+        self.restored_pending_clear = false;
+        let mut result = Result::send(backspace, &chars);
+        self.selection_result(&mut result);
+        result
+    }
+
+    /// `candidates()` as JSON: `[{"text":"hòa","kind":"tone"},...]`
+    pub fn candidates_json(&self) -> String {
+        let items: Vec<String> = self
+            .candidates()
+            .iter()
+            .map(|c| {
+                format!(
+                    "{{\"text\":\"{}\",\"kind\":\"{}\"}}",
+                    utils::json_escape(&c.text),
+                    c.kind.name()
+                )
+            })
+            .collect();
+        format!("[{}]", items.join(","))
+    }
+}
+
+/// Whether `word` starts like `typed` (both lowercase): each typed letter
+/// matches, and any diacritic typed so far is there too
+fn can_become(typed: &str, word: &str) -> bool {
+    if word.chars().count() < typed.chars().count() {
+        return false;
+    }
+    typed.chars().zip(word.chars()).all(|(t, w)| {
+        match (chars::parse_char(t), chars::parse_char(w)) {
+            (Some(t), Some(w)) => {
+                t.key == w.key
+                    && (t.tone == 0 || t.tone == w.tone)
+                    && (t.mark == 0 || t.mark == w.mark)
+                    && (!t.stroke || w.stroke)
+            }
+            _ => t == w,
+        }
+    })
+}
+
+/// `word` (lowercase) in the case of `typed`: "Tie" → "Tiếng", "TIE" → "TIẾNG"
+fn match_case(word: &str, typed: &str) -> String {
+    let mut letters = typed.chars().filter(|c| c.is_alphabetic());
+    let first_upper = letters.next().is_some_and(char::is_uppercase);
+    if first_upper && typed.chars().count() > 1 && letters.all(char::is_uppercase) {
+        return word.to_uppercase();
+    }
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) if first_upper => c.to_uppercase().chain(chars).collect(),
+        _ => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_become() {
+        assert!(can_become("tie", "tiếng"));
+        assert!(can_become("tieng", "tiếng"));
+        assert!(can_become("tiê", "tiền"));
+        assert!(!can_become("tiế", "tiền"));
+        assert!(!can_become("đi", "di"));
+        assert!(!can_become("tiếng", "tiế"));
+    }
+
+    #[test]
+    fn test_match_case() {
+        assert_eq!(match_case("tiếng", "tie"), "tiếng");
+        assert_eq!(match_case("tiếng", "Tie"), "Tiếng");
+        assert_eq!(match_case("tiếng", "TIE"), "TIẾNG");
+        assert_eq!(match_case("tiếng", "T"), "Tiếng");
+    }
+}
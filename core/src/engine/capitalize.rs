@@ -0,0 +1,201 @@
+//! Auto-capitalize punctuation rules
+//!
+//! After ". " the next letter is capitalized. Punctuation typed before that
+//! letter either keeps the pending capital (an opening quote: `. "Xin`) or
+//! cancels it (a comma). Conventions differ, so the host can change the
+//! table; navigation keys always keep and Enter always capitalizes.
+//!
+//! The same rules split text into sentences ([`sentences`]), for features
+//! that work on finished text rather than keystrokes.
+
+use std::ops::Range;
+
+/// What a punctuation character does to auto-capitalize
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapitalizeRule {
+    /// Cancels a pending capital (any character not in the table)
+    Cancel = 0,
+    /// Leaves a pending capital in place: quotes, brackets
+    Keep = 1,
+    /// Ends a sentence: the letter after the next space is capitalized
+    EndSentence = 2,
+}
+
+impl CapitalizeRule {
+    /// From FFI value, None if unknown
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Cancel),
+            1 => Some(Self::Keep),
+            2 => Some(Self::EndSentence),
+            _ => None,
+        }
+    }
+}
+
+/// Built-in rules; everything else cancels
+const DEFAULT_RULES: &[(char, CapitalizeRule)] = &[
+    ('.', CapitalizeRule::EndSentence),
+    ('!', CapitalizeRule::EndSentence),
+    ('?', CapitalizeRule::EndSentence),
+    ('\'', CapitalizeRule::Keep),
+    ('"', CapitalizeRule::Keep),
+    ('(', CapitalizeRule::Keep),
+    (')', CapitalizeRule::Keep),
+    ('[', CapitalizeRule::Keep),
+    (']', CapitalizeRule::Keep),
+    ('{', CapitalizeRule::Keep),
+    ('}', CapitalizeRule::Keep),
+];
+
+/// Punctuation → rule table
+#[derive(Debug, Clone)]
+pub struct CapitalizeRules {
+    rules: Vec<(char, CapitalizeRule)>,
+}
+
+impl Default for CapitalizeRules {
+    fn default() -> Self {
+        Self {
+            rules: DEFAULT_RULES.to_vec(),
+        }
+    }
+}
+
+impl CapitalizeRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rule for `c` (Cancel if not listed)
+    pub fn get(&self, c: char) -> CapitalizeRule {
+        self.rules
+            .iter()
+            .find(|&&(ch, _)| ch == c)
+            .map_or(CapitalizeRule::Cancel, |&(_, rule)| rule)
+    }
+
+    /// Listed characters and their rules, in the order added
+    pub fn iter(&self) -> impl Iterator<Item = (char, CapitalizeRule)> + '_ {
+        self.rules.iter().copied()
+    }
+
+    /// Set the rule for `c`
+    pub fn set(&mut self, c: char, rule: CapitalizeRule) {
+        match self.rules.iter_mut().find(|(ch, _)| *ch == c) {
+            Some(entry) => entry.1 = rule,
+            None => self.rules.push((c, rule)),
+        }
+    }
+
+    /// Back to the built-in rules
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Byte ranges of the sentences in `text`, split where auto-capitalize
+    /// would capitalize the next letter
+    ///
+    /// A sentence ends at a sentence-ending character followed by
+    /// whitespace (closing quotes and brackets in between stay with it), or
+    /// at a line break. A letter or digit right after the punctuation
+    /// ("google.com", "1.5") does not end it. Ranges exclude surrounding
+    /// whitespace; blank text gives no sentences.
+    pub fn sentences(&self, text: &str) -> Vec<Range<usize>> {
+        let mut spans = Vec::new();
+        let mut start: Option<usize> = None;
+        let mut end = 0;
+        let mut saw_end = false;
+        for (i, c) in text.char_indices() {
+            if c.is_whitespace() {
+                if saw_end || c == '\n' || c == '\r' {
+                    if let Some(s) = start.take() {
+                        spans.push(s..end);
+                    }
+                    saw_end = false;
+                }
+                continue;
+            }
+            start.get_or_insert(i);
+            end = i + c.len_utf8();
+            saw_end = match self.get(c) {
+                _ if c.is_alphanumeric() => false,
+                CapitalizeRule::EndSentence => true,
+                CapitalizeRule::Keep => saw_end,
+                CapitalizeRule::Cancel => false,
+            };
+        }
+        if let Some(s) = start {
+            spans.push(s..end);
+        }
+        spans
+    }
+}
+
+/// Sentences of `text` under the default rules (see
+/// [`CapitalizeRules::sentences`])
+///
+/// ```
+/// use gonhanh_core::engine::capitalize::sentences;
+/// let text = "Xin chào. Bạn khoẻ không?\nTôi ổn.";
+/// let spans: Vec<&str> = sentences(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(spans, ["Xin chào.", "Bạn khoẻ không?", "Tôi ổn."]);
+/// ```
+pub fn sentences(text: &str) -> Vec<Range<usize>> {
+    CapitalizeRules::default().sentences(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_overrides() {
+        let mut rules = CapitalizeRules::new();
+        assert_eq!(rules.get('?'), CapitalizeRule::EndSentence);
+        assert_eq!(rules.get('"'), CapitalizeRule::Keep);
+        assert_eq!(rules.get(','), CapitalizeRule::Cancel);
+        rules.set(':', CapitalizeRule::EndSentence);
+        rules.set('(', CapitalizeRule::Cancel);
+        assert_eq!(rules.get(':'), CapitalizeRule::EndSentence);
+        assert_eq!(rules.get('('), CapitalizeRule::Cancel);
+        rules.reset();
+        assert_eq!(rules.get(':'), CapitalizeRule::Cancel);
+        assert_eq!(CapitalizeRule::from_u8(3), None);
+    }
+
+    fn split(rules: &CapitalizeRules, text: &str) -> Vec<String> {
+        rules
+            .sentences(text)
+            .into_iter()
+            .map(|r| text[r].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_sentences() {
+        let rules = CapitalizeRules::new();
+        assert_eq!(
+            split(
+                &rules,
+                "  Ông nói: \"Đi thôi.\" Rồi (đi). Xem google.com, 1.5 kg!  "
+            ),
+            [
+                "Ông nói: \"Đi thôi.\"",
+                "Rồi (đi).",
+                "Xem google.com, 1.5 kg!"
+            ]
+        );
+        assert_eq!(split(&rules, "a\r\nb"), ["a", "b"]);
+        assert_eq!(split(&rules, "ok...  vậy"), ["ok...", "vậy"]);
+        assert!(rules.sentences(" \n ").is_empty());
+    }
+
+    #[test]
+    fn test_sentences_follow_rules() {
+        let mut rules = CapitalizeRules::new();
+        rules.set(':', CapitalizeRule::EndSentence);
+        assert_eq!(split(&rules, "Lưu ý: xong"), ["Lưu ý:", "xong"]);
+    }
+}
@@ -0,0 +1,294 @@
+//! Word hooks: a host script sees each committed word and may rewrite it
+//!
+//! Power users want their own auto-corrections and domain expansions
+//! ("kg" → "kilôgam" only in some apps, a regex over the word) without
+//! forking the engine. The engine passes one hook each committed word; the
+//! host runs whatever it likes behind it (a Lua state, a WASM module), so
+//! the core stays free of dependencies and the host picks the sandbox.
+//!
+//! A word is committed where word boundary shortcuts run, when none
+//! matched: Space or punctuation after it. The engine only queues it. The
+//! hook runs once the key is handled and the engine is free
+//! (`Engine::take_word_hook_call`), as events are reported: behind the
+//! C API the engine is not locked while a script runs, so the script may
+//! call back into it and other threads are not held up. A rewrite is then
+//! merged into the result of the key that committed the word, before the
+//! host applies it, so it goes out like a shortcut expansion and is undone
+//! the same way. What a hook can do is kept small:
+//!
+//! - Hooks are off until turned on (`Engine::set_word_hooks`), even with
+//!   one set
+//! - A rewrite replaces the committed word only, and only if the key's
+//!   result leaves it with its boundary character right before the caret; it
+//!   may not be empty or hold control characters other than line breaks
+//!   and tabs, and is cut at `MAX_REPLACEMENT_LEN` characters
+//! - Enter is not rewritten after: the app may have acted on it
+//! - A call taking longer than `TIME_BUDGET` is dropped and turns hooks
+//!   off. The thread calling the hook still waits for it: a hook that never
+//!   returns blocks its caller like any host callback
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use super::shortcut::MAX_REPLACEMENT_LEN;
+use super::{
+    host_types_key, result_chars, Action, AutoAction, AutoUndo, Engine, Result, FLAG_DECOMPOSED,
+};
+use crate::logging::log_debug;
+
+/// Longest a hook may take on one word
+pub const TIME_BUDGET: Duration = Duration::from_millis(50);
+
+/// Observer of committed words (a host script behind it)
+pub trait WordHook: fmt::Debug + Send {
+    /// Called with each committed word; Some(text) to replace it
+    fn on_commit(&mut self, word: &str) -> Option<String>;
+}
+
+/// A committed word: the word and the boundary character typed after it
+#[derive(Debug, Clone)]
+struct Commit {
+    word: String,
+    trigger: char,
+}
+
+impl Commit {
+    /// What the app shows for it
+    fn typed(&self) -> Vec<char> {
+        self.word.chars().chain([self.trigger]).collect()
+    }
+}
+
+/// The host's hook, the on/off switch and the word waiting for it
+#[derive(Debug, Default)]
+pub(super) struct Hooks {
+    enabled: bool,
+    hook: Option<Box<dyn WordHook>>,
+    /// Changed with the hook, so a call finishing late does not put back a
+    /// hook that was replaced meanwhile
+    generation: u32,
+    /// Keys handled; a rewrite only goes out with the key of its word
+    keys: u64,
+    /// Key code and Shift of the current key
+    key: (u16, bool),
+    /// Word committed by the current key
+    committed: Option<Commit>,
+}
+
+impl Hooks {
+    /// Start a key: drop a word the hook was not run on
+    pub(super) fn next_key(&mut self, key: u16, shift: bool) {
+        self.keys += 1;
+        self.key = (key, shift);
+        self.committed = None;
+    }
+}
+
+/// A committed word and the hook, out of the engine while the hook runs
+///
+/// From `Engine::take_word_hook_call`; `run` it with the engine free, then
+/// hand it back with `Engine::finish_word_hook_call`.
+#[derive(Debug)]
+pub struct HookCall {
+    hook: Box<dyn WordHook>,
+    commit: Commit,
+    generation: u32,
+    key: u64,
+    rewrite: Option<String>,
+    elapsed: Duration,
+}
+
+impl HookCall {
+    /// The committed word
+    pub fn word(&self) -> &str {
+        &self.commit.word
+    }
+
+    /// Run the hook on the word
+    pub fn run(&mut self) {
+        let start = Instant::now();
+        self.rewrite = self.hook.on_commit(&self.commit.word);
+        self.elapsed = start.elapsed();
+    }
+}
+
+/// The part of a hook's rewrite the engine types, None to keep the word
+fn accept(word: &str, rewrite: String) -> Option<String> {
+    let allowed = |c: char| !c.is_control() || c == '\n' || c == '\t';
+    if rewrite.is_empty() || rewrite == word || !rewrite.chars().all(allowed) {
+        return None;
+    }
+    Some(rewrite.chars().take(MAX_REPLACEMENT_LEN).collect())
+}
+
+impl Engine {
+    /// Set the hook committed words are passed to, None to remove it
+    ///
+    /// It is only called once hooks are turned on with `set_word_hooks`.
+    pub fn set_word_hook(&mut self, hook: Option<Box<dyn WordHook>>) {
+        self.hooks.hook = hook;
+        self.hooks.generation = self.hooks.generation.wrapping_add(1);
+    }
+
+    /// Turn word hooks on or off (off by default)
+    pub fn set_word_hooks(&mut self, enabled: bool) {
+        self.hooks.enabled = enabled;
+    }
+
+    /// Whether word hooks are on; false after a hook ran over `TIME_BUDGET`
+    pub fn word_hooks(&self) -> bool {
+        self.hooks.enabled
+    }
+
+    /// The word the last key committed, with the hook to run on it
+    ///
+    /// Call right after each key, as for `take_events`. None when hooks
+    /// are off, no hook is set or the key committed no word.
+    pub fn take_word_hook_call(&mut self) -> Option<HookCall> {
+        let commit = self.hooks.committed.take()?;
+        if !self.hooks.enabled {
+            return None;
+        }
+        Some(HookCall {
+            hook: self.hooks.hook.take()?,
+            commit,
+            generation: self.hooks.generation,
+            key: self.hooks.keys,
+            rewrite: None,
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    /// Take back the hook after `HookCall::run` and merge an accepted
+    /// rewrite into `result`, the key's result the host has not applied yet
+    ///
+    /// The rewrite is dropped if another key came in meanwhile, and hooks
+    /// are turned off if the call took longer than `TIME_BUDGET`.
+    pub fn finish_word_hook_call(&mut self, call: HookCall, result: &mut Result) {
+        let hooks = &mut self.hooks;
+        if call.generation != hooks.generation {
+            return;
+        }
+        hooks.hook = Some(call.hook);
+        if call.elapsed > TIME_BUDGET {
+            log_debug!(
+                "hook",
+                "took {:?} on {:?}, turned off",
+                call.elapsed,
+                call.commit.word
+            );
+            hooks.enabled = false;
+            return;
+        }
+        if call.key != hooks.keys || !hooks.enabled {
+            return;
+        }
+        let Some(rewrite) = call.rewrite.and_then(|r| accept(&call.commit.word, r)) else {
+            return;
+        };
+        log_debug!("hook", "rewrite {:?} → {:?}", call.commit.word, rewrite);
+        self.merge_rewrite(&call.commit, &rewrite, result);
+    }
+
+    /// Run the hook on the word the last key committed and merge its
+    /// rewrite into `result`, for callers that own the engine
+    /// (`take_word_hook_call`, `run`, `finish_word_hook_call`)
+    pub fn run_word_hook(&mut self, result: &mut Result) {
+        if let Some(mut call) = self.take_word_hook_call() {
+            call.run();
+            self.finish_word_hook_call(call, result);
+        }
+    }
+
+    /// Queue the word `trigger_char` commits for the hook
+    ///
+    /// Enter is left out: a rewrite would come after the app got it.
+    pub(super) fn queue_word_hook(&mut self, trigger_char: char) {
+        if !self.hooks.enabled
+            || trigger_char == '\n'
+            || self.buf.is_empty()
+            || self.has_non_letter_prefix
+        {
+            return;
+        }
+        self.hooks.committed = Some(Commit {
+            word: self.buf.to_full_string(),
+            trigger: trigger_char,
+        });
+    }
+
+    /// Turn `result` into one that also replaces the committed word with
+    /// `rewrite`, keeping the boundary character after it
+    ///
+    /// The screen tail already holds what the app shows once `result` is
+    /// applied; it must end with the word and its boundary character. The
+    /// rewrite is dropped for results that do more than type text, and when
+    /// it no longer fits in one result after the text `result` sends first.
+    fn merge_rewrite(&mut self, commit: &Commit, rewrite: &str, result: &mut Result) {
+        let typed = commit.typed();
+        if !self.screen.ends_with(&typed) {
+            log_debug!("hook", "word no longer on screen, rewrite dropped");
+            return;
+        }
+        let (key, shift) = self.hooks.key;
+        // What the key's result deletes, types, and leaves for the host
+        let (deleted, sent, host_typed) = match result.action {
+            a if a == Action::None as u8 && !result.key_consumed() => (0, Vec::new(), 1),
+            a if a == Action::Send as u8 || a == Action::Replace as u8 => (
+                result.backspace as usize,
+                result_chars(result),
+                host_types_key(result, key, shift) as usize,
+            ),
+            _ => return,
+        };
+        // Word and boundary the result leaves, without what the host types
+        let before = typed.len() - host_typed;
+        let mut flags = result.flags;
+        let (backspace, mut chars) = if before <= sent.len() {
+            (deleted, sent[..sent.len() - before].to_vec())
+        } else {
+            let kept = &typed[..before - sent.len()];
+            let backspaces = self.screen.backspaces(kept);
+            if backspaces > kept.len() {
+                flags |= FLAG_DECOMPOSED;
+            }
+            (deleted + backspaces, Vec::new())
+        };
+        let mut output: Vec<char> = rewrite.chars().collect();
+        output.push(commit.trigger);
+        if chars.len() + output.len() > MAX_REPLACEMENT_LEN {
+            // Cut short, the result would no longer end with the trigger
+            log_debug!("hook", "rewrite does not fit the result, dropped");
+            return;
+        }
+        chars.extend_from_slice(&output);
+        let mut merged = Result::send(0, &chars);
+        merged.backspace = backspace.min(u8::MAX as usize) as u8;
+        merged.flags = flags;
+        if host_types_key(&merged, key, shift) {
+            // The host types the boundary character after the result
+            merged.count -= 1;
+            merged.chars[merged.count as usize] = 0;
+        }
+        self.selection_result(&mut merged);
+        self.screen.replace_end(typed.len(), &output);
+        self.last_auto = Some(AutoUndo::new(AutoAction::Shortcut, typed, output));
+        *result = merged;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_rejects_unsafe_rewrites() {
+        assert_eq!(accept("kg", "kilôgam".into()).as_deref(), Some("kilôgam"));
+        assert_eq!(accept("kg", "kg".into()), None);
+        assert_eq!(accept("kg", String::new()), None);
+        assert_eq!(accept("kg", "a\u{8}b".into()), None);
+        assert_eq!(accept("kg", "a\nb".into()).as_deref(), Some("a\nb"));
+        let long = "x".repeat(MAX_REPLACEMENT_LEN + 10);
+        assert_eq!(accept("kg", long).unwrap().len(), MAX_REPLACEMENT_LEN);
+    }
+}
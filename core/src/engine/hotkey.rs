@@ -0,0 +1,148 @@
+//! Hotkeys handled by the engine
+//!
+//! Hosts register a key combination per action; `Engine::on_key_ext`
+//! swallows a matching key (`FLAG_KEY_CONSUMED`) and runs the action
+//! instead of typing it. Each action has at most one binding.
+//!
+//! `ctrl` is the engine's combined Cmd/Ctrl/Alt flag, so Cmd+Shift+M and
+//! Ctrl+Shift+M are the same binding.
+
+/// What a hotkey does
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Switch to the next input method (Telex → VNI → Telex)
+    CycleMethod = 0,
+    /// Switch to the next profile, in name order (see `engine::profiles`)
+    NextProfile = 1,
+    /// Undo the automatic action of the previous key (`Engine::undo_last`)
+    UndoLast = 2,
+    /// Turn Title Case mode on or off
+    ToggleTitleCase = 3,
+}
+
+impl HotkeyAction {
+    /// All actions, in FFI value order
+    pub const ALL: &'static [HotkeyAction] = &[
+        Self::CycleMethod,
+        Self::NextProfile,
+        Self::UndoLast,
+        Self::ToggleTitleCase,
+    ];
+
+    /// From FFI value, None if unknown
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::CycleMethod),
+            1 => Some(Self::NextProfile),
+            2 => Some(Self::UndoLast),
+            3 => Some(Self::ToggleTitleCase),
+            _ => None,
+        }
+    }
+
+    /// Name used in settings files ("cycle_method")
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::CycleMethod => "cycle_method",
+            Self::NextProfile => "next_profile",
+            Self::UndoLast => "undo_last",
+            Self::ToggleTitleCase => "title_case",
+        }
+    }
+
+    /// From a settings file name, None if unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|a| a.name() == name)
+    }
+}
+
+/// A key combination
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hotkey {
+    pub key: u16,
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+impl Hotkey {
+    pub fn new(key: u16, ctrl: bool, shift: bool) -> Self {
+        Self { key, ctrl, shift }
+    }
+}
+
+/// Action → key combination table
+#[derive(Debug, Clone, Default)]
+pub struct Hotkeys {
+    bindings: Vec<(HotkeyAction, Hotkey)>,
+}
+
+impl Hotkeys {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind `action` to `hotkey` (None unbinds), replacing its old binding
+    pub fn bind(&mut self, action: HotkeyAction, hotkey: Option<Hotkey>) {
+        self.bindings.retain(|(a, _)| *a != action);
+        if let Some(hotkey) = hotkey {
+            self.bindings.push((action, hotkey));
+        }
+    }
+
+    /// Key combination bound to `action`
+    pub fn get(&self, action: HotkeyAction) -> Option<Hotkey> {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|&(_, hotkey)| hotkey)
+    }
+
+    /// Action bound to this key press
+    pub fn find(&self, key: u16, ctrl: bool, shift: bool) -> Option<HotkeyAction> {
+        let pressed = Hotkey::new(key, ctrl, shift);
+        self.bindings
+            .iter()
+            .find(|(_, hotkey)| *hotkey == pressed)
+            .map(|&(action, _)| action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::keys;
+
+    #[test]
+    fn test_bind_and_find() {
+        let mut hotkeys = Hotkeys::new();
+        assert_eq!(hotkeys.find(keys::M, true, true), None);
+        hotkeys.bind(
+            HotkeyAction::CycleMethod,
+            Some(Hotkey::new(keys::M, true, true)),
+        );
+        assert_eq!(
+            hotkeys.find(keys::M, true, true),
+            Some(HotkeyAction::CycleMethod)
+        );
+        assert_eq!(hotkeys.find(keys::M, true, false), None);
+
+        // Rebinding replaces the old combination
+        hotkeys.bind(
+            HotkeyAction::CycleMethod,
+            Some(Hotkey::new(keys::N, true, false)),
+        );
+        assert_eq!(hotkeys.find(keys::M, true, true), None);
+        assert_eq!(
+            hotkeys.get(HotkeyAction::CycleMethod),
+            Some(Hotkey::new(keys::N, true, false))
+        );
+        hotkeys.bind(HotkeyAction::CycleMethod, None);
+        assert_eq!(hotkeys.find(keys::N, true, false), None);
+        assert_eq!(HotkeyAction::from_u8(9), None);
+        for (i, &action) in HotkeyAction::ALL.iter().enumerate() {
+            assert_eq!(HotkeyAction::from_u8(i as u8), Some(action));
+            assert_eq!(HotkeyAction::from_name(action.name()), Some(action));
+        }
+    }
+}
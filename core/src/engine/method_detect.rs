@@ -0,0 +1,112 @@
+//! Input method auto-detection
+//!
+//! Watches the first words typed after detection is turned on and tells
+//! Telex from VNI by their raw keys: "vie65t" only makes a Vietnamese word
+//! as VNI, "vieetj" only as Telex. Words both or neither method turn into
+//! Vietnamese ("nha", "test") say nothing, nor do English words.
+
+use super::Engine;
+use crate::convert::type_text;
+use crate::data::english_dict;
+use crate::engine::validation::validate_word;
+
+/// Committed words watched before detection stops
+const WATCH_WORDS: u8 = 20;
+/// Words one method needs ahead of the other before switching
+const LEAD: u8 = 2;
+
+/// Tally of words that only make sense in one method
+#[derive(Debug, Clone, Default)]
+pub struct MethodDetector {
+    words: u8,
+    telex: u8,
+    vni: u8,
+}
+
+impl MethodDetector {
+    /// Start watching again
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Feed the raw keys of a committed word ("vie65t")
+    ///
+    /// Returns the method (0 Telex, 1 VNI) to switch to once one is ahead
+    /// and differs from `current`.
+    pub fn observe(&mut self, raw: &str, current: u8) -> Option<u8> {
+        if self.words >= WATCH_WORDS {
+            return None;
+        }
+        self.words += 1;
+        match classify(raw)? {
+            0 => self.telex += 1,
+            _ => self.vni += 1,
+        }
+        let detected = if self.telex >= self.vni + LEAD {
+            0
+        } else if self.vni >= self.telex + LEAD {
+            1
+        } else {
+            return None;
+        };
+        (detected != current).then_some(detected)
+    }
+}
+
+/// Method (0 Telex, 1 VNI) a word's raw keys were typed in, if only one
+/// of them makes it a Vietnamese word
+pub fn classify(raw: &str) -> Option<u8> {
+    if raw.len() < 2 || english_dict::is_english_word(&raw.to_lowercase()) {
+        return None;
+    }
+    match (is_vietnamese(raw, 0), is_vietnamese(raw, 1)) {
+        (true, false) => Some(0),
+        (false, true) => Some(1),
+        _ => None,
+    }
+}
+
+/// Whether `raw` typed in `method` becomes a valid word with diacritics
+fn is_vietnamese(raw: &str, method: u8) -> bool {
+    let mut e = Engine::new();
+    e.set_method(method);
+    let typed = type_text(&mut e, raw);
+    typed != raw && validate_word(&typed, false).is_valid()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("vieetj"), Some(0));
+        assert_eq!(classify("ddoongf"), Some(0));
+        assert_eq!(classify("vie65t"), Some(1));
+        assert_eq!(classify("hoa2"), Some(1));
+        assert_eq!(classify("nha"), None);
+        assert_eq!(classify("test"), None);
+        assert_eq!(classify("mp3"), None);
+    }
+
+    #[test]
+    fn test_needs_lead() {
+        let mut d = MethodDetector::default();
+        assert_eq!(d.observe("vie65t", 0), None);
+        assert_eq!(d.observe("vieetj", 0), None);
+        assert_eq!(d.observe("hoa2", 0), None);
+        assert_eq!(d.observe("nam9", 0), None);
+        assert_eq!(d.observe("to6i", 0), Some(1));
+        assert_eq!(d.observe("la2", 1), None);
+    }
+
+    #[test]
+    fn test_stops_after_watch_words() {
+        let mut d = MethodDetector::default();
+        for _ in 0..WATCH_WORDS {
+            d.observe("nha", 0);
+        }
+        assert_eq!(d.observe("vie65t", 0), None);
+        assert_eq!(d.observe("hoa2", 0), None);
+    }
+}
@@ -10,24 +10,63 @@
 //! 3. **Shortcut Support**: User-defined abbreviations with priority
 //! 4. **Longest-Match-First**: For diacritic placement
 
+mod app_enabled;
+pub mod app_state;
 pub mod buffer;
+pub mod candidates;
+pub mod capitalize;
+pub mod hooks;
+pub mod hotkey;
+pub mod method_detect;
+pub mod placeholder;
+pub mod profiles;
+pub mod proper_noun;
+mod reasons;
+pub mod recorder;
+mod screen;
+pub mod session;
+pub mod settings;
 pub mod shortcut;
+pub mod snippet;
 pub mod syllable;
+pub mod teencode;
+pub mod terminal;
+pub mod tone_detect;
 pub mod transform;
 pub mod validation;
 
+use std::collections::{BTreeMap, HashSet};
+
 use crate::data::{
     chars::{self, mark, tone},
-    constants, dictionary, english_dict, keys, telex_doubles,
+    constants, dictionary, english_dict, keys, quirks, telex_doubles,
     vowel::{Phonology, Vowel},
 };
 use crate::input::{self, ToneType};
+use crate::latency::{self, Stage};
+use crate::learning::{Learning, Preference};
+use crate::logging::{log_debug, log_trace};
+use crate::managed;
+use crate::stats::Stats;
 use crate::utils;
-use buffer::{Buffer, Char, MAX};
-use shortcut::{InputMethod, ShortcutTable};
+use app_state::AppStates;
+use buffer::{Buffer, Char, StackVec, MAX};
+use capitalize::{CapitalizeRule, CapitalizeRules};
+use hooks::Hooks;
+use hotkey::{Hotkey, HotkeyAction, Hotkeys};
+use placeholder::HostContext;
+use profiles::Profiles;
+use proper_noun::ProperNouns;
+use reasons::{reason, Reasons};
+use recorder::MacroRecorder;
+use screen::ScreenTail;
+use session::SessionTrace;
+use shortcut::{InputMethod, ShortcutMatch, ShortcutTable};
+use teencode::Teencode;
+use terminal::{Composer, TerminalMode};
 use validation::{
     is_foreign_word_pattern, is_valid, is_valid_for_transform_with_foreign, is_valid_with_foreign,
-    is_valid_with_tones, is_valid_with_tones_and_foreign,
+    is_valid_with_marks, is_valid_with_tones, mark_allowed, Strictness,
 };
 
 /// Engine action result
@@ -37,8 +76,176 @@ pub enum Action {
     None = 0,
     Send = 1,
     Restore = 2,
+    /// Select `backspace` characters before the caret and type `chars`
+    /// over them (hosts with a text API, see `set_selection_replace`)
+    Replace = 3,
+}
+
+/// How Telex handles a digit typed inside a word
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DigitPolicy {
+    /// Digit stays in the word, which is then typed literally ("k3anhs")
+    InWord = 0,
+    /// Digit ends the word; letters after it start a new one ("k3anhs" → "k3ánh")
+    Boundary = 1,
+}
+
+impl DigitPolicy {
+    /// FFI value to policy (unknown values → InWord)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => DigitPolicy::Boundary,
+            _ => DigitPolicy::InWord,
+        }
+    }
+}
+
+/// What pressing a modifier key again after its revert does
+/// ("aa" → "â", "aaa" → "aa", then "a")
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RevertPolicy {
+    /// Later presses are plain letters: "aaaa" → "aaa", "asss" → "ass"
+    Literal = 0,
+    /// The next press re-applies the transform: "aaaa" → "â", "asss" → "á",
+    /// "dddd" → "đ", and the one after reverts again
+    Cycle = 1,
+}
+
+impl RevertPolicy {
+    /// FFI value to policy (unknown values → Literal)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => RevertPolicy::Cycle,
+            _ => RevertPolicy::Literal,
+        }
+    }
+}
+
+/// What a held key does once it auto-repeats (see `on_key_repeat`)
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RepeatPolicy {
+    /// Repeats are typed as-is and the word passes through untouched until
+    /// the next word boundary: holding `a` gives "aaaa", holding `s` after
+    /// "a" gives "ásss", never a revert flickering back and forth
+    Literal = 0,
+    /// Every repeat is handled as a separate key press
+    Press = 1,
+}
+
+impl RepeatPolicy {
+    /// FFI value to policy (unknown values → Literal)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => RepeatPolicy::Press,
+            _ => RepeatPolicy::Literal,
+        }
+    }
+}
+
+/// Where the tone mark goes in open oa, oe, uy syllables
+///
+/// Every other syllable is written the same in both styles: "hoàn",
+/// "quý", "ngoài".
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum ToneStyle {
+    /// Mark on the first vowel: "hòa", "khỏe", "thủy"
+    TraditionalStyle = 0,
+    /// Mark on the main (second) vowel, as in the 1980s spelling rules:
+    /// "hoà", "khoẻ", "thuỷ"
+    #[default]
+    NewStyle = 1,
+}
+
+impl ToneStyle {
+    /// FFI value to style (unknown values → NewStyle)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => ToneStyle::TraditionalStyle,
+            _ => ToneStyle::NewStyle,
+        }
+    }
+
+    /// Whether this is the new style (the `modern` flag of tone placement)
+    pub fn is_new(self) -> bool {
+        self == ToneStyle::NewStyle
+    }
+}
+
+/// Something the host may want to show, reported after the key that
+/// caused it (see `Engine::take_events`)
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The word just committed repeats the word before it ("của của")
+    RepeatedWord(String),
+    /// The input method changed by itself: auto-detection or the cycle
+    /// hotkey (0 Telex, 1 VNI, 2 Simple Telex, 3 Telex + VNI)
+    MethodChanged(u8),
+    /// The active profile changed by itself (the profile hotkey)
+    ProfileChanged(String),
+    /// The engine turned on or off by itself: the app in front has its
+    /// own state (see `Engine::set_per_app_enabled`)
+    EnabledChanged(bool),
+    /// The tone style changed by itself (see `Engine::set_auto_tone_style`)
+    ToneStyleChanged(ToneStyle),
+    /// Title Case mode turned on or off (the Title Case hotkey)
+    TitleCaseChanged(bool),
+}
+
+impl Event {
+    /// FFI event code
+    pub fn code(&self) -> u8 {
+        match self {
+            Event::RepeatedWord(_) => 1,
+            Event::MethodChanged(_) => 2,
+            Event::ProfileChanged(_) => 3,
+            Event::EnabledChanged(_) => 4,
+            Event::ToneStyleChanged(_) => 5,
+            Event::TitleCaseChanged(_) => 6,
+        }
+    }
+
+    /// Text the event is about
+    pub fn text(&self) -> &str {
+        match self {
+            Event::RepeatedWord(word) | Event::ProfileChanged(word) => word,
+            Event::MethodChanged(1) => "vni",
+            Event::MethodChanged(2) => "simple-telex",
+            Event::MethodChanged(3) => "telex-vni",
+            Event::MethodChanged(_) => "telex",
+            Event::EnabledChanged(true) | Event::TitleCaseChanged(true) => "on",
+            Event::EnabledChanged(false) | Event::TitleCaseChanged(false) => "off",
+            Event::ToneStyleChanged(ToneStyle::NewStyle) => "new",
+            Event::ToneStyleChanged(ToneStyle::TraditionalStyle) => "traditional",
+        }
+    }
 }
 
+/// Events kept for the host before the oldest are dropped
+const MAX_EVENTS: usize = 16;
+
+/// Keys a word may have before it is taken for a long token (pasted
+/// identifier, German compound) and passed through; keeps backspace and
+/// output counts well inside the `u8` result fields
+pub const MAX_WORD_KEYS: usize = 64;
+
+/// Keys spelling an English word this frequent restore a valid syllable
+/// that is no dictionary word ("down" → "dơn"; see `is_english_coincidence`).
+/// This check never restores a dictionary word, nor the keys of a word
+/// further down the ranked list ("town", "war", "yes")
+const COMMON_ENGLISH_RANK: usize = 200;
+
+/// Methods visited by the cycle hotkey, in order
+const METHOD_CYCLE: &[u8] = &[0, 1];
+
+/// Reduplicated words that are written twice on purpose
+const INTENDED_REPEATS: &[&str] = &[
+    "từ", "ngày", "người", "nhà", "đâu", "lâu", "ai", "gì", "nơi", "năm", "đời", "chiều",
+];
+
 /// Result for FFI
 #[repr(C)]
 pub struct Result {
@@ -49,11 +256,36 @@ pub struct Result {
     /// Flags byte:
     /// - bit 0 (0x01): key_consumed - if set, the trigger key should NOT be passed through
     ///   Used for shortcuts where the trigger key is part of the replacement
+    /// - bit 1 (0x02): cursor_move - after applying the result (and passing the
+    ///   key through), press Left `Engine::cursor_offset()` times
+    /// - bit 2 (0x04): snippet - the output has fields; jump to the first with
+    ///   `Engine::snippet_next()`
+    /// - bit 3 (0x08): decomposed - `backspace` counts code points of the
+    ///   decomposed text deleted, more than its characters
+    ///   (`Engine::set_decomposed_backspace`)
     pub flags: u8,
 }
 
 /// Flag: key was consumed by shortcut, don't pass through
 pub const FLAG_KEY_CONSUMED: u8 = 0x01;
+/// Flag: shortcut output has a cursor marker, move the caret back
+pub const FLAG_CURSOR_MOVE: u8 = 0x02;
+/// Flag: shortcut output has snippet fields, jump to the first one
+pub const FLAG_SNIPPET: u8 = 0x04;
+/// Flag: backspace counts code points of decomposed (NFD) text
+pub const FLAG_DECOMPOSED: u8 = 0x08;
+
+/// Auto-restore trigger: Space
+pub const RESTORE_ON_SPACE: u8 = 0x01;
+/// Auto-restore trigger: Tab
+pub const RESTORE_ON_TAB: u8 = 0x02;
+/// Auto-restore trigger: Enter/Return
+pub const RESTORE_ON_ENTER: u8 = 0x04;
+/// Auto-restore trigger: punctuation and other break keys (arrows, brackets)
+pub const RESTORE_ON_PUNCTUATION: u8 = 0x08;
+/// Auto-restore triggers: every word boundary (default)
+pub const RESTORE_ON_ALL: u8 =
+    RESTORE_ON_SPACE | RESTORE_ON_TAB | RESTORE_ON_ENTER | RESTORE_ON_PUNCTUATION;
 
 impl Result {
     pub fn none() -> Self {
@@ -71,15 +303,32 @@ impl Result {
             chars: [0; MAX],
             action: Action::Send as u8,
             backspace,
-            count: chars.len().min(MAX) as u8,
+            count: chars.len().min(u8::MAX as usize) as u8,
             flags: 0,
         };
-        for (i, &c) in chars.iter().take(MAX).enumerate() {
+        for (i, &c) in chars.iter().take(u8::MAX as usize).enumerate() {
             result.chars[i] = c as u32;
         }
         result
     }
 
+    /// Replace the whole word with its raw keystrokes (ESC restore)
+    ///
+    /// Same payload as `send`, but hosts must apply it as one replacement
+    /// (no coalescing) and swallow the trigger key.
+    pub fn restore(backspace: u8, chars: &[char]) -> Self {
+        let mut result = Self::send(backspace, chars);
+        result.action = Action::Restore as u8;
+        result
+    }
+
+    /// Nothing to send, but the key must not reach the app (hotkeys)
+    pub fn consumed() -> Self {
+        let mut result = Self::none();
+        result.flags = FLAG_KEY_CONSUMED;
+        result
+    }
+
     /// Send with key_consumed flag set (shortcut consumed the trigger key)
     pub fn send_consumed(backspace: u8, chars: &[char]) -> Self {
         let mut result = Self::send(backspace, chars);
@@ -91,6 +340,16 @@ impl Result {
     pub fn key_consumed(&self) -> bool {
         self.flags & FLAG_KEY_CONSUMED != 0
     }
+
+    /// Check if the caret must move back after this result
+    pub fn cursor_moved(&self) -> bool {
+        self.flags & FLAG_CURSOR_MOVE != 0
+    }
+
+    /// Check if this result started a snippet
+    pub fn snippet_started(&self) -> bool {
+        self.flags & FLAG_SNIPPET != 0
+    }
 }
 
 /// Transform type for revert tracking
@@ -114,6 +373,68 @@ enum Transform {
     BracketAsVowel,
 }
 
+/// Automatic action that `undo_last` can reverse
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AutoAction {
+    /// English auto-restore at a word boundary
+    Restore,
+    /// Shortcut expansion
+    Shortcut,
+    /// Auto-capitalized first letter of a sentence
+    Capitalize,
+}
+
+/// Buffer and raw input of a word, kept to bring it back after auto-restore
+type WordSnapshot = (Buffer, Vec<(u16, bool, bool)>);
+
+/// Undo record for the most recent automatic action
+///
+/// Valid only until the next key: anything typed afterwards changes the
+/// text following it on screen, so a single slot is kept rather than a stack.
+struct AutoUndo {
+    action: AutoAction,
+    /// Text the action left on screen
+    inserted: Vec<char>,
+    /// Text it replaced, put back on undo
+    replaced: Vec<char>,
+    /// Auto-restore only: pre-restore buffer and raw input, so Backspace can
+    /// bring the word back for editing
+    snapshot: Option<WordSnapshot>,
+}
+
+impl AutoUndo {
+    fn new(action: AutoAction, replaced: Vec<char>, inserted: Vec<char>) -> Self {
+        Self {
+            action,
+            inserted,
+            replaced,
+            snapshot: None,
+        }
+    }
+
+    /// Auto-restore record carrying the pre-restore word state
+    fn restore(engine: &Engine, replaced: Vec<char>, inserted: Vec<char>) -> Self {
+        Self {
+            snapshot: Some((engine.buf.clone(), engine.raw_input.clone())),
+            ..Self::new(AutoAction::Restore, replaced, inserted)
+        }
+    }
+}
+
+/// Last `n` chars of `s` (the typed text a shortcut trigger replaced)
+fn tail_chars(s: &str, n: usize) -> Vec<char> {
+    let chars: Vec<char> = s.chars().collect();
+    chars[chars.len().saturating_sub(n)..].to_vec()
+}
+
+/// Output chars of a result
+fn result_chars(r: &Result) -> Vec<char> {
+    r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect()
+}
+
 /// Word history ring buffer capacity (stores last N committed words)
 const HISTORY_CAPACITY: usize = 10;
 
@@ -122,6 +443,7 @@ const HISTORY_CAPACITY: usize = 10;
 /// Used for backspace-after-space feature: when user presses backspace
 /// immediately after committing a word with space, restore the previous
 /// buffer state to allow editing.
+#[derive(Clone)]
 struct WordHistory {
     data: [Buffer; HISTORY_CAPACITY],
     head: usize,
@@ -146,6 +468,11 @@ impl WordHistory {
         }
     }
 
+    /// Most recent buffer in history
+    fn last(&self) -> Option<&Buffer> {
+        (self.len > 0).then(|| &self.data[(self.head + HISTORY_CAPACITY - 1) % HISTORY_CAPACITY])
+    }
+
     /// Pop most recent buffer from history
     fn pop(&mut self) -> Option<Buffer> {
         if self.len == 0 {
@@ -162,40 +489,84 @@ impl WordHistory {
     }
 }
 
-/// Check if key is sentence-ending punctuation (. ! ?) but NOT Enter
-/// Issue #185: Only set pending_capitalize after punctuation + space
+/// Auto-capitalize rule for a break key (Enter is handled by the caller)
+///
+/// Navigation keys (arrows, Tab, ESC) keep a pending capital; punctuation
+/// follows the configured table.
 #[inline]
-fn is_sentence_ending_punctuation(key: u16, shift: bool) -> bool {
-    key == keys::DOT
-        || (shift && key == keys::N1) // !
-        || (shift && key == keys::SLASH) // ?
+fn capitalize_rule(rules: &CapitalizeRules, key: u16, shift: bool) -> CapitalizeRule {
+    if matches!(
+        key,
+        keys::LEFT | keys::RIGHT | keys::UP | keys::DOWN | keys::TAB | keys::ESC
+    ) {
+        return CapitalizeRule::Keep;
+    }
+    break_key_to_char(key, shift).map_or(CapitalizeRule::Cancel, |c| rules.get(c))
 }
 
-/// Check if a break key should reset pending_capitalize
-/// Neutral keys like quotes, parentheses, arrows should NOT reset (preserve pending)
-/// Word-breaking keys like comma should reset
-#[inline]
-fn should_reset_pending_capitalize(key: u16, shift: bool) -> bool {
-    // These neutral characters/keys should NOT reset pending_capitalize:
-    // - Quotes: ' " (QUOTE with/without shift)
-    // - Parentheses: ( ) (Shift+9, Shift+0)
-    // - Brackets: [ ] { } (LBRACKET, RBRACKET with/without shift)
-    // - Arrow keys: navigation shouldn't reset pending state
-    // - Tab, ESC: navigation/cancel shouldn't reset pending state
-    let is_neutral = key == keys::QUOTE
-        || key == keys::LBRACKET
-        || key == keys::RBRACKET
-        || (shift && key == keys::N9)  // (
-        || (shift && key == keys::N0)  // )
-        || key == keys::LEFT
-        || key == keys::RIGHT
-        || key == keys::UP
-        || key == keys::DOWN
-        || key == keys::TAB
-        || key == keys::ESC;
-
-    // Reset for all other break keys (comma, semicolon, etc.)
-    !is_neutral
+/// Character a key types: letters, digits and punctuation
+fn typed_char(key: u16, caps: bool, shift: bool) -> Option<char> {
+    utils::key_to_char_ext(key, caps, shift).or_else(|| break_key_to_char(key, shift))
+}
+
+/// ASCII a key of the word was typed as, for restoring it raw
+///
+/// Brackets typed for ơ/ư (`set_bracket_shortcut`) come back as brackets,
+/// '{' and '}' with caps as when reverted.
+fn raw_key_char(key: u16, caps: bool, shift: bool) -> Option<char> {
+    match key {
+        keys::LBRACKET | keys::RBRACKET => break_key_to_char(key, caps || shift),
+        _ => utils::key_to_char_ext(key, caps, shift),
+    }
+}
+
+/// Whether the host types `key` itself after applying a Send `result`
+///
+/// Break keys pass through unless consumed; Space results already hold the
+/// space when it is wanted.
+pub(crate) fn host_types_key(result: &Result, key: u16, shift: bool) -> bool {
+    !result.key_consumed() && key != keys::SPACE && keys::is_break_ext(key, shift)
+}
+
+/// Character the host types for a key passed through, Enter and Tab included
+pub(crate) fn key_text(key: u16, caps: bool, shift: bool) -> Option<char> {
+    match key {
+        keys::SPACE => Some(' '),
+        keys::TAB => Some('\t'),
+        keys::RETURN | keys::ENTER => Some('\n'),
+        _ => typed_char(key, caps, shift),
+    }
+}
+
+/// Characters a key adds at the caret once the host applies `result`
+///
+/// None for keys that may move the caret instead (arrows, ESC, Home...).
+fn text_delta(result: &Result, key: u16, caps: bool, shift: bool) -> Option<i32> {
+    if result.action != Action::None as u8 {
+        let typed = host_types_key(result, key, shift) as i32;
+        return Some(result.count as i32 - result.backspace as i32 + typed);
+    }
+    match key {
+        keys::DELETE => Some(-1),
+        keys::SPACE | keys::TAB | keys::RETURN | keys::ENTER => Some(1),
+        _ => typed_char(key, caps, shift).map(|_| 1),
+    }
+}
+
+/// Keys that end a URL/email/path token: whitespace, ESC and cursor moves
+fn ends_token(key: u16) -> bool {
+    matches!(
+        key,
+        keys::SPACE
+            | keys::TAB
+            | keys::RETURN
+            | keys::ENTER
+            | keys::ESC
+            | keys::LEFT
+            | keys::RIGHT
+            | keys::UP
+            | keys::DOWN
+    )
 }
 
 /// Convert break key to its character representation
@@ -251,9 +622,15 @@ fn break_key_to_char(key: u16, shift: bool) -> Option<char> {
 pub struct Engine {
     buf: Buffer,
     method: u8,
+    /// Simple Telex (method 2): Telex keys, w is never a standalone ư,
+    /// ] and [ give ư and ơ
+    simple_telex: bool,
     enabled: bool,
     last_transform: Option<Transform>,
     shortcuts: ShortcutTable,
+    /// User exception words (lowercase raw keystrokes) always left as typed,
+    /// a runtime extension of keep.dic
+    english_words: HashSet<String>,
     /// Raw keystroke history for ESC restore (key, caps, shift)
     raw_input: Vec<(u16, bool, bool)>,
     /// True if current word has non-letter characters before letters
@@ -263,24 +640,41 @@ pub struct Engine {
     /// When true, typing 'w' stays as 'w' instead of converting to 'ư'
     /// Horn modifier (try_tone) still works: "ow" → "ơ", "uw" → "ư"
     skip_w_shortcut: bool,
+    /// Word-initial w stays w; ư only after a consonant (Telex)
+    initial_w_literal: bool,
     /// Enable bracket shortcuts: ] → ư, [ → ơ (Issue #159)
     bracket_shortcut: bool,
     /// Enable ESC key to restore raw ASCII (undo Vietnamese transforms)
     /// When false, ESC key is passed through without restoration
     esc_restore_enabled: bool,
+    /// Key that triggers restore when `esc_restore_enabled` (default: ESC)
+    restore_key: u16,
+    /// Key that removes diacritics: [Telex, VNI], None = off
+    remove_keys: [Option<u16>; 2],
     /// Enable free tone placement (skip validation)
     /// When true, allows placing diacritics anywhere without spelling validation
     free_tone_enabled: bool,
-    /// Use modern orthography for tone placement (hoà vs hòa)
-    /// When true: oà, uý (tone on second vowel)
-    /// When false: òa, úy (tone on first vowel - traditional)
-    modern_tone: bool,
+    /// Refuse invalid Vietnamese while typing and restore English mid-word
+    /// When false, keys always apply and English is restored at the boundary
+    spell_check: bool,
+    /// Tone placement in open oa, oe, uy syllables (hoà vs hòa)
+    tone_style: ToneStyle,
     /// Enable English auto-restore (experimental)
     /// When true, automatically restores English words that were transformed
     /// e.g., "tẽt" → "text", "ễpct" → "expect"
     english_auto_restore: bool,
+    /// Word boundaries that evaluate auto-restore (`RESTORE_ON_*` bits)
+    /// A word committed by any other boundary is kept as typed
+    auto_restore_triggers: u8,
     /// Word history for backspace-after-space feature
     word_history: WordHistory,
+    /// Local typing statistics (off by default, see `stats` module)
+    stats: Stats,
+    /// Learned auto-restore corrections (off by default, see `learning` module)
+    learning: Learning,
+    /// Most recent automatic action (auto-restore, shortcut, auto-capitalize)
+    /// for `undo_last`; cleared on every key
+    last_auto: Option<AutoUndo>,
     /// Number of spaces typed after committing a word (for backspace tracking)
     /// When this reaches 0 on backspace, we restore the committed word
     spaces_after_commit: u8,
@@ -342,14 +736,123 @@ pub struct Engine {
     /// Restored word was pure ASCII (no Vietnamese chars) - clear on ANY letter
     /// For Vietnamese restored words, only clear on consonant (allow mark/tone edits)
     restored_is_ascii: bool,
+    /// User undid an auto-restore of this word with Backspace: keep it Vietnamese
+    auto_restore_declined: bool,
+    /// Backspace right after an auto-restore brings back the Vietnamese word
+    /// When false, Backspace only deletes the space (restored word is kept)
+    backspace_undo_restore: bool,
+    /// `-` commits the segment like Space instead of ending the word history
+    /// Backspace over the hyphen restores the previous segment ("Việt-Nam")
+    hyphen_soft_boundary: bool,
+    /// Detect URLs, emails and paths and pass their keys through untouched
+    url_passthrough: bool,
+    /// Unicode code-point input: "u+1ec7" + Space → ệ
+    unicode_input: bool,
+    /// Hex digits typed after "u+" (Some while a code point is being typed)
+    codepoint_hex: Option<String>,
+    /// Built-in arrow/symbol abbreviations (`->` → →), off by default
+    symbol_pack: bool,
+    /// Symbol pack shortcuts, kept apart from the user's table
+    symbols: ShortcutTable,
+    /// Output characters after the cursor marker of this key's expansion
+    cursor_back: Option<usize>,
+    /// Left-arrow presses for the last result flagged `FLAG_CURSOR_MOVE`
+    cursor_offset: u8,
+    /// Snippet stops of this key's expansion
+    pending_fields: Vec<snippet::Field>,
+    /// Snippet being filled in (None: no fields to visit)
+    snippet: Option<snippet::Session>,
+    /// Characters to select after the last `snippet_next()` jump
+    snippet_select: usize,
+    /// Text typed while recording a macro
+    recorder: MacroRecorder,
+    /// Host calls recorded for replay, off by default
+    session: SessionTrace,
+    /// Decisions taken on the last key (`debug-trace` feature)
+    reasons: Reasons,
+    /// Host hook that may rewrite committed words, off by default
+    hooks: Hooks,
+    /// Clock offset and clipboard text for shortcut placeholders
+    host_context: HostContext,
+    /// Telex digit handling inside words (VNI digits are tone keys)
+    digit_policy: DigitPolicy,
+    /// What a modifier pressed again after its revert does
+    revert_policy: RevertPolicy,
+    /// What an auto-repeated key does
+    repeat_policy: RepeatPolicy,
+    /// Last revert: transform undone, its buffer position and the buffer
+    /// length after the revert (for `RevertPolicy::Cycle`)
+    last_revert: Option<(Transform, usize, usize)>,
+    /// Code-context mode: identifiers (snake_case, camelCase, utf8) are
+    /// typed without transforms or auto-restore
+    code_context: bool,
+    /// The host replaces a selection instead of typing backspaces
+    selection_replace: bool,
+    /// Inside a detected URL/email/path/identifier: no transforms until whitespace
+    passthrough_word: bool,
+    /// Suspend Vietnamese after this many auto-restored words in a row (0 = off)
+    auto_english_words: u8,
+    /// Auto-restored words in a row (words without transforms don't count)
+    english_streak: u8,
+    /// A transform was applied to the current word, even if restored since
+    /// (restoring while typing resets `had_any_transform`)
+    word_transformed: bool,
+    /// Vietnamese suspended by the auto language switch
+    english_suspended: bool,
+    /// Keys of the word typed while suspended, replayed at the word boundary
+    english_keys: Vec<(u16, bool, bool)>,
+    /// Temporary English key held: every key passes through untouched
+    english_hold: bool,
+    /// A key went through while `english_hold`, so the cursor has moved
+    english_hold_typed: bool,
+    /// Capitalize a standalone "i" in English text ("i think" → "I think")
+    english_capital_i: bool,
+    /// Last word with transforms ended up English (restored or plain ASCII)
+    english_context: bool,
+    /// Report a word committed twice in a row ("của của")
+    repeated_word_check: bool,
+    /// Switch between Telex and VNI from the first words typed
+    auto_detect_method: bool,
+    method_detector: method_detect::MethodDetector,
+    /// Infer the tone style from the user's corrections
+    auto_tone_style: bool,
+    tone_detector: tone_detect::ToneStyleDetector,
+    /// Events not yet collected by the host
+    events: Vec<Event>,
+    /// Key combinations the engine handles itself
+    hotkeys: Hotkeys,
+    /// Active profile and the others' options and shortcuts
+    profiles: Profiles,
+    /// Word state of apps without focus (see `app_state`)
+    app_states: AppStates,
+    /// Terminal-safe output for apps without their own mode
+    terminal_mode: TerminalMode,
+    /// Terminal-safe output per app (normalized app id, see `data::quirks`)
+    terminal_apps: BTreeMap<String, TerminalMode>,
+    /// Keep the on/off toggle per app (see `app_enabled`)
+    per_app_enabled: bool,
+    /// On/off for apps without their own state
+    default_enabled: bool,
+    /// Apps whose on/off differs from `default_enabled` (normalized app id)
+    app_enabled: BTreeMap<String, bool>,
+    /// Word held back by `TerminalMode::Compose`
+    composer: Composer,
+    /// Text before the caret, to send only what changes
+    screen: ScreenTail,
+    /// Last character typed since whitespace (None at token start)
+    last_token_char: Option<char>,
     /// Auto-capitalize first letter after sentence-ending punctuation
     /// Triggers: . ! ? Enter → next letter becomes uppercase
     auto_capitalize: bool,
+    /// Which punctuation ends a sentence, keeps or cancels a pending capital
+    capitalize_rules: CapitalizeRules,
     /// Pending capitalize state: set after sentence-ending punctuation + space
     pending_capitalize: bool,
     /// Tracks if auto-capitalize was just used on the current word
     /// Used to restore pending_capitalize when user deletes the capitalized letter
     auto_capitalize_used: bool,
+    /// Title Case: capitalize the first letter of every word (headings)
+    title_case: bool,
     /// Tracks if we just saw sentence-ending punctuation (. ! ?)
     /// Only set pending_capitalize when space/Enter follows
     /// Issue #185: don't capitalize immediately after punctuation (e.g., google.com)
@@ -357,6 +860,23 @@ pub struct Engine {
     /// Allow foreign consonants (z, w, j, f) as valid initial consonants
     /// When true, these letters are accepted as Vietnamese consonants for loanwords
     allow_foreign_consonants: bool,
+    /// Validation profile (strict for office typing, relaxed for chat)
+    strictness: Strictness,
+    /// Regional and loanword spellings accepted (`dictionary::VARIANT_*` bits)
+    spelling_variants: u8,
+    /// Respell c/k, g/gh, ng/ngh at the word boundary ("nge" → "nghe")
+    spelling_autofix: bool,
+    /// Capitalize place/person names at the word boundary ("hà nội" → "Hà Nội")
+    proper_nouns: ProperNouns,
+    /// Chat abbreviations committed as typed ("ko", "wa")
+    teencode: Teencode,
+    /// Words committed before the current one, each followed by one space
+    /// (lookback for multi-word names)
+    name_context: Vec<String>,
+    /// Re-case the whole word when CapsLock is toggled mid-word
+    caps_lock_recase: bool,
+    /// CapsLock state at the previous letter of the current word
+    word_caps_lock: Option<bool>,
 }
 
 impl Default for Engine {
@@ -367,21 +887,32 @@ impl Default for Engine {
 
 impl Engine {
     pub fn new() -> Self {
-        Self {
+        let mut e = Self {
             buf: Buffer::new(),
             method: 0,
+            simple_telex: false,
             enabled: true,
             last_transform: None,
             shortcuts: ShortcutTable::with_defaults(),
+            english_words: HashSet::new(),
+            host_context: HostContext::default(),
             raw_input: Vec::with_capacity(64),
             has_non_letter_prefix: false,
             skip_w_shortcut: false,
+            initial_w_literal: false,   // Default: OFF
             bracket_shortcut: false,    // Default: OFF (Issue #159)
             esc_restore_enabled: false, // Default: OFF (user request)
+            restore_key: keys::ESC,
+            remove_keys: [Some(keys::Z), Some(keys::N0)],
             free_tone_enabled: false,
-            modern_tone: true,           // Default: modern style (hoà, thuý)
-            english_auto_restore: false, // Default: OFF (experimental feature)
+            spell_check: true,               // Default: ON
+            tone_style: ToneStyle::NewStyle, // Default: new style (hoà, thuý)
+            english_auto_restore: false,     // Default: OFF (experimental feature)
+            auto_restore_triggers: RESTORE_ON_ALL,
             word_history: WordHistory::new(),
+            stats: Stats::new(),
+            learning: Learning::new(),
+            last_auto: None,
             spaces_after_commit: 0,
             pending_breve_pos: None,
             pending_u_horn_pos: None,
@@ -398,20 +929,268 @@ impl Engine {
             shortcut_prefix: String::new(),
             restored_pending_clear: false,
             restored_is_ascii: false,
+            auto_restore_declined: false,
+            backspace_undo_restore: false, // Default: OFF
+            hyphen_soft_boundary: false,   // Default: OFF
+            url_passthrough: true,
+            code_context: false, // Default: OFF
+            selection_replace: false,
+            digit_policy: DigitPolicy::InWord,
+            revert_policy: RevertPolicy::Literal, // Default: literal (aaaa → aaa)
+            repeat_policy: RepeatPolicy::Literal,
+            last_revert: None,
+            symbol_pack: false,   // Default: OFF
+            unicode_input: false, // Default: OFF
+            codepoint_hex: None,
+            symbols: ShortcutTable::symbol_pack(),
+            cursor_back: None,
+            cursor_offset: 0,
+            pending_fields: Vec::new(),
+            snippet: None,
+            snippet_select: 0,
+            recorder: MacroRecorder::default(),
+            session: SessionTrace::default(),
+            reasons: Reasons::default(),
+            hooks: Hooks::default(),
+            passthrough_word: false,
+            auto_english_words: 0, // Default: OFF
+            english_streak: 0,
+            word_transformed: false,
+            english_suspended: false,
+            english_keys: Vec::new(),
+            english_hold: false,
+            english_hold_typed: false,
+            english_capital_i: false, // Default: OFF
+            english_context: false,
+            repeated_word_check: false, // Default: OFF
+            auto_detect_method: false,  // Default: OFF
+            method_detector: method_detect::MethodDetector::default(),
+            auto_tone_style: false,
+            tone_detector: tone_detect::ToneStyleDetector::default(),
+            events: Vec::new(),
+            hotkeys: Hotkeys::new(),
+            profiles: Profiles::default(),
+            app_states: AppStates::default(),
+            terminal_mode: TerminalMode::Off,
+            terminal_apps: BTreeMap::new(),
+            per_app_enabled: false,
+            default_enabled: true,
+            app_enabled: BTreeMap::new(),
+            composer: Composer::default(),
+            screen: ScreenTail::default(),
+            last_token_char: None,
             auto_capitalize: false, // Default: OFF
+            capitalize_rules: CapitalizeRules::new(),
             pending_capitalize: false,
             auto_capitalize_used: false,
+            title_case: false, // Default: OFF
             saw_sentence_ending: false,
             allow_foreign_consonants: false, // Default: OFF
-        }
+            strictness: Strictness::Standard,
+            spelling_variants: 0,             // Default: OFF
+            spelling_autofix: false,          // Default: OFF
+            proper_nouns: ProperNouns::new(), // Default: OFF
+            teencode: Teencode::new(),        // Default: OFF
+            name_context: Vec::new(),
+            caps_lock_recase: false, // Default: OFF
+            word_caps_lock: None,
+        };
+        e.shortcuts
+            .set_placeholder_context(Box::new(e.host_context.clone()));
+        e
     }
 
+    /// Set input method (0=Telex, 1=VNI, 2=Simple Telex, 3=Telex + VNI)
+    ///
+    /// Simple Telex types like Telex except that `w` only adds a horn or
+    /// breve ("uw" → "ư", "ow" → "ơ", "aw" → "ă"), never a standalone ư,
+    /// and `]`/`[` give ư/ơ as in OpenKey/EVKey. Telex + VNI accepts both
+    /// key sets at once ("vieetj", "vie6t5").
     pub fn set_method(&mut self, method: u8) {
-        self.method = method;
+        self.simple_telex = method == 2;
+        self.method = if self.simple_telex { 0 } else { method };
+        self.method_detector.reset();
+    }
+
+    /// Get current input method (0=Telex, 1=VNI, 2=Simple Telex, 3=Telex + VNI)
+    pub fn method(&self) -> u8 {
+        if self.simple_telex {
+            2
+        } else {
+            self.method
+        }
+    }
+
+    /// Letters are Telex modifiers (Telex, Simple Telex, Telex + VNI)
+    fn telex_letters(&self) -> bool {
+        self.method == 0 || self.method == 3
+    }
+
+    /// Digits are VNI modifiers (VNI, Telex + VNI)
+    fn vni_digits(&self) -> bool {
+        self.method == 1 || self.method == 3
+    }
+
+    /// Create a fresh, enabled engine with the same method and options
+    ///
+    /// Buffer, history and user shortcuts are not copied. Used to replay
+    /// input under the live configuration without disturbing it.
+    pub fn fresh_copy(&self) -> Engine {
+        let mut e = Engine::new();
+        e.method = self.method;
+        e.simple_telex = self.simple_telex;
+        e.skip_w_shortcut = self.skip_w_shortcut;
+        e.initial_w_literal = self.initial_w_literal;
+        e.bracket_shortcut = self.bracket_shortcut;
+        e.esc_restore_enabled = self.esc_restore_enabled;
+        e.restore_key = self.restore_key;
+        e.remove_keys = self.remove_keys;
+        e.free_tone_enabled = self.free_tone_enabled;
+        e.spell_check = self.spell_check;
+        e.tone_style = self.tone_style;
+        e.english_auto_restore = self.english_auto_restore;
+        e.auto_restore_triggers = self.auto_restore_triggers;
+        e.backspace_undo_restore = self.backspace_undo_restore;
+        e.hyphen_soft_boundary = self.hyphen_soft_boundary;
+        e.url_passthrough = self.url_passthrough;
+        e.code_context = self.code_context;
+        e.digit_policy = self.digit_policy;
+        e.revert_policy = self.revert_policy;
+        e.repeat_policy = self.repeat_policy;
+        e.symbol_pack = self.symbol_pack;
+        e.unicode_input = self.unicode_input;
+        e.auto_capitalize = self.auto_capitalize;
+        e.capitalize_rules = self.capitalize_rules.clone();
+        e.title_case = self.title_case;
+        e.allow_foreign_consonants = self.allow_foreign_consonants;
+        e.strictness = self.strictness;
+        e.spelling_variants = self.spelling_variants;
+        e.spelling_autofix = self.spelling_autofix;
+        e.proper_nouns.set_enabled(self.proper_nouns.enabled());
+        e.teencode.set_enabled(self.teencode.enabled());
+        e.caps_lock_recase = self.caps_lock_recase;
+        e.auto_english_words = self.auto_english_words;
+        e.english_capital_i = self.english_capital_i;
+        e.repeated_word_check = self.repeated_word_check;
+        e.auto_detect_method = self.auto_detect_method;
+        e.auto_tone_style = self.auto_tone_style;
+        e
+    }
+
+    /// Undo the most recent automatic action
+    ///
+    /// Reverses an auto-restore, shortcut expansion or auto-capitalize that
+    /// happened on the previous key, putting back what was on screen before.
+    /// Returns `Result::none()` if the previous key did nothing automatic.
+    /// In terminal Compose mode the held word is edited instead, and sent
+    /// only if the undo reaches past it.
+    pub fn undo_last(&mut self) -> Result {
+        self.traced(|| "undo".to_string(), Self::undo_auto)
+    }
+
+    fn undo_auto(&mut self) -> Result {
+        let Some(undo) = self.last_auto.take() else {
+            return Result::none();
+        };
+        let backspace = self.screen.backspaces(&undo.inserted);
+        self.screen.clear();
+        match undo.action {
+            AutoAction::Capitalize => {
+                // Keep typing the word in lowercase
+                let last = self.buf.len().saturating_sub(1);
+                if let Some(c) = self.buf.get_mut(last) {
+                    c.caps = false;
+                }
+                if let Some(raw) = self.raw_input.last_mut() {
+                    raw.1 = false;
+                }
+            }
+            AutoAction::Restore | AutoAction::Shortcut => {
+                self.learn_from_undo(&undo);
+                // Word is committed as shown; history no longer matches the screen
+                self.clear();
+                self.word_history.clear();
+                self.spaces_after_commit = 0;
+            }
+        }
+        let mut result = Result::send(backspace.min(u8::MAX as usize) as u8, &undo.replaced);
+        if backspace > undo.inserted.len() {
+            result.flags |= FLAG_DECOMPOSED;
+        }
+        if self.terminal_mode() == TerminalMode::Compose {
+            result = self.composer.edit(result, self.buf.is_empty());
+        }
+        self.selection_result(&mut result);
+        result
+    }
+
+    /// Backspace right after an auto-restore: remove the boundary char and
+    /// bring back the Vietnamese rendering as the editable current word
+    ///
+    /// Example: "user" + Space → "user " → Backspace → "uẻ" (not "user")
+    fn undo_restore_on_backspace(&mut self, undo: AutoUndo) -> Result {
+        self.learn_from_undo(&undo);
+        let Some((buf, raw_input)) = undo.snapshot else {
+            return Result::none();
+        };
+        self.clear();
+        // Space restore pushed the restored word; it is being edited again
+        if self.spaces_after_commit > 0 {
+            self.word_history.pop();
+        }
+        self.spaces_after_commit = 0;
+        self.buf = buf;
+        self.raw_input = raw_input;
+        self.had_any_transform = true;
+        self.auto_restore_declined = true;
+
+        // Drop the trailing space/punctuation: that is what Backspace deletes
+        let word = &undo.replaced[..undo.replaced.len().saturating_sub(1)];
+        Result::send(undo.inserted.len() as u8, word)
+    }
+
+    /// Undoing an auto-restore is a vote to keep the word Vietnamese
+    fn learn_from_undo(&mut self, undo: &AutoUndo) {
+        if undo.action == AutoAction::Restore {
+            // Drop the boundary char typed after the restored word
+            let word = &undo.inserted[..undo.inserted.len().saturating_sub(1)];
+            self.learning.record_keep(word);
+        }
+    }
+
+    /// Learned auto-restore corrections
+    pub fn learning(&self) -> &Learning {
+        &self.learning
+    }
+
+    /// Mutable learned corrections (enable, reset, load)
+    pub fn learning_mut(&mut self) -> &mut Learning {
+        &mut self.learning
     }
 
+    /// Typing statistics
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Mutable typing statistics (enable, reset, load)
+    pub fn stats_mut(&mut self) -> &mut Stats {
+        &mut self.stats
+    }
+
+    /// Turn the engine on or off (the user's toggle)
+    ///
+    /// With `per_app_enabled` the state is kept for the app in front.
     pub fn set_enabled(&mut self, enabled: bool) {
+        self.remember_enabled(enabled);
+        self.apply_enabled(enabled);
+    }
+
+    fn apply_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
+        self.english_suspended = false;
+        self.english_streak = 0;
+        self.english_keys.clear();
         if !enabled {
             self.buf.clear();
             self.word_history.clear();
@@ -424,6 +1203,14 @@ impl Engine {
         self.skip_w_shortcut = skip;
     }
 
+    /// Set whether a word-initial w stays w in Telex ("web", "www")
+    ///
+    /// After a consonant w still becomes ư ("nhw" → "như"); a word-initial
+    /// ư is typed "uw". Ignored while the w→ư shortcut is skipped.
+    pub fn set_initial_w_literal(&mut self, enabled: bool) {
+        self.initial_w_literal = enabled;
+    }
+
     /// Set whether bracket shortcuts are enabled: ] → ư, [ → ơ (Issue #159)
     pub fn set_bracket_shortcut(&mut self, enabled: bool) {
         self.bracket_shortcut = enabled;
@@ -434,119 +1221,866 @@ impl Engine {
         self.esc_restore_enabled = enabled;
     }
 
-    /// Set whether to enable free tone placement (skip validation)
-    pub fn set_free_tone(&mut self, enabled: bool) {
-        self.free_tone_enabled = enabled;
-    }
-
-    /// Set whether to use modern orthography for tone placement
-    pub fn set_modern_tone(&mut self, modern: bool) {
-        self.modern_tone = modern;
+    /// Set whether Backspace right after an auto-restore undoes it
+    ///
+    /// "user" + Space → "user " → Backspace → "uẻ" (kept Vietnamese at the
+    /// next boundary) instead of "user".
+    pub fn set_backspace_undo_restore(&mut self, enabled: bool) {
+        self.backspace_undo_restore = enabled;
     }
 
-    /// Set whether to enable English auto-restore (experimental)
-    pub fn set_english_auto_restore(&mut self, enabled: bool) {
-        self.english_auto_restore = enabled;
+    /// Set whether `-` is a soft boundary for hyphenated compounds
+    ///
+    /// The segment before the hyphen is committed, and Backspace over the
+    /// hyphen brings it back for further tone edits ("nha-" + ⌫ + "f" → "nhà").
+    pub fn set_hyphen_soft_boundary(&mut self, enabled: bool) {
+        self.hyphen_soft_boundary = enabled;
     }
 
-    /// Set whether to enable auto-capitalize after sentence-ending punctuation
-    pub fn set_auto_capitalize(&mut self, enabled: bool) {
-        self.auto_capitalize = enabled;
+    /// Set whether URLs, emails and paths are typed without transforms
+    ///
+    /// A word starting with "http", "www." + anything, text + "@", or a path
+    /// ("/usr", "~/", "C:\\", "./") passes through until the next whitespace.
+    pub fn set_url_passthrough(&mut self, enabled: bool) {
+        self.url_passthrough = enabled;
         if !enabled {
-            self.pending_capitalize = false;
-            self.saw_sentence_ending = false;
+            self.passthrough_word = false;
         }
     }
 
-    /// Set whether to allow foreign consonants (z, w, j, f) as valid initials
-    pub fn set_allow_foreign_consonants(&mut self, enabled: bool) {
-        self.allow_foreign_consonants = enabled;
+    /// Set the terminal-safe mode for apps without their own
+    pub fn set_terminal_mode(&mut self, mode: TerminalMode) {
+        self.terminal_mode = mode;
     }
 
-    /// Get whether foreign consonants are allowed
-    pub fn allow_foreign_consonants(&self) -> bool {
-        self.allow_foreign_consonants
+    /// Set the terminal-safe mode for `app`, None to follow the default
+    ///
+    /// `app` is an id as passed to `ShortcutTable::set_active_app`.
+    pub fn set_app_terminal_mode(&mut self, app: &str, mode: Option<TerminalMode>) {
+        let app = quirks::normalize_app(app);
+        match mode {
+            Some(mode) if !app.is_empty() => {
+                self.terminal_apps.insert(app, mode);
+            }
+            _ => {
+                self.terminal_apps.remove(&app);
+            }
+        }
     }
 
-    pub fn shortcuts(&self) -> &ShortcutTable {
-        &self.shortcuts
+    /// Apps with their own terminal-safe mode, by app id
+    pub fn terminal_apps(&self) -> impl Iterator<Item = (&str, TerminalMode)> {
+        self.terminal_apps
+            .iter()
+            .map(|(app, &mode)| (app.as_str(), mode))
     }
 
-    pub fn shortcuts_mut(&mut self) -> &mut ShortcutTable {
-        &mut self.shortcuts
+    /// Terminal-safe mode for the active app
+    pub fn terminal_mode(&self) -> TerminalMode {
+        self.shortcuts
+            .active_app()
+            .and_then(|app| self.terminal_apps.get(&quirks::normalize_app(app)))
+            .copied()
+            .unwrap_or(self.terminal_mode)
     }
 
-    /// Debug: get buffer length
-    pub fn debug_buffer_len(&self) -> usize {
-        self.buf.len()
+    /// Word held back by `TerminalMode::Compose`, for hosts that show it
+    pub fn composing(&self) -> String {
+        self.composer.text()
     }
 
-    /// Debug: get raw_input length (alias for raw_input_len)
-    pub fn debug_raw_input_len(&self) -> usize {
-        self.raw_input.len()
+    /// Forget the held word and return it
+    ///
+    /// Hosts call this before a focus change or click and type the text
+    /// themselves if it should not be lost.
+    pub fn take_composing(&mut self) -> String {
+        self.composer.take()
     }
 
-    /// Debug: check had_any_transform flag
-    pub fn debug_had_any_transform(&self) -> bool {
-        self.had_any_transform
+    /// Set the auto language switch: suspend Vietnamese after `words`
+    /// auto-restored words in a row (0 = off)
+    ///
+    /// Words without transforms ("the", "in") do not break the streak; a word
+    /// kept as Vietnamese does. While suspended, words pass through as typed
+    /// until one reads as Vietnamese ("vieetj" + Space → "việt "), which is
+    /// converted and ends the suspension.
+    pub fn set_auto_english_switch(&mut self, words: u8) {
+        self.auto_english_words = words;
+        self.english_streak = 0;
+        if words == 0 {
+            self.english_suspended = false;
+            self.english_keys.clear();
+        }
     }
 
-    /// Debug: get buffer content as string
-    pub fn debug_buffer_string(&self) -> String {
-        self.buf.to_full_string()
+    /// Whether Vietnamese is suspended by the auto language switch
+    pub fn english_suspended(&self) -> bool {
+        self.english_suspended
     }
 
-    /// Debug: dump full buffer state
-    pub fn debug_buffer_state(&self) -> String {
-        let mut result = String::new();
-        for (i, c) in self.buf.iter().enumerate() {
-            result.push_str(&format!(
-                "[{}] key={} tone={} mark={} stroke={}\n",
-                i, c.key, c.tone, c.mark, c.stroke
-            ));
+    /// Press (true) or release (false) the temporary English key
+    ///
+    /// While held, every key passes through as typed and the engine state
+    /// is left alone, so an English word can go mid-sentence ("dùng " +
+    /// hold "iPhone" + release " mới"). The host binds the key (e.g.
+    /// Right-Alt) and reports its transitions. On release after typing,
+    /// the word in progress and Space-Backspace restore are dropped, since
+    /// the cursor is no longer where they expect it.
+    pub fn set_english_hold(&mut self, held: bool) {
+        if !held && self.english_hold && self.english_hold_typed {
+            self.clear();
+            self.spaces_after_commit = 0;
+            // The English word took the pending capital
+            self.pending_capitalize = false;
+            self.saw_sentence_ending = false;
         }
-        result
+        self.english_hold = held;
+        self.english_hold_typed = false;
     }
 
-    /// Debug: check had_mark_revert flag
-    pub fn debug_had_mark_revert(&self) -> bool {
-        self.had_mark_revert
+    /// Let results that delete text come as `Action::Replace`
+    ///
+    /// Hosts that can select text before the caret and replace it in one
+    /// step (macOS accessibility API, TSF) turn this on per app on focus
+    /// change and apply the replacement without simulated backspaces.
+    /// Break keys still follow the replacement as after a Send.
+    pub fn set_selection_replace(&mut self, enabled: bool) {
+        self.selection_replace = enabled;
     }
 
-    /// Debug: dump raw_input
-    pub fn debug_raw_input(&self) -> String {
-        self.raw_input
-            .iter()
-            .map(|(k, c, s)| format!("({},{},{})", k, c, s))
-            .collect::<Vec<_>>()
-            .join(" ")
+    /// Count backspaces for an app that stores text decomposed (NFD)
+    ///
+    /// Some apps (Electron editors, text converted on paste) keep "ấ" as
+    /// "a" plus two combining marks and need a backspace for each. Results
+    /// that delete such characters then carry the larger count and
+    /// `FLAG_DECOMPOSED`. Hosts turn this on per app on focus change.
+    pub fn set_decomposed_backspace(&mut self, enabled: bool) {
+        self.screen.set_decomposed(enabled);
     }
 
-    /// Get current input method as InputMethod enum
-    fn current_input_method(&self) -> InputMethod {
-        match self.method {
-            0 => InputMethod::Telex,
-            1 => InputMethod::Vni,
-            _ => InputMethod::All,
+    /// Set code-context mode for developers
+    ///
+    /// A word containing `_`, a Telex letter/digit mix ("utf8", "3d") or a
+    /// camelCase hump ("getUser") passes through until the next whitespace.
+    /// Platforms turn this on for IDEs and terminals on focus change.
+    pub fn set_code_context(&mut self, enabled: bool) {
+        self.code_context = enabled;
+        if !enabled {
+            self.passthrough_word = false;
         }
     }
 
-    /// Handle key event - main entry point
+    /// Set whether the built-in arrow/symbol pack is active
     ///
-    /// # Arguments
-    /// * `key` - macOS virtual keycode
-    /// * `caps` - true if Caps Lock is active (for uppercase letters)
-    /// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
-    pub fn on_key(&mut self, key: u16, caps: bool, ctrl: bool) -> Result {
-        self.on_key_ext(key, caps, ctrl, false)
+    /// `->` → →, `=>` → ⇒, `<=` → ≤, `+-` → ± (see `shortcut::SYMBOL_PACK`).
+    /// User shortcuts with the same trigger win. Never fires in code-context
+    /// mode, where `->` and `<=` are operators.
+    pub fn set_symbol_pack(&mut self, enabled: bool) {
+        self.symbol_pack = enabled;
     }
 
-    /// Handle key event with actual Unicode character for shortcuts.
-    ///
-    /// Used for Option-modified keys on macOS where the keycode doesn't change
-    /// but the character is different (e.g., Option+V produces √).
-    ///
-    /// # Arguments
+    /// Set the local UTC offset for `%date%`/`%time%` placeholders
+    pub fn set_utc_offset(&mut self, minutes: i32) {
+        self.host_context.utc_offset_minutes = minutes;
+        self.shortcuts
+            .set_placeholder_context(Box::new(self.host_context.clone()));
+    }
+
+    /// Set the clipboard text for the `%clipboard%` placeholder
+    pub fn set_clipboard_text(&mut self, text: Option<&str>) {
+        self.host_context.clipboard = text.map(str::to_string);
+        self.shortcuts
+            .set_placeholder_context(Box::new(self.host_context.clone()));
+    }
+
+    /// Set whether "u+XXXX" + Space inserts the code point U+XXXX
+    pub fn set_unicode_input(&mut self, enabled: bool) {
+        self.unicode_input = enabled;
+        if !enabled {
+            self.codepoint_hex = None;
+        }
+    }
+
+    /// Immediate shortcut for `trigger`: user table first, then the symbol pack
+    fn match_immediate_shortcut(
+        &self,
+        trigger: &str,
+        input_method: InputMethod,
+    ) -> Option<ShortcutMatch> {
+        self.shortcuts
+            .try_match_for_method(trigger, None, false, input_method)
+            .or_else(|| {
+                if self.symbol_pack && !self.code_context {
+                    self.symbols
+                        .try_match_for_method(trigger, None, false, input_method)
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Set how Telex handles digits inside words
+    pub fn set_digit_policy(&mut self, policy: DigitPolicy) {
+        self.digit_policy = policy;
+    }
+
+    /// Set what a modifier pressed again after its revert does
+    pub fn set_revert_policy(&mut self, policy: RevertPolicy) {
+        self.revert_policy = policy;
+    }
+
+    /// Set what an auto-repeated key does
+    pub fn set_repeat_policy(&mut self, policy: RepeatPolicy) {
+        self.repeat_policy = policy;
+    }
+
+    /// Set the key that restores raw ASCII (default: ESC)
+    ///
+    /// Should be a key that produces no text (ESC, F-keys, `` ` ``); letters,
+    /// digits, Space and Delete keep their normal meaning. ESC still ends
+    /// the word when another key is configured.
+    pub fn set_restore_key(&mut self, key: u16) {
+        self.restore_key = key;
+    }
+
+    /// Set the key that removes diacritics for `method` (0 Telex, 1 VNI)
+    ///
+    /// Defaults are z for Telex and 0 for VNI; None turns removal off so
+    /// the key always types itself ("jazz", "quiz"). Simple Telex uses the
+    /// Telex key, Telex + VNI both. Use a letter or digit key with no other
+    /// role in the method (punctuation ends the word first). Other method
+    /// values are ignored.
+    pub fn set_remove_key(&mut self, method: u8, key: Option<u16>) {
+        if let Some(slot) = self.remove_keys.get_mut(method as usize) {
+            *slot = key;
+        }
+    }
+
+    /// Key `key` removes diacritics in the current method
+    fn is_remove_key(&self, key: u16) -> bool {
+        let [telex, vni] = self.remove_keys;
+        (self.telex_letters() && telex == Some(key)) || (self.vni_digits() && vni == Some(key))
+    }
+
+    /// Set whether to enable free tone placement (skip validation)
+    pub fn set_free_tone(&mut self, enabled: bool) {
+        self.free_tone_enabled = enabled;
+    }
+
+    /// Set whether spelling is checked while typing (default: on)
+    ///
+    /// Off never blocks a keystroke: diacritics apply anywhere, as with
+    /// free tone, and English auto-restore waits for the word boundary
+    /// instead of rewriting mid-word ("tex" + "t" stays "tẽt" until Space).
+    /// Auto-restore itself is still controlled by `set_english_auto_restore`.
+    pub fn set_spell_check(&mut self, enabled: bool) {
+        self.spell_check = enabled;
+    }
+
+    /// Invalid combinations are refused while typing
+    fn checks_spelling(&self) -> bool {
+        self.spell_check && !self.free_tone_enabled
+    }
+
+    /// English auto-restore may rewrite the word before its boundary
+    fn restores_mid_word(&self) -> bool {
+        self.english_auto_restore && self.spell_check
+    }
+
+    /// Set the tone placement style
+    pub fn set_tone_style(&mut self, style: ToneStyle) {
+        self.tone_style = style;
+        self.tone_detector.reset();
+    }
+
+    /// Tone placement style
+    pub fn tone_style(&self) -> ToneStyle {
+        self.tone_style
+    }
+
+    /// Set new style (true, "hoà") or traditional style (false, "hòa")
+    pub fn set_modern_tone(&mut self, modern: bool) {
+        self.tone_style = if modern {
+            ToneStyle::NewStyle
+        } else {
+            ToneStyle::TraditionalStyle
+        };
+    }
+
+    /// Whether tone marks use new-style placement ("hoà" rather than "hòa")
+    pub fn modern_tone(&self) -> bool {
+        self.tone_style.is_new()
+    }
+
+    /// Set whether to enable English auto-restore (experimental)
+    pub fn set_english_auto_restore(&mut self, enabled: bool) {
+        self.english_auto_restore = enabled;
+    }
+
+    /// Set which word boundaries evaluate auto-restore (`RESTORE_ON_*` bits)
+    ///
+    /// E.g. `RESTORE_ON_SPACE | RESTORE_ON_ENTER` restores at Space and Enter
+    /// but keeps "uẻ," as typed. Default: `RESTORE_ON_ALL`.
+    pub fn set_auto_restore_triggers(&mut self, triggers: u8) {
+        self.auto_restore_triggers = triggers & RESTORE_ON_ALL;
+    }
+
+    /// Whether the boundary `key` is in the auto-restore trigger set
+    fn is_auto_restore_trigger(&self, key: u16) -> bool {
+        let bit = match key {
+            keys::SPACE => RESTORE_ON_SPACE,
+            keys::TAB => RESTORE_ON_TAB,
+            keys::RETURN | keys::ENTER => RESTORE_ON_ENTER,
+            _ => RESTORE_ON_PUNCTUATION,
+        };
+        self.auto_restore_triggers & bit != 0
+    }
+
+    /// Set whether to enable auto-capitalize after sentence-ending punctuation
+    pub fn set_auto_capitalize(&mut self, enabled: bool) {
+        self.auto_capitalize = enabled;
+        if !enabled {
+            self.pending_capitalize = false;
+            self.saw_sentence_ending = false;
+        }
+    }
+
+    /// Set what punctuation character `c` does to auto-capitalize
+    ///
+    /// Defaults: `. ! ?` end a sentence; quotes and brackets keep a pending
+    /// capital; anything else cancels it.
+    pub fn set_capitalize_rule(&mut self, c: char, rule: CapitalizeRule) {
+        self.capitalize_rules.set(c, rule);
+    }
+
+    /// Auto-capitalize punctuation rules, e.g. to split text into sentences
+    /// the way the engine does
+    pub fn capitalize_rules(&self) -> &CapitalizeRules {
+        &self.capitalize_rules
+    }
+
+    /// Restore the default auto-capitalize punctuation rules
+    pub fn reset_capitalize_rules(&mut self) {
+        self.capitalize_rules.reset();
+    }
+
+    /// Auto-capitalize state after a break key
+    fn update_pending_capitalize(&mut self, key: u16, shift: bool) {
+        if !self.auto_capitalize {
+            return;
+        }
+        if key == keys::RETURN || key == keys::ENTER {
+            // Enter = newline = immediate capitalize (no space needed)
+            self.pending_capitalize = true;
+            self.saw_sentence_ending = false;
+            return;
+        }
+        match capitalize_rule(&self.capitalize_rules, key, shift) {
+            // Issue #185: Only set saw_sentence_ending for punctuation (not Enter)
+            // pending_capitalize will be set when space follows
+            CapitalizeRule::EndSentence => self.saw_sentence_ending = true,
+            // Quotes, parentheses, brackets, navigation: preserve pending
+            CapitalizeRule::Keep => {}
+            // Word-breaking keys (comma, semicolon, etc.)
+            CapitalizeRule::Cancel => {
+                self.pending_capitalize = false;
+                self.saw_sentence_ending = false;
+            }
+        }
+    }
+
+    /// Set whether to allow foreign consonants (z, w, j, f) as valid initials
+    pub fn set_allow_foreign_consonants(&mut self, enabled: bool) {
+        self.allow_foreign_consonants = enabled;
+    }
+
+    /// Get whether foreign consonants are allowed
+    pub fn allow_foreign_consonants(&self) -> bool {
+        self.allow_foreign_consonants
+    }
+
+    /// Set the validation profile
+    pub fn set_strictness(&mut self, strictness: Strictness) {
+        self.strictness = strictness;
+    }
+
+    /// Get the validation profile
+    pub fn strictness(&self) -> Strictness {
+        self.strictness
+    }
+
+    /// Set which regional and loanword spellings are accepted
+    /// (`dictionary::VARIANT_*` bits)
+    ///
+    /// Words on the selected lists are never auto-restored, and with
+    /// `VARIANT_SOUTHERN` z/dz start a syllable ("dzoo" → "dzô", "zaajy" →
+    /// "zậy") outside the strict profile. Default: none.
+    pub fn set_spelling_variants(&mut self, variants: u8) {
+        self.spelling_variants = variants & dictionary::VARIANT_ALL;
+    }
+
+    /// Get the accepted spelling variants
+    pub fn spelling_variants(&self) -> u8 {
+        self.spelling_variants
+    }
+
+    /// Whether z/dz are valid initials (southern spellings)
+    fn southern_initials(&self) -> bool {
+        self.spelling_variants & dictionary::VARIANT_SOUTHERN != 0
+            && self.strictness != Strictness::Strict
+    }
+
+    /// Whether `word` is in the Vietnamese dictionary or an accepted
+    /// variant list
+    fn is_dictionary_word(&self, word: &str) -> bool {
+        dictionary::is_vietnamese(word, self.allow_foreign_consonants)
+            || dictionary::is_variant(word, self.spelling_variants)
+    }
+
+    /// Set whether misspelled initials are fixed at the word boundary
+    ///
+    /// "nge" → "nghe", "cees" → "kế", "ghas" → "gá". Off in the relaxed
+    /// validation profile, where those spellings are accepted.
+    pub fn set_spelling_autofix(&mut self, enabled: bool) {
+        self.spelling_autofix = enabled;
+    }
+
+    /// Set whether place/person names are capitalized at the word boundary
+    ///
+    /// "hà nội" + Space → "Hà Nội ". Uses the built-in place names plus any
+    /// added with `add_proper_noun`.
+    pub fn set_proper_noun_capitalize(&mut self, enabled: bool) {
+        self.proper_nouns.set_enabled(enabled);
+        self.name_context.clear();
+    }
+
+    /// Set whether a standalone "i" becomes "I" in English text
+    ///
+    /// Applies at the word boundary when the last word with transforms ended
+    /// up English (auto-restored, "tẽt" → "text"), or while the auto language
+    /// switch has suspended Vietnamese ("text i " → "text I ", "i'm" → "I'm").
+    pub fn set_english_capital_i(&mut self, enabled: bool) {
+        self.english_capital_i = enabled;
+    }
+
+    /// Set whether to report a word committed twice in a row
+    ///
+    /// When the word committed at a boundary equals the word before it
+    /// (ignoring case, one space apart), an `Event::RepeatedWord` is queued
+    /// so the frontend can underline it. Intended reduplications ("từ từ",
+    /// "ngày ngày") are not reported.
+    pub fn set_repeated_word_check(&mut self, enabled: bool) {
+        self.repeated_word_check = enabled;
+    }
+
+    /// Set whether to detect Telex vs VNI from the first words typed
+    ///
+    /// Turning it on (or setting the method) starts a new watch of 20
+    /// words. Once two more words only make sense in one method than in
+    /// the other, the engine switches to it and queues
+    /// `Event::MethodChanged`. Only Telex and VNI switch.
+    pub fn set_auto_detect_method(&mut self, enabled: bool) {
+        self.auto_detect_method = enabled;
+        self.method_detector.reset();
+    }
+
+    /// Feed the word being committed to method auto-detection
+    fn detect_method(&mut self) {
+        if !self.auto_detect_method
+            || self.simple_telex
+            || self.method > 1
+            || managed::is_locked("method")
+        {
+            return;
+        }
+        let raw: String = self.raw_chars().into_iter().collect();
+        if let Some(method) = self.method_detector.observe(&raw, self.method) {
+            self.method = method;
+            self.push_event(Event::MethodChanged(method));
+        }
+    }
+
+    /// Set whether to infer the tone style from the user's corrections
+    ///
+    /// Once three committed words use the other placement ("hòa" while
+    /// the style is "hoà"), picked from the candidate list or edited on
+    /// screen, the engine switches style and queues
+    /// `Event::ToneStyleChanged` for the host to confirm.
+    pub fn set_auto_tone_style(&mut self, enabled: bool) {
+        self.auto_tone_style = enabled;
+        self.tone_detector.reset();
+    }
+
+    /// Feed the word being committed to tone style inference
+    fn detect_tone_style(&mut self) {
+        if !self.auto_tone_style || managed::is_locked("tone_style") {
+            return;
+        }
+        let word = self.buf.to_full_string();
+        if let Some(style) = self.tone_detector.observe(&word, self.tone_style) {
+            self.tone_style = style;
+            self.push_event(Event::ToneStyleChanged(style));
+        }
+    }
+
+    /// Bind a hotkey (None unbinds); the key is swallowed when pressed
+    pub fn set_hotkey(&mut self, action: HotkeyAction, hotkey: Option<Hotkey>) {
+        self.hotkeys.bind(action, hotkey);
+    }
+
+    /// Switch to the next method (Telex → VNI → Telex), returning it
+    ///
+    /// Other methods (Simple Telex, Telex + VNI) go to Telex. The word in
+    /// progress ends and `Event::MethodChanged` is queued. A method the
+    /// managed config locks stays.
+    pub fn cycle_method(&mut self) -> u8 {
+        let current = self.method();
+        if managed::is_locked("method") {
+            return current;
+        }
+        let next = METHOD_CYCLE
+            .iter()
+            .position(|&m| m == current)
+            .map_or(METHOD_CYCLE[0], |i| {
+                METHOD_CYCLE[(i + 1) % METHOD_CYCLE.len()]
+            });
+        self.clear();
+        self.set_method(next);
+        self.push_event(Event::MethodChanged(next));
+        next
+    }
+
+    /// Run a hotkey action; the key itself is swallowed
+    fn run_hotkey(&mut self, action: HotkeyAction) -> Result {
+        match action {
+            HotkeyAction::CycleMethod => {
+                self.cycle_method();
+            }
+            HotkeyAction::NextProfile => {
+                self.cycle_profile();
+            }
+            HotkeyAction::UndoLast => {
+                let mut result = self.undo_auto();
+                result.flags |= FLAG_KEY_CONSUMED;
+                return result;
+            }
+            // An administrator's value stays
+            HotkeyAction::ToggleTitleCase if !managed::is_locked("title_case") => {
+                let enabled = self.toggle_title_case();
+                self.push_event(Event::TitleCaseChanged(enabled));
+            }
+            HotkeyAction::ToggleTitleCase => {}
+        }
+        Result::consumed()
+    }
+
+    /// Take the events queued since the last call, oldest first
+    ///
+    /// At most the last 16 are kept if nobody collects them.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn push_event(&mut self, event: Event) {
+        if self.events.len() >= MAX_EVENTS {
+            self.events.remove(0);
+        }
+        self.events.push(event);
+    }
+
+    /// Queue `Event::RepeatedWord` if `word`, being committed, repeats the
+    /// previous word
+    fn check_repeated_word(&mut self, word: &str) {
+        if !self.repeated_word_check || self.spaces_after_commit != 1 {
+            return;
+        }
+        let Some(prev) = self.word_history.last() else {
+            return;
+        };
+        let word_lower = word.to_lowercase();
+        if word_lower.chars().any(char::is_alphabetic)
+            && word_lower == prev.to_full_string().to_lowercase()
+            && !INTENDED_REPEATS.contains(&word_lower.as_str())
+        {
+            self.push_event(Event::RepeatedWord(word.to_string()));
+        }
+    }
+
+    /// Set Title Case mode: the first letter of every word is capitalized
+    ///
+    /// For typing headings ("tiêu đề bài viết" → "Tiêu Đề Bài Viết"). Letters
+    /// after an apostrophe or inside a token ("don't", "a.b") are left alone.
+    pub fn set_title_case(&mut self, enabled: bool) {
+        self.title_case = enabled;
+    }
+
+    /// Flip Title Case mode (hotkey), returning the new state
+    pub fn toggle_title_case(&mut self) -> bool {
+        self.title_case = !self.title_case;
+        self.title_case
+    }
+
+    /// Whether Title Case mode is on
+    pub fn title_case(&self) -> bool {
+        self.title_case
+    }
+
+    /// Set whether toggling CapsLock mid-word re-cases the whole word
+    ///
+    /// CapsLock on upper-cases the word typed so far ("vie" + CapsLock +
+    /// "ETJ" → "VIỆT"); off lower-cases all but its first letter ("VIEE" +
+    /// "tj" → "Việt"). Needs the host to report `caps` as Shift XOR CapsLock.
+    pub fn set_caps_lock_recase(&mut self, enabled: bool) {
+        self.caps_lock_recase = enabled;
+    }
+
+    /// Add a name as it should be written ("Nguyễn Văn An", at most 5 words)
+    ///
+    /// Returns false if the name is empty or too long.
+    pub fn add_proper_noun(&mut self, name: &str) -> bool {
+        self.proper_nouns.add(name)
+    }
+
+    /// Set whether chat abbreviations are committed as typed
+    ///
+    /// "wa" + Space → "wa " instead of "ưa ", and "ko" is not respelled or
+    /// restored. Uses the built-in abbreviations plus any added with
+    /// `add_teencode`; shortcuts still expand first.
+    pub fn set_teencode_passthrough(&mut self, enabled: bool) {
+        self.teencode.set_enabled(enabled);
+    }
+
+    /// Whether chat abbreviations are committed as typed
+    pub fn teencode_passthrough(&self) -> bool {
+        self.teencode.enabled()
+    }
+
+    /// Add a chat abbreviation to keep as typed ("vcl")
+    ///
+    /// Returns false if the word is empty or has spaces.
+    pub fn add_teencode(&mut self, word: &str) -> bool {
+        self.teencode.add(word)
+    }
+
+    /// Remove all added chat abbreviations (built-in ones stay)
+    pub fn clear_teencode(&mut self) {
+        self.teencode.clear();
+    }
+
+    /// Whether the keys of the current word spell a chat abbreviation
+    fn is_teencode(&self) -> bool {
+        self.teencode.contains(&self.get_raw_input_string())
+    }
+
+    /// Add an exception word that is always left as typed
+    ///
+    /// Like keep.dic, but editable at runtime ("expect", "deadline", brand
+    /// names). Matched case-insensitively against the keys typed for a word;
+    /// at the word boundary any transforms are undone, whether or not English
+    /// auto-restore is on.
+    pub fn add_english_word(&mut self, word: &str) {
+        let word = word.trim().to_lowercase();
+        if !word.is_empty() {
+            self.english_words.insert(word);
+        }
+    }
+
+    /// Remove an exception word, false if it was not in the list
+    pub fn remove_english_word(&mut self, word: &str) -> bool {
+        self.english_words.remove(&word.trim().to_lowercase())
+    }
+
+    /// Remove all exception words
+    pub fn clear_english_words(&mut self) {
+        self.english_words.clear();
+    }
+
+    /// Whether `word` is an exception word
+    pub fn has_english_word(&self, word: &str) -> bool {
+        self.english_words.contains(&word.to_lowercase())
+    }
+
+    pub fn shortcuts(&self) -> &ShortcutTable {
+        &self.shortcuts
+    }
+
+    pub fn shortcuts_mut(&mut self) -> &mut ShortcutTable {
+        &mut self.shortcuts
+    }
+
+    /// Debug: get buffer length
+    pub fn debug_buffer_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Debug: get raw_input length (alias for raw_input_len)
+    pub fn debug_raw_input_len(&self) -> usize {
+        self.raw_input.len()
+    }
+
+    /// Debug: check had_any_transform flag
+    pub fn debug_had_any_transform(&self) -> bool {
+        self.had_any_transform
+    }
+
+    /// Debug: get buffer content as string
+    pub fn debug_buffer_string(&self) -> String {
+        self.buf.to_full_string()
+    }
+
+    /// Debug: dump full buffer state
+    pub fn debug_buffer_state(&self) -> String {
+        let mut result = String::new();
+        for (i, c) in self.buf.iter().enumerate() {
+            result.push_str(&format!(
+                "[{}] key={} tone={} mark={} stroke={}\n",
+                i, c.key, c.tone, c.mark, c.stroke
+            ));
+        }
+        result
+    }
+
+    /// Debug: check had_mark_revert flag
+    pub fn debug_had_mark_revert(&self) -> bool {
+        self.had_mark_revert
+    }
+
+    /// Debug: dump raw_input
+    pub fn debug_raw_input(&self) -> String {
+        self.raw_input
+            .iter()
+            .map(|(k, c, s)| format!("({},{},{})", k, c, s))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Snapshot of engine state as a JSON object
+    ///
+    /// Used by the frontend debug panel to show exactly what the engine thinks
+    /// is on screen. Format is for humans/tools, not a stable contract.
+    pub fn dump_state(&self) -> String {
+        let esc = utils::json_escape;
+        let json_key = |k: Option<u16>| k.map_or("null".to_string(), |k| k.to_string());
+        let chars: Vec<String> = self
+            .buf
+            .iter()
+            .map(|c| {
+                let ch = if c.key == keys::D && c.stroke {
+                    Some(chars::get_d(c.caps))
+                } else {
+                    chars::to_char(c.key, c.caps, c.tone, c.mark)
+                        .or_else(|| utils::key_to_char(c.key, c.caps))
+                };
+                format!(
+                    "{{\"char\":\"{}\",\"key\":{},\"caps\":{},\"tone\":{},\"mark\":{},\"stroke\":{}}}",
+                    esc(&ch.map(String::from).unwrap_or_default()),
+                    c.key,
+                    c.caps,
+                    c.tone,
+                    c.mark,
+                    c.stroke
+                )
+            })
+            .collect();
+        let last_transform = match self.last_transform {
+            Some(t) => format!("\"{}\"", esc(&format!("{:?}", t))),
+            None => "null".to_string(),
+        };
+
+        format!(
+            concat!(
+                "{{\"method\":{},\"enabled\":{},",
+                "\"buffer\":\"{}\",\"chars\":[{}],\"raw_input\":\"{}\",",
+                "\"last_transform\":{},\"had_any_transform\":{},\"had_mark_revert\":{},",
+                "\"pending_capitalize\":{},\"saw_sentence_ending\":{},",
+                "\"shortcut_prefix\":\"{}\",\"word_history\":{},\"spaces_after_commit\":{},\"english_hold\":{},",
+                "\"options\":{{\"skip_w_shortcut\":{},\"initial_w_literal\":{},\"bracket_shortcut\":{},",
+                "\"esc_restore\":{},\"restore_key\":{},\"remove_keys\":[{},{}],\"free_tone\":{},\"spell_check\":{},\"tone_style\":{},",
+                "\"english_auto_restore\":{},\"auto_restore_triggers\":{},",
+                "\"backspace_undo_restore\":{},\"hyphen_soft_boundary\":{},\"url_passthrough\":{},\"code_context\":{},\"digit_policy\":{},\"revert_policy\":{},\"repeat_policy\":{},\"symbol_pack\":{},\"unicode_input\":{},\"auto_capitalize\":{},\"title_case\":{},",
+                "\"allow_foreign_consonants\":{},\"strictness\":{},\"spelling_variants\":{},\"spelling_autofix\":{},\"proper_noun_capitalize\":{},\"teencode_passthrough\":{},\"caps_lock_recase\":{},\"english_capital_i\":{},\"auto_english_switch\":{},\"per_app_enabled\":{},\"english_words\":{},\"shortcuts\":{}}}}}"
+            ),
+            self.method(),
+            self.enabled,
+            esc(&self.buf.to_full_string()),
+            chars.join(","),
+            esc(&self.get_raw_input_string_preserve_case()),
+            last_transform,
+            self.had_any_transform,
+            self.had_mark_revert,
+            self.pending_capitalize,
+            self.saw_sentence_ending,
+            esc(&self.shortcut_prefix),
+            self.word_history.len,
+            self.spaces_after_commit,
+            self.english_hold,
+            self.skip_w_shortcut,
+            self.initial_w_literal,
+            self.bracket_shortcut,
+            self.esc_restore_enabled,
+            self.restore_key,
+            json_key(self.remove_keys[0]),
+            json_key(self.remove_keys[1]),
+            self.free_tone_enabled,
+            self.spell_check,
+            self.tone_style as u8,
+            self.english_auto_restore,
+            self.auto_restore_triggers,
+            self.backspace_undo_restore,
+            self.hyphen_soft_boundary,
+            self.url_passthrough,
+            self.code_context,
+            self.digit_policy as u8,
+            self.revert_policy as u8,
+            self.repeat_policy as u8,
+            self.symbol_pack,
+            self.unicode_input,
+            self.auto_capitalize,
+            self.title_case,
+            self.allow_foreign_consonants,
+            self.strictness as u8,
+            self.spelling_variants,
+            self.spelling_autofix,
+            self.proper_nouns.enabled(),
+            self.teencode.enabled(),
+            self.caps_lock_recase,
+            self.english_capital_i,
+            self.auto_english_words,
+            self.per_app_enabled,
+            self.english_words.len(),
+            self.shortcuts.len()
+        )
+    }
+
+    /// Get current input method as InputMethod enum
+    fn current_input_method(&self) -> InputMethod {
+        match self.method {
+            0 => InputMethod::Telex,
+            1 => InputMethod::Vni,
+            _ => InputMethod::All,
+        }
+    }
+
+    /// Handle key event - main entry point
+    ///
+    /// # Arguments
+    /// * `key` - macOS virtual keycode
+    /// * `caps` - true if Caps Lock is active (for uppercase letters)
+    /// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME). With
+    ///   Backspace it is a word delete (Ctrl+Backspace, Option+Backspace):
+    ///   the engine drops the word the app deleted and keeps what it knows
+    ///   of the text before it. Cmd+Backspace on macOS deletes to the start
+    ///   of the line; hosts report it with `clear_all` instead.
+    pub fn on_key(&mut self, key: u16, caps: bool, ctrl: bool) -> Result {
+        self.on_key_ext(key, caps, ctrl, false)
+    }
+
+    /// Handle key event with actual Unicode character for shortcuts.
+    ///
+    /// Used for Option-modified keys on macOS where the keycode doesn't change
+    /// but the character is different (e.g., Option+V produces √).
+    ///
+    /// # Arguments
     /// * `key` - macOS virtual keycode
     /// * `caps` - true if Caps Lock is active
     /// * `ctrl` - true if Cmd/Ctrl is pressed (bypasses IME)
@@ -568,7 +2102,34 @@ impl Engine {
         let Some(ch) = ch else {
             return self.on_key_ext(key, caps, ctrl, shift);
         };
+        self.traced(
+            || session::char_event(key, caps, ctrl, shift, ch),
+            |e| e.char_key_down(key, ctrl, shift, ch),
+        )
+    }
 
+    fn char_key_down(&mut self, key: u16, ctrl: bool, shift: bool, ch: char) -> Result {
+        let _timing = latency::span(Stage::Key);
+        self.reasons.clear();
+        self.hooks.next_key(key, shift);
+        self.last_auto = None;
+        self.cursor_back = None;
+        self.screen.clear();
+        let mut result = self.handle_char_key(ctrl, ch);
+        self.apply_cursor_marker(&mut result, key, shift);
+        // The host types `ch` when the key is not consumed
+        let delta = (!ctrl).then(|| text_delta(&result, key, false, shift).unwrap_or(1));
+        self.track_snippet(&mut result, key, shift, delta);
+        self.stats.record_key(&result);
+        if !ctrl {
+            self.recorder.record(&result, key, shift, Some(ch));
+        }
+        self.selection_result(&mut result);
+        result
+    }
+
+    /// Suffix-match shortcuts against the accumulated Unicode characters
+    fn handle_char_key(&mut self, ctrl: bool, ch: char) -> Result {
         // Ctrl/Cmd bypasses everything
         if ctrl {
             self.clear();
@@ -584,13 +2145,17 @@ impl Engine {
         let input_method = self.current_input_method();
         for (idx, _) in self.shortcut_prefix.char_indices() {
             let suffix = &self.shortcut_prefix[idx..];
-            if let Some(m) = self.shortcuts.try_match_for_method(
-                suffix,
-                None,
-                false, // immediate, not word boundary
-                input_method,
-            ) {
+            if let Some(m) = self.match_immediate_shortcut(suffix, input_method) {
+                // Field-wise: `suffix` still borrows the prefix
+                self.stats.record_shortcut(&m.trigger);
+                self.cursor_back = m.cursor_back;
+                self.pending_fields = m.fields.clone();
                 let output: Vec<char> = m.output.chars().collect();
+                self.last_auto = Some(AutoUndo::new(
+                    AutoAction::Shortcut,
+                    tail_chars(suffix, m.backspace_count),
+                    output.clone(),
+                ));
                 let backspace_count = (m.backspace_count as u8).saturating_sub(1);
                 self.shortcut_prefix.clear();
                 return Result::send_consumed(backspace_count, &output);
@@ -621,6 +2186,35 @@ impl Engine {
         )
     }
 
+    /// Handle a key the OS auto-repeats while it is held
+    ///
+    /// Same arguments as `on_key_ext`. With `RepeatPolicy::Literal` a
+    /// repeated character key ends the word for the IME: what is on screen
+    /// stays and the repeats are typed as-is until the next word boundary.
+    /// Backspace, keys with Cmd/Ctrl and word boundaries (holding Space)
+    /// repeat as ordinary presses.
+    pub fn on_key_repeat(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        self.traced(
+            || session::repeat_event(key, caps, ctrl, shift),
+            |e| e.key_repeat(key, caps, ctrl, shift),
+        )
+    }
+
+    fn key_repeat(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        if self.repeat_policy == RepeatPolicy::Literal
+            && self.enabled
+            && !ctrl
+            && key != keys::DELETE
+            && !ends_token(key)
+            && !self.passthrough_word
+        {
+            self.clear();
+            self.english_keys.clear();
+            self.passthrough_word = true;
+        }
+        self.key_down(key, caps, ctrl, shift)
+    }
+
     /// Handle key event with extended parameters
     ///
     /// # Arguments
@@ -629,8 +2223,560 @@ impl Engine {
     /// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
     /// * `shift` - true if Shift key is pressed (for symbols like @, #, $)
     pub fn on_key_ext(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        self.traced(
+            || session::key_event(key, caps, ctrl, shift),
+            |e| e.key_down(key, caps, ctrl, shift),
+        )
+    }
+
+    /// `on_key_ext` without tracing
+    fn key_down(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        let _timing = latency::span(Stage::Key);
+        self.reasons.clear();
+        self.hooks.next_key(key, shift);
+        log_trace!(
+            "engine",
+            "key key={} caps={} ctrl={} shift={} buf=\"{}\"",
+            key,
+            caps,
+            ctrl,
+            shift,
+            self.buf.to_full_string()
+        );
+        if let Some(action) = self.hotkeys.find(key, ctrl, shift) {
+            return self.run_hotkey(action);
+        }
+        let terminal = self.terminal_mode();
+        if terminal == TerminalMode::Passthrough {
+            if !self.buf.is_empty() {
+                self.clear();
+            }
+            self.screen.clear();
+            return Result::none();
+        }
+        if self.english_hold {
+            self.english_hold_typed = true;
+            self.screen.clear();
+            return Result::none();
+        }
+        // Word boundaries clear the buffer mid-key; the screen stays
+        let mut screen = std::mem::take(&mut self.screen);
+        let previous_auto = self.last_auto.take();
+        self.cursor_back = None;
+        let mut result = match previous_auto {
+            Some(undo)
+                if self.backspace_undo_restore
+                    && key == keys::DELETE
+                    && !ctrl
+                    && undo.snapshot.is_some() =>
+            {
+                self.undo_restore_on_backspace(undo)
+            }
+            _ => match self.try_codepoint_input(key, caps, ctrl, shift) {
+                Some(result) => result,
+                None => match self.try_url_passthrough(key, caps, ctrl, shift) {
+                    Some(result) => result,
+                    None => match self.try_word_overflow(key, caps, ctrl, shift) {
+                        Some(result) => result,
+                        None => match self.try_english_suspended(key, caps, ctrl, shift) {
+                            Some(result) => result,
+                            None => self.handle_key_recase(key, caps, ctrl, shift),
+                        },
+                    },
+                },
+            },
+        };
+        self.last_token_char = match key {
+            _ if ctrl || ends_token(key) => None,
+            keys::DELETE => self.last_token_char,
+            _ => typed_char(key, caps, shift),
+        };
+        self.word_transformed |= self.had_any_transform;
+        self.apply_cursor_marker(&mut result, key, shift);
+        let delta = (!ctrl)
+            .then(|| text_delta(&result, key, caps, shift))
+            .flatten();
+        self.track_snippet(&mut result, key, shift, delta);
+        self.stats.record_key(&result);
+        if !ctrl {
+            let ch = key_text(key, caps, shift);
+            self.recorder.record(&result, key, shift, ch);
+        }
+        // Only rewrites inside a word; expansions and restores at a word
+        // boundary stay whole so they can be undone as one
+        let trim = self.last_auto.is_none() && !self.buf.is_empty();
+        screen.apply(&mut result, trim, key, caps, ctrl, shift);
+        self.screen = screen;
+        if terminal == TerminalMode::Compose {
+            let word_open = !self.buf.is_empty();
+            result = self
+                .composer
+                .filter(result, key, caps, ctrl, shift, word_open);
+        }
+        self.selection_result(&mut result);
+        result
+    }
+
+    /// Turn a result that deletes text into `Action::Replace` for hosts
+    /// that asked for it (`set_selection_replace`)
+    fn selection_result(&self, result: &mut Result) {
+        if !self.selection_replace || result.backspace == 0 {
+            return;
+        }
+        match result.action {
+            a if a == Action::Send as u8 => result.action = Action::Replace as u8,
+            a if a == Action::Restore as u8 => {
+                // Restore swallows its key; Replace says so with the flag
+                result.action = Action::Replace as u8;
+                result.flags |= FLAG_KEY_CONSUMED;
+            }
+            _ => {}
+        }
+    }
+
+    /// `handle_key`, re-casing the word if CapsLock was toggled since its
+    /// previous letter (see `set_caps_lock_recase`)
+    fn handle_key_recase(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        let letter = !ctrl && keys::is_letter(key);
+        // Hosts pass caps = Shift XOR CapsLock
+        let caps_lock = caps != shift;
+        let before = (self.caps_lock_recase
+            && letter
+            && !self.buf.is_empty()
+            && self.word_caps_lock.is_some_and(|c| c != caps_lock))
+        .then(|| self.buf.to_full_string());
+        let result = self.handle_key(key, caps, ctrl, shift);
+        if letter {
+            self.word_caps_lock = Some(caps_lock);
+        }
+        before
+            .and_then(|before| self.recase_word(&before, &result, key, caps, shift, caps_lock))
+            .unwrap_or(result)
+    }
+
+    /// Rewrite the word after a CapsLock toggle
+    ///
+    /// `before` is the word on screen before the key. Gives up (None) when
+    /// the buffer does not match the screen after `result`, so the rewrite
+    /// never deletes text the engine does not know about.
+    fn recase_word(
+        &mut self,
+        before: &str,
+        result: &Result,
+        key: u16,
+        caps: bool,
+        shift: bool,
+        caps_lock: bool,
+    ) -> Option<Result> {
+        let mut screen: Vec<char> = before.chars().collect();
+        if result.action == Action::None as u8 {
+            screen.push(typed_char(key, caps, shift)?);
+        } else if result.action == Action::Send as u8 {
+            screen.truncate(screen.len().checked_sub(result.backspace as usize)?);
+            screen.extend(result_chars(result));
+        } else {
+            return None;
+        }
+        let screen: String = screen.into_iter().collect();
+        if screen != self.buf.to_full_string() {
+            return None;
+        }
+
+        // CapsLock on: all uppercase. Off: lowercase after the first letter
+        for i in 0..self.buf.len() {
+            if let Some(c) = self.buf.get_mut(i) {
+                c.caps = caps_lock || (i == 0 && c.caps);
+            }
+        }
+        for (i, raw) in self.raw_input.iter_mut().enumerate() {
+            raw.1 = caps_lock || (i == 0 && raw.1);
+        }
+        let recased = self.buf.to_full_string();
+        if recased == screen {
+            return None;
+        }
+        let chars: Vec<char> = recased.chars().collect();
+        Some(Result::send(before.chars().count() as u8, &chars))
+    }
+
+    /// Case for a vowel inserted by a modifier key (w → ư, ] → ư)
+    ///
+    /// The key's own case, except in a word typed ALL-CAPS so far: "TRw" →
+    /// "TRƯ", since a lowercase modifier there is a Shift released early.
+    /// A single capital is Title case ("Tw" → "Tư").
+    fn inserted_vowel_caps(&self, caps: bool) -> bool {
+        caps || (self.buf.len() >= 2 && self.buf.iter().all(|c| c.caps))
+    }
+
+    /// Count a shortcut expansion and remember its cursor marker and fields
+    fn record_expansion(&mut self, m: &ShortcutMatch) {
+        self.stats.record_shortcut(&m.trigger);
+        self.cursor_back = m.cursor_back;
+        self.pending_fields = m.fields.clone();
+    }
+
+    /// Start a snippet for an expansion with fields, or follow the caret
+    /// through one being filled in
+    ///
+    /// `delta` is the text the key adds at the caret (None: the key may have
+    /// moved the caret, which ends the snippet).
+    fn track_snippet(&mut self, result: &mut Result, key: u16, shift: bool, delta: Option<i32>) {
+        let fields = std::mem::take(&mut self.pending_fields);
+        if !fields.is_empty() && result.action == Action::Send as u8 {
+            let caret = result.count as usize + host_types_key(result, key, shift) as usize;
+            self.last_auto = None;
+            self.clear_all();
+            self.snippet = Some(snippet::Session::new(fields, caret));
+            result.flags |= FLAG_SNIPPET;
+            return;
+        }
+        let Some(session) = self.snippet.as_mut() else {
+            return;
+        };
+        match delta {
+            Some(delta) => session.edit(delta, key == keys::DELETE),
+            None => self.snippet = None,
+        }
+    }
+
+    /// Jump to the next snippet field, None when no snippet is being filled in
+    ///
+    /// Hosts call this right after a result flagged `FLAG_SNIPPET` and on Tab
+    /// while `snippet_active()` (swallowing the Tab): move the caret by
+    /// `caret`, then select `select` characters to the right.
+    pub fn snippet_next(&mut self) -> Option<snippet::Jump> {
+        let mut session = self.snippet.take()?;
+        let jump = session.next_field()?;
+        // The caret is somewhere new: forget the word around the old one
+        self.last_auto = None;
+        self.clear_all();
+        if session.is_active() {
+            self.snippet = Some(session);
+        }
+        self.snippet_select = jump.select;
+        Some(jump)
+    }
+
+    /// Whether a snippet has fields left to visit
+    pub fn snippet_active(&self) -> bool {
+        self.snippet.as_ref().is_some_and(|s| s.is_active())
+    }
+
+    /// Characters to select after the last `snippet_next()` jump
+    pub fn snippet_selection(&self) -> usize {
+        self.snippet_select
+    }
+
+    /// Flag a shortcut result whose output has a cursor marker
+    ///
+    /// The offset counts the key itself when the host types it after the
+    /// output (punctuation, Enter). Moving the caret ends the word, so the
+    /// engine forgets it like on a click.
+    fn apply_cursor_marker(&mut self, result: &mut Result, key: u16, shift: bool) {
+        let Some(mut back) = self.cursor_back.take() else {
+            return;
+        };
+        if result.action != Action::Send as u8 {
+            return;
+        }
+        if host_types_key(result, key, shift) {
+            back += 1;
+        }
+        if back == 0 {
+            return;
+        }
+        self.cursor_offset = back.min(u8::MAX as usize) as u8;
+        result.flags |= FLAG_CURSOR_MOVE;
+        self.last_auto = None;
+        self.clear_all();
+    }
+
+    /// Left-arrow presses for the last result with `FLAG_CURSOR_MOVE`
+    pub fn cursor_offset(&self) -> u8 {
+        self.cursor_offset
+    }
+
+    /// Unicode code-point input ("u+1ec7" + Space → ệ)
+    ///
+    /// "u" + "+" starts a code point; up to 6 hex digits pass through as
+    /// typed and Space replaces "u+XXXX" with the character (Space consumed).
+    /// Any other key, or an invalid code point, leaves the text as typed.
+    fn try_codepoint_input(
+        &mut self,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) -> Option<Result> {
+        if ctrl || !self.enabled || !self.unicode_input {
+            self.codepoint_hex = None;
+            return None;
+        }
+
+        if let Some(hex) = self.codepoint_hex.as_mut() {
+            let digit = (!shift)
+                .then(|| utils::key_to_char(key, false))
+                .flatten()
+                .filter(|c| c.is_ascii_hexdigit());
+            match digit {
+                Some(c) if hex.len() < 6 => {
+                    hex.push(if caps { c.to_ascii_uppercase() } else { c });
+                    return Some(Result::none());
+                }
+                _ if key == keys::DELETE => {
+                    // Empty: Backspace deletes the '+' and leaves code-point input
+                    if hex.pop().is_none() {
+                        self.codepoint_hex = None;
+                    }
+                    return Some(Result::none());
+                }
+                _ => {}
+            }
+            let hex = self.codepoint_hex.take()?;
+            if key != keys::SPACE {
+                return None;
+            }
+            let ch = u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)?;
+            log_debug!("engine", "codepoint u+{} → {:?}", hex, ch);
+            self.clear();
+            self.word_history.clear();
+            self.spaces_after_commit = 0;
+            return Some(Result::send((hex.len() + 2) as u8, &[ch]));
+        }
+
+        // "u" then Shift+= ('+'): the 'u' is untransformed, '+' passes through
+        let is_u = self.buf.len() == 1 && matches!(self.raw_chars()[..], ['u' | 'U']);
+        if key == keys::EQUAL && shift && is_u {
+            self.clear();
+            self.word_history.clear();
+            self.spaces_after_commit = 0;
+            self.codepoint_hex = Some(String::new());
+            return Some(Result::none());
+        }
+        None
+    }
+
+    /// Whether `ch` makes the current word a code identifier
+    ///
+    /// `_` anywhere, a lowercase→uppercase hump, or letters next to digits.
+    /// Digits are tone keys in VNI, so the digit rule is Telex only.
+    fn is_identifier_char(&self, ch: char) -> bool {
+        let prev = self.last_token_char;
+        let prev_letter = prev.is_some_and(|c| c.is_ascii_alphabetic());
+        let prev_digit = prev.is_some_and(|c| c.is_ascii_digit());
+        match ch {
+            '_' => true,
+            c if c.is_ascii_uppercase() => prev.is_some_and(|c| c.is_ascii_lowercase()),
+            c if c.is_ascii_digit() => self.method == 0 && prev_letter,
+            c if c.is_ascii_alphabetic() => self.method == 0 && prev_digit,
+            _ => false,
+        }
+    }
+
+    /// URL/email/path detection (and identifiers in code-context mode)
+    ///
+    /// Returns Some when the key belongs to a URL, email, path or identifier:
+    /// the word typed so far is restored to raw ASCII and every key passes
+    /// through untouched until whitespace ("www." → "www.", "uẻ@" → "user@").
+    fn try_url_passthrough(
+        &mut self,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) -> Option<Result> {
+        if ctrl || !self.enabled {
+            self.passthrough_word = false;
+            return None;
+        }
+        if self.passthrough_word {
+            if ends_token(key) {
+                self.passthrough_word = false;
+                return None;
+            }
+            return Some(Result::none());
+        }
+        if !(self.url_passthrough || self.code_context) {
+            return None;
+        }
+
+        let ch = typed_char(key, caps, shift)?;
+        let raw = || self.raw_chars().into_iter().collect::<String>();
+        let url = self.url_passthrough
+            && match ch {
+                'p' | 'P' => raw().eq_ignore_ascii_case("htt"),
+                '.' => raw().eq_ignore_ascii_case("www"),
+                '@' => !self.buf.is_empty(),
+                '/' | '\\' => matches!(
+                    self.last_token_char,
+                    None | Some(':' | '~' | '.' | '/' | '\\')
+                ),
+                _ => false,
+            };
+        let identifier = self.code_context && self.is_identifier_char(ch);
+        if !url && !identifier {
+            return None;
+        }
+        log_debug!("engine", "passthrough trigger={:?}", ch);
+        Some(self.start_passthrough(key, ch, shift))
+    }
+
+    /// Word overflow: a word reaching `MAX_WORD_KEYS` keys is a long token
+    /// (pasted identifier, German compound), not Vietnamese
+    ///
+    /// Instead of growing past what a result can erase, the word is
+    /// restored to raw ASCII and the rest of the token passes through
+    /// untouched until whitespace, as with URLs.
+    fn try_word_overflow(
+        &mut self,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) -> Option<Result> {
+        if ctrl || !self.enabled || self.raw_input.len() < MAX_WORD_KEYS || ends_token(key) {
+            return None;
+        }
+        let ch = typed_char(key, caps, shift)?;
+        log_debug!("engine", "word overflow keys={}", self.raw_input.len());
+        Some(self.start_passthrough(key, ch, shift))
+    }
+
+    /// Restore the word to raw ASCII, followed by `ch` unless `key` is a
+    /// break key, and pass keys through until whitespace
+    fn start_passthrough(&mut self, key: u16, ch: char, shift: bool) -> Result {
+        // Break keys pass through after the raw word; letters and digits
+        // are part of the replacement like any other typed letter
+        let mut result = self.restore_to_raw();
+        if result.action == Action::Restore as u8 {
+            let mut chars = result_chars(&result);
+            if !keys::is_break_ext(key, shift) {
+                chars.push(ch);
+            }
+            result = Result::send(result.backspace, &chars);
+        }
+        if !self.buf.is_empty() {
+            self.stats.record_word();
+        }
+        self.clear();
+        self.word_history.clear();
+        self.spaces_after_commit = 0;
+        self.passthrough_word = true;
+        result
+    }
+
+    /// Auto language switch: pass keys through while Vietnamese is suspended
+    ///
+    /// Returns Some while suspended. At a word boundary the word is replayed
+    /// through a fresh engine; if it reads as a Vietnamese word that is not
+    /// English ("vieetj" → "việt"), it is converted and Vietnamese resumes.
+    fn try_english_suspended(
+        &mut self,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) -> Option<Result> {
+        if !self.english_suspended {
+            return None;
+        }
+        if ctrl || !self.enabled {
+            self.english_keys.clear();
+            return None;
+        }
+        if key == keys::DELETE {
+            self.english_keys.pop();
+            return Some(Result::none());
+        }
+        if !keys::is_break_ext(key, shift) {
+            if utils::key_to_char_ext(key, caps, shift).is_some() {
+                self.english_keys.push((key, caps, shift));
+            }
+            return Some(Result::none());
+        }
+
+        let word = std::mem::take(&mut self.english_keys);
+        // A long token stays as typed (see `try_word_overflow`)
+        if word.len() > MAX_WORD_KEYS {
+            return Some(Result::none());
+        }
+        let boundary = (key == keys::SPACE).then_some(' ');
+        if let Some(result) = self.try_capital_i(&word, boundary) {
+            return Some(result);
+        }
+        // Space is part of the replacement; other break chars follow it
+        let converts = key == keys::SPACE || break_key_to_char(key, shift).is_some();
+        let Some(mut chars) = self.vietnamese_form(&word).filter(|_| converts) else {
+            return Some(Result::none());
+        };
+        log_debug!("engine", "auto language switch: resume Vietnamese");
+        self.english_suspended = false;
+        self.english_context = false;
+        self.stats.record_word();
+        if key == keys::SPACE {
+            chars.push(' ');
+        }
+        Some(Result::send(word.len() as u8, &chars))
+    }
+
+    /// Vietnamese rendering of `word` if it reads as Vietnamese, not English
+    fn vietnamese_form(&self, word: &[(u16, bool, bool)]) -> Option<Vec<char>> {
+        let raw: String = word
+            .iter()
+            .filter_map(|&(key, caps, shift)| raw_key_char(key, caps, shift))
+            .collect();
+        if raw.is_empty() || english_dict::is_english_word(&raw) {
+            return None;
+        }
+        let mut e = self.fresh_copy();
+        e.auto_english_words = 0;
+        for &(key, caps, shift) in word {
+            e.on_key_ext(key, caps, false, shift);
+        }
+        let rendered = e.buf.to_full_string();
+        let vietnamese =
+            e.had_any_transform && rendered != raw && self.is_dictionary_word(&rendered);
+        vietnamese.then(|| rendered.chars().collect())
+    }
+
+    /// Count auto-restored words toward the auto language switch
+    ///
+    /// Called when a word is committed; words without transforms are neutral.
+    /// A word already back to plain ASCII ("text", restored while typing)
+    /// counts as restored. Also keeps the English context for a capital "i".
+    fn track_english_streak(&mut self, restored: bool) {
+        let transformed = self.had_any_transform || self.word_transformed;
+        // Chat abbreviations say nothing about the language either
+        if self.buf.is_empty() || !transformed || self.is_teencode() {
+            return;
+        }
+        let english = restored || self.buf.to_full_string().is_ascii();
+        self.english_context = english;
+        if self.auto_english_words == 0 {
+            return;
+        }
+        if !english {
+            self.english_streak = 0;
+            return;
+        }
+        self.english_streak = self.english_streak.saturating_add(1);
+        if self.english_streak >= self.auto_english_words {
+            log_debug!("engine", "auto language switch: suspend Vietnamese");
+            self.english_streak = 0;
+            self.english_suspended = true;
+        }
+    }
+
+    /// Key handling behind `on_key_ext` (which adds tracing and stats)
+    fn handle_key(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
         // Issue #129: Process shortcuts even when IME is disabled
         // Only bypass completely for Ctrl/Cmd modifier keys
+        if ctrl && key == keys::DELETE {
+            self.delete_word();
+            return Result::none();
+        }
         if ctrl {
             self.clear();
             self.word_history.clear();
@@ -657,13 +2803,22 @@ impl Engine {
                         true, // is_word_boundary = true for word shortcuts
                         input_method,
                     ) {
+                        self.record_expansion(&m);
                         let output: Vec<char> = m.output.chars().collect();
                         let backspace_count = m.backspace_count as u8;
+                        let mut replaced = tail_chars(&self.shortcut_prefix, m.backspace_count);
                         self.shortcut_prefix.clear();
                         // For Space, include space in output; for Enter, don't
                         if key == keys::SPACE {
                             let mut output_with_space = output;
                             output_with_space.push(' ');
+                            self.cursor_back = self.cursor_back.map(|n| n + 1);
+                            replaced.push(' ');
+                            self.last_auto = Some(AutoUndo::new(
+                                AutoAction::Shortcut,
+                                replaced,
+                                output_with_space.clone(),
+                            ));
                             return Result::send(backspace_count, &output_with_space);
                         } else {
                             return Result::send(backspace_count, &output);
@@ -680,13 +2835,16 @@ impl Engine {
                     self.shortcut_prefix.push(ch);
 
                     let input_method = self.current_input_method();
-                    if let Some(m) = self.shortcuts.try_match_for_method(
-                        &self.shortcut_prefix,
-                        None,
-                        false,
-                        input_method,
-                    ) {
+                    if let Some(m) =
+                        self.match_immediate_shortcut(&self.shortcut_prefix, input_method)
+                    {
+                        self.record_expansion(&m);
                         let output: Vec<char> = m.output.chars().collect();
+                        self.last_auto = Some(AutoUndo::new(
+                            AutoAction::Shortcut,
+                            tail_chars(&self.shortcut_prefix, m.backspace_count),
+                            output.clone(),
+                        ));
                         let backspace_count = (m.backspace_count as u8).saturating_sub(1);
                         self.shortcut_prefix.clear();
                         return Result::send_consumed(backspace_count, &output);
@@ -712,6 +2870,10 @@ impl Engine {
         // Check for word boundary shortcuts ONLY on SPACE
         // Also auto-restore invalid Vietnamese to raw English
         if key == keys::SPACE {
+            if !self.buf.is_empty() {
+                self.stats.record_word();
+            }
+
             // Handle pending mark revert pop on space (end of word)
             // When telex_double_raw is set, we use it directly for restore, no pop needed.
             // The telex_double_raw contains the exact original input before any modification.
@@ -730,18 +2892,41 @@ impl Engine {
             let shortcut_result = self.try_word_boundary_shortcut();
             if shortcut_result.action != 0 {
                 self.clear();
+                self.name_context.clear();
                 return shortcut_result;
             }
+            self.queue_word_hook(' ');
+
+            // Spelling auto-fix replaces the word instead of restoring it
+            let spelling_fix = self.try_spelling_fix(Some(' '));
 
             // Auto-restore: if buffer has transforms but is invalid Vietnamese,
             // restore to raw English (like ESC but triggered by space)
-            let restore_result = self.try_auto_restore_on_space();
+            let restore_result = if spelling_fix.is_none() && self.is_auto_restore_trigger(key) {
+                self.try_auto_restore_on_space()
+            } else {
+                Result::none()
+            };
+            self.track_english_streak(restore_result.action != 0);
+            let case_fix = if spelling_fix.is_none() && restore_result.action == 0 {
+                self.try_proper_noun(Some(' '))
+                    .or_else(|| self.try_capital_i_in_buffer(Some(' ')))
+            } else {
+                None
+            };
 
             // If auto-restore happened, repopulate buffer with plain chars from raw_input
             // This ensures word_history stores the correct restored word (not transformed)
             // Example: "restore" → buffer was "rếtore" (6 chars), raw_input has 7 keys
             // After this, buffer has "restore" (7 chars) for correct history
             if restore_result.action != 0 {
+                let mut replaced: Vec<char> = self.buf.to_full_string().chars().collect();
+                replaced.push(' ');
+                self.last_auto = Some(AutoUndo::restore(
+                    self,
+                    replaced,
+                    result_chars(&restore_result),
+                ));
                 self.buf.clear();
                 for &(key, caps, _) in &self.raw_input {
                     self.buf.push(Char::new(key, caps));
@@ -750,6 +2935,10 @@ impl Engine {
 
             // Push buffer to history before clearing (for backspace-after-space feature)
             if !self.buf.is_empty() {
+                self.check_repeated_word(&self.buf.to_full_string());
+                self.detect_method();
+                self.detect_tone_style();
+                self.push_name_context();
                 self.word_history.push(self.buf.clone());
                 self.spaces_after_commit = 1; // First space after word
             } else if self.spaces_after_commit > 0 {
@@ -766,17 +2955,22 @@ impl Engine {
             }
 
             self.clear();
-            return restore_result;
+            return spelling_fix.or(case_fix).unwrap_or(restore_result);
         }
 
-        // ESC key: restore to raw ASCII (undo all Vietnamese transforms)
-        // Only if esc_restore is enabled by user
-        if key == keys::ESC {
-            let result = if self.esc_restore_enabled {
+        // Restore key (ESC by default): restore to raw ASCII (undo all Vietnamese
+        // transforms). Only if esc_restore is enabled by user. ESC always ends the word.
+        let is_restore_key = self.esc_restore_enabled && key == self.restore_key;
+        if key == keys::ESC || is_restore_key {
+            let result = if is_restore_key {
                 self.restore_to_raw()
             } else {
                 Result::none()
             };
+            // Restoring a word auto-restore kept is a vote for English
+            if result.action == Action::Restore as u8 && self.english_auto_restore {
+                self.learning.record_restore(&self.raw_chars());
+            }
             self.clear();
             self.word_history.clear();
             self.spaces_after_commit = 0;
@@ -785,7 +2979,7 @@ impl Engine {
 
         // Issue #159: In Telex mode, `]` → ư and `[` → ơ
         // caps affects revert: ]] → ], uppercase (Shift/CapsLock) → }
-        if self.method == 0 && (key == keys::RBRACKET || key == keys::LBRACKET) {
+        if self.telex_letters() && (key == keys::RBRACKET || key == keys::LBRACKET) {
             if let Some(result) = self.try_bracket_as_vowel(key, caps) {
                 return result;
             }
@@ -795,6 +2989,10 @@ impl Engine {
         // Also trigger auto-restore for invalid Vietnamese before clearing
         // Use is_break_ext to handle shifted symbols like @, !, #, etc.
         if keys::is_break_ext(key, shift) {
+            if !self.buf.is_empty() {
+                self.stats.record_word();
+            }
+
             // Issue #107 + Bug #11: When buffer is empty AND we're at true start of input
             // (no word history), accumulate break chars for shortcuts.
             // This allows shortcuts like "#fne", "->", "=>" to work.
@@ -819,48 +3017,32 @@ impl Engine {
 
                     // Check for immediate shortcut match
                     let input_method = self.current_input_method();
-                    if let Some(m) = self.shortcuts.try_match_for_method(
-                        &self.shortcut_prefix,
-                        None,
-                        false,
-                        input_method,
-                    ) {
+                    if let Some(m) =
+                        self.match_immediate_shortcut(&self.shortcut_prefix, input_method)
+                    {
                         // Found a match! Send the replacement with key_consumed flag
                         // Note: backspace_count - 1 because current key hasn't been typed yet
                         // Example: "->" trigger has backspace_count=2, but only '-' is on screen
+                        self.record_expansion(&m);
                         let output: Vec<char> = m.output.chars().collect();
+                        self.last_auto = Some(AutoUndo::new(
+                            AutoAction::Shortcut,
+                            tail_chars(&self.shortcut_prefix, m.backspace_count),
+                            output.clone(),
+                        ));
                         let backspace_count = (m.backspace_count as u8).saturating_sub(1);
                         self.shortcut_prefix.clear();
                         return Result::send_consumed(backspace_count, &output);
                     }
 
                     // Issue #185: Only set saw_sentence_ending for punctuation (not Enter)
-                    // pending_capitalize will be set when space follows
-                    if self.auto_capitalize && is_sentence_ending_punctuation(key, shift) {
-                        self.saw_sentence_ending = true;
-                    } else if self.auto_capitalize && (key == keys::RETURN || key == keys::ENTER) {
-                        // Enter = newline = immediate capitalize (no space needed)
-                        self.pending_capitalize = true;
-                        self.saw_sentence_ending = false;
-                    }
-                    return Result::none(); // Let the char pass through, keep accumulating
-                }
-            }
-
-            // Issue #185: Only set saw_sentence_ending for punctuation (not Enter)
-            // pending_capitalize will be set when space follows
-            if self.auto_capitalize && is_sentence_ending_punctuation(key, shift) {
-                self.saw_sentence_ending = true;
-            } else if self.auto_capitalize && (key == keys::RETURN || key == keys::ENTER) {
-                // Enter = newline = immediate capitalize (no space needed)
-                self.pending_capitalize = true;
-                self.saw_sentence_ending = false;
-            } else if self.auto_capitalize && should_reset_pending_capitalize(key, shift) {
-                // Reset pending for word-breaking keys (comma, semicolon, etc.)
-                // But preserve pending for neutral keys (quotes, parentheses, brackets)
-                self.pending_capitalize = false;
-                self.saw_sentence_ending = false;
+                    // pending_capitalize will be set when space follows
+                    self.update_pending_capitalize(key, shift);
+                    return Result::none(); // Let the char pass through, keep accumulating
+                }
             }
+
+            self.update_pending_capitalize(key, shift);
             self.auto_capitalize_used = false; // Reset on word boundary
 
             // Issue #167: Check for word boundary shortcuts on punctuation and ENTER
@@ -879,9 +3061,60 @@ impl Engine {
                     self.spaces_after_commit = 0;
                     return shortcut_result;
                 }
+                self.queue_word_hook(ch);
+            }
+
+            let spelling_fix = self.try_spelling_fix(None);
+            let restore_result = if spelling_fix.is_none() && self.is_auto_restore_trigger(key) {
+                self.try_auto_restore_on_break()
+            } else {
+                Result::none()
+            };
+            self.track_english_streak(restore_result.action != 0);
+            let case_fix = if spelling_fix.is_none() && restore_result.action == 0 {
+                self.try_proper_noun(None)
+                    .or_else(|| self.try_capital_i_in_buffer(None))
+            } else {
+                None
+            };
+            self.name_context.clear();
+            if !self.buf.is_empty() {
+                let word: String = if restore_result.action != 0 {
+                    self.raw_chars().into_iter().collect()
+                } else {
+                    self.buf.to_full_string()
+                };
+                self.check_repeated_word(&word);
+                self.detect_method();
+                self.detect_tone_style();
+            }
+            if restore_result.action != 0 {
+                // Host types the break char after the restored word
+                if let Some(ch) = break_key_to_char(key, shift) {
+                    let mut replaced: Vec<char> = self.buf.to_full_string().chars().collect();
+                    let mut inserted = result_chars(&restore_result);
+                    replaced.push(ch);
+                    inserted.push(ch);
+                    self.last_auto = Some(AutoUndo::restore(self, replaced, inserted));
+                }
+            }
+
+            // Soft hyphen: keep the segment in history like Space does, so
+            // Backspace over '-' restores it (see backspace-after-space below)
+            if self.hyphen_soft_boundary && key == keys::MINUS && !shift && !self.buf.is_empty() {
+                if restore_result.action != 0 {
+                    self.buf.clear();
+                    for &(key, caps, _) in &self.raw_input {
+                        self.buf.push(Char::new(key, caps));
+                    }
+                }
+                self.word_history.push(self.buf.clone());
+                self.clear();
+                self.spaces_after_commit = 1;
+                self.shortcut_prefix.push('-');
+                return spelling_fix.or(case_fix).unwrap_or(restore_result);
             }
 
-            let restore_result = self.try_auto_restore_on_break();
             self.clear();
             self.word_history.clear();
             self.spaces_after_commit = 0;
@@ -893,7 +3126,7 @@ impl Engine {
                 self.shortcut_prefix.push(ch);
             }
 
-            return restore_result;
+            return spelling_fix.or(case_fix).unwrap_or(restore_result);
         }
 
         if key == keys::DELETE {
@@ -922,8 +3155,11 @@ impl Engine {
             // If buffer is already empty, user is deleting content from previous word
             // that we don't track. Mark this to prevent false shortcut matches.
             // e.g., "đa" + SPACE + backspace×2 + "a" should NOT match shortcut "a"
+            // The deleted char may end a shortcut prefix: "-" + backspace + ">"
+            // is not "->"
             if self.buf.is_empty() {
                 self.has_non_letter_prefix = true;
+                self.shortcut_prefix.pop();
             }
 
             // Issue: When deleting a char with mark/tone, we need to pop both the base char
@@ -985,7 +3221,13 @@ impl Engine {
             if self.buf.is_empty() {
                 self.raw_input.clear();
             }
-            self.last_transform = None;
+            // The transform stays revertible while the character carrying it
+            // is still there: "trăng" ⌫ "w" → "tranw", as "trăn" + "w" right
+            // after the transform would. Deleting that character drops it.
+            if !self.last_transform_kept() {
+                self.last_transform = None;
+            }
+            self.last_revert = None;
             // Reset stroke_reverted on backspace so user can re-trigger stroke
             // e.g., "ddddd" → "dddd", then backspace×3 → "d", then "d" → "đ"
             self.stroke_reverted = false;
@@ -1020,8 +3262,10 @@ impl Engine {
         // unless they're mark/tone keys (allow "ban" + restore + "s" → "bán")
         if self.restored_pending_clear && keys::is_letter(key) {
             let m = input::get(self.method);
-            let is_modifier =
-                m.mark(key).is_some() || m.tone(key).is_some() || m.remove(key) || m.stroke(key);
+            let is_modifier = m.mark(key).is_some()
+                || m.tone(key).is_some()
+                || self.is_remove_key(key)
+                || m.stroke(key);
             // Clear buffer when letter is NOT a modifier (mark/tone/remove):
             // - Vietnamese restored: clear on consonant (vowels may add diacritics)
             // - ASCII restored: clear on any non-modifier letter (consonant OR vowel)
@@ -1047,8 +3291,17 @@ impl Engine {
             self.has_non_letter_prefix = false;
         }
 
+        // Title Case: first letter of a word, unless inside a token ("don't")
+        let title_start = self.title_case
+            && keys::is_letter(key)
+            && self.buf.is_empty()
+            && self
+                .last_token_char
+                .is_none_or(|c| !c.is_alphanumeric() && c != '\'' && c != '.');
+
         // Auto-capitalize: force uppercase for first letter after sentence-ending punctuation
-        let was_auto_capitalized = self.pending_capitalize && keys::is_letter(key) && !caps;
+        let was_auto_capitalized =
+            (self.pending_capitalize || title_start) && keys::is_letter(key) && !caps;
         let effective_caps = if self.pending_capitalize && keys::is_letter(key) {
             self.pending_capitalize = false;
             self.saw_sentence_ending = false; // Reset after capitalizing
@@ -1062,13 +3315,33 @@ impl Engine {
                 self.auto_capitalize_used = false; // Number after punctuation, reset
             }
             // Issue #185: Reset saw_sentence_ending when letter is typed without space
-            // e.g., "google.com" - 'c' typed after '.' without space, don't capitalize
-            if self.saw_sentence_ending && keys::is_letter(key) {
+            // e.g., "google.com" - 'c' typed after '.' without space, don't capitalize.
+            // Same for a digit: "1.5 kg" is one sentence
+            if self.saw_sentence_ending && (keys::is_letter(key) || keys::is_number(key)) {
                 self.saw_sentence_ending = false;
             }
-            caps
+            caps || title_start
         };
 
+        // Telex digit as word boundary: the letters before it are left as
+        // typed and the letters after it start a fresh word ("q1nhaf" → "q1nhà")
+        if self.digit_policy == DigitPolicy::Boundary
+            && self.method == 0
+            && keys::is_number(key)
+            && !shift
+        {
+            if !self.buf.is_empty() {
+                self.stats.record_word();
+            }
+            self.auto_capitalize_used = false; // Keep clear() from re-arming it
+            self.clear();
+            self.word_history.clear();
+            self.spaces_after_commit = 0;
+            // Letters after the digit are not a shortcut trigger ("a1ko")
+            self.has_non_letter_prefix = true;
+            return Result::none();
+        }
+
         // Record raw keystroke for ESC restore (letters and numbers only)
         if keys::is_letter(key) || keys::is_number(key) {
             self.raw_input.push((key, effective_caps, shift));
@@ -1080,6 +3353,10 @@ impl Engine {
         // we need to send the uppercase character since the original key was lowercase
         if was_auto_capitalized && result.action == Action::None as u8 && self.buf.len() == 1 {
             if let Some(ch) = crate::utils::key_to_char(key, true) {
+                if let Some(lower) = crate::utils::key_to_char(key, false) {
+                    self.last_auto =
+                        Some(AutoUndo::new(AutoAction::Capitalize, vec![lower], vec![ch]));
+                }
                 return Result::send(0, &[ch]);
             }
         }
@@ -1091,6 +3368,10 @@ impl Engine {
     fn process(&mut self, key: u16, caps: bool, shift: bool) -> Result {
         let m = input::get(self.method);
 
+        if let Some(result) = self.try_cycle_revert(key) {
+            return result;
+        }
+
         // Handle pending mark revert pop: if previous key was a mark revert,
         // reset the flag. When telex_double_raw is set, we use it directly for
         // restore, so no need to modify raw_input here.
@@ -1267,7 +3548,7 @@ impl Engine {
 
         // In VNI mode, if Shift is pressed with a number key, skip all modifiers
         // User wants the symbol (@ for Shift+2, # for Shift+3, etc.), not VNI marks
-        let skip_vni_modifiers = self.method == 1 && shift && keys::is_number(key);
+        let skip_vni_modifiers = self.vni_digits() && shift && keys::is_number(key);
 
         // Skip modifiers after circumflex revert (ooo→oo, eee→ee, aaa→aa)
         // Example: "booo" → "boo" (revert), then "s" → "boos" (not "boós")
@@ -1284,8 +3565,10 @@ impl Engine {
         // 1. Stroke modifier (d → đ)
         if !skip_vni_modifiers && m.stroke(key) {
             if let Some(result) = self.try_stroke(key, caps) {
+                reason!(self, "key {}: stroke đ", key);
                 return result;
             }
+            reason!(self, "key {}: stroke not applied", key);
         }
 
         // 2. Tone modifier (circumflex, horn, breve)
@@ -1293,8 +3576,10 @@ impl Engine {
             if let Some(tone_type) = m.tone(key) {
                 let targets = m.tone_targets(key);
                 if let Some(result) = self.try_tone(key, caps, tone_type, targets) {
+                    reason!(self, "key {}: tone {:?} on {:?}", key, tone_type, targets);
                     return result;
                 }
+                reason!(self, "key {}: tone {:?} not applied", key, tone_type);
             }
         }
 
@@ -1304,13 +3589,16 @@ impl Engine {
                 if let Some(result) = self.try_mark(key, caps, mark_val) {
                     return result;
                 }
+                reason!(self, "key {}: mark {} not applied", key, mark_val);
             }
+        } else if skip_after_revert && m.mark(key).is_some() {
+            reason!(self, "key {}: modifiers off after circumflex revert", key);
         }
 
         // 4. Remove modifier
         // Only consume key if there's something to remove; otherwise fall through to normal letter
         // This allows shortcuts like "zz" to work when buffer has no marks/tones to remove
-        if !skip_vni_modifiers && m.remove(key) {
+        if !skip_vni_modifiers && self.is_remove_key(key) {
             if let Some(result) = self.try_remove() {
                 return result;
             }
@@ -1318,7 +3606,7 @@ impl Engine {
 
         // 5. In Telex: "w" as vowel "ư" when valid Vietnamese context
         // Examples: "w" → "ư", "nhw" → "như", but "kw" → "kw" (invalid)
-        if self.method == 0 && key == keys::W {
+        if self.telex_letters() && key == keys::W {
             if let Some(result) = self.try_w_as_vowel(caps) {
                 return result;
             }
@@ -1365,7 +3653,25 @@ impl Engine {
             self.shortcuts
                 .try_match_for_method(&full_trigger, key_char, true, input_method)
         {
+            log_debug!(
+                "shortcut",
+                "expand trigger=\"{}\" output=\"{}\"",
+                full_trigger,
+                m.output
+            );
+            self.record_expansion(&m);
             let output: Vec<char> = m.output.chars().collect();
+            // Space is already in the output; punctuation is typed by the host after it.
+            // Enter is not undoable (the line break is not ours to remove).
+            let mut replaced = tail_chars(&full_trigger, m.backspace_count);
+            let mut inserted = output.clone();
+            if trigger_char != '\n' {
+                replaced.push(trigger_char);
+                if key_char.is_none() {
+                    inserted.push(trigger_char);
+                }
+                self.last_auto = Some(AutoUndo::new(AutoAction::Shortcut, replaced, inserted));
+            }
             // backspace_count = trigger.len() which already includes prefix (e.g., "#fne" = 4)
             return Result::send(m.backspace_count as u8, &output);
         }
@@ -1385,7 +3691,7 @@ impl Engine {
     /// - "nhw" → "như" (valid consonant + ư)
     /// - "kw" → "kw" (invalid, k cannot precede ư)
     /// - "ww" → revert to "w" (shortcut skipped)
-    /// - "www" → "ww" (subsequent w just adds normally)
+    /// - "www" → "www" (the w consumed by the revert comes back)
     fn try_w_as_vowel(&mut self, caps: bool) -> Option<Result> {
         // Issue #44: If breve is pending (deferred due to open syllable),
         // don't convert w→ư. Let w be added as regular letter.
@@ -1394,14 +3700,39 @@ impl Engine {
             return None;
         }
 
-        // If user disabled w→ư shortcut, skip w→ư conversion entirely
+        // If user disabled w→ư shortcut (or uses Simple Telex), skip w→ư
+        // conversion entirely
         // Horn modifier (try_tone) still works: "ow" → "ơ", "uw" → "ư"
-        if self.skip_w_shortcut {
+        if self.skip_w_shortcut || self.simple_telex {
+            return None;
+        }
+
+        // Word-initial w stays a letter ("web", "www"); after a consonant
+        // the syllable needs a vowel, so it is still ư ("nhw" → "như")
+        if self.initial_w_literal && self.buf.iter().all(|c| c.key == keys::W) {
             return None;
         }
 
         // If shortcut was previously skipped, don't try again
         if matches!(self.last_transform, Some(Transform::WShortcutSkipped)) {
+            // "www": the second w only undid ư, so its letter comes back
+            if self.buf.len() == 1 && self.raw_input.len() == 2 {
+                // The revert kept the second w in raw_input and the first in
+                // the buffer; put the first back into raw_input
+                let first_caps = self.buf.get(0).is_some_and(|c| c.caps);
+                let consumed_caps = self.raw_input[0].1;
+                self.raw_input.insert(0, (keys::W, first_caps, false));
+                self.buf.push(Char::new(keys::W, consumed_caps));
+                self.buf.push(Char::new(keys::W, caps));
+                // The word is plain "www" again: nothing for auto-restore
+                self.telex_double_raw = None;
+                self.telex_double_raw_len = 0;
+                self.had_telex_transform = false;
+                self.had_any_transform = false;
+                self.last_transform = None;
+                let w = |caps: bool| if caps { 'W' } else { 'w' };
+                return Some(Result::send(0, &[w(consumed_caps), w(caps)]));
+            }
             return None;
         }
 
@@ -1442,6 +3773,7 @@ impl Engine {
         }
 
         // Try adding U (ư base) to buffer and validate
+        let caps = self.inserted_vowel_caps(caps);
         self.buf.push(Char::new(keys::U, caps));
 
         // Set horn tone to make it ư
@@ -1478,16 +3810,17 @@ impl Engine {
     /// In VNI mode, '9' is always an intentional stroke command (not a letter), so
     /// delayed stroke is allowed (e.g., "duong9" → "đuong").
     fn try_stroke(&mut self, key: u16, caps: bool) -> Option<Result> {
-        // If stroke was already reverted in this word (ddd → dd), skip further stroke attempts
-        // This prevents "ddddd" from oscillating and ensures subsequent 'd's are just letters
-        if self.stroke_reverted && key == keys::D {
+        // If stroke was already reverted in this word (ddd → dd, d99 → d9), skip further
+        // stroke attempts. This prevents "ddddd" from oscillating and ensures subsequent
+        // 'd's (or '9's in VNI) are just letters
+        if self.stroke_reverted && (key == keys::D || input::get(self.method).stroke(key)) {
             return None;
         }
 
         // Check for stroke revert first: ddd → dd
         // If last transform was stroke and same key pressed again, revert the stroke
         if let Some(Transform::Stroke(last_key)) = self.last_transform {
-            if last_key == key {
+            if input::get(self.method).reverts(last_key, key) {
                 // Find the stroked 'd' to revert
                 if let Some(pos) = self.buf.iter().position(|c| c.key == keys::D && c.stroke) {
                     // Revert: un-stroke the 'd'
@@ -1496,6 +3829,7 @@ impl Engine {
                     }
                     // Add another 'd' as normal char (preserve caps state)
                     self.buf.push(Char::new(key, caps));
+                    self.last_revert = self.last_transform.map(|t| (t, pos, self.buf.len()));
                     self.last_transform = None;
                     // Mark that stroke was reverted - subsequent 'd' keys will be normal letters
                     self.stroke_reverted = true;
@@ -1538,6 +3872,7 @@ impl Engine {
                     }
                     // Add another 'd' as normal char (preserve caps state)
                     self.buf.push(Char::new(key, caps));
+                    self.last_revert = self.last_transform.map(|t| (t, pos, self.buf.len()));
                     self.last_transform = None;
                     // Mark that stroke was reverted - subsequent 'd' keys will be normal letters
                     self.stroke_reverted = true;
@@ -1614,7 +3949,7 @@ impl Engine {
 
         // Find position of un-stroked 'd' to apply stroke
         // Also track if this is a short pattern stroke (revertible)
-        let (pos, is_short_pattern_stroke) = if self.method == 0 {
+        let (pos, is_short_pattern_stroke) = if key == keys::D {
             // Telex: First try adjacent 'd' (last char is un-stroked d)
             let last_pos = self.buf.len().checked_sub(1)?;
             let last_char = self.buf.get(last_pos)?;
@@ -1641,7 +3976,12 @@ impl Engine {
                 // BUT: Allow circumflex trigger patterns even if they look invalid now
                 // ALSO: Allow Vietnamese triple-o words (đoòng) which have literal double-o
                 if !has_circumflex_trigger_pattern
-                    && !is_valid_with_foreign(&buffer_keys, self.allow_foreign_consonants)
+                    && !is_valid_with_foreign(
+                        &buffer_keys,
+                        self.allow_foreign_consonants,
+                        self.southern_initials(),
+                        self.strictness,
+                    )
                     && !self.is_vietnamese_triple_o_word()
                 {
                     return None;
@@ -1694,7 +4034,7 @@ impl Engine {
 
         // Check revert: if last transform was stroke on same key at same position
         if let Some(Transform::Stroke(last_key)) = self.last_transform {
-            if last_key == key {
+            if input::get(self.method).reverts(last_key, key) {
                 return Some(self.revert_stroke(key, pos));
             }
         }
@@ -1702,14 +4042,19 @@ impl Engine {
         // Validate buffer structure before applying stroke
         // Only validate if buffer has vowels (complete syllable)
         // Allow stroke on initial consonant before vowel is typed (e.g., "dd" → "đ" then "đi")
-        // Skip validation if free_tone mode is enabled
+        // Skip validation if spell check is off (or free tone)
         // Also skip validation for circumflex trigger patterns (duoto → đuôt)
         // Also skip validation for Vietnamese triple-o words (đoòng) which have literal double-o
-        if !self.free_tone_enabled
+        if self.checks_spelling()
             && has_vowel
             && !has_circumflex_trigger_pattern
             && !self.is_vietnamese_triple_o_word()
-            && !is_valid_for_transform_with_foreign(&buffer_keys, self.allow_foreign_consonants)
+            && !is_valid_for_transform_with_foreign(
+                &buffer_keys,
+                self.allow_foreign_consonants,
+                self.southern_initials(),
+                self.strictness,
+            )
         {
             return None;
         }
@@ -1755,8 +4100,14 @@ impl Engine {
         }
 
         // Check revert first (same key pressed twice)
-        if let Some(Transform::Tone(last_key, _)) = self.last_transform {
-            if last_key == key {
+        if let Some(Transform::Tone(last_key, last_tone)) = self.last_transform {
+            let vowel = self
+                .buf
+                .iter()
+                .rev()
+                .find(|c| c.tone == last_tone)
+                .map_or(0, |c| c.key);
+            if input::get(self.method).reverts_tone(last_key, key, vowel) {
                 return Some(self.revert_tone(key, caps));
             }
         }
@@ -1768,11 +4119,16 @@ impl Engine {
         }
 
         // Validate buffer structure (not vowel patterns - those are checked after transform)
-        // Skip validation if free_tone mode is enabled
+        // Skip validation if spell check is off (or free tone)
         let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
 
-        if !self.free_tone_enabled
-            && !is_valid_for_transform_with_foreign(&buffer_keys, self.allow_foreign_consonants)
+        if self.checks_spelling()
+            && !is_valid_for_transform_with_foreign(
+                &buffer_keys,
+                self.allow_foreign_consonants,
+                self.southern_initials(),
+                self.strictness,
+            )
         {
             return None;
         }
@@ -1780,7 +4136,7 @@ impl Engine {
         // Check for invalid "-ing" rhyme: Vietnamese uses "-inh", NOT "-ing" with tone
         // Examples: "thíng" is invalid (things), but "tính" is valid
         // If vowel is 'i' and final is 'ng', reject tone marks
-        if !self.free_tone_enabled {
+        if self.checks_spelling() {
             let syllable = syllable::parse(&buffer_keys);
             if syllable.vowel.len() == 1 && syllable.final_c.len() == 2 {
                 let vowel_key = buffer_keys[syllable.vowel[0]];
@@ -1881,7 +4237,7 @@ impl Engine {
                 // 2. No consonants between target and end (delayed diphthong: "oio" → "ôi")
                 // This prevents transformation in words like "teacher" where consonants
                 // (c, h) appear between the two 'e's
-                let is_telex_circumflex = self.method == 0
+                let is_telex_circumflex = self.telex_letters()
                     && tone_type == ToneType::Circumflex
                     && matches!(key, keys::A | keys::E | keys::O);
 
@@ -2599,7 +4955,7 @@ impl Engine {
             // Only restore if buffer has EARLIER transforms (tone or mark)
             // Don't restore for simple "aw" or "raw" - let breve deferral handle those
             // Only run if english_auto_restore is enabled (experimental feature)
-            if self.english_auto_restore && key == keys::W && self.raw_input.len() >= 2 {
+            if self.restores_mid_word() && key == keys::W && self.raw_input.len() >= 2 {
                 let (prev_key, _, _) = self.raw_input[self.raw_input.len() - 2];
                 if prev_key == keys::A {
                     // Check if there are earlier Vietnamese transforms in buffer
@@ -2620,7 +4976,7 @@ impl Engine {
                         let raw_chars: Vec<char> = self
                             .raw_input
                             .iter()
-                            .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
+                            .filter_map(|&(k, c, s)| raw_key_char(k, c, s))
                             .collect();
                         let backspace = self.buf.len() as u8;
                         self.buf.clear();
@@ -2706,7 +5062,7 @@ impl Engine {
 
         // Check revert first
         if let Some(Transform::Mark(last_key, _)) = self.last_transform {
-            if last_key == key {
+            if input::get(self.method).reverts(last_key, key) {
                 return Some(self.revert_mark(key, caps));
             }
         }
@@ -2714,7 +5070,7 @@ impl Engine {
         // Telex: Check for delayed stroke pattern (d + vowels + d)
         // When buffer is "dod" and mark key is typed, apply stroke to initial 'd'
         // This enables "dods" → "đó" while preventing "de" + "d" → "đe"
-        let had_delayed_stroke = self.method == 0
+        let had_delayed_stroke = self.telex_letters()
             && self.buf.len() >= 2
             && self
                 .buf
@@ -2775,7 +5131,7 @@ impl Engine {
         // This enables "totos" → "tốt" while preventing "data" → "dât"
         // Pattern: C₁ + V + C₂ + V where V is same vowel (a, e, o)
         let mut had_delayed_circumflex = false;
-        if self.method == 0 && self.buf.len() >= 3 {
+        if self.telex_letters() && self.buf.len() >= 3 {
             // Get vowel positions
             let vowel_positions: Vec<(usize, u16)> = self
                 .buf
@@ -2962,21 +5318,38 @@ impl Engine {
         let has_stroke_transforms = self.buf.iter().any(|c| c.stroke);
 
         // Validate buffer structure (skip if has horn/stroke transforms - already intentional Vietnamese)
-        // Also skip validation if free_tone mode is enabled
+        // Strict validates those too, so final k is refused in "đák" and "đắk" as in "ták"
+        // Also skip validation if spell check is off (or free tone)
         let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
         let buffer_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
-        if !self.free_tone_enabled
-            && !has_horn_transforms
-            && !has_stroke_transforms
-            && !is_valid_for_transform_with_foreign(&buffer_keys, self.allow_foreign_consonants)
+        if self.checks_spelling()
+            && (self.strictness == Strictness::Strict
+                || (!has_horn_transforms && !has_stroke_transforms))
+            && !is_valid_for_transform_with_foreign(
+                &buffer_keys,
+                self.allow_foreign_consonants,
+                self.southern_initials(),
+                self.strictness,
+            )
         {
+            reason!(
+                self,
+                "mark {}: validation failed {:?}",
+                mark_val,
+                validation::transform_rule_failed(
+                    &buffer_keys,
+                    self.allow_foreign_consonants,
+                    self.southern_initials(),
+                    self.strictness,
+                )
+            );
             return None;
         }
 
         // Check for invalid "-ing" rhyme: Vietnamese uses "-inh", NOT "-ing" with tone marks
         // Examples: "thíng" is invalid (things), but "tính" is valid
         // If vowel is 'i' and final is 'ng', reject marks
-        if !self.free_tone_enabled && !has_horn_transforms && !has_stroke_transforms {
+        if self.checks_spelling() && !has_horn_transforms && !has_stroke_transforms {
             let syllable = syllable::parse(&buffer_keys);
             if syllable.vowel.len() == 1 && syllable.final_c.len() == 2 {
                 let vowel_key = buffer_keys[syllable.vowel[0]];
@@ -2986,11 +5359,18 @@ impl Engine {
                 ];
                 // i + ng = invalid Vietnamese rhyme for tone/mark
                 if vowel_key == keys::I && final_keys == [keys::N, keys::G] {
+                    reason!(self, "mark {}: -ing rhyme takes no mark", mark_val);
                     return None;
                 }
             }
         }
 
+        // Stop finals (p, t, c, ch) only take sắc or nặng: "hocf" stays as typed
+        if self.checks_spelling() && !mark_allowed(&buffer_keys, mark_val) {
+            reason!(self, "mark {}: stop final takes only sắc or nặng", mark_val);
+            return None;
+        }
+
         // Skip modifier if buffer shows foreign word patterns.
         // Only check when NO horn/stroke transforms exist.
         //
@@ -3003,12 +5383,13 @@ impl Engine {
         // - "you" + 'r' → "ou" vowel pattern invalid → skip modifier
         // - "rươu" + 'j' → has horn transforms → DON'T skip, apply mark normally
         // - "đe" + 's' → has stroke transform → DON'T skip, apply mark normally (Issue #48)
-        // Skip foreign word detection if free_tone mode is enabled
-        if !self.free_tone_enabled
+        // Skip foreign word detection if spell check is off (or free tone)
+        if self.checks_spelling()
             && !has_horn_transforms
             && !has_stroke_transforms
             && is_foreign_word_pattern(&buffer_keys, &buffer_tones, key)
         {
+            reason!(self, "mark {}: foreign word pattern", mark_val);
             return None;
         }
 
@@ -3026,8 +5407,25 @@ impl Engine {
         let has_final = self.has_final_consonant(last_vowel_pos);
         let has_qu = self.has_qu_initial();
         let has_gi = self.has_gi_initial();
-        let pos =
-            Phonology::find_tone_position(&vowels, has_final, self.modern_tone, has_qu, has_gi);
+        let pos = Phonology::find_tone_position(
+            &vowels,
+            has_final,
+            self.tone_style.is_new(),
+            has_qu,
+            has_gi,
+        );
+        reason!(
+            self,
+            "mark {}: on position {} of {:?} (vowels at {:?}, final={}, modern={}, qu={}, gi={})",
+            mark_val,
+            pos,
+            self.buf.to_full_string(),
+            vowels.iter().map(|v| v.pos).collect::<Vec<_>>(),
+            has_final,
+            self.tone_style.is_new(),
+            has_qu,
+            has_gi
+        );
 
         // Check if target vowel already has the same mark
         // This handles two cases:
@@ -3397,10 +5795,22 @@ impl Engine {
                 return None;
             }
 
-            let new_pos =
-                Phonology::find_tone_position(&vowels, has_final, self.modern_tone, has_qu, has_gi);
+            let new_pos = Phonology::find_tone_position(
+                &vowels,
+                has_final,
+                self.tone_style.is_new(),
+                has_qu,
+                has_gi,
+            );
 
             if new_pos != old_pos {
+                reason!(
+                    self,
+                    "mark moved from position {} to {} (final={})",
+                    old_pos,
+                    new_pos,
+                    has_final
+                );
                 // Move tone from old position to new position
                 if let Some(c) = self.buf.get_mut(old_pos) {
                     c.mark = mark::NONE;
@@ -3650,6 +6060,13 @@ impl Engine {
 
     /// Revert tone transformation
     fn revert_tone(&mut self, key: u16, caps: bool) -> Result {
+        reason!(self, "key {}: reverts the tone", key);
+        log_debug!(
+            "engine",
+            "revert kind=tone key={} buf=\"{}\"",
+            key,
+            self.buf.to_full_string()
+        );
         self.last_transform = None;
         // Issue #211: Track which vowel triggered revert for extended vowel mode
         // After revert, subsequent same-key vowels append raw instead of re-transforming
@@ -3663,6 +6080,7 @@ impl Engine {
                     if c.tone == tone::CIRCUMFLEX {
                         self.had_circumflex_revert = true;
                     }
+                    let reverted = Transform::Tone(key, c.tone);
                     c.tone = tone::NONE;
                     // Track for auto-restore logic (double ss/ff detection)
                     self.had_mark_revert = true;
@@ -3683,7 +6101,9 @@ impl Engine {
                     }
                     // Store length AFTER modification
                     self.telex_double_raw_len = self.raw_input.len();
-                    return self.revert_and_rebuild(pos, key, caps);
+                    let result = self.revert_and_rebuild(pos, key, caps);
+                    self.last_revert = Some((reverted, pos, self.buf.len()));
+                    return result;
                 }
             }
         }
@@ -3695,6 +6115,13 @@ impl Engine {
     /// Standard behavior: "ass" → "as" (first 's' was modifier, second 's' reverts + outputs one 's')
     /// This matches standard Vietnamese IME behavior (UniKey, ibus-unikey, etc.)
     fn revert_mark(&mut self, key: u16, caps: bool) -> Result {
+        reason!(self, "key {}: reverts the mark", key);
+        log_debug!(
+            "engine",
+            "revert kind=mark key={} buf=\"{}\"",
+            key,
+            self.buf.to_full_string()
+        );
         self.last_transform = None;
         self.had_mark_revert = true; // Track for auto-restore
                                      // Set had_telex_transform for whitelist-based auto-restore
@@ -3707,6 +6134,7 @@ impl Engine {
         for pos in self.buf.find_vowels().into_iter().rev() {
             if let Some(c) = self.buf.get_mut(pos) {
                 if c.mark > mark::NONE {
+                    let reverted = Transform::Mark(key, c.mark);
                     c.mark = mark::NONE;
 
                     // Set flag to defer raw_input pop until next key
@@ -3719,6 +6147,7 @@ impl Engine {
                     // Add only the reverting key (current key being pressed)
                     // The original mark key was consumed as a modifier and doesn't produce output
                     self.buf.push(Char::new(key, caps));
+                    self.last_revert = Some((reverted, pos, self.buf.len()));
 
                     // Calculate backspace and output
                     let backspace = (self.buf.len() - pos - 1) as u8; // -1 because we added 1 char
@@ -3734,8 +6163,63 @@ impl Engine {
         Result::none()
     }
 
+    /// Whether the character `last_transform` changed is still in the
+    /// buffer with that change, after a Backspace
+    fn last_transform_kept(&self) -> bool {
+        match self.last_transform {
+            Some(Transform::Tone(_, t)) => self.buf.iter().any(|c| c.tone == t),
+            Some(Transform::Mark(_, m)) => self.buf.iter().any(|c| c.mark == m),
+            Some(Transform::Stroke(_)) => self.buf.iter().any(|c| c.stroke),
+            _ => false,
+        }
+    }
+
+    /// Re-apply the transform the previous key reverted, under
+    /// `RevertPolicy::Cycle` ("aaa" → "aa", then "a" → "â")
+    fn try_cycle_revert(&mut self, key: u16) -> Option<Result> {
+        let (transform, pos, len) = self.last_revert.take()?;
+        if self.revert_policy != RevertPolicy::Cycle
+            || self.buf.len() != len
+            || self.buf.last().map(|c| c.key) != Some(key)
+        {
+            return None;
+        }
+        // Drop the letter the revert added and restore the modifier
+        self.buf.pop();
+        let c = self.buf.get_mut(pos)?;
+        match transform {
+            Transform::Tone(_, t) => c.tone = t,
+            Transform::Mark(_, m) => c.mark = m,
+            _ => c.stroke = true,
+        }
+        // raw_input goes back to the transformed word's keys: drop this key,
+        // and the reverting key a mark revert kept
+        self.raw_input.pop();
+        if std::mem::take(&mut self.pending_mark_revert_pop) {
+            self.raw_input.pop();
+        }
+        // Nor is it a revert for auto-restore ("ass" → "as" is English-like)
+        self.stroke_reverted = false;
+        self.had_mark_revert = false;
+        self.had_circumflex_revert = false;
+        self.reverted_circumflex_key = None;
+        self.telex_double_raw = None;
+        self.telex_double_raw_len = 0;
+        self.last_transform = Some(transform);
+        let mut result = self.rebuild_from(pos);
+        result.backspace = (len - pos) as u8;
+        Some(result)
+    }
+
     /// Revert stroke transformation at specific position
     fn revert_stroke(&mut self, key: u16, pos: usize) -> Result {
+        reason!(self, "key {}: reverts the stroke", key);
+        log_debug!(
+            "engine",
+            "revert kind=stroke key={} buf=\"{}\"",
+            key,
+            self.buf.to_full_string()
+        );
         self.last_transform = None;
 
         if let Some(c) = self.buf.get_mut(pos) {
@@ -3798,7 +6282,7 @@ impl Engine {
         // IMPORTANT: Only apply this revert for DELAYED circumflex (V+C+V pattern), not for
         // immediate circumflex (VV pattern like "deep" → "dêp"). For immediate circumflex,
         // typing another vowel should NOT revert (allows words like "deeper").
-        if self.method == 0
+        if self.telex_letters()
             && self.had_vowel_triggered_circumflex
             && matches!(key, keys::A | keys::E | keys::O)
             && self.buf.len() >= 2
@@ -3847,7 +6331,8 @@ impl Engine {
         // The second vowel triggers circumflex on the first vowel (keeping existing mark)
         // IMPORTANT: Must have initial consonant to form valid Vietnamese syllable
         // "expect" (e-x-p-e) should NOT trigger because no initial consonant
-        if self.method == 0 && matches!(key, keys::A | keys::E | keys::O) && self.buf.len() >= 3 {
+        if self.telex_letters() && matches!(key, keys::A | keys::E | keys::O) && self.buf.len() >= 3
+        {
             let last_idx = self.buf.len() - 1;
             let vowel_idx = self.buf.len() - 2;
 
@@ -3915,8 +6400,12 @@ impl Engine {
             // "trawm" → after "traw" (pending breve on 'a'), typing 'm' applies breve → "trăm"
             if let Some(breve_pos) = self.pending_breve_pos {
                 // Valid final consonants that make breve valid: c, k, m, n, p, t
-                // Note: k is included for ethnic minority words (Đắk Lắk)
-                if matches!(
+                // Note: k is included for ethnic minority words (Đắk Lắk), except in Strict
+                let strict_final_k = key == keys::K && self.strictness == Strictness::Strict;
+                if strict_final_k {
+                    // Final k refused - breve not valid, clear pending
+                    self.pending_breve_pos = None;
+                } else if matches!(
                     key,
                     keys::C | keys::K | keys::M | keys::N | keys::P | keys::T
                 ) {
@@ -3981,7 +6470,7 @@ impl Engine {
             // "booos" → "boó", but "booost" → "boost" (revert mark when T follows)
             // Only revert for consonants that can't form valid finals (not N for NG)
             if self.had_circumflex_revert
-                && self.method == 0
+                && self.telex_letters()
                 && keys::is_consonant(key)
                 && key != keys::N
             {
@@ -4051,7 +6540,7 @@ impl Engine {
             // When NG final is typed after a pattern like "boo" + mark_key (f/s),
             // retroactively apply the mark and remove the literal mark key
             // This handles B/C/M initials that were excluded from is_vietnamese_triple_o_word
-            if key == keys::G && self.had_circumflex_revert && self.method == 0 {
+            if key == keys::G && self.had_circumflex_revert && self.telex_letters() {
                 let buf_len = self.buf.len();
                 // Check for pattern: [initial] + OO + [f/s] + N + G (just added)
                 // Buffer now has: [B, O, O, F, N, G] or [M, O, O, S, N, G]
@@ -4160,7 +6649,7 @@ impl Engine {
             // (like "rượu" = ươu, "mười" = ươi) - don't revert in these cases
             // Only skip for vowels that form valid triphthongs (u, i), not for consonants
             // Only run foreign word detection if english_auto_restore is enabled
-            if self.english_auto_restore {
+            if self.restores_mid_word() {
                 let is_valid_triphthong_ending =
                     self.has_complete_uo_compound() && (key == keys::U || key == keys::I);
                 if self.has_w_as_vowel_transform() && !is_valid_triphthong_ending {
@@ -4188,7 +6677,7 @@ impl Engine {
             // Only run if english_auto_restore is enabled (experimental feature)
             let im = input::get(self.method);
             let is_mark_key = im.mark(key).is_some();
-            if self.english_auto_restore
+            if self.restores_mid_word()
                 && keys::is_consonant(key)
                 && !is_mark_key
                 && self.buf.len() >= 2
@@ -4292,7 +6781,7 @@ impl Engine {
     }
 
     /// Collect vowels from buffer
-    fn collect_vowels(&self) -> Vec<Vowel> {
+    fn collect_vowels(&self) -> StackVec<Vowel, MAX> {
         utils::collect_vowels(&self.buf)
     }
 
@@ -4313,6 +6802,7 @@ impl Engine {
 
     /// Rebuild output from position
     fn rebuild_from(&self, from: usize) -> Result {
+        let _timing = latency::span(Stage::Rebuild);
         let mut output = Vec::with_capacity(self.buf.len().saturating_sub(from));
         let mut backspace = 0u8;
 
@@ -4343,6 +6833,7 @@ impl Engine {
     /// in the buffer was just added but NOT yet displayed on screen.
     /// So backspace count = (chars from `from` to end - 1) because last char isn't on screen.
     fn rebuild_from_after_insert(&self, from: usize) -> Result {
+        let _timing = latency::span(Stage::Rebuild);
         if self.buf.is_empty() {
             return Result::none();
         }
@@ -4375,6 +6866,10 @@ impl Engine {
     /// Note: Does NOT clear word_history to preserve backspace-after-space feature
     /// Also restores pending_capitalize if auto_capitalize was used (for selection-delete)
     pub fn clear(&mut self) {
+        self.traced(|| "clear".to_string(), Self::clear_word);
+    }
+
+    fn clear_word(&mut self) {
         // Restore pending_capitalize if auto_capitalize was used
         // This handles selection-delete: user selects and deletes text,
         // we should restore pending state so next letter is capitalized
@@ -4384,6 +6879,7 @@ impl Engine {
         }
         self.buf.clear();
         self.raw_input.clear();
+        self.screen.clear();
         self.last_transform = None;
         self.has_non_letter_prefix = false;
         self.pending_breve_pos = None;
@@ -4392,32 +6888,103 @@ impl Engine {
         self.had_mark_revert = false;
         self.pending_mark_revert_pop = false;
         self.had_any_transform = false;
+        self.word_transformed = false;
         self.had_vowel_triggered_circumflex = false;
         self.had_circumflex_revert = false;
         self.reverted_circumflex_key = None;
+        self.last_revert = None;
         self.had_telex_transform = false;
         self.telex_double_raw = None;
         self.telex_double_raw_len = 0;
         self.restored_pending_clear = false;
         self.restored_is_ascii = false;
+        self.auto_restore_declined = false;
+        self.word_caps_lock = None;
         self.shortcut_prefix.clear();
     }
 
+    /// Ctrl/Option+Backspace: the app deleted the word before the caret
+    ///
+    /// Mid-word that is the word being typed, and the committed words
+    /// before it stay known: Backspace over the space still brings the
+    /// previous word back ("xin chà" → "xin " → "xin"). Right after a space
+    /// the app deletes the spaces and the previous word, and how the text
+    /// before that was spaced is not known.
+    fn delete_word(&mut self) {
+        let mid_word = !self.buf.is_empty();
+        self.clear();
+        if !mid_word {
+            self.word_history.clear();
+            self.spaces_after_commit = 0;
+        }
+    }
+
     /// Clear everything including word history
     /// Used when cursor position changes (mouse click, arrow keys, etc.)
     /// to prevent accidental restore from stale history
     /// Issue #274: Also reset auto-capitalize state to prevent incorrect
     /// capitalization after paste/cursor change
     pub fn clear_all(&mut self) {
+        self.traced(|| "clear_all".to_string(), Self::clear_context);
+    }
+
+    fn clear_context(&mut self) {
         self.clear();
+        self.passthrough_word = false;
+        self.english_keys.clear();
+        self.last_token_char = None;
+        self.codepoint_hex = None;
+        self.snippet = None;
         self.word_history.clear();
         self.spaces_after_commit = 0;
+        self.english_context = false;
         // Issue #274: Reset auto-capitalize state on cursor change
         // This prevents incorrect capitalization after copy-paste
         self.pending_capitalize = false;
         self.saw_sentence_ending = false;
     }
 
+    /// Cmd/Ctrl+A: whatever is typed next replaces the selection
+    ///
+    /// Drops the word and everything known of the text before it, plus the
+    /// undo of the last automatic action (a Backspace now deletes the
+    /// selection, not an auto-restore).
+    pub fn on_select_all(&mut self) {
+        self.traced(|| "select_all".to_string(), Self::reset_for_text_event);
+    }
+
+    /// Cmd/Ctrl+V: `text` was inserted at the caret (None if unknown)
+    ///
+    /// The next key starts a new word. When the text is known, its last
+    /// character is what the caret follows, so "foo_" + "bar" is still
+    /// taken for an identifier. Auto-capitalize is not inferred from it.
+    pub fn on_paste(&mut self, text: Option<&str>) {
+        self.traced(
+            || session::text_event("paste", text),
+            |e| {
+                e.reset_for_text_event();
+                if let Some(text) = text {
+                    e.recorder.paste(text);
+                }
+                e.last_token_char = text
+                    .and_then(|t| t.chars().next_back())
+                    .filter(|c| !c.is_whitespace());
+            },
+        );
+    }
+
+    /// The app changed the text by itself (autocorrect, undo, a script)
+    pub fn on_text_changed(&mut self) {
+        self.traced(|| "text_changed".to_string(), Self::reset_for_text_event);
+    }
+
+    /// Forget the word, the text around it and any pending undo
+    fn reset_for_text_event(&mut self) {
+        self.clear_all();
+        self.last_auto = None;
+        self.cursor_back = None;
+    }
+
     /// Get the full composed buffer as a Vietnamese string with diacritics.
     ///
     /// Used for "Select All + Replace" injection method.
@@ -4482,7 +7049,7 @@ impl Engine {
             return None;
         }
 
-        if self.raw_input.is_empty() || self.buf.is_empty() {
+        if self.raw_input.is_empty() || self.buf.is_empty() || self.auto_restore_declined {
             return None;
         }
 
@@ -4495,6 +7062,13 @@ impl Engine {
             return None;
         }
 
+        // COMPOUND CHECK: a syllable that completes a common two-syllable word
+        // with the previous one is Vietnamese, however English it looks alone
+        // ("bánh" + "test" → "bánh tét", "kẹo" + "must" → "kẹo mút")
+        if is_word_complete && self.completes_compound() {
+            return None;
+        }
+
         // Issue #211: Skip auto-restore for extended character patterns
         // When user types "ơiiiiii", "điiii", "ôiiii", "vàooooo", etc.
         // This is intentional Vietnamese (casual messaging) not English.
@@ -4614,7 +7188,7 @@ impl Engine {
                     .raw_input
                     .iter()
                     .skip(subsequent_start)
-                    .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
+                    .filter_map(|&(key, caps, shift)| raw_key_char(key, caps, shift))
                     .collect();
                 format!("{}{}", stored.to_lowercase(), subsequent.to_lowercase())
             } else {
@@ -4967,6 +7541,13 @@ impl Engine {
             return self.build_raw_chars();
         }
 
+        // FREQUENCY CHECK: a valid syllable that is no word, typed as one of
+        // the most common English words, is a coincidence ("down" → "dơn",
+        // "her" → "hẻ"); dictionary words stay ("most" → "mót", "six" → "sĩ")
+        if !buffer_invalid_vn && self.is_english_coincidence() {
+            return self.build_raw_chars();
+        }
+
         // OW PATTERN CHECK: raw_input has 'ow' but buffer has ơ/ở/ờ/ớ/ỡ/ợ (horn-o)
         // English words: power, tower, down, town, etc.
         // Telex 'w' converts 'o' to 'ơ' (horn mark), which is wrong for English
@@ -5273,28 +7854,23 @@ impl Engine {
 
         // Check if it's a vowel tone key (Telex: a, e, o for circumflex; w for horn/breve)
         // These are always intentional reverts - no English words use double vowels like this
-        if self.method == 0 {
-            if matches!(last_key, keys::A | keys::E | keys::O | keys::W) {
-                return true;
-            }
-        } else {
-            // VNI: 6, 7, 8 for vowel tones
-            if matches!(last_key, keys::N6 | keys::N7 | keys::N8) {
-                return true;
-            }
+        if self.telex_letters() && matches!(last_key, keys::A | keys::E | keys::O | keys::W) {
+            return true;
+        }
+        // VNI: 6, 7, 8 for vowel tones
+        if self.vni_digits() && matches!(last_key, keys::N6 | keys::N7 | keys::N8) {
+            return true;
         }
 
         // Check if it's a mark key
-        let is_mark_key = if self.method == 0 {
-            // Telex tone modifiers: s, f, r, x, j
-            matches!(last_key, keys::S | keys::F | keys::R | keys::X | keys::J)
-        } else {
-            // VNI tone modifiers: 1, 2, 3, 4, 5
-            matches!(
-                last_key,
-                keys::N1 | keys::N2 | keys::N3 | keys::N4 | keys::N5
-            )
-        };
+        // Telex tone modifiers: s, f, r, x, j; VNI tone modifiers: 1, 2, 3, 4, 5
+        let is_mark_key = (self.telex_letters()
+            && matches!(last_key, keys::S | keys::F | keys::R | keys::X | keys::J))
+            || (self.vni_digits()
+                && matches!(
+                    last_key,
+                    keys::N1 | keys::N2 | keys::N3 | keys::N4 | keys::N5
+                ));
 
         if !is_mark_key {
             return false;
@@ -5330,13 +7906,47 @@ impl Engine {
         // For longer words (5+ chars), check modifier type:
         // - 'x', 'j' (Telex) or VNI numbers: not common doubles in English → keep
         // - 's', 'f', 'r' (Telex): very common doubles in English (bass, staff, error) → restore
-        if self.method == 0 {
-            // Telex: only keep for uncommon double letters (x, j)
-            matches!(last_key, keys::X | keys::J)
-        } else {
+        if keys::is_number(last_key) {
             // VNI: number modifiers are always intentional → keep
             true
+        } else {
+            // Telex: only keep for uncommon double letters (x, j)
+            matches!(last_key, keys::X | keys::J)
+        }
+    }
+
+    /// Whether the buffer is a syllable outside the dictionary whose keys
+    /// spell a common English word (neither in the keep list, nor the
+    /// buffer in the user's word lists)
+    ///
+    /// Dictionary words stay however common the English word: "bàn" is
+    /// typed far more often than "ban".
+    ///
+    /// A lone toned vowel ("or" → "ỏ") is always typed on purpose.
+    fn is_english_coincidence(&self) -> bool {
+        if self.buf.len() < 2 {
+            return false;
+        }
+        let raw = self.get_raw_input_string();
+        let buffer = self.buf.to_full_string();
+        english_dict::frequency_rank(&raw).is_some_and(|rank| rank < COMMON_ENGLISH_RANK)
+            && !dictionary::is_vietnamese(&buffer, self.allow_foreign_consonants)
+            && !dictionary::is_variant(&buffer, self.spelling_variants)
+            && !dictionary::is_user_word(&buffer)
+            && !dictionary::should_keep(&raw)
+            && !dictionary::should_keep(&buffer)
+    }
+
+    /// Whether the buffer and the word committed just before it (one space
+    /// apart) form a common two-syllable word
+    fn completes_compound(&self) -> bool {
+        if self.spaces_after_commit != 1 {
+            return false;
         }
+        let Some(prev) = self.word_history.last() else {
+            return false;
+        };
+        dictionary::is_compound(&prev.to_full_string(), &self.buf.to_full_string())
     }
 
     /// Get raw_input as lowercase ASCII string
@@ -5352,7 +7962,7 @@ impl Engine {
     fn get_raw_input_string_preserve_case(&self) -> String {
         self.raw_input
             .iter()
-            .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
+            .filter_map(|&(key, caps, shift)| raw_key_char(key, caps, shift))
             .collect()
     }
 
@@ -5370,7 +7980,7 @@ impl Engine {
         // If word is in Vietnamese dictionary, it's definitely valid Vietnamese.
         if self.english_auto_restore {
             let buffer_str = self.buf.to_full_string();
-            if dictionary::is_vietnamese(&buffer_str, self.allow_foreign_consonants) {
+            if self.is_dictionary_word(&buffer_str) {
                 return false; // Valid VN word in dictionary
             }
 
@@ -5412,10 +8022,14 @@ impl Engine {
         let buffer_marks: Vec<u8> = self.buf.iter().map(|c| c.mark).collect();
 
         // Check 1: Basic structural validation (with foreign consonants support)
-        if !is_valid_with_tones_and_foreign(
+        // Tone marks are checked against the final: "hoàp" is not Vietnamese
+        if !is_valid_with_marks(
             &buffer_keys,
             &buffer_tones,
+            &buffer_marks,
             self.allow_foreign_consonants,
+            self.southern_initials(),
+            self.strictness,
         ) {
             return true;
         }
@@ -5467,27 +8081,9 @@ impl Engine {
             }
         }
 
-        // Check 5: Open diphthong + consonant final = INVALID
-        // Open diphthongs (ai, ao, au, ay, eo, iu, oi, ui, ưu) cannot take consonant finals.
-        // Example: "mason" → "máon" has diphthong "ao" + final "n" → invalid
-        // This catches English words like mason, reason, poison, etc.
+        // Check 5: Open diphthong + consonant final ("mason" → "máon") is not a
+        // rhyme, so Check 1 already rejects it
         let syllable = syllable::parse(&buffer_keys);
-        if syllable.vowel.len() == 2 && !syllable.final_c.is_empty() {
-            let vowel_pair = [
-                buffer_keys[syllable.vowel[0]],
-                buffer_keys[syllable.vowel[1]],
-            ];
-            // Check if final is a consonant (not semi-vowel that's part of diphthong)
-            let final_key = buffer_keys[syllable.final_c[0]];
-            let is_consonant_final = matches!(
-                final_key,
-                keys::C | keys::K | keys::M | keys::N | keys::P | keys::T
-            ) || (syllable.final_c.len() == 2); // CH, NG, NH are always consonant finals
-
-            if is_consonant_final && constants::OPEN_DIPHTHONGS.contains(&vowel_pair) {
-                return true;
-            }
-        }
 
         // Check 6: HORN-O + E pattern is INVALID Vietnamese
         // "oe" is valid diphthong (xoe, hoe), but "ơe" / "ởe" doesn't exist
@@ -5673,7 +8269,7 @@ impl Engine {
                 };
                 for i in subsequent_start..self.raw_input.len() {
                     if let Some(&(key, caps, shift)) = self.raw_input.get(i) {
-                        if let Some(ch) = utils::key_to_char_ext(key, caps, shift) {
+                        if let Some(ch) = raw_key_char(key, caps, shift) {
                             result.push(ch);
                         }
                     }
@@ -5685,7 +8281,7 @@ impl Engine {
         let chars: Vec<char> = self
             .raw_input
             .iter()
-            .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
+            .filter_map(|&(key, caps, shift)| raw_key_char(key, caps, shift))
             .collect();
         if chars.is_empty() {
             None
@@ -5712,7 +8308,7 @@ impl Engine {
             let mut chars: Vec<char> = self
                 .raw_input
                 .iter()
-                .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
+                .filter_map(|&(key, caps, shift)| raw_key_char(key, caps, shift))
                 .collect();
 
             // Collapse vowel patterns for English restore (Telex circumflex patterns)
@@ -5914,7 +8510,7 @@ impl Engine {
             // Example: "tafoo" = t + a + f + o + o → restore to "tàoo"
             // - Keep the tone on first vowel (from 'f' = huyền)
             // - Keep double vowel at end (not collapsed to circumflex)
-            if chars.len() == 5 && self.method == 0 {
+            if chars.len() == 5 && self.telex_letters() {
                 // Telex only
                 let c0 = chars[0].to_ascii_lowercase();
                 let c1 = chars[1].to_ascii_lowercase();
@@ -6355,7 +8951,7 @@ impl Engine {
                         let raw_str: String = self
                             .raw_input
                             .iter()
-                            .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
+                            .filter_map(|&(k, c, s)| raw_key_char(k, c, s))
                             .collect();
                         let raw_in_dict = english_dict::is_english_word(&raw_str);
 
@@ -6932,7 +9528,7 @@ impl Engine {
                             let raw_str: String = self
                                 .raw_input
                                 .iter()
-                                .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
+                                .filter_map(|&(k, c, s)| raw_key_char(k, c, s))
                                 .collect();
                             if english_dict::is_english_word(&raw_str) {
                                 return true; // Restore to English
@@ -7018,9 +9614,7 @@ impl Engine {
                                         let raw_str: String = self
                                             .raw_input
                                             .iter()
-                                            .filter_map(|&(k, c, s)| {
-                                                utils::key_to_char_ext(k, c, s)
-                                            })
+                                            .filter_map(|&(k, c, s)| raw_key_char(k, c, s))
                                             .collect();
                                         if english_dict::is_english_word(&raw_str) {
                                             return true; // English word (param, etc.)
@@ -7107,7 +9701,7 @@ impl Engine {
                                 let raw_str: String = self
                                     .raw_input
                                     .iter()
-                                    .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
+                                    .filter_map(|&(k, c, s)| raw_key_char(k, c, s))
                                     .collect();
                                 if !english_dict::is_english_word(&raw_str) {
                                     // Not a common English word, keep Vietnamese
@@ -7155,7 +9749,7 @@ impl Engine {
                                 let raw_str: String = self
                                     .raw_input
                                     .iter()
-                                    .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
+                                    .filter_map(|&(k, c, s)| raw_key_char(k, c, s))
                                     .collect();
                                 if !english_dict::is_english_word(&raw_str) {
                                     // Not a common English word, keep Vietnamese
@@ -7340,9 +9934,12 @@ impl Engine {
 
                     // Vietnamese ethnic minority words have breve: ắ, ẳ, ẵ (from 'aw')
                     // Check if there's a 'w' in raw_input before the modifier (indicating breve)
-                    let has_breve_marker = self.raw_input[..self.raw_input.len() - 2]
-                        .iter()
-                        .any(|(k, _, _)| *k == keys::W);
+                    // Strict has no ethnic minority exceptions: final k is always English
+                    let strict = self.strictness == Strictness::Strict;
+                    let has_breve_marker = !strict
+                        && self.raw_input[..self.raw_input.len() - 2]
+                            .iter()
+                            .any(|(k, _, _)| *k == keys::W);
 
                     // Also check for common English -Vsk patterns where V is i, a, e, o, u
                     // but NOT ethnic minority patterns
@@ -7370,7 +9967,8 @@ impl Engine {
                             // Note: Đắk uses DD (double D) for Đ, not single D
                             // So D initial (disk, desk, dusk) should restore as English
                             let (first, _, _) = self.raw_input[0];
-                            let is_ethnic_initial = first == keys::B || first == keys::L;
+                            let is_ethnic_initial =
+                                !strict && (first == keys::B || first == keys::L);
 
                             if !is_ethnic_initial {
                                 return true;
@@ -7439,8 +10037,8 @@ impl Engine {
     /// - Double bracket reverts: ]] → ], [[ → [, uppercase revert → } or {
     /// - Valid Vietnamese vowel combinations: ươ (from ][)
     fn try_bracket_as_vowel(&mut self, key: u16, caps: bool) -> Option<Result> {
-        // Check if bracket shortcut is enabled
-        if !self.bracket_shortcut {
+        // Check if bracket shortcut is enabled (always on in Simple Telex)
+        if !self.bracket_shortcut && !self.simple_telex {
             return None;
         }
 
@@ -7485,7 +10083,8 @@ impl Engine {
         };
 
         // Add vowel to buffer (similar to W shortcut pattern)
-        self.buf.push(Char::new(base_key, caps));
+        let vowel_caps = self.inserted_vowel_caps(caps);
+        self.buf.push(Char::new(base_key, vowel_caps));
 
         // Set horn tone to make ư or ơ
         if let Some(c) = self.buf.get_mut(self.buf.len() - 1) {
@@ -7509,18 +10108,162 @@ impl Engine {
         self.had_any_transform = true;
 
         // Return result with key consumed (don't pass through bracket)
-        let vowel_char = chars::to_char(base_key, caps, tone::HORN, 0).unwrap();
+        let vowel_char = chars::to_char(base_key, vowel_caps, tone::HORN, 0).unwrap();
         Some(Result::send_consumed(0, &[vowel_char]))
     }
 
+    /// Respell a misspelled initial at the word boundary (spelling auto-fix)
+    ///
+    /// "nge" → "nghe", "cees" → "kế", "ghas" → "gá": the raw keys are replayed
+    /// with the initial fixed (c/k, g/gh, ng/ngh) and the word is replaced only
+    /// if the result is a Vietnamese dictionary word and the typed word is
+    /// neither Vietnamese nor English. The buffer takes the fixed word;
+    /// `boundary` is appended to the replacement (Space).
+    fn try_spelling_fix(&mut self, boundary: Option<char>) -> Option<Result> {
+        if !self.spelling_autofix
+            || self.strictness == Strictness::Relaxed
+            || self.buf.is_empty()
+            || self.telex_double_raw.is_some()
+            || self.is_teencode()
+        {
+            return None;
+        }
+        let typed = self.buf.to_full_string();
+        let raw: String = self.raw_chars().into_iter().collect();
+        if dictionary::is_vietnamese(&typed, self.allow_foreign_consonants)
+            || english_dict::is_english_word(&raw)
+        {
+            return None;
+        }
+        let e = self.respelled()?;
+        let fixed = e.buf.to_full_string();
+        log_debug!("engine", "spelling fix {:?} → {:?}", typed, fixed);
+
+        let backspace = self.buf.len() as u8;
+        let mut chars: Vec<char> = fixed.chars().collect();
+        chars.extend(boundary);
+        self.buf = e.buf;
+        self.raw_input = e.raw_input;
+        self.had_any_transform = true;
+        Some(Result::send(backspace, &chars))
+    }
+
+    /// The word replayed with its initial respelled (c/k, g/gh, ng/ngh),
+    /// if that gives a Vietnamese dictionary word
+    fn respelled(&self) -> Option<Engine> {
+        let raw_keys: Vec<u16> = self.raw_input.iter().map(|&(key, _, _)| key).collect();
+        let (wrong_len, right) = validation::fix_spelling(&raw_keys)?;
+
+        // Respelled letters follow the case of the letters they replace
+        let mut e = self.fresh_copy();
+        e.auto_english_words = 0;
+        for (i, &key) in right.iter().enumerate() {
+            let caps = self.raw_input[i.min(wrong_len - 1)].1;
+            e.on_key_ext(key, caps, false, false);
+        }
+        for &(key, caps, shift) in &self.raw_input[wrong_len..] {
+            e.on_key_ext(key, caps, false, shift);
+        }
+        let fixed = e.buf.to_full_string();
+        dictionary::is_vietnamese(&fixed, self.allow_foreign_consonants).then_some(e)
+    }
+
+    /// Capitalize a place/person name ending at the current word
+    ///
+    /// "hà" + Space + "nội" + Space → "Hà Nội ": the current word and up to
+    /// four words before it (separated by single spaces) are looked up, and the
+    /// whole name is retyped with `boundary` appended. The buffer takes the
+    /// new capitalization.
+    fn try_proper_noun(&mut self, boundary: Option<char>) -> Option<Result> {
+        if !self.proper_nouns.enabled() || self.buf.is_empty() {
+            return None;
+        }
+        // Earlier words are only adjacent if exactly one space was typed since
+        // the last commit and nothing cleared the word history in between
+        if self.word_history.len == 0 || self.spaces_after_commit != 1 {
+            self.name_context.clear();
+        }
+        let mut words = self.name_context.clone();
+        words.push(self.buf.to_full_string());
+        let (n, fixed) = self.proper_nouns.capitalize(&words)?;
+        log_debug!(
+            "engine",
+            "proper noun {:?} → {:?}",
+            &words[words.len() - n..],
+            fixed
+        );
+
+        let typed = &words[words.len() - n..];
+        let backspace = typed.iter().map(|w| w.chars().count()).sum::<usize>() + n - 1;
+        let mut chars: Vec<char> = fixed.join(" ").chars().collect();
+        chars.extend(boundary);
+
+        let context_start = self.name_context.len() + 1 - n;
+        self.name_context[context_start..].clone_from_slice(&fixed[..n - 1]);
+        if fixed[n - 1] != words[words.len() - 1] {
+            if let Some(c) = self.buf.get_mut(0) {
+                c.caps = true;
+            }
+            if let Some(raw) = self.raw_input.first_mut() {
+                raw.1 = true;
+            }
+        }
+        Some(Result::send(backspace as u8, &chars))
+    }
+
+    /// Capitalize a standalone "i" typed in English context
+    ///
+    /// `word` holds the keys of the word; `boundary` is appended to the
+    /// replacement (Space).
+    fn try_capital_i(&self, word: &[(u16, bool, bool)], boundary: Option<char>) -> Option<Result> {
+        let lone_i = matches!(word, [(keys::I, false, _)]);
+        if !self.english_capital_i || !lone_i || !(self.english_context || self.english_suspended) {
+            return None;
+        }
+        let mut chars = vec!['I'];
+        chars.extend(boundary);
+        Some(Result::send(1, &chars))
+    }
+
+    /// `try_capital_i` for the word in the buffer, which takes the capital
+    fn try_capital_i_in_buffer(&mut self, boundary: Option<char>) -> Option<Result> {
+        if self.buf.to_full_string() != "i" {
+            return None;
+        }
+        let result = self.try_capital_i(&self.raw_input, boundary)?;
+        if let Some(c) = self.buf.get_mut(0) {
+            c.caps = true;
+        }
+        self.raw_input[0].1 = true;
+        Some(result)
+    }
+
+    /// Remember the word being committed by Space for `try_proper_noun`
+    fn push_name_context(&mut self) {
+        if !self.proper_nouns.enabled() {
+            return;
+        }
+        if self.word_history.len == 0 || self.spaces_after_commit != 1 {
+            self.name_context.clear();
+        }
+        if self.name_context.len() + 1 >= proper_noun::MAX_WORDS {
+            self.name_context.remove(0);
+        }
+        self.name_context.push(self.buf.to_full_string());
+    }
+
     /// Auto-restore invalid Vietnamese to raw English on space
     ///
     /// Called when SPACE is pressed. If buffer has transforms but result is not
     /// valid Vietnamese, restore to original English + space.
     /// Example: "tẽt" (from typing "text") → "text " (restored + space)
     /// Example: "ễpct" (from typing "expect") → "expect " (restored + space)
-    fn try_auto_restore_on_space(&self) -> Result {
-        if let Some(mut raw_chars) = self.should_auto_restore(true) {
+    fn try_auto_restore_on_space(&mut self) -> Result {
+        let restore = self
+            .exception_restore()
+            .or_else(|| self.learned_restore(self.should_auto_restore(true)));
+        self.log_auto_restore("space", restore.as_deref());
+        if let Some(mut raw_chars) = restore {
             // Add space at the end
             raw_chars.push(' ');
             // Backspace count = current buffer length (displayed chars)
@@ -7537,8 +10280,12 @@ impl Engine {
     /// but result is not valid Vietnamese, restore to original English.
     /// Does NOT include the break key (it's passed through by the app).
     /// Example: "ễpct" + comma → "expect" (comma added by app)
-    fn try_auto_restore_on_break(&self) -> Result {
-        if let Some(raw_chars) = self.should_auto_restore(true) {
+    fn try_auto_restore_on_break(&mut self) -> Result {
+        let restore = self
+            .exception_restore()
+            .or_else(|| self.learned_restore(self.should_auto_restore(true)));
+        self.log_auto_restore("break", restore.as_deref());
+        if let Some(raw_chars) = restore {
             // Backspace count = current buffer length (displayed chars)
             let backspace = self.buf.len() as u8;
             Result::send(backspace, &raw_chars)
@@ -7547,9 +10294,83 @@ impl Engine {
         }
     }
 
+    /// Raw keystrokes of an exception word or chat abbreviation that was
+    /// transformed
+    ///
+    /// Example: with "expect" added, "ễpct" → "expect" even when English
+    /// auto-restore is off; with teencode on, "ưa" → "wa"
+    fn exception_restore(&self) -> Option<Vec<char>> {
+        if (self.english_words.is_empty() && !self.teencode.enabled())
+            || self.buf.is_empty()
+            || self.auto_restore_declined
+        {
+            return None;
+        }
+        let raw = self.raw_chars();
+        let raw_str: String = raw.iter().collect();
+        let transformed = self.buf.to_full_string() != raw_str;
+        (transformed && (self.has_english_word(&raw_str) || self.is_teencode())).then_some(raw)
+    }
+
+    /// Override an auto-restore decision with what the user taught for the word
+    ///
+    /// Example: after "user" + Space → Backspace twice, "user" stays "uẻ"
+    fn learned_restore(&self, restore: Option<Vec<char>>) -> Option<Vec<char>> {
+        if !self.learning.enabled()
+            || !self.english_auto_restore
+            || self.auto_restore_declined
+            || !self.had_any_transform
+            || self.buf.is_empty()
+        {
+            return restore;
+        }
+        let raw = restore.clone().unwrap_or_else(|| self.raw_chars());
+        match self.learning.preference(&raw) {
+            Some(Preference::Vietnamese) => None,
+            Some(Preference::English) => Some(raw),
+            None => restore,
+        }
+    }
+
+    /// Log the auto-restore decision for a completed word
+    ///
+    /// Answers "why was this word reverted / kept" from a release build log.
+    fn log_auto_restore(&mut self, trigger: &str, restore: Option<&[char]>) {
+        reason!(
+            self,
+            "auto-restore on {}: {} (enabled={}, transformed={})",
+            trigger,
+            restore.map_or("keep".to_string(), |raw| format!(
+                "restore to {:?}",
+                raw.iter().collect::<String>()
+            )),
+            self.english_auto_restore,
+            self.had_any_transform
+        );
+        if self.buf.is_empty() || !crate::logging::enabled(crate::logging::Level::Debug) {
+            return;
+        }
+        let decision = match restore {
+            Some(raw) => format!("restore to=\"{}\"", raw.iter().collect::<String>()),
+            None => "keep".to_string(),
+        };
+        log_debug!(
+            "auto_restore",
+            "{} trigger={} enabled={} buf=\"{}\" raw=\"{}\" transformed={} mark_revert={}",
+            decision,
+            trigger,
+            self.english_auto_restore,
+            self.buf.to_full_string(),
+            self.get_raw_input_string_preserve_case(),
+            self.had_any_transform,
+            self.had_mark_revert
+        );
+    }
+
     /// Restore buffer to raw ASCII (undo all Vietnamese transforms)
     ///
-    /// Called when ESC is pressed. Replaces transformed output with original keystrokes.
+    /// Called when the restore key (ESC by default) is pressed. Replaces transformed
+    /// output with original keystrokes as one `Action::Restore` replacement.
     /// Example: "tẽt" (from typing "text" in Telex) → "text"
     /// Example: "of" → "ò" → ESC → "of" (mark was applied)
     /// Example: "off" → "of" → ESC → "off" (mark was applied then reverted)
@@ -7558,27 +10379,7 @@ impl Engine {
             return Result::none();
         }
 
-        // Build raw ASCII output from raw_input history
-        // If telex_double_raw is set (revert happened), use it as base and append subsequent chars
-        // This ensures "aww" → ESC → "aww" (not "aw"), "a66" → ESC → "a66" (not "a6")
-        let raw_chars: Vec<char> = if let Some(ref base_raw) = self.telex_double_raw {
-            // Start with the original raw string before revert modification
-            let mut chars: Vec<char> = base_raw.chars().collect();
-            // Append any characters typed after the revert
-            for &(key, caps, shift) in self.raw_input.iter().skip(self.telex_double_raw_len) {
-                if let Some(ch) = utils::key_to_char_ext(key, caps, shift) {
-                    chars.push(ch);
-                }
-            }
-            chars
-        } else {
-            // Normal case: use raw_input directly
-            self.raw_input
-                .iter()
-                .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
-                .collect()
-        };
-
+        let raw_chars = self.raw_chars();
         if raw_chars.is_empty() {
             return Result::none();
         }
@@ -7597,7 +10398,31 @@ impl Engine {
         // Backspace count = current buffer length (displayed chars)
         let backspace = self.buf.len() as u8;
 
-        Result::send(backspace, &raw_chars)
+        Result::restore(backspace, &raw_chars)
+    }
+
+    /// Keys typed for the current word, as ASCII (case preserved)
+    fn raw_chars(&self) -> Vec<char> {
+        // Build raw ASCII output from raw_input history
+        // If telex_double_raw is set (revert happened), use it as base and append subsequent chars
+        // This ensures "aww" → ESC → "aww" (not "aw"), "a66" → ESC → "a66" (not "a6")
+        if let Some(ref base_raw) = self.telex_double_raw {
+            // Start with the original raw string before revert modification
+            let mut chars: Vec<char> = base_raw.chars().collect();
+            // Append any characters typed after the revert
+            for &(key, caps, shift) in self.raw_input.iter().skip(self.telex_double_raw_len) {
+                if let Some(ch) = raw_key_char(key, caps, shift) {
+                    chars.push(ch);
+                }
+            }
+            chars
+        } else {
+            // Normal case: use raw_input directly
+            self.raw_input
+                .iter()
+                .filter_map(|&(key, caps, shift)| raw_key_char(key, caps, shift))
+                .collect()
+        }
     }
 
     /// Restore raw_input from buffer (for ESC restore to work after backspace-restore)
@@ -7773,6 +10598,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_esc_restore_action_and_custom_key() {
+        use crate::data::keys;
+        use crate::engine::Action;
+
+        let mut e = Engine::new();
+        e.set_esc_restore(true);
+        type_word(&mut e, "vieetj");
+        let r = e.on_key(keys::ESC, false, false);
+        assert_eq!(r.action, Action::Restore as u8);
+        assert_eq!(r.backspace, 4);
+        let raw: String = r.chars[..r.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(raw, "vieetj");
+
+        // Custom key restores; ESC only ends the word
+        let mut e = Engine::new();
+        e.set_esc_restore(true);
+        e.set_restore_key(keys::BACKQUOTE);
+        type_word(&mut e, "vieetj");
+        assert_eq!(
+            e.on_key(keys::BACKQUOTE, false, false).action,
+            Action::Restore as u8
+        );
+        type_word(&mut e, "vieetj");
+        assert_eq!(e.on_key(keys::ESC, false, false).action, Action::None as u8);
+        assert_eq!(e.get_buffer_string(), "");
+    }
+
     #[test]
     fn test_nurses_horses_auto_restore() {
         // Test patterns where multiple modifiers are consumed but not added to buffer
@@ -8015,6 +10871,7 @@ mod tests {
     /// - "booo" → "boo" (revert), then "s" → "boos" (not "boós")
     /// - "seee" → "see" (revert), then "m" → "seem" (not "seém")
     /// - "booo" + "k" → "book" (consonant also literal)
+    ///
     /// Note: Only works with valid Vietnamese initials (b, c, d, h, l, m, n, p, s, t, etc.)
     #[test]
     fn test_literal_after_circumflex_revert() {
@@ -0,0 +1,209 @@
+//! Shortcut placeholders - dynamic values expanded at trigger time
+//!
+//! A replacement like "Hà Nội, %date_long%" expands when the shortcut fires:
+//!
+//! | Placeholder   | Output                    |
+//! |---------------|---------------------------|
+//! | `%date%`      | 16/10/2026                |
+//! | `%date_long%` | ngày 16 tháng 10 năm 2026 |
+//! | `%weekday%`   | Thứ Sáu                   |
+//! | `%time%`      | 14:05                     |
+//! | `%clipboard%` | current clipboard text    |
+//!
+//! Names are case-insensitive (MatchCase shortcuts uppercase the template).
+//! Unknown `%name%` sequences are left as typed. The clock and clipboard come
+//! from a `PlaceholderContext` supplied by the platform.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Vietnamese weekday names, Sunday first
+const WEEKDAYS: [&str; 7] = [
+    "Chủ Nhật",
+    "Thứ Hai",
+    "Thứ Ba",
+    "Thứ Tư",
+    "Thứ Năm",
+    "Thứ Sáu",
+    "Thứ Bảy",
+];
+
+/// Local calendar date and wall-clock time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    /// 0 = Sunday
+    pub weekday: u8,
+}
+
+impl DateTime {
+    /// Date/time for a Unix timestamp shifted by `utc_offset_minutes`
+    pub fn from_unix(secs: i64, utc_offset_minutes: i32) -> Self {
+        let local = secs + utc_offset_minutes as i64 * 60;
+        let days = local.div_euclid(86_400);
+        let secs_of_day = local.rem_euclid(86_400);
+
+        // Civil-from-days (Howard Hinnant), proleptic Gregorian calendar
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+
+        Self {
+            year,
+            month,
+            day,
+            hour: (secs_of_day / 3600) as u8,
+            minute: (secs_of_day % 3600 / 60) as u8,
+            // 1970-01-01 was a Thursday
+            weekday: (days + 4).rem_euclid(7) as u8,
+        }
+    }
+}
+
+/// Platform values for placeholders (clock and clipboard)
+pub trait PlaceholderContext: fmt::Debug + Send {
+    /// Current local date and time
+    fn now(&self) -> DateTime;
+    /// Clipboard text, None if empty or not text
+    fn clipboard(&self) -> Option<String>;
+}
+
+/// Context fed by the host through FFI
+///
+/// The system clock is shifted by the host's UTC offset (the core has no
+/// timezone database). The host pushes clipboard text when it changes.
+#[derive(Debug, Clone, Default)]
+pub struct HostContext {
+    pub utc_offset_minutes: i32,
+    pub clipboard: Option<String>,
+}
+
+impl PlaceholderContext for HostContext {
+    fn now(&self) -> DateTime {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        DateTime::from_unix(secs, self.utc_offset_minutes)
+    }
+
+    fn clipboard(&self) -> Option<String> {
+        self.clipboard.clone()
+    }
+}
+
+/// Value for one placeholder name, None if unknown
+fn value(name: &str, ctx: &dyn PlaceholderContext) -> Option<String> {
+    let value = match name.to_lowercase().as_str() {
+        "date" => {
+            let t = ctx.now();
+            format!("{:02}/{:02}/{}", t.day, t.month, t.year)
+        }
+        "date_long" => {
+            let t = ctx.now();
+            format!("ngày {} tháng {} năm {}", t.day, t.month, t.year)
+        }
+        "weekday" => WEEKDAYS[ctx.now().weekday as usize % 7].to_string(),
+        "time" => {
+            let t = ctx.now();
+            format!("{:02}:{:02}", t.hour, t.minute)
+        }
+        "clipboard" => ctx.clipboard().unwrap_or_default(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Whether `text` contains anything that looks like a placeholder
+#[inline]
+pub fn has_placeholders(text: &str) -> bool {
+    text.matches('%').count() >= 2
+}
+
+/// Expand every known `%name%` in `template`
+pub fn expand(template: &str, ctx: &dyn PlaceholderContext) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('%') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%') {
+            Some(end) => match value(&after[..end], ctx) {
+                Some(v) => {
+                    out.push_str(&v);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    // Not a placeholder: keep '%' and retry from the next one
+                    out.push('%');
+                    rest = after;
+                }
+            },
+            None => {
+                out.push('%');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Fixed;
+
+    impl PlaceholderContext for Fixed {
+        fn now(&self) -> DateTime {
+            // 2026-10-16 07:05 UTC → 14:05 in Vietnam (UTC+7)
+            DateTime::from_unix(1_792_134_300, 7 * 60)
+        }
+        fn clipboard(&self) -> Option<String> {
+            Some("Hà Nội".to_string())
+        }
+    }
+
+    #[test]
+    fn test_from_unix() {
+        let t = DateTime::from_unix(0, 0);
+        assert_eq!((t.year, t.month, t.day, t.weekday), (1970, 1, 1, 4));
+        let t = DateTime::from_unix(951_782_400, 0); // 2000-02-29
+        assert_eq!((t.year, t.month, t.day, t.weekday), (2000, 2, 29, 2));
+        let t = DateTime::from_unix(-1, 0);
+        assert_eq!(
+            (t.year, t.month, t.day, t.hour, t.minute),
+            (1969, 12, 31, 23, 59)
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(expand("%date% %time%", &Fixed), "16/10/2026 14:05");
+        assert_eq!(
+            expand("%WEEKDAY%, %Date_Long%", &Fixed),
+            "Thứ Sáu, ngày 16 tháng 10 năm 2026"
+        );
+        assert_eq!(expand("từ %clipboard%", &Fixed), "từ Hà Nội");
+    }
+
+    #[test]
+    fn test_expand_keeps_unknown() {
+        assert_eq!(expand("50% off", &Fixed), "50% off");
+        assert_eq!(expand("%foo% %date%", &Fixed), "%foo% 16/10/2026");
+        assert_eq!(expand("100%%time%", &Fixed), "100%14:05");
+        assert!(!has_placeholders("50% off"));
+    }
+}
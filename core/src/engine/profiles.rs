@@ -0,0 +1,233 @@
+//! Named profiles: separate options and shortcuts per use ("work", "gaming")
+//!
+//! A profile is the options and shortcuts part of a settings bundle
+//! (`Engine::export_profile`). Word lists, proper nouns, hotkeys and
+//! learned corrections stay shared, so the switch hotkey works in every
+//! profile.
+//!
+//! The engine keeps the bundles of the other profiles in memory; switching
+//! stores the current configuration under the active name and applies the
+//! target's. A profile that was never stored starts as a copy of the
+//! current one. On disk each profile is `<name>.txt` in the profiles
+//! directory (`config::PROFILES_DIR`), and `active` holds the active name.
+
+use super::settings::{self, SettingsError};
+use super::{Engine, Event};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Profile in use before any switch
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// File holding the active profile's name
+const ACTIVE_FILE: &str = "active";
+
+/// Longest profile name, in characters
+const MAX_NAME: usize = 32;
+
+/// Active profile and the stored bundles of the others
+#[derive(Debug)]
+pub struct Profiles {
+    active: String,
+    stored: BTreeMap<String, String>,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            stored: BTreeMap::new(),
+        }
+    }
+}
+
+/// Whether `name` can be a profile: letters, digits, '-', '_' and inner
+/// spaces, at most 32 characters
+pub fn valid_name(name: &str) -> bool {
+    let len = name.chars().count();
+    len > 0
+        && len <= MAX_NAME
+        && name.trim() == name
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+}
+
+fn io_error(err: std::io::Error) -> SettingsError {
+    SettingsError::Io(err.to_string())
+}
+
+impl Engine {
+    /// Name of the active profile
+    pub fn profile(&self) -> &str {
+        &self.profiles.active
+    }
+
+    /// All profile names, sorted, the active one included
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.stored.keys().cloned().collect();
+        if !self.profiles.stored.contains_key(&self.profiles.active) {
+            names.push(self.profiles.active.clone());
+            names.sort();
+        }
+        names
+    }
+
+    /// Make `name` the active profile
+    ///
+    /// The current options and shortcuts are stored under the old name.
+    /// The word in progress ends and `Event::ProfileChanged` is queued.
+    /// On error nothing changes.
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), SettingsError> {
+        if !valid_name(name) {
+            return Err(SettingsError::InvalidProfile(name.to_string()));
+        }
+        if name == self.profiles.active {
+            return Ok(());
+        }
+        let current = self.export_profile();
+        match self.profiles.stored.get(name).cloned() {
+            Some(text) => self.import_settings(&text)?,
+            None => self.clear(),
+        }
+        self.profiles
+            .stored
+            .insert(self.profiles.active.clone(), current);
+        self.profiles.active = name.to_string();
+        self.push_event(Event::ProfileChanged(name.to_string()));
+        Ok(())
+    }
+
+    /// Switch to the profile after the active one, in name order, returning
+    /// its name (hotkey)
+    ///
+    /// With a single profile nothing changes.
+    pub fn cycle_profile(&mut self) -> String {
+        let names = self.profile_names();
+        let next = names
+            .iter()
+            .position(|n| *n == self.profiles.active)
+            .map_or(0, |i| (i + 1) % names.len());
+        let name = names[next].clone();
+        // Stored bundles were checked when they were loaded or exported
+        let _ = self.switch_profile(&name);
+        self.profiles.active.clone()
+    }
+
+    /// Forget a stored profile; the active one cannot be deleted
+    pub fn delete_profile(&mut self, name: &str) -> bool {
+        name != self.profiles.active && self.profiles.stored.remove(name).is_some()
+    }
+
+    /// Write every profile to `dir`, creating it, and remove files of
+    /// deleted profiles
+    pub fn save_profiles(&self, dir: &Path) -> Result<(), SettingsError> {
+        fs::create_dir_all(dir).map_err(io_error)?;
+        for (name, text) in &self.profiles.stored {
+            if *name != self.profiles.active {
+                fs::write(dir.join(format!("{}.txt", name)), text).map_err(io_error)?;
+            }
+        }
+        let active = &self.profiles.active;
+        fs::write(dir.join(format!("{}.txt", active)), self.export_profile()).map_err(io_error)?;
+        fs::write(dir.join(ACTIVE_FILE), format!("{}\n", active)).map_err(io_error)?;
+
+        let names = self.profile_names();
+        for (name, path) in profile_files(dir)? {
+            if !names.contains(&name) {
+                fs::remove_file(path).map_err(io_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the profiles with those saved in `dir` and apply the active
+    /// one
+    ///
+    /// A missing directory leaves everything as is. Files with a name that
+    /// is not a valid profile are skipped; an unreadable or invalid profile
+    /// rejects the load before anything changes.
+    pub fn load_profiles(&mut self, dir: &Path) -> Result<(), SettingsError> {
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        let mut stored = BTreeMap::new();
+        for (name, path) in profile_files(dir)? {
+            let text = fs::read_to_string(&path).map_err(io_error)?;
+            settings::check(&text)?;
+            stored.insert(name, text);
+        }
+        let active = fs::read_to_string(dir.join(ACTIVE_FILE))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|name| stored.contains_key(name))
+            .unwrap_or_else(|| self.profiles.active.clone());
+        if let Some(text) = stored.get(&active) {
+            self.import_settings(text)?;
+        }
+        self.profiles = Profiles { active, stored };
+        Ok(())
+    }
+}
+
+/// (name, path) of the profile files in `dir`
+fn profile_files(dir: &Path) -> Result<Vec<(String, std::path::PathBuf)>, SettingsError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+            if valid_name(name) {
+                files.push((name.to_string(), path.clone()));
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_name() {
+        for name in ["work", "gaming", "Công việc", "a-b_c"] {
+            assert!(valid_name(name), "{}", name);
+        }
+        for name in ["", " work", "../x", "a/b", "a.txt", &"x".repeat(33)] {
+            assert!(!valid_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_switch_keeps_options_apart() {
+        let mut e = Engine::new();
+        e.switch_profile("gaming").unwrap();
+        e.set_method(1);
+        assert_eq!(e.profile_names(), ["default", "gaming"]);
+
+        e.switch_profile(DEFAULT_PROFILE).unwrap();
+        assert_eq!(e.method(), 0);
+        assert_eq!(e.cycle_profile(), "gaming");
+        assert_eq!(e.method(), 1);
+        assert_eq!(
+            e.take_events(),
+            [
+                Event::ProfileChanged("gaming".into()),
+                Event::ProfileChanged("default".into()),
+                Event::ProfileChanged("gaming".into()),
+            ]
+        );
+
+        assert!(!e.delete_profile("gaming"));
+        assert!(e.delete_profile("default"));
+        assert_eq!(e.profile_names(), ["gaming"]);
+        assert_eq!(
+            e.switch_profile("a/b"),
+            Err(SettingsError::InvalidProfile("a/b".into()))
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! Proper-noun capitalization - "hà nội" → "Hà Nội"
+//!
+//! At a word boundary the last few words are looked up in a list of
+//! Vietnamese place names plus names added by the host. A match gets the
+//! capitalization of the listed name; the typed letters are kept otherwise,
+//! so "khánh hoà" becomes "Khánh Hoà" whichever tone placement was used.
+//!
+//! Names that are also common phrases ("hòa bình", "hải dương") are left out
+//! of the built-in list. Off by default.
+
+use crate::data::chars;
+use crate::data::keys;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Longest name, in words
+pub const MAX_WORDS: usize = 5;
+
+/// Built-in names: places that do not double as common phrases
+const BUILTIN: &[&str] = &[
+    "Việt Nam",
+    "Hà Nội",
+    "Thành phố Hồ Chí Minh",
+    "Hồ Chí Minh",
+    "Sài Gòn",
+    "Đà Nẵng",
+    "Hải Phòng",
+    "Cần Thơ",
+    "Thừa Thiên Huế",
+    "Nha Trang",
+    "Đà Lạt",
+    "Vũng Tàu",
+    "Hạ Long",
+    "Phú Quốc",
+    "Tây Nguyên",
+    "Đông Nam Á",
+    "Mê Kông",
+    "Hoàn Kiếm",
+    "Tân Sơn Nhất",
+    "Nội Bài",
+    // Provinces
+    "An Giang",
+    "Bà Rịa",
+    "Bạc Liêu",
+    "Bắc Giang",
+    "Bắc Kạn",
+    "Bắc Ninh",
+    "Bến Tre",
+    "Bình Định",
+    "Bình Dương",
+    "Bình Phước",
+    "Bình Thuận",
+    "Cà Mau",
+    "Cao Bằng",
+    "Đắk Lắk",
+    "Đắk Nông",
+    "Điện Biên",
+    "Đồng Nai",
+    "Đồng Tháp",
+    "Gia Lai",
+    "Hà Giang",
+    "Hà Nam",
+    "Hà Tĩnh",
+    "Hậu Giang",
+    "Hưng Yên",
+    "Khánh Hòa",
+    "Kiên Giang",
+    "Kon Tum",
+    "Lai Châu",
+    "Lâm Đồng",
+    "Lạng Sơn",
+    "Lào Cai",
+    "Long An",
+    "Nam Định",
+    "Nghệ An",
+    "Ninh Bình",
+    "Ninh Thuận",
+    "Phú Thọ",
+    "Phú Yên",
+    "Quảng Bình",
+    "Quảng Nam",
+    "Quảng Ngãi",
+    "Quảng Ninh",
+    "Quảng Trị",
+    "Sóc Trăng",
+    "Sơn La",
+    "Tây Ninh",
+    "Thái Nguyên",
+    "Thanh Hóa",
+    "Tiền Giang",
+    "Trà Vinh",
+    "Tuyên Quang",
+    "Vĩnh Long",
+    "Vĩnh Phúc",
+    "Yên Bái",
+];
+
+/// Lookup form of a word: lowercase letters with modifiers, then the tone
+/// mark as a digit ("Hòa" and "hoà" → "hoa2")
+fn fold_word(word: &str) -> String {
+    let mut out = String::with_capacity(word.len() + 1);
+    let mut word_mark = 0;
+    for c in word.chars() {
+        match chars::parse_char(c) {
+            Some(p) => {
+                word_mark = word_mark.max(p.mark);
+                if p.key == keys::D && p.stroke {
+                    out.push(chars::get_d(false));
+                } else if let Some(base) = chars::to_char(p.key, false, p.tone, 0) {
+                    out.push(base);
+                } else {
+                    out.extend(c.to_lowercase());
+                }
+            }
+            None => out.extend(c.to_lowercase()),
+        }
+    }
+    if word_mark != 0 {
+        out.push(char::from(b'0' + word_mark));
+    }
+    out
+}
+
+fn fold(words: &[&str]) -> String {
+    words
+        .iter()
+        .map(|w| fold_word(w))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Folded name → which of its words start with a capital
+type NameTable = HashMap<String, Vec<bool>>;
+
+static BUILTIN_NAMES: LazyLock<NameTable> = LazyLock::new(|| {
+    let mut names = NameTable::new();
+    for name in BUILTIN {
+        insert(&mut names, name);
+    }
+    names
+});
+
+fn insert(names: &mut NameTable, name: &str) -> bool {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    if words.is_empty() || words.len() > MAX_WORDS {
+        return false;
+    }
+    let caps = words
+        .iter()
+        .map(|w| w.chars().next().is_some_and(char::is_uppercase))
+        .collect();
+    names.insert(fold(&words), caps);
+    true
+}
+
+/// Names added by the host, plus the on/off switch
+#[derive(Debug, Clone, Default)]
+pub struct ProperNouns {
+    enabled: bool,
+    names: NameTable,
+    /// Added names as written, in the order added
+    written: Vec<String>,
+}
+
+impl ProperNouns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Add a name as it should be written ("Nguyễn Văn An")
+    ///
+    /// Returns false (and adds nothing) when empty or longer than
+    /// `MAX_WORDS` words.
+    pub fn add(&mut self, name: &str) -> bool {
+        if !insert(&mut self.names, name) {
+            return false;
+        }
+        let words: Vec<&str> = name.split_whitespace().collect();
+        let key = fold(&words);
+        self.written.retain(|n| {
+            let w: Vec<&str> = n.split_whitespace().collect();
+            fold(&w) != key
+        });
+        self.written.push(words.join(" "));
+        true
+    }
+
+    /// Added names as written (built-in names not included)
+    pub fn names(&self) -> &[String] {
+        &self.written
+    }
+
+    /// Remove all added names
+    pub fn clear(&mut self) {
+        self.names.clear();
+        self.written.clear();
+    }
+
+    /// Number of names added (built-in names not counted)
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Capitalized form of the longest name ending the typed `words`
+    ///
+    /// Returns how many trailing words matched and their new spelling, or
+    /// None when nothing matches or the words are already capitalized.
+    pub fn capitalize(&self, words: &[String]) -> Option<(usize, Vec<String>)> {
+        if !self.enabled {
+            return None;
+        }
+        let longest = words.len().min(MAX_WORDS);
+        (1..=longest).rev().find_map(|n| {
+            let tail: Vec<&str> = words[words.len() - n..]
+                .iter()
+                .map(String::as_str)
+                .collect();
+            let key = fold(&tail);
+            let caps = self.names.get(&key).or_else(|| BUILTIN_NAMES.get(&key))?;
+            let fixed: Vec<String> = tail
+                .iter()
+                .zip(caps)
+                .map(|(word, &cap)| capitalize_word(word, cap))
+                .collect();
+            (fixed.iter().zip(&tail).any(|(f, t)| f != t)).then_some((n, fixed))
+        })
+    }
+}
+
+/// `word` with its first letter upper-cased (or left as typed)
+fn capitalize_word(word: &str, cap: bool) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) if cap => first.to_uppercase().chain(chars).collect(),
+        _ => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(s: &str) -> Vec<String> {
+        s.split(' ').map(String::from).collect()
+    }
+
+    #[test]
+    fn test_fold_ignores_tone_placement() {
+        assert_eq!(fold_word("Hòa"), fold_word("hoà"));
+        assert_eq!(fold_word("đà"), "đa2");
+        assert_ne!(fold_word("nội"), fold_word("nơi"));
+    }
+
+    #[test]
+    fn test_capitalize() {
+        let mut nouns = ProperNouns::new();
+        assert_eq!(nouns.capitalize(&words("hà nội")), None);
+        nouns.set_enabled(true);
+        assert_eq!(
+            nouns.capitalize(&words("ở hà nội")),
+            Some((2, words("Hà Nội")))
+        );
+        assert_eq!(
+            nouns.capitalize(&words("thành phố hồ chí minh")),
+            Some((5, words("Thành phố Hồ Chí Minh")))
+        );
+        assert_eq!(nouns.capitalize(&words("Hà Nội")), None);
+        assert_eq!(nouns.capitalize(&words("hòa bình")), None);
+    }
+
+    #[test]
+    fn test_add() {
+        let mut nouns = ProperNouns::new();
+        nouns.set_enabled(true);
+        assert!(nouns.add("Nguyễn Văn An"));
+        assert!(!nouns.add(" "));
+        assert_eq!(nouns.len(), 1);
+        assert_eq!(
+            nouns.capitalize(&words("nguyễn văn an")),
+            Some((3, words("Nguyễn Văn An")))
+        );
+    }
+}
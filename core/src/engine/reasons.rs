@@ -0,0 +1,58 @@
+//! Decision trace for the last key (`debug-trace` feature)
+//!
+//! Placement bugs ("kían" instead of "kián") are hard to triage from the
+//! output alone: was the mark key taken as a mark, which vowel did the
+//! placement rule pick, did validation reject the syllable, did the word
+//! get auto-restored? With the `debug-trace` feature the engine notes each
+//! of those decisions while handling a key, and `Engine::reasons` returns
+//! them until the next key. `Result` keeps its C layout; hosts read the
+//! notes with `ime_reasons`.
+//!
+//! Without the feature `reason!` compiles to nothing and its arguments are
+//! never evaluated.
+
+use super::Engine;
+
+/// Notes on the key being handled
+#[derive(Debug, Default)]
+pub(super) struct Reasons {
+    #[cfg(feature = "debug-trace")]
+    lines: Vec<String>,
+}
+
+impl Reasons {
+    /// Start a new key
+    pub(super) fn clear(&mut self) {
+        #[cfg(feature = "debug-trace")]
+        self.lines.clear();
+    }
+
+    #[cfg_attr(not(feature = "debug-trace"), allow(unused_variables))]
+    pub(super) fn push(&mut self, note: String) {
+        #[cfg(feature = "debug-trace")]
+        self.lines.push(note);
+    }
+}
+
+/// Note a decision: `reason!(self, "mark on {}", pos)`
+macro_rules! reason {
+    ($engine:expr, $($arg:tt)*) => {
+        if cfg!(feature = "debug-trace") {
+            $engine.reasons.push(format!($($arg)*));
+        }
+    };
+}
+
+pub(super) use reason;
+
+impl Engine {
+    /// Decisions behind the last key, in the order they were taken
+    ///
+    /// Always empty without the `debug-trace` feature.
+    pub fn reasons(&self) -> &[String] {
+        #[cfg(feature = "debug-trace")]
+        return &self.reasons.lines;
+        #[cfg(not(feature = "debug-trace"))]
+        &[]
+    }
+}
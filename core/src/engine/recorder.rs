@@ -0,0 +1,106 @@
+//! Macro recording: type an expansion once, save it as a shortcut
+//!
+//! Typing a long expansion into a settings field is slow, and multi-line
+//! templates (a signature, an email reply) are awkward there. Instead the
+//! host starts recording, the user types the text in any app the usual way,
+//! and when recording stops the host asks for a trigger and saves the text
+//! under it (`Engine::start_macro` / `stop_macro` / `save_macro`).
+//!
+//! What is recorded is the text the app ends up with, not the keys: tones,
+//! corrections and Backspace are already applied, and Enter and Tab are
+//! kept as line breaks and tabs. Keys that may move the caret (arrows, ESC,
+//! Ctrl shortcuts) are not followed, so the recording is only right when
+//! the text is typed straight through. Pasted text is added when the host
+//! passes it to `Engine::on_paste`.
+
+use super::shortcut::{Shortcut, MAX_REPLACEMENT_LEN};
+use super::{host_types_key, Action, Engine, Result};
+use crate::data::keys;
+
+/// Text typed since recording started, and the last finished recording
+#[derive(Debug, Default)]
+pub(super) struct MacroRecorder {
+    /// Some while recording
+    text: Option<Vec<char>>,
+    /// Last stopped recording, kept for `save_macro`
+    last: Option<String>,
+}
+
+impl MacroRecorder {
+    /// Follow what the host does with `result` for `key`, which types `ch`
+    /// when passed through
+    pub(super) fn record(&mut self, result: &Result, key: u16, shift: bool, ch: Option<char>) {
+        let Some(text) = self.text.as_mut() else {
+            return;
+        };
+        let passes = if result.action == Action::None as u8 {
+            !result.key_consumed()
+        } else {
+            let backspace = (result.backspace as usize).min(text.len());
+            text.truncate(text.len() - backspace);
+            let count = result.count as usize;
+            text.extend(
+                result.chars[..count]
+                    .iter()
+                    .filter_map(|&c| char::from_u32(c)),
+            );
+            host_types_key(result, key, shift)
+        };
+        if !passes {
+            return;
+        }
+        if key == keys::DELETE && result.action == Action::None as u8 {
+            text.pop();
+        } else if let Some(c) = ch {
+            text.push(c);
+        }
+        text.truncate(MAX_REPLACEMENT_LEN);
+    }
+
+    /// Add pasted text
+    pub(super) fn paste(&mut self, pasted: &str) {
+        if let Some(text) = self.text.as_mut() {
+            text.extend(pasted.chars());
+            text.truncate(MAX_REPLACEMENT_LEN);
+        }
+    }
+}
+
+impl Engine {
+    /// Start recording what the user types, dropping any recording in progress
+    pub fn start_macro(&mut self) {
+        self.recorder.text = Some(Vec::new());
+    }
+
+    pub fn macro_recording(&self) -> bool {
+        self.recorder.text.is_some()
+    }
+
+    /// Stop recording and return the text typed, None if not recording
+    ///
+    /// The text is kept for `save_macro` until the next recording. Text
+    /// past `MAX_REPLACEMENT_LEN` characters is dropped, as a shortcut could
+    /// not hold it.
+    pub fn stop_macro(&mut self) -> Option<String> {
+        let text: String = self.recorder.text.take()?.into_iter().collect();
+        self.recorder.last = Some(text.clone());
+        Some(text)
+    }
+
+    /// Save the last stopped recording as a shortcut for `trigger`
+    ///
+    /// Replaces a shortcut with the same trigger. Returns false when nothing
+    /// was recorded or the trigger is empty.
+    pub fn save_macro(&mut self, trigger: &str) -> bool {
+        let trigger = trigger.trim();
+        let Some(text) = self.recorder.last.as_deref() else {
+            return false;
+        };
+        if trigger.is_empty() || text.is_empty() {
+            return false;
+        }
+        let shortcut = Shortcut::auto(trigger, text);
+        self.shortcuts.add(shortcut);
+        true
+    }
+}
@@ -0,0 +1,243 @@
+//! What the app shows before the caret, for minimal replacements
+//!
+//! The engine rebuilds a word from the first changed position, so a
+//! result often deletes characters only to type them again ("hoa" + `f`
+//! in traditional style rewrites "oa" as "òa"). Slow apps show every
+//! deletion as flicker and sometimes drop events, so before a result goes
+//! out, the characters it would retype unchanged are taken off both ends:
+//! only the part of the word that really changes is replaced. Shortcut
+//! expansions and auto-restores at a word boundary still go out whole, as
+//! one unit the user can undo.
+//!
+//! That needs the text the app has, which the engine keeps as the tail of
+//! what it sent and the host typed since the last cursor move.
+//!
+//! The same text tells how many backspaces a deletion takes in apps that
+//! store it decomposed (some Electron apps): there "ấ" is "a" plus two
+//! combining marks, and each of them needs its own backspace.
+
+use super::{
+    host_types_key, typed_char, Action, Result, FLAG_CURSOR_MOVE, FLAG_DECOMPOSED, FLAG_SNIPPET,
+};
+use crate::data::{chars, keys};
+
+/// Characters kept; replacements never reach further back
+const KEEP: usize = 64;
+
+/// Known text right before the caret (may be shorter than the app's)
+#[derive(Debug, Default)]
+pub(super) struct ScreenTail {
+    chars: Vec<char>,
+    /// The app stores text decomposed: count backspaces in code points
+    decomposed: bool,
+}
+
+impl ScreenTail {
+    pub(super) fn set_decomposed(&mut self, decomposed: bool) {
+        self.decomposed = decomposed;
+    }
+
+    /// Whether the app shows `text` right before the caret
+    pub(super) fn ends_with(&self, text: &[char]) -> bool {
+        self.chars.ends_with(text)
+    }
+
+    /// Backspaces that delete `text`, counted in code points when the app
+    /// stores text decomposed
+    pub(super) fn backspaces(&self, text: &[char]) -> usize {
+        if !self.decomposed {
+            return text.len();
+        }
+        text.iter().map(|&c| chars::decomposed_len(c)).sum()
+    }
+
+    /// Replace the last `len` characters with `text` (checked with
+    /// `ends_with` first)
+    pub(super) fn replace_end(&mut self, len: usize, text: &[char]) {
+        self.chars.truncate(self.chars.len().saturating_sub(len));
+        self.chars.extend_from_slice(text);
+        if self.chars.len() > KEEP {
+            self.chars.drain(..self.chars.len() - KEEP);
+        }
+    }
+
+    /// Forget the text (caret moved or the app changed it)
+    pub(super) fn clear(&mut self) {
+        self.chars.clear();
+    }
+
+    /// Drop the unchanged prefix of a Send `result` if `trim`, then record
+    /// what the app shows once the host applied it for `key`
+    pub(super) fn apply(
+        &mut self,
+        result: &mut Result,
+        trim: bool,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) {
+        if ctrl || result.flags & (FLAG_CURSOR_MOVE | FLAG_SNIPPET) != 0 {
+            self.chars.clear();
+            return;
+        }
+        if result.action == Action::None as u8 {
+            if result.key_consumed() {
+                return;
+            }
+            match key {
+                keys::DELETE => {
+                    self.chars.pop();
+                }
+                keys::SPACE => self.chars.push(' '),
+                _ => match typed_char(key, caps, shift) {
+                    Some(c) => self.chars.push(c),
+                    None => self.chars.clear(),
+                },
+            }
+        } else {
+            if trim && result.action == Action::Send as u8 {
+                self.trim(result);
+            }
+            let backspace = result.backspace as usize;
+            if self.decomposed {
+                self.widen_backspace(result);
+            }
+            match self.chars.len().checked_sub(backspace) {
+                Some(keep) => self.chars.truncate(keep),
+                // Deleted text from before what is known
+                None => self.chars.clear(),
+            }
+            let count = result.count as usize;
+            self.chars.extend(
+                result.chars[..count]
+                    .iter()
+                    .filter_map(|&c| char::from_u32(c)),
+            );
+            if host_types_key(result, key, shift) {
+                match typed_char(key, caps, shift) {
+                    Some(c) => self.chars.push(c),
+                    None => self.chars.clear(),
+                }
+            }
+        }
+        if self.chars.len() > KEEP {
+            self.chars.drain(..self.chars.len() - KEEP);
+        }
+    }
+
+    /// Count the backspaces of `result` in code points of the decomposed
+    /// text it deletes, flagging it when that takes more
+    ///
+    /// Characters from before the known text count as one.
+    fn widen_backspace(&self, result: &mut Result) {
+        let backspace = result.backspace as usize;
+        let known = backspace.min(self.chars.len());
+        let marks: usize = self.chars[self.chars.len() - known..]
+            .iter()
+            .map(|&c| chars::decomposed_len(c) - 1)
+            .sum();
+        if marks > 0 {
+            result.backspace = (backspace + marks).min(u8::MAX as usize) as u8;
+            result.flags |= FLAG_DECOMPOSED;
+        }
+    }
+
+    /// Leave out the characters `result` deletes and types back unchanged
+    fn trim(&self, result: &mut Result) {
+        let backspace = result.backspace as usize;
+        let Some(start) = self.chars.len().checked_sub(backspace) else {
+            return;
+        };
+        let count = result.count as usize;
+        let same = self.chars[start..]
+            .iter()
+            .zip(&result.chars[..count])
+            .take_while(|(&shown, &new)| shown as u32 == new)
+            .count();
+        if same == 0 {
+            return;
+        }
+        result.chars.copy_within(same..count, 0);
+        result.chars[count - same..count].fill(0);
+        result.count -= same as u8;
+        result.backspace -= same as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tail(text: &str) -> ScreenTail {
+        ScreenTail {
+            chars: text.chars().collect(),
+            decomposed: false,
+        }
+    }
+
+    fn sent(r: &Result) -> (u8, String) {
+        let text = r.chars[..r.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        (r.backspace, text)
+    }
+
+    #[test]
+    fn test_trims_known_text_only() {
+        let chars: Vec<char> = "natat".chars().collect();
+
+        let mut screen = tail("nât");
+        let mut r = Result::send(3, &chars);
+        screen.apply(&mut r, true, keys::T, false, false, false);
+        assert_eq!(sent(&r), (2, "atat".into()));
+        assert_eq!(screen.chars, chars);
+
+        // Deletes reach before the known text: sent as is
+        let mut screen = tail("ât");
+        let mut r = Result::send(3, &chars);
+        screen.apply(&mut r, true, keys::T, false, false, false);
+        assert_eq!(sent(&r), (3, "natat".into()));
+
+        let mut screen = tail("nât");
+        let mut r = Result::send(3, &chars);
+        screen.apply(&mut r, false, keys::T, false, false, false);
+        assert_eq!(sent(&r), (3, "natat".into()));
+    }
+
+    #[test]
+    fn test_decomposed_backspace() {
+        let chars: Vec<char> = "viết".chars().collect();
+        let mut screen = tail("việt");
+        screen.set_decomposed(true);
+        let mut r = Result::send(2, &['ế', 't']);
+        screen.apply(&mut r, false, keys::S, false, false, false);
+        // "ệ" is e + U+0302 + U+0323
+        assert_eq!(sent(&r), (4, "ết".into()));
+        assert_eq!(r.flags, FLAG_DECOMPOSED);
+        assert_eq!(screen.chars, chars);
+
+        // Only the unchanged "vi" is trimmed; plain letters need no more
+        let mut screen = tail("vie");
+        screen.set_decomposed(true);
+        let mut r = Result::send(1, &['e']);
+        screen.apply(&mut r, false, keys::E, false, false, false);
+        assert_eq!((r.backspace, r.flags), (1, 0));
+    }
+
+    #[test]
+    fn test_follows_typed_keys() {
+        let mut screen = tail("ab");
+        let mut r = Result::none();
+        screen.apply(&mut r, true, keys::C, false, false, false);
+        screen.apply(&mut r, true, keys::SPACE, false, false, false);
+        assert_eq!(screen.chars, ['a', 'b', 'c', ' ']);
+        screen.apply(&mut r, true, keys::DELETE, false, false, false);
+        assert_eq!(screen.chars, ['a', 'b', 'c']);
+
+        // The caret moved: nothing is known
+        screen.apply(&mut r, true, keys::LEFT, false, false, false);
+        assert!(screen.chars.is_empty());
+    }
+}
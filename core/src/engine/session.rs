@@ -0,0 +1,218 @@
+//! Session traces: record what the host sent, replay it in a test
+//!
+//! A user who hits a bug turns tracing on, reproduces it and attaches the
+//! trace (`Engine::start_session_trace` / `stop_session_trace`). The trace
+//! is the settings file at the start (see `engine::settings`) followed by
+//! a `[trace]` section with one host call per line:
+//!
+//! ```text
+//! [trace]
+//! app firefox
+//! key 17 shift
+//! repeat 0
+//! char 9 u+221a
+//! paste xin chào\n
+//! undo
+//! clear_all
+//! ```
+//!
+//! `key` and `repeat` are a keycode and the modifiers set (`caps`, `ctrl`,
+//! `shift`); `char` adds the character passed to `on_key_with_char`.
+//! `paste`, `app` and the other calls match the engine methods, with text
+//! escaped as in settings files; `paste` and `app` without text pass None.
+//!
+//! `Engine::replay` imports the settings and makes the same calls on the
+//! engine while a `testkit::Screen` follows the results, then returns the
+//! text on that screen. Only calls made from outside the engine are
+//! recorded, so replay is deterministic apart from `%date%` placeholders
+//! and slow word hooks. Option changes made after the trace started, and
+//! what the user typed outside the engine (mouse, app autocorrect), are
+//! not in the trace.
+//!
+//! The trace holds everything typed while it runs, so it is off by
+//! default and kept in memory only.
+
+use super::settings::{self, SettingsError};
+use super::Engine;
+use crate::testkit::Screen;
+
+/// Marks the start of the events in a trace
+pub const SECTION: &str = "[trace]";
+
+/// Events kept in one trace; later ones are dropped
+pub const MAX_EVENTS: usize = 100_000;
+
+/// The trace being recorded
+#[derive(Debug, Default)]
+pub(super) struct SessionTrace {
+    /// Some while recording
+    text: Option<String>,
+    events: usize,
+    /// Traced calls in progress; calls made by the engine itself while
+    /// handling one are not recorded
+    depth: u32,
+}
+
+/// Modifiers of a key line, each with a leading space
+fn modifiers(caps: bool, ctrl: bool, shift: bool) -> String {
+    [(caps, " caps"), (ctrl, " ctrl"), (shift, " shift")]
+        .iter()
+        .filter(|&&(on, _)| on)
+        .map(|&(_, name)| name)
+        .collect()
+}
+
+pub(super) fn key_event(key: u16, caps: bool, ctrl: bool, shift: bool) -> String {
+    format!("key {}{}", key, modifiers(caps, ctrl, shift))
+}
+
+pub(super) fn repeat_event(key: u16, caps: bool, ctrl: bool, shift: bool) -> String {
+    format!("repeat {}{}", key, modifiers(caps, ctrl, shift))
+}
+
+pub(super) fn char_event(key: u16, caps: bool, ctrl: bool, shift: bool, ch: char) -> String {
+    let ch = ch as u32;
+    format!("char {} u+{:x}{}", key, ch, modifiers(caps, ctrl, shift))
+}
+
+/// Line for a call with optional text (`paste`, `app`)
+pub(super) fn text_event(name: &str, text: Option<&str>) -> String {
+    match text {
+        Some(text) => format!("{} {}", name, settings::escape(text)),
+        None => name.to_string(),
+    }
+}
+
+impl Engine {
+    /// Run `f` as the host call described by `event`, recording it when a
+    /// trace is running and the engine is not already inside a call
+    pub(super) fn traced<T>(
+        &mut self,
+        event: impl FnOnce() -> String,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let trace = &mut self.session;
+        if trace.depth == 0 && trace.events < MAX_EVENTS {
+            if let Some(text) = trace.text.as_mut() {
+                text.push_str(&event());
+                text.push('\n');
+                trace.events += 1;
+            }
+        }
+        trace.depth += 1;
+        let result = f(self);
+        self.session.depth -= 1;
+        result
+    }
+
+    /// Start recording a trace, dropping any trace in progress
+    pub fn start_session_trace(&mut self) {
+        let mut text = self.export_settings();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(SECTION);
+        text.push('\n');
+        self.session.text = Some(text);
+        self.session.events = 0;
+    }
+
+    pub fn session_tracing(&self) -> bool {
+        self.session.text.is_some()
+    }
+
+    /// Stop recording and return the trace, None if not recording
+    pub fn stop_session_trace(&mut self) -> Option<String> {
+        self.session.events = 0;
+        self.session.text.take()
+    }
+
+    /// Replay a trace on this engine and return the text the app shows
+    /// afterwards, starting from an empty app
+    ///
+    /// The trace's settings replace the engine's (as `import_settings`).
+    /// Use a new engine for a deterministic result. Fails on a trace
+    /// without settings or with a line that cannot be read.
+    pub fn replay(&mut self, trace: &str) -> Result<String, SettingsError> {
+        let Some(start) = trace.find(&format!("\n{}\n", SECTION)) else {
+            return Err(SettingsError::NotSettings);
+        };
+        self.import_settings(&trace[..start + 1])?;
+        let first_line = trace[..start + 1].lines().count() + 2;
+        let mut screen = Screen::new();
+        for (i, line) in trace[start + SECTION.len() + 2..].lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                continue;
+            }
+            replay_line(self, &mut screen, line).ok_or(SettingsError::Malformed(first_line + i))?;
+        }
+        Ok(screen.text())
+    }
+}
+
+/// Make the call one trace line records, None if it cannot be read
+fn replay_line(e: &mut Engine, screen: &mut Screen, line: &str) -> Option<()> {
+    let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+    match name {
+        "key" | "repeat" | "char" => {
+            let mut words = rest.split(' ');
+            let key: u16 = words.next()?.parse().ok()?;
+            let ch = if name == "char" {
+                let hex = words.next()?.strip_prefix("u+")?;
+                Some(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?)
+            } else {
+                None
+            };
+            let (mut caps, mut ctrl, mut shift) = (false, false, false);
+            for word in words {
+                match word {
+                    "caps" => caps = true,
+                    "ctrl" => ctrl = true,
+                    "shift" => shift = true,
+                    _ => return None,
+                }
+            }
+            match (name, ch) {
+                ("repeat", _) => screen.repeat(e, key, caps, ctrl, shift),
+                (_, Some(ch)) => screen.press_char(e, key, caps, ctrl, shift, ch),
+                _ => screen.press(e, key, caps, ctrl, shift),
+            };
+        }
+        "paste" | "app" => {
+            let text = if line == name {
+                None
+            } else {
+                Some(settings::unescape(rest)?)
+            };
+            if name == "paste" {
+                screen.paste(e, text.as_deref());
+            } else {
+                e.set_active_app(text.as_deref());
+            }
+        }
+        "undo" => {
+            screen.undo(e);
+        }
+        "clear" => e.clear(),
+        "clear_all" => e.clear_all(),
+        "select_all" => e.on_select_all(),
+        "text_changed" => e.on_text_changed(),
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_lines() {
+        assert_eq!(key_event(0, false, false, false), "key 0");
+        assert_eq!(repeat_event(17, true, false, true), "repeat 17 caps shift");
+        assert_eq!(char_event(9, false, true, false, '√'), "char 9 u+221a ctrl");
+        assert_eq!(text_event("paste", Some("a\tb")), "paste a\\tb");
+        assert_eq!(text_event("app", None), "app");
+    }
+}
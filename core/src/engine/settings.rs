@@ -0,0 +1,849 @@
+//! Settings bundle: everything the user configured, in one portable file
+//!
+//! Moving to a new machine should keep the gõ tắt and exceptions, so the
+//! options, shortcuts, English exception words, proper nouns, chat
+//! abbreviations, auto-capitalize rules, hotkeys and learned corrections
+//! are exported
+//! together as UTF-8 text:
+//!
+//! ```text
+//! # gonhanh settings 1
+//! [options]
+//! method 0
+//! free_tone false
+//! remove_key_telex 6
+//! [shortcuts]
+//! ko⇥không
+//! ->⇥→⇥when=immediate
+//! kg⇥kính gửi\n⇥only=outlook.exe
+//! [english_words]
+//! expect
+//! [proper_nouns]
+//! Nguyễn Văn An
+//! [teencode]
+//! vcl
+//! [capitalize_rules]
+//! . 2
+//! [hotkeys]
+//! cycle_method 46 ctrl shift
+//! [terminal_apps]
+//! 1 windowsterminal
+//! [app_enabled]
+//! 0 idea64
+//! [learning]
+//! 2 0 user
+//! ```
+//!
+//! Options are `key value` lines (see `OPTIONS`); enums and keycodes use
+//! their FFI values. A shortcut line is trigger, replacement and optional
+//! attributes separated by tabs (⇥ above), with `\t`, `\n`, `\r` and
+//! `\\` escaped. A terminal app line is the mode, a space and the app id;
+//! an app enabled line is 0 (off) or 1 (on), a space and the app id.
+//!
+//! Importing sets the listed options and replaces each table whose
+//! section is present. Unknown options and sections are skipped, so files
+//! from newer versions still load; an invalid value rejects the whole file
+//! before anything changes. Options locked by the managed config
+//! (`crate::managed`) are never changed by an import.
+
+use super::capitalize::CapitalizeRule;
+use super::hotkey::{Hotkey, HotkeyAction, Hotkeys};
+use super::shortcut::{AppScope, CaseMode, InputMethod, Shortcut, TriggerCondition};
+use super::terminal::TerminalMode;
+use super::validation::Strictness;
+use super::{DigitPolicy, Engine, RepeatPolicy, RevertPolicy, ToneStyle, RESTORE_ON_ALL};
+use crate::data::dictionary::VARIANT_ALL;
+use crate::json;
+use crate::managed;
+use std::fs;
+
+/// First line of a settings file (format version 1)
+pub const HEADER: &str = "# gonhanh settings 1";
+
+/// Value type of an option
+#[derive(Clone, Copy)]
+enum Kind {
+    /// "true" / "false"
+    Bool,
+    /// 0 up to the given maximum
+    Number(u8),
+    /// Keycode
+    Key,
+    /// Keycode or "none"
+    OptionalKey,
+}
+
+/// Exported options, in file order
+const OPTIONS: &[(&str, Kind)] = &[
+    ("method", Kind::Number(3)),
+    ("tone_style", Kind::Number(1)),
+    ("free_tone", Kind::Bool),
+    ("spell_check", Kind::Bool),
+    ("skip_w_shortcut", Kind::Bool),
+    ("initial_w_literal", Kind::Bool),
+    ("bracket_shortcut", Kind::Bool),
+    ("esc_restore", Kind::Bool),
+    ("restore_key", Kind::Key),
+    ("remove_key_telex", Kind::OptionalKey),
+    ("remove_key_vni", Kind::OptionalKey),
+    ("english_auto_restore", Kind::Bool),
+    ("auto_restore_triggers", Kind::Number(RESTORE_ON_ALL)),
+    ("backspace_undo_restore", Kind::Bool),
+    ("hyphen_soft_boundary", Kind::Bool),
+    ("url_passthrough", Kind::Bool),
+    ("digit_policy", Kind::Number(1)),
+    ("revert_policy", Kind::Number(1)),
+    ("repeat_policy", Kind::Number(1)),
+    ("symbol_pack", Kind::Bool),
+    ("unicode_input", Kind::Bool),
+    ("auto_capitalize", Kind::Bool),
+    ("title_case", Kind::Bool),
+    ("allow_foreign_consonants", Kind::Bool),
+    ("strictness", Kind::Number(2)),
+    ("spelling_variants", Kind::Number(VARIANT_ALL)),
+    ("spelling_autofix", Kind::Bool),
+    ("proper_noun_capitalize", Kind::Bool),
+    ("teencode_passthrough", Kind::Bool),
+    ("caps_lock_recase", Kind::Bool),
+    ("auto_english_switch", Kind::Number(u8::MAX)),
+    ("english_capital_i", Kind::Bool),
+    ("repeated_word_check", Kind::Bool),
+    ("auto_detect_method", Kind::Bool),
+    ("auto_tone_style", Kind::Bool),
+    ("stats", Kind::Bool),
+    ("learning", Kind::Bool),
+    ("terminal_mode", Kind::Number(2)),
+    ("per_app_enabled", Kind::Bool),
+    ("word_hooks", Kind::Bool),
+];
+
+/// Names of the exported options, in file order
+pub fn option_keys() -> impl Iterator<Item = &'static str> {
+    OPTIONS.iter().map(|&(key, _)| key)
+}
+
+/// Why settings could not be applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsError {
+    /// File could not be read or written
+    Io(String),
+    /// First line is not `HEADER`
+    NotSettings,
+    /// No option with this name
+    UnknownOption(String),
+    /// Value out of range for the option: (key, value)
+    InvalidValue(String, String),
+    /// Line that cannot be read (1-based)
+    Malformed(usize),
+    /// Profile name that cannot be a file name (see `engine::profiles`)
+    InvalidProfile(String),
+    /// Option locked by the managed config (see `crate::managed`)
+    Locked(String),
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::Io(msg) => write!(f, "{}", msg),
+            SettingsError::NotSettings => write!(f, "not a settings file"),
+            SettingsError::UnknownOption(key) => write!(f, "unknown option: {}", key),
+            SettingsError::InvalidValue(key, value) => {
+                write!(f, "invalid value for {}: {:?}", key, value)
+            }
+            SettingsError::Malformed(line) => write!(f, "line {}: cannot read", line),
+            SettingsError::InvalidProfile(name) => write!(f, "invalid profile name: {:?}", name),
+            SettingsError::Locked(key) => write!(f, "locked by managed config: {}", key),
+        }
+    }
+}
+
+enum Value {
+    Bool(bool),
+    Number(u8),
+    Key(Option<u16>),
+}
+
+fn parse_value(kind: Kind, text: &str) -> Option<Value> {
+    let key = |text: &str| text.parse::<u16>().ok().filter(|&k| k != u16::MAX);
+    match kind {
+        Kind::Bool => match text {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        Kind::Number(max) => text
+            .parse::<u8>()
+            .ok()
+            .filter(|&n| n <= max)
+            .map(Value::Number),
+        Kind::Key => key(text).map(|k| Value::Key(Some(k))),
+        Kind::OptionalKey if text == "none" => Some(Value::Key(None)),
+        Kind::OptionalKey => key(text).map(|k| Value::Key(Some(k))),
+    }
+}
+
+/// Whether `value` is valid for option `key`, without setting it
+pub fn check_option(key: &str, value: &str) -> Result<(), SettingsError> {
+    let kind = option_kind(key).ok_or_else(|| SettingsError::UnknownOption(key.into()))?;
+    match parse_value(kind, value.trim()) {
+        Some(_) => Ok(()),
+        None => Err(SettingsError::InvalidValue(key.into(), value.into())),
+    }
+}
+
+fn option_kind(key: &str) -> Option<Kind> {
+    OPTIONS
+        .iter()
+        .find(|&&(k, _)| k == key)
+        .map(|&(_, kind)| kind)
+}
+
+/// A parsed settings file; `None` tables were not in the file
+#[derive(Default)]
+struct Settings {
+    options: Vec<(String, String)>,
+    shortcuts: Option<Vec<Shortcut>>,
+    english_words: Option<Vec<String>>,
+    proper_nouns: Option<Vec<String>>,
+    teencode: Option<Vec<String>>,
+    capitalize_rules: Option<Vec<(char, CapitalizeRule)>>,
+    hotkeys: Option<Vec<(HotkeyAction, Hotkey)>>,
+    terminal_apps: Option<Vec<(String, TerminalMode)>>,
+    app_enabled: Option<Vec<(String, bool)>>,
+    learning: Option<String>,
+}
+
+impl Engine {
+    /// Current value of option `key` as written in settings files
+    pub fn option(&self, key: &str) -> Option<String> {
+        let key_text = |k: Option<u16>| k.map_or("none".to_string(), |k| k.to_string());
+        let value = match key {
+            "method" => self.method().to_string(),
+            "tone_style" => (self.tone_style as u8).to_string(),
+            "free_tone" => self.free_tone_enabled.to_string(),
+            "spell_check" => self.spell_check.to_string(),
+            "skip_w_shortcut" => self.skip_w_shortcut.to_string(),
+            "initial_w_literal" => self.initial_w_literal.to_string(),
+            "bracket_shortcut" => self.bracket_shortcut.to_string(),
+            "esc_restore" => self.esc_restore_enabled.to_string(),
+            "restore_key" => self.restore_key.to_string(),
+            "remove_key_telex" => key_text(self.remove_keys[0]),
+            "remove_key_vni" => key_text(self.remove_keys[1]),
+            "english_auto_restore" => self.english_auto_restore.to_string(),
+            "auto_restore_triggers" => self.auto_restore_triggers.to_string(),
+            "backspace_undo_restore" => self.backspace_undo_restore.to_string(),
+            "hyphen_soft_boundary" => self.hyphen_soft_boundary.to_string(),
+            "url_passthrough" => self.url_passthrough.to_string(),
+            "digit_policy" => (self.digit_policy as u8).to_string(),
+            "revert_policy" => (self.revert_policy as u8).to_string(),
+            "repeat_policy" => (self.repeat_policy as u8).to_string(),
+            "symbol_pack" => self.symbol_pack.to_string(),
+            "unicode_input" => self.unicode_input.to_string(),
+            "auto_capitalize" => self.auto_capitalize.to_string(),
+            "title_case" => self.title_case.to_string(),
+            "allow_foreign_consonants" => self.allow_foreign_consonants.to_string(),
+            "strictness" => (self.strictness as u8).to_string(),
+            "spelling_variants" => self.spelling_variants.to_string(),
+            "spelling_autofix" => self.spelling_autofix.to_string(),
+            "proper_noun_capitalize" => self.proper_nouns.enabled().to_string(),
+            "teencode_passthrough" => self.teencode.enabled().to_string(),
+            "caps_lock_recase" => self.caps_lock_recase.to_string(),
+            "auto_english_switch" => self.auto_english_words.to_string(),
+            "english_capital_i" => self.english_capital_i.to_string(),
+            "repeated_word_check" => self.repeated_word_check.to_string(),
+            "auto_detect_method" => self.auto_detect_method.to_string(),
+            "auto_tone_style" => self.auto_tone_style.to_string(),
+            "stats" => self.stats.enabled().to_string(),
+            "learning" => self.learning.enabled().to_string(),
+            "terminal_mode" => (self.terminal_mode as u8).to_string(),
+            "per_app_enabled" => self.per_app_enabled.to_string(),
+            "word_hooks" => self.word_hooks().to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Set option `key` from its settings-file text ("true", "2", "none")
+    ///
+    /// Options locked by the managed config are refused.
+    pub fn set_option(&mut self, key: &str, value: &str) -> Result<(), SettingsError> {
+        if managed::is_locked(key) {
+            return Err(SettingsError::Locked(key.into()));
+        }
+        self.apply_option(key, value)
+    }
+
+    /// Set the options locked by the managed config to their values
+    pub fn apply_managed(&mut self) {
+        let Some(managed) = managed::current() else {
+            return;
+        };
+        for (key, value) in &managed.options {
+            // Checked when the managed config was parsed
+            let _ = self.apply_option(key, value);
+        }
+    }
+
+    fn apply_option(&mut self, key: &str, value: &str) -> Result<(), SettingsError> {
+        let kind = option_kind(key).ok_or_else(|| SettingsError::UnknownOption(key.into()))?;
+        let parsed = parse_value(kind, value.trim())
+            .ok_or_else(|| SettingsError::InvalidValue(key.into(), value.into()))?;
+        match parsed {
+            Value::Bool(on) => match key {
+                "free_tone" => self.set_free_tone(on),
+                "spell_check" => self.set_spell_check(on),
+                "skip_w_shortcut" => self.set_skip_w_shortcut(on),
+                "initial_w_literal" => self.set_initial_w_literal(on),
+                "bracket_shortcut" => self.set_bracket_shortcut(on),
+                "esc_restore" => self.set_esc_restore(on),
+                "english_auto_restore" => self.set_english_auto_restore(on),
+                "backspace_undo_restore" => self.set_backspace_undo_restore(on),
+                "hyphen_soft_boundary" => self.set_hyphen_soft_boundary(on),
+                "url_passthrough" => self.set_url_passthrough(on),
+                "symbol_pack" => self.set_symbol_pack(on),
+                "unicode_input" => self.set_unicode_input(on),
+                "auto_capitalize" => self.set_auto_capitalize(on),
+                "title_case" => self.set_title_case(on),
+                "allow_foreign_consonants" => self.set_allow_foreign_consonants(on),
+                "spelling_autofix" => self.set_spelling_autofix(on),
+                "proper_noun_capitalize" => self.set_proper_noun_capitalize(on),
+                "teencode_passthrough" => self.set_teencode_passthrough(on),
+                "caps_lock_recase" => self.set_caps_lock_recase(on),
+                "english_capital_i" => self.set_english_capital_i(on),
+                "repeated_word_check" => self.set_repeated_word_check(on),
+                "auto_detect_method" => self.set_auto_detect_method(on),
+                "auto_tone_style" => self.set_auto_tone_style(on),
+                "per_app_enabled" => self.set_per_app_enabled(on),
+                "word_hooks" => self.set_word_hooks(on),
+                "stats" => self.stats.set_enabled(on),
+                "learning" => self.learning.set_enabled(on),
+                _ => {}
+            },
+            Value::Number(n) => match key {
+                "method" => self.set_method(n),
+                "tone_style" => self.set_tone_style(ToneStyle::from_u8(n)),
+                "auto_restore_triggers" => self.set_auto_restore_triggers(n),
+                "digit_policy" => self.set_digit_policy(DigitPolicy::from_u8(n)),
+                "revert_policy" => self.set_revert_policy(RevertPolicy::from_u8(n)),
+                "repeat_policy" => self.set_repeat_policy(RepeatPolicy::from_u8(n)),
+                "strictness" => self.set_strictness(Strictness::from_u8(n)),
+                "spelling_variants" => self.set_spelling_variants(n),
+                "auto_english_switch" => self.set_auto_english_switch(n),
+                "terminal_mode" => self.set_terminal_mode(TerminalMode::from_u8(n)),
+                _ => {}
+            },
+            Value::Key(k) => match key {
+                "restore_key" => self.set_restore_key(k.unwrap_or(self.restore_key)),
+                "remove_key_telex" => self.set_remove_key(0, k),
+                "remove_key_vni" => self.set_remove_key(1, k),
+                _ => {}
+            },
+        }
+        Ok(())
+    }
+
+    /// The settings file for the current configuration
+    pub fn export_settings(&self) -> String {
+        let mut out = self.export_profile();
+
+        out.push_str("[english_words]\n");
+        let mut words: Vec<&String> = self.english_words.iter().collect();
+        words.sort();
+        for word in words {
+            out.push_str(&format!("{}\n", word));
+        }
+
+        out.push_str("[proper_nouns]\n");
+        for name in self.proper_nouns.names() {
+            out.push_str(&format!("{}\n", name));
+        }
+
+        out.push_str("[teencode]\n");
+        for word in self.teencode.words() {
+            out.push_str(&format!("{}\n", word));
+        }
+
+        out.push_str("[capitalize_rules]\n");
+        for (c, rule) in self.capitalize_rules.iter() {
+            out.push_str(&format!("{} {}\n", escape(&c.to_string()), rule as u8));
+        }
+
+        out.push_str("[hotkeys]\n");
+        for &action in HotkeyAction::ALL {
+            if let Some(h) = self.hotkeys.get(action) {
+                out.push_str(&format!("{} {}", action.name(), h.key));
+                if h.ctrl {
+                    out.push_str(" ctrl");
+                }
+                if h.shift {
+                    out.push_str(" shift");
+                }
+                out.push('\n');
+            }
+        }
+
+        out.push_str("[terminal_apps]\n");
+        for (app, mode) in self.terminal_apps() {
+            out.push_str(&format!("{} {}\n", mode as u8, app));
+        }
+
+        out.push_str("[app_enabled]\n");
+        for (app, enabled) in self.app_enabled_states() {
+            out.push_str(&format!("{} {}\n", u8::from(enabled), app));
+        }
+
+        out.push_str("[learning]\n");
+        out.push_str(&self.learning.to_text());
+        out
+    }
+
+    /// The settings file with only the options and shortcuts, the part
+    /// that differs between profiles (see `engine::profiles`)
+    pub fn export_profile(&self) -> String {
+        let mut out = format!("{}\n[options]\n", HEADER);
+        for key in option_keys() {
+            if let Some(value) = self.option(key) {
+                out.push_str(&format!("{} {}\n", key, value));
+            }
+        }
+
+        out.push_str("[shortcuts]\n");
+        for s in self.shortcuts.iter() {
+            out.push_str(&shortcut_line(s));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// The options as one JSON object, typed by option: booleans,
+    /// numbers, and null for an unset optional key
+    ///
+    /// `{"method":0,"tone_style":1,"free_tone":false,...,"remove_key_vni":null}`
+    pub fn config_json(&self) -> String {
+        let members: Vec<String> = OPTIONS
+            .iter()
+            .filter_map(|&(key, kind)| {
+                let value = self.option(key)?;
+                let value = match kind {
+                    Kind::OptionalKey if value == "none" => "null".to_string(),
+                    _ => value,
+                };
+                Some(format!("\"{}\":{}", key, value))
+            })
+            .collect();
+        format!("{{{}}}", members.join(","))
+    }
+
+    /// Apply a JSON object of options as written by `config_json`
+    ///
+    /// Only the listed options change. Strings are accepted in place of
+    /// booleans and numbers ("true", "2"). As with a settings file,
+    /// unknown keys are skipped, locked options keep their values, and an
+    /// invalid value rejects the whole object before anything changes.
+    pub fn set_config_json(&mut self, text: &str) -> Result<(), SettingsError> {
+        let doc = json::parse(text).ok_or(SettingsError::NotSettings)?;
+        self.set_config(&doc)
+    }
+
+    /// `set_config_json` with the JSON already parsed
+    pub fn set_config(&mut self, doc: &json::Value) -> Result<(), SettingsError> {
+        let json::Value::Object(members) = doc else {
+            return Err(SettingsError::NotSettings);
+        };
+        let mut options = Vec::new();
+        for (key, value) in members {
+            let text = match value {
+                json::Value::Null => "none".to_string(),
+                json::Value::Bool(b) => b.to_string(),
+                json::Value::Number(n) => value.as_u64().map_or(n.to_string(), |n| n.to_string()),
+                json::Value::String(s) => s.clone(),
+                json::Value::Array(_) => "[...]".to_string(),
+                json::Value::Object(_) => "{...}".to_string(),
+            };
+            match check_option(key, &text) {
+                Ok(()) if !managed::is_locked(key) => options.push((key, text)),
+                Ok(()) | Err(SettingsError::UnknownOption(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        for (key, text) in options {
+            self.apply_option(key, &text)?;
+        }
+        Ok(())
+    }
+
+    /// Apply a settings file (see module docs); nothing changes on error
+    ///
+    /// Options locked by the managed config keep their values.
+    pub fn import_settings(&mut self, text: &str) -> Result<(), SettingsError> {
+        let settings = parse(text)?;
+        for (key, value) in &settings.options {
+            if !managed::is_locked(key) {
+                self.apply_option(key, value)?;
+            }
+        }
+        if let Some(shortcuts) = settings.shortcuts {
+            self.shortcuts.clear();
+            for s in shortcuts {
+                self.shortcuts.add(s);
+            }
+        }
+        if let Some(words) = settings.english_words {
+            self.clear_english_words();
+            for word in words {
+                self.add_english_word(&word);
+            }
+        }
+        if let Some(names) = settings.proper_nouns {
+            self.proper_nouns.clear();
+            for name in names {
+                self.proper_nouns.add(&name);
+            }
+        }
+        if let Some(words) = settings.teencode {
+            self.teencode.clear();
+            for word in words {
+                self.teencode.add(&word);
+            }
+        }
+        if let Some(rules) = settings.capitalize_rules {
+            self.capitalize_rules.reset();
+            for (c, rule) in rules {
+                self.capitalize_rules.set(c, rule);
+            }
+        }
+        if let Some(hotkeys) = settings.hotkeys {
+            self.hotkeys = Hotkeys::new();
+            for (action, hotkey) in hotkeys {
+                self.hotkeys.bind(action, Some(hotkey));
+            }
+        }
+        if let Some(apps) = settings.terminal_apps {
+            self.terminal_apps.clear();
+            for (app, mode) in apps {
+                self.set_app_terminal_mode(&app, Some(mode));
+            }
+        }
+        if let Some(apps) = settings.app_enabled {
+            self.app_enabled.clear();
+            for (app, enabled) in apps {
+                self.set_app_enabled(&app, Some(enabled));
+            }
+        }
+        if let Some(learning) = settings.learning {
+            self.learning.reset();
+            self.learning.load_text(&learning);
+        }
+        self.clear();
+        Ok(())
+    }
+
+    /// Write `export_settings()` to `path`
+    pub fn save_settings(&self, path: &str) -> Result<(), SettingsError> {
+        fs::write(path, self.export_settings()).map_err(|err| SettingsError::Io(err.to_string()))
+    }
+
+    /// Read `path` and `import_settings()` it
+    pub fn load_settings(&mut self, path: &str) -> Result<(), SettingsError> {
+        let text = fs::read_to_string(path).map_err(|err| SettingsError::Io(err.to_string()))?;
+        self.import_settings(&text)
+    }
+}
+
+/// Whether `text` would import, without applying it
+pub fn check(text: &str) -> Result<(), SettingsError> {
+    parse(text).map(|_| ())
+}
+
+fn parse(text: &str) -> Result<Settings, SettingsError> {
+    let mut lines = text.lines().enumerate();
+    match lines.next() {
+        Some((_, first)) if first.trim_start_matches('\u{feff}').trim_end() == HEADER => {}
+        _ => return Err(SettingsError::NotSettings),
+    }
+    let mut settings = Settings::default();
+    let mut section: Option<&str> = None;
+    for (i, line) in lines {
+        let line = line.trim_end_matches('\r');
+        let malformed = || SettingsError::Malformed(i + 1);
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(name);
+            match name {
+                "shortcuts" => settings.shortcuts = Some(Vec::new()),
+                "english_words" => settings.english_words = Some(Vec::new()),
+                "proper_nouns" => settings.proper_nouns = Some(Vec::new()),
+                "teencode" => settings.teencode = Some(Vec::new()),
+                "capitalize_rules" => settings.capitalize_rules = Some(Vec::new()),
+                "hotkeys" => settings.hotkeys = Some(Vec::new()),
+                "terminal_apps" => settings.terminal_apps = Some(Vec::new()),
+                "app_enabled" => settings.app_enabled = Some(Vec::new()),
+                "learning" => settings.learning = Some(String::new()),
+                _ => {}
+            }
+            continue;
+        }
+        match section {
+            Some("options") => {
+                let (key, value) = line.trim().split_once(' ').ok_or_else(malformed)?;
+                let value = value.trim();
+                if let Some(kind) = option_kind(key) {
+                    if parse_value(kind, value).is_none() {
+                        return Err(SettingsError::InvalidValue(key.into(), value.into()));
+                    }
+                    settings.options.push((key.to_string(), value.to_string()));
+                }
+            }
+            Some("shortcuts") => {
+                let s = parse_shortcut(line).ok_or_else(malformed)?;
+                settings.shortcuts.get_or_insert_default().push(s);
+            }
+            Some("english_words") => settings
+                .english_words
+                .get_or_insert_default()
+                .push(line.trim().to_string()),
+            Some("proper_nouns") => settings
+                .proper_nouns
+                .get_or_insert_default()
+                .push(line.trim().to_string()),
+            Some("teencode") => settings
+                .teencode
+                .get_or_insert_default()
+                .push(line.trim().to_string()),
+            Some("capitalize_rules") => {
+                let (c, rule) = line.rsplit_once(' ').ok_or_else(malformed)?;
+                let c = unescape(c).ok_or_else(malformed)?;
+                let mut chars = c.chars();
+                let (Some(c), None) = (chars.next(), chars.next()) else {
+                    return Err(malformed());
+                };
+                let rule = rule
+                    .parse()
+                    .ok()
+                    .and_then(CapitalizeRule::from_u8)
+                    .ok_or_else(malformed)?;
+                settings
+                    .capitalize_rules
+                    .get_or_insert_default()
+                    .push((c, rule));
+            }
+            Some("hotkeys") => {
+                let mut parts = line.split_whitespace();
+                let action = parts
+                    .next()
+                    .and_then(HotkeyAction::from_name)
+                    .ok_or_else(malformed)?;
+                let key = parts
+                    .next()
+                    .and_then(|k| k.parse().ok())
+                    .ok_or_else(malformed)?;
+                let mut hotkey = Hotkey::new(key, false, false);
+                for modifier in parts {
+                    match modifier {
+                        "ctrl" => hotkey.ctrl = true,
+                        "shift" => hotkey.shift = true,
+                        _ => return Err(malformed()),
+                    }
+                }
+                settings
+                    .hotkeys
+                    .get_or_insert_default()
+                    .push((action, hotkey));
+            }
+            Some("terminal_apps") => {
+                let (mode, app) = line.trim().split_once(' ').ok_or_else(malformed)?;
+                let mode = match mode {
+                    "0" | "1" | "2" => TerminalMode::from_u8(mode.parse().unwrap_or(0)),
+                    _ => return Err(malformed()),
+                };
+                settings
+                    .terminal_apps
+                    .get_or_insert_default()
+                    .push((app.trim().to_string(), mode));
+            }
+            Some("app_enabled") => {
+                let (enabled, app) = line.trim().split_once(' ').ok_or_else(malformed)?;
+                let enabled = match enabled {
+                    "0" => false,
+                    "1" => true,
+                    _ => return Err(malformed()),
+                };
+                settings
+                    .app_enabled
+                    .get_or_insert_default()
+                    .push((app.trim().to_string(), enabled));
+            }
+            Some("learning") => {
+                let learning = settings.learning.get_or_insert_default();
+                learning.push_str(line);
+                learning.push('\n');
+            }
+            // Section from a newer version
+            Some(_) => {}
+            None => return Err(malformed()),
+        }
+    }
+    Ok(settings)
+}
+
+/// Tab-separated shortcut: trigger, replacement, non-default attributes
+fn shortcut_line(s: &Shortcut) -> String {
+    let mut fields = vec![escape(&s.trigger), escape(&s.replacement)];
+    if s.condition == TriggerCondition::Immediate {
+        fields.push("when=immediate".into());
+    }
+    match s.input_method {
+        InputMethod::All => {}
+        InputMethod::Telex => fields.push("method=telex".into()),
+        InputMethod::Vni => fields.push("method=vni".into()),
+    }
+    if s.case_mode == CaseMode::Exact {
+        fields.push("case=exact".into());
+    }
+    if !s.enabled {
+        fields.push("off".into());
+    }
+    if !s.apps.only.is_empty() {
+        fields.push(format!("only={}", s.apps.only.join(",")));
+    }
+    if !s.apps.never.is_empty() {
+        fields.push(format!("never={}", s.apps.never.join(",")));
+    }
+    fields.join("\t")
+}
+
+fn parse_shortcut(line: &str) -> Option<Shortcut> {
+    let mut fields = line.split('\t');
+    let trigger = unescape(fields.next()?)?;
+    let replacement = unescape(fields.next()?)?;
+    if trigger.is_empty() {
+        return None;
+    }
+    let mut s = Shortcut::new(&trigger, &replacement);
+    let (mut only, mut never) = ("", "");
+    for attr in fields {
+        match attr.split_once('=') {
+            None if attr == "off" => s.enabled = false,
+            Some(("when", "word")) => s.condition = TriggerCondition::OnWordBoundary,
+            Some(("when", "immediate")) => s.condition = TriggerCondition::Immediate,
+            Some(("method", "all")) => s.input_method = InputMethod::All,
+            Some(("method", "telex")) => s.input_method = InputMethod::Telex,
+            Some(("method", "vni")) => s.input_method = InputMethod::Vni,
+            Some(("case", "match")) => s.case_mode = CaseMode::MatchCase,
+            Some(("case", "exact")) => s.case_mode = CaseMode::Exact,
+            Some(("only", apps)) => only = apps,
+            Some(("never", apps)) => never = apps,
+            _ => return None,
+        }
+    }
+    s.apps = AppScope::from_lists(only, never);
+    Some(s)
+}
+
+pub(super) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reverse of `escape`, None on an unknown escape
+pub(super) fn unescape(text: &str) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_round_trip() {
+        for text in ["kính gửi\n\tAnh", "a\\b", "", "C:\\tmp"] {
+            assert_eq!(unescape(&escape(text)).as_deref(), Some(text));
+        }
+        assert_eq!(unescape("bad\\x"), None);
+    }
+
+    #[test]
+    fn test_every_option_round_trips() {
+        let e = Engine::new();
+        let mut copy = Engine::new();
+        for key in option_keys() {
+            let value = e.option(key).unwrap();
+            copy.set_option(key, &value).unwrap();
+            assert_eq!(copy.option(key), Some(value), "{}", key);
+        }
+        assert_eq!(e.option("nope"), None);
+    }
+
+    #[test]
+    fn test_config_json() {
+        let mut e = Engine::new();
+        e.set_remove_key(1, None);
+        let json = e.config_json();
+        assert!(json.starts_with("{\"method\":0,\"tone_style\":1,\"free_tone\":false,"));
+        assert!(json.contains("\"remove_key_vni\":null"));
+
+        let mut copy = Engine::new();
+        copy.set_config_json(&json).unwrap();
+        assert_eq!(copy.config_json(), json);
+
+        copy.set_config_json(r#"{"method":"1","spell_check":false,"newer":7}"#)
+            .unwrap();
+        assert_eq!(copy.method(), 1);
+        assert_eq!(copy.option("spell_check").as_deref(), Some("false"));
+        assert_eq!(
+            copy.set_config_json(r#"{"method":0,"strictness":1.5}"#),
+            Err(SettingsError::InvalidValue(
+                "strictness".into(),
+                "1.5".into()
+            ))
+        );
+        assert_eq!(copy.method(), 1);
+        assert_eq!(copy.set_config_json("[]"), Err(SettingsError::NotSettings));
+    }
+
+    #[test]
+    fn test_rejects_invalid_files() {
+        let mut e = Engine::new();
+        assert_eq!(
+            e.import_settings("method 1"),
+            Err(SettingsError::NotSettings)
+        );
+        let bad = format!("{}\n[options]\nmethod 1\nstrictness 7\n", HEADER);
+        assert_eq!(
+            e.import_settings(&bad),
+            Err(SettingsError::InvalidValue("strictness".into(), "7".into()))
+        );
+        // Nothing applied
+        assert_eq!(e.method(), 0);
+        let bad = format!("{}\n[shortcuts]\nko\tkhông\tcolor=red\n", HEADER);
+        assert_eq!(e.import_settings(&bad), Err(SettingsError::Malformed(3)));
+        let bad = format!("{}\nmethod 1\n", HEADER);
+        assert_eq!(e.import_settings(&bad), Err(SettingsError::Malformed(2)));
+    }
+}
@@ -2,8 +2,13 @@
 //!
 //! Allows users to define shortcuts like "vn" → "Việt Nam"
 //! Shortcuts can be specific to input methods (Telex/VNI) or apply to all.
+//! Triggers may contain Vietnamese letters ("đc" → "được"): word shortcuts
+//! match the composed buffer, so "ddc" in Telex and "d9c" in VNI both fire.
 
 use super::buffer::MAX;
+use super::placeholder::{self, PlaceholderContext};
+use super::snippet;
+use crate::data::chars;
 use std::collections::HashMap;
 
 /// Maximum replacement length in UTF-32 codepoints (matches Result.chars array size)
@@ -11,6 +16,27 @@ use std::collections::HashMap;
 /// Note: Vietnamese characters with diacritics (ồ, ế, ẫ) count as 1 codepoint each.
 pub const MAX_REPLACEMENT_LEN: usize = MAX - 1; // -1 to leave room for trailing space
 
+/// Built-in arrow/symbol abbreviations (see `ShortcutTable::symbol_pack`)
+///
+/// No trigger is a prefix of another: immediate triggers fire as soon as
+/// they match, so "<-" would shadow a longer "<->".
+pub const SYMBOL_PACK: &[(&str, &str)] = &[
+    ("->", "→"),
+    ("<-", "←"),
+    ("=>", "⇒"),
+    ("<=", "≤"),
+    (">=", "≥"),
+    ("!=", "≠"),
+    ("+-", "±"),
+    ("~=", "≈"),
+];
+
+/// Caret position marker in replacements ("Kính gửi %cursor%,")
+///
+/// A replacement with exactly one `|` uses it as the marker too; use
+/// `%cursor%` when the text needs a literal `|`.
+pub const CURSOR_MARKER: &str = "%cursor%";
+
 /// Input method that shortcut applies to
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum InputMethod {
@@ -23,6 +49,45 @@ pub enum InputMethod {
     Vni,
 }
 
+/// Apps a shortcut may fire in (e.g. only in Mail, never in terminals)
+///
+/// App ids are whatever the host reports for the frontmost app (process name
+/// on Windows, bundle id on macOS), compared case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AppScope {
+    /// Fire only in these apps (empty = any app)
+    pub only: Vec<String>,
+    /// Never fire in these apps
+    pub never: Vec<String>,
+}
+
+impl AppScope {
+    /// Scope from comma-separated app lists ("outlook.exe, thunderbird.exe")
+    pub fn from_lists(only: &str, never: &str) -> Self {
+        let parse = |list: &str| {
+            list.split(',')
+                .map(|app| app.trim().to_lowercase())
+                .filter(|app| !app.is_empty())
+                .collect()
+        };
+        Self {
+            only: parse(only),
+            never: parse(never),
+        }
+    }
+
+    /// Whether a shortcut with this scope may fire in `app` (None = unknown app)
+    ///
+    /// An `only` list needs a known app; `never` cannot match an unknown one.
+    pub fn allows(&self, app: Option<&str>) -> bool {
+        let Some(app) = app else {
+            return self.only.is_empty();
+        };
+        let app = app.to_lowercase();
+        (self.only.is_empty() || self.only.contains(&app)) && !self.never.contains(&app)
+    }
+}
+
 /// Trigger condition for shortcut
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TriggerCondition {
@@ -56,6 +121,8 @@ pub struct Shortcut {
     pub enabled: bool,
     /// Which input method this shortcut applies to
     pub input_method: InputMethod,
+    /// Which apps this shortcut fires in
+    pub apps: AppScope,
 }
 
 impl Shortcut {
@@ -76,12 +143,24 @@ impl Shortcut {
     /// Replacement is truncated to MAX_REPLACEMENT_LEN (255) codepoints if too long.
     pub fn new(trigger: &str, replacement: &str) -> Self {
         Self {
-            trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
+            trigger: normalize_trigger(trigger),
             replacement: Self::validate_replacement(replacement),
             condition: TriggerCondition::OnWordBoundary,
             case_mode: CaseMode::MatchCase, // Smart case transformation
             enabled: true,
             input_method: InputMethod::All,
+            apps: AppScope::default(),
+        }
+    }
+
+    /// Create a shortcut whose trigger condition suits the trigger: immediate
+    /// for symbols only ("->", "=>"), word boundary for abbreviations
+    /// ("vn" → "Việt Nam")
+    pub fn auto(trigger: &str, replacement: &str) -> Self {
+        if trigger.chars().all(|c| !c.is_alphabetic()) {
+            Self::immediate(trigger, replacement)
+        } else {
+            Self::new(trigger, replacement)
         }
     }
 
@@ -90,12 +169,13 @@ impl Shortcut {
     /// Replacement is truncated to MAX_REPLACEMENT_LEN (255) codepoints if too long.
     pub fn immediate(trigger: &str, replacement: &str) -> Self {
         Self {
-            trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
+            trigger: normalize_trigger(trigger),
             replacement: Self::validate_replacement(replacement),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
             enabled: true,
             input_method: InputMethod::All,
+            apps: AppScope::default(),
         }
     }
 
@@ -104,12 +184,13 @@ impl Shortcut {
     /// Replacement is truncated to MAX_REPLACEMENT_LEN (255) codepoints if too long.
     pub fn telex(trigger: &str, replacement: &str) -> Self {
         Self {
-            trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
+            trigger: normalize_trigger(trigger),
             replacement: Self::validate_replacement(replacement),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
             enabled: true,
             input_method: InputMethod::Telex,
+            apps: AppScope::default(),
         }
     }
 
@@ -118,12 +199,13 @@ impl Shortcut {
     /// Replacement is truncated to MAX_REPLACEMENT_LEN (255) codepoints if too long.
     pub fn vni(trigger: &str, replacement: &str) -> Self {
         Self {
-            trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
+            trigger: normalize_trigger(trigger),
             replacement: Self::validate_replacement(replacement),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
             enabled: true,
             input_method: InputMethod::Vni,
+            apps: AppScope::default(),
         }
     }
 
@@ -133,6 +215,12 @@ impl Shortcut {
         self
     }
 
+    /// Set the apps this shortcut fires in
+    pub fn in_apps(mut self, apps: AppScope) -> Self {
+        self.apps = apps;
+        self
+    }
+
     /// Check if shortcut applies to given input method
     ///
     /// - If shortcut is for `All`: matches any method
@@ -162,6 +250,12 @@ pub struct ShortcutMatch {
     pub output: String,
     /// Whether to include the trigger key in output
     pub include_trigger_key: bool,
+    /// Trigger of the matched shortcut
+    pub trigger: String,
+    /// Characters of `output` after the cursor marker (None = no marker)
+    pub cursor_back: Option<usize>,
+    /// Snippet stops in `output`, in visiting order (empty = no fields)
+    pub fields: Vec<snippet::Field>,
 }
 
 /// Shortcut table manager
@@ -171,6 +265,10 @@ pub struct ShortcutTable {
     shortcuts: HashMap<String, Shortcut>,
     /// Sorted triggers by length (longest first) for matching
     sorted_triggers: Vec<String>,
+    /// Clock and clipboard for `%date%`-style placeholders (None: left as typed)
+    placeholders: Option<Box<dyn PlaceholderContext>>,
+    /// Frontmost app reported by the host, for `AppScope` (None: unknown)
+    active_app: Option<String>,
 }
 
 impl ShortcutTable {
@@ -178,6 +276,8 @@ impl ShortcutTable {
         Self {
             shortcuts: HashMap::new(),
             sorted_triggers: vec![],
+            placeholders: None,
+            active_app: None,
         }
     }
 
@@ -227,6 +327,41 @@ impl ShortcutTable {
         table
     }
 
+    /// Create with the arrow/symbol abbreviation pack (`SYMBOL_PACK`)
+    pub fn symbol_pack() -> Self {
+        let mut table = Self::new();
+        for &(trigger, symbol) in SYMBOL_PACK {
+            table.add(Shortcut::immediate(trigger, symbol));
+        }
+        table
+    }
+
+    /// Frontmost app (lowercase), None when unknown
+    pub fn active_app(&self) -> Option<&str> {
+        self.active_app.as_deref()
+    }
+
+    /// Set the frontmost app (host calls this on focus change)
+    pub fn set_active_app(&mut self, app: Option<&str>) {
+        self.active_app = app.filter(|a| !a.is_empty()).map(str::to_lowercase);
+    }
+
+    /// Set the app scope of an existing shortcut; false if there is none
+    pub fn set_apps(&mut self, trigger: &str, apps: AppScope) -> bool {
+        match self.shortcuts.get_mut(&normalize_trigger(trigger)) {
+            Some(shortcut) => {
+                shortcut.apps = apps;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the context that expands placeholders in replacements
+    pub fn set_placeholder_context(&mut self, ctx: Box<dyn PlaceholderContext>) {
+        self.placeholders = Some(ctx);
+    }
+
     /// Add a shortcut
     pub fn add(&mut self, shortcut: Shortcut) {
         let trigger = shortcut.trigger.clone();
@@ -234,9 +369,12 @@ impl ShortcutTable {
         self.rebuild_sorted_triggers();
     }
 
-    /// Remove a shortcut (exact match, case-sensitive)
+    /// Remove a shortcut (exact match, or the trigger as the constructors store it)
     pub fn remove(&mut self, trigger: &str) -> Option<Shortcut> {
-        let result = self.shortcuts.remove(trigger);
+        let result = self
+            .shortcuts
+            .remove(trigger)
+            .or_else(|| self.shortcuts.remove(&normalize_trigger(trigger)));
         if result.is_some() {
             self.rebuild_sorted_triggers();
         }
@@ -253,6 +391,7 @@ impl ShortcutTable {
     /// Check if buffer matches any shortcut for specific input method
     ///
     /// Issue #86: Case-insensitive matching - "ko", "Ko", "KO" all match trigger "ko"
+    /// Shortcuts scoped away from the active app are skipped.
     /// Returns (original_buffer, shortcut) if match found
     pub fn lookup_for_method(
         &self,
@@ -264,7 +403,10 @@ impl ShortcutTable {
         for trigger in &self.sorted_triggers {
             if buffer_lower == *trigger {
                 if let Some(shortcut) = self.shortcuts.get(trigger) {
-                    if shortcut.enabled && shortcut.applies_to(method) {
+                    if shortcut.enabled
+                        && shortcut.applies_to(method)
+                        && shortcut.apps.allows(self.active_app.as_deref())
+                    {
                         return Some((trigger, shortcut));
                     }
                 }
@@ -312,27 +454,34 @@ impl ShortcutTable {
 
         match shortcut.condition {
             TriggerCondition::Immediate => {
-                let output = self.apply_case(buffer, &shortcut.replacement, shortcut.case_mode);
+                let (output, cursor, fields) = self.render(buffer, shortcut);
                 Some(ShortcutMatch {
                     // Use char count, not byte length (UTF-8 chars like đ are multi-byte)
                     backspace_count: trigger.chars().count(),
                     output,
                     include_trigger_key: false,
+                    trigger: trigger.to_string(),
+                    cursor_back: cursor,
+                    fields,
                 })
             }
             TriggerCondition::OnWordBoundary => {
                 if is_word_boundary {
-                    let mut output =
-                        self.apply_case(buffer, &shortcut.replacement, shortcut.case_mode);
+                    let (mut output, mut cursor, fields) = self.render(buffer, shortcut);
                     // Append the trigger key (space, etc.)
                     if let Some(ch) = key_char {
                         output.push(ch);
+                        // Trigger key lands after the marker too
+                        cursor = cursor.map(|n| n + 1);
                     }
                     Some(ShortcutMatch {
                         // Use char count, not byte length (UTF-8 chars like đ are multi-byte)
                         backspace_count: trigger.chars().count(),
                         output,
                         include_trigger_key: true,
+                        trigger: trigger.to_string(),
+                        cursor_back: cursor,
+                        fields,
                     })
                 } else {
                     None
@@ -341,6 +490,48 @@ impl ShortcutTable {
         }
     }
 
+    /// Replacement text for `shortcut` typed as `buffer`, and the number of
+    /// characters after the cursor marker (None without a marker)
+    ///
+    /// Case is applied to the template first so placeholder values (dates,
+    /// clipboard) keep their own case; the result is capped like a
+    /// replacement so it fits the FFI result.
+    ///
+    /// Snippet fields (`${1:tên}`) take the place of a cursor marker.
+    fn render(
+        &self,
+        buffer: &str,
+        shortcut: &Shortcut,
+    ) -> (String, Option<usize>, Vec<snippet::Field>) {
+        let template = self.apply_case(buffer, &shortcut.replacement, shortcut.case_mode);
+        if snippet::has_fields(&template) {
+            let (text, mut fields) = snippet::parse(&self.expand(&template));
+            let output = Shortcut::validate_replacement(&text);
+            if output.len() < text.len() {
+                // Truncated: stops may point past the end
+                fields.clear();
+            }
+            return (output, None, fields);
+        }
+        let Some((before, after)) = split_cursor_marker(&template) else {
+            return (self.expand(&template), None, Vec::new());
+        };
+        let after = self.expand(after);
+        let output = Shortcut::validate_replacement(&(self.expand(before) + &after));
+        let cursor_back = after.chars().count().min(output.chars().count());
+        (output, Some(cursor_back), Vec::new())
+    }
+
+    /// Expand placeholders if a context is set
+    fn expand(&self, text: &str) -> String {
+        match &self.placeholders {
+            Some(ctx) if placeholder::has_placeholders(text) => {
+                Shortcut::validate_replacement(&placeholder::expand(text, ctx.as_ref()))
+            }
+            _ => text.to_string(),
+        }
+    }
+
     /// Apply case transformation based on mode
     fn apply_case(&self, trigger: &str, replacement: &str, mode: CaseMode) -> String {
         match mode {
@@ -376,6 +567,13 @@ impl ShortcutTable {
             .sort_by_key(|s| std::cmp::Reverse(s.len()));
     }
 
+    /// All shortcuts, sorted by trigger
+    pub fn iter(&self) -> impl Iterator<Item = &Shortcut> {
+        let mut all: Vec<&Shortcut> = self.shortcuts.values().collect();
+        all.sort_by(|a, b| a.trigger.cmp(&b.trigger));
+        all.into_iter()
+    }
+
     /// Check if shortcut table is empty
     pub fn is_empty(&self) -> bool {
         self.shortcuts.is_empty()
@@ -393,6 +591,26 @@ impl ShortcutTable {
     }
 }
 
+/// Trigger as stored: lowercase for case-insensitive matching, precomposed
+/// so Vietnamese triggers ("đc", "kô") match the engine's composed buffer
+/// even when entered decomposed
+fn normalize_trigger(trigger: &str) -> String {
+    chars::compose(trigger).to_lowercase()
+}
+
+/// Split a replacement at its cursor marker (`%cursor%`, or a single `|`)
+fn split_cursor_marker(text: &str) -> Option<(&str, &str)> {
+    // Case-insensitive: MatchCase may have uppercased the marker
+    let marker = text.char_indices().find_map(|(i, _)| {
+        text.get(i..i + CURSOR_MARKER.len())
+            .filter(|s| s.eq_ignore_ascii_case(CURSOR_MARKER))
+            .map(|_| (i, CURSOR_MARKER.len()))
+    });
+    let (idx, len) = marker
+        .or_else(|| (text.matches('|').count() == 1).then(|| (text.find('|').unwrap_or(0), 1)))?;
+    Some((&text[..idx], &text[idx + len..]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -894,4 +1112,60 @@ mod tests {
             InputMethod::All,
         );
     }
+
+    #[test]
+    fn test_symbol_pack_triggers_are_not_prefixes() {
+        for &(a, _) in SYMBOL_PACK {
+            for &(b, _) in SYMBOL_PACK {
+                assert!(a == b || !b.starts_with(a), "{} shadows {}", a, b);
+            }
+        }
+        let table = ShortcutTable::symbol_pack();
+        assert_eq!(table.len(), SYMBOL_PACK.len());
+        let m = table.try_match("+-", None, false).unwrap();
+        assert_eq!((m.output.as_str(), m.backspace_count), ("±", 2));
+    }
+
+    #[test]
+    fn test_cursor_marker() {
+        assert_eq!(split_cursor_marker("a|b"), Some(("a", "b")));
+        assert_eq!(split_cursor_marker("x %CURSOR% | y"), Some(("x ", " | y")));
+        assert_eq!(split_cursor_marker("a || b"), None);
+
+        let mut table = ShortcutTable::new();
+        table.add(Shortcut::immediate("kg", "Kính gửi |,"));
+        let m = table.try_match("kg", None, false).unwrap();
+        assert_eq!((m.output.as_str(), m.cursor_back), ("Kính gửi ,", Some(1)));
+    }
+
+    #[test]
+    fn test_app_scope() {
+        let scope = AppScope::from_lists("OUTLOOK.EXE, thunderbird.exe", "");
+        assert!(scope.allows(Some("outlook.exe")));
+        assert!(!scope.allows(Some("code.exe")));
+        assert!(!scope.allows(None));
+
+        let scope = AppScope::from_lists("", "wt.exe,,cmd.exe");
+        assert_eq!(scope.never, vec!["wt.exe", "cmd.exe"]);
+        assert!(!scope.allows(Some("WT.exe")));
+        assert!(scope.allows(Some("winword.exe")));
+        assert!(scope.allows(None));
+    }
+
+    #[test]
+    fn test_app_scoped_lookup() {
+        let mut table = ShortcutTable::new();
+        table.add(Shortcut::new("kg", "Kính gửi").in_apps(AppScope::from_lists("outlook.exe", "")));
+        table.add(Shortcut::new("fn", "function"));
+        assert!(table.set_apps("FN", AppScope::from_lists("", "code.exe")));
+        assert!(!table.set_apps("xyz", AppScope::default()));
+
+        assert!(table.lookup("kg").is_none());
+        assert!(table.lookup("fn").is_some());
+        table.set_active_app(Some("Outlook.exe"));
+        assert!(table.lookup("kg").is_some());
+        table.set_active_app(Some("code.exe"));
+        assert!(table.lookup("kg").is_none());
+        assert!(table.lookup("fn").is_none());
+    }
 }
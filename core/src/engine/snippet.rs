@@ -0,0 +1,253 @@
+//! Snippet fields - tab stops inside shortcut replacements
+//!
+//! A replacement like "Kính gửi ${1:anh/chị} ${2:tên}, ngày ${3}" expands to
+//! its default texts and leaves a session the host walks with Tab:
+//!
+//! - `${N:text}` - field N with default text (selected when visited)
+//! - `${N}` - empty field N
+//! - `${0}` - final caret position (default: end of the expansion)
+//!
+//! Fields are visited by number, `${0}` last. The engine does not see the
+//! screen, so the session follows the caret from the keys it processes; any
+//! key that moves the caret (arrows, clicks) ends it.
+
+/// A field in the expanded text, in characters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Field {
+    /// Offset from the start of the expansion
+    pub start: usize,
+    /// Length of the field text
+    pub len: usize,
+}
+
+/// Caret movement for a field jump
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jump {
+    /// Characters to move: negative = Left, positive = Right
+    pub caret: i32,
+    /// Characters to select to the right after moving (the default text)
+    pub select: usize,
+}
+
+/// Whether `text` contains a `${N...}` field
+pub fn has_fields(text: &str) -> bool {
+    text.contains("${")
+}
+
+/// Parse `${N:text}` / `${N}` at the start of `s` (after the `$`)
+fn parse_field(s: &str) -> Option<(u32, &str, usize)> {
+    let body = s.strip_prefix('{')?;
+    let end = body.find('}')?;
+    let body = &body[..end];
+    let (num, text) = match body.split_once(':') {
+        Some((num, text)) => (num, text),
+        None => (body, ""),
+    };
+    if num.is_empty() || !num.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((num.parse().ok()?, text, end + 2))
+}
+
+/// Expanded text and its stops in visiting order
+///
+/// The last stop is `${0}` or the end of the text. A repeated field number
+/// keeps its first position; later ones are plain default text.
+pub fn parse(template: &str) -> (String, Vec<Field>) {
+    let mut out = String::with_capacity(template.len());
+    let mut out_len = 0;
+    let mut fields: Vec<(u32, Field)> = Vec::new();
+    let mut rest = template;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        out_len += rest[..pos].chars().count();
+        let after = &rest[pos + 1..];
+        match parse_field(after) {
+            Some((num, text, consumed)) => {
+                let len = text.chars().count();
+                if !fields.iter().any(|(n, _)| *n == num) {
+                    let start = out_len;
+                    fields.push((num, Field { start, len }));
+                }
+                out.push_str(text);
+                out_len += len;
+                rest = &after[consumed..];
+            }
+            None => {
+                out.push('$');
+                out_len += 1;
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out_len += rest.chars().count();
+
+    if fields.is_empty() {
+        return (out, Vec::new());
+    }
+    if !fields.iter().any(|(n, _)| *n == 0) {
+        let end = Field {
+            start: out_len,
+            len: 0,
+        };
+        fields.push((0, end));
+    }
+    // ${0} sorts last
+    fields.sort_by_key(|(n, _)| n.wrapping_sub(1));
+    (out, fields.into_iter().map(|(_, f)| f).collect())
+}
+
+/// Fields of one expansion being filled in
+#[derive(Debug, Clone)]
+pub struct Session {
+    stops: Vec<Field>,
+    /// Next stop to visit
+    next: usize,
+    /// Stop the caret is in (None before the first jump)
+    current: Option<usize>,
+    /// Caret offset from the start of the expansion
+    caret: usize,
+    /// Characters selected right of the caret (default text of the field)
+    selected: usize,
+}
+
+impl Session {
+    /// Session for `stops` with the caret `caret` characters into the expansion
+    pub fn new(stops: Vec<Field>, caret: usize) -> Self {
+        Self {
+            stops,
+            next: 0,
+            current: None,
+            caret,
+            selected: 0,
+        }
+    }
+
+    /// Whether there are stops left to visit
+    pub fn is_active(&self) -> bool {
+        self.next < self.stops.len()
+    }
+
+    /// Text changed at the caret by `delta` characters
+    ///
+    /// A selected default text is replaced first: `delete` (Backspace) only
+    /// removes it, typed text takes its place.
+    pub fn edit(&mut self, delta: i32, delete: bool) {
+        let pos = self.caret;
+        let selected = std::mem::take(&mut self.selected) as i32;
+        let (change, caret) = if selected > 0 {
+            let inserted = if delete { 0 } else { delta.max(0) };
+            (inserted - selected, pos as i32 + inserted)
+        } else {
+            (delta, pos as i32 + delta)
+        };
+        let moved = |n: usize| (n as i32 + change).max(0) as usize;
+        for (i, stop) in self.stops.iter_mut().enumerate() {
+            if Some(i) == self.current {
+                stop.len = moved(stop.len);
+            } else if stop.start > pos || (stop.start == pos && i >= self.next) {
+                stop.start = moved(stop.start);
+            }
+        }
+        self.caret = caret.max(0) as usize;
+    }
+
+    /// Move to the next stop, None when there is none left
+    pub fn next_field(&mut self) -> Option<Jump> {
+        let stop = *self.stops.get(self.next)?;
+        let jump = Jump {
+            caret: stop.start as i32 - self.caret as i32,
+            select: stop.len,
+        };
+        self.current = Some(self.next);
+        self.next += 1;
+        self.caret = stop.start;
+        self.selected = stop.len;
+        Some(jump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let (text, stops) = parse("Kính gửi ${1:anh}, ngày ${2} $5 ${x}");
+        assert_eq!(text, "Kính gửi anh, ngày  $5 ${x}");
+        assert_eq!(
+            stops,
+            vec![
+                Field { start: 9, len: 3 },
+                Field { start: 19, len: 0 },
+                Field { start: 27, len: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_final_stop_and_order() {
+        let (text, stops) = parse("${0}<${2:b}|${1:a}|${2:c}>");
+        assert_eq!(text, "<b|a|c>");
+        assert_eq!(
+            stops,
+            vec![
+                Field { start: 3, len: 1 },
+                Field { start: 1, len: 1 },
+                Field { start: 0, len: 0 },
+            ]
+        );
+        assert!(parse("no fields $").1.is_empty());
+    }
+
+    #[test]
+    fn test_session_walk() {
+        // "Gửi anh, ngày ." with fields "anh" and "" (before ".")
+        let (_, stops) = parse("Gửi ${1:anh}, ngày ${2}.");
+        let mut s = Session::new(stops, 16);
+        assert_eq!(
+            s.next_field(),
+            Some(Jump {
+                caret: -12,
+                select: 3
+            })
+        );
+        // Type "chị" over the selected "anh"
+        for _ in 0..3 {
+            s.edit(1, false);
+        }
+        assert_eq!(
+            s.next_field(),
+            Some(Jump {
+                caret: 7,
+                select: 0
+            })
+        );
+        s.edit(2, false);
+        assert_eq!(
+            s.next_field(),
+            Some(Jump {
+                caret: 1,
+                select: 0
+            })
+        );
+        assert!(!s.is_active());
+        assert_eq!(s.next_field(), None);
+    }
+
+    #[test]
+    fn test_session_backspace_clears_selection() {
+        let (_, stops) = parse("${1:abc} ${2:de}");
+        let mut s = Session::new(stops, 6);
+        s.next_field();
+        s.edit(-1, true);
+        assert_eq!(
+            s.next_field(),
+            Some(Jump {
+                caret: 1,
+                select: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Teencode passthrough - "ko", "dc", "wa" are kept as typed
+//!
+//! Chat abbreviations are not Vietnamese syllables, so Telex turns some of
+//! them into something else ("wa" → "ưa", "hix" → "hĩ") and auto-restore
+//! and spelling fixes fight over the rest. A word whose keys spell a listed
+//! abbreviation is committed exactly as typed. Shortcuts are checked first,
+//! so an abbreviation the user defined as a gõ tắt ("ko" → "không") still
+//! expands.
+//!
+//! The built-in list holds common abbreviations typed in plain ASCII; the
+//! host can add more. Off by default.
+
+use std::collections::BTreeSet;
+
+/// Built-in abbreviations, lowercase keys as typed
+#[rustfmt::skip]
+const BUILTIN: &[&str] = &[
+    // không, được, biết
+    "ko", "k", "kh", "hk", "hok", "hem", "dc", "bt", "bik", "bit",
+    // với, mình, mọi người, gì, vậy, rồi, cũng
+    "vs", "mk", "mik", "mn", "mng", "j", "z", "r", "cx",
+    // nói, nhắn, như thế nào, trước, tin nhắn, inbox, comment
+    "ns", "nc", "nt", "ntn", "trc", "tn", "ib", "cmt",
+    // quá, thôi, luôn, yêu
+    "wa", "thui", "lun", "iu",
+    // ok, sorry, thanks
+    "ok", "oke", "okie", "sr", "tks", "thks",
+    // ừ, ừm, anh em, người yêu, vợ, chồng
+    "uk", "ukm", "ae", "ny", "vk", "ck",
+    // cảm thán
+    "hix", "hic", "hjx", "huhu", "hihi", "haha", "hehe", "kk", "kaka", "vl",
+];
+
+/// Abbreviations added by the host, plus the on/off switch
+#[derive(Debug, Clone, Default)]
+pub struct Teencode {
+    enabled: bool,
+    /// Added abbreviations, lowercase
+    words: BTreeSet<String>,
+}
+
+impl Teencode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Add an abbreviation (case-insensitive)
+    ///
+    /// Returns false (and adds nothing) when empty or not a single word.
+    pub fn add(&mut self, word: &str) -> bool {
+        let word = word.trim();
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            return false;
+        }
+        self.words.insert(word.to_lowercase());
+        true
+    }
+
+    /// Added abbreviations in sorted order (built-in ones not included)
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words.iter().map(String::as_str)
+    }
+
+    /// Remove all added abbreviations
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// Number of abbreviations added (built-in ones not counted)
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Whether `typed` (the word's keys, lowercase) is an abbreviation to
+    /// keep as typed; always false when off
+    pub fn contains(&self, typed: &str) -> bool {
+        self.enabled && (BUILTIN.contains(&typed) || self.words.contains(typed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_and_added_words() {
+        let mut t = Teencode::new();
+        assert!(!t.contains("ko"));
+        t.set_enabled(true);
+        assert!(t.contains("ko") && t.contains("wa"));
+        assert!(!t.contains("vcl"));
+        assert!(t.add(" VCL "));
+        assert!(t.contains("vcl"));
+        assert!(!t.add("a b") && !t.add(""));
+        assert_eq!(t.words().collect::<Vec<_>>(), ["vcl"]);
+        t.clear();
+        assert!(t.is_empty() && !t.contains("vcl"));
+    }
+}
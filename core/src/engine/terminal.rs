@@ -0,0 +1,171 @@
+//! Terminal-safe output for terminals and TUI editors
+//!
+//! Replacing text with backspaces is fragile there: vim in normal mode,
+//! tmux prefixes and slow SSH links turn a burst of backspaces into
+//! commands or lost characters. In these apps the engine can avoid them:
+//!
+//! - [`TerminalMode::Compose`] holds the word back while it is typed and
+//!   sends it once, finished, when a break key ends it. Nothing reaches the
+//!   app before that, so nothing has to be deleted.
+//! - [`TerminalMode::Passthrough`] turns the engine off for the app.
+//!
+//! Backspaces are still sent when an edit reaches into text the app
+//! already has (e.g. undoing an auto-restore of the previous word).
+
+use super::{
+    host_types_key, typed_char, Action, Result, FLAG_CURSOR_MOVE, FLAG_DECOMPOSED, FLAG_SNIPPET,
+    MAX,
+};
+use crate::data::{chars, keys};
+
+/// How the engine writes into an app
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TerminalMode {
+    /// Replace as usual: backspaces plus the new text
+    #[default]
+    Off = 0,
+    /// Send each word once, when it is finished
+    Compose = 1,
+    /// Leave every key alone
+    Passthrough = 2,
+}
+
+impl TerminalMode {
+    /// FFI value to mode (unknown values → Off)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TerminalMode::Compose,
+            2 => TerminalMode::Passthrough,
+            _ => TerminalMode::Off,
+        }
+    }
+}
+
+/// Text of the current word that the app has not received yet
+#[derive(Debug, Default)]
+pub(super) struct Composer {
+    held: Vec<char>,
+}
+
+impl Composer {
+    /// Held text
+    pub(super) fn text(&self) -> String {
+        self.held.iter().collect()
+    }
+
+    /// Forget the held text and return it
+    pub(super) fn take(&mut self) -> String {
+        self.held.drain(..).collect()
+    }
+
+    /// Turn the engine's `result` for `key` into one without backspaces
+    ///
+    /// `word_open` tells whether the engine is still inside a word after
+    /// the key.
+    pub(super) fn filter(
+        &mut self,
+        result: Result,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+        word_open: bool,
+    ) -> Result {
+        if ctrl {
+            // Word delete mid-word takes the held word, which the app does
+            // not have; Ctrl+C, Ctrl+U and the like act on what it has
+            let held = !self.held.is_empty();
+            self.held.clear();
+            if key == keys::DELETE && held {
+                return Result::consumed();
+            }
+            return result;
+        }
+        if result.action != Action::None as u8 {
+            let ends = host_types_key(&result, key, shift) || !word_open;
+            return self.edit(result, ends);
+        }
+        if result.key_consumed() {
+            return result;
+        }
+        if key == keys::DELETE {
+            return match self.held.pop() {
+                Some(_) => Result::consumed(),
+                None => result,
+            };
+        }
+        match typed_char(key, caps, shift) {
+            Some(c) if !keys::is_break_ext(key, shift) => {
+                self.held.push(c);
+                if word_open {
+                    Result::consumed()
+                } else {
+                    self.flush(Action::Send as u8, 0, 0)
+                }
+            }
+            _ if self.held.is_empty() => result,
+            _ => {
+                // Space is part of Send results; other break keys are typed
+                // by the host after the text
+                if key == keys::SPACE {
+                    self.held.push(' ');
+                }
+                self.flush(Action::Send as u8, 0, 0)
+            }
+        }
+    }
+
+    /// Apply a `result` that edits text to the held word
+    ///
+    /// The word reaches the app when `ends` is set or `result` deletes
+    /// past it; until then the key is only consumed.
+    pub(super) fn edit(&mut self, result: Result, ends: bool) -> Result {
+        let (held, overflow) = self.deleted(&result);
+        self.held.truncate(self.held.len() - held);
+        self.held.extend(
+            result.chars[..result.count as usize]
+                .iter()
+                .filter_map(|&c| char::from_u32(c)),
+        );
+        let ends = ends || overflow > 0 || result.flags & (FLAG_CURSOR_MOVE | FLAG_SNIPPET) != 0;
+        if !ends {
+            return Result::consumed();
+        }
+        let mut flags = result.flags;
+        if overflow == 0 {
+            flags &= !FLAG_DECOMPOSED;
+        }
+        self.flush(result.action, overflow, flags)
+    }
+
+    /// Held characters the backspaces of `result` delete, and how many
+    /// backspaces are left for the app
+    ///
+    /// Decomposed backspaces count code points, of the held text too.
+    fn deleted(&self, result: &Result) -> (usize, usize) {
+        let mut backspace = result.backspace as usize;
+        if result.flags & FLAG_DECOMPOSED == 0 {
+            let held = backspace.min(self.held.len());
+            return (held, backspace - held);
+        }
+        let mut held = 0;
+        for &c in self.held.iter().rev() {
+            if backspace == 0 {
+                break;
+            }
+            backspace = backspace.saturating_sub(chars::decomposed_len(c));
+            held += 1;
+        }
+        (held, backspace)
+    }
+
+    /// Send the held text, after `backspace` deletions in the app
+    fn flush(&mut self, action: u8, backspace: usize, flags: u8) -> Result {
+        let text: Vec<char> = self.held.drain(..).take(MAX).collect();
+        let mut result = Result::send(backspace.min(u8::MAX as usize) as u8, &text);
+        result.action = action;
+        result.flags = flags;
+        result
+    }
+}
@@ -0,0 +1,70 @@
+//! Tone style inference
+//!
+//! Watches committed words for the tone placement the user picks over the
+//! engine's: "hòa" committed while the style is new ("hoà") is a
+//! correction, made by choosing the other placement in the candidate list
+//! or by editing a word already on screen. After a few corrections the
+//! style flips. Words written the same in both styles ("hoàn", "quý") say
+//! nothing.
+
+use super::ToneStyle;
+use crate::convert::normalize_tone;
+
+/// Corrections needed before the style flips
+const EVIDENCE: u8 = 3;
+
+/// Corrections seen toward the other style
+#[derive(Debug, Clone, Default)]
+pub struct ToneStyleDetector {
+    corrections: u8,
+}
+
+impl ToneStyleDetector {
+    /// Start counting again
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Feed a committed word ("hòa")
+    ///
+    /// Returns the style to switch to once enough words were written in
+    /// the other style than `current`.
+    pub fn observe(&mut self, word: &str, current: ToneStyle) -> Option<ToneStyle> {
+        let other = match current {
+            ToneStyle::NewStyle => ToneStyle::TraditionalStyle,
+            ToneStyle::TraditionalStyle => ToneStyle::NewStyle,
+        };
+        if !is_written_in(word, other, current) {
+            return None;
+        }
+        self.corrections += 1;
+        if self.corrections < EVIDENCE {
+            return None;
+        }
+        self.reset();
+        Some(other)
+    }
+}
+
+/// Whether `word` follows `style` and not `current`
+fn is_written_in(word: &str, style: ToneStyle, current: ToneStyle) -> bool {
+    normalize_tone(word, style) == word && normalize_tone(word, current) != word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flips_after_evidence() {
+        let mut d = ToneStyleDetector::default();
+        let new = ToneStyle::NewStyle;
+        assert_eq!(d.observe("hòa", new), None);
+        // Same in both styles, or already in the current one
+        assert_eq!(d.observe("hoàn", new), None);
+        assert_eq!(d.observe("khoẻ", new), None);
+        assert_eq!(d.observe("khỏe", new), None);
+        assert_eq!(d.observe("thủy", new), Some(ToneStyle::TraditionalStyle));
+        assert_eq!(d.observe("hoà", ToneStyle::TraditionalStyle), None);
+    }
+}
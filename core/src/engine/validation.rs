@@ -3,20 +3,40 @@
 //! Whitelist-based validation for Vietnamese syllables.
 //! Uses valid patterns from docs/vietnamese-language-system.md Section 7.6.1
 
+use super::buffer::{StackVec, MAX};
 use super::syllable::{parse, Syllable};
-use crate::data::chars::tone;
+use crate::cache::Lru;
+use crate::data::chars::{self, mark, tone};
 use crate::data::constants;
 use crate::data::keys;
+use crate::data::rhymes;
+use crate::latency::{self, Stage};
+use std::cell::RefCell;
+
+/// Recent results kept by each validation cache
+const CACHE_SIZE: usize = 64;
+
+thread_local! {
+    /// Recent `validate` results
+    static VALIDATED: RefCell<Lru<BufferSnapshot, ValidationResult>> =
+        const { RefCell::new(Lru::new(CACHE_SIZE)) };
+    /// Recent `is_valid_for_transform_with_foreign` results
+    static TRANSFORMABLE: RefCell<Lru<BufferSnapshot, bool>> =
+        const { RefCell::new(Lru::new(CACHE_SIZE)) };
+}
 
-/// Validation result
-#[derive(Debug, Clone, PartialEq)]
+/// Validation result (values are the FFI codes of `ime_validate_word`)
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ValidationResult {
-    Valid,
-    InvalidInitial,
-    InvalidFinal,
-    InvalidSpelling,
-    InvalidVowelPattern,
-    NoVowel,
+    Valid = 0,
+    InvalidInitial = 1,
+    InvalidFinal = 2,
+    InvalidSpelling = 3,
+    InvalidVowelPattern = 4,
+    NoVowel = 5,
+    /// Tone mark not allowed with the final: p/t/c/ch take only sắc or nặng
+    InvalidTone = 6,
 }
 
 impl ValidationResult {
@@ -25,20 +45,53 @@ impl ValidationResult {
     }
 }
 
+/// How strictly syllables are held to Vietnamese orthography
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Strictness {
+    /// All rules; foreign initials follow the allow-foreign option
+    #[default]
+    Standard = 0,
+    /// Office typing: no foreign initials (z, w, j, f) or final k (Đắk),
+    /// even with allow-foreign on
+    Strict = 1,
+    /// Casual chat: foreign initials, c/k g/gh ng/ngh mixups ("ko", "ngi")
+    /// and rhymes outside the table ("ping") are accepted
+    Relaxed = 2,
+}
+
+impl Strictness {
+    /// FFI value to profile (unknown values → Standard)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Strictness::Strict,
+            2 => Strictness::Relaxed,
+            _ => Strictness::Standard,
+        }
+    }
+}
+
 // =============================================================================
 // BUFFER SNAPSHOT - Keys + Modifiers for validation
 // =============================================================================
 
 /// Snapshot of buffer state for validation
-/// Contains both keys and their modifiers (tones)
+/// Contains keys, their modifiers (tones) and tone marks
+#[derive(Debug, Clone, PartialEq)]
 pub struct BufferSnapshot {
     pub keys: Vec<u16>,
     pub tones: Vec<u8>,
+    /// Tone marks (sắc..nặng) per key; empty when unknown (tone rules skipped)
+    pub marks: Vec<u8>,
     /// True when tones were explicitly provided (validate modifier requirements)
     /// False when created from keys-only (legacy, skip modifier checks)
     pub has_tone_info: bool,
     /// True when foreign consonants (z, w, j, f) are allowed as valid initials
     pub allow_foreign_consonants: bool,
+    /// True when z and dz are valid initials for southern spellings ("dzô")
+    pub southern_initials: bool,
+    /// Validation profile
+    pub strictness: Strictness,
 }
 
 impl BufferSnapshot {
@@ -49,8 +102,11 @@ impl BufferSnapshot {
         Self {
             keys,
             tones: vec![0; len],
+            marks: Vec::new(),
             has_tone_info: false,
             allow_foreign_consonants: false,
+            southern_initials: false,
+            strictness: Strictness::Standard,
         }
     }
 
@@ -60,8 +116,32 @@ impl BufferSnapshot {
         Self {
             keys,
             tones: vec![0; len],
+            marks: Vec::new(),
             has_tone_info: false,
             allow_foreign_consonants,
+            southern_initials: false,
+            strictness: Strictness::Standard,
+        }
+    }
+
+    /// Same snapshot validated with `strictness`
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Same snapshot with z/dz initials allowed or not
+    pub fn with_southern_initials(mut self, allowed: bool) -> Self {
+        self.southern_initials = allowed;
+        self
+    }
+
+    /// Whether z, w, j, f are valid initials
+    fn foreign_allowed(&self) -> bool {
+        match self.strictness {
+            Strictness::Standard => self.allow_foreign_consonants,
+            Strictness::Strict => false,
+            Strictness::Relaxed => true,
         }
     }
 }
@@ -81,6 +161,8 @@ const RULES: &[Rule] = &[
     rule_spelling,
     rule_valid_final,
     rule_valid_vowel_pattern,
+    rule_valid_rhyme,
+    rule_tone_final,
 ];
 
 /// Rule 1: Must have at least one vowel
@@ -102,12 +184,15 @@ fn rule_valid_initial(snap: &BufferSnapshot, syllable: &Syllable) -> Option<Vali
     let is_valid = match initial.len() {
         1 => {
             constants::VALID_INITIALS_1.contains(&initial[0])
-                || (snap.allow_foreign_consonants
-                    && constants::FOREIGN_INITIALS.contains(&initial[0]))
+                || (snap.foreign_allowed() && constants::FOREIGN_INITIALS.contains(&initial[0]))
+                || (snap.southern_initials && initial[0] == keys::Z)
+        }
+        2 => {
+            constants::VALID_INITIALS_2
+                .iter()
+                .any(|p| p[0] == initial[0] && p[1] == initial[1])
+                || (snap.southern_initials && initial == constants::SOUTHERN_INITIAL_DZ)
         }
-        2 => constants::VALID_INITIALS_2
-            .iter()
-            .any(|p| p[0] == initial[0] && p[1] == initial[1]),
         3 => initial[0] == keys::N && initial[1] == keys::G && initial[2] == keys::H,
         _ => false,
     };
@@ -131,31 +216,39 @@ fn rule_all_chars_parsed(snap: &BufferSnapshot, syllable: &Syllable) -> Option<V
     None
 }
 
-/// Rule 4: Vietnamese spelling rules (c/k, g/gh, ng/ngh)
+/// Rule 4: Vietnamese spelling rules (c/k, g/gh, ng/ngh), skipped when relaxed
 fn rule_spelling(snap: &BufferSnapshot, syllable: &Syllable) -> Option<ValidationResult> {
-    if syllable.initial.is_empty() || syllable.vowel.is_empty() {
+    if snap.strictness == Strictness::Relaxed {
         return None;
     }
+    misspelled_initial(&snap.keys, syllable).map(|_| ValidationResult::InvalidSpelling)
+}
 
-    let initial: Vec<u16> = syllable.initial.iter().map(|&i| snap.keys[i]).collect();
-    let first_vowel = snap.keys[syllable.glide.unwrap_or(syllable.vowel[0])];
-
-    for &(consonant, vowels, _msg) in constants::SPELLING_RULES {
-        if initial == consonant && vowels.contains(&first_vowel) {
-            return Some(ValidationResult::InvalidSpelling);
-        }
+/// Initial consonant that breaks a spelling rule before the first vowel
+fn misspelled_initial(keys: &[u16], syllable: &Syllable) -> Option<&'static [u16]> {
+    if syllable.initial.is_empty() || syllable.vowel.is_empty() {
+        return None;
     }
 
-    None
+    let initial: Vec<u16> = syllable.initial.iter().map(|&i| keys[i]).collect();
+    let first_vowel = keys[syllable.glide.unwrap_or(syllable.vowel[0])];
+
+    constants::SPELLING_RULES
+        .iter()
+        .find(|&&(consonant, vowels, _msg)| initial == consonant && vowels.contains(&first_vowel))
+        .map(|&(consonant, _, _)| consonant)
 }
 
-/// Rule 5: Final consonant must be valid
+/// Rule 5: Final consonant must be valid (final k only outside strict)
 fn rule_valid_final(snap: &BufferSnapshot, syllable: &Syllable) -> Option<ValidationResult> {
     if syllable.final_c.is_empty() {
         return None;
     }
 
     let final_c: Vec<u16> = syllable.final_c.iter().map(|&i| snap.keys[i]).collect();
+    if snap.strictness == Strictness::Strict && final_c == [keys::K] {
+        return Some(ValidationResult::InvalidFinal);
+    }
 
     let is_valid = match final_c.len() {
         1 => constants::VALID_FINALS_1.contains(&final_c[0]),
@@ -171,13 +264,10 @@ fn rule_valid_final(snap: &BufferSnapshot, syllable: &Syllable) -> Option<Valida
     None
 }
 
-/// Rule 6: Vowel patterns must be valid Vietnamese (WHITELIST approach)
+/// Rule 6: Modifier requirements of vowel patterns
 ///
-/// Validates against 40 valid patterns from docs 7.6.1:
-/// - 29 diphthongs (nguyên âm đôi)
-/// - 11 triphthongs (nguyên âm ba)
-///
-/// This catches common English patterns NOT in Vietnamese:
+/// Which vowel sequences exist at all is decided by the rhyme table (rule 7),
+/// which catches common English patterns NOT in Vietnamese:
 /// - "ea" (search, beach, teacher) - not valid in Vietnamese
 /// - "ou" (you, our, house, about) - not valid in Vietnamese
 /// - "yo" (yoke, York, your) - not valid in Vietnamese
@@ -194,18 +284,13 @@ fn rule_valid_vowel_pattern(
     }
 
     let vowel_indices: &[usize] = &syllable.vowel;
-    let vowel_keys: Vec<u16> = vowel_indices.iter().map(|&i| snap.keys[i]).collect();
-    let vowel_tones: Vec<u8> = vowel_indices.iter().map(|&i| snap.tones[i]).collect();
+    let vowel_keys: StackVec<u16, MAX> = vowel_indices.iter().map(|&i| snap.keys[i]).collect();
+    let vowel_tones: StackVec<u8, MAX> = vowel_indices.iter().map(|&i| snap.tones[i]).collect();
 
     match vowel_keys.len() {
         2 => {
             let pair = [vowel_keys[0], vowel_keys[1]];
 
-            // Check if base pattern is in whitelist
-            if !constants::VALID_DIPHTHONGS.contains(&pair) {
-                return Some(ValidationResult::InvalidVowelPattern);
-            }
-
             // Only check modifier requirements when tone info was explicitly provided
             // This is the key fix for "new" → "neư" bug
             // E+U requires circumflex on E (êu valid, eu/eư invalid)
@@ -240,11 +325,6 @@ fn rule_valid_vowel_pattern(
         3 => {
             let triple = [vowel_keys[0], vowel_keys[1], vowel_keys[2]];
 
-            // Check if base pattern is in whitelist
-            if !constants::VALID_TRIPHTHONGS.contains(&triple) {
-                return Some(ValidationResult::InvalidVowelPattern);
-            }
-
             // Triphthong modifier checks only when tone info provided
             if snap.has_tone_info {
                 // uyê requires circumflex on E (last vowel)
@@ -272,12 +352,111 @@ fn rule_valid_vowel_pattern(
     None
 }
 
+/// Rule 7: Medial + nucleus + final must be a rhyme from `rhymes::RHYMES`
+///
+/// Catches combinations each part allows on its own: "ơc", "oain", "uyun",
+/// "âi". The start of a rhyme passes ("tiê" before "tiên"); `validate_word`
+/// requires the whole rhyme. Skipped when relaxed.
+fn rule_valid_rhyme(snap: &BufferSnapshot, syllable: &Syllable) -> Option<ValidationResult> {
+    if snap.strictness == Strictness::Relaxed {
+        return None;
+    }
+    (!rhyme_fits(snap, syllable, true)).then(|| rhyme_error(snap, syllable))
+}
+
+/// Error for a rhyme missing from the table: the vowels when no rhyme
+/// starts with them ("ea"), the final otherwise ("oain")
+fn rhyme_error(snap: &BufferSnapshot, syllable: &Syllable) -> ValidationResult {
+    let vowels_only = Syllable {
+        final_c: Vec::new(),
+        ..syllable.clone()
+    };
+    if syllable.has_final() && rhyme_fits(snap, &vowels_only, true) {
+        ValidationResult::InvalidFinal
+    } else {
+        ValidationResult::InvalidVowelPattern
+    }
+}
+
+/// Whether the rhyme of `syllable` is in the rhyme table
+fn rhyme_fits(snap: &BufferSnapshot, syllable: &Syllable, partial: bool) -> bool {
+    let indices: Vec<usize> = syllable
+        .glide
+        .iter()
+        .chain(&syllable.vowel)
+        .chain(&syllable.final_c)
+        .copied()
+        .collect();
+    let mut rhyme_keys: Vec<u16> = indices.iter().map(|&i| snap.keys[i]).collect();
+    let mut rhyme_tones: Vec<u8> = indices.iter().map(|&i| snap.tones[i]).collect();
+    if rhymes::fits(&rhyme_keys, &rhyme_tones, partial) {
+        return true;
+    }
+
+    // The medial can be spelled into the initial: gi(ếng) = gi + iêng,
+    // qu(yết) = qu + uyêt, qu(ang) = qu + oang
+    let initial: Vec<u16> = syllable.initial.iter().map(|&i| snap.keys[i]).collect();
+    let medials: &[u16] = match initial.as_slice() {
+        [keys::G, keys::I] => &[keys::I],
+        [keys::Q, keys::U] => &[keys::U, keys::O],
+        _ => &[],
+    };
+    rhyme_keys.insert(0, 0);
+    rhyme_tones.insert(0, 0);
+    medials.iter().any(|&medial| {
+        rhyme_keys[0] = medial;
+        rhymes::fits(&rhyme_keys, &rhyme_tones, partial)
+    })
+}
+
+/// Rule 8: Stop finals (p, t, c, ch) only take sắc or nặng
+///
+/// "hoàp", "mặt" vs "mằt": huyền/hỏi/ngã never end in a stop consonant.
+/// No mark is allowed too: the tone usually comes after the final is typed.
+fn rule_tone_final(snap: &BufferSnapshot, syllable: &Syllable) -> Option<ValidationResult> {
+    let stop_final = syllable
+        .final_c
+        .first()
+        .is_some_and(|&i| is_stop_final(snap.keys[i]));
+    if !stop_final {
+        return None;
+    }
+    let has_level_tone_mark = syllable
+        .vowel
+        .iter()
+        .filter_map(|&i| snap.marks.get(i))
+        .any(|&m| !mark_fits_stop_final(m));
+    has_level_tone_mark.then_some(ValidationResult::InvalidTone)
+}
+
+/// First key of a stop final: p, t, c (and ch)
+fn is_stop_final(key: u16) -> bool {
+    matches!(key, keys::P | keys::T | keys::C)
+}
+
+/// Whether a syllable ending in a stop final can carry `mark_val`
+fn mark_fits_stop_final(mark_val: u8) -> bool {
+    matches!(mark_val, mark::NONE | mark::SAC | mark::NANG)
+}
+
 // =============================================================================
 // PUBLIC API
 // =============================================================================
 
 /// Validate buffer as Vietnamese syllable - runs all rules
 pub fn validate(snap: &BufferSnapshot) -> ValidationResult {
+    let _timing = latency::span(Stage::Validation);
+    let cached = VALIDATED.with_borrow_mut(|cache| cache.get(snap));
+    latency::record_lookup(Stage::Validation, cached.is_some());
+    if let Some(result) = cached {
+        return result;
+    }
+    let result = run_rules(snap);
+    VALIDATED.with_borrow_mut(|cache| cache.insert(snap.clone(), result));
+    result
+}
+
+fn run_rules(snap: &BufferSnapshot) -> ValidationResult {
     if snap.keys.is_empty() {
         return ValidationResult::NoVowel;
     }
@@ -293,14 +472,64 @@ pub fn validate(snap: &BufferSnapshot) -> ValidationResult {
     ValidationResult::Valid
 }
 
+/// Validate one syllable as shown on screen ("trường", "Nghiêng", "kem")
+///
+/// Circumflex/horn/breve are checked like the engine does, and tone marks
+/// against the final ("hoàp" is `InvalidTone`). A character that is not a
+/// Vietnamese letter (digit, symbol, space) makes the word `InvalidSpelling`.
+pub fn validate_word(word: &str, allow_foreign_consonants: bool) -> ValidationResult {
+    validate_word_with_strictness(word, allow_foreign_consonants, Strictness::Standard)
+}
+
+/// `validate_word` under a validation profile
+pub fn validate_word_with_strictness(
+    word: &str,
+    allow_foreign_consonants: bool,
+    strictness: Strictness,
+) -> ValidationResult {
+    let mut keys = Vec::with_capacity(word.len());
+    let mut tones = Vec::with_capacity(word.len());
+    let mut marks = Vec::with_capacity(word.len());
+    for c in word.chars() {
+        let Some(parsed) = chars::parse_char(c) else {
+            return ValidationResult::InvalidSpelling;
+        };
+        keys.push(parsed.key);
+        tones.push(parsed.tone);
+        marks.push(parsed.mark);
+    }
+    let snap = BufferSnapshot {
+        keys,
+        tones,
+        marks,
+        has_tone_info: true,
+        allow_foreign_consonants,
+        southern_initials: false,
+        strictness,
+    };
+    let result = validate(&snap);
+    // A shown word is complete: "tiê" is not a syllable
+    let syllable = parse(&snap.keys);
+    if result.is_valid()
+        && strictness != Strictness::Relaxed
+        && !rhyme_fits(&snap, &syllable, false)
+    {
+        return rhyme_error(&snap, &syllable);
+    }
+    result
+}
+
 /// Quick check if buffer could be valid Vietnamese (with modifier info)
 /// This will fully validate modifier requirements (e.g., E+U requires circumflex)
 pub fn is_valid_with_tones(keys: &[u16], tones: &[u8]) -> bool {
     let snap = BufferSnapshot {
         keys: keys.to_vec(),
         tones: tones.to_vec(),
+        marks: Vec::new(),
         has_tone_info: true, // Enforce modifier requirements
         allow_foreign_consonants: false,
+        southern_initials: false,
+        strictness: Strictness::Standard,
     };
     validate(&snap).is_valid()
 }
@@ -314,12 +543,60 @@ pub fn is_valid_with_tones_and_foreign(
     let snap = BufferSnapshot {
         keys: keys.to_vec(),
         tones: tones.to_vec(),
+        marks: Vec::new(),
+        has_tone_info: true,
+        allow_foreign_consonants,
+        southern_initials: false,
+        strictness: Strictness::Standard,
+    };
+    validate(&snap).is_valid()
+}
+
+/// Full check with modifiers and tone marks (tone–final rule included)
+pub fn is_valid_with_marks(
+    keys: &[u16],
+    tones: &[u8],
+    marks: &[u8],
+    allow_foreign_consonants: bool,
+    southern_initials: bool,
+    strictness: Strictness,
+) -> bool {
+    let snap = BufferSnapshot {
+        keys: keys.to_vec(),
+        tones: tones.to_vec(),
+        marks: marks.to_vec(),
         has_tone_info: true,
         allow_foreign_consonants,
+        southern_initials,
+        strictness,
     };
     validate(&snap).is_valid()
 }
 
+/// Respelled initial for a word that breaks a c/k, g/gh, ng/ngh rule
+///
+/// Returns the length of the wrong initial and the right one: "nge" →
+/// (2, "ngh"), "ka" → (1, "c"). None when the spelling is fine.
+pub fn fix_spelling(keys: &[u16]) -> Option<(usize, &'static [u16])> {
+    let wrong = misspelled_initial(keys, &parse(keys))?;
+    constants::SPELLING_FIXES
+        .iter()
+        .find(|&&(from, _)| from == wrong)
+        .map(|&(_, right)| (wrong.len(), right))
+}
+
+/// Whether `mark_val` can be placed on a syllable with these keys
+///
+/// False only for huyền/hỏi/ngã on a syllable ending in p, t, c or ch.
+pub fn mark_allowed(keys: &[u16], mark_val: u8) -> bool {
+    let syllable = parse(keys);
+    let stop_final = syllable
+        .final_c
+        .first()
+        .is_some_and(|&i| is_stop_final(keys[i]));
+    !stop_final || mark_fits_stop_final(mark_val)
+}
+
 /// Quick check if buffer could be valid Vietnamese (keys only - legacy)
 ///
 /// NOTE: This cannot fully validate modifier requirements.
@@ -330,9 +607,16 @@ pub fn is_valid(buffer_keys: &[u16]) -> bool {
 }
 
 /// Quick check if buffer could be valid Vietnamese with foreign consonants option
-pub fn is_valid_with_foreign(buffer_keys: &[u16], allow_foreign_consonants: bool) -> bool {
+pub fn is_valid_with_foreign(
+    buffer_keys: &[u16],
+    allow_foreign_consonants: bool,
+    southern_initials: bool,
+    strictness: Strictness,
+) -> bool {
     let snap =
-        BufferSnapshot::from_keys_with_foreign(buffer_keys.to_vec(), allow_foreign_consonants);
+        BufferSnapshot::from_keys_with_foreign(buffer_keys.to_vec(), allow_foreign_consonants)
+            .with_southern_initials(southern_initials)
+            .with_strictness(strictness);
     validate(&snap).is_valid()
 }
 
@@ -353,29 +637,58 @@ const RULES_FOR_TRANSFORM: &[Rule] = &[
 /// Used by try_tone/try_stroke to validate buffer structure before transformation.
 /// Does NOT check vowel patterns since intermediate states like "aa" → "â" are valid.
 pub fn is_valid_for_transform(buffer_keys: &[u16]) -> bool {
-    is_valid_for_transform_with_foreign(buffer_keys, false)
+    is_valid_for_transform_with_foreign(buffer_keys, false, false, Strictness::Standard)
 }
 
 /// Pre-transformation validation with foreign consonants option
 pub fn is_valid_for_transform_with_foreign(
     buffer_keys: &[u16],
     allow_foreign_consonants: bool,
+    southern_initials: bool,
+    strictness: Strictness,
 ) -> bool {
+    let _timing = latency::span(Stage::Validation);
     if buffer_keys.is_empty() {
         return false;
     }
 
     let snap =
-        BufferSnapshot::from_keys_with_foreign(buffer_keys.to_vec(), allow_foreign_consonants);
+        BufferSnapshot::from_keys_with_foreign(buffer_keys.to_vec(), allow_foreign_consonants)
+            .with_southern_initials(southern_initials)
+            .with_strictness(strictness);
+    let cached = TRANSFORMABLE.with_borrow_mut(|cache| cache.get(&snap));
+    latency::record_lookup(Stage::Validation, cached.is_some());
+    if let Some(valid) = cached {
+        return valid;
+    }
     let syllable = parse(&snap.keys);
+    let valid = RULES_FOR_TRANSFORM
+        .iter()
+        .all(|rule| rule(&snap, &syllable).is_none());
+    TRANSFORMABLE.with_borrow_mut(|cache| cache.insert(snap, valid));
+    valid
+}
 
-    for rule in RULES_FOR_TRANSFORM {
-        if rule(&snap, &syllable).is_some() {
-            return false;
-        }
+/// First rule `is_valid_for_transform_with_foreign` fails, None if valid
+///
+/// Uncached, for explaining a rejected transform (`debug-trace`).
+pub fn transform_rule_failed(
+    buffer_keys: &[u16],
+    allow_foreign_consonants: bool,
+    southern_initials: bool,
+    strictness: Strictness,
+) -> Option<ValidationResult> {
+    if buffer_keys.is_empty() {
+        return Some(ValidationResult::NoVowel);
     }
-
-    true
+    let snap =
+        BufferSnapshot::from_keys_with_foreign(buffer_keys.to_vec(), allow_foreign_consonants)
+            .with_southern_initials(southern_initials)
+            .with_strictness(strictness);
+    let syllable = parse(&snap.keys);
+    RULES_FOR_TRANSFORM
+        .iter()
+        .find_map(|rule| rule(&snap, &syllable))
 }
 
 /// Check if the buffer shows patterns that suggest foreign word input.
@@ -395,6 +708,7 @@ pub fn is_foreign_word_pattern(
     _buffer_tones: &[u8],
     modifier_key: u16,
 ) -> bool {
+    let _timing = latency::span(Stage::Validation);
     let syllable = parse(buffer_keys);
 
     // Check 1: Invalid vowel patterns (not in whitelist)
@@ -673,4 +987,97 @@ mod tests {
             "'ăi' should be invalid"
         );
     }
+
+    #[test]
+    fn test_validate_word() {
+        use ValidationResult::*;
+        let cases = [
+            ("trường", Valid),
+            ("Nghiêng", Valid),
+            ("đặc", Valid),
+            ("clau", InvalidInitial),
+            ("ke", Valid),
+            ("ka", InvalidSpelling),
+            ("gues", InvalidFinal),
+            ("tăi", InvalidVowelPattern),
+            ("bcd", NoVowel),
+            ("học", Valid),
+            ("thích", Valid),
+            ("hoàp", InvalidTone),
+            ("mẵt", InvalidTone),
+            ("", NoVowel),
+            ("a1", InvalidSpelling),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(validate_word(word, false), expected, "{}", word);
+        }
+        assert_eq!(validate_word("zô", false), InvalidInitial);
+        assert!(mark_allowed(&keys_from_str("hoc"), mark::NANG));
+        assert!(!mark_allowed(&keys_from_str("thich"), mark::HUYEN));
+        assert!(mark_allowed(&keys_from_str("ho"), mark::HUYEN));
+        assert_eq!(validate_word("zô", true), Valid);
+    }
+
+    /// Rare rhymes that exist
+    const RARE_RHYMES: &[&str] = &[
+        "khuya", "khuỷu", "xoong", "soóc", "quạu", "giếng", "huỳnh", "quyết", "hoặc", "loắt",
+        "huých", "nguều", "hươu", "yểng", "Đắk",
+    ];
+
+    /// Combinations each part allows but no rhyme has
+    const IMPOSSIBLE_RHYMES: &[(&str, ValidationResult)] = &[
+        ("bơc", ValidationResult::InvalidFinal),
+        ("hoain", ValidationResult::InvalidFinal),
+        ("khuyun", ValidationResult::InvalidFinal),
+        ("mâi", ValidationResult::InvalidVowelPattern),
+        ("tôa", ValidationResult::InvalidVowelPattern),
+        ("ping", ValidationResult::InvalidFinal),
+        // Only the start of a rhyme
+        ("tiê", ValidationResult::InvalidVowelPattern),
+    ];
+
+    #[test]
+    fn test_rhyme_table() {
+        for word in RARE_RHYMES {
+            assert_eq!(
+                validate_word(word, false),
+                ValidationResult::Valid,
+                "{}",
+                word
+            );
+        }
+        for &(word, expected) in IMPOSSIBLE_RHYMES {
+            assert_eq!(validate_word(word, false), expected, "{}", word);
+        }
+        // While typing, the start of a rhyme is fine
+        assert!(is_valid(&keys_from_str("tie")));
+        assert!(!is_valid(&keys_from_str("hoain")));
+    }
+
+    #[test]
+    fn test_strictness() {
+        use ValidationResult::*;
+        let check =
+            |word, foreign, strictness| validate_word_with_strictness(word, foreign, strictness);
+        assert_eq!(Strictness::from_u8(9), Strictness::Standard);
+        assert_eq!(check("zô", true, Strictness::Strict), InvalidInitial);
+        assert_eq!(check("Đắk", false, Strictness::Strict), InvalidFinal);
+        assert_eq!(check("Đắk", false, Strictness::Standard), Valid);
+        for word in ["ko", "zô", "ngì", "ping"] {
+            assert_eq!(check(word, false, Strictness::Relaxed), Valid, "{}", word);
+        }
+        // Structure and tone rules still apply when relaxed
+        assert_eq!(check("clau", false, Strictness::Relaxed), InvalidInitial);
+        assert_eq!(check("hoàp", false, Strictness::Relaxed), InvalidTone);
+    }
+
+    #[test]
+    fn test_fix_spelling() {
+        let fix = |w: &str| fix_spelling(&keys_from_str(w));
+        assert_eq!(fix("nge"), Some((2, &[keys::N, keys::G, keys::H][..])));
+        assert_eq!(fix("ka"), Some((1, &[keys::C][..])));
+        assert_eq!(fix("ghas"), Some((2, &[keys::G][..])));
+        assert_eq!(fix("nghe"), None);
+        assert_eq!(fix("gi"), None);
+    }
 }
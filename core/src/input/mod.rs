@@ -4,9 +4,11 @@
 //! Engine handles all pattern matching based on buffer scan.
 
 pub mod telex;
+pub mod telex_vni;
 pub mod vni;
 
 pub use telex::Telex;
+pub use telex_vni::TelexVni;
 pub use vni::Vni;
 
 use crate::data::chars::tone;
@@ -58,16 +60,30 @@ pub trait Method {
 
     /// Check if key removes diacritics
     fn remove(&self, key: u16) -> bool;
+
+    /// Check if `key` undoes a transform made by `applied` ("ass" → "as")
+    fn reverts(&self, applied: u16, key: u16) -> bool {
+        applied == key
+    }
+
+    /// `reverts` for a tone `applied` put on `vowel` ("aaa" → "aa")
+    fn reverts_tone(&self, applied: u16, key: u16, _vowel: u16) -> bool {
+        self.reverts(applied, key)
+    }
 }
 
 /// Static method instances (zero-sized types, no heap allocation)
 static TELEX: Telex = Telex;
 static VNI: Vni = Vni;
+static TELEX_VNI: TelexVni = TelexVni;
 
 /// Get method by id (returns static reference, no allocation)
+///
+/// 0 Telex (also Simple Telex), 1 VNI, 3 Telex + VNI.
 pub fn get(id: u8) -> &'static dyn Method {
     match id {
         1 => &VNI,
+        3 => &TELEX_VNI,
         _ => &TELEX,
     }
 }
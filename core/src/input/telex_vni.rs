@@ -0,0 +1,99 @@
+//! Telex + VNI Input Method
+//!
+//! Accepts Telex letters and VNI digits at the same time, as Unikey's
+//! "Telex + VNI" does: "vieetj", "vie6t5" and "vie6tj" all give "việt".
+//!
+//! Revert rule: a transform is undone by pressing its key again or the
+//! other method's key for it ("as1" → "a1", "a1s" → "as"):
+//! - s/1, f/2, r/3, x/4, j/5
+//! - a, e, o / 6 (circumflex), w/7 (horn)
+//! - d/9, z/0
+//!
+//! A circumflex typed with 6 is only undone by its own vowel: "to6o" →
+//! "too", but "to6a" is "tôa".
+
+use super::{Method, Telex, ToneType, Vni};
+use crate::data::keys;
+
+pub struct TelexVni;
+
+/// VNI digit with the same role as a Telex letter
+fn vni_twin(key: u16) -> Option<u16> {
+    match key {
+        keys::S => Some(keys::N1),
+        keys::F => Some(keys::N2),
+        keys::R => Some(keys::N3),
+        keys::X => Some(keys::N4),
+        keys::J => Some(keys::N5),
+        keys::A | keys::E | keys::O => Some(keys::N6),
+        keys::W => Some(keys::N7),
+        keys::D => Some(keys::N9),
+        keys::Z => Some(keys::N0),
+        _ => None,
+    }
+}
+
+impl Method for TelexVni {
+    fn mark(&self, key: u16) -> Option<u8> {
+        Telex.mark(key).or_else(|| Vni.mark(key))
+    }
+
+    fn tone(&self, key: u16) -> Option<ToneType> {
+        Telex.tone(key).or_else(|| Vni.tone(key))
+    }
+
+    fn tone_targets(&self, key: u16) -> &'static [u16] {
+        match Telex.tone_targets(key) {
+            [] => Vni.tone_targets(key),
+            targets => targets,
+        }
+    }
+
+    fn stroke(&self, key: u16) -> bool {
+        Telex.stroke(key) || Vni.stroke(key)
+    }
+
+    fn remove(&self, key: u16) -> bool {
+        Telex.remove(key) || Vni.remove(key)
+    }
+
+    fn reverts(&self, applied: u16, key: u16) -> bool {
+        applied == key
+            || vni_twin(applied) == Some(key)
+            // 6 stands for a, e and o: which one undoes it depends on the vowel
+            || (!keys::is_vowel(key) && vni_twin(key) == Some(applied))
+    }
+
+    fn reverts_tone(&self, applied: u16, key: u16, vowel: u16) -> bool {
+        self.reverts(applied, key) || (applied == keys::N6 && key == vowel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_both_key_sets() {
+        let m = TelexVni;
+        assert_eq!(m.mark(keys::S), Some(1));
+        assert_eq!(m.mark(keys::N1), Some(1));
+        assert_eq!(m.tone(keys::W), Some(ToneType::Horn));
+        assert_eq!(m.tone(keys::N8), Some(ToneType::Breve));
+        assert_eq!(m.tone_targets(keys::N6), &[keys::A, keys::E, keys::O]);
+        assert!(m.stroke(keys::D) && m.stroke(keys::N9));
+        assert!(m.remove(keys::Z) && m.remove(keys::N0));
+    }
+
+    #[test]
+    fn test_reverts() {
+        let m = TelexVni;
+        assert!(m.reverts(keys::S, keys::S));
+        assert!(m.reverts(keys::S, keys::N1));
+        assert!(m.reverts(keys::O, keys::N6));
+        assert!(m.reverts_tone(keys::N6, keys::O, keys::O));
+        assert!(!m.reverts_tone(keys::N6, keys::A, keys::O));
+        assert!(!m.reverts(keys::S, keys::N2));
+        assert!(!m.reverts(keys::A, keys::O));
+    }
+}
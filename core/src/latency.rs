@@ -0,0 +1,303 @@
+//! Keystroke latency metrics (off by default)
+//!
+//! For "máy yếu gõ bị trễ" reports: with metrics on, the engine times every
+//! key it handles and the stages inside it, and keeps the last `WINDOW`
+//! samples of each so the host can show p50/p99 and users can paste real
+//! numbers into an issue.
+//!
+//! - `Key`: the whole key, from `on_key_ext` in to the result out
+//! - `Validation`: Vietnamese syllable rules
+//! - `Dictionary`: word list lookups (English, Vietnamese, keep list)
+//! - `Rebuild`: turning the buffer back into output characters
+//!
+//! A stage's sample for a key is the sum of its calls during that key, and
+//! only keys that reached the stage count. Stages may nest (a lookup made
+//! while validating counts in both). Timing is per thread and samples are
+//! process-wide, like the engine itself. When off, a timed call costs one
+//! atomic load.
+//!
+//! Validation and word list lookups are answered from small caches (see
+//! `cache`) when the same word comes up again; their hits and misses are
+//! counted alongside the timings.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Samples kept per stage (older ones are overwritten)
+pub const WINDOW: usize = 1024;
+
+const STAGES: usize = 4;
+
+/// Part of key handling that is timed
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Key = 0,
+    Validation = 1,
+    Dictionary = 2,
+    Rebuild = 3,
+}
+
+impl Stage {
+    pub const ALL: [Stage; STAGES] = [
+        Stage::Key,
+        Stage::Validation,
+        Stage::Dictionary,
+        Stage::Rebuild,
+    ];
+
+    pub fn from_u8(v: u8) -> Option<Self> {
+        Self::ALL.get(v as usize).copied()
+    }
+
+    /// Name in `to_json()`
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Key => "key",
+            Stage::Validation => "validation",
+            Stage::Dictionary => "dictionary",
+            Stage::Rebuild => "rebuild",
+        }
+    }
+}
+
+/// Percentiles of one stage, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    /// Keys timed since the last reset (may exceed the window)
+    pub count: u64,
+    pub p50_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+/// Last `WINDOW` samples of one stage
+struct Ring {
+    values: Vec<u64>,
+    next: usize,
+    count: u64,
+}
+
+impl Ring {
+    const fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            next: 0,
+            count: 0,
+        }
+    }
+
+    fn push(&mut self, ns: u64) {
+        if self.values.len() < WINDOW {
+            self.values.push(ns);
+        } else {
+            self.values[self.next] = ns;
+        }
+        self.next = (self.next + 1) % WINDOW;
+        self.count += 1;
+    }
+
+    fn summary(&self) -> Option<Summary> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let mut sorted = self.values.clone();
+        sorted.sort_unstable();
+        // Nearest rank
+        let rank = |p: usize| sorted[(sorted.len() * p).div_ceil(100) - 1];
+        Some(Summary {
+            count: self.count,
+            p50_ns: rank(50),
+            p99_ns: rank(99),
+            max_ns: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Time spent in each stage during the key being handled on this thread
+#[derive(Clone, Copy, Default)]
+struct Current {
+    spent: [u64; STAGES],
+    /// Open spans per stage; only the outermost one times
+    depth: [u8; STAGES],
+    /// Bit per stage that ran
+    ran: u8,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Cache (hits, misses) per stage
+static LOOKUPS: [[AtomicU64; 2]; STAGES] =
+    [const { [AtomicU64::new(0), AtomicU64::new(0)] }; STAGES];
+static SAMPLES: Mutex<[Ring; STAGES]> =
+    Mutex::new([Ring::new(), Ring::new(), Ring::new(), Ring::new()]);
+
+thread_local! {
+    static CURRENT: Cell<Current> = const {
+        Cell::new(Current {
+            spent: [0; STAGES],
+            depth: [0; STAGES],
+            ran: 0,
+        })
+    };
+}
+
+/// Turn timing on or off (samples are kept)
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Drop all samples
+pub fn reset() {
+    let mut rings = SAMPLES.lock().unwrap_or_else(|e| e.into_inner());
+    *rings = [Ring::new(), Ring::new(), Ring::new(), Ring::new()];
+    for counter in LOOKUPS.iter().flatten() {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Count a cache lookup made in `stage`
+pub fn record_lookup(stage: Stage, hit: bool) {
+    if enabled() {
+        LOOKUPS[stage as usize][usize::from(!hit)].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Cache (hits, misses) counted in `stage` since the last reset
+pub fn lookups(stage: Stage) -> (u64, u64) {
+    let [hits, misses] = &LOOKUPS[stage as usize];
+    (hits.load(Ordering::Relaxed), misses.load(Ordering::Relaxed))
+}
+
+/// Percentiles of `stage`, None before its first sample
+pub fn summary(stage: Stage) -> Option<Summary> {
+    SAMPLES.lock().unwrap_or_else(|e| e.into_inner())[stage as usize].summary()
+}
+
+/// Time `stage` until the returned guard is dropped
+///
+/// A `Stage::Key` span starts a new key and records the samples of every
+/// stage when it ends.
+#[must_use]
+pub fn span(stage: Stage) -> Span {
+    if !enabled() {
+        return Span { stage, start: None };
+    }
+    let i = stage as usize;
+    let mut current = CURRENT.get();
+    let outermost = current.depth[i] == 0;
+    if outermost && stage == Stage::Key {
+        current = Current::default();
+    }
+    current.depth[i] = current.depth[i].saturating_add(1);
+    CURRENT.set(current);
+    Span {
+        stage,
+        start: Some((Instant::now(), outermost)),
+    }
+}
+
+/// Open timing of one stage, see `span`
+pub struct Span {
+    stage: Stage,
+    /// Start, and whether this is the outermost span of its stage
+    start: Option<(Instant, bool)>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some((start, outermost)) = self.start else {
+            return;
+        };
+        let i = self.stage as usize;
+        let mut current = CURRENT.get();
+        current.depth[i] = current.depth[i].saturating_sub(1);
+        if outermost {
+            current.spent[i] += start.elapsed().as_nanos() as u64;
+            current.ran |= 1 << i;
+        }
+        CURRENT.set(current);
+        if outermost && self.stage == Stage::Key {
+            let mut rings = SAMPLES.lock().unwrap_or_else(|e| e.into_inner());
+            for (s, ring) in rings.iter_mut().enumerate() {
+                if current.ran & (1 << s) != 0 {
+                    ring.push(current.spent[s]);
+                }
+            }
+        }
+    }
+}
+
+/// Serialize for bug reports, times in microseconds:
+/// `{"enabled":b,"window":N,"stages":{"key":{"count":N,"p50_us":F,"p99_us":F,"max_us":F},...},
+/// "cache":{"validation":{"hits":N,"misses":N},...}}`
+/// (stages without samples or lookups are left out)
+pub fn to_json() -> String {
+    let us = |ns: u64| format!("{:.1}", ns as f64 / 1000.0);
+    let stages: Vec<String> = Stage::ALL
+        .iter()
+        .filter_map(|&stage| {
+            let s = summary(stage)?;
+            Some(format!(
+                "\"{}\":{{\"count\":{},\"p50_us\":{},\"p99_us\":{},\"max_us\":{}}}",
+                stage.name(),
+                s.count,
+                us(s.p50_ns),
+                us(s.p99_ns),
+                us(s.max_ns)
+            ))
+        })
+        .collect();
+    let cache: Vec<String> = Stage::ALL
+        .iter()
+        .filter_map(|&stage| {
+            let (hits, misses) = lookups(stage);
+            (hits + misses > 0).then(|| {
+                format!(
+                    "\"{}\":{{\"hits\":{},\"misses\":{}}}",
+                    stage.name(),
+                    hits,
+                    misses
+                )
+            })
+        })
+        .collect();
+    format!(
+        "{{\"enabled\":{},\"window\":{},\"stages\":{{{}}},\"cache\":{{{}}}}}",
+        enabled(),
+        WINDOW,
+        stages.join(","),
+        cache.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_percentiles() {
+        let mut ring = Ring::new();
+        assert_eq!(ring.summary(), None);
+        for ns in (1..=100).rev() {
+            ring.push(ns * 1000);
+        }
+        let s = ring.summary().unwrap();
+        assert_eq!(
+            (s.count, s.p50_ns, s.p99_ns, s.max_ns),
+            (100, 50_000, 99_000, 100_000)
+        );
+
+        // Only the window is ranked, the count keeps going
+        for _ in 0..WINDOW {
+            ring.push(7);
+        }
+        let s = ring.summary().unwrap();
+        assert_eq!((s.count, s.p99_ns, s.max_ns), (100 + WINDOW as u64, 7, 7));
+    }
+}
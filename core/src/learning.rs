@@ -0,0 +1,201 @@
+//! Learned auto-restore preferences (local only)
+//!
+//! Auto-restore guesses whether a word was English from its shape. When the
+//! user keeps correcting the same guess, the engine follows them instead:
+//!
+//! - Undoing an auto-restore (Backspace right after it, or `undo_last`)
+//!   votes to keep the Vietnamese rendering of that word.
+//! - Restoring a kept word with the restore key (ESC) votes to restore it.
+//!
+//! After `THRESHOLD` more votes one way than the other, the decision for that
+//! word is overridden. Off by default; words are keyed by their raw keystrokes
+//! (lowercase) and only written by `save()` to a path chosen by the host.
+
+use std::collections::HashMap;
+use std::fs;
+
+/// Net votes needed before a learned preference overrides auto-restore
+pub const THRESHOLD: u32 = 2;
+
+/// What the user wants for a word
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preference {
+    /// Keep the Vietnamese rendering
+    Vietnamese,
+    /// Restore the raw keystrokes
+    English,
+}
+
+/// Correction votes per raw word
+#[derive(Debug, Clone, Default)]
+pub struct Learning {
+    enabled: bool,
+    /// Raw word (lowercase) → (keep Vietnamese, restore English) votes
+    votes: HashMap<String, (u32, u32)>,
+}
+
+/// Lookup key for a raw word
+fn key(raw: &[char]) -> String {
+    raw.iter().flat_map(|c| c.to_lowercase()).collect()
+}
+
+impl Learning {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The user undid the auto-restore of `raw`
+    pub fn record_keep(&mut self, raw: &[char]) {
+        self.record(raw, Preference::Vietnamese);
+    }
+
+    /// The user restored `raw` after the engine kept it as Vietnamese
+    pub fn record_restore(&mut self, raw: &[char]) {
+        self.record(raw, Preference::English);
+    }
+
+    fn record(&mut self, raw: &[char], pref: Preference) {
+        if !self.enabled || raw.is_empty() {
+            return;
+        }
+        let votes = self.votes.entry(key(raw)).or_default();
+        match pref {
+            Preference::Vietnamese => votes.0 += 1,
+            Preference::English => votes.1 += 1,
+        }
+    }
+
+    /// Learned preference for `raw`, None if not learned (or disabled)
+    pub fn preference(&self, raw: &[char]) -> Option<Preference> {
+        if !self.enabled || raw.is_empty() {
+            return None;
+        }
+        let &(keep, restore) = self.votes.get(&key(raw))?;
+        if keep >= restore + THRESHOLD {
+            Some(Preference::Vietnamese)
+        } else if restore >= keep + THRESHOLD {
+            Some(Preference::English)
+        } else {
+            None
+        }
+    }
+
+    /// Number of words with votes
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+
+    /// Forget all votes (keeps the enabled flag)
+    pub fn reset(&mut self) {
+        self.votes.clear();
+    }
+
+    /// Serialize as `<keep> <restore> <word>` lines, sorted by word
+    pub fn to_text(&self) -> String {
+        let mut words: Vec<_> = self.votes.iter().collect();
+        words.sort_by(|a, b| a.0.cmp(b.0));
+        words
+            .iter()
+            .map(|(word, (keep, restore))| format!("{} {} {}\n", keep, restore, word))
+            .collect()
+    }
+
+    /// Merge votes from `to_text()` output (malformed lines are ignored)
+    pub fn load_text(&mut self, text: &str) {
+        for line in text.lines() {
+            let mut parts = line.splitn(3, ' ');
+            let (Some(keep), Some(restore), Some(word)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let (Ok(keep), Ok(restore)) = (keep.parse(), restore.parse()) else {
+                continue;
+            };
+            if !word.is_empty() {
+                self.votes.insert(word.to_lowercase(), (keep, restore));
+            }
+        }
+    }
+
+    /// Write learned words to `path`
+    pub fn save(&self, path: &str) -> bool {
+        fs::write(path, self.to_text()).is_ok()
+    }
+
+    /// Replace learned words with those stored at `path`
+    pub fn load(&mut self, path: &str) -> bool {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                self.reset();
+                self.load_text(&text);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let mut l = Learning::new();
+        for _ in 0..3 {
+            l.record_keep(&chars("user"));
+        }
+        assert!(l.is_empty());
+        assert_eq!(l.preference(&chars("user")), None);
+    }
+
+    #[test]
+    fn test_threshold_and_net_votes() {
+        let mut l = Learning::new();
+        l.set_enabled(true);
+        l.record_keep(&chars("User"));
+        assert_eq!(l.preference(&chars("user")), None);
+        l.record_keep(&chars("user"));
+        assert_eq!(l.preference(&chars("USER")), Some(Preference::Vietnamese));
+        // A contradicting vote undoes the preference
+        l.record_restore(&chars("user"));
+        assert_eq!(l.preference(&chars("user")), None);
+        for _ in 0..3 {
+            l.record_restore(&chars("user"));
+        }
+        assert_eq!(l.preference(&chars("user")), Some(Preference::English));
+    }
+
+    #[test]
+    fn test_text_roundtrip() {
+        let mut l = Learning::new();
+        l.set_enabled(true);
+        l.record_keep(&chars("dduwowcj"));
+        l.record_restore(&chars("text"));
+        l.record_restore(&chars("text"));
+        assert_eq!(l.to_text(), "1 0 dduwowcj\n0 2 text\n");
+
+        let mut loaded = Learning::new();
+        loaded.set_enabled(true);
+        loaded.load_text(&format!("{}bad line\n", l.to_text()));
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.preference(&chars("text")), Some(Preference::English));
+    }
+}
@@ -19,39 +19,186 @@
 //! // Clean up on word boundary
 //! ime_clear();
 //! ```
+//!
+//! # Thread Safety
+//!
+//! The engine is a process-wide singleton behind a `Mutex`. Every `ime_*`
+//! function takes the lock for its whole duration, so the host may call in
+//! from any thread (e.g. keyboard hook thread + settings UI thread).
+//! Calls are serialized, not reordered: a keystroke and a setting change
+//! racing each other are applied in lock-acquisition order.
+//!
+//! Returned `Result` pointers are owned by the caller and do not borrow
+//! engine state; they may be read and freed on any thread.
+
+pub mod logging;
 
+pub mod cache;
+pub mod config;
+pub mod convert;
+pub mod corpus;
+pub mod daemon;
 pub mod data;
+pub mod encoding;
 pub mod engine;
 pub mod input;
+pub mod json;
+pub mod latency;
+pub mod learning;
+pub mod managed;
+pub mod selftest;
+pub mod stats;
+pub mod testkit;
 pub mod updater;
 pub mod utils;
 
-use engine::{Engine, Result};
+use data::dictionary;
+use data::quirks;
+use engine::capitalize::CapitalizeRule;
+use engine::hotkey::{Hotkey, HotkeyAction};
+use engine::settings;
+use engine::terminal::TerminalMode;
+use engine::validation::{self, Strictness, ValidationResult};
+use engine::{DigitPolicy, Engine, RepeatPolicy, Result, RevertPolicy, ToneStyle};
 use std::sync::Mutex;
 
+/// C ABI version, mirrored as `GONHANH_ABI_VERSION` in `include/gonhanh_core.h`.
+///
+/// Bump when an existing export changes signature or `Result` changes layout.
+pub const ABI_VERSION: u32 = 2;
+
+/// "No key" keycode for key settings, `GONHANH_KEY_NONE` in the header.
+const KEY_NONE: u16 = 0xFFFF;
+
 // Global engine instance (thread-safe via Mutex)
 static ENGINE: Mutex<Option<Engine>> = Mutex::new(None);
 
+// The singleton is only sound if Engine can move between host threads.
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<Engine>();
+};
+
 /// Lock the engine mutex, recovering from poisoned state if needed (for tests)
 fn lock_engine() -> std::sync::MutexGuard<'static, Option<Engine>> {
     ENGINE.lock().unwrap_or_else(|e| e.into_inner())
 }
 
+/// Host callback for engine events: event code and its text (UTF-8, valid
+/// only during the call)
+pub type EventCallback = extern "C" fn(event: u8, text: *const std::os::raw::c_char);
+
+static EVENT_CALLBACK: Mutex<Option<EventCallback>> = Mutex::new(None);
+
+/// Host hook for committed words: the word (UTF-8, valid only during the
+/// call) in, a rewrite or null to keep it out. The rewrite is copied when
+/// the call returns; the host keeps it valid until then.
+pub type WordHookCallback =
+    extern "C" fn(word: *const std::os::raw::c_char) -> *const std::os::raw::c_char;
+
+/// `WordHook` calling back into the host
+#[derive(Debug)]
+struct HostWordHook(WordHookCallback);
+
+impl engine::hooks::WordHook for HostWordHook {
+    fn on_commit(&mut self, word: &str) -> Option<String> {
+        let word = std::ffi::CString::new(word).ok()?;
+        let rewrite = (self.0)(word.as_ptr());
+        if rewrite.is_null() {
+            return None;
+        }
+        // SAFETY: the host returns null or a NUL-terminated string that stays
+        // valid until this call returns
+        let rewrite = unsafe { std::ffi::CStr::from_ptr(rewrite) };
+        rewrite.to_str().ok().map(str::to_string)
+    }
+}
+
+/// Run a key handler, then report its events and run the word hook with
+/// the engine unlocked, merging its rewrite into the key's result
+fn process_key(handle: impl FnOnce(&mut Engine) -> Result) -> *mut Result {
+    let (mut r, events, hook_call) = {
+        let mut guard = lock_engine();
+        let Some(ref mut e) = *guard else {
+            return std::ptr::null_mut();
+        };
+        let r = handle(e);
+        (r, e.take_events(), e.take_word_hook_call())
+    };
+    report_events(events);
+    if let Some(mut call) = hook_call {
+        call.run();
+        if let Some(ref mut e) = *lock_engine() {
+            e.finish_word_hook_call(call, &mut r);
+        }
+    }
+    Box::into_raw(Box::new(r))
+}
+
+/// Pass events to the host callback (call with the engine unlocked)
+fn report_events(events: Vec<engine::Event>) {
+    let callback = *EVENT_CALLBACK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(callback) = callback {
+        for event in events {
+            if let Ok(text) = std::ffi::CString::new(event.text()) {
+                callback(event.code(), text.as_ptr());
+            }
+        }
+    }
+}
+
 // ============================================================
 // FFI Interface
 // ============================================================
 
+/// Get the C ABI version of this library.
+///
+/// Hosts should compare this against the `GONHANH_ABI_VERSION` they were
+/// built with and refuse to load a mismatching DLL.
+#[no_mangle]
+pub extern "C" fn ime_abi_version() -> u32 {
+    ABI_VERSION
+}
+
 /// Initialize the IME engine.
 ///
 /// Must be called exactly once before any other `ime_*` functions.
-/// Thread-safe: uses internal mutex.
+/// Thread-safe: uses internal mutex. Loads the administrator's managed
+/// config, if any, and applies its locked options (`ime_managed_load`),
+/// then the user dictionaries in the config directory
+/// (`ime_user_dictionaries_reload`).
 ///
 /// # Panics
 /// Panics if mutex is poisoned (only if previous call panicked).
 #[no_mangle]
 pub extern "C" fn ime_init() {
+    if let Err(err) = managed::load() {
+        let path = managed::system_path();
+        logging::log_warn!("managed", "{} path=\"{}\"", err, path.display());
+    }
+    if let Some(dir) = config::path(config::DICTIONARIES_DIR) {
+        if let Err(err) = dictionary::load_user_dir(&dir) {
+            logging::log_warn!("dictionary", "{} path=\"{}\"", err, dir.display());
+        }
+    }
+    let mut engine = Engine::new();
+    engine.apply_managed();
+    let mut guard = lock_engine();
+    *guard = Some(engine);
+}
+
+/// Run an option setter unless the managed config locks `option`
+///
+/// No-op if engine not initialized.
+fn with_option(option: &str, set: impl FnOnce(&mut Engine)) {
+    if managed::is_locked(option) {
+        logging::log_debug!("managed", "locked option={}", option);
+        return;
+    }
     let mut guard = lock_engine();
-    *guard = Some(Engine::new());
+    if let Some(ref mut e) = *guard {
+        set(e);
+    }
 }
 
 /// Process a key event and return the result.
@@ -59,7 +206,9 @@ pub extern "C" fn ime_init() {
 /// # Arguments
 /// * `key` - macOS virtual keycode (0-127 for standard keys)
 /// * `caps` - true if CapsLock is pressed (for uppercase letters)
-/// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
+/// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME). Ctrl or
+///   Option + Backspace is a word delete; report Cmd+Backspace (delete to
+///   line start) with `ime_clear_all` instead.
 ///
 /// # Returns
 /// * Pointer to `Result` struct (caller must free with `ime_free`)
@@ -76,13 +225,7 @@ pub extern "C" fn ime_init() {
 /// use `ime_key_ext` with the shift parameter.
 #[no_mangle]
 pub extern "C" fn ime_key(key: u16, caps: bool, ctrl: bool) -> *mut Result {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        let r = e.on_key(key, caps, ctrl);
-        Box::into_raw(Box::new(r))
-    } else {
-        std::ptr::null_mut()
-    }
+    process_key(|e| e.on_key(key, caps, ctrl))
 }
 
 /// Process a key event with extended parameters.
@@ -105,13 +248,17 @@ pub extern "C" fn ime_key(key: u16, caps: bool, ctrl: bool) -> *mut Result {
 /// - etc.
 #[no_mangle]
 pub extern "C" fn ime_key_ext(key: u16, caps: bool, ctrl: bool, shift: bool) -> *mut Result {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        let r = e.on_key_ext(key, caps, ctrl, shift);
-        Box::into_raw(Box::new(r))
-    } else {
-        std::ptr::null_mut()
-    }
+    process_key(|e| e.on_key_ext(key, caps, ctrl, shift))
+}
+
+/// Process an auto-repeated key down (key held by the user).
+///
+/// Same arguments and result as `ime_key_ext`; hosts send the first press
+/// with `ime_key_ext` and the repeats that follow with this. How repeats
+/// are handled is set with `ime_repeat_policy`.
+#[no_mangle]
+pub extern "C" fn ime_key_repeat(key: u16, caps: bool, ctrl: bool, shift: bool) -> *mut Result {
+    process_key(|e| e.on_key_repeat(key, caps, ctrl, shift))
 }
 
 /// Process a key event with the actual Unicode character.
@@ -144,32 +291,72 @@ pub extern "C" fn ime_key_with_char(
     shift: bool,
     char_code: u32,
 ) -> *mut Result {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        let ch = if char_code > 0 {
-            char::from_u32(char_code)
-        } else {
-            None
-        };
-        let r = e.on_key_with_char(key, caps, ctrl, shift, ch);
-        Box::into_raw(Box::new(r))
+    let ch = if char_code > 0 {
+        char::from_u32(char_code)
     } else {
-        std::ptr::null_mut()
+        None
+    };
+    process_key(|e| e.on_key_with_char(key, caps, ctrl, shift, ch))
+}
+
+/// Get the candidate list for the word in progress as JSON.
+///
+/// Other tone placements, a respelled initial, common syllables the word
+/// can still become and the keys as typed, for hosts that draw a popup:
+/// `[{"text":"hòa","kind":"tone"},{"text":"hoaf","kind":"raw"}]`
+/// (kinds: "tone", "spelling", "prediction", "raw"). Empty array when no
+/// word is being typed.
+///
+/// # Returns
+/// * Pointer to a null-terminated JSON string (free with `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_candidates() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => into_c_string(e.candidates_json()),
+        None => std::ptr::null_mut(),
     }
 }
 
+/// Replace the word in progress with candidate `index` of `ime_candidates`.
+///
+/// The picked word stays the word in progress. Returns a `Send` result to
+/// apply like any other, or `None` if there is no such candidate.
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_select_candidate(index: u32) -> *mut Result {
+    process_key(|e| e.select_candidate(index as usize))
+}
+
+/// Undo the most recent automatic action (bind to a hotkey).
+///
+/// Reverses an English auto-restore, shortcut expansion or auto-capitalize
+/// performed by the previous key, putting back what was on screen before.
+/// Returns a `Send` result to apply like any other, or `None` if the
+/// previous key did nothing automatic.
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_undo_last() -> *mut Result {
+    process_key(Engine::undo_last)
+}
+
 /// Set the input method.
 ///
 /// # Arguments
-/// * `method` - 0 for Telex, 1 for VNI
+/// * `method` - 0 for Telex, 1 for VNI, 2 for Simple Telex (w is never a
+///   standalone ư; `]`/`[` give ư/ơ), 3 for Telex + VNI (both key sets)
 ///
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_method(method: u8) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_method(method);
-    }
+    with_option("method", |e| e.set_method(method));
 }
 
 /// Enable or disable the engine.
@@ -191,10 +378,17 @@ pub extern "C" fn ime_enabled(enabled: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_skip_w_shortcut(skip: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_skip_w_shortcut(skip);
-    }
+    with_option("skip_w_shortcut", |e| e.set_skip_w_shortcut(skip));
+}
+
+/// Set whether a word-initial 'w' stays 'w' in Telex.
+///
+/// When `enabled` is true, "web" and "www" type literally; after a
+/// consonant 'w' still becomes 'ư' ("nhw" → "như"). A word-initial 'ư' is
+/// typed "uw". Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_initial_w_literal(enabled: bool) {
+    with_option("initial_w_literal", |e| e.set_initial_w_literal(enabled));
 }
 
 /// Set whether bracket shortcuts are enabled: ] → ư, [ → ơ (Issue #159)
@@ -203,10 +397,7 @@ pub extern "C" fn ime_skip_w_shortcut(skip: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_bracket_shortcut(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_bracket_shortcut(enabled);
-    }
+    with_option("bracket_shortcut", |e| e.set_bracket_shortcut(enabled));
 }
 
 /// Set whether ESC key restores raw ASCII input.
@@ -216,10 +407,41 @@ pub extern "C" fn ime_bracket_shortcut(enabled: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_esc_restore(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_esc_restore(enabled);
-    }
+    with_option("esc_restore", |e| e.set_esc_restore(enabled));
+}
+
+/// Set the key that restores raw ASCII input (default: ESC).
+///
+/// Only active while ESC restore is enabled (`ime_esc_restore`). Use a key
+/// that produces no text, e.g. an F-key or backquote.
+///
+/// # Arguments
+/// * `key` - macOS virtual keycode
+///
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_restore_key(key: u16) {
+    with_option("restore_key", |e| e.set_restore_key(key));
+}
+
+/// Set the key that removes diacritics (default: z in Telex, 0 in VNI).
+///
+/// # Arguments
+/// * `method` - 0: Telex (also Simple Telex), 1: VNI
+/// * `key` - macOS virtual keycode, or 0xFFFF (`GONHANH_KEY_NONE`) so no
+///   key removes diacritics and z/0 always type themselves
+///
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_remove_key(method: u8, key: u16) {
+    let option = if method == 1 {
+        "remove_key_vni"
+    } else {
+        "remove_key_telex"
+    };
+    with_option(option, |e| {
+        e.set_remove_key(method, (key != KEY_NONE).then_some(key))
+    });
 }
 
 /// Set whether to enable free tone placement (skip validation).
@@ -230,23 +452,42 @@ pub extern "C" fn ime_esc_restore(enabled: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_free_tone(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_free_tone(enabled);
-    }
+    with_option("free_tone", |e| e.set_free_tone(enabled));
 }
 
-/// Set whether to use modern orthography for tone placement.
+/// Set whether spelling is checked while typing.
 ///
-/// When `modern` is true: hoà, thuý (tone on second vowel - new style)
-/// When `modern` is false (default): hòa, thúy (tone on first vowel - traditional)
+/// When `enabled` is false, no keystroke is refused as invalid Vietnamese
+/// and English auto-restore (`ime_english_auto_restore`) only acts at word
+/// boundaries: "never block my keys, fix the word at Space".
+/// Default: on. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_spell_check(enabled: bool) {
+    with_option("spell_check", |e| e.set_spell_check(enabled));
+}
+
+/// Set whether to use new-style tone placement.
+///
+/// When `modern` is true (default): hoà, thuý (tone on second vowel - new style)
+/// When `modern` is false: hòa, thúy (tone on first vowel - traditional)
+/// Same as `ime_tone_style(modern ? 1 : 0)`.
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_modern(modern: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_modern_tone(modern);
-    }
+    with_option("tone_style", |e| e.set_modern_tone(modern));
+}
+
+/// Set the tone placement style for open oa, oe, uy syllables.
+///
+/// `style` 0: traditional, mark on the first vowel (hòa, khỏe, thủy).
+/// 1 (default): new style, mark on the main vowel (hoà, khoẻ, thuỷ).
+/// Unknown values select new style. Other syllables are the same in both.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_tone_style(style: u8) {
+    with_option("tone_style", |e| {
+        e.set_tone_style(ToneStyle::from_u8(style))
+    });
 }
 
 /// Enable/disable English auto-restore (experimental feature).
@@ -257,219 +498,2398 @@ pub extern "C" fn ime_modern(modern: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_english_auto_restore(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_english_auto_restore(enabled);
-    }
+    with_option("english_auto_restore", |e| {
+        e.set_english_auto_restore(enabled)
+    });
 }
 
-/// Enable/disable auto-capitalize after sentence-ending punctuation.
+/// Enable/disable `-` as a soft boundary for hyphenated compounds.
 ///
-/// When `enabled` is true, automatically capitalizes the first letter
-/// after sentence-ending punctuation (. ! ? Enter).
-/// When `enabled` is false (default), no auto-capitalize happens.
+/// When `enabled` is true, the segment before a hyphen is kept so Backspace
+/// over the hyphen restores it for tone edits. When `enabled` is false
+/// (default), `-` ends the word like other punctuation.
 /// No-op if engine not initialized.
 #[no_mangle]
-pub extern "C" fn ime_auto_capitalize(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_auto_capitalize(enabled);
-    }
+pub extern "C" fn ime_hyphen_soft_boundary(enabled: bool) {
+    with_option("hyphen_soft_boundary", |e| {
+        e.set_hyphen_soft_boundary(enabled)
+    });
 }
 
-/// Enable/disable foreign consonants (z, w, j, f) as valid initial consonants.
+/// Enable/disable URL, email and path detection.
 ///
-/// When `enabled` is true, allows z, w, j, f as valid Vietnamese consonants
-/// for typing loanwords while still getting Vietnamese diacritics.
-/// When `enabled` is false (default), these letters are treated as invalid initials.
+/// When `enabled` is true (default), a word starting with "http" or "www.",
+/// text followed by "@", or a path ("/usr", "~/", "C:\\") is typed without
+/// Vietnamese transforms until the next whitespace.
 /// No-op if engine not initialized.
 #[no_mangle]
-pub extern "C" fn ime_allow_foreign_consonants(enabled: bool) {
+pub extern "C" fn ime_url_passthrough(enabled: bool) {
+    with_option("url_passthrough", |e| e.set_url_passthrough(enabled));
+}
+
+/// Let results that delete text come as `GONHANH_ACTION_REPLACE`.
+///
+/// For hosts that can select the `backspace` characters before the caret
+/// and type `chars` over them in one step (AX API, TSF) instead of sending
+/// backspaces. Platforms toggle this per app on focus change. Break keys
+/// follow the replacement as after a Send unless the key is consumed.
+/// Default false. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_selection_replace(enabled: bool) {
     let mut guard = lock_engine();
     if let Some(ref mut e) = *guard {
-        e.set_allow_foreign_consonants(enabled);
+        e.set_selection_replace(enabled);
     }
 }
 
-/// Clear the input buffer.
+/// Count backspaces in code points for apps storing decomposed text.
 ///
-/// Call on word boundaries (space, punctuation).
-/// Preserves word history for backspace-after-space feature.
-/// No-op if engine not initialized.
+/// In apps that keep text as NFD ("ấ" = "a" + two combining marks), one
+/// character takes a backspace per code point. Results that delete such
+/// characters then carry the larger `backspace` and
+/// `GONHANH_FLAG_DECOMPOSED`. Platforms toggle this per app on focus
+/// change. Default false. No-op if engine not initialized.
 #[no_mangle]
-pub extern "C" fn ime_clear() {
+pub extern "C" fn ime_decomposed_backspace(enabled: bool) {
     let mut guard = lock_engine();
     if let Some(ref mut e) = *guard {
-        e.clear();
+        e.set_decomposed_backspace(enabled);
     }
 }
 
-/// Clear everything including word history.
+/// Enable/disable code-context mode (identifier detection).
 ///
-/// Call when cursor position changes (mouse click, arrow keys, focus change).
-/// This prevents accidental restore from stale history.
-/// No-op if engine not initialized.
+/// When `enabled` is true, words containing `_`, camelCase humps or (Telex)
+/// letters mixed with digits are typed without transforms until the next
+/// whitespace. Platforms toggle this per app (IDEs, terminals) on focus
+/// change. Default false. No-op if engine not initialized.
 #[no_mangle]
-pub extern "C" fn ime_clear_all() {
+pub extern "C" fn ime_code_context(enabled: bool) {
     let mut guard = lock_engine();
     if let Some(ref mut e) = *guard {
-        e.clear_all();
+        e.set_code_context(enabled);
     }
 }
 
-/// Get the full composed buffer as UTF-32 codepoints.
+/// Enable/disable Unicode code-point input.
 ///
-/// Used for "Select All + Replace" injection method where the entire
-/// buffer content is needed instead of incremental backspace + chars.
+/// When `enabled` is true, typing "u+" and 1-6 hex digits followed by Space
+/// replaces them with the character ("u+1ec7" → "ệ"). Default false.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_unicode_input(enabled: bool) {
+    with_option("unicode_input", |e| e.set_unicode_input(enabled));
+}
+
+/// Enable/disable the built-in arrow/symbol abbreviation pack.
 ///
-/// # Arguments
-/// * `out` - Pointer to output buffer for UTF-32 codepoints
-/// * `max_len` - Maximum number of codepoints to write
+/// When `enabled` is true, `->` → →, `<-` → ←, `=>` → ⇒, `<=` → ≤,
+/// `>=` → ≥, `!=` → ≠, `+-` → ±, `~=` → ≈. User shortcuts take precedence
+/// and the pack never fires in code-context mode. Default false.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_symbol_pack(enabled: bool) {
+    with_option("symbol_pack", |e| e.set_symbol_pack(enabled));
+}
+
+/// Set how Telex handles digits typed inside a word.
 ///
-/// # Returns
-/// Number of codepoints written to `out`.
+/// `policy` 0 (default): the digit stays in the word, which is then typed
+/// literally ("k3anhs"). 1: the digit ends the word and the letters after it
+/// are typed as a new word ("k3anhs" → "k3ánh"). VNI is unaffected.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_digit_policy(policy: u8) {
+    with_option("digit_policy", |e| {
+        e.set_digit_policy(DigitPolicy::from_u8(policy))
+    });
+}
+
+/// Set what a modifier key does when pressed again after its revert.
 ///
-/// # Safety
-/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+/// `policy` 0 (default): it is typed as a letter ("aaaa" → "aaa",
+/// "asss" → "ass"). 1: it re-applies the transform ("aaaa" → "â",
+/// "asss" → "á"), so repeated presses cycle.
+/// No-op if engine not initialized.
 #[no_mangle]
-pub unsafe extern "C" fn ime_get_buffer(out: *mut u32, max_len: i64) -> i64 {
-    if out.is_null() || max_len <= 0 {
-        return 0;
-    }
+pub extern "C" fn ime_revert_policy(policy: u8) {
+    with_option("revert_policy", |e| {
+        e.set_revert_policy(RevertPolicy::from_u8(policy))
+    });
+}
 
-    let guard = lock_engine();
-    if let Some(ref e) = *guard {
-        let full = e.get_buffer_string();
-        let utf32: Vec<u32> = full.chars().map(|c| c as u32).collect();
-        let len = utf32.len().min(max_len as usize);
-        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
-        len as i64
-    } else {
-        0
-    }
+/// Set what a key does when the OS auto-repeats it (see `ime_key_repeat`).
+///
+/// `policy` 0 (default): repeats are typed as-is and the word is left as
+/// it is on screen ("ásss" when holding `s`). 1: every repeat is handled
+/// as a separate key press.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_repeat_policy(policy: u8) {
+    with_option("repeat_policy", |e| {
+        e.set_repeat_policy(RepeatPolicy::from_u8(policy))
+    });
 }
 
-/// Free a result pointer returned by `ime_key`.
+/// Set which word boundaries trigger English auto-restore.
 ///
-/// # Safety
-/// * `r` must be a pointer returned by `ime_key`, or null
-/// * Must be called exactly once per non-null `ime_key` return
-/// * Do not use `r` after calling this function
+/// `triggers` is a bitmask: 0x01 Space, 0x02 Tab, 0x04 Enter, 0x08
+/// punctuation and other break keys. Default 0x0F (all). A word committed
+/// by a boundary outside the set is kept as typed.
+/// No-op if engine not initialized.
 #[no_mangle]
-pub unsafe extern "C" fn ime_free(r: *mut Result) {
-    if !r.is_null() {
-        drop(Box::from_raw(r));
-    }
+pub extern "C" fn ime_auto_restore_triggers(triggers: u8) {
+    with_option("auto_restore_triggers", |e| {
+        e.set_auto_restore_triggers(triggers)
+    });
 }
 
-// ============================================================
-// Shortcut FFI
-// ============================================================
+/// Enable/disable repeated-word detection.
+///
+/// When enabled, a word committed right after the same word ("của của")
+/// is reported through the event callback as `GONHANH_EVENT_REPEATED_WORD`
+/// so the frontend can underline it. Intended reduplications ("từ từ") are
+/// not reported.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_repeated_word_check(enabled: bool) {
+    with_option("repeated_word_check", |e| {
+        e.set_repeated_word_check(enabled)
+    });
+}
 
-/// Add a shortcut to the engine.
+/// Enable/disable Telex/VNI auto-detection.
 ///
-/// # Arguments
-/// * `trigger` - C string for trigger (e.g., "vn")
-/// * `replacement` - C string for replacement (e.g., "Việt Nam")
+/// When enabled, the first words typed (from now, or from the next
+/// `ime_method` call) are checked for keys that only make sense in one
+/// method ("vieetj" vs "vie65t"). Once one method is clearly ahead the
+/// engine switches to it and reports `GONHANH_EVENT_METHOD_CHANGED` with
+/// text "telex" or "vni", so the UI can show it.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_auto_detect_method(enabled: bool) {
+    with_option("auto_detect_method", |e| e.set_auto_detect_method(enabled));
+}
+
+/// Enable/disable tone style inference.
 ///
-/// # Safety
-/// Both pointers must be valid null-terminated UTF-8 strings.
+/// When enabled, words the user commits in the other tone placement
+/// ("hòa" while the style is "hoà"), picked with `ime_select_candidate` or
+/// edited on screen, count as corrections. After three the engine switches
+/// style and reports `GONHANH_EVENT_TONE_STYLE_CHANGED` with text "new" or
+/// "traditional", so the UI can confirm (or switch back with
+/// `ime_tone_style`).
+/// Default: off. No-op if engine not initialized.
 #[no_mangle]
-pub unsafe extern "C" fn ime_add_shortcut(
-    trigger: *const std::os::raw::c_char,
-    replacement: *const std::os::raw::c_char,
-) {
-    if trigger.is_null() || replacement.is_null() {
-        return;
-    }
+pub extern "C" fn ime_auto_tone_style(enabled: bool) {
+    with_option("auto_tone_style", |e| e.set_auto_tone_style(enabled));
+}
 
-    let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    let replacement_str = match std::ffi::CStr::from_ptr(replacement).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
-    };
+/// Set the callback for engine events, or `null` to remove it.
+///
+/// Called on the thread that passed the key, after `ime_key*` has
+/// processed it and released the engine, so the callback may call back
+/// into `ime_*`. `text` is only valid during the call. Events that occur
+/// with no callback set are dropped.
+#[no_mangle]
+pub extern "C" fn ime_set_event_callback(callback: Option<EventCallback>) {
+    *EVENT_CALLBACK.lock().unwrap_or_else(|e| e.into_inner()) = callback;
+}
 
+/// Set the hook committed words are passed to, or `null` to remove it.
+///
+/// The host runs its own script engine (Lua, WASM) behind the hook, which
+/// may return a rewrite of the word or `null` to keep it. It is called
+/// after the key that committed the word, with the engine unlocked, so it
+/// may call `ime_*`; the rewrite goes out in that key's result. Only called
+/// once turned on with `ime_word_hooks`. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_set_word_hook(hook: Option<WordHookCallback>) {
     let mut guard = lock_engine();
     if let Some(ref mut e) = *guard {
-        // Auto-detect shortcut type:
-        // - If trigger contains only non-letter chars (like "->", "=>"), use immediate trigger
-        // - Otherwise use word boundary trigger (traditional abbreviations like "vn" → "Việt Nam")
-        let is_symbol_trigger = trigger_str.chars().all(|c| !c.is_alphabetic());
-        let shortcut = if is_symbol_trigger {
-            engine::shortcut::Shortcut::immediate(trigger_str, replacement_str)
-        } else {
-            engine::shortcut::Shortcut::new(trigger_str, replacement_str)
-        };
-        e.shortcuts_mut().add(shortcut);
+        e.set_word_hook(hook.map(|h| Box::new(HostWordHook(h)) as _));
     }
 }
 
-/// Remove a shortcut from the engine.
+/// Turn word hooks on or off.
 ///
-/// # Arguments
-/// * `trigger` - C string for trigger to remove
+/// Rewrites go out like word boundary shortcuts, which are tried first,
+/// with the key that ends the word. A hook taking longer than 50 ms on a word
+/// has its rewrite dropped and turns hooks off again.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_word_hooks(enabled: bool) {
+    with_option("word_hooks", |e| e.set_word_hooks(enabled));
+}
+
+/// Set the auto language switch.
+///
+/// After `words` English words in a row are auto-restored, Vietnamese
+/// processing is suspended and keys pass through as typed. It resumes on the
+/// first word that reads as Vietnamese, which is converted at its boundary.
+/// 0 (default) turns the switch off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_auto_english_switch(words: u8) {
+    with_option("auto_english_switch", |e| e.set_auto_english_switch(words));
+}
+
+/// Whether Vietnamese is suspended by the auto language switch.
+///
+/// Frontends poll this after each key to show an "E" indicator.
+///
+/// # Returns
+/// `false` if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_english_suspended() -> bool {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => e.english_suspended(),
+        None => false,
+    }
+}
+
+/// Press or release the temporary English key (press-and-hold).
+///
+/// While held, every key passes through untouched and the Vietnamese state
+/// is kept, so an English word can be typed mid-sentence. Call with true on
+/// key down of the chosen key (e.g. Right-Alt) and false on key up.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_english_hold(held: bool) {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.set_english_hold(held);
+    }
+}
+
+/// Enable/disable capitalizing a standalone "i" in English text.
+///
+/// When enabled, "i" typed as a whole word becomes "I" at the word boundary
+/// if the last word with transforms was restored to English, or while the
+/// auto language switch has suspended Vietnamese.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_english_capital_i(enabled: bool) {
+    with_option("english_capital_i", |e| e.set_english_capital_i(enabled));
+}
+
+/// Enable/disable undoing an auto-restore with an immediate Backspace.
+///
+/// When `enabled` is true, Backspace right after an English auto-restore
+/// brings back the Vietnamese word ("user " → "uẻ") and keeps it at the next
+/// word boundary. When `enabled` is false (default), Backspace only deletes
+/// the space. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_backspace_undo_restore(enabled: bool) {
+    with_option("backspace_undo_restore", |e| {
+        e.set_backspace_undo_restore(enabled)
+    });
+}
+
+/// Enable/disable auto-capitalize after sentence-ending punctuation.
+///
+/// When `enabled` is true, automatically capitalizes the first letter
+/// after sentence-ending punctuation (. ! ? Enter).
+/// When `enabled` is false (default), no auto-capitalize happens.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_auto_capitalize(enabled: bool) {
+    with_option("auto_capitalize", |e| e.set_auto_capitalize(enabled));
+}
+
+/// Set what a punctuation character does to auto-capitalize.
+///
+/// `ch` is a Unicode code point; `rule` is 0 = cancel a pending capital,
+/// 1 = keep it, 2 = end a sentence. Defaults: `. ! ?` end a sentence,
+/// quotes and brackets keep, anything else cancels. Invalid `ch` or unknown
+/// `rule` values are ignored.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_capitalize_rule(ch: u32, rule: u8) {
+    let (Some(c), Some(rule)) = (char::from_u32(ch), CapitalizeRule::from_u8(rule)) else {
+        return;
+    };
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.set_capitalize_rule(c, rule);
+    }
+}
+
+/// Restore the default auto-capitalize punctuation rules.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_reset_capitalize_rules() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.reset_capitalize_rules();
+    }
+}
+
+/// Enable/disable Title Case mode.
+///
+/// When `enabled` is true, the first letter of every word is capitalized as
+/// it is typed ("tiêu đề" → "Tiêu Đề"), for document titles.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_title_case(enabled: bool) {
+    with_option("title_case", |e| e.set_title_case(enabled));
+}
+
+/// Flip Title Case mode (bind to a hotkey).
+///
+/// # Returns
+/// The new state, `false` if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_toggle_title_case() -> bool {
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) if managed::is_locked("title_case") => e.title_case(),
+        Some(ref mut e) => e.toggle_title_case(),
+        None => false,
+    }
+}
+
+/// Bind a key combination the engine handles itself.
+///
+/// A matching key press is swallowed (`FLAG_KEY_CONSUMED`) and runs the
+/// action: 0 cycles the input method Telex → VNI → Telex, reported as
+/// `GONHANH_EVENT_METHOD_CHANGED`; 1 switches to the next profile,
+/// reported as `GONHANH_EVENT_PROFILE_CHANGED`; 2 undoes the previous
+/// key's automatic action like `ime_undo_last`, returning its result with
+/// the key; 3 flips Title Case mode (unless managed), reported as
+/// `GONHANH_EVENT_TITLE_CASE_CHANGED`.
+///
+/// # Arguments
+/// * `action` - GONHANH_HOTKEY_* (unknown values are ignored)
+/// * `key` - macOS virtual keycode, or 0xFFFF (`GONHANH_KEY_NONE`) to unbind
+/// * `ctrl` - Cmd/Ctrl/Alt held, as passed to `ime_key`
+/// * `shift` - Shift held
+///
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_hotkey(action: u8, key: u16, ctrl: bool, shift: bool) {
+    let Some(action) = HotkeyAction::from_u8(action) else {
+        return;
+    };
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        let hotkey = (key != KEY_NONE).then(|| Hotkey::new(key, ctrl, shift));
+        e.set_hotkey(action, hotkey);
+    }
+}
+
+/// Enable/disable re-casing the word when CapsLock is toggled mid-word.
+///
+/// When enabled, turning CapsLock on upper-cases the word typed so far
+/// ("vie" + CapsLock + "ETJ" → "VIỆT") and turning it off lower-cases all
+/// but its first letter. The engine reads CapsLock as `caps != shift`, so
+/// `ime_key_ext` must get `caps` as Shift XOR CapsLock.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_caps_lock_recase(enabled: bool) {
+    with_option("caps_lock_recase", |e| e.set_caps_lock_recase(enabled));
+}
+
+/// Enable/disable foreign consonants (z, w, j, f) as valid initial consonants.
+///
+/// When `enabled` is true, allows z, w, j, f as valid Vietnamese consonants
+/// for typing loanwords while still getting Vietnamese diacritics.
+/// When `enabled` is false (default), these letters are treated as invalid initials.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_allow_foreign_consonants(enabled: bool) {
+    with_option("allow_foreign_consonants", |e| {
+        e.set_allow_foreign_consonants(enabled)
+    });
+}
+
+/// Set the validation profile.
+///
+/// `profile` 0 (default): standard rules. 1: strict, for office typing; no
+/// foreign initials (z, w, j, f) or final k even with
+/// `ime_allow_foreign_consonants`. 2: relaxed, for casual chat; foreign
+/// initials, c/k g/gh ng/ngh mixups ("ko") and non-standard rhymes are
+/// accepted. Unknown values select standard.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_validation_strictness(profile: u8) {
+    with_option("strictness", |e| {
+        e.set_strictness(Strictness::from_u8(profile))
+    });
+}
+
+/// Set which regional and loanword spellings are accepted.
+///
+/// `variants` is a bitmask: 0x01 southern ("dzô", "zậy"), 0x02 northern
+/// ("chẩy", "quí"), 0x04 loanwords ("pốt", "sịp"). Default 0 (none).
+/// Words on the selected lists are not auto-restored; southern also lets
+/// z and dz start a syllable, except in the strict profile.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_spelling_variants(variants: u8) {
+    with_option("spelling_variants", |e| e.set_spelling_variants(variants));
+}
+
+/// Enable or disable spelling auto-fix.
+///
+/// When enabled, a word whose only fault is a c/k, g/gh or ng/ngh spelling
+/// is respelled at the word boundary ("nge" → "nghe", "cees" → "kế") if the
+/// result is a Vietnamese word. Off in the relaxed validation profile.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_spelling_autofix(enabled: bool) {
+    with_option("spelling_autofix", |e| e.set_spelling_autofix(enabled));
+}
+
+/// Enable or disable proper-noun capitalization.
+///
+/// When enabled, known place/person names are capitalized at the word
+/// boundary ("hà nội" + Space → "Hà Nội "). Names beyond the built-in place
+/// list are added with `ime_add_proper_noun`.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_proper_noun_capitalize(enabled: bool) {
+    with_option("proper_noun_capitalize", |e| {
+        e.set_proper_noun_capitalize(enabled)
+    });
+}
+
+/// Enable or disable teencode passthrough.
+///
+/// When enabled, a word whose keys spell a chat abbreviation ("ko", "dc",
+/// "wa") is committed as typed: not transformed ("wa" stays "wa", not
+/// "ưa"), respelled or restored. Shortcuts still expand first. More
+/// abbreviations are added with `ime_add_teencode`.
+/// Default: off. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_teencode_passthrough(enabled: bool) {
+    with_option("teencode_passthrough", |e| {
+        e.set_teencode_passthrough(enabled)
+    });
+}
+
+/// Clear the input buffer.
+///
+/// Call on word boundaries (space, punctuation).
+/// Preserves word history for backspace-after-space feature.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.clear();
+    }
+}
+
+/// Clear everything including word history.
+///
+/// Call when cursor position changes (mouse click, arrow keys, focus change).
+/// This prevents accidental restore from stale history.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear_all() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.clear_all();
+    }
+}
+
+/// Tell the engine the user pressed Cmd/Ctrl+A.
+///
+/// The next key replaces the selection: the word in progress and the
+/// undo of the last automatic action are dropped.
+#[no_mangle]
+pub extern "C" fn ime_notify_select_all() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.on_select_all();
+    }
+}
+
+/// Tell the engine the user pasted (Cmd/Ctrl+V).
+///
+/// # Arguments
+/// * `text` - Pasted text as UTF-8, or null if unknown
+///
+/// # Safety
+/// `text` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_notify_paste(text: *const std::os::raw::c_char) {
+    let text = if text.is_null() {
+        None
+    } else {
+        std::ffi::CStr::from_ptr(text).to_str().ok()
+    };
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.on_paste(text);
+    }
+}
+
+/// Tell the engine the app changed the text around the caret by itself
+/// (autocorrect, undo, text set by a script).
+#[no_mangle]
+pub extern "C" fn ime_notify_text_changed() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.on_text_changed();
+    }
+}
+
+/// Get the full composed buffer as UTF-32 codepoints.
+///
+/// Used for "Select All + Replace" injection method where the entire
+/// buffer content is needed instead of incremental backspace + chars.
+///
+/// # Arguments
+/// * `out` - Pointer to output buffer for UTF-32 codepoints
+/// * `max_len` - Maximum number of codepoints to write
+///
+/// # Returns
+/// Number of codepoints written to `out`.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_get_buffer(out: *mut u32, max_len: i64) -> i64 {
+    if out.is_null() || max_len <= 0 {
+        return 0;
+    }
+
+    let guard = lock_engine();
+    if let Some(ref e) = *guard {
+        let full = e.get_buffer_string();
+        let utf32: Vec<u32> = full.chars().map(|c| c as u32).collect();
+        let len = utf32.len().min(max_len as usize);
+        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+        len as i64
+    } else {
+        0
+    }
+}
+
+/// Free a result pointer returned by `ime_key`.
+///
+/// # Safety
+/// * `r` must be a pointer returned by `ime_key`, or null
+/// * Must be called exactly once per non-null `ime_key` return
+/// * Do not use `r` after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn ime_free(r: *mut Result) {
+    if !r.is_null() {
+        drop(Box::from_raw(r));
+    }
+}
+
+// ============================================================
+// Result Accessors
+// ============================================================
+//
+// Let hosts read `Result` without mirroring its layout. All accessors
+// are null-safe and return 0 (or null) for a null pointer.
+
+/// Get the action of a result (0=None, 1=Send, 2=Restore).
+///
+/// # Safety
+/// `r` must be null or a live pointer returned by `ime_key*`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_result_action(r: *const Result) -> u8 {
+    r.as_ref().map_or(0, |r| r.action)
+}
+
+/// Get the number of characters to delete before inserting.
+///
+/// # Safety
+/// `r` must be null or a live pointer returned by `ime_key*`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_result_backspace(r: *const Result) -> u8 {
+    r.as_ref().map_or(0, |r| r.backspace)
+}
+
+/// Get the number of valid codepoints in `chars`.
+///
+/// # Safety
+/// `r` must be null or a live pointer returned by `ime_key*`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_result_count(r: *const Result) -> u8 {
+    r.as_ref().map_or(0, |r| r.count)
+}
+
+/// Get the flags byte (bit 0: key consumed, bit 1: cursor move, bit 2: snippet).
+///
+/// # Safety
+/// `r` must be null or a live pointer returned by `ime_key*`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_result_flags(r: *const Result) -> u8 {
+    r.as_ref().map_or(0, |r| r.flags)
+}
+
+/// Get a pointer to the UTF-32 codepoints (`count` entries are valid).
+///
+/// The pointer is valid until the result is passed to `ime_free`.
+///
+/// # Safety
+/// `r` must be null or a live pointer returned by `ime_key*`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_result_chars(r: *const Result) -> *const u32 {
+    r.as_ref().map_or(std::ptr::null(), |r| r.chars.as_ptr())
+}
+
+// ============================================================
+// Shortcut FFI
+// ============================================================
+
+/// Add a shortcut to the engine.
+///
+/// # Arguments
+/// * `trigger` - C string for trigger (e.g., "vn")
+/// * `replacement` - C string for replacement (e.g., "Việt Nam")
+///
+/// # Safety
+/// Both pointers must be valid null-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_shortcut(
+    trigger: *const std::os::raw::c_char,
+    replacement: *const std::os::raw::c_char,
+) {
+    if trigger.is_null() || replacement.is_null() {
+        return;
+    }
+
+    let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    let replacement_str = match std::ffi::CStr::from_ptr(replacement).to_str() {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.shortcuts_mut().add(engine::shortcut::Shortcut::auto(
+            trigger_str,
+            replacement_str,
+        ));
+    }
+}
+
+/// Remove a shortcut from the engine.
+///
+/// # Arguments
+/// * `trigger` - C string for trigger to remove
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_remove_shortcut(trigger: *const std::os::raw::c_char) {
+    if trigger.is_null() {
+        return;
+    }
+
+    let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.shortcuts_mut().remove(trigger_str);
+    }
+}
+
+/// Clear all shortcuts from the engine.
+#[no_mangle]
+pub extern "C" fn ime_clear_shortcuts() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.shortcuts_mut().clear();
+    }
+}
+
+/// Start recording a macro: what the user types from now on, as it ends up
+/// in the app (line breaks included), becomes the expansion of a shortcut
+/// saved with `ime_macro_save`. Restarts a recording in progress.
+#[no_mangle]
+pub extern "C" fn ime_macro_start() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.start_macro();
+    }
+}
+
+/// Stop recording a macro.
+///
+/// # Returns
+/// * Pointer to the null-terminated UTF-8 text recorded, for the host to
+///   show while asking for a trigger (free with `ime_free_string`)
+/// * `null` if not recording or engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_macro_stop() -> *mut std::os::raw::c_char {
+    let mut guard = lock_engine();
+    match guard.as_mut().and_then(|e| e.stop_macro()) {
+        Some(text) => into_c_string(text),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Save the last recorded macro as a shortcut for `trigger` (replacing one
+/// with the same trigger).
+///
+/// # Returns
+/// `false` if nothing was recorded, the trigger is empty or the engine is
+/// not initialized.
+///
+/// # Safety
+/// `trigger` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_macro_save(trigger: *const std::os::raw::c_char) -> bool {
+    if trigger.is_null() {
+        return false;
+    }
+    let Ok(trigger) = std::ffi::CStr::from_ptr(trigger).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    guard.as_mut().is_some_and(|e| e.save_macro(trigger))
+}
+
+/// Set the local UTC offset used by `%date%`/`%time%` shortcut placeholders.
+///
+/// `minutes` east of UTC (Vietnam: 420). No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_set_utc_offset(minutes: i32) {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.set_utc_offset(minutes);
+    }
+}
+
+/// Set the clipboard text used by the `%clipboard%` shortcut placeholder.
+///
+/// Hosts call this when the clipboard changes. Null clears it.
+///
+/// # Safety
+/// `text` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_clipboard(text: *const std::os::raw::c_char) {
+    let text = if text.is_null() {
+        None
+    } else {
+        match std::ffi::CStr::from_ptr(text).to_str() {
+            Ok(s) => Some(s),
+            Err(_) => return,
+        }
+    };
+
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.set_clipboard_text(text);
+    }
+}
+
+/// Set the frontmost app for per-app shortcuts, terminal modes and on/off.
+///
+/// Hosts call this on focus change with the app id they use in
+/// `ime_set_shortcut_apps` (process name, bundle id). Null = unknown app.
+/// With `ime_per_app_enabled`, a change of on/off is reported to the event
+/// callback (`GONHANH_EVENT_ENABLED_CHANGED`) before this returns.
+/// No-op if engine not initialized.
+///
+/// # Safety
+/// `app` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_active_app(app: *const std::os::raw::c_char) {
+    let app = if app.is_null() {
+        None
+    } else {
+        match std::ffi::CStr::from_ptr(app).to_str() {
+            Ok(s) => Some(s),
+            Err(_) => return,
+        }
+    };
+
+    let events = {
+        let mut guard = lock_engine();
+        let Some(ref mut e) = *guard else {
+            return;
+        };
+        e.set_active_app(app);
+        e.take_events()
+    };
+    report_events(events);
+}
+
+/// Keep the on/off toggle per app.
+///
+/// When on, `ime_enabled` sets the state of the app in front and
+/// `ime_set_active_app` brings back each app's own state. Apps never
+/// toggled follow the state last set with no app in front.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_per_app_enabled(enabled: bool) {
+    with_option("per_app_enabled", |e| e.set_per_app_enabled(enabled));
+}
+
+/// Set whether an app starts with the engine on or off.
+///
+/// `app` is an id as passed to `ime_set_active_app`; `state` 0 = off,
+/// 1 = on, 255 removes the app's own state so it follows the default.
+/// Used with `ime_per_app_enabled`. No-op if engine not initialized.
+///
+/// # Safety
+/// `app` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_app_enabled(app: *const std::os::raw::c_char, state: u8) {
+    if app.is_null() {
+        return;
+    }
+    let Ok(app) = std::ffi::CStr::from_ptr(app).to_str() else {
+        return;
+    };
+    let enabled = (state != u8::MAX).then_some(state != 0);
+    let events = {
+        let mut guard = lock_engine();
+        let Some(ref mut e) = *guard else {
+            return;
+        };
+        e.set_app_enabled(app, enabled);
+        e.take_events()
+    };
+    report_events(events);
+}
+
+/// Keep the word in progress of `app`, the app losing focus.
+///
+/// With `ime_restore_state` for the app gaining focus, a word left
+/// mid-way in one app can still be edited when the user comes back.
+/// Null `app` uses the app set with `ime_set_active_app`, so hosts can
+/// save, set the new active app, then restore. The last 16 apps are kept.
+///
+/// # Returns
+/// `false` if engine not initialized or no app is known.
+///
+/// # Safety
+/// `app` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_save_state(app: *const std::os::raw::c_char) -> bool {
+    let mut guard = lock_engine();
+    let Some(ref mut e) = *guard else {
+        return false;
+    };
+    let Some(app) = app_or_active(e, app) else {
+        return false;
+    };
+    e.save_app_state(&app);
+    true
+}
+
+/// Continue the word saved for `app` with `ime_save_state`.
+///
+/// An app without a saved state starts with nothing known, as after a
+/// cursor move. Null `app` uses the app set with `ime_set_active_app`.
+///
+/// # Returns
+/// `true` if a saved word was restored; `false` if there was none, no app
+/// is known or engine not initialized.
+///
+/// # Safety
+/// `app` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_restore_state(app: *const std::os::raw::c_char) -> bool {
+    let mut guard = lock_engine();
+    let Some(ref mut e) = *guard else {
+        return false;
+    };
+    let Some(app) = app_or_active(e, app) else {
+        e.clear_all();
+        return false;
+    };
+    e.restore_app_state(&app)
+}
+
+/// `app` as a string, or the active app when null
+///
+/// # Safety
+/// `app` must be null or a valid null-terminated UTF-8 string.
+unsafe fn app_or_active(e: &Engine, app: *const std::os::raw::c_char) -> Option<String> {
+    if app.is_null() {
+        return e.shortcuts().active_app().map(str::to_string);
+    }
+    std::ffi::CStr::from_ptr(app)
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
+
+/// Set the terminal-safe mode for apps without their own.
+///
+/// `mode` 0 (default): replace with backspaces as usual. 1: hold each word
+/// back and send it once when it is finished, so no backspaces reach the
+/// app (terminals, vim, tmux). 2: pass every key through untouched.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_terminal_mode(mode: u8) {
+    with_option("terminal_mode", |e| {
+        e.set_terminal_mode(TerminalMode::from_u8(mode))
+    });
+}
+
+/// Set the terminal-safe mode for one app (see `ime_terminal_mode`).
+///
+/// `app` is an id as passed to `ime_set_active_app`; `mode` 255 removes
+/// the app's own mode so it follows the default again.
+/// No-op if engine not initialized.
+///
+/// # Safety
+/// `app` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_app_terminal_mode(app: *const std::os::raw::c_char, mode: u8) {
+    if app.is_null() {
+        return;
+    }
+    let Ok(app) = std::ffi::CStr::from_ptr(app).to_str() else {
+        return;
+    };
+    let mode = (mode != u8::MAX).then(|| TerminalMode::from_u8(mode));
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.set_app_terminal_mode(app, mode);
+    }
+}
+
+/// Take the word held back by terminal-safe mode 1.
+///
+/// Call before a focus change or mouse click: the text has not reached
+/// the app yet and is forgotten by the engine; type it to keep it.
+///
+/// # Returns
+/// * Pointer to a null-terminated UTF-8 string, empty if nothing is held
+///   (free with `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_terminal_take() -> *mut std::os::raw::c_char {
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => into_c_string(e.take_composing()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Limit a shortcut to some apps, or keep it out of others.
+///
+/// # Arguments
+/// * `trigger` - Trigger of a shortcut added with `ime_add_shortcut`
+/// * `only` - Comma-separated app ids it fires in (null or empty = any app)
+/// * `never` - Comma-separated app ids it never fires in (null = none)
+///
+/// No-op if engine not initialized or the shortcut does not exist.
+///
+/// # Safety
+/// `trigger` must be a valid null-terminated UTF-8 string; `only` and
+/// `never` must be null or valid null-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_shortcut_apps(
+    trigger: *const std::os::raw::c_char,
+    only: *const std::os::raw::c_char,
+    never: *const std::os::raw::c_char,
+) {
+    if trigger.is_null() {
+        return;
+    }
+    let list = |p: *const std::os::raw::c_char| {
+        if p.is_null() {
+            Ok("")
+        } else {
+            std::ffi::CStr::from_ptr(p).to_str()
+        }
+    };
+    let (Ok(trigger_str), Ok(only_str), Ok(never_str)) = (list(trigger), list(only), list(never))
+    else {
+        return;
+    };
+
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        let scope = engine::shortcut::AppScope::from_lists(only_str, never_str);
+        e.shortcuts_mut().set_apps(trigger_str, scope);
+    }
+}
+
+/// Left-arrow presses after applying a result flagged `FLAG_CURSOR_MOVE`.
+///
+/// A shortcut replacement with a cursor marker (`Kính gửi |,` or
+/// `%cursor%`) leaves the caret at the marker: hosts send the replacement,
+/// pass the key through unless consumed, then press Left this many times.
+///
+/// # Returns
+/// 0 if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_cursor_offset() -> u8 {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => e.cursor_offset(),
+        None => 0,
+    }
+}
+
+/// Jump to the next snippet field.
+///
+/// Call right after a result flagged `FLAG_SNIPPET`, and on Tab while
+/// `ime_snippet_active()` returns true (swallow that Tab). Move the caret by
+/// the returned count (negative = Left, positive = Right), then select
+/// `ime_snippet_select()` characters with Shift+Right.
+///
+/// # Returns
+/// 0 if engine not initialized or no snippet is being filled in.
+#[no_mangle]
+pub extern "C" fn ime_snippet_next() -> i32 {
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => e.snippet_next().map_or(0, |jump| jump.caret),
+        None => 0,
+    }
+}
+
+/// Characters to select after the last `ime_snippet_next()` (the field's default text).
+///
+/// # Returns
+/// 0 if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_snippet_select() -> u8 {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => e.snippet_selection().min(u8::MAX as usize) as u8,
+        None => 0,
+    }
+}
+
+/// Whether a snippet has fields left to visit (Tab jumps instead of typing).
+///
+/// # Returns
+/// `false` if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_snippet_active() -> bool {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => e.snippet_active(),
+        None => false,
+    }
+}
+
+// ============================================================
+// Word Restore FFI
+// ============================================================
+
+/// Restore buffer from a Vietnamese word string.
+///
+/// Used when native app detects cursor at word boundary and user
+/// wants to continue editing (e.g., backspace into previous word).
+/// Parses Vietnamese characters back to buffer components.
+///
+/// # Arguments
+/// * `word` - C string containing the Vietnamese word to restore
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_restore_word(word: *const std::os::raw::c_char) {
+    if word.is_null() {
+        return;
+    }
+    let word_str = match std::ffi::CStr::from_ptr(word).to_str() {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.restore_word(word_str);
+    }
+}
+
+/// Check whether a word is a valid Vietnamese syllable, and why not.
+///
+/// Frontends use this to explain an auto-restore or to underline misspelled
+/// words. Foreign initials (z, w, j, f) follow the engine setting.
+///
+/// # Returns
+/// `GONHANH_WORD_*` code: 0 valid, 1 invalid initial, 2 invalid final,
+/// 3 invalid spelling (also for non-Vietnamese characters), 4 invalid vowel
+/// pattern, 5 no vowel, 6 tone not allowed with the final ("hoàp").
+///
+/// # Safety
+/// `word` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_validate_word(word: *const std::os::raw::c_char) -> u8 {
+    let word_str = if word.is_null() {
+        ""
+    } else {
+        match std::ffi::CStr::from_ptr(word).to_str() {
+            Ok(s) => s,
+            Err(_) => return ValidationResult::InvalidSpelling as u8,
+        }
+    };
+    let (allow_foreign, strictness, variants) = match *lock_engine() {
+        Some(ref e) => (
+            e.allow_foreign_consonants(),
+            e.strictness(),
+            e.spelling_variants(),
+        ),
+        None => (false, Strictness::Standard, 0),
+    };
+    // Accepted regional spellings are words even when off the syllable rules
+    if dictionary::is_variant(word_str, variants) {
+        return ValidationResult::Valid as u8;
+    }
+    validation::validate_word_with_strictness(word_str, allow_foreign, strictness) as u8
+}
+
+// ============================================================
+// Diagnostics FFI
+// ============================================================
+
+/// Get a JSON snapshot of the engine state.
+///
+/// Includes buffer contents with per-char tone/mark/stroke, raw input,
+/// last transform, pending auto-capitalize state, method and options.
+/// Intended for debug panels; the shape is not a stable contract.
+///
+/// # Returns
+/// * Pointer to a null-terminated UTF-8 string (free with `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_dump_state() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => into_c_string(e.dump_state()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Free a string returned by the engine (e.g. `ime_dump_state`).
+///
+/// # Safety
+/// * `s` must be a pointer returned by an `ime_*` string function, or null
+/// * Must be called exactly once per non-null pointer
+#[no_mangle]
+pub unsafe extern "C" fn ime_free_string(s: *mut std::os::raw::c_char) {
+    if !s.is_null() {
+        drop(std::ffi::CString::from_raw(s));
+    }
+}
+
+/// Run the built-in self-test corpora against the live configuration.
+///
+/// Replays the golden corpus for the current input method (`corpus::TELEX`
+/// or `corpus::VNI`) on a fresh engine with the same options; the live
+/// buffer is not touched. A passing self-test with wrong on-screen output points at the
+/// platform injection layer rather than the engine.
+///
+/// # Returns
+/// * JSON `{"total":N,"failed":M,"failures":[{corpus,line,input,expected,actual}]}`
+///   (free with `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_self_test() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => into_c_string(selftest::run(e).to_json()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Start recording a session trace for a bug report: the settings, then
+/// every call the host makes (keys, pastes, clears, focus changes).
+/// Restarts a trace in progress. The trace holds what the user types, so
+/// hosts should say so before starting it.
+#[no_mangle]
+pub extern "C" fn ime_trace_start() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.start_session_trace();
+    }
+}
+
+/// Stop recording a session trace.
+///
+/// # Returns
+/// * Pointer to the null-terminated UTF-8 trace, which
+///   `Engine::replay` turns back into the text typed (free with
+///   `ime_free_string`)
+/// * `null` if not recording or engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_trace_stop() -> *mut std::os::raw::c_char {
+    let mut guard = lock_engine();
+    match guard.as_mut().and_then(|e| e.stop_session_trace()) {
+        Some(text) => into_c_string(text),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Why the engine did what it did on the last key: the mark or tone rule
+/// matched, the vowel the mark went on, the validation rule that failed,
+/// the auto-restore verdict.
+///
+/// Only filled in builds with the `debug-trace` feature.
+///
+/// # Returns
+/// * Pointer to null-terminated UTF-8 text, one decision per line, empty
+///   when there are none (free with `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_reasons() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => into_c_string(e.reasons().join("\n")),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Hand a Rust string to the host as a C string (interior NULs are dropped)
+fn into_c_string(s: String) -> *mut std::os::raw::c_char {
+    let bytes: Vec<u8> = s.into_bytes().into_iter().filter(|&b| b != 0).collect();
+    match std::ffi::CString::new(bytes) {
+        Ok(c) => c.into_raw(),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+// ============================================================
+// Logging FFI
+// ============================================================
+
+/// Set the log level.
+///
+/// # Arguments
+/// * `level` - 0=Off (default), 1=Error, 2=Warn, 3=Info, 4=Debug, 5=Trace
+///
+/// Debug logs auto-restore, revert and shortcut decisions; Trace also logs
+/// every keystroke. Works without `ime_init`.
+#[no_mangle]
+pub extern "C" fn ime_log_level(level: u8) {
+    logging::set_level(logging::Level::from_u8(level));
+}
+
+/// Set the log file path.
+///
+/// Logs are appended to `path` and rotated to `<path>.1` at 1 MiB.
+/// Pass null or an empty string to log to stderr instead.
+///
+/// # Returns
+/// `true` on success, `false` if the file cannot be opened (previous
+/// output is kept).
+///
+/// # Safety
+/// `path` must be null or a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_log_file(path: *const std::os::raw::c_char) -> bool {
+    if path.is_null() {
+        return logging::set_file(None);
+    }
+    let Ok(path_str) = std::ffi::CStr::from_ptr(path).to_str() else {
+        return false;
+    };
+    let ok = logging::set_file(Some(path_str));
+    if !ok {
+        logging::log_warn!("logging", "cannot open log file path=\"{}\"", path_str);
+    }
+    ok
+}
+
+// ============================================================
+// English Exception Words FFI
+// ============================================================
+
+/// Add a word that is always left as typed ("expect", brand names).
+///
+/// Extends the built-in keep list at runtime: when the keys typed for a word
+/// match (case-insensitive), any transforms are undone at the word boundary,
+/// whether or not English auto-restore is on.
+///
+/// # Safety
+/// `word` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_english_word(word: *const std::os::raw::c_char) {
+    if word.is_null() {
+        return;
+    }
+    let Ok(word_str) = std::ffi::CStr::from_ptr(word).to_str() else {
+        return;
+    };
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.add_english_word(word_str);
+    }
+}
+
+/// Remove a word added with `ime_add_english_word`.
+///
+/// # Returns
+/// `false` if engine not initialized or the word was not in the list.
+///
+/// # Safety
+/// `word` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_remove_english_word(word: *const std::os::raw::c_char) -> bool {
+    if word.is_null() {
+        return false;
+    }
+    let Ok(word_str) = std::ffi::CStr::from_ptr(word).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => e.remove_english_word(word_str),
+        None => false,
+    }
+}
+
+/// Remove all words added with `ime_add_english_word`.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear_english_words() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.clear_english_words();
+    }
+}
+
+// ============================================================
+// Proper Nouns FFI
+// ============================================================
+
+/// Add a name for proper-noun capitalization, written as it should appear
+/// ("Nguyễn Văn An", at most 5 words).
+///
+/// # Returns
+/// `false` if engine not initialized or the name is empty or too long.
+///
+/// # Safety
+/// `name` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_proper_noun(name: *const std::os::raw::c_char) -> bool {
+    if name.is_null() {
+        return false;
+    }
+    let Ok(name_str) = std::ffi::CStr::from_ptr(name).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => e.add_proper_noun(name_str),
+        None => false,
+    }
+}
+
+// ============================================================
+// Teencode FFI
+// ============================================================
+
+/// Add a chat abbreviation to keep as typed ("vcl").
+///
+/// # Returns
+/// `false` if engine not initialized or the word is empty or has spaces.
+///
+/// # Safety
+/// `word` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_teencode(word: *const std::os::raw::c_char) -> bool {
+    if word.is_null() {
+        return false;
+    }
+    let Ok(word_str) = std::ffi::CStr::from_ptr(word).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => e.add_teencode(word_str),
+        None => false,
+    }
+}
+
+/// Remove all added chat abbreviations (the built-in list stays).
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear_teencode() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.clear_teencode();
+    }
+}
+
+// ============================================================
+// Text Conversion FFI
+// ============================================================
+
+/// Strip Vietnamese diacritics ("Nguyễn Văn A" → "Nguyen Van A").
+///
+/// For filenames, slugs and bank transfer fields. Works without an engine.
+///
+/// # Returns
+/// * Pointer to a null-terminated UTF-8 string (free with `ime_free_string`)
+/// * `null` if `text` is null or not UTF-8
+///
+/// # Safety
+/// `text` must be a valid null-terminated string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_strip_diacritics(
+    text: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    if text.is_null() {
+        return std::ptr::null_mut();
+    }
+    match std::ffi::CStr::from_ptr(text).to_str() {
+        Ok(s) => into_c_string(utils::strip_diacritics(s)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Run a clipboard conversion command on `text`.
+///
+/// `command` is one of `GONHANH_CONVERT_*`: 0 = raw keystrokes to
+/// Vietnamese, 1 = strip diacritics, 2 = re-place tone marks, 3 = TCVN3 to
+/// Unicode, 4 = VNI to Unicode. Keystrokes and tone placement follow the
+/// engine's method and options (defaults if not initialized); the engine's
+/// typing state is not touched.
+///
+/// # Returns
+/// * Pointer to a null-terminated UTF-8 string (free with `ime_free_string`)
+/// * `null` if `text` is null or not UTF-8, or `command` is unknown
+///
+/// # Safety
+/// `text` must be a valid null-terminated string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_convert_text(
+    text: *const std::os::raw::c_char,
+    command: u8,
+) -> *mut std::os::raw::c_char {
+    let Some(command) = convert::Command::from_u8(command) else {
+        return std::ptr::null_mut();
+    };
+    if text.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Ok(text) = std::ffi::CStr::from_ptr(text).to_str() else {
+        return std::ptr::null_mut();
+    };
+    let engine = lock_engine()
+        .as_ref()
+        .map(Engine::fresh_copy)
+        .unwrap_or_default();
+    into_c_string(convert::convert(&engine, text, command))
+}
+
+/// Rewrite tone placement in `text` to one style.
+///
+/// `style` as for `ime_tone_style`: 0 gives "hòa, thủy", 1 gives "hoà, thuỷ".
+/// Works without an engine.
+///
+/// # Returns
+/// * Pointer to a null-terminated UTF-8 string (free with `ime_free_string`)
+/// * `null` if `text` is null or not UTF-8
+///
+/// # Safety
+/// `text` must be a valid null-terminated string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_normalize_tone(
+    text: *const std::os::raw::c_char,
+    style: u8,
+) -> *mut std::os::raw::c_char {
+    if text.is_null() {
+        return std::ptr::null_mut();
+    }
+    match std::ffi::CStr::from_ptr(text).to_str() {
+        Ok(s) => into_c_string(convert::normalize_tone(s, ToneStyle::from_u8(style))),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Acceptable spellings of one syllable, for a candidate list.
+///
+/// Returns a JSON array of strings, the spelling in the engine's tone style
+/// first: "hòa" → `["hoà","hòa"]`, "quý" → `["quý","qúy"]`. Words with a
+/// single spelling give a one-element array. Uses new style if the engine
+/// is not initialized.
+///
+/// # Returns
+/// * Pointer to a null-terminated JSON string (free with `ime_free_string`)
+/// * `null` if `word` is null or not UTF-8
+///
+/// # Safety
+/// `word` must be a valid null-terminated string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_tone_renderings(
+    word: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    if word.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Ok(word) = std::ffi::CStr::from_ptr(word).to_str() else {
+        return std::ptr::null_mut();
+    };
+    let style = lock_engine()
+        .as_ref()
+        .map_or(ToneStyle::default(), Engine::tone_style);
+    let items: Vec<String> = convert::tone_renderings(word, style)
+        .iter()
+        .map(|r| format!("\"{}\"", utils::json_escape(r)))
+        .collect();
+    into_c_string(format!("[{}]", items.join(",")))
+}
+
+/// Convert legacy-encoded text (TCVN3, VNI) to Unicode.
+///
+/// `encoding` is 0 = TCVN3 (ABC), 1 = VNI-Windows. Works without an engine.
+///
+/// # Returns
+/// * Pointer to a null-terminated UTF-8 string (free with `ime_free_string`)
+/// * `null` if `bytes` is null or `encoding` is unknown
+///
+/// # Safety
+/// `bytes` must be a valid null-terminated string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_from_legacy(
+    bytes: *const std::os::raw::c_char,
+    encoding: u8,
+) -> *mut std::os::raw::c_char {
+    let Some(encoding) = encoding::Encoding::from_u8(encoding) else {
+        return std::ptr::null_mut();
+    };
+    if bytes.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = std::ffi::CStr::from_ptr(bytes).to_bytes();
+    into_c_string(encoding::decode(bytes, encoding))
+}
+
+/// Convert UTF-8 text to a legacy encoding (TCVN3, VNI).
+///
+/// `encoding` as for `ime_from_legacy`. Characters the encoding cannot hold
+/// become `?`. Works without an engine.
+///
+/// # Returns
+/// * Pointer to a null-terminated string of encoded bytes (free with
+///   `ime_free_string`)
+/// * `null` if `text` is null or not UTF-8, or `encoding` is unknown
+///
+/// # Safety
+/// `text` must be a valid null-terminated string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_to_legacy(
+    text: *const std::os::raw::c_char,
+    encoding: u8,
+) -> *mut std::os::raw::c_char {
+    let Some(encoding) = encoding::Encoding::from_u8(encoding) else {
+        return std::ptr::null_mut();
+    };
+    if text.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Ok(text) = std::ffi::CStr::from_ptr(text).to_str() else {
+        return std::ptr::null_mut();
+    };
+    match std::ffi::CString::new(encoding::encode(text, encoding)) {
+        Ok(c) => c.into_raw(),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+// ============================================================
+// Statistics FFI
+// ============================================================
+
+/// Enable or disable typing statistics.
+///
+/// Off by default. Counts keystrokes, committed words, engine-sent
+/// backspaces and shortcut usage. Stats stay on this machine; they are only
+/// written by `ime_stats_save`.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_stats_enabled(enabled: bool) {
+    with_option("stats", |e| e.stats_mut().set_enabled(enabled));
+}
+
+/// Get typing statistics as JSON.
+///
+/// # Returns
+/// * `{"enabled":b,"keystrokes":N,"words":N,"backspaces_avoided":N,
+///   "top_shortcuts":[{"trigger":"..","count":N}]}` (top 10, free with
+///   `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_stats_json() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => into_c_string(e.stats().to_json()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Reset all typing statistics counters.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_stats_reset() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.stats_mut().reset();
+    }
+}
+
+/// Save typing statistics to a file.
+///
+/// # Returns
+/// `false` if engine not initialized or the file cannot be written.
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_stats_save(path: *const std::os::raw::c_char) -> bool {
+    if path.is_null() {
+        return false;
+    }
+    let Ok(path_str) = std::ffi::CStr::from_ptr(path).to_str() else {
+        return false;
+    };
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => e.stats().save(path_str),
+        None => false,
+    }
+}
+
+/// Load typing statistics saved by `ime_stats_save`, replacing current counters.
+///
+/// # Returns
+/// `false` if engine not initialized or the file cannot be read.
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_stats_load(path: *const std::os::raw::c_char) -> bool {
+    if path.is_null() {
+        return false;
+    }
+    let Ok(path_str) = std::ffi::CStr::from_ptr(path).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => e.stats_mut().load(path_str),
+        None => false,
+    }
+}
+
+// ============================================================
+// Latency FFI
+// ============================================================
+
+/// Enable or disable keystroke latency metrics (off by default).
+///
+/// Process-wide and independent of the engine: samples survive `ime_init`
+/// and are only dropped by `ime_latency_reset`.
+#[no_mangle]
+pub extern "C" fn ime_latency_enabled(enabled: bool) {
+    latency::set_enabled(enabled);
+}
+
+/// Get latency percentiles as JSON, for attaching to a bug report.
+///
+/// # Returns
+/// `{"enabled":b,"window":N,"stages":{"key":{"count":N,"p50_us":F,
+/// "p99_us":F,"max_us":F},"validation":{..},"dictionary":{..},
+/// "rebuild":{..}},"cache":{"validation":{"hits":N,"misses":N},
+/// "dictionary":{..}}}` (stages without samples left out; free with
+/// `ime_free_string`)
+#[no_mangle]
+pub extern "C" fn ime_latency_json() -> *mut std::os::raw::c_char {
+    into_c_string(latency::to_json())
+}
+
+/// Drop all latency samples.
+#[no_mangle]
+pub extern "C" fn ime_latency_reset() {
+    latency::reset();
+}
+
+// ============================================================
+// Learning FFI
+// ============================================================
+
+/// Enable or disable learning from auto-restore corrections.
+///
+/// Off by default. When on, undoing an auto-restore (Backspace right after
+/// it) or restoring a kept word with the restore key counts as a vote for
+/// that word; after repeated votes auto-restore follows the user.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_learning_enabled(enabled: bool) {
+    with_option("learning", |e| e.learning_mut().set_enabled(enabled));
+}
+
+/// Forget all learned words.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_learning_reset() {
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.learning_mut().reset();
+    }
+}
+
+/// Save learned words to a file (the user dictionary).
+///
+/// # Returns
+/// `false` if engine not initialized or the file cannot be written.
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_learning_save(path: *const std::os::raw::c_char) -> bool {
+    if path.is_null() {
+        return false;
+    }
+    let Ok(path_str) = std::ffi::CStr::from_ptr(path).to_str() else {
+        return false;
+    };
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => e.learning().save(path_str),
+        None => false,
+    }
+}
+
+/// Load learned words saved by `ime_learning_save`, replacing current ones.
+///
+/// # Returns
+/// `false` if engine not initialized or the file cannot be read.
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_learning_load(path: *const std::os::raw::c_char) -> bool {
+    if path.is_null() {
+        return false;
+    }
+    let Ok(path_str) = std::ffi::CStr::from_ptr(path).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => e.learning_mut().load(path_str),
+        None => false,
+    }
+}
+
+// ============================================================
+// Config Directory FFI
+// ============================================================
+
+/// Directory the core keeps its files in (settings bundle, word lists).
+///
+/// `GONHANH_CONFIG_DIR` when set, else `%APPDATA%\FKey`,
+/// `~/Library/Application Support/FKey` or `$XDG_CONFIG_HOME/fkey`.
+///
+/// # Returns
+/// UTF-8 path (free with `ime_free_string`), null if no home directory is
+/// known.
+#[no_mangle]
+pub extern "C" fn ime_config_dir() -> *mut std::os::raw::c_char {
+    match config::dir().and_then(|d| d.to_str().map(str::to_string)) {
+        Some(dir) => into_c_string(dir),
+        None => std::ptr::null_mut(),
+    }
+}
+
+// ============================================================
+// Config Key FFI
+// ============================================================
+
+/// Current value of one option, as written in settings files.
+///
+/// Keys are the settings file names (`ime_config_keys`): "method" → "1",
+/// "free_tone" → "false", "remove_key_telex" → "none".
+///
+/// # Returns
+/// * Pointer to a null-terminated UTF-8 string (free with `ime_free_string`)
+/// * `null` if engine not initialized or the key is unknown
+///
+/// # Safety
+/// `key` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_config_get(
+    key: *const std::os::raw::c_char,
+) -> *mut std::os::raw::c_char {
+    if key.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Ok(key) = std::ffi::CStr::from_ptr(key).to_str() else {
+        return std::ptr::null_mut();
+    };
+    let guard = lock_engine();
+    match guard.as_ref().and_then(|e| e.option(key)) {
+        Some(value) => into_c_string(value),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Set one option from its settings file text ("true", "2", "none").
+///
+/// Same validation as a settings bundle: booleans are "true"/"false",
+/// numbers must be in range and keycodes are decimal.
+///
+/// # Returns
+/// `false` if engine not initialized, the key is unknown, the value is
+/// invalid or the option is locked by the managed config; nothing changes
+/// then.
+///
+/// # Safety
+/// `key` and `value` must be valid null-terminated UTF-8 strings or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_config_set(
+    key: *const std::os::raw::c_char,
+    value: *const std::os::raw::c_char,
+) -> bool {
+    if key.is_null() || value.is_null() {
+        return false;
+    }
+    let (Ok(key), Ok(value)) = (
+        std::ffi::CStr::from_ptr(key).to_str(),
+        std::ffi::CStr::from_ptr(value).to_str(),
+    ) else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    let Some(ref mut e) = *guard else {
+        return false;
+    };
+    match e.set_option(key, value) {
+        Ok(()) => true,
+        Err(err) => {
+            logging::log_warn!("settings", "{}", err);
+            false
+        }
+    }
+}
+
+/// Names of all options as a JSON array, in settings file order:
+/// `["method","tone_style",...]`.
+///
+/// # Returns
+/// Pointer to a null-terminated JSON string (free with `ime_free_string`).
+#[no_mangle]
+pub extern "C" fn ime_config_keys() -> *mut std::os::raw::c_char {
+    let keys: Vec<String> = settings::option_keys()
+        .map(|k| format!("\"{}\"", k))
+        .collect();
+    into_c_string(format!("[{}]", keys.join(",")))
+}
+
+/// All options as one JSON object, for frontends that treat the
+/// configuration as a document instead of calling one setter per option.
+///
+/// Values are typed: `{"method":0,"free_tone":false,"remove_key_vni":null,...}`
+/// (see `Engine::config_json`). New options only add members, so hosts
+/// keep working across versions.
+///
+/// # Returns
+/// * Pointer to a null-terminated JSON string (free with `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_load_config_json() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => into_c_string(e.config_json()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Apply a JSON object of options, as returned by `ime_load_config_json`.
+///
+/// Only the members present change; unknown keys are skipped and locked
+/// options keep their values.
+///
+/// # Returns
+/// `false` if engine not initialized, the text is not a JSON object or a
+/// value is invalid; nothing changes then.
+///
+/// # Safety
+/// `json` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_save_config_json(json: *const std::os::raw::c_char) -> bool {
+    if json.is_null() {
+        return false;
+    }
+    let Ok(json) = std::ffi::CStr::from_ptr(json).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    let Some(ref mut e) = *guard else {
+        return false;
+    };
+    match e.set_config_json(json) {
+        Ok(()) => true,
+        Err(err) => {
+            logging::log_warn!("settings", "{}", err);
+            false
+        }
+    }
+}
+
+// ============================================================
+// Managed Config FFI
+// ============================================================
+
+/// Reload the administrator's managed config and apply its locked options.
+///
+/// `ime_init` already loads it; call this after the file changed. Always
+/// reads the system file (`C:\ProgramData\FKey`, `/Library/Application
+/// Support/FKey` or `/etc/fkey`, see `managed`): a path from the host
+/// could point at a missing file and unlock every option. A missing file
+/// means not managed. Applies to every engine in the process.
+///
+/// # Returns
+/// `false` if the file cannot be read or has no managed header; the
+/// previous managed config stays. Bad lines only cost their own key (see
+/// `managed`).
+#[no_mangle]
+pub extern "C" fn ime_managed_load() -> bool {
+    if let Err(err) = managed::load() {
+        let path = managed::system_path();
+        logging::log_warn!("managed", "{} path=\"{}\"", err, path.display());
+        return false;
+    }
+    let mut guard = lock_engine();
+    if let Some(ref mut e) = *guard {
+        e.apply_managed();
+    }
+    true
+}
+
+/// Whether the managed config locks an option (settings file key, e.g.
+/// "tone_style"), so the settings UI can disable its control.
+///
+/// # Safety
+/// `key` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_managed_locked(key: *const std::os::raw::c_char) -> bool {
+    if key.is_null() {
+        return false;
+    }
+    std::ffi::CStr::from_ptr(key)
+        .to_str()
+        .is_ok_and(managed::is_locked)
+}
+
+/// Whether network features are allowed: `false` when the managed config
+/// has `network false`. Hosts skip word list downloads then;
+/// `update_check` refuses by itself.
+#[no_mangle]
+pub extern "C" fn ime_network_allowed() -> bool {
+    managed::network_allowed()
+}
+
+// ============================================================
+// Settings Bundle FFI
+// ============================================================
+
+/// Path argument, or `name` in the config directory when null
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
+unsafe fn path_or_config(
+    path: *const std::os::raw::c_char,
+    name: &str,
+    create_dir: bool,
+) -> Option<String> {
+    if path.is_null() {
+        let path = if create_dir {
+            config::create_path(name)
+        } else {
+            config::path(name)
+        };
+        return path?.to_str().map(str::to_string);
+    }
+    std::ffi::CStr::from_ptr(path)
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
+
+/// Export options, shortcuts, exception words, proper nouns, hotkeys and
+/// learned corrections to one portable file (see `engine::settings`).
+///
+/// A null `path` writes `settings.txt` in the config directory
+/// (`ime_config_dir`), creating it if needed.
+///
+/// # Returns
+/// `false` if engine not initialized or the file cannot be written.
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_settings_export(path: *const std::os::raw::c_char) -> bool {
+    let Some(path_str) = path_or_config(path, config::SETTINGS_FILE, true) else {
+        return false;
+    };
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => e.save_settings(&path_str).is_ok(),
+        None => false,
+    }
+}
+
+/// Import a file written by `ime_settings_export`.
+///
+/// Listed options are set and the tables in the file replace the current
+/// ones. The host should read its own copy of the options back afterwards
+/// (`ime_dump_state`). A null `path` reads `settings.txt` in the config
+/// directory.
+///
+/// # Returns
+/// `false` if engine not initialized, or the file cannot be read or is
+/// invalid; nothing is changed then.
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_settings_import(path: *const std::os::raw::c_char) -> bool {
+    let Some(path_str) = path_or_config(path, config::SETTINGS_FILE, false) else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    let Some(ref mut e) = *guard else {
+        return false;
+    };
+    match e.load_settings(&path_str) {
+        Ok(()) => true,
+        Err(err) => {
+            logging::log_warn!("settings", "{} path=\"{}\"", err, path_str);
+            false
+        }
+    }
+}
+
+// ============================================================
+// Profile FFI
+// ============================================================
+
+/// Switch to the named profile ("work", "gaming"), each with its own
+/// options and shortcuts (see `engine::profiles`).
+///
+/// A new name starts as a copy of the current profile. The host should
+/// read its own copy of the options back afterwards (`ime_dump_state`) and
+/// save with `ime_profiles_save`.
+///
+/// # Returns
+/// `false` if engine not initialized or the name is invalid (letters,
+/// digits, '-', '_' and inner spaces, at most 32 characters).
+///
+/// # Safety
+/// `name` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_profile_switch(name: *const std::os::raw::c_char) -> bool {
+    if name.is_null() {
+        return false;
+    }
+    let Ok(name) = std::ffi::CStr::from_ptr(name).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    let Some(ref mut e) = *guard else {
+        return false;
+    };
+    match e.switch_profile(name) {
+        Ok(()) => true,
+        Err(err) => {
+            logging::log_warn!("profiles", "{}", err);
+            false
+        }
+    }
+}
+
+/// Name of the active profile ("default" until a switch).
+///
+/// # Returns
+/// * Pointer to a null-terminated UTF-8 string (free with `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_profile_active() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    match *guard {
+        Some(ref e) => into_c_string(e.profile().to_string()),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// All profile names as a JSON array, sorted: `["default","gaming"]`.
+///
+/// # Returns
+/// * Pointer to a null-terminated JSON string (free with `ime_free_string`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_profile_list() -> *mut std::os::raw::c_char {
+    let guard = lock_engine();
+    let Some(ref e) = *guard else {
+        return std::ptr::null_mut();
+    };
+    let names: Vec<String> = e
+        .profile_names()
+        .iter()
+        .map(|n| format!("\"{}\"", utils::json_escape(n)))
+        .collect();
+    into_c_string(format!("[{}]", names.join(",")))
+}
+
+/// Delete a profile; its file goes on the next `ime_profiles_save`.
+///
+/// # Returns
+/// `false` if engine not initialized, the profile does not exist or is
+/// the active one.
 ///
 /// # Safety
-/// Pointer must be a valid null-terminated UTF-8 string.
+/// `name` must be a valid null-terminated UTF-8 string or null.
 #[no_mangle]
-pub unsafe extern "C" fn ime_remove_shortcut(trigger: *const std::os::raw::c_char) {
-    if trigger.is_null() {
-        return;
+pub unsafe extern "C" fn ime_profile_delete(name: *const std::os::raw::c_char) -> bool {
+    if name.is_null() {
+        return false;
+    }
+    let Ok(name) = std::ffi::CStr::from_ptr(name).to_str() else {
+        return false;
+    };
+    let mut guard = lock_engine();
+    match *guard {
+        Some(ref mut e) => e.delete_profile(name),
+        None => false,
     }
+}
 
-    let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
+/// Save every profile to `dir` as `<name>.txt`, plus the active name.
+///
+/// A null `dir` uses `profiles` in the config directory, creating it if
+/// needed. Call after `ime_profile_switch`, `ime_profile_delete` and
+/// `GONHANH_EVENT_PROFILE_CHANGED`.
+///
+/// # Returns
+/// `false` if engine not initialized or a file cannot be written.
+///
+/// # Safety
+/// `dir` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_profiles_save(dir: *const std::os::raw::c_char) -> bool {
+    let Some(dir_str) = path_or_config(dir, config::PROFILES_DIR, true) else {
+        return false;
     };
+    let guard = lock_engine();
+    let Some(ref e) = *guard else {
+        return false;
+    };
+    match e.save_profiles(std::path::Path::new(&dir_str)) {
+        Ok(()) => true,
+        Err(err) => {
+            logging::log_warn!("profiles", "{} path=\"{}\"", err, dir_str);
+            false
+        }
+    }
+}
 
+/// Load the profiles saved by `ime_profiles_save` and apply the active one.
+///
+/// A null `dir` uses `profiles` in the config directory; a missing
+/// directory loads nothing and succeeds.
+///
+/// # Returns
+/// `false` if engine not initialized, or a profile cannot be read or is
+/// invalid; nothing is changed then.
+///
+/// # Safety
+/// `dir` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_profiles_load(dir: *const std::os::raw::c_char) -> bool {
+    let Some(dir_str) = path_or_config(dir, config::PROFILES_DIR, false) else {
+        return false;
+    };
     let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.shortcuts_mut().remove(trigger_str);
+    let Some(ref mut e) = *guard else {
+        return false;
+    };
+    match e.load_profiles(std::path::Path::new(&dir_str)) {
+        Ok(()) => true,
+        Err(err) => {
+            logging::log_warn!("profiles", "{} path=\"{}\"", err, dir_str);
+            false
+        }
     }
 }
 
-/// Clear all shortcuts from the engine.
+// ============================================================
+// Word List FFI
+// ============================================================
+
+/// Use a downloaded word list instead of the embedded one.
+///
+/// `list` is 0 for `vi.dic` (valid Vietnamese words), 1 for `keep.dic`
+/// (never auto-restored). The file must carry a version stamp and checksum
+/// (see `data::dictionary`) and be newer than the embedded list. Applies to
+/// every engine in the process; works before `ime_init`. A null `path`
+/// reads the list's file (`vi.dic`, `keep.dic`) in the config directory.
+///
+/// # Returns
+/// `false` if the list is unknown or the file is missing, corrupt or not
+/// newer; the previous list stays in use.
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
 #[no_mangle]
-pub extern "C" fn ime_clear_shortcuts() {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.shortcuts_mut().clear();
+pub unsafe extern "C" fn ime_dictionary_load(list: u8, path: *const std::os::raw::c_char) -> bool {
+    let Some(list) = dictionary::WordList::from_u8(list) else {
+        return false;
+    };
+    let Some(path_str) = path_or_config(path, list.file_name(), false) else {
+        return false;
+    };
+    match dictionary::load_override(list, &path_str) {
+        Ok(_) => true,
+        Err(err) => {
+            logging::log_warn!("dictionary", "{} path=\"{}\"", err, path_str);
+            false
+        }
     }
 }
 
+/// Version of the word list in use (0 = embedded).
+#[no_mangle]
+pub extern "C" fn ime_dictionary_version(list: u8) -> u32 {
+    dictionary::WordList::from_u8(list).map_or(0, dictionary::version)
+}
+
+/// Go back to the embedded word list.
+#[no_mangle]
+pub extern "C" fn ime_dictionary_reset(list: u8) {
+    if let Some(list) = dictionary::WordList::from_u8(list) {
+        dictionary::reset_override(list);
+    }
+}
+
+/// Reload the user's own word lists.
+///
+/// Every `*.dic` file in `dir` (one word per line, `#` comments) is read
+/// and its words count as valid Vietnamese, on top of `vi.dic`, replacing
+/// the ones loaded before. `ime_init` loads them once; call this after the
+/// folder changed. Applies to every engine in the process. A null `dir`
+/// uses `dictionaries` in the config directory; a missing folder loads
+/// nothing and succeeds.
+///
+/// # Returns
+/// `false` if a file cannot be read; the previous words stay in use.
+///
+/// # Safety
+/// `dir` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_user_dictionaries_reload(dir: *const std::os::raw::c_char) -> bool {
+    let Some(dir_str) = path_or_config(dir, config::DICTIONARIES_DIR, false) else {
+        return false;
+    };
+    match dictionary::load_user_dir(std::path::Path::new(&dir_str)) {
+        Ok(count) => {
+            logging::log_debug!("dictionary", "user words={} path=\"{}\"", count, dir_str);
+            true
+        }
+        Err(err) => {
+            logging::log_warn!("dictionary", "{} path=\"{}\"", err, dir_str);
+            false
+        }
+    }
+}
+
+/// Number of words loaded from the user's word lists.
+#[no_mangle]
+pub extern "C" fn ime_user_dictionary_words() -> u32 {
+    dictionary::user_word_count() as u32
+}
+
 // ============================================================
-// Word Restore FFI
+// App Quirk FFI
 // ============================================================
 
-/// Restore buffer from a Vietnamese word string.
+/// Send strategy for `app` from the quirk table (see `data::quirks`).
 ///
-/// Used when native app detects cursor at word boundary and user
-/// wants to continue editing (e.g., backspace into previous word).
-/// Parses Vietnamese characters back to buffer components.
+/// Hosts look it up on focus change to pick how to inject replacements
+/// (per-character delays, paste, Unicode backspace, ...). A null `app`
+/// uses the app set with `ime_set_active_app`.
 ///
-/// # Arguments
-/// * `word` - C string containing the Vietnamese word to restore
+/// # Returns
+/// `true` with `*out` filled if the app has an entry; `false` if it needs
+/// nothing special (use the host default), or `out` is null.
 ///
 /// # Safety
-/// Pointer must be a valid null-terminated UTF-8 string.
+/// `app` must be null or a valid null-terminated UTF-8 string; `out` must
+/// be null or point to a writable `ImeAppQuirk`.
 #[no_mangle]
-pub unsafe extern "C" fn ime_restore_word(word: *const std::os::raw::c_char) {
-    if word.is_null() {
-        return;
+pub unsafe extern "C" fn ime_app_quirk(
+    app: *const std::os::raw::c_char,
+    out: *mut quirks::Quirk,
+) -> bool {
+    if out.is_null() {
+        return false;
     }
-    let word_str = match std::ffi::CStr::from_ptr(word).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
+    let app = if app.is_null() {
+        let guard = lock_engine();
+        match guard.as_ref().and_then(|e| e.shortcuts().active_app()) {
+            Some(app) => app.to_string(),
+            None => return false,
+        }
+    } else {
+        match std::ffi::CStr::from_ptr(app).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return false,
+        }
     };
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.restore_word(word_str);
+    match quirks::lookup(&app) {
+        Some(quirk) => {
+            *out = quirk;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Use a downloaded quirk table instead of the embedded one.
+///
+/// The file is stamped like word lists (see `ime_dictionary_load`) and
+/// replaces the whole table. A null `path` reads `quirks.txt` in the
+/// config directory.
+///
+/// # Returns
+/// `false` if the file is missing, corrupt or older than the table in use.
+///
+/// # Safety
+/// `path` must be a valid null-terminated UTF-8 string or null.
+#[no_mangle]
+pub unsafe extern "C" fn ime_quirks_load(path: *const std::os::raw::c_char) -> bool {
+    let Some(path_str) = path_or_config(path, quirks::FILE_NAME, false) else {
+        return false;
+    };
+    match quirks::load_override(&path_str) {
+        Ok(_) => true,
+        Err(err) => {
+            logging::log_warn!("quirks", "{} path=\"{}\"", err, path_str);
+            false
+        }
     }
 }
 
+/// Version of the quirk table in use (0 = embedded).
+#[no_mangle]
+pub extern "C" fn ime_quirks_version() -> u32 {
+    quirks::version()
+}
+
+/// Go back to the embedded quirk table.
+#[no_mangle]
+pub extern "C" fn ime_quirks_reset() {
+    quirks::reset_override();
+}
+
 // ============================================================
 // Tests
 // ============================================================
@@ -502,6 +2922,399 @@ mod tests {
         ime_clear();
     }
 
+    #[test]
+    #[serial]
+    fn test_result_accessors() {
+        ime_init();
+        ime_method(0); // Telex
+
+        assert_eq!(ime_abi_version(), ABI_VERSION);
+        assert_eq!(std::mem::size_of::<Result>(), 1028, "Result layout is ABI");
+
+        let r1 = ime_key(keys::A, false, false);
+        unsafe { ime_free(r1) };
+        let r2 = ime_key(keys::S, false, false);
+        unsafe {
+            assert_eq!(ime_result_action(r2), engine::Action::Send as u8);
+            assert_eq!(ime_result_backspace(r2), 1);
+            assert_eq!(ime_result_count(r2), 1);
+            assert_eq!(ime_result_flags(r2), 0);
+            assert_eq!(*ime_result_chars(r2), 'á' as u32);
+            ime_free(r2);
+
+            // Null-safe
+            assert_eq!(ime_result_action(std::ptr::null()), 0);
+            assert!(ime_result_chars(std::ptr::null()).is_null());
+        }
+
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_concurrent_callers() {
+        // Hook thread types while UI threads flip settings - must not panic or deadlock
+        ime_init();
+        ime_method(0);
+
+        let typer = std::thread::spawn(|| {
+            for _ in 0..200 {
+                for key in [
+                    keys::V,
+                    keys::I,
+                    keys::E,
+                    keys::E,
+                    keys::T,
+                    keys::J,
+                    keys::SPACE,
+                ] {
+                    let r = ime_key_ext(key, false, false, false);
+                    assert!(!r.is_null());
+                    unsafe { ime_free(r) };
+                }
+            }
+        });
+        let ui: Vec<_> = (0..4)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    for n in 0..200 {
+                        ime_modern(n % 2 == 0);
+                        ime_english_auto_restore(i % 2 == 0);
+                        ime_clear_all();
+                    }
+                })
+            })
+            .collect();
+
+        typer.join().unwrap();
+        for t in ui {
+            t.join().unwrap();
+        }
+
+        // Engine still usable afterwards
+        ime_method(0);
+        ime_clear_all();
+        let r = ime_key(keys::A, false, false);
+        assert!(!r.is_null());
+        unsafe { ime_free(r) };
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_dump_state_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+
+        for key in [keys::V, keys::I, keys::E, keys::E, keys::T] {
+            unsafe { ime_free(ime_key(key, false, false)) };
+        }
+
+        let ptr = ime_dump_state();
+        assert!(!ptr.is_null());
+        let json = unsafe { std::ffi::CStr::from_ptr(ptr) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { ime_free_string(ptr) };
+
+        assert!(json.starts_with('{') && json.ends_with('}'));
+        assert!(json.contains("\"buffer\":\"viêt\""), "{}", json);
+        assert!(json.contains("\"raw_input\":\"vieet\""), "{}", json);
+        assert!(json.contains("\"method\":0"));
+        assert!(json.contains("\"pending_capitalize\":false"));
+        assert!(json.contains("\"char\":\"ê\",\"key\":14,\"caps\":false,\"tone\":1"));
+
+        // Null-safe free
+        unsafe { ime_free_string(std::ptr::null_mut()) };
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_validate_word_ffi() {
+        ime_init();
+        let check = |word: &str| {
+            let c_word = CString::new(word).unwrap();
+            unsafe { ime_validate_word(c_word.as_ptr()) }
+        };
+        assert_eq!(check("được"), ValidationResult::Valid as u8);
+        assert_eq!(check("clau"), ValidationResult::InvalidInitial as u8);
+        assert_eq!(check("ka"), ValidationResult::InvalidSpelling as u8);
+        assert_eq!(check("zô"), ValidationResult::InvalidInitial as u8);
+        ime_allow_foreign_consonants(true);
+        assert_eq!(check("zô"), ValidationResult::Valid as u8);
+        ime_allow_foreign_consonants(false);
+        ime_validation_strictness(Strictness::Relaxed as u8);
+        assert_eq!(check("ko"), ValidationResult::Valid as u8);
+        ime_validation_strictness(Strictness::Standard as u8);
+        assert_eq!(check("dzô"), ValidationResult::InvalidInitial as u8);
+        ime_spelling_variants(dictionary::VARIANT_SOUTHERN);
+        assert_eq!(check("dzô"), ValidationResult::Valid as u8);
+        ime_spelling_variants(0);
+        assert_eq!(
+            unsafe { ime_validate_word(std::ptr::null()) },
+            ValidationResult::NoVowel as u8
+        );
+    }
+
+    static EVENTS: Mutex<Vec<(u8, String)>> = Mutex::new(Vec::new());
+
+    extern "C" fn record_event(event: u8, text: *const std::os::raw::c_char) {
+        let text = unsafe { std::ffi::CStr::from_ptr(text) }.to_str().unwrap();
+        // The engine is unlocked: calling back in must not deadlock
+        ime_clear();
+        EVENTS.lock().unwrap().push((event, text.to_string()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_event_callback_ffi() {
+        ime_init();
+        ime_method(0);
+        ime_repeated_word_check(true);
+        ime_set_event_callback(Some(record_event));
+        for key in [
+            keys::A,
+            keys::N,
+            keys::SPACE,
+            keys::A,
+            keys::N,
+            keys::SPACE,
+            keys::D,
+            keys::I,
+        ] {
+            unsafe { ime_free(ime_key(key, false, false)) };
+        }
+        assert_eq!(*EVENTS.lock().unwrap(), [(1, "an".to_string())]);
+
+        ime_set_event_callback(None);
+        for key in [keys::A, keys::N, keys::SPACE, keys::A, keys::N, keys::SPACE] {
+            unsafe { ime_free(ime_key(key, false, false)) };
+        }
+        assert_eq!(EVENTS.lock().unwrap().len(), 1);
+        ime_repeated_word_check(false);
+        ime_clear_all();
+    }
+
+    extern "C" fn kg_hook(word: *const std::os::raw::c_char) -> *const std::os::raw::c_char {
+        // The engine is unlocked: calling back in must not deadlock
+        ime_method(0);
+        let word = unsafe { std::ffi::CStr::from_ptr(word) };
+        if word.to_bytes() == b"kg" {
+            c"kilo".as_ptr()
+        } else {
+            std::ptr::null()
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_word_hook_ffi() {
+        ime_init();
+        ime_method(0);
+        ime_set_word_hook(Some(kg_hook));
+        ime_word_hooks(true);
+        for key in [keys::K, keys::G] {
+            unsafe { ime_free(ime_key(key, false, false)) };
+        }
+        // The rewrite goes out with the Space that committed the word
+        let r = ime_key(keys::SPACE, false, false);
+        let result = unsafe { &*r };
+        assert_eq!((result.backspace, result.count), (2, 5));
+        let chars: String = result.chars[..5]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(chars, "kilo ");
+        unsafe { ime_free(r) };
+        ime_word_hooks(false);
+        ime_set_word_hook(None);
+        ime_clear_all();
+    }
+
+    #[test]
+    #[serial]
+    fn test_tone_renderings_ffi() {
+        ime_init();
+        let renderings = |word: &str| {
+            let c_word = CString::new(word).unwrap();
+            let ptr = unsafe { ime_tone_renderings(c_word.as_ptr()) };
+            let json = unsafe { std::ffi::CStr::from_ptr(ptr) }
+                .to_str()
+                .unwrap()
+                .to_string();
+            unsafe { ime_free_string(ptr) };
+            json
+        };
+        assert_eq!(renderings("hòa"), r#"["hoà","hòa"]"#);
+        ime_tone_style(ToneStyle::TraditionalStyle as u8);
+        assert_eq!(renderings("hòa"), r#"["hòa","hoà"]"#);
+        assert_eq!(renderings("đi"), r#"["đi"]"#);
+        ime_tone_style(ToneStyle::NewStyle as u8);
+        assert!(unsafe { ime_tone_renderings(std::ptr::null()) }.is_null());
+    }
+
+    #[test]
+    #[serial]
+    fn test_self_test_ffi() {
+        ime_init();
+        ime_method(1); // VNI
+
+        let ptr = ime_self_test();
+        assert!(!ptr.is_null());
+        let json = unsafe { std::ffi::CStr::from_ptr(ptr) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { ime_free_string(ptr) };
+
+        assert!(json.contains("\"failed\":0"), "{}", json);
+        ime_method(0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_stats_ffi() {
+        ime_init();
+        ime_method(0);
+        ime_stats_reset();
+        ime_stats_enabled(true);
+
+        for &key in &[
+            keys::V,
+            keys::I,
+            keys::E,
+            keys::E,
+            keys::T,
+            keys::J,
+            keys::SPACE,
+        ] {
+            unsafe { ime_free(ime_key(key, false, false)) };
+        }
+
+        let ptr = ime_stats_json();
+        assert!(!ptr.is_null());
+        let json = unsafe { std::ffi::CStr::from_ptr(ptr) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { ime_free_string(ptr) };
+        assert!(json.contains("\"keystrokes\":7"), "{}", json);
+        assert!(json.contains("\"words\":1"), "{}", json);
+
+        let path = std::env::temp_dir().join(format!("gonhanh-stats-{}.txt", std::process::id()));
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        assert!(unsafe { ime_stats_save(c_path.as_ptr()) });
+        ime_stats_reset();
+        assert!(unsafe { ime_stats_load(c_path.as_ptr()) });
+        assert_eq!(lock_engine().as_ref().unwrap().stats().words, 1);
+        let _ = std::fs::remove_file(&path);
+
+        ime_stats_enabled(false);
+        ime_stats_reset();
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_get_set_ffi() {
+        ime_init();
+        let get = |key: &str| {
+            let c_key = CString::new(key).unwrap();
+            let ptr = unsafe { ime_config_get(c_key.as_ptr()) };
+            if ptr.is_null() {
+                return None;
+            }
+            let value = unsafe { std::ffi::CStr::from_ptr(ptr) }
+                .to_str()
+                .unwrap()
+                .to_string();
+            unsafe { ime_free_string(ptr) };
+            Some(value)
+        };
+        let set = |key: &str, value: &str| {
+            let (k, v) = (CString::new(key).unwrap(), CString::new(value).unwrap());
+            unsafe { ime_config_set(k.as_ptr(), v.as_ptr()) }
+        };
+
+        assert_eq!(get("method").as_deref(), Some("0"));
+        assert!(set("method", "1"));
+        assert_eq!(get("method").as_deref(), Some("1"));
+        assert!(set("remove_key_telex", "none"));
+        assert_eq!(get("remove_key_telex").as_deref(), Some("none"));
+
+        assert!(!set("method", "9"));
+        assert!(!set("free_tone", "yes"));
+        assert!(!set("nope", "1"));
+        assert_eq!(get("nope"), None);
+        assert_eq!(get("method").as_deref(), Some("1"));
+
+        let ptr = ime_config_keys();
+        let keys = unsafe { std::ffi::CStr::from_ptr(ptr) }.to_str().unwrap();
+        assert!(keys.starts_with("[\"method\",\"tone_style\","));
+        unsafe { ime_free_string(ptr) };
+
+        let json = CString::new(r#"{"method":0,"free_tone":true}"#).unwrap();
+        assert!(unsafe { ime_save_config_json(json.as_ptr()) });
+        let ptr = ime_load_config_json();
+        let doc = unsafe { std::ffi::CStr::from_ptr(ptr) }.to_str().unwrap();
+        assert!(doc.starts_with(r#"{"method":0,"tone_style":1,"free_tone":true,"#));
+        unsafe { ime_free_string(ptr) };
+        let json = CString::new("[1]").unwrap();
+        assert!(!unsafe { ime_save_config_json(json.as_ptr()) });
+
+        ime_init();
+    }
+
+    #[test]
+    #[serial]
+    fn test_settings_ffi_config_dir() {
+        let dir = std::env::temp_dir().join(format!("gonhanh-config-{}", std::process::id()));
+        std::env::set_var(config::ENV_VAR, &dir);
+        let ptr = ime_config_dir();
+        assert_eq!(
+            unsafe { std::ffi::CStr::from_ptr(ptr) }.to_str().unwrap(),
+            dir.to_str().unwrap()
+        );
+        unsafe { ime_free_string(ptr) };
+
+        ime_init();
+        ime_method(1);
+        assert!(unsafe { ime_settings_export(std::ptr::null()) });
+        assert!(dir.join(config::SETTINGS_FILE).exists());
+        ime_method(0);
+        assert!(unsafe { ime_settings_import(std::ptr::null()) });
+        assert_eq!(lock_engine().as_ref().unwrap().method(), 1);
+        // No downloaded word list there
+        assert!(!unsafe { ime_dictionary_load(0, std::ptr::null()) });
+
+        std::env::remove_var(config::ENV_VAR);
+        let _ = std::fs::remove_dir_all(&dir);
+        ime_method(0);
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_app_quirk_ffi() {
+        ime_init();
+        let mut quirk = quirks::Quirk::new(quirks::SendMethod::Fast);
+        let app = CString::new("firefox.exe").unwrap();
+        unsafe { ime_set_active_app(app.as_ptr()) };
+        assert!(unsafe { ime_app_quirk(std::ptr::null(), &mut quirk) });
+        assert_eq!(quirk.method, quirks::SendMethod::Slow);
+        assert!(quirk.has(quirks::QUIRK_KEYUP_RACE));
+
+        let app = CString::new("notepad.exe").unwrap();
+        assert!(!unsafe { ime_app_quirk(app.as_ptr(), &mut quirk) });
+        assert!(!unsafe { ime_app_quirk(app.as_ptr(), std::ptr::null_mut()) });
+
+        unsafe { ime_set_active_app(std::ptr::null()) };
+        assert!(!unsafe { ime_app_quirk(std::ptr::null(), &mut quirk) });
+        assert_eq!(ime_quirks_version(), 0);
+    }
+
     #[test]
     #[serial]
     fn test_shortcut_ffi_add_and_clear() {
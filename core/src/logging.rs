@@ -0,0 +1,236 @@
+//! Runtime-configurable logging
+//!
+//! Minimal leveled logger for diagnosing engine decisions (auto-restore,
+//! reverts, shortcut expansion) in release builds without a debugger.
+//!
+//! - Level is a global atomic: when logging is off (default), every log
+//!   macro is a single relaxed load and nothing is formatted or allocated.
+//! - Output goes to stderr, or to a file set via `set_file()`. The file is
+//!   rotated to `<path>.1` once it exceeds `MAX_FILE_SIZE`.
+//!
+//! Lines are `key=value` structured for easy grepping:
+//! ```text
+//! 1718000000123 DEBUG auto_restore: restore to="text" trigger=space buf="tẽt" raw="text"
+//! ```
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Rotate the log file when it grows past this size (1 MiB)
+pub const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Log severity, ordered from quietest to noisiest
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl Level {
+    /// Convert from FFI value; values above Trace clamp to Trace
+    pub fn from_u8(v: u8) -> Level {
+        match v {
+            0 => Level::Off,
+            1 => Level::Error,
+            2 => Level::Warn,
+            3 => Level::Info,
+            4 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Off => "OFF",
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Off as u8);
+
+/// Open log file plus its path (needed for rotation)
+struct FileSink {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+static SINK: Mutex<Option<FileSink>> = Mutex::new(None);
+
+fn lock_sink() -> std::sync::MutexGuard<'static, Option<FileSink>> {
+    SINK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the global log level
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Get the global log level
+pub fn level() -> Level {
+    Level::from_u8(LEVEL.load(Ordering::Relaxed))
+}
+
+/// Check if messages at `level` would be written
+#[inline]
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && (level as u8) <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Send output to `path` (appending). `None` reverts to stderr.
+///
+/// Returns false if the file cannot be opened; the previous sink is kept.
+pub fn set_file(path: Option<&str>) -> bool {
+    let mut sink = lock_sink();
+    let Some(path) = path.filter(|p| !p.is_empty()) else {
+        *sink = None;
+        return true;
+    };
+    match OpenOptions::new().create(true).append(true).open(path) {
+        Ok(file) => {
+            let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+            *sink = Some(FileSink {
+                path: PathBuf::from(path),
+                file,
+                size,
+            });
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Write one log line. Use the `log_*!` macros instead of calling directly.
+pub fn write(level: Level, target: &str, args: std::fmt::Arguments) {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let line = format!("{} {} {}: {}\n", millis, level.as_str(), target, args);
+
+    let mut sink = lock_sink();
+    match sink.as_mut() {
+        Some(s) => {
+            if s.size + line.len() as u64 > MAX_FILE_SIZE {
+                rotate(s);
+            }
+            if s.file.write_all(line.as_bytes()).is_ok() {
+                s.size += line.len() as u64;
+            }
+        }
+        None => {
+            let _ = std::io::stderr().write_all(line.as_bytes());
+        }
+    }
+}
+
+/// Move the current file to `<path>.1` and start a fresh one
+fn rotate(s: &mut FileSink) {
+    let mut backup = s.path.clone().into_os_string();
+    backup.push(".1");
+    let _ = fs::rename(&s.path, &backup);
+    if let Ok(file) = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&s.path)
+    {
+        s.file = file;
+        s.size = 0;
+    }
+}
+
+macro_rules! log_at {
+    ($level:expr, $target:expr, $($arg:tt)*) => {
+        if $crate::logging::enabled($level) {
+            $crate::logging::write($level, $target, format_args!($($arg)*));
+        }
+    };
+}
+
+macro_rules! log_warn {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::logging::log_at!($crate::logging::Level::Warn, $target, $($arg)*)
+    };
+}
+
+macro_rules! log_debug {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::logging::log_at!($crate::logging::Level::Debug, $target, $($arg)*)
+    };
+}
+
+macro_rules! log_trace {
+    ($target:expr, $($arg:tt)*) => {
+        $crate::logging::log_at!($crate::logging::Level::Trace, $target, $($arg)*)
+    };
+}
+
+pub(crate) use {log_at, log_debug, log_trace, log_warn};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_level_filtering() {
+        set_level(Level::Off);
+        assert!(!enabled(Level::Error));
+
+        set_level(Level::Info);
+        assert!(enabled(Level::Error));
+        assert!(enabled(Level::Info));
+        assert!(!enabled(Level::Debug));
+        assert!(!enabled(Level::Off));
+
+        assert_eq!(Level::from_u8(200), Level::Trace);
+        set_level(Level::Off);
+    }
+
+    #[test]
+    #[serial]
+    fn test_file_output_and_rotation() {
+        let path = std::env::temp_dir().join(format!("gonhanh-log-{}.log", std::process::id()));
+        let backup = PathBuf::from(format!("{}.1", path.display()));
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&backup);
+
+        assert!(set_file(path.to_str()));
+        set_level(Level::Debug);
+        log_debug!("test", "hello key={}", 42);
+        log_trace!("test", "filtered out");
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("DEBUG test: hello key=42"));
+        assert!(!content.contains("filtered out"));
+
+        // Fill past the limit to force rotation
+        let chunk = "x".repeat(4096);
+        for _ in 0..(MAX_FILE_SIZE / 4096 + 1) {
+            log_debug!("test", "{}", chunk);
+        }
+        assert!(backup.exists(), "Log file should rotate to .1");
+        assert!(fs::metadata(&path).unwrap().len() <= MAX_FILE_SIZE);
+
+        set_level(Level::Off);
+        assert!(set_file(None));
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&backup);
+    }
+}
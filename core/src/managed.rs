@@ -0,0 +1,332 @@
+//! Managed config: options an administrator sets for every user
+//!
+//! IT departments deploy one read-only file to a system path; the options
+//! in it override the user's settings and stay locked, so the settings UI,
+//! settings bundles and profiles cannot change them. It can also turn off
+//! network features (update checks, word list downloads).
+//!
+//! - Windows: `FKey\managed.txt` in the ProgramData known folder
+//!   (`C:\ProgramData`), asked from the shell, not from `%ProgramData%`
+//! - macOS: `/Library/Application Support/FKey/managed.txt`
+//! - Linux and other Unix: `/etc/fkey/managed.txt`
+//!
+//! There is deliberately no environment override, on any platform: users
+//! could point it at an empty file. For the same reason hosts cannot pick
+//! the path either (`load` only reads `system_path()`). The file has option
+//! lines as in a settings bundle (`engine::settings`) plus `network`:
+//!
+//! ```text
+//! # gonhanh managed 1
+//! tone_style 0
+//! spell_check true
+//! network false
+//! ```
+//!
+//! Unknown keys are skipped, so files written for newer versions still
+//! load. A bad line costs only its own key, with a warning in the log: an
+//! invalid option value stays locked at the default, an invalid `network`
+//! value turns network features off, and a line without a value is
+//! skipped. Only a file without the header is rejected.
+
+use crate::config::Os;
+use crate::engine::settings::{self, SettingsError};
+use crate::engine::Engine;
+use crate::logging;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// First line of a managed config file (format version 1)
+pub const HEADER: &str = "# gonhanh managed 1";
+
+/// Managed config file name
+pub const FILE_NAME: &str = "managed.txt";
+
+/// Locked options and policies from a managed config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Managed {
+    /// (key, value) as in settings files, in file order
+    pub options: Vec<(String, String)>,
+    /// Update checks and word list downloads allowed
+    pub network: bool,
+}
+
+impl Default for Managed {
+    fn default() -> Self {
+        Self {
+            options: Vec::new(),
+            network: true,
+        }
+    }
+}
+
+impl Managed {
+    /// Parse a managed config file (see module docs)
+    pub fn parse(text: &str) -> Result<Self, SettingsError> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, first)) if first.trim_start_matches('\u{feff}').trim_end() == HEADER => {}
+            _ => return Err(SettingsError::NotSettings),
+        }
+        let mut managed = Managed::default();
+        // Defaults for options with an invalid value, built on first need
+        let mut defaults: Option<Engine> = None;
+        for (i, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(' ') else {
+                logging::log_warn!("managed", "{}", SettingsError::Malformed(i + 1));
+                continue;
+            };
+            let value = value.trim();
+            let value = match key {
+                "network" => {
+                    managed.network = value.parse().unwrap_or_else(|_| {
+                        logging::log_warn!(
+                            "managed",
+                            "{} (network off)",
+                            SettingsError::InvalidValue(key.into(), value.into())
+                        );
+                        false
+                    });
+                    continue;
+                }
+                _ => match settings::check_option(key, value) {
+                    Ok(()) => value.to_string(),
+                    // Option from a newer version
+                    Err(SettingsError::UnknownOption(_)) => continue,
+                    Err(err) => {
+                        // Keep the key locked rather than hand it back to the user
+                        let default = defaults.get_or_insert_with(Engine::new).option(key);
+                        let Some(default) = default else { continue };
+                        logging::log_warn!("managed", "{} (locked at {})", err, default);
+                        default
+                    }
+                },
+            };
+            managed.options.retain(|(k, _)| k != key);
+            managed.options.push((key.to_string(), value));
+        }
+        Ok(managed)
+    }
+
+    /// Locked value of option `key`
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+static MANAGED: RwLock<Option<Managed>> = RwLock::new(None);
+
+/// Managed config file for this platform
+pub fn system_path() -> PathBuf {
+    resolve(Os::current(), program_data())
+}
+
+/// `system_path()` for `os`, with the ProgramData known folder passed in
+/// (None: the standard `C:\ProgramData`)
+pub fn resolve(os: Os, program_data: Option<PathBuf>) -> PathBuf {
+    match os {
+        Os::Windows => program_data
+            .unwrap_or_else(|| PathBuf::from("C:\\ProgramData"))
+            .join("FKey")
+            .join(FILE_NAME),
+        Os::MacOs => PathBuf::from("/Library/Application Support/FKey").join(FILE_NAME),
+        Os::Unix => PathBuf::from("/etc/fkey").join(FILE_NAME),
+    }
+}
+
+/// The ProgramData known folder (`FOLDERID_ProgramData`)
+///
+/// Asked from the shell: `%ProgramData%` is set per process and anyone can
+/// change it.
+#[cfg(windows)]
+fn program_data() -> Option<PathBuf> {
+    use std::ffi::{c_void, OsString};
+    use std::os::windows::ffi::OsStringExt;
+
+    #[repr(C)]
+    struct Guid {
+        data1: u32,
+        data2: u16,
+        data3: u16,
+        data4: [u8; 8],
+    }
+
+    const FOLDERID_PROGRAM_DATA: Guid = Guid {
+        data1: 0x62AB_5D82,
+        data2: 0xFDC1,
+        data3: 0x4DC3,
+        data4: [0xA9, 0xDD, 0x07, 0x0D, 0x1D, 0x49, 0x5D, 0x97],
+    };
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHGetKnownFolderPath(
+            rfid: *const Guid,
+            flags: u32,
+            token: *mut c_void,
+            path: *mut *mut u16,
+        ) -> i32;
+    }
+
+    #[link(name = "ole32")]
+    extern "system" {
+        fn CoTaskMemFree(pv: *mut c_void);
+    }
+
+    let mut raw: *mut u16 = std::ptr::null_mut();
+    // SAFETY: a valid folder id and out pointer; the returned string is
+    // NUL-terminated and freed with CoTaskMemFree, even on failure
+    unsafe {
+        let hr = SHGetKnownFolderPath(&FOLDERID_PROGRAM_DATA, 0, std::ptr::null_mut(), &mut raw);
+        let path = (hr >= 0 && !raw.is_null()).then(|| {
+            let len = (0..).take_while(|&i| *raw.add(i) != 0).count();
+            PathBuf::from(OsString::from_wide(std::slice::from_raw_parts(raw, len)))
+        });
+        CoTaskMemFree(raw.cast());
+        path
+    }
+}
+
+#[cfg(not(windows))]
+fn program_data() -> Option<PathBuf> {
+    None
+}
+
+/// Read `system_path()` and make it the process-wide managed config
+///
+/// A missing file clears it (not managed); on any other error the previous
+/// config stays.
+pub fn load() -> Result<(), SettingsError> {
+    load_file(&system_path())
+}
+
+/// `load` from `path`
+fn load_file(path: &Path) -> Result<(), SettingsError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            set(None);
+            return Ok(());
+        }
+        Err(err) => return Err(SettingsError::Io(err.to_string())),
+    };
+    set(Some(Managed::parse(&text)?));
+    Ok(())
+}
+
+/// Replace the process-wide managed config (None: not managed)
+pub fn set(managed: Option<Managed>) {
+    *MANAGED.write().unwrap_or_else(|e| e.into_inner()) = managed;
+}
+
+/// Current managed config, None when not managed
+pub fn current() -> Option<Managed> {
+    MANAGED.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Whether option `key` is locked by the managed config
+pub fn is_locked(key: &str) -> bool {
+    MANAGED
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .is_some_and(|m| m.value(key).is_some())
+}
+
+/// Whether network features are allowed
+pub fn network_allowed() -> bool {
+    MANAGED
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .is_none_or(|m| m.network)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    fn test_parse() {
+        let text = format!(
+            "{}\n# fleet defaults\ntone_style 0\nspell_check true\nnetwork false\nfuture_option 3\n",
+            HEADER
+        );
+        let managed = Managed::parse(&text).unwrap();
+        assert_eq!(managed.value("tone_style"), Some("0"));
+        assert_eq!(managed.value("spell_check"), Some("true"));
+        assert_eq!(managed.value("future_option"), None);
+        assert!(!managed.network);
+
+        assert_eq!(
+            Managed::parse("tone_style 0"),
+            Err(SettingsError::NotSettings)
+        );
+    }
+
+    #[test]
+    fn test_parse_bad_lines() {
+        // Each bad line costs only its own key, and fails closed
+        let text = format!(
+            "{}\ntone_style 5\nspell_check\nfree_tone true\nnetwork off\n",
+            HEADER
+        );
+        let managed = Managed::parse(&text).unwrap();
+        let default = Engine::new().option("tone_style");
+        assert_eq!(managed.value("tone_style"), default.as_deref());
+        assert_eq!(managed.value("spell_check"), None);
+        assert_eq!(managed.value("free_tone"), Some("true"));
+        assert!(!managed.network);
+    }
+
+    #[test]
+    fn test_system_paths() {
+        assert_eq!(
+            resolve(Os::Windows, Some(PathBuf::from("D:\\Data"))),
+            PathBuf::from("D:\\Data").join("FKey").join(FILE_NAME)
+        );
+        assert_eq!(
+            resolve(Os::Windows, None),
+            PathBuf::from("C:\\ProgramData")
+                .join("FKey")
+                .join(FILE_NAME)
+        );
+        assert_eq!(
+            resolve(Os::Unix, None),
+            PathBuf::from("/etc/fkey/managed.txt")
+        );
+        assert_eq!(
+            resolve(Os::MacOs, None),
+            PathBuf::from("/Library/Application Support/FKey/managed.txt")
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_load_file() {
+        let dir = std::env::temp_dir().join(format!("gonhanh-managed-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE_NAME);
+        fs::write(&path, format!("{}\nspell_check true\n", HEADER)).unwrap();
+        load_file(&path).unwrap();
+        assert!(is_locked("spell_check"));
+
+        // A file without the header is rejected and the old config stays
+        fs::write(&path, "spell_check false\n").unwrap();
+        assert!(load_file(&path).is_err());
+        assert!(is_locked("spell_check"));
+
+        // A missing file means not managed
+        fs::remove_dir_all(&dir).unwrap();
+        load_file(&path).unwrap();
+        assert!(!is_locked("spell_check"));
+    }
+}
@@ -0,0 +1,70 @@
+//! Built-in self-test
+//!
+//! Replays the golden corpus for the live input method (`corpus::TELEX` or
+//! `corpus::VNI`: basic words, sentences, a paragraph) on a fresh engine
+//! carrying the live options, so support can tell an engine bug (self-test
+//! fails) from a platform injection bug (self-test passes, output on screen
+//! is wrong).
+//!
+//! Those corpora avoid anything option-dependent: no oa/oe/uy open syllables
+//! (modern vs traditional tone), no standalone w (w→ư toggle), no brackets,
+//! no English words (auto-restore), and every sentence already starts with a
+//! capital letter (auto-capitalize). Title Case is switched off for the run:
+//! it recases every word by design, so no corpus could pass under it.
+
+use crate::corpus;
+use crate::engine::Engine;
+
+pub use crate::corpus::{Failure, Report};
+
+/// Run the corpus for the live engine's input method and options
+///
+/// The live engine is not touched: each case runs on a fresh copy
+/// (`Engine::fresh_copy`) so the user's buffer and history survive.
+pub fn run(live: &Engine) -> Report {
+    let name = if live.method() == 1 { "vni" } else { "telex" };
+    let mut base = live.fresh_copy();
+    base.set_title_case(false);
+    corpus::run_builtin(name, &base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::type_word;
+
+    /// Corpora must pass under every option that the header promises to avoid
+    #[test]
+    fn test_corpus_passes_under_all_options() {
+        for method in [0, 1, 2] {
+            for flags in 0u8..128 {
+                let mut e = Engine::new();
+                e.set_method(method);
+                e.set_modern_tone(flags & 1 != 0);
+                e.set_english_auto_restore(flags & 2 != 0);
+                e.set_auto_capitalize(flags & 4 != 0);
+                e.set_skip_w_shortcut(flags & 8 != 0);
+                e.set_bracket_shortcut(flags & 16 != 0);
+                e.set_free_tone(flags & 32 != 0);
+                e.set_title_case(flags & 64 != 0);
+                let report = run(&e);
+                assert!(
+                    report.passed(),
+                    "method={} flags={:07b}: {:?}",
+                    method,
+                    flags,
+                    report.failures
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_does_not_touch_live_engine() {
+        let mut e = Engine::new();
+        type_word(&mut e, "vie");
+        let report = run(&e);
+        assert!(report.total > 0);
+        assert_eq!(e.get_buffer_string(), "vie");
+    }
+}
@@ -0,0 +1,228 @@
+//! Typing statistics (local only)
+//!
+//! Counts keystrokes, committed words, backspaces the engine sent on the
+//! user's behalf, and shortcut expansions for a "thống kê gõ phím" screen.
+//!
+//! - Off by default. Nothing is counted until `set_enabled(true)`.
+//! - Never leaves the machine: the only persistence is `save()`/`load()` to a
+//!   path chosen by the host. There is no network code in the core.
+//! - Only counts and shortcut triggers are stored, never typed text.
+
+use std::collections::HashMap;
+use std::fs;
+
+use crate::engine::{Action, Result};
+use crate::utils;
+
+/// Number of shortcuts reported by `to_json()`
+pub const TOP_SHORTCUTS: usize = 10;
+
+/// Typing statistics counters
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    enabled: bool,
+    /// Keys processed by the engine
+    pub keystrokes: u64,
+    /// Words committed at a word boundary (space, punctuation, Enter)
+    pub words: u64,
+    /// Backspaces sent by the engine instead of the user
+    pub backspaces_avoided: u64,
+    /// Expansion count per shortcut trigger
+    pub shortcuts: HashMap<String, u64>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Count one processed key and the backspaces in its result
+    #[inline]
+    pub fn record_key(&mut self, result: &Result) {
+        if !self.enabled {
+            return;
+        }
+        self.keystrokes += 1;
+        if result.action == Action::Send as u8 {
+            self.backspaces_avoided += result.backspace as u64;
+        }
+    }
+
+    /// Count one committed word
+    #[inline]
+    pub fn record_word(&mut self) {
+        if self.enabled {
+            self.words += 1;
+        }
+    }
+
+    /// Count one shortcut expansion
+    pub fn record_shortcut(&mut self, trigger: &str) {
+        if !self.enabled {
+            return;
+        }
+        match self.shortcuts.get_mut(trigger) {
+            Some(n) => *n += 1,
+            None => {
+                self.shortcuts.insert(trigger.to_string(), 1);
+            }
+        }
+    }
+
+    /// Clear all counters (keeps the enabled flag)
+    pub fn reset(&mut self) {
+        let enabled = self.enabled;
+        *self = Self::default();
+        self.enabled = enabled;
+    }
+
+    /// Most used shortcuts, highest count first (ties by trigger)
+    pub fn top_shortcuts(&self, n: usize) -> Vec<(&str, u64)> {
+        let mut top: Vec<(&str, u64)> = self
+            .shortcuts
+            .iter()
+            .map(|(t, &c)| (t.as_str(), c))
+            .collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        top.truncate(n);
+        top
+    }
+
+    /// Serialize for the frontend
+    pub fn to_json(&self) -> String {
+        let top: Vec<String> = self
+            .top_shortcuts(TOP_SHORTCUTS)
+            .iter()
+            .map(|(t, c)| {
+                format!(
+                    "{{\"trigger\":\"{}\",\"count\":{}}}",
+                    utils::json_escape(t),
+                    c
+                )
+            })
+            .collect();
+        format!(
+            "{{\"enabled\":{},\"keystrokes\":{},\"words\":{},\"backspaces_avoided\":{},\"top_shortcuts\":[{}]}}",
+            self.enabled,
+            self.keystrokes,
+            self.words,
+            self.backspaces_avoided,
+            top.join(",")
+        )
+    }
+
+    /// Serialize as `name value` lines; shortcuts as `shortcut <count> <trigger>`
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "keystrokes {}\nwords {}\nbackspaces_avoided {}\n",
+            self.keystrokes, self.words, self.backspaces_avoided
+        );
+        for (trigger, count) in self.top_shortcuts(usize::MAX) {
+            out.push_str(&format!("shortcut {} {}\n", count, trigger));
+        }
+        out
+    }
+
+    /// Merge counters from `to_text()` output (unknown lines are ignored)
+    pub fn load_text(&mut self, text: &str) {
+        for line in text.lines() {
+            let mut parts = line.splitn(3, ' ');
+            let (Some(name), Some(value)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            let Ok(value) = value.parse::<u64>() else {
+                continue;
+            };
+            match name {
+                "keystrokes" => self.keystrokes = value,
+                "words" => self.words = value,
+                "backspaces_avoided" => self.backspaces_avoided = value,
+                "shortcut" => {
+                    if let Some(trigger) = parts.next().filter(|t| !t.is_empty()) {
+                        self.shortcuts.insert(trigger.to_string(), value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Write counters to `path`
+    pub fn save(&self, path: &str) -> bool {
+        fs::write(path, self.to_text()).is_ok()
+    }
+
+    /// Replace counters with those stored at `path`
+    pub fn load(&mut self, path: &str) -> bool {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                self.reset();
+                self.load_text(&text);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_by_default() {
+        let mut s = Stats::new();
+        s.record_key(&Result::send(2, &['a']));
+        s.record_word();
+        s.record_shortcut("vn");
+        assert_eq!(s.keystrokes, 0);
+        assert_eq!(s.words, 0);
+        assert!(s.shortcuts.is_empty());
+    }
+
+    #[test]
+    fn test_counts_and_top_shortcuts() {
+        let mut s = Stats::new();
+        s.set_enabled(true);
+        s.record_key(&Result::none());
+        s.record_key(&Result::send(2, &['á']));
+        s.record_word();
+        for t in ["vn", "hn", "vn"] {
+            s.record_shortcut(t);
+        }
+        assert_eq!(s.keystrokes, 2);
+        assert_eq!(s.backspaces_avoided, 2);
+        assert_eq!(s.words, 1);
+        assert_eq!(s.top_shortcuts(1), vec![("vn", 2)]);
+        assert_eq!(
+            s.to_json(),
+            r#"{"enabled":true,"keystrokes":2,"words":1,"backspaces_avoided":2,"top_shortcuts":[{"trigger":"vn","count":2},{"trigger":"hn","count":1}]}"#
+        );
+    }
+
+    #[test]
+    fn test_text_roundtrip() {
+        let mut s = Stats::new();
+        s.set_enabled(true);
+        s.record_key(&Result::send(1, &['a']));
+        s.record_word();
+        s.record_shortcut("->");
+        s.record_shortcut("a b");
+
+        let mut loaded = Stats::new();
+        loaded.load_text(&s.to_text());
+        assert_eq!(loaded.keystrokes, 1);
+        assert_eq!(loaded.words, 1);
+        assert_eq!(loaded.backspaces_avoided, 1);
+        assert_eq!(loaded.shortcuts.get("a b"), Some(&1));
+        assert_eq!(loaded.shortcuts.get("->"), Some(&1));
+    }
+}
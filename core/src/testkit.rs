@@ -0,0 +1,292 @@
+//! Typing simulator for golden tests against the engine
+//!
+//! A test types a string and compares what the app shows. That needs the
+//! host's side of every key: apply the result, pass the key through when
+//! the engine leaves it, press Left after a cursor marker. Each test file
+//! used to carry its own copy of that loop and of a char → keycode table,
+//! each a little different. `Screen` is the one shared model, for the
+//! crate's tests, platform frontends and anyone testing against the engine.
+//!
+//! Keys come from characters: letters (uppercase with Shift), digits,
+//! punctuation and shifted symbols of a US layout, Space, Tab and Enter
+//! ('\n'). As in the other test helpers, '<' is Backspace; ESC is '\x1b'.
+//! Other keys go through `Screen::press`.
+//!
+//! The app is modelled as storing composed text (one Backspace per
+//! character) and not reacting to Ctrl shortcuts.
+
+use std::sync::OnceLock;
+
+use crate::data::keys;
+use crate::engine::{host_types_key, key_text, Action, Engine, Result, FLAG_CURSOR_MOVE};
+
+/// One key press: keycode and modifiers as hosts pass them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keystroke {
+    pub key: u16,
+    /// Shift XOR CapsLock
+    pub caps: bool,
+    pub shift: bool,
+}
+
+/// Key press typing `c`, None for characters without a key
+pub fn keystroke(c: char) -> Option<Keystroke> {
+    let plain = |key| Keystroke {
+        key,
+        caps: false,
+        shift: false,
+    };
+    match c {
+        '<' => return Some(plain(keys::DELETE)),
+        '\x1b' => return Some(plain(keys::ESC)),
+        _ => {}
+    }
+    let lower = c.to_ascii_lowercase();
+    let &(_, key, shift) = key_table().iter().find(|&&(ch, _, _)| ch == lower)?;
+    let upper = c != lower;
+    Some(Keystroke {
+        key,
+        caps: upper,
+        shift: shift || upper,
+    })
+}
+
+/// (character, key, shift) for every key typing a character, from the
+/// engine's own key tables
+fn key_table() -> &'static [(char, u16, bool)] {
+    static TABLE: OnceLock<Vec<(char, u16, bool)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = Vec::new();
+        for shift in [false, true] {
+            for key in 0..=u8::MAX as u16 {
+                if let Some(c) = key_text(key, false, shift) {
+                    if !table.iter().any(|&(ch, _, _)| ch == c) {
+                        table.push((c, key, shift));
+                    }
+                }
+            }
+        }
+        table
+    })
+}
+
+/// What the app shows, following the engine's results the way a host does
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Screen {
+    text: Vec<char>,
+    /// Characters before the caret
+    caret: usize,
+    /// Backspaces the app received, from keys and results
+    backspaces: usize,
+}
+
+impl Screen {
+    /// Empty app
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// App already showing `text`, caret at the end
+    ///
+    /// The engine knows nothing of it, as after clicking into a document.
+    pub fn with_text(text: &str) -> Self {
+        let text: Vec<char> = text.chars().collect();
+        Self {
+            caret: text.len(),
+            text,
+            backspaces: 0,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Backspaces the app received so far
+    pub fn backspaces(&self) -> usize {
+        self.backspaces
+    }
+
+    /// Type each character of `input` (see the module docs for keys)
+    ///
+    /// Characters without a key are typed by the app directly, as with an
+    /// on-screen keyboard the engine does not see.
+    pub fn type_text(&mut self, e: &mut Engine, input: &str) -> &mut Self {
+        for c in input.chars() {
+            match keystroke(c) {
+                Some(k) => {
+                    self.press(e, k.key, k.caps, false, k.shift);
+                }
+                None => self.insert(&[c]),
+            }
+        }
+        self
+    }
+
+    /// Press a key: `Engine::on_key_ext`, run the word hook on its result,
+    /// as hosts do after each key, then apply it
+    pub fn press(
+        &mut self,
+        e: &mut Engine,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) -> Result {
+        let mut r = e.on_key_ext(key, caps, ctrl, shift);
+        e.run_word_hook(&mut r);
+        self.apply(e, &r, key, caps, ctrl, shift);
+        r
+    }
+
+    /// Auto-repeat of a held key: `Engine::on_key_repeat`, then as `press`
+    pub fn repeat(
+        &mut self,
+        e: &mut Engine,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) -> Result {
+        let mut r = e.on_key_repeat(key, caps, ctrl, shift);
+        e.run_word_hook(&mut r);
+        self.apply(e, &r, key, caps, ctrl, shift);
+        r
+    }
+
+    /// Key typing `ch` in the app's layout: `Engine::on_key_with_char`,
+    /// then apply the result, with `ch` typed when the key passes through
+    pub fn press_char(
+        &mut self,
+        e: &mut Engine,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+        ch: char,
+    ) -> Result {
+        let r = e.on_key_with_char(key, caps, ctrl, shift, Some(ch));
+        if !ctrl {
+            self.follow(e, &r, key, shift, |screen| screen.insert(&[ch]));
+        }
+        r
+    }
+
+    /// Paste `text` (None: the engine is not told what): the app inserts
+    /// it, then `Engine::on_paste`
+    pub fn paste(&mut self, e: &mut Engine, text: Option<&str>) {
+        if let Some(text) = text {
+            self.insert(&text.chars().collect::<Vec<_>>());
+        }
+        e.on_paste(text);
+    }
+
+    /// Undo hotkey: `Engine::undo_last`, then apply the result
+    pub fn undo(&mut self, e: &mut Engine) -> Result {
+        let r = e.undo_last();
+        if r.action != Action::None as u8 {
+            self.follow(e, &r, keys::ESC, false, |_| {});
+        }
+        r
+    }
+
+    /// Apply a result the engine returned for `key`, which the host then
+    /// passes through unless the result consumed it
+    pub fn apply(&mut self, e: &Engine, r: &Result, key: u16, caps: bool, ctrl: bool, shift: bool) {
+        if !ctrl {
+            self.follow(e, r, key, shift, |screen| screen.host_key(key, caps, shift));
+        }
+    }
+
+    /// Apply `r`, calling `host` where the host handles the key itself
+    fn follow(
+        &mut self,
+        e: &Engine,
+        r: &Result,
+        key: u16,
+        shift: bool,
+        host: impl FnOnce(&mut Self),
+    ) {
+        if r.action == Action::None as u8 {
+            if !r.key_consumed() {
+                host(self);
+            }
+            return;
+        }
+        if r.action == Action::Replace as u8 {
+            // Selected and typed over: no Backspace reaches the app
+            let n = (r.backspace as usize).min(self.caret);
+            self.text.drain(self.caret - n..self.caret);
+            self.caret -= n;
+        } else {
+            for _ in 0..r.backspace {
+                self.backspace();
+            }
+        }
+        let chars: Vec<char> = r.chars[..r.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        self.insert(&chars);
+        if host_types_key(r, key, shift) {
+            host(self);
+        }
+        if r.flags & FLAG_CURSOR_MOVE != 0 {
+            self.caret = self.caret.saturating_sub(e.cursor_offset() as usize);
+        }
+    }
+
+    /// What the app does with a key the engine left alone
+    fn host_key(&mut self, key: u16, caps: bool, shift: bool) {
+        match key {
+            keys::DELETE => self.backspace(),
+            keys::LEFT => self.caret = self.caret.saturating_sub(1),
+            keys::RIGHT => self.caret = (self.caret + 1).min(self.text.len()),
+            _ => {
+                if let Some(c) = key_text(key, caps, shift) {
+                    self.insert(&[c]);
+                }
+            }
+        }
+    }
+
+    fn backspace(&mut self) {
+        self.backspaces += 1;
+        if self.caret > 0 {
+            self.caret -= 1;
+            self.text.remove(self.caret);
+        }
+    }
+
+    fn insert(&mut self, chars: &[char]) {
+        self.text
+            .splice(self.caret..self.caret, chars.iter().copied());
+        self.caret += chars.len();
+    }
+}
+
+/// Type `input` into an empty app and return what it shows
+pub fn type_text(e: &mut Engine, input: &str) -> String {
+    Screen::new().type_text(e, input).text()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystrokes_round_trip() {
+        for c in "az09 \t\n.,;'[]-=/\\`!@#$%^&*()_+:\"{}|~>?".chars() {
+            let k = keystroke(c).unwrap();
+            assert_eq!(key_text(k.key, k.caps, k.shift), Some(c), "{:?}", c);
+        }
+        let k = keystroke('V').unwrap();
+        assert_eq!((k.key, k.caps, k.shift), (keys::V, true, true));
+        assert_eq!(keystroke('<').unwrap().key, keys::DELETE);
+        assert_eq!(keystroke('ệ'), None);
+    }
+}
@@ -1,7 +1,27 @@
 //! Auto-update module for GoNhanh
 //!
 //! Provides version comparison utilities that can be used across all platforms.
-//! HTTP calls are handled by the platform layer (Swift/C#/GTK) for flexibility.
+//! HTTP calls are handled by the platform layer (Swift/C#/GTK) for flexibility,
+//! or by `check` when built with the `update-check` feature.
+
+pub mod notes;
+pub mod proxy;
+mod release;
+pub mod schedule;
+
+pub use notes::NoteSection;
+pub use release::{check, latest_release_url, Asset, CheckError, UpdateInfo, DEFAULT_REPO};
+pub use schedule::Schedule;
+
+use std::sync::Mutex;
+
+/// Process-wide check schedule used by the FFI
+static SCHEDULE: Mutex<Option<Schedule>> = Mutex::new(None);
+
+fn with_schedule<T>(f: impl FnOnce(&mut Schedule) -> T) -> T {
+    let mut guard = SCHEDULE.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(Schedule::new))
+}
 
 /// Semantic version representation
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -10,12 +30,22 @@ pub struct Version {
     pub minor: u32,
     pub patch: u32,
     pub pre: Option<String>,
+    /// Build metadata after `+`, ignored when comparing
+    pub build: Option<String>,
 }
 
 impl Version {
-    /// Parse a version string like "1.2.3", "v1.2.3", or "1.2.3-pre.100"
+    /// Parse a version string like "1.2.3", "v1.2.3", "1.2.3-pre.100" or
+    /// "1.2.3+abc"
     pub fn parse(s: &str) -> Option<Version> {
-        let s = s.trim().strip_prefix('v').unwrap_or(s);
+        let s = s.trim();
+        let s = s.strip_prefix('v').unwrap_or(s);
+
+        // Split off build metadata: "1.2.3-rc.1+abc" → ("1.2.3-rc.1", "abc")
+        let (s, build) = match s.split_once('+') {
+            Some((s, build)) => (s, Some(build.to_string())),
+            None => (s, None),
+        };
 
         // Split off pre-release suffix: "1.0.121-pre.368" → ("1.0.121", "pre.368")
         let (version_part, pre) = match s.find('-') {
@@ -37,12 +67,14 @@ impl Version {
             minor,
             patch,
             pre,
+            build,
         })
     }
 
     /// Compare two versions (semver rules)
     /// Returns: -1 if self < other, 0 if equal, 1 if self > other
     /// Pre-release has lower precedence: 1.0.1-pre.1 < 1.0.1
+    /// Build metadata is ignored: 1.0.1+abc == 1.0.1
     pub fn compare(&self, other: &Version) -> i32 {
         if self.major != other.major {
             return if self.major < other.major { -1 } else { 1 };
@@ -58,7 +90,7 @@ impl Version {
             (None, None) => 0,
             (Some(_), None) => -1, // self is pre-release, other is stable → self < other
             (None, Some(_)) => 1,  // self is stable, other is pre-release → self > other
-            (Some(a), Some(b)) => a.cmp(b) as i32, // both pre-release: lexicographic
+            (Some(a), Some(b)) => compare_pre(a, b) as i32,
         }
     }
 
@@ -74,10 +106,47 @@ impl std::fmt::Display for Version {
         if let Some(pre) = &self.pre {
             write!(f, "-{}", pre)?;
         }
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
+        }
         Ok(())
     }
 }
 
+/// Semver pre-release precedence, identifier by identifier
+///
+/// Numeric identifiers compare as numbers ("pre.9" < "pre.10") and rank
+/// below alphanumeric ones ("1" < "alpha"); others compare as ASCII. When
+/// one list is a prefix of the other, the shorter one is lower
+/// ("alpha" < "alpha.1").
+fn compare_pre(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let numeric = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+    loop {
+        let ord = match (a_ids.next(), b_ids.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (numeric(x), numeric(y)) {
+                // No leading zeros in semver, but compare them safely anyway
+                (true, true) => {
+                    let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                    x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+                }
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                (false, false) => x.cmp(y),
+            },
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
 // ============================================================
 // FFI Interface for Version Comparison
 // ============================================================
@@ -138,6 +207,129 @@ pub extern "C" fn version_has_update(current: *const i8, latest: *const i8) -> i
     }
 }
 
+/// Fetch the latest release and compare it with `current`
+///
+/// `repo` is "owner/name", or null for the default repository. Blocking
+/// (10 s timeout), so call it off the UI thread.
+///
+/// # Returns
+/// JSON (free with `ime_free_string`), either the release:
+/// `{"available":true,"version":"1.3.0","tag":"v1.3.0","notes":"...",
+/// "notes_lang":"vi","sections":[{"title":"Có gì mới","items":["..."]}],
+/// "url":"...","assets":[{"name":"...","url":"...","size":123}]}`
+/// or `{"error":"...","code":"..."}`, where `code` is "offline",
+/// "proxy", "network", "status" (with `"status":403`), "invalid_response"
+/// or "unsupported" (built without `update-check`). Offline and proxy
+/// failures do not count as a check, so `update_should_check_now` stays
+/// true and the host retries on its next timer tick.
+/// `sections` is the notes as plain text, the Vietnamese part when the
+/// notes have one (`notes_lang` "vi").
+/// Null if `current` is null or not a version.
+///
+/// # Safety
+/// Callers must ensure `current` and `repo` are valid null-terminated C
+/// strings or null pointers.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn update_check(current: *const i8, repo: *const i8) -> *mut i8 {
+    let read = |p: *const i8| {
+        if p.is_null() {
+            return None;
+        }
+        unsafe { std::ffi::CStr::from_ptr(p) }.to_str().ok()
+    };
+    let Some(current) = read(current).and_then(Version::parse) else {
+        return std::ptr::null_mut();
+    };
+    let result = check(read(repo).unwrap_or(DEFAULT_REPO));
+    // The server answered: count it against the schedule
+    if matches!(result, Ok(_) | Err(CheckError::Status(_))) {
+        with_schedule(|s| s.record_check(schedule::now()));
+    }
+    let json = match result {
+        Ok(info) => info.to_json(&current),
+        Err(err) => err.to_json(),
+    };
+    crate::into_c_string(json)
+}
+
+/// Configure update check scheduling
+///
+/// `path` is the file the last-check time is kept in (e.g. next to the
+/// settings), or null to keep it in memory only. A check is due
+/// `interval_secs` after the last one plus a random delay of up to
+/// `jitter_secs`; 0 for either uses the default (1 day, 1 hour).
+///
+/// Returns false if `path` exists but cannot be read.
+///
+/// # Safety
+/// Callers must ensure `path` is a valid null-terminated C string or null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn update_schedule(path: *const i8, interval_secs: u64, jitter_secs: u64) -> bool {
+    let path = if path.is_null() {
+        None
+    } else {
+        match unsafe { std::ffi::CStr::from_ptr(path) }.to_str() {
+            Ok(s) => Some(s),
+            Err(_) => return false,
+        }
+    };
+    with_schedule(|s| {
+        s.interval = match interval_secs {
+            0 => schedule::DEFAULT_INTERVAL_SECS,
+            secs => secs,
+        };
+        s.jitter = match jitter_secs {
+            0 => schedule::DEFAULT_JITTER_SECS,
+            secs => secs,
+        };
+        path.is_none_or(|p| s.set_path(p))
+    })
+}
+
+/// Set the proxy for update requests
+///
+/// Null uses the environment (`HTTPS_PROXY`, `ALL_PROXY`, `HTTP_PROXY`,
+/// `NO_PROXY`), the default; "" connects directly; anything else is the
+/// proxy URL, e.g. the system proxy the host read from the OS settings.
+///
+/// # Safety
+/// Callers must ensure `url` is a valid null-terminated C string or null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn update_proxy(url: *const i8) -> bool {
+    let setting = if url.is_null() {
+        proxy::ProxySetting::Environment
+    } else {
+        match unsafe { std::ffi::CStr::from_ptr(url) }.to_str() {
+            Ok("") => proxy::ProxySetting::Direct,
+            Ok(url) => proxy::ProxySetting::Url(url.to_string()),
+            Err(_) => return false,
+        }
+    };
+    proxy::set(setting);
+    true
+}
+
+/// Whether an update check is due now
+///
+/// Call on launch and on a timer; `update_check` records each check that
+/// reached the server. Never due when the managed config turns network
+/// features off.
+#[no_mangle]
+pub extern "C" fn update_should_check_now() -> bool {
+    crate::managed::network_allowed() && with_schedule(|s| s.should_check(schedule::now()))
+}
+
+/// Record an update check done by the host's own HTTP client
+///
+/// Returns false if the schedule file could not be written.
+#[no_mangle]
+pub extern "C" fn update_record_check() -> bool {
+    with_schedule(|s| s.record_check(schedule::now()))
+}
+
 // ============================================================
 // Tests
 // ============================================================
@@ -204,6 +396,46 @@ mod tests {
         assert!(!current.has_update(&latest)); // no update, current is higher
     }
 
+    #[test]
+    fn test_prerelease_precedence() {
+        // Example chain from semver.org, plus numeric ordering
+        let chain = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+        for pair in chain.windows(2) {
+            let a = Version::parse(pair[0]).unwrap();
+            let b = Version::parse(pair[1]).unwrap();
+            assert_eq!(a.compare(&b), -1, "{} < {}", pair[0], pair[1]);
+            assert_eq!(b.compare(&a), 1, "{} > {}", pair[1], pair[0]);
+        }
+
+        let pre9 = Version::parse("1.0.121-pre.9").unwrap();
+        let pre10 = Version::parse("1.0.121-pre.10").unwrap();
+        assert!(pre9.has_update(&pre10));
+        assert!(!pre10.has_update(&pre9));
+    }
+
+    #[test]
+    fn test_build_metadata_ignored() {
+        let v = Version::parse("v1.2.3-rc.1+build.5").unwrap();
+        assert_eq!(v.pre, Some("rc.1".to_string()));
+        assert_eq!(v.build, Some("build.5".to_string()));
+        assert_eq!(v.to_string(), "1.2.3-rc.1+build.5");
+
+        let plain = Version::parse("1.2.3+abc").unwrap();
+        let other = Version::parse("1.2.3+def").unwrap();
+        assert_eq!(plain.compare(&other), 0);
+        assert_eq!(plain.compare(&Version::parse("1.2.3").unwrap()), 0);
+        assert_eq!(v.compare(&plain), -1);
+    }
+
     #[test]
     fn test_has_update() {
         let current = Version::parse("1.0.9").unwrap();
@@ -237,6 +469,22 @@ mod tests {
         assert_eq!(version_has_update(latest.as_ptr(), current.as_ptr()), 0);
     }
 
+    #[test]
+    fn test_ffi_update_check_rejects_bad_version() {
+        use std::ffi::CString;
+
+        let bad = CString::new("latest").unwrap();
+        assert!(update_check(bad.as_ptr(), std::ptr::null()).is_null());
+        assert!(update_check(std::ptr::null(), std::ptr::null()).is_null());
+    }
+
+    #[test]
+    fn test_ffi_schedule() {
+        assert!(update_schedule(std::ptr::null(), 3600, 1));
+        assert!(update_record_check());
+        assert!(!update_should_check_now());
+    }
+
     #[test]
     fn test_ffi_prerelease_no_downgrade() {
         use std::ffi::CString;
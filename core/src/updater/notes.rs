@@ -0,0 +1,297 @@
+//! Release notes: Markdown → plain sections
+//!
+//! GitHub release bodies are Markdown. The update dialog only needs headed
+//! lists of plain text, so headings become section titles and list items
+//! and paragraphs become items, with inline formatting, links and HTML
+//! stripped.
+//!
+//! Bilingual notes put each language under its own heading ("## Tiếng
+//! Việt", "## English", or a flag emoji); `sections` picks the requested
+//! language's part when there is one.
+
+/// A titled group of plain-text items
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteSection {
+    /// Heading text, empty for text before the first heading
+    pub title: String,
+    pub items: Vec<String>,
+}
+
+/// Language headings: (lowercase heading text, language code)
+const LANGUAGE_HEADINGS: &[(&str, &str)] = &[
+    ("tiếng việt", "vi"),
+    ("vietnamese", "vi"),
+    ("🇻🇳", "vi"),
+    ("english", "en"),
+    ("🇬🇧", "en"),
+    ("🇺🇸", "en"),
+];
+
+/// A heading or a plain-text block, in document order
+enum Block {
+    Heading(usize, String),
+    Item(String),
+}
+
+/// Plain sections of `markdown`, limited to language `lang` ("vi", "en")
+/// when the notes have a heading for it
+///
+/// Otherwise the whole document is returned. Headings without items are
+/// dropped.
+pub fn sections(markdown: &str, lang: &str) -> Vec<NoteSection> {
+    let blocks = blocks(markdown);
+    let start = blocks
+        .iter()
+        .position(|b| matches!(b, Block::Heading(_, text) if heading_language(text) == Some(lang)));
+    let selected = match start {
+        Some(i) => {
+            let Block::Heading(level, _) = blocks[i] else {
+                unreachable!()
+            };
+            let end = blocks[i + 1..]
+                .iter()
+                .position(|b| matches!(b, Block::Heading(l, _) if *l <= level))
+                .map_or(blocks.len(), |n| i + 1 + n);
+            &blocks[i + 1..end]
+        }
+        None => &blocks[..],
+    };
+    group(selected)
+}
+
+/// Whether the notes have a heading for language `lang`
+pub fn has_language(markdown: &str, lang: &str) -> bool {
+    blocks(markdown)
+        .iter()
+        .any(|b| matches!(b, Block::Heading(_, text) if heading_language(text) == Some(lang)))
+}
+
+fn heading_language(text: &str) -> Option<&'static str> {
+    let text = text.to_lowercase();
+    let text = text.trim_matches(|c: char| !c.is_alphanumeric() && !is_flag(c));
+    LANGUAGE_HEADINGS
+        .iter()
+        .find(|(name, _)| text == *name || text.starts_with(&format!("{} ", name)))
+        .map(|&(_, lang)| lang)
+}
+
+/// Regional indicator symbols (flag emoji halves)
+fn is_flag(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+fn group(blocks: &[Block]) -> Vec<NoteSection> {
+    let mut out: Vec<NoteSection> = Vec::new();
+    let mut current = NoteSection {
+        title: String::new(),
+        items: Vec::new(),
+    };
+    for block in blocks {
+        match block {
+            Block::Heading(_, title) => {
+                let done = std::mem::replace(
+                    &mut current,
+                    NoteSection {
+                        title: title.clone(),
+                        items: Vec::new(),
+                    },
+                );
+                if !done.items.is_empty() {
+                    out.push(done);
+                }
+            }
+            Block::Item(text) => current.items.push(text.clone()),
+        }
+    }
+    if !current.items.is_empty() {
+        out.push(current);
+    }
+    out
+}
+
+fn blocks(markdown: &str) -> Vec<Block> {
+    let mut out = Vec::new();
+    let mut in_code = false;
+    // Last block is a paragraph or item that a plain next line continues
+    let mut open = false;
+    for raw in markdown.lines() {
+        let line = raw.trim();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_code = !in_code;
+            open = false;
+            continue;
+        }
+        if in_code {
+            if !line.is_empty() {
+                out.push(Block::Item(line.to_string()));
+            }
+            continue;
+        }
+        let line = line.trim_start_matches('>').trim();
+        if is_rule(line) {
+            open = false;
+            continue;
+        }
+        if let Some((level, text)) = heading(line) {
+            out.push(Block::Heading(level, strip_inline(text).trim().to_string()));
+            open = false;
+            continue;
+        }
+        let item = list_item(line);
+        let text = strip_inline(item.unwrap_or(line));
+        let text = text.trim();
+        if text.is_empty() {
+            open = false;
+            continue;
+        }
+        match out.last_mut() {
+            Some(Block::Item(prev)) if open && item.is_none() => {
+                prev.push(' ');
+                prev.push_str(text);
+            }
+            _ => {
+                out.push(Block::Item(text.to_string()));
+                open = true;
+            }
+        }
+    }
+    out
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let text = line[level..].strip_prefix(' ')?;
+    Some((level, text.trim().trim_end_matches('#').trim()))
+}
+
+fn is_rule(line: &str) -> bool {
+    line.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&c| line.chars().all(|x| x == c || x == ' '))
+}
+
+/// Text of a "- ", "* ", "+ " or "1. " item
+fn list_item(line: &str) -> Option<&str> {
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(marker) {
+            return Some(text.trim_start_matches("[ ] ").trim_start_matches("[x] "));
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(text) = line[digits..]
+            .strip_prefix(". ")
+            .or_else(|| line[digits..].strip_prefix(") "))
+        {
+            return Some(text);
+        }
+    }
+    None
+}
+
+/// Remove emphasis, code spans, links, images and HTML tags
+fn strip_inline(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '*' | '`' => {}
+            '_' if chars.get(i + 1) == Some(&'_') => i += 1,
+            '!' if chars.get(i + 1) == Some(&'[') => {}
+            '[' => {
+                // [text](url) → text
+                if let Some(close) = find(&chars, i + 1, ']') {
+                    if chars.get(close + 1) == Some(&'(') {
+                        if let Some(end) = find(&chars, close + 2, ')') {
+                            out.extend(&chars[i + 1..close]);
+                            i = end + 1;
+                            continue;
+                        }
+                    }
+                }
+                out.push(c);
+            }
+            '<' => match find(&chars, i + 1, '>') {
+                Some(end)
+                    if chars
+                        .get(i + 1)
+                        .is_some_and(|&n| n.is_ascii_alphabetic() || n == '/' || n == '!') =>
+                {
+                    i = end + 1;
+                    continue;
+                }
+                _ => out.push(c),
+            },
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+fn find(chars: &[char], from: usize, target: char) -> Option<usize> {
+    chars
+        .get(from..)?
+        .iter()
+        .position(|&c| c == target)
+        .map(|n| from + n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BILINGUAL: &str = "\
+## 🇻🇳 Tiếng Việt
+
+### Có gì mới
+- Thêm **gõ tắt** theo ứng dụng
+- Sửa lỗi `uo` → [ươ](https://example.com/1)
+  khi gõ nhanh
+
+### Sửa lỗi
+1. Không còn mất chữ trong Discord
+
+## English
+
+### What's new
+- Per-app **shortcuts**
+";
+
+    fn titles(sections: &[NoteSection]) -> Vec<&str> {
+        sections.iter().map(|s| s.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_picks_vietnamese() {
+        let vi = sections(BILINGUAL, "vi");
+        assert_eq!(titles(&vi), ["Có gì mới", "Sửa lỗi"]);
+        assert_eq!(
+            vi[0].items,
+            ["Thêm gõ tắt theo ứng dụng", "Sửa lỗi uo → ươ khi gõ nhanh"]
+        );
+        assert_eq!(vi[1].items, ["Không còn mất chữ trong Discord"]);
+
+        let en = sections(BILINGUAL, "en");
+        assert_eq!(titles(&en), ["What's new"]);
+        assert_eq!(en[0].items, ["Per-app shortcuts"]);
+        assert!(has_language(BILINGUAL, "vi"));
+    }
+
+    #[test]
+    fn test_single_language_notes() {
+        let notes = "Bản vá nhỏ.\n\n<!-- hidden -->\n---\n## Changes\r\n* Fix crash\r\n* <b>Faster</b> start\r\n";
+        let all = sections(notes, "vi");
+        assert_eq!(titles(&all), ["", "Changes"]);
+        assert_eq!(all[0].items, ["Bản vá nhỏ."]);
+        assert_eq!(all[1].items, ["Fix crash", "Faster start"]);
+        assert!(!has_language(notes, "vi"));
+        assert!(sections("", "vi").is_empty());
+    }
+}
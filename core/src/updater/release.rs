@@ -0,0 +1,358 @@
+//! Latest-release lookup on GitHub
+//!
+//! `check` asks the releases API for the newest published release and
+//! returns it as an `UpdateInfo`. The HTTPS request needs the
+//! `update-check` cargo feature; without it `check` returns
+//! `CheckError::Unsupported` and hosts keep fetching themselves, feeding
+//! the response to `UpdateInfo::from_github_json`.
+
+use super::notes::{self, NoteSection};
+use super::Version;
+use crate::json::{self, Value};
+use crate::utils::json_escape;
+
+/// Repository whose releases are checked by default
+pub const DEFAULT_REPO: &str = "miken90/fkey";
+
+/// A downloadable file attached to a release
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub name: String,
+    /// Direct download URL (`browser_download_url`)
+    pub url: String,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// The latest published release
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateInfo {
+    pub version: Version,
+    /// Git tag as published ("v1.2.0")
+    pub tag: String,
+    /// Release notes (Markdown)
+    pub notes: String,
+    /// Release page
+    pub page_url: String,
+    pub assets: Vec<Asset>,
+}
+
+impl UpdateInfo {
+    /// Read a GitHub "get release" response
+    ///
+    /// None if it is not valid JSON, is a draft, or its tag is not a
+    /// version.
+    pub fn from_github_json(text: &str) -> Option<Self> {
+        let doc = json::parse(text)?;
+        if doc.get("draft").and_then(Value::as_bool) == Some(true) {
+            return None;
+        }
+        let field = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).map(str::to_string);
+        let tag = field(&doc, "tag_name")?;
+        let assets = doc
+            .get("assets")
+            .and_then(Value::as_array)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|a| {
+                Some(Asset {
+                    name: field(a, "name")?,
+                    url: field(a, "browser_download_url")?,
+                    size: a.get("size").and_then(Value::as_u64).unwrap_or(0),
+                })
+            })
+            .collect();
+        Some(Self {
+            version: Version::parse(&tag)?,
+            tag,
+            notes: field(&doc, "body").unwrap_or_default(),
+            page_url: field(&doc, "html_url").unwrap_or_default(),
+            assets,
+        })
+    }
+
+    /// Whether this release is newer than `current`
+    pub fn is_newer_than(&self, current: &Version) -> bool {
+        current.has_update(&self.version)
+    }
+
+    /// Release notes as plain sections, in language `lang` ("vi", "en")
+    /// when the notes have a part for it (see `notes::sections`)
+    pub fn sections(&self, lang: &str) -> Vec<NoteSection> {
+        notes::sections(&self.notes, lang)
+    }
+
+    /// First asset whose name ends with `suffix` ("-portable.zip")
+    pub fn asset(&self, suffix: &str) -> Option<&Asset> {
+        self.assets.iter().find(|a| a.name.ends_with(suffix))
+    }
+
+    /// JSON for the host, with `available` relative to `current`
+    ///
+    /// `sections` holds the Vietnamese part of the notes when there is one
+    /// (`notes_lang` "vi"), else all of them (`notes_lang` "").
+    pub fn to_json(&self, current: &Version) -> String {
+        let quoted = |s: &str| format!("\"{}\"", json_escape(s));
+        let sections: Vec<String> = self
+            .sections("vi")
+            .iter()
+            .map(|s| {
+                let items: Vec<String> = s.items.iter().map(|i| quoted(i)).collect();
+                format!(
+                    "{{\"title\":{},\"items\":[{}]}}",
+                    quoted(&s.title),
+                    items.join(",")
+                )
+            })
+            .collect();
+        let notes_lang = if notes::has_language(&self.notes, "vi") {
+            "vi"
+        } else {
+            ""
+        };
+        let assets: Vec<String> = self
+            .assets
+            .iter()
+            .map(|a| {
+                format!(
+                    "{{\"name\":\"{}\",\"url\":\"{}\",\"size\":{}}}",
+                    json_escape(&a.name),
+                    json_escape(&a.url),
+                    a.size
+                )
+            })
+            .collect();
+        format!(
+            concat!(
+                "{{\"available\":{},\"version\":\"{}\",\"tag\":\"{}\",",
+                "\"notes\":\"{}\",\"notes_lang\":\"{}\",\"sections\":[{}],",
+                "\"url\":\"{}\",\"assets\":[{}]}}"
+            ),
+            self.is_newer_than(current),
+            self.version,
+            json_escape(&self.tag),
+            json_escape(&self.notes),
+            notes_lang,
+            sections.join(","),
+            json_escape(&self.page_url),
+            assets.join(",")
+        )
+    }
+}
+
+/// Why a release check failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckError {
+    /// Built without the `update-check` feature
+    Unsupported,
+    /// Network features turned off by the managed config
+    Disabled,
+    /// Server not reachable (no network, DNS failure, connection refused)
+    Offline,
+    /// Proxy URL invalid, unreachable or refusing the credentials
+    Proxy(String),
+    /// TLS, timeout or other transfer failure
+    Network(String),
+    /// Non-success HTTP status (403: rate limited, 404: no release)
+    Status(u16),
+    /// Response was not a usable release
+    InvalidResponse,
+}
+
+impl std::fmt::Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckError::Unsupported => write!(f, "built without update-check"),
+            CheckError::Disabled => write!(f, "disabled by managed config"),
+            CheckError::Offline => write!(f, "offline"),
+            CheckError::Proxy(msg) => write!(f, "proxy error: {}", msg),
+            CheckError::Network(msg) => write!(f, "network error: {}", msg),
+            CheckError::Status(code) => write!(f, "HTTP {}", code),
+            CheckError::InvalidResponse => write!(f, "invalid release response"),
+        }
+    }
+}
+
+impl CheckError {
+    /// Stable identifier for hosts ("offline", "proxy", "status", ...)
+    pub fn code(&self) -> &'static str {
+        match self {
+            CheckError::Unsupported => "unsupported",
+            CheckError::Disabled => "disabled",
+            CheckError::Offline => "offline",
+            CheckError::Proxy(_) => "proxy",
+            CheckError::Network(_) => "network",
+            CheckError::Status(_) => "status",
+            CheckError::InvalidResponse => "invalid_response",
+        }
+    }
+
+    /// JSON for the host: `{"error":"...","code":"offline"}`, plus
+    /// `"status"` for HTTP errors
+    pub fn to_json(&self) -> String {
+        let status = match self {
+            CheckError::Status(code) => format!(",\"status\":{}", code),
+            _ => String::new(),
+        };
+        format!(
+            "{{\"error\":\"{}\",\"code\":\"{}\"{}}}",
+            json_escape(&self.to_string()),
+            self.code(),
+            status
+        )
+    }
+}
+
+/// Host part of an http(s) URL
+#[cfg_attr(not(feature = "update-check"), allow(dead_code))]
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let authority = authority.rsplit('@').next().unwrap_or(authority);
+    authority.split(':').next().unwrap_or(authority)
+}
+
+/// Releases API URL of the latest release of `repo` ("owner/name")
+pub fn latest_release_url(repo: &str) -> String {
+    format!("https://api.github.com/repos/{}/releases/latest", repo)
+}
+
+/// Fetch the latest release of `repo` ("owner/name")
+///
+/// Blocking, with a 10 s timeout; call it off the UI thread. Goes through
+/// the proxy from `proxy::for_host`. Refused when the managed config turns
+/// network features off.
+pub fn check(repo: &str) -> Result<UpdateInfo, CheckError> {
+    if !crate::managed::network_allowed() {
+        return Err(CheckError::Disabled);
+    }
+    let body = fetch(&latest_release_url(repo))?;
+    UpdateInfo::from_github_json(&body).ok_or(CheckError::InvalidResponse)
+}
+
+#[cfg(feature = "update-check")]
+fn fetch(url: &str) -> Result<String, CheckError> {
+    use std::time::Duration;
+
+    let mut builder = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .user_agent(concat!("gonhanh-core/", env!("CARGO_PKG_VERSION")))
+        .try_proxy_from_env(false);
+    if let Some(proxy) = super::proxy::for_host(url_host(url)) {
+        let proxy = ureq::Proxy::new(&proxy).map_err(|err| CheckError::Proxy(err.to_string()))?;
+        builder = builder.proxy(proxy);
+    }
+    let response = builder
+        .build()
+        .get(url)
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .map_err(|err| match err {
+            ureq::Error::Status(code, _) => CheckError::Status(code),
+            ureq::Error::Transport(t) => match t.kind() {
+                ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed => CheckError::Offline,
+                ureq::ErrorKind::InvalidProxyUrl
+                | ureq::ErrorKind::ProxyConnect
+                | ureq::ErrorKind::ProxyUnauthorized => CheckError::Proxy(t.to_string()),
+                _ => CheckError::Network(t.to_string()),
+            },
+        })?;
+    response
+        .into_string()
+        .map_err(|err| CheckError::Network(err.to_string()))
+}
+
+#[cfg(not(feature = "update-check"))]
+fn fetch(_url: &str) -> Result<String, CheckError> {
+    Err(CheckError::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE: &str = r###"{
+        "tag_name": "v1.3.0",
+        "html_url": "https://github.com/miken90/fkey/releases/tag/v1.3.0",
+        "draft": false,
+        "prerelease": false,
+        "body": "## Có gì mới\r\n- Sửa lỗi \"gõ nhanh\"",
+        "assets": [
+            {"name": "FKey-v1.3.0-portable.zip", "size": 5242880,
+             "browser_download_url": "https://github.com/miken90/fkey/releases/download/v1.3.0/FKey-v1.3.0-portable.zip"},
+            {"name": "broken"}
+        ]
+    }"###;
+
+    #[test]
+    fn test_from_github_json() {
+        let info = UpdateInfo::from_github_json(RELEASE).unwrap();
+        assert_eq!(info.version, Version::parse("1.3.0").unwrap());
+        assert_eq!(info.tag, "v1.3.0");
+        assert!(info.notes.starts_with("## Có gì mới"));
+        assert_eq!(info.assets.len(), 1);
+        let zip = info.asset("-portable.zip").unwrap();
+        assert_eq!(zip.size, 5242880);
+        assert!(zip.url.ends_with("FKey-v1.3.0-portable.zip"));
+
+        assert!(info.is_newer_than(&Version::parse("1.2.9").unwrap()));
+        assert!(!info.is_newer_than(&Version::parse("1.3.0").unwrap()));
+
+        let draft = RELEASE.replace("\"draft\": false", "\"draft\": true");
+        assert_eq!(UpdateInfo::from_github_json(&draft), None);
+        assert_eq!(
+            UpdateInfo::from_github_json("{\"tag_name\":\"nightly\"}"),
+            None
+        );
+        assert_eq!(UpdateInfo::from_github_json("<html>"), None);
+    }
+
+    #[test]
+    fn test_to_json_round_trips() {
+        let info = UpdateInfo::from_github_json(RELEASE).unwrap();
+        let out = json::parse(&info.to_json(&Version::parse("1.2.0").unwrap())).unwrap();
+        assert_eq!(out.get("available").and_then(Value::as_bool), Some(true));
+        assert_eq!(out.get("version").and_then(Value::as_str), Some("1.3.0"));
+        assert_eq!(
+            out.get("notes").and_then(Value::as_str),
+            Some(info.notes.as_str())
+        );
+        assert_eq!(out.get("notes_lang").and_then(Value::as_str), Some(""));
+        let sections = out.get("sections").and_then(Value::as_array).unwrap();
+        assert_eq!(
+            sections[0].get("title").and_then(Value::as_str),
+            Some("Có gì mới")
+        );
+        let assets = out.get("assets").and_then(Value::as_array).unwrap();
+        assert_eq!(assets[0].get("size").and_then(Value::as_u64), Some(5242880));
+    }
+
+    #[test]
+    fn test_error_json() {
+        let out = json::parse(&CheckError::Status(403).to_json()).unwrap();
+        assert_eq!(out.get("code").and_then(Value::as_str), Some("status"));
+        assert_eq!(out.get("status").and_then(Value::as_u64), Some(403));
+        assert_eq!(out.get("error").and_then(Value::as_str), Some("HTTP 403"));
+        let out = json::parse(&CheckError::Offline.to_json()).unwrap();
+        assert_eq!(out.get("code").and_then(Value::as_str), Some("offline"));
+        assert_eq!(out.get("status"), None);
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host(&latest_release_url(DEFAULT_REPO)),
+            "api.github.com"
+        );
+        assert_eq!(
+            url_host("http://user:pw@example.com:8080/x?y"),
+            "example.com"
+        );
+    }
+
+    #[cfg(not(feature = "update-check"))]
+    #[test]
+    fn test_check_needs_feature() {
+        assert_eq!(check(DEFAULT_REPO), Err(CheckError::Unsupported));
+    }
+}
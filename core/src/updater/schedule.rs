@@ -0,0 +1,220 @@
+//! Update check scheduling
+//!
+//! Frontends ask `should_check` on launch and on a timer instead of calling
+//! the releases API every time. A check is due `interval` seconds after the
+//! last one plus a per-check random delay of up to `jitter` seconds, so
+//! installs started together (e.g. at login) spread out their requests.
+//!
+//! The last-check time is persisted as `name value` lines in a file the
+//! host chooses (next to its settings):
+//!
+//! ```text
+//! last_checked 1760600000
+//! jitter_offset 1234
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default time between checks: one day
+pub const DEFAULT_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// Default random extra delay: up to one hour
+pub const DEFAULT_JITTER_SECS: u64 = 60 * 60;
+
+/// When the next update check is due
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    /// Seconds between checks
+    pub interval: u64,
+    /// Upper bound of the random extra delay, in seconds
+    pub jitter: u64,
+    /// Unix time of the last check, None if never checked
+    last_checked: Option<u64>,
+    /// Random extra delay picked at the last check
+    jitter_offset: u64,
+    /// File the schedule is saved to after each check
+    path: Option<String>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_INTERVAL_SECS,
+            jitter: DEFAULT_JITTER_SECS,
+            last_checked: None,
+            jitter_offset: 0,
+            path: None,
+        }
+    }
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Unix time of the last check
+    pub fn last_checked(&self) -> Option<u64> {
+        self.last_checked
+    }
+
+    /// Unix time the next check is due (None: right away)
+    pub fn next_check(&self) -> Option<u64> {
+        let last = self.last_checked?;
+        Some(
+            last.saturating_add(self.interval)
+                .saturating_add(self.jitter_offset.min(self.jitter)),
+        )
+    }
+
+    /// Whether a check is due at `now` (Unix seconds)
+    ///
+    /// Also true when the clock is earlier than the last check, so a clock
+    /// set far ahead once cannot block checks for good.
+    pub fn should_check(&self, now: u64) -> bool {
+        match (self.last_checked, self.next_check()) {
+            (Some(last), Some(next)) => now >= next || now < last,
+            _ => true,
+        }
+    }
+
+    /// Record a check at `now` and pick the next random delay
+    ///
+    /// Saved to the schedule file when one is set; returns false if that
+    /// write failed.
+    pub fn record_check(&mut self, now: u64) -> bool {
+        self.last_checked = Some(now);
+        self.jitter_offset = match self.jitter {
+            0 => 0,
+            jitter => random_u64() % (jitter + 1),
+        };
+        match &self.path {
+            Some(path) => self.save(path),
+            None => true,
+        }
+    }
+
+    /// Save after every `record_check` to `path`, loading it now
+    ///
+    /// A missing file is a first launch, not an error; returns false only
+    /// if the file exists but cannot be read.
+    pub fn set_path(&mut self, path: &str) -> bool {
+        self.path = Some(path.to_string());
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                self.load_text(&text);
+                true
+            }
+            Err(err) => err.kind() == std::io::ErrorKind::NotFound,
+        }
+    }
+
+    /// Serialize as `name value` lines
+    pub fn to_text(&self) -> String {
+        match self.last_checked {
+            Some(last) => format!(
+                "last_checked {}\njitter_offset {}\n",
+                last, self.jitter_offset
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// Read `to_text()` output (unknown lines are ignored)
+    pub fn load_text(&mut self, text: &str) {
+        for line in text.lines() {
+            let Some((name, value)) = line.split_once(' ') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<u64>() else {
+                continue;
+            };
+            match name {
+                "last_checked" => self.last_checked = Some(value),
+                "jitter_offset" => self.jitter_offset = value,
+                _ => {}
+            }
+        }
+    }
+
+    /// Write the schedule to `path`
+    pub fn save(&self, path: &str) -> bool {
+        fs::write(path, self.to_text()).is_ok()
+    }
+}
+
+/// Current Unix time in seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Random number from the std hasher's per-process random keys
+fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(now());
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_after_interval_and_jitter() {
+        let mut s = Schedule::new();
+        s.interval = 100;
+        s.jitter = 10;
+        assert!(s.should_check(1_000));
+        s.record_check(1_000);
+        let next = s.next_check().unwrap();
+        assert!((1_100..=1_110).contains(&next));
+        assert!(!s.should_check(1_050));
+        assert!(!s.should_check(next - 1));
+        assert!(s.should_check(next));
+        // Clock moved back before the last check
+        assert!(s.should_check(900));
+    }
+
+    #[test]
+    fn test_no_jitter() {
+        let mut s = Schedule::new();
+        s.interval = 60;
+        s.jitter = 0;
+        s.record_check(500);
+        assert_eq!(s.next_check(), Some(560));
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut s = Schedule::new();
+        assert_eq!(s.to_text(), "");
+        s.record_check(1_760_600_000);
+        let mut loaded = Schedule::new();
+        loaded.load_text(&s.to_text());
+        assert_eq!(loaded.last_checked(), Some(1_760_600_000));
+        assert_eq!(loaded.next_check(), s.next_check());
+        loaded.load_text("garbage\nlast_checked soon\n");
+        assert_eq!(loaded.last_checked(), Some(1_760_600_000));
+    }
+
+    #[test]
+    fn test_path_persists_checks() {
+        let path = std::env::temp_dir().join(format!("gonhanh-schedule-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = fs::remove_file(path);
+
+        let mut s = Schedule::new();
+        assert!(s.set_path(path)); // missing file: first launch
+        assert!(s.record_check(42));
+
+        let mut reloaded = Schedule::new();
+        assert!(reloaded.set_path(path));
+        assert_eq!(reloaded.last_checked(), Some(42));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -4,53 +4,15 @@
 //! Also includes test utilities under #[cfg(test)].
 
 use crate::data::{
-    chars::tone,
+    chars::{self, tone},
     keys,
     vowel::{Modifier, Vowel},
 };
-use crate::engine::buffer::Buffer;
+use crate::engine::buffer::{Buffer, StackVec, MAX};
 
 /// Convert key code to character
 pub fn key_to_char(key: u16, caps: bool) -> Option<char> {
-    let ch = match key {
-        keys::A => 'a',
-        keys::B => 'b',
-        keys::C => 'c',
-        keys::D => 'd',
-        keys::E => 'e',
-        keys::F => 'f',
-        keys::G => 'g',
-        keys::H => 'h',
-        keys::I => 'i',
-        keys::J => 'j',
-        keys::K => 'k',
-        keys::L => 'l',
-        keys::M => 'm',
-        keys::N => 'n',
-        keys::O => 'o',
-        keys::P => 'p',
-        keys::Q => 'q',
-        keys::R => 'r',
-        keys::S => 's',
-        keys::T => 't',
-        keys::U => 'u',
-        keys::V => 'v',
-        keys::W => 'w',
-        keys::X => 'x',
-        keys::Y => 'y',
-        keys::Z => 'z',
-        keys::N0 => return Some('0'),
-        keys::N1 => return Some('1'),
-        keys::N2 => return Some('2'),
-        keys::N3 => return Some('3'),
-        keys::N4 => return Some('4'),
-        keys::N5 => return Some('5'),
-        keys::N6 => return Some('6'),
-        keys::N7 => return Some('7'),
-        keys::N8 => return Some('8'),
-        keys::N9 => return Some('9'),
-        _ => return None,
-    };
+    let ch = keys::base_char(key)?;
     Some(if caps { ch.to_ascii_uppercase() } else { ch })
 }
 
@@ -59,36 +21,15 @@ pub fn key_to_char(key: u16, caps: bool) -> Option<char> {
 pub fn key_to_char_ext(key: u16, caps: bool, shift: bool) -> Option<char> {
     // If shift is pressed, check for shifted symbols first
     if shift {
-        return match key {
-            keys::N1 => Some('!'),
-            keys::N2 => Some('@'),
-            keys::N3 => Some('#'),
-            keys::N4 => Some('$'),
-            keys::N5 => Some('%'),
-            keys::N6 => Some('^'),
-            keys::N7 => Some('&'),
-            keys::N8 => Some('*'),
-            keys::N9 => Some('('),
-            keys::N0 => Some(')'),
-            keys::MINUS => Some('_'),
-            keys::EQUAL => Some('+'),
-            keys::SEMICOLON => Some(':'),
-            keys::QUOTE => Some('"'),
-            keys::COMMA => Some('<'),
-            keys::DOT => Some('>'),
-            keys::SLASH => Some('?'),
-            keys::BACKSLASH => Some('|'),
-            keys::LBRACKET => Some('{'),
-            keys::RBRACKET => Some('}'),
-            keys::BACKQUOTE => Some('~'),
-            _ => key_to_char(key, caps),
-        };
+        if let Some(symbol) = keys::shifted_char(key) {
+            return Some(symbol);
+        }
     }
     key_to_char(key, caps)
 }
 
 /// Collect vowels from buffer with phonological info
-pub fn collect_vowels(buf: &Buffer) -> Vec<Vowel> {
+pub fn collect_vowels(buf: &Buffer) -> StackVec<Vowel, MAX> {
     buf.iter()
         .enumerate()
         .filter(|(_, c)| keys::is_vowel(c.key))
@@ -139,6 +80,39 @@ pub fn has_gi_initial(buf: &Buffer) -> bool {
         && third.map(keys::is_vowel).unwrap_or(false)
 }
 
+/// Escape a string for embedding in a JSON string literal (without quotes)
+pub fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Strip Vietnamese diacritics, leaving ASCII letters
+///
+/// "Nguyễn Văn A" → "Nguyen Van A", "Đà Nẵng" → "Da Nang": for filenames,
+/// slugs and bank transfer fields. Decomposed marks (U+0300–U+036F) are
+/// dropped too; other characters are kept.
+pub fn strip_diacritics(text: &str) -> String {
+    text.chars()
+        .filter(|c| !('\u{300}'..='\u{36f}').contains(c))
+        .map(|c| match chars::parse_char(c) {
+            Some(p) if p.key == keys::D => key_to_char(keys::D, p.caps).unwrap_or(c),
+            Some(p) => chars::to_char(p.key, p.caps, 0, 0).unwrap_or(c),
+            None => c,
+        })
+        .collect()
+}
+
 mod test_utils {
     //! Shared test utilities for inline tests
     //!
@@ -286,7 +260,7 @@ mod test_utils {
             // ESC key: restore to raw ASCII
             if key == keys::ESC {
                 let r = e.on_key_ext(key, false, false, false);
-                if r.action == Action::Send as u8 {
+                if r.action == Action::Restore as u8 {
                     for _ in 0..r.backspace {
                         screen.pop();
                     }
@@ -465,7 +439,7 @@ mod test_utils {
 
             if key == keys::ESC {
                 let r = e.on_key_ext(key, false, false, false);
-                if r.action == Action::Send as u8 {
+                if r.action == Action::Restore as u8 {
                     for _ in 0..r.backspace {
                         screen.pop();
                     }
@@ -516,6 +490,301 @@ mod test_utils {
         }
         screen
     }
+
+    // ============================================================
+    // FUZZING
+    // ============================================================
+
+    /// One key event: (key, caps, ctrl, shift)
+    pub type KeyEvent = (u16, bool, bool, bool);
+
+    /// Keys the fuzzer draws from: letters, digits, editing and break keys
+    const FUZZ_KEYS: &[u16] = &[
+        keys::A,
+        keys::B,
+        keys::C,
+        keys::D,
+        keys::E,
+        keys::F,
+        keys::G,
+        keys::H,
+        keys::I,
+        keys::J,
+        keys::K,
+        keys::L,
+        keys::M,
+        keys::N,
+        keys::O,
+        keys::P,
+        keys::Q,
+        keys::R,
+        keys::S,
+        keys::T,
+        keys::U,
+        keys::V,
+        keys::W,
+        keys::X,
+        keys::Y,
+        keys::Z,
+        keys::N0,
+        keys::N1,
+        keys::N2,
+        keys::N3,
+        keys::N4,
+        keys::N5,
+        keys::N6,
+        keys::N7,
+        keys::N8,
+        keys::N9,
+        keys::SPACE,
+        keys::DELETE,
+        keys::DELETE,
+        keys::DELETE,
+        keys::ESC,
+        keys::TAB,
+        keys::RETURN,
+        keys::LEFT,
+        keys::DOT,
+        keys::COMMA,
+        keys::SLASH,
+        keys::SEMICOLON,
+        keys::QUOTE,
+        keys::LBRACKET,
+        keys::RBRACKET,
+        keys::MINUS,
+        keys::EQUAL,
+        keys::BACKQUOTE,
+    ];
+
+    /// Decode arbitrary bytes into key events (2 bytes per event)
+    ///
+    /// First byte picks the key, second the modifiers. Ctrl is set on
+    /// roughly 1 in 8 events so it does not drown out the interesting paths.
+    pub fn key_events_from_bytes(data: &[u8]) -> Vec<KeyEvent> {
+        data.chunks_exact(2)
+            .map(|pair| {
+                let key = FUZZ_KEYS[pair[0] as usize % FUZZ_KEYS.len()];
+                let mods = pair[1];
+                (key, mods & 1 != 0, mods & 0x1c == 0x1c, mods & 2 != 0)
+            })
+            .collect()
+    }
+
+    /// On/off options set from fuzz bytes (`settings` option names)
+    pub const FUZZ_FLAGS: &[&str] = &[
+        "free_tone",
+        "spell_check",
+        "skip_w_shortcut",
+        "initial_w_literal",
+        "bracket_shortcut",
+        "esc_restore",
+        "english_auto_restore",
+        "backspace_undo_restore",
+        "hyphen_soft_boundary",
+        "url_passthrough",
+        "symbol_pack",
+        "unicode_input",
+        "auto_capitalize",
+        "title_case",
+        "allow_foreign_consonants",
+        "spelling_autofix",
+        "proper_noun_capitalize",
+        "teencode_passthrough",
+        "caps_lock_recase",
+        "english_capital_i",
+        "repeated_word_check",
+        "auto_detect_method",
+        "auto_tone_style",
+        "stats",
+        "learning",
+        "per_app_enabled",
+        "word_hooks",
+    ];
+
+    /// Numeric options set from fuzz bytes: (name, largest value)
+    pub const FUZZ_NUMBERS: &[(&str, u8)] = &[
+        ("method", 3),
+        ("tone_style", 1),
+        ("auto_restore_triggers", crate::engine::RESTORE_ON_ALL),
+        ("digit_policy", 1),
+        ("revert_policy", 1),
+        ("repeat_policy", 1),
+        ("strictness", 2),
+        ("spelling_variants", crate::data::dictionary::VARIANT_ALL),
+        ("auto_english_switch", u8::MAX),
+        ("terminal_mode", 2),
+    ];
+
+    /// Bytes `engine_from_bytes` reads for the options
+    pub const FUZZ_OPTION_BYTES: usize = 8;
+
+    /// Word hook for fuzzing: rewrites every word to itself reversed
+    #[derive(Debug)]
+    struct ReverseHook;
+
+    impl crate::engine::hooks::WordHook for ReverseHook {
+        fn on_commit(&mut self, word: &str) -> Option<String> {
+            Some(word.chars().rev().collect())
+        }
+    }
+
+    /// Engine with every option set from the first `FUZZ_OPTION_BYTES` of
+    /// `data`, and the rest of `data` (for `key_events_from_bytes`)
+    ///
+    /// Covers the `FUZZ_FLAGS` and `FUZZ_NUMBERS` options, the per-app
+    /// modes hosts set outside the settings file, the engine hotkeys (on
+    /// Shift + letter) and a word hook.
+    pub fn engine_from_bytes(data: &[u8]) -> (Engine, &[u8]) {
+        use crate::engine::hotkey::{Hotkey, HotkeyAction};
+
+        let split = data.len().min(FUZZ_OPTION_BYTES);
+        let mut bits = data[..split]
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &b| acc << 8 | b as u64);
+        let mut take = |n: u32| {
+            let value = bits & ((1 << n) - 1);
+            bits >>= n;
+            value
+        };
+
+        let mut e = Engine::new();
+        e.set_word_hook(Some(Box::new(ReverseHook)));
+        for &key in FUZZ_FLAGS {
+            let value = if take(1) != 0 { "true" } else { "false" };
+            e.set_option(key, value).unwrap();
+        }
+        for &(key, max) in FUZZ_NUMBERS {
+            let value = take(8 - max.leading_zeros()) % (max as u64 + 1);
+            e.set_option(key, &value.to_string()).unwrap();
+        }
+        e.set_modern_tone(take(1) != 0);
+        e.set_selection_replace(take(1) != 0);
+        e.set_decomposed_backspace(take(1) != 0);
+        e.set_code_context(take(1) != 0);
+        // Held, every key passes through: keep it rare
+        e.set_english_hold(take(2) == 3);
+        for (&action, key) in HotkeyAction::ALL
+            .iter()
+            .zip([keys::Q, keys::X, keys::J, keys::Z])
+        {
+            if take(1) != 0 {
+                e.set_hotkey(action, Some(Hotkey::new(key, false, true)));
+            }
+        }
+        (e, &data[split..])
+    }
+
+    /// Feed key events and panic if an engine invariant is broken
+    ///
+    /// Each key's result goes through the word hook first, as in the C
+    /// ABI. Invariants checked after every key:
+    /// - backspace count never exceeds what is on the simulated screen
+    ///   (counted in code points of decomposed text with `FLAG_DECOMPOSED`)
+    /// - output count fits the result buffer
+    /// - buffer stays within `buffer::MAX`
+    pub fn assert_key_invariants(e: &mut Engine, events: &[KeyEvent]) {
+        use crate::engine::buffer::MAX;
+
+        let mut screen: Vec<char> = Vec::new();
+        for (i, &(key, caps, ctrl, shift)) in events.iter().enumerate() {
+            let mut r = e.on_key_ext(key, caps, ctrl, shift);
+            e.run_word_hook(&mut r);
+            let ctx = || {
+                let start = i.saturating_sub(16);
+                format!("event #{} after {:?}", i, &events[start..=i])
+            };
+
+            assert!(r.count as usize <= MAX, "count overflow at {}", ctx());
+            assert!(e.debug_buffer_len() <= MAX, "buffer overflow at {}", ctx());
+
+            if r.action != Action::None as u8 {
+                let deleted = deleted_chars(&screen, &r);
+                assert!(
+                    deleted.is_some(),
+                    "backspace {} (flags {:#x}) does not fit screen {:?} at {}",
+                    r.backspace,
+                    r.flags,
+                    screen.iter().collect::<String>(),
+                    ctx()
+                );
+                screen.truncate(screen.len() - deleted.unwrap_or(0));
+                screen.extend((0..r.count as usize).filter_map(|j| char::from_u32(r.chars[j])));
+                if crate::engine::host_types_key(&r, key, shift) {
+                    if let Some(c) = screen_char(key, caps, shift) {
+                        screen.push(c);
+                    }
+                }
+                if r.flags & crate::engine::FLAG_CURSOR_MOVE != 0 {
+                    // Only the text before the caret can be deleted
+                    let keep = screen.len().saturating_sub(e.cursor_offset() as usize);
+                    screen.truncate(keep);
+                }
+            } else if r.key_consumed() {
+                // Swallowed (hotkey, held back): the app sees nothing
+            } else if ctrl && key == keys::DELETE {
+                // Word delete: spaces before the caret, then the word or
+                // the run of punctuation before them
+                while screen.last().is_some_and(|c| c.is_whitespace()) {
+                    screen.pop();
+                }
+                let word = screen.last().is_some_and(|c| c.is_alphanumeric());
+                while screen
+                    .last()
+                    .is_some_and(|c| !c.is_whitespace() && c.is_alphanumeric() == word)
+                {
+                    screen.pop();
+                }
+            } else if ctrl {
+                // Shortcut chord (copy, undo, ...): screen is unknown from here on
+                screen.clear();
+            } else if key == keys::DELETE {
+                screen.pop();
+            } else if let Some(c) = screen_char(key, caps, shift) {
+                screen.push(c);
+            } else {
+                // Cursor moved or line broken: earlier text is out of reach
+                screen.clear();
+            }
+        }
+    }
+
+    /// Characters at the end of `screen` the backspaces of `r` delete,
+    /// None if they reach past it or, decomposed, end inside a character
+    fn deleted_chars(screen: &[char], r: &crate::engine::Result) -> Option<usize> {
+        let backspace = r.backspace as usize;
+        if r.flags & crate::engine::FLAG_DECOMPOSED == 0 {
+            return (backspace <= screen.len()).then_some(backspace);
+        }
+        let mut points = 0;
+        let mut deleted = 0;
+        for &c in screen.iter().rev() {
+            if points >= backspace {
+                break;
+            }
+            points += crate::data::chars::decomposed_len(c);
+            deleted += 1;
+        }
+        (points == backspace).then_some(deleted)
+    }
+
+    /// Character a passed-through key leaves on screen
+    fn screen_char(key: u16, caps: bool, shift: bool) -> Option<char> {
+        match key {
+            keys::SPACE => Some(' '),
+            keys::DOT => Some(if shift { '>' } else { '.' }),
+            keys::COMMA => Some(if shift { '<' } else { ',' }),
+            keys::SLASH => Some(if shift { '?' } else { '/' }),
+            keys::SEMICOLON => Some(if shift { ':' } else { ';' }),
+            keys::QUOTE => Some(if shift { '"' } else { '\'' }),
+            keys::LBRACKET => Some(if shift { '{' } else { '[' }),
+            keys::RBRACKET => Some(if shift { '}' } else { ']' }),
+            keys::MINUS => Some(if shift { '_' } else { '-' }),
+            keys::EQUAL => Some(if shift { '+' } else { '=' }),
+            keys::BACKQUOTE => Some(if shift { '~' } else { '`' }),
+            _ => super::key_to_char_ext(key, caps, shift),
+        }
+    }
 }
 
 // Re-export test utilities for use in other test modules
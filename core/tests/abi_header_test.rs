@@ -0,0 +1,182 @@
+//! C ABI header consistency tests
+//!
+//! `build.rs` generates `include/gonhanh_core.h` from its template into
+//! `$OUT_DIR`, and the Python, Kotlin and Swift bindings next to it. These
+//! tests check the checked-in copies are current and match the
+//! `#[no_mangle]` exports, so platform shells can rely on them.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn read_header() -> String {
+    read("include/gonhanh_core.h")
+}
+
+fn read(path: &str) -> String {
+    fs::read_to_string(manifest_dir().join(path))
+        .unwrap_or_else(|_| panic!("{} should exist", path))
+}
+
+/// Fail when checked-in `path` differs from what build.rs generated; with
+/// `GONHANH_BLESS` set, update it instead
+fn check_generated(path: &str) {
+    let generated = Path::new(env!("OUT_DIR")).join("abi").join(path);
+    let expected = fs::read_to_string(&generated)
+        .unwrap_or_else(|_| panic!("{} should be generated", generated.display()));
+    if env::var_os("GONHANH_BLESS").is_some() {
+        fs::write(manifest_dir().join(path), &expected).unwrap();
+        return;
+    }
+    assert!(
+        read(path) == expected,
+        "{} is out of date, run `GONHANH_BLESS=1 cargo test --test abi_header_test`",
+        path
+    );
+}
+
+/// Collect `.rs` files under `dir` recursively
+fn rust_sources(dir: &Path, out: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            rust_sources(&path, out);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+/// Names of all functions marked `#[no_mangle]` in `src/`
+fn exported_functions() -> Vec<String> {
+    let mut files = Vec::new();
+    rust_sources(&manifest_dir().join("src"), &mut files);
+
+    let mut names = Vec::new();
+    for file in files {
+        let source = fs::read_to_string(&file).unwrap();
+        let mut pending = false;
+        for line in source.lines() {
+            let line = line.trim();
+            if line == "#[no_mangle]" {
+                pending = true;
+                continue;
+            }
+            if pending {
+                if let Some(idx) = line.find("extern \"C\" fn ") {
+                    let rest = &line[idx + "extern \"C\" fn ".len()..];
+                    let name: String = rest
+                        .chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    names.push(name);
+                    pending = false;
+                } else if !line.starts_with("#[") && !line.starts_with("///") {
+                    pending = false;
+                }
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+#[test]
+fn header_is_current() {
+    check_generated("include/gonhanh_core.h");
+}
+
+#[test]
+fn header_declares_every_export() {
+    let header = read_header();
+    let exports = exported_functions();
+    assert!(!exports.is_empty(), "No #[no_mangle] exports found");
+
+    let missing: Vec<&String> = exports
+        .iter()
+        .filter(|name| {
+            !header.contains(&format!(" {}(", name)) && !header.contains(&format!("*{}(", name))
+        })
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "Exports missing from include/gonhanh_core.h: {:?}",
+        missing
+    );
+}
+
+#[test]
+fn header_abi_version_matches_crate() {
+    let header = read_header();
+    let define = format!("#define GONHANH_ABI_VERSION {}", gonhanh_core::ABI_VERSION);
+    assert!(
+        header.contains(&define),
+        "Header ABI version out of sync, expected `{}`",
+        define
+    );
+}
+
+#[test]
+fn header_max_chars_matches_buffer() {
+    let header = read_header();
+    let define = format!(
+        "#define GONHANH_MAX_CHARS {}",
+        gonhanh_core::engine::buffer::MAX
+    );
+    assert!(header.contains(&define), "Expected `{}`", define);
+}
+
+#[test]
+fn header_has_no_template_markers_left() {
+    let header = read_header();
+    let left: Vec<&str> = header
+        .lines()
+        .filter(|line| {
+            line.starts_with("@fn ") || line.starts_with("#define") && line.contains('@')
+        })
+        .collect();
+    assert!(left.is_empty(), "Unexpanded template lines: {:?}", left);
+}
+
+#[test]
+fn bindings_are_current() {
+    check_generated("bindings/python/gonhanh_core.py");
+    check_generated("bindings/kotlin/GonhanhCore.kt");
+    check_generated("include/module.modulemap");
+}
+
+#[test]
+fn bindings_declare_every_export() {
+    let python = read("bindings/python/gonhanh_core.py");
+    let kotlin = read("bindings/kotlin/GonhanhCore.kt");
+    let exports = exported_functions();
+
+    let missing: Vec<&String> = exports
+        .iter()
+        .filter(|name| {
+            !python.contains(&format!("    \"{}\": ([", name))
+                || !kotlin.contains(&format!("    fun {}(", name))
+        })
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "Exports missing from bindings: {:?}",
+        missing
+    );
+}
+
+#[test]
+fn bindings_carry_header_constants() {
+    let python = read("bindings/python/gonhanh_core.py");
+    let kotlin = read("bindings/kotlin/GonhanhCore.kt");
+    let version = gonhanh_core::ABI_VERSION;
+    assert!(python.contains(&format!("\nGONHANH_ABI_VERSION = {}\n", version)));
+    assert!(kotlin.contains(&format!("\nconst val GONHANH_ABI_VERSION = {}\n", version)));
+
+    let modulemap = read("include/module.modulemap");
+    assert!(modulemap.contains("header \"gonhanh_core.h\""));
+}
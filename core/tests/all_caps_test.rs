@@ -0,0 +1,61 @@
+//! ALL-CAPS words
+//!
+//! A word typed in capitals stays in capitals, including vowels a modifier
+//! key inserts (w → ư) when Shift is released early. With CapsLock re-case
+//! on, toggling CapsLock mid-word re-cases the whole word.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn check(e: &mut Engine, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        e.clear_all();
+        assert_eq!(type_word(e, input), *expected, "'{}'", input);
+    }
+}
+
+#[test]
+fn all_caps_words() {
+    check(
+        &mut Engine::new(),
+        &[
+            ("VIEETJ", "VIỆT"),
+            ("VIEJET", "VIỆT"),
+            ("VIEeTj", "VIỆT"),
+            ("DDUWOWCJ", "ĐƯỢC"),
+            ("NGUOIWF", "NGƯỜI"),
+            ("QUOOCS", "QUỐC"),
+        ],
+    );
+}
+
+#[test]
+fn inserted_vowel_follows_all_caps_word() {
+    check(
+        &mut Engine::new(),
+        &[("TRw", "TRƯ"), ("NGwNG", "NGƯNG"), ("trw", "trư")],
+    );
+    // One capital is Title case
+    check(&mut Engine::new(), &[("Tw", "Tư"), ("Nhw", "Như")]);
+}
+
+#[test]
+fn caps_lock_recase() {
+    let mut e = Engine::new();
+    e.set_caps_lock_recase(true);
+    check(
+        &mut e,
+        &[
+            ("vieETJ", "VIỆT"),
+            ("dduwoWCJ", "ĐƯỢC"),
+            ("VIEEtj", "Việt"),
+            ("Vieetj", "Việt"),
+            ("VIEETJ", "VIỆT"),
+        ],
+    );
+}
+
+#[test]
+fn caps_lock_recase_off_by_default() {
+    check(&mut Engine::new(), &[("vieETJ", "việT")]);
+}
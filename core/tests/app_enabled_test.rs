@@ -0,0 +1,63 @@
+//! Per-app on/off: the toggle is kept for the app in front and comes back
+//! with it
+
+use gonhanh_core::engine::{Engine, Event};
+use gonhanh_core::utils::type_word;
+
+fn per_app_engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_per_app_enabled(true);
+    e
+}
+
+#[test]
+fn toggle_is_kept_per_app() {
+    let mut e = per_app_engine();
+    e.set_active_app(Some("idea64.exe"));
+    e.set_enabled(false);
+    assert_eq!(type_word(&mut e, "vieetj"), "vieetj");
+
+    e.set_active_app(Some("slack.exe"));
+    assert!(e.is_enabled());
+    assert_eq!(type_word(&mut e, "vieetj"), "việt");
+
+    e.set_active_app(Some("IDEA64.EXE"));
+    assert!(!e.is_enabled());
+    assert_eq!(
+        e.take_events(),
+        [Event::EnabledChanged(true), Event::EnabledChanged(false)]
+    );
+}
+
+#[test]
+fn toggle_back_forgets_app() {
+    let mut e = per_app_engine();
+    e.set_active_app(Some("idea64.exe"));
+    e.set_enabled(false);
+    e.set_enabled(true);
+    assert_eq!(e.app_enabled_states().count(), 0);
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    e.set_active_app(Some("idea64.exe"));
+    e.set_enabled(false);
+    e.set_active_app(Some("slack.exe"));
+    assert!(!e.is_enabled());
+    assert!(e.take_events().is_empty());
+}
+
+#[test]
+fn app_states_round_trip_through_settings() {
+    let mut e = per_app_engine();
+    e.set_app_enabled("idea64.exe", Some(false));
+    let text = e.export_settings();
+    assert!(text.contains("[app_enabled]\n0 idea64\n"), "{}", text);
+
+    let mut copy = Engine::new();
+    copy.import_settings(&text).unwrap();
+    assert!(copy.per_app_enabled());
+    copy.set_active_app(Some("idea64.exe"));
+    assert!(!copy.is_enabled());
+}
@@ -0,0 +1,64 @@
+//! Per-app word state: a word left mid-way in one app is still editable
+//! after typing in another
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::Screen;
+
+#[test]
+fn word_survives_app_switch() {
+    let mut e = Engine::new();
+    let (mut slack, mut chrome) = (Screen::new(), Screen::new());
+    slack.type_text(&mut e, "vieet");
+
+    e.save_app_state("Slack");
+    // Chrome starts fresh: the tone key is not applied to the Slack word
+    assert!(!e.restore_app_state("chrome.exe"));
+    chrome.type_text(&mut e, "j ddi");
+    assert_eq!(chrome.text(), "j đi");
+
+    e.save_app_state("chrome");
+    assert!(e.restore_app_state("slack"));
+    slack.type_text(&mut e, "j nam");
+    assert_eq!(slack.text(), "việt nam");
+
+    // Chrome's word is still there too
+    e.save_app_state("slack");
+    assert!(e.restore_app_state("Chrome.exe"));
+    chrome.type_text(&mut e, "r");
+    assert_eq!(chrome.text(), "j đỉ");
+}
+
+#[test]
+fn backspace_after_space_survives() {
+    let mut e = Engine::new();
+    let mut notes = Screen::new();
+    notes.type_text(&mut e, "tieeng ");
+    e.save_app_state("notes");
+    assert!(!e.restore_app_state("mail"));
+    assert!(e.restore_app_state("notes"));
+    // Back into the committed word: it takes the tone again
+    notes.type_text(&mut e, "<s");
+    assert_eq!(notes.text(), "tiếng");
+}
+
+#[test]
+fn state_is_restored_once() {
+    let mut e = Engine::new();
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "as");
+    e.save_app_state("a");
+    assert!(e.restore_app_state("a"));
+    assert!(!e.restore_app_state("a"));
+}
+
+#[test]
+fn state_value_round_trip() {
+    let mut e = Engine::new();
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "dd");
+    let state = e.save_state();
+    e.clear_all();
+    e.restore_state(state);
+    screen.type_text(&mut e, "uwowngf");
+    assert_eq!(screen.text(), "đường");
+}
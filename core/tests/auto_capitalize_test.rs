@@ -12,7 +12,9 @@
 mod common;
 use common::telex_auto_capitalize;
 use gonhanh_core::data::keys;
-use gonhanh_core::engine::Engine;
+use gonhanh_core::engine::capitalize::CapitalizeRule;
+use gonhanh_core::engine::hotkey::{Hotkey, HotkeyAction};
+use gonhanh_core::engine::{Engine, Event};
 use gonhanh_core::utils::type_word;
 
 // ============================================================
@@ -517,3 +519,133 @@ fn clear_all_resets_saw_sentence_ending() {
         "After clear_all(), space+letter should NOT capitalize - expecting Action::None"
     );
 }
+
+// ============================================================
+// TITLE CASE MODE
+// ============================================================
+
+#[test]
+fn title_case_capitalizes_every_word() {
+    let mut e = Engine::new();
+    e.set_title_case(true);
+    assert_eq!(
+        type_word(&mut e, "tieeu ddeef baif vieets "),
+        "Tiêu Đề Bài Viết "
+    );
+    assert_eq!(type_word(&mut e, "(haf nooij)"), "(Hà Nội)");
+}
+
+#[test]
+fn title_case_skips_letters_inside_token() {
+    let mut e = Engine::new();
+    e.set_title_case(true);
+    assert_eq!(type_word(&mut e, "don't a.b q1n "), "Don't A.b Q1n ");
+}
+
+#[test]
+fn title_case_toggle() {
+    let mut e = Engine::new();
+    assert!(!e.title_case());
+    assert!(e.toggle_title_case());
+    assert_eq!(type_word(&mut e, "chaof "), "Chào ");
+    assert!(!e.toggle_title_case());
+    assert_eq!(type_word(&mut e, "chaof "), "chào ");
+}
+
+#[test]
+fn title_case_hotkey() {
+    let mut e = Engine::new();
+    e.set_hotkey(
+        HotkeyAction::ToggleTitleCase,
+        Some(Hotkey::new(keys::T, true, true)),
+    );
+    let r = e.on_key_ext(keys::T, true, true, true);
+    assert!(r.key_consumed());
+    assert_eq!(e.take_events(), [Event::TitleCaseChanged(true)]);
+    assert_eq!(type_word(&mut e, "chaof "), "Chào ");
+
+    e.on_key_ext(keys::T, true, true, true);
+    assert_eq!(e.take_events()[0].text(), "off");
+    assert_eq!(type_word(&mut e, "chaof "), "chào ");
+}
+
+// ============================================================
+// CONFIGURABLE PUNCTUATION RULES
+// ============================================================
+
+fn auto_cap_engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    e
+}
+
+#[test]
+fn default_rules_keep_quotes_cancel_comma() {
+    telex_auto_capitalize(&[
+        ("ok. \"ban", "ok. \"Ban"),
+        ("ok. (ban", "ok. (Ban"),
+        ("ok. ,ban", "ok. ,ban"),
+        // Shift+DOT is '>', not a sentence end
+        ("ok> ban", "ok> ban"),
+    ]);
+}
+
+#[test]
+fn rule_can_end_sentence() {
+    let mut e = auto_cap_engine();
+    assert_eq!(type_word(&mut e, "chuys: ban"), "chuý: ban");
+    e.clear_all();
+    e.set_capitalize_rule(':', CapitalizeRule::EndSentence);
+    assert_eq!(type_word(&mut e, "chuys: ban"), "chuý: Ban");
+}
+
+#[test]
+fn rule_can_cancel_or_keep() {
+    let mut e = auto_cap_engine();
+    e.set_capitalize_rule('(', CapitalizeRule::Cancel);
+    e.set_capitalize_rule('-', CapitalizeRule::Keep);
+    assert_eq!(type_word(&mut e, "ok. (ban"), "ok. (ban");
+    e.clear_all();
+    assert_eq!(type_word(&mut e, "ok. -ban"), "ok. -Ban");
+    e.clear_all();
+    e.reset_capitalize_rules();
+    assert_eq!(type_word(&mut e, "ok. (ban"), "ok. (Ban");
+}
+
+// ============================================================
+// SENTENCE SEGMENTATION (shared with auto-capitalize)
+// ============================================================
+
+#[test]
+fn digit_after_dot_is_not_sentence_end() {
+    telex_auto_capitalize(&[("1.5 kg", "1.5 kg"), ("gias 2.5 trieeuj", "giá 2.5 triệu")]);
+}
+
+#[test]
+fn sentences_match_auto_capitalize() {
+    let mut e = auto_cap_engine();
+    let out = type_word(
+        &mut e,
+        "xin chaof. ban khoer khoong? \"tooi ddi.\" vaayj (ddi nhes). xem ddaay.vn, 1.5 kg! heets",
+    );
+    let starts: Vec<usize> = e
+        .capitalize_rules()
+        .sentences(&out)
+        .into_iter()
+        .map(|r| r.start)
+        .collect();
+    for (i, c) in out.char_indices().filter(|(_, c)| c.is_alphabetic()) {
+        // The first letter of each sentence (after any opening quote/bracket)
+        let sentence_start = starts
+            .iter()
+            .any(|&s| s > 0 && s <= i && out[s..i].chars().all(|p| !p.is_alphanumeric()));
+        assert_eq!(
+            c.is_uppercase(),
+            sentence_start,
+            "'{}' at {} in {}",
+            c,
+            i,
+            out
+        );
+    }
+}
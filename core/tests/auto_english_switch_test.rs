@@ -0,0 +1,85 @@
+//! Auto language switch
+//!
+//! After N auto-restored English words in a row, Vietnamese processing is
+//! suspended so English text types untouched. The first word that reads as
+//! Vietnamese is converted at its boundary and processing resumes.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine(words: u8) -> Engine {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_auto_english_switch(words);
+    e
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    assert_eq!(type_word(&mut e, "text user expect "), "text user expect ");
+    assert!(!e.english_suspended());
+    assert_eq!(type_word(&mut e, "box "), "bõ ");
+}
+
+#[test]
+fn suspends_after_consecutive_restores() {
+    let mut e = engine(2);
+    assert_eq!(type_word(&mut e, "text "), "text ");
+    assert!(!e.english_suspended());
+    assert_eq!(type_word(&mut e, "user "), "user ");
+    assert!(e.english_suspended());
+    // Words auto-restore would keep as Vietnamese now pass through
+    assert_eq!(type_word(&mut e, "box mix, "), "box mix, ");
+    assert!(e.english_suspended());
+}
+
+#[test]
+fn words_without_transforms_do_not_break_streak() {
+    let mut e = engine(2);
+    type_word(&mut e, "the text in a user ");
+    assert!(e.english_suspended());
+}
+
+#[test]
+fn vietnamese_word_breaks_streak() {
+    let mut e = engine(2);
+    type_word(&mut e, "text vieetj user ");
+    assert!(!e.english_suspended());
+}
+
+#[test]
+fn resumes_on_vietnamese_word() {
+    let mut e = engine(2);
+    type_word(&mut e, "text user ");
+    assert!(e.english_suspended());
+    assert_eq!(type_word(&mut e, "vieetj "), "việt ");
+    assert!(!e.english_suspended());
+    assert_eq!(type_word(&mut e, "nhaf,"), "nhà,");
+}
+
+#[test]
+fn resume_before_punctuation() {
+    let mut e = engine(2);
+    type_word(&mut e, "text user ");
+    // The comma is typed by the host after the converted word
+    assert_eq!(type_word(&mut e, "dduwowcj,"), "được,");
+    assert!(!e.english_suspended());
+}
+
+#[test]
+fn backspace_edits_suspended_word() {
+    let mut e = engine(2);
+    type_word(&mut e, "text user ");
+    assert_eq!(type_word(&mut e, "vieetx<j "), "việt ");
+}
+
+#[test]
+fn disabling_resets_suspension() {
+    let mut e = engine(2);
+    type_word(&mut e, "text user ");
+    e.set_auto_english_switch(0);
+    assert!(!e.english_suspended());
+    assert_eq!(type_word(&mut e, "vieetj "), "việt ");
+}
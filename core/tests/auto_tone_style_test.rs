@@ -0,0 +1,48 @@
+//! Tone style inference: words committed in the other placement flip the
+//! style after a few corrections
+
+use gonhanh_core::engine::{Engine, Event, ToneStyle};
+use gonhanh_core::utils::type_word;
+
+/// Type `input`, pick the first candidate (the other placement), commit
+fn correct(e: &mut Engine, input: &str) {
+    type_word(e, input);
+    e.select_candidate(0);
+    type_word(e, " ");
+}
+
+#[test]
+fn flips_after_three_corrections() {
+    let mut e = Engine::new();
+    e.set_auto_tone_style(true);
+    correct(&mut e, "hoaf");
+    correct(&mut e, "khoer");
+    assert_eq!(e.tone_style(), ToneStyle::NewStyle);
+    correct(&mut e, "thuyr");
+    assert_eq!(e.tone_style(), ToneStyle::TraditionalStyle);
+    assert_eq!(
+        e.take_events(),
+        [Event::ToneStyleChanged(ToneStyle::TraditionalStyle)]
+    );
+    assert_eq!(type_word(&mut e, "hoaf"), "hòa");
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    for word in ["hoaf", "khoer", "thuyr"] {
+        correct(&mut e, word);
+    }
+    assert_eq!(e.tone_style(), ToneStyle::NewStyle);
+}
+
+#[test]
+fn setting_the_style_restarts_count() {
+    let mut e = Engine::new();
+    e.set_auto_tone_style(true);
+    correct(&mut e, "hoaf");
+    correct(&mut e, "khoer");
+    e.set_tone_style(ToneStyle::NewStyle);
+    correct(&mut e, "thuyr");
+    assert_eq!(e.tone_style(), ToneStyle::NewStyle);
+}
@@ -0,0 +1,43 @@
+//! Double-key revert across Backspace: deleting characters typed after a
+//! transform keeps it revertible, deleting the transformed character
+//! drops it
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::type_text;
+
+fn check(method: u8, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(method);
+        assert_eq!(type_text(&mut e, input), *expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn telex_revert_after_backspace() {
+    check(
+        0,
+        &[
+            // Same as pressing the key again right after the transform
+            ("as<s", "s"),
+            ("bas<s", "bs"),
+            ("tieens<s", "ties"),
+            ("trangw<w", "tranw"),
+            ("trangw<<w", "traw"),
+            ("vieetj<<j", "vị"),
+            // Backspace over the space brings the word back
+            ("as <s", "as"),
+        ],
+    );
+}
+
+#[test]
+fn vni_revert_after_backspace() {
+    check(1, &[("a1<1", "1"), ("ba1n<1", "ba1"), ("tie61n<1", "tie1")]);
+}
+
+#[test]
+fn deleting_transformed_char_drops_revert() {
+    check(0, &[("ass<s", "á"), ("tuow<w", "tư")]);
+    check(1, &[("ba8<8", "b8")]);
+}
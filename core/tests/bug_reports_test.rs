@@ -392,6 +392,17 @@ fn issue128_shortcut_after_delete() {
     assert_eq!(result3, "→", "Second '->' after delete should produce '→'");
 }
 
+#[test]
+fn deleted_char_leaves_shortcut_prefix() {
+    use gonhanh_core::engine::shortcut::Shortcut;
+
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::immediate("->", "→"));
+    // "-" deleted: ">" alone is not the trigger
+    assert_eq!(type_word(&mut e, "-<>"), ">");
+    assert_eq!(type_word(&mut e, " ->"), " →");
+}
+
 #[test]
 fn issue128_shortcut_after_multiple_deletes() {
     use gonhanh_core::data::keys;
@@ -1076,6 +1087,19 @@ fn issue159_bracket_revert() {
     assert_eq!(result.chars[0], ']' as u32, "t]] should revert to t]");
 }
 
+#[test]
+fn issue159_bracket_restores_raw() {
+    use gonhanh_core::engine::Engine;
+
+    let mut e = Engine::new();
+    e.set_bracket_shortcut(true);
+    e.set_esc_restore(true);
+    // The bracket typed for ơ comes back with the rest of the word
+    assert_eq!(type_word(&mut e, "d[\x1b"), "d[");
+    e.clear();
+    assert_eq!(type_word(&mut e, "t]\x1b"), "t]");
+}
+
 // =============================================================================
 // ISSUE #200: "khoảng " → "khoan " (tone mark lost on space)
 // https://github.com/user/gonhanh/issues/200
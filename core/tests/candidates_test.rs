@@ -0,0 +1,65 @@
+//! Candidate list: what the word in progress could be instead, and
+//! picking one
+
+use gonhanh_core::engine::candidates::{Candidate, CandidateKind};
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::utils::type_word;
+
+fn texts(list: &[Candidate], kind: CandidateKind) -> Vec<&str> {
+    list.iter()
+        .filter(|c| c.kind == kind)
+        .map(|c| c.text.as_str())
+        .collect()
+}
+
+#[test]
+fn tone_placement_and_raw_keys() {
+    let mut e = Engine::new();
+    type_word(&mut e, "hoaf");
+    let list = e.candidates();
+    assert_eq!(texts(&list, CandidateKind::Tone), ["hòa"]);
+    assert_eq!(texts(&list, CandidateKind::Raw), ["hoaf"]);
+    assert!(list.iter().all(|c| c.text != "hoà"));
+}
+
+#[test]
+fn spelling_suggestion() {
+    let mut e = Engine::new();
+    type_word(&mut e, "nge");
+    assert_eq!(texts(&e.candidates(), CandidateKind::Spelling), ["nghe"]);
+}
+
+#[test]
+fn predictions_follow_case() {
+    let mut e = Engine::new();
+    type_word(&mut e, "Tie");
+    let list = e.candidates();
+    let predictions = texts(&list, CandidateKind::Prediction);
+    // Most frequent first, "tin" cannot become "tie…"
+    assert_eq!(predictions, ["Tiếp", "Tiến", "Tiền", "Tiêu", "Tiên"]);
+}
+
+#[test]
+fn no_word_no_candidates() {
+    let mut e = Engine::new();
+    assert!(e.candidates().is_empty());
+    type_word(&mut e, "xin ");
+    assert_eq!(e.candidates_json(), "[]");
+}
+
+#[test]
+fn select_replaces_word() {
+    let mut e = Engine::new();
+    type_word(&mut e, "hoaf");
+    let r = e.select_candidate(0);
+    assert_eq!(r.action, Action::Send as u8);
+    assert_eq!(r.backspace, 3);
+    let text: String = r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect();
+    assert_eq!(text, "hòa");
+    assert_eq!(e.get_buffer_string(), "hòa");
+
+    assert_eq!(e.select_candidate(99).action, Action::None as u8);
+}
@@ -0,0 +1,209 @@
+//! gonhanh CLI tests
+//!
+//! Runs the built `gonhanh` binary end-to-end through stdin/stdout.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn gonhanh(args: &[&str], input: &str) -> (i32, String) {
+    let (code, out) = gonhanh_bytes(args, input.as_bytes());
+    (code, String::from_utf8(out).unwrap())
+}
+
+fn gonhanh_bytes(args: &[&str], input: &[u8]) -> (i32, Vec<u8>) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gonhanh"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("gonhanh should start");
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    let output = child.wait_with_output().unwrap();
+    (output.status.code().unwrap_or(-1), output.stdout)
+}
+
+#[test]
+fn convert_telex_stdin() {
+    let (code, out) = gonhanh(
+        &["convert"],
+        "Tooi ddang hocj tieengs Vieetj.\nHoom nay trowif ddepj quas!\n",
+    );
+    assert_eq!(code, 0);
+    assert_eq!(out, "Tôi đang học tiếng Việt.\nHôm nay trời đẹp quá!\n");
+}
+
+#[test]
+fn convert_vni() {
+    let (_, out) = gonhanh(&["convert", "--method", "vni"], "Vie65t Nam");
+    assert_eq!(out, "Việt Nam");
+}
+
+#[test]
+fn convert_keeps_symbols_and_unicode() {
+    let (_, out) = gonhanh(&["convert"], "<b>vieetj</b> → \"ok\"\r\n");
+    assert_eq!(out, "<b>việt</b> → \"ok\"\r\n");
+}
+
+#[test]
+fn convert_options() {
+    let (_, out) = gonhanh(&["convert", "--traditional", "--auto-restore"], "hoaf text");
+    assert_eq!(out, "hòa text");
+}
+
+#[test]
+fn convert_with_settings_file() {
+    let path = std::env::temp_dir().join(format!("gonhanh-cli-settings-{}", std::process::id()));
+    std::fs::write(
+        &path,
+        "# gonhanh settings 1\n[options]\nmethod 1\n[shortcuts]\nko\tkhông\n",
+    )
+    .unwrap();
+    let path = path.to_str().unwrap();
+    let (_, out) = gonhanh(&["convert", "--settings", path], "ko vie65t");
+    assert_eq!(out, "không việt");
+    // Flags after the file still apply
+    let (_, out) = gonhanh(&["convert", "--settings", path, "-m", "telex"], "vieejt");
+    assert_eq!(out, "việt");
+
+    let (code, out) = gonhanh(&["settings", path], "");
+    assert_eq!(code, 0);
+    assert!(out.contains("\nmethod 1\n"));
+    assert!(out.contains("\n[shortcuts]\nko\tkhông\n"));
+    std::fs::remove_file(path).unwrap();
+    let (code, _) = gonhanh(&["settings", path], "");
+    assert_eq!(code, 2);
+}
+
+#[test]
+fn unknown_method_fails() {
+    let (code, out) = gonhanh(&["convert", "-m", "qwerty"], "");
+    assert_eq!(code, 2);
+    assert!(out.is_empty());
+}
+
+#[test]
+fn strip_diacritics() {
+    let (code, out) = gonhanh(&["strip"], "Nguyễn Văn A\nĐà Nẵng → Huế\n");
+    assert_eq!(code, 0);
+    assert_eq!(out, "Nguyen Van A\nDa Nang → Hue\n");
+}
+
+#[test]
+fn tone_style() {
+    let (code, out) = gonhanh(&["tone"], "Hòa, thủy\n");
+    assert_eq!(code, 0);
+    assert_eq!(out, "Hoà, thuỷ\n");
+    let (_, out) = gonhanh(&["tone", "--traditional"], "Hoà, thuỷ");
+    assert_eq!(out, "Hòa, thủy");
+}
+
+#[test]
+fn legacy_encodings() {
+    let (code, out) = gonhanh_bytes(&["decode", "--from", "tcvn3"], b"H\xb5 N\xe9i\n");
+    assert_eq!(code, 0);
+    assert_eq!(String::from_utf8(out).unwrap(), "Hà Nội\n");
+    let (_, out) = gonhanh_bytes(&["encode", "--to", "vni"], "Việt Nam".as_bytes());
+    assert_eq!(out, b"Vie\xe4t Nam");
+    let (code, _) = gonhanh(&["decode"], "");
+    assert_eq!(code, 2);
+}
+
+#[test]
+fn serve_stdio() {
+    let requests = concat!(
+        "{\"id\":1,\"cmd\":\"key\",\"key\":0}\n",
+        "{\"id\":2,\"cmd\":\"key\",\"key\":18}\n",
+        "{\"id\":3,\"cmd\":\"get\",\"key\":\"method\"}\n",
+    );
+    let (code, out) = gonhanh(&["serve", "-m", "vni"], requests);
+    assert_eq!(code, 0);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("{\"id\":1,\"action\":0,"));
+    // 1 is the sắc key in VNI
+    assert!(lines[1].starts_with("{\"id\":2,\"action\":1,\"backspace\":1,\"chars\":\"á\""));
+    assert_eq!(lines[2], "{\"id\":3,\"value\":\"1\"}");
+}
+
+#[cfg(unix)]
+#[test]
+fn serve_unix_socket_shared_by_clients() {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixStream;
+
+    let path = std::env::temp_dir().join(format!("gonhanh-cli-{}.sock", std::process::id()));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gonhanh"))
+        .args(["serve", "--socket", path.to_str().unwrap()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    let connect = || {
+        for _ in 0..200 {
+            if let Ok(stream) = UnixStream::connect(&path) {
+                return stream;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        panic!("service did not start");
+    };
+    let request = |stream: &mut UnixStream, line: &str| {
+        writeln!(stream, "{}", line).unwrap();
+        let mut response = String::new();
+        BufReader::new(stream.try_clone().unwrap())
+            .read_line(&mut response)
+            .unwrap();
+        response
+    };
+
+    let mut first = connect();
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    request(&mut first, r#"{"cmd":"set","key":"method","value":"1"}"#);
+    let mut second = connect();
+    assert_eq!(
+        request(&mut second, r#"{"id":7,"cmd":"get","key":"method"}"#),
+        "{\"id\":7,\"value\":\"1\"}\n"
+    );
+    request(&mut second, r#"{"cmd":"shutdown"}"#);
+    assert!(child.wait().unwrap().success());
+    assert!(!path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn serve_unix_replaces_only_a_stale_socket() {
+    use std::os::unix::net::UnixListener;
+
+    let serve = |path: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_gonhanh"))
+            .args(["serve", "--socket", path.to_str().unwrap()])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap()
+    };
+
+    // A mistyped path naming a regular file: kept, not unlinked
+    let file = std::env::temp_dir().join(format!("gonhanh-cli-{}.txt", std::process::id()));
+    std::fs::write(&file, "notes").unwrap();
+    assert!(!serve(&file).wait().unwrap().success());
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "notes");
+    std::fs::remove_file(&file).unwrap();
+
+    // A socket left behind by a crashed service is replaced
+    let path = std::env::temp_dir().join(format!("gonhanh-stale-{}.sock", std::process::id()));
+    drop(UnixListener::bind(&path).unwrap());
+    let mut child = serve(&path);
+    let mut stream = (0..200)
+        .find_map(|_| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            std::os::unix::net::UnixStream::connect(&path).ok()
+        })
+        .expect("service did not start");
+    writeln!(stream, r#"{{"cmd":"shutdown"}}"#).unwrap();
+    assert!(child.wait().unwrap().success());
+}
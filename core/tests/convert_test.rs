@@ -0,0 +1,54 @@
+//! Clipboard conversion commands
+
+use gonhanh_core::convert::{convert, Command};
+use gonhanh_core::engine::Engine;
+
+#[test]
+fn keystrokes_follow_engine_method() {
+    let mut e = Engine::new();
+    assert_eq!(
+        convert(&e, "Tieengs Vieetj\nHaf Nooij", Command::Keystrokes),
+        "Tiếng Việt\nHà Nội"
+    );
+    e.set_method(1);
+    assert_eq!(convert(&e, "Vie65t Nam", Command::Keystrokes), "Việt Nam");
+}
+
+#[test]
+fn keystrokes_leave_engine_untouched() {
+    let mut e = Engine::new();
+    e.on_key(gonhanh_core::data::keys::A, false, false);
+    convert(&e, "dd", Command::Keystrokes);
+    let r = e.on_key(gonhanh_core::data::keys::S, false, false);
+    assert_eq!(char::from_u32(r.chars[0]), Some('á'));
+}
+
+#[test]
+fn tone_style_follows_engine_option() {
+    let mut e = Engine::new();
+    let mixed = "Khánh Hòa và Thanh Hoá";
+    assert_eq!(
+        convert(&e, mixed, Command::ToneStyle),
+        "Khánh Hoà và Thanh Hoá"
+    );
+    e.set_modern_tone(false);
+    assert_eq!(
+        convert(&e, mixed, Command::ToneStyle),
+        "Khánh Hòa và Thanh Hóa"
+    );
+}
+
+#[test]
+fn strip_and_legacy() {
+    let e = Engine::new();
+    assert_eq!(convert(&e, "Đà Nẵng", Command::StripDiacritics), "Da Nang");
+    // TCVN3 bytes as they arrive from a clipboard: one Latin-1 char each
+    assert_eq!(
+        convert(&e, "\u{a7}\u{b5} N\u{bd}ng", Command::FromTcvn3),
+        "Đà Nẵng"
+    );
+    assert_eq!(
+        convert(&e, "\u{d1}a\u{f8} Na\u{fc}ng", Command::FromVni),
+        "Đà Nẵng"
+    );
+}
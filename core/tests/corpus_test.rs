@@ -0,0 +1,44 @@
+//! Golden corpora: every case in src/data/corpus must pass
+
+use gonhanh_core::corpus::{self, Report};
+use gonhanh_core::engine::Engine;
+
+fn assert_passes(name: &str) {
+    let report: Report = corpus::run_builtin(name, &Engine::new());
+    assert!(report.total > 0, "{}: no cases", name);
+    let failures: Vec<String> = report
+        .failures
+        .iter()
+        .map(|f| {
+            format!(
+                "{}.tsv:{}: {:?} → {:?}, expected {:?}",
+                f.corpus, f.line, f.input, f.actual, f.expected
+            )
+        })
+        .collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn telex_corpus() {
+    assert_passes("telex");
+}
+
+#[test]
+fn vni_corpus() {
+    assert_passes("vni");
+}
+
+#[test]
+fn edge_cases_corpus() {
+    assert_passes("edge-cases");
+}
+
+#[test]
+fn bad_option_fails_its_case() {
+    let cases = corpus::parse("@no_such_option=1\nas\tá\n").unwrap();
+    let mut report = Report::default();
+    report.run("inline", &cases, &Engine::new());
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].line, 2);
+}
@@ -0,0 +1,69 @@
+//! Debug trace: why the engine did what it did on the last key
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::type_text;
+
+#[cfg(not(feature = "debug-trace"))]
+#[test]
+fn reasons_are_empty_without_the_feature() {
+    let mut e = Engine::new();
+    type_text(&mut e, "kians");
+    assert!(e.reasons().is_empty());
+}
+
+#[cfg(feature = "debug-trace")]
+mod traced {
+    use super::*;
+
+    fn reasons_after(input: &str) -> Vec<String> {
+        let mut e = Engine::new();
+        type_text(&mut e, input);
+        e.reasons().to_vec()
+    }
+
+    #[test]
+    fn mark_position_is_explained() {
+        // Mark on "i" of "kian", the vowels being at 1 and 2
+        let reasons = reasons_after("kians");
+        assert!(
+            reasons
+                .iter()
+                .any(|r| r.starts_with("mark 1: on position 1") && r.contains("[1, 2]")),
+            "{:?}",
+            reasons
+        );
+    }
+
+    #[test]
+    fn rejected_mark_names_the_rule() {
+        let reasons = reasons_after("hocf");
+        assert!(
+            reasons.iter().any(|r| r.contains("stop final")),
+            "{:?}",
+            reasons
+        );
+    }
+
+    #[test]
+    fn restore_verdict_is_noted() {
+        let mut e = Engine::new();
+        e.set_english_auto_restore(true);
+        type_text(&mut e, "down ");
+        assert!(
+            e.reasons()
+                .iter()
+                .any(|r| r.starts_with("auto-restore on space: restore to \"down\"")),
+            "{:?}",
+            e.reasons()
+        );
+    }
+
+    #[test]
+    fn reasons_cover_only_the_last_key() {
+        let mut e = Engine::new();
+        type_text(&mut e, "as");
+        assert!(!e.reasons().is_empty());
+        type_text(&mut e, "n");
+        assert!(e.reasons().is_empty(), "{:?}", e.reasons());
+    }
+}
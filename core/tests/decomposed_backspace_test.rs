@@ -0,0 +1,112 @@
+//! Decomposed backspace: apps that store text as NFD need a backspace per
+//! code point, so "ấ" (a + U+0302 + U+0301) takes three.
+
+use gonhanh_core::data::chars::{self, mark, parse_char, tone};
+use gonhanh_core::data::{keys, to_char};
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::{Action, Engine, FLAG_DECOMPOSED};
+use gonhanh_core::utils::char_to_key;
+
+/// `c` as the app stores it: base letter, then combining marks
+fn decompose(c: char) -> Vec<char> {
+    let Some(p) = parse_char(c).filter(|p| !p.stroke) else {
+        return vec![c];
+    };
+    let mut out = vec![to_char(p.key, p.caps, 0, 0).unwrap_or(c)];
+    match p.tone {
+        tone::CIRCUMFLEX => out.push('\u{0302}'),
+        tone::HORN if p.key == keys::A => out.push('\u{0306}'),
+        tone::HORN => out.push('\u{031B}'),
+        _ => {}
+    }
+    match p.mark {
+        mark::SAC => out.push('\u{0301}'),
+        mark::HUYEN => out.push('\u{0300}'),
+        mark::HOI => out.push('\u{0309}'),
+        mark::NGA => out.push('\u{0303}'),
+        mark::NANG => out.push('\u{0323}'),
+        _ => {}
+    }
+    out
+}
+
+/// Type `input` into an app storing NFD, one backspace per code point;
+/// returns what it shows, composed again
+fn type_nfd(e: &mut Engine, input: &str) -> String {
+    let mut screen: Vec<char> = Vec::new();
+    for c in input.chars() {
+        let key = char_to_key(c);
+        let r = e.on_key_ext(key, c.is_uppercase(), false, false);
+        if r.action == Action::None as u8 {
+            screen.push(c);
+            continue;
+        }
+        for _ in 0..r.backspace {
+            screen.pop();
+        }
+        for &ch in &r.chars[..r.count as usize] {
+            screen.extend(decompose(char::from_u32(ch).unwrap()));
+        }
+        if keys::is_break(key) && !r.key_consumed() {
+            screen.push(c);
+        }
+    }
+    chars::compose(&screen.into_iter().collect::<String>())
+}
+
+#[test]
+fn deletes_every_code_point() {
+    for (input, expected) in [
+        ("vieetj", "việt"),
+        ("tieengs vieetj ", "tiếng việt "),
+        ("nguowif", "người"),
+        ("ddawngr", "đẳng"),
+        ("hoaf", "hoà"),
+        ("Vieetj Nam", "Việt Nam"),
+    ] {
+        let mut e = Engine::new();
+        e.set_decomposed_backspace(true);
+        assert_eq!(type_nfd(&mut e, input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn flags_only_wider_deletes() {
+    let mut e = Engine::new();
+    e.set_decomposed_backspace(true);
+    for c in "vieet".chars() {
+        e.on_key_ext(char_to_key(c), false, false, false);
+    }
+    // "viêt" + j: "êt" → "ệt", the ê is three code points with its mark
+    let r = e.on_key_ext(keys::J, false, false, false);
+    assert_eq!(r.backspace, 3);
+    assert_ne!(r.flags & FLAG_DECOMPOSED, 0);
+
+    e.clear();
+    let r = e.on_key_ext(keys::A, false, false, false);
+    assert_eq!(r.action, Action::None as u8);
+    let r = e.on_key_ext(keys::S, false, false, false);
+    assert_eq!((r.backspace, r.flags & FLAG_DECOMPOSED), (1, 0));
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    for c in "vieet".chars() {
+        e.on_key_ext(char_to_key(c), false, false, false);
+    }
+    let r = e.on_key_ext(keys::J, false, false, false);
+    assert_eq!((r.backspace, r.flags & FLAG_DECOMPOSED), (2, 0));
+}
+
+#[test]
+fn undo_deletes_every_code_point() {
+    let mut e = Engine::new();
+    e.set_decomposed_backspace(true);
+    e.shortcuts_mut().add(Shortcut::new("vn", "Việt Nam"));
+    type_nfd(&mut e, "vn ");
+    // "Việt Nam " is nine characters, eleven code points
+    let r = e.undo_last();
+    assert_eq!(r.backspace, 11);
+    assert_ne!(r.flags & FLAG_DECOMPOSED, 0);
+}
@@ -8,6 +8,7 @@
 //! - Vowel: words with vowel patterns (aa/ee/oo/aw/ow/uw/dd)
 
 use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::Screen;
 use std::fs;
 
 // =============================================================================
@@ -61,96 +62,14 @@ fn categorize_failure(word: &str) -> FailureCause {
 // KEY SIMULATION
 // =============================================================================
 
-fn char_to_key(c: char) -> u16 {
-    match c.to_ascii_lowercase() {
-        'a' => 0,
-        's' => 1,
-        'd' => 2,
-        'f' => 3,
-        'h' => 4,
-        'g' => 5,
-        'z' => 6,
-        'x' => 7,
-        'c' => 8,
-        'v' => 9,
-        'b' => 11,
-        'q' => 12,
-        'w' => 13,
-        'e' => 14,
-        'r' => 15,
-        'y' => 16,
-        't' => 17,
-        '1' => 18,
-        '2' => 19,
-        '3' => 20,
-        '4' => 21,
-        '6' => 22,
-        '5' => 23,
-        '9' => 25,
-        '7' => 26,
-        '8' => 28,
-        '0' => 29,
-        'o' => 31,
-        'u' => 32,
-        'i' => 34,
-        'p' => 35,
-        'l' => 37,
-        'j' => 38,
-        'k' => 40,
-        'n' => 45,
-        'm' => 46,
-        _ => 255,
-    }
-}
-
 /// Returns (final_output, buffer_before_space)
 fn type_word_with_space(engine: &mut Engine, word: &str) -> (String, String) {
     engine.clear();
-    let mut output = String::new();
-
-    for ch in word.chars() {
-        let key = char_to_key(ch);
-        if key == 255 {
-            output.push(ch);
-            continue;
-        }
-        let result = engine.on_key(key, ch.is_uppercase(), false);
-
-        if result.action == 1 {
-            let bs = result.backspace as usize;
-            for _ in 0..bs.min(output.len()) {
-                output.pop();
-            }
-            for i in 0..result.count as usize {
-                if let Some(c) = char::from_u32(result.chars[i]) {
-                    output.push(c);
-                }
-            }
-        } else {
-            output.push(ch);
-        }
-    }
-
+    let mut screen = Screen::new();
     // Save buffer state before space (Vietnamese transform state)
-    let buffer = output.clone();
-
+    let buffer = screen.type_text(engine, word).text();
     // Type space to trigger auto-restore
-    let result = engine.on_key(49, false, false);
-    if result.action == 1 {
-        let bs = result.backspace as usize;
-        for _ in 0..bs.min(output.len()) {
-            output.pop();
-        }
-        for i in 0..result.count as usize {
-            if let Some(c) = char::from_u32(result.chars[i]) {
-                output.push(c);
-            }
-        }
-    } else {
-        output.push(' ');
-    }
-
-    (output, buffer)
+    (screen.type_text(engine, " ").text(), buffer)
 }
 
 // =============================================================================
@@ -1420,3 +1420,59 @@ fn w_medial_vowel_modifier_pattern() {
         ("banwjg ", "bặng "), // nặng tone
     ]);
 }
+
+// =============================================================================
+// FREQUENCY: COMMON ENGLISH WORD VS SYLLABLE OUTSIDE THE DICTIONARY
+// A valid syllable that is no dictionary word is restored when its keys spell
+// one of the most frequent English words; dictionary words always stay
+// =============================================================================
+
+#[test]
+fn frequency_restores_coincidences() {
+    telex_auto_restore(&[
+        ("her ", "her "),   // hẻ
+        ("down ", "down "), // dơn
+        ("ask ", "ask "),   // ák
+    ]);
+}
+
+#[test]
+fn frequency_keeps_dictionary_words() {
+    telex_auto_restore(&[
+        ("most ", "mót "), // rare, but a word
+        ("must ", "mút "),
+        ("test ", "tét "),
+        ("six ", "sĩ "),
+    ]);
+}
+
+#[test]
+fn frequency_below_threshold_stays() {
+    // Ranked, but not among the most frequent English words
+    telex_auto_restore(&[("town ", "tơn "), ("war ", "ửa "), ("yes ", "yé ")]);
+}
+
+// =============================================================================
+// COMPOUNDS: THE PREVIOUS SYLLABLE AS CONTEXT
+// A syllable completing a common two-syllable word stays Vietnamese even when
+// it would be restored on its own
+// =============================================================================
+
+#[test]
+fn compound_keeps_second_syllable() {
+    telex_auto_restore(&[
+        ("banhs test ", "bánh tét "),
+        ("keoj must ", "kẹo mút "),
+        ("ngawns turn ", "ngắn tủn "),
+        ("thawts now ", "thắt nơ "),
+    ]);
+}
+
+#[test]
+fn compound_needs_adjacent_syllable() {
+    telex_auto_restore(&[
+        ("down ", "down "),              // no previous word
+        ("banhs  down ", "bánh  down "), // two spaces apart
+        ("cos down ", "có down "),       // "có dơn" is not a compound
+    ]);
+}
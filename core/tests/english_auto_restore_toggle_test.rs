@@ -2,7 +2,11 @@
 //! Verifies that when feature is OFF, NO auto-restore happens
 //! and when ON, all auto-restore patterns work correctly.
 
-use gonhanh_core::engine::Engine;
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{
+    Engine, RESTORE_ON_ALL, RESTORE_ON_ENTER, RESTORE_ON_PUNCTUATION, RESTORE_ON_SPACE,
+    RESTORE_ON_TAB,
+};
 use gonhanh_core::utils::type_word;
 
 // =============================================================================
@@ -244,3 +248,67 @@ fn edge_case_fox_transforms_when_off() {
     println!("OFF: 'fox ' -> '{}'", result);
     // The key point: when OFF, no auto-restore should happen
 }
+
+// =============================================================================
+// TEST: TRIGGER SET (Space / Tab / Enter / punctuation)
+// =============================================================================
+
+/// Type "user" then commit it with `key`; returns the replacement, if any
+fn commit_user_with(triggers: u8, key: u16) -> Option<String> {
+    let mut e = engine_on();
+    e.set_auto_restore_triggers(triggers);
+    type_word(&mut e, "user");
+    let r = e.on_key(key, false, false);
+    (r.action != 0).then(|| {
+        r.chars[..r.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect()
+    })
+}
+
+#[test]
+fn triggers_default_to_every_boundary() {
+    assert_eq!(
+        commit_user_with(RESTORE_ON_ALL, keys::SPACE).as_deref(),
+        Some("user ")
+    );
+    for key in [keys::TAB, keys::RETURN, keys::ENTER, keys::COMMA, keys::DOT] {
+        assert_eq!(
+            commit_user_with(RESTORE_ON_ALL, key).as_deref(),
+            Some("user"),
+            "key {}",
+            key
+        );
+    }
+}
+
+#[test]
+fn triggers_outside_set_keep_word() {
+    let triggers = RESTORE_ON_SPACE | RESTORE_ON_ENTER;
+    assert_eq!(
+        commit_user_with(triggers, keys::SPACE).as_deref(),
+        Some("user ")
+    );
+    assert_eq!(
+        commit_user_with(triggers, keys::RETURN).as_deref(),
+        Some("user")
+    );
+    assert_eq!(commit_user_with(triggers, keys::TAB), None);
+    assert_eq!(commit_user_with(triggers, keys::COMMA), None);
+}
+
+#[test]
+fn triggers_each_bit_selects_its_keys() {
+    assert_eq!(
+        commit_user_with(RESTORE_ON_TAB, keys::TAB).as_deref(),
+        Some("user")
+    );
+    assert_eq!(commit_user_with(RESTORE_ON_TAB, keys::SPACE), None);
+    assert_eq!(
+        commit_user_with(RESTORE_ON_PUNCTUATION, keys::SLASH).as_deref(),
+        Some("user")
+    );
+    assert_eq!(commit_user_with(RESTORE_ON_PUNCTUATION, keys::ENTER), None);
+    assert_eq!(commit_user_with(0, keys::SPACE), None);
+}
@@ -0,0 +1,47 @@
+//! Capital "i" in English text
+//!
+//! A standalone "i" becomes "I" at the word boundary when the last word
+//! with transforms was restored to English. Vietnamese words end the
+//! English context; words without transforms leave it as it was.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_english_capital_i(true);
+    e
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    assert_eq!(type_word(&mut e, "text i "), "text i ");
+}
+
+#[test]
+fn capitalizes_after_english_word() {
+    let mut e = engine();
+    assert_eq!(type_word(&mut e, "text i "), "text I ");
+    assert_eq!(type_word(&mut e, "and i'm"), "and I'm");
+    assert_eq!(type_word(&mut e, " in ik "), " in ik ");
+}
+
+#[test]
+fn needs_english_context() {
+    let mut e = engine();
+    assert_eq!(type_word(&mut e, "i "), "i ");
+    assert_eq!(type_word(&mut e, "text vieetj i "), "text việt i ");
+}
+
+#[test]
+fn while_english_suspended() {
+    let mut e = engine();
+    e.set_auto_english_switch(2);
+    type_word(&mut e, "text user ");
+    assert!(e.english_suspended());
+    e.clear_all();
+    assert_eq!(type_word(&mut e, "so i "), "so I ");
+}
@@ -0,0 +1,57 @@
+//! Temporary English key: while held, keys pass through untouched and the
+//! Vietnamese state survives for the rest of the sentence.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+/// Type `before`, hold the key for `held`, release and type `after`
+fn type_held(e: &mut Engine, before: &str, held: &str, after: &str) -> String {
+    let mut screen = type_word(e, before);
+    e.set_english_hold(true);
+    screen.push_str(&type_word(e, held));
+    e.set_english_hold(false);
+    screen.push_str(&type_word(e, after));
+    screen
+}
+
+#[test]
+fn keys_pass_through_while_held() {
+    let mut e = Engine::new();
+    assert_eq!(
+        type_held(&mut e, "dungf ", "tex", " mowis "),
+        "dùng tex mới "
+    );
+    assert_eq!(type_held(&mut e, "", "aas", ""), "aas");
+}
+
+#[test]
+fn state_survives_empty_hold() {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    assert_eq!(type_held(&mut e, "xong. ", "", "ok"), "xong. Ok");
+    e.clear_all();
+    type_held(&mut e, "vie", "", "es");
+    assert_eq!(e.get_buffer_string(), "viế");
+}
+
+#[test]
+fn english_word_takes_pending_capital() {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    assert_eq!(
+        type_held(&mut e, "xong. ", "iPhone", " mowis"),
+        "xong. iPhone mới"
+    );
+}
+
+#[test]
+fn typing_while_held_ends_word_at_cursor() {
+    let mut e = Engine::new();
+    assert_eq!(type_held(&mut e, "vie", "X", "e"), "vieXe");
+    assert_eq!(e.get_buffer_string(), "e");
+
+    // Backspace no longer restores the word before the English text
+    e.clear_all();
+    type_held(&mut e, "dungf ", "x", "<");
+    assert_eq!(e.get_buffer_string(), "");
+}
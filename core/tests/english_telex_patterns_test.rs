@@ -17,6 +17,7 @@
 //! Run with: cargo test --test english_telex_patterns_test -- --nocapture
 
 use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::type_text;
 use std::fs;
 
 // =============================================================================
@@ -91,9 +92,8 @@ fn has_valid_vn_initial(word: &str) -> bool {
     ];
 
     for initial in valid_initials {
-        if lower.starts_with(initial) {
+        if let Some(rest) = lower.strip_prefix(initial) {
             // Check what follows the initial
-            let rest = &lower[initial.len()..];
             if rest.is_empty() {
                 return true;
             }
@@ -194,7 +194,7 @@ fn insert_cancel_char(word: &str, pattern: &str, cancel_char: char) -> String {
             // In this case, 'w' creates horn/breve, not circumflex, so no cancel needed
             // Examples: harwood (w+oo), biweekly (w+ee), sapwood (w+oo)
             let preceded_by_w = i > 0
-                && chars[i - 1].to_ascii_lowercase() == 'w'
+                && chars[i - 1].eq_ignore_ascii_case(&'w')
                 && matches!(pattern, "aa" | "ee" | "oo");
 
             if preceded_by_w {
@@ -256,93 +256,10 @@ fn replace_pattern_preserve_case(text: &str, pattern: &str, replacement: &str) -
 // KEY SIMULATION
 // =============================================================================
 
-fn char_to_key(c: char) -> u16 {
-    match c.to_ascii_lowercase() {
-        'a' => 0,
-        's' => 1,
-        'd' => 2,
-        'f' => 3,
-        'h' => 4,
-        'g' => 5,
-        'z' => 6,
-        'x' => 7,
-        'c' => 8,
-        'v' => 9,
-        'b' => 11,
-        'q' => 12,
-        'w' => 13,
-        'e' => 14,
-        'r' => 15,
-        'y' => 16,
-        't' => 17,
-        '1' => 18,
-        '2' => 19,
-        '3' => 20,
-        '4' => 21,
-        '6' => 22,
-        '5' => 23,
-        '9' => 25,
-        '7' => 26,
-        '8' => 28,
-        '0' => 29,
-        'o' => 31,
-        'u' => 32,
-        'i' => 34,
-        'p' => 35,
-        'l' => 37,
-        'j' => 38,
-        'k' => 40,
-        'n' => 45,
-        'm' => 46,
-        _ => 255,
-    }
-}
-
 fn type_word_with_space(engine: &mut Engine, word: &str) -> String {
     engine.clear();
-    let mut output = String::new();
-
-    // Type the word
-    for ch in word.chars() {
-        let key = char_to_key(ch);
-        if key == 255 {
-            output.push(ch);
-            continue;
-        }
-        let result = engine.on_key(key, ch.is_uppercase(), false);
-
-        if result.action == 1 {
-            let bs = result.backspace as usize;
-            for _ in 0..bs.min(output.len()) {
-                output.pop();
-            }
-            for i in 0..result.count as usize {
-                if let Some(c) = char::from_u32(result.chars[i]) {
-                    output.push(c);
-                }
-            }
-        } else {
-            output.push(ch);
-        }
-    }
-
-    // Type space to trigger auto-restore
-    let result = engine.on_key(49, false, false); // 49 = SPACE key
-    if result.action == 1 {
-        let bs = result.backspace as usize;
-        for _ in 0..bs.min(output.len()) {
-            output.pop();
-        }
-        for i in 0..result.count as usize {
-            if let Some(c) = char::from_u32(result.chars[i]) {
-                output.push(c);
-            }
-        }
-    } else {
-        output.push(' ');
-    }
-
-    output
+    // Space triggers auto-restore
+    type_text(engine, &format!("{} ", word))
 }
 
 // =============================================================================
@@ -13,6 +13,7 @@
 //! - Invalid final clusters: T+R, P+R, C+R patterns
 
 use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::type_text;
 
 fn assert_no_transform(words: &[&str]) {
     let mut telex = Engine::new();
@@ -20,27 +21,7 @@ fn assert_no_transform(words: &[&str]) {
 
     for word in words {
         telex.clear();
-        let mut output = String::new();
-
-        for ch in word.chars() {
-            let key = char_to_key(ch);
-            let result = telex.on_key(key, ch.is_uppercase(), false);
-
-            if result.action == 1 {
-                let bs = result.backspace as usize;
-                for _ in 0..bs.min(output.len()) {
-                    output.pop();
-                }
-                for i in 0..result.count as usize {
-                    if let Some(c) = char::from_u32(result.chars[i]) {
-                        output.push(c);
-                    }
-                }
-            } else {
-                output.push(ch);
-            }
-        }
-
+        let output = type_text(&mut telex, word);
         assert_eq!(output, *word, "'{}' → '{}'", word, output);
     }
 }
@@ -53,91 +34,13 @@ fn assert_auto_restore(words: &[&str]) {
 
     for word in words {
         telex.clear();
-        let mut output = String::new();
-
-        // Type the word
-        for ch in word.chars() {
-            let key = char_to_key(ch);
-            let result = telex.on_key(key, ch.is_uppercase(), false);
-
-            if result.action == 1 {
-                let bs = result.backspace as usize;
-                for _ in 0..bs.min(output.len()) {
-                    output.pop();
-                }
-                for i in 0..result.count as usize {
-                    if let Some(c) = char::from_u32(result.chars[i]) {
-                        output.push(c);
-                    }
-                }
-            } else {
-                output.push(ch);
-            }
-        }
-
-        // Type space to trigger auto-restore
-        let result = telex.on_key(49, false, false); // 49 = SPACE key
-        if result.action == 1 {
-            let bs = result.backspace as usize;
-            for _ in 0..bs.min(output.len()) {
-                output.pop();
-            }
-            for i in 0..result.count as usize {
-                if let Some(c) = char::from_u32(result.chars[i]) {
-                    output.push(c);
-                }
-            }
-        } else {
-            output.push(' ');
-        }
-
+        // Space triggers auto-restore
+        let output = type_text(&mut telex, &format!("{} ", word));
         let expected = format!("{} ", word);
         assert_eq!(output, expected, "'{}' → '{}'", word, output);
     }
 }
 
-fn char_to_key(c: char) -> u16 {
-    match c.to_ascii_lowercase() {
-        'a' => 0,
-        's' => 1,
-        'd' => 2,
-        'f' => 3,
-        'h' => 4,
-        'g' => 5,
-        'z' => 6,
-        'x' => 7,
-        'c' => 8,
-        'v' => 9,
-        'b' => 11,
-        'q' => 12,
-        'w' => 13,
-        'e' => 14,
-        'r' => 15,
-        'y' => 16,
-        't' => 17,
-        '1' => 18,
-        '2' => 19,
-        '3' => 20,
-        '4' => 21,
-        '6' => 22,
-        '5' => 23,
-        '9' => 25,
-        '7' => 26,
-        '8' => 28,
-        '0' => 29,
-        'o' => 31,
-        'u' => 32,
-        'i' => 34,
-        'p' => 35,
-        'l' => 37,
-        'j' => 38,
-        'k' => 40,
-        'n' => 45,
-        'm' => 46,
-        _ => 255,
-    }
-}
-
 // =============================================================================
 // INVALID INITIALS - bl, br, cl, cr, dr, fl, fr, gl, gr, pl, pr, sc, sk, sl, sm, sn, sp, st
 // (Excludes sw-, tw-, wr- vì bị transform thành sư-, tư-, ư-)
@@ -0,0 +1,64 @@
+//! Randomized key-sequence invariants
+//!
+//! Deterministic, std-only counterpart of the cargo-fuzz target in `fuzz/`:
+//! sets random options, feeds pseudo-random keycode/caps/ctrl sequences
+//! (DELETE and break keys interleaved) and checks `assert_key_invariants`
+//! after every key.
+
+use gonhanh_core::engine::{settings, Engine};
+use gonhanh_core::utils::{
+    assert_key_invariants, engine_from_bytes, key_events_from_bytes, FUZZ_FLAGS, FUZZ_NUMBERS,
+    FUZZ_OPTION_BYTES,
+};
+
+/// xorshift64 - good enough to spread keys, reproducible across runs
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+#[test]
+fn random_sequences_keep_invariants() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..10000 {
+        let len = FUZZ_OPTION_BYTES + 2 * (1 + rng.next() as usize % 48);
+        let data = rng.bytes(len);
+        let (mut e, keys) = engine_from_bytes(&data);
+        assert_key_invariants(&mut e, &key_events_from_bytes(keys));
+    }
+}
+
+#[test]
+fn long_sequence_without_breaks_stays_bounded() {
+    // 600 letters with no word boundary must not overflow the buffer
+    let data: Vec<u8> = (0..1200)
+        .map(|i| if i % 2 == 0 { (i / 2 % 26) as u8 } else { 0 })
+        .collect();
+    let mut e = Engine::new();
+    assert_key_invariants(&mut e, &key_events_from_bytes(&data));
+}
+
+#[test]
+fn fuzzing_sets_every_option() {
+    // Keycode options only move keys the fuzzer already presses
+    let keycodes = ["restore_key", "remove_key_telex", "remove_key_vni"];
+    for key in settings::option_keys() {
+        assert!(
+            keycodes.contains(&key)
+                || FUZZ_FLAGS.contains(&key)
+                || FUZZ_NUMBERS.iter().any(|&(name, _)| name == key),
+            "{} is not fuzzed",
+            key
+        );
+    }
+}
@@ -3,7 +3,8 @@
 mod common;
 use common::{assert_action, assert_passthrough, telex, type_word};
 use gonhanh_core::data::keys;
-use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::engine::{Action, DigitPolicy, Engine};
+use rstest::rstest;
 
 // ============================================================
 // ENGINE STATE: Enable/Disable
@@ -71,6 +72,42 @@ fn ctrl_clears_buffer() {
     assert_passthrough(&mut e, keys::S);
 }
 
+#[test]
+fn ctrl_backspace_mid_word_keeps_previous_word() {
+    let mut e = Engine::new();
+    type_word(&mut e, "xin chaf");
+    // The app deletes "chà"; "xin " is still on screen
+    assert_action(&mut e, keys::DELETE, false, true, Action::None);
+    // Backspace over the space brings "xin" back for editing
+    let r = e.on_key(keys::DELETE, false, false);
+    assert_eq!((r.action, r.backspace), (Action::Send as u8, 1));
+    let r = e.on_key(keys::F, false, false);
+    let chars: String = r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect();
+    assert_eq!((r.backspace, chars.as_str()), (2, "ìn"));
+}
+
+#[test]
+fn ctrl_backspace_after_space_forgets_history() {
+    let mut e = Engine::new();
+    type_word(&mut e, "an em ");
+    // The app deletes "em " too: nothing before the caret is known
+    assert_action(&mut e, keys::DELETE, false, true, Action::None);
+    assert_passthrough(&mut e, keys::DELETE);
+    assert_passthrough(&mut e, keys::S);
+}
+
+#[test]
+fn ctrl_backspace_ends_current_word() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieet");
+    assert_action(&mut e, keys::DELETE, false, true, Action::None);
+    // "j" starts a new word instead of toning the deleted one
+    assert_passthrough(&mut e, keys::J);
+}
+
 // ============================================================
 // METHOD SWITCHING: Telex <-> VNI
 // ============================================================
@@ -191,7 +228,7 @@ fn telex_w_passthrough_after_invalid_consonant() {
 #[test]
 fn telex_ww_reverts() {
     // "ww" → revert to "w" (shortcut skipped)
-    // User typing pattern: w→ư, ww→w, www→ww
+    // User typing pattern: w→ư, ww→w, www→www
     let mut e = Engine::new();
 
     // First w → ư
@@ -206,7 +243,13 @@ fn telex_ww_reverts() {
     assert_eq!(result.count, 1); // output "w"
     assert_eq!(result.chars[0], 'w' as u32);
 
-    // Third w → just adds w (shortcut was skipped, not retried)
+    // Third w → brings back the w consumed by the revert: "www"
+    let result = e.on_key(keys::W, false, false);
+    assert_eq!(result.action, 1);
+    assert_eq!(result.backspace, 0);
+    assert_eq!(result.count, 2);
+
+    // Fourth w → just adds w (shortcut was skipped, not retried)
     let result = e.on_key(keys::W, false, false);
     assert_eq!(result.action, 0); // Pass through (normal letter)
 }
@@ -757,7 +800,7 @@ fn telex_shift_not_affected() {
 // SHORTCUT TESTS
 // ============================================================
 
-use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::shortcut::{AppScope, Shortcut};
 
 #[test]
 fn shortcut_triggers_on_space() {
@@ -913,6 +956,47 @@ fn shortcut_removed_does_not_trigger() {
     assert_eq!(result, "vn ", "removed shortcut should not trigger");
 }
 
+/// Triggers with Vietnamese letters match the composed word
+#[rstest]
+#[case(0, "DDc ", "Được ")]
+#[case(1, "d9c ", "được ")]
+#[case(0, "ddc.", "được.")]
+fn shortcut_vietnamese_trigger(#[case] method: u8, #[case] input: &str, #[case] expected: &str) {
+    let mut e = Engine::new();
+    e.set_method(method);
+    e.shortcuts_mut().add(Shortcut::new("đc", "được"));
+    assert_eq!(type_word(&mut e, input), expected);
+}
+
+/// Shortcuts scoped to apps follow the host's frontmost app
+#[test]
+fn shortcut_per_app_scope() {
+    let mut e = Engine::new();
+    e.shortcuts_mut()
+        .add(Shortcut::new("kg", "Kính gửi").in_apps(AppScope::from_lists("outlook.exe", "")));
+    e.shortcuts_mut()
+        .add(Shortcut::new("vn", "Việt Nam").in_apps(AppScope::from_lists("", "wt.exe, cmd.exe")));
+
+    e.shortcuts_mut().set_active_app(Some("outlook.exe"));
+    assert_eq!(type_word(&mut e, "kg "), "Kính gửi ");
+    assert_eq!(type_word(&mut e, "vn "), "Việt Nam ");
+
+    e.shortcuts_mut().set_active_app(Some("WT.exe"));
+    assert_eq!(type_word(&mut e, "kg "), "kg ");
+    assert_eq!(type_word(&mut e, "vn "), "vn ");
+}
+
+/// Decomposed triggers (o + U+0302) are stored precomposed
+#[test]
+fn shortcut_decomposed_trigger() {
+    let mut e = Engine::new();
+    e.shortcuts_mut()
+        .add(Shortcut::new("bie\u{0302}\u{0301}t", "biết rồi"));
+    assert_eq!(type_word(&mut e, "bieets "), "biết rồi ");
+    e.shortcuts_mut().remove("bie\u{0302}\u{0301}t");
+    assert_eq!(type_word(&mut e, "bieets "), "biết ");
+}
+
 #[test]
 fn shortcut_clear_all() {
     let mut e = Engine::new();
@@ -1634,6 +1718,68 @@ fn skip_w_shortcut_multiple_words() {
     assert_eq!(result3, "ư");
 }
 
+// ============================================================
+// INITIAL W LITERAL: w stays w until a consonant needs a vowel
+// ============================================================
+
+#[rstest]
+#[case("w", "w")]
+#[case("www", "www")]
+#[case("web ", "web ")]
+#[case("wa ", "wa ")]
+#[case("Wow ", "Wow ")]
+#[case("nhw ", "như ")]
+#[case("twf ", "từ ")]
+#[case("nhwngx ", "những ")]
+#[case("uwng ", "ưng ")]
+fn initial_w_literal(#[case] input: &str, #[case] expected: &str) {
+    let mut e = Engine::new();
+    e.set_initial_w_literal(true);
+    assert_eq!(type_word(&mut e, input), expected);
+}
+
+// ============================================================
+// SIMPLE TELEX: w only as horn/breve, brackets give ư/ơ
+// ============================================================
+
+#[rstest]
+#[case("w", "w")]
+#[case("nhw", "nhw")]
+#[case("web", "web")]
+#[case("uw", "ư")]
+#[case("nhuwngx", "những")]
+#[case("muwowjt", "mượt")]
+#[case("nhuwf", "nhừ")]
+#[case("awn", "ăn")]
+#[case("nh]", "như")]
+#[case("t]f", "từ")]
+#[case("m[", "mơ")]
+#[case("]]", "]")]
+#[case("tieengs Vieetj", "tiếng Việt")]
+fn simple_telex(#[case] input: &str, #[case] expected: &str) {
+    let mut e = Engine::new();
+    e.set_method(2);
+    assert_eq!(e.method(), 2);
+    assert_eq!(type_word(&mut e, input), expected);
+}
+
+/// "www" types as "www" in default mode too (w → ư, ww → w, www → www)
+#[rstest]
+#[case("www", "www")]
+#[case("wwww", "wwww")]
+#[case("Www.", "Www.")]
+#[case("wwax", "wax")]
+fn triple_w_stays_literal(#[case] input: &str, #[case] expected: &str) {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, input), expected);
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    assert_eq!(
+        type_word(&mut e, &format!("{} ", input)),
+        format!("{} ", expected)
+    );
+}
+
 // ============================================================
 // BACKSPACE-AFTER-SPACE: Issue #32
 // ============================================================
@@ -2023,10 +2169,10 @@ fn backspace_after_space_with_number_in_word() {
 fn backspace_after_space_vni_multiple_words() {
     let mut e = Engine::new();
     e.set_method(1); // VNI
-                     // VNI: 6=circumflex, 9=stroke(đ), 5=hỏi, 2=huyền
-                     // "to6i d9i ho5c" + space + backspace + "2" → change hỏc → hòc
-    let result = type_word(&mut e, "to6i d9i ho5c <2");
-    assert_eq!(result, "tôi đi hòc", "VNI multi-word restore should work");
+                     // VNI: 6=circumflex, 9=stroke(đ), 1=sắc, 5=nặng
+                     // "to6i d9i ho1c" + space + backspace + "5" → change hóc → học
+    let result = type_word(&mut e, "to6i d9i ho1c <5");
+    assert_eq!(result, "tôi đi học", "VNI multi-word restore should work");
 }
 
 /// Uppercase in middle of word
@@ -2449,9 +2595,9 @@ fn restore_word_with_horn() {
 #[test]
 fn restore_word_uppercase() {
     let mut e = Engine::new();
-    let result = restore_and_type(&mut e, "Việt", "f");
-    // Typing 'f' (huyền) should change ệ to ề
-    assert_eq!(result, "Viềt", "Should change mark on uppercase word");
+    let result = restore_and_type(&mut e, "Việt", "s");
+    // Typing 's' (sắc) should change ệ to ế
+    assert_eq!(result, "Viết", "Should change mark on uppercase word");
 }
 
 /// restore_word empty string
@@ -2497,6 +2643,7 @@ fn restore_word_then_extend() {
 /// After backspacing into "shortcuts", typing "Nuw" should produce:
 /// - Internal buffer: "Nư" (buffer cleared on consonant 'N', then fresh typing)
 /// - Screen: "shortcutsNư" (screen keeps restored word, adds transformed output)
+///
 /// The key fix: "uw" → "ư" transformation now works after restore
 #[test]
 fn restore_word_non_vietnamese_then_type_new() {
@@ -3801,3 +3948,435 @@ fn test_abc_space_ook_space() {
         "abc ook should give 'abc ôk ' (circumflex from double vowel preserved)"
     );
 }
+
+// ============================================================
+// HYPHEN SOFT BOUNDARY
+// ============================================================
+
+fn hyphen_engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_hyphen_soft_boundary(true);
+    e
+}
+
+/// Each segment is typed independently
+#[test]
+fn hyphen_soft_boundary_segments() {
+    let mut e = hyphen_engine();
+    assert_eq!(type_word(&mut e, "Vieejt-Nam"), "Việt-Nam");
+}
+
+/// Backspace over the hyphen restores the previous segment for tone edits
+#[test]
+fn hyphen_soft_boundary_backspace_restores_segment() {
+    let mut e = hyphen_engine();
+    assert_eq!(type_word(&mut e, "nha-<f"), "nhà");
+    let mut e = hyphen_engine();
+    assert_eq!(type_word(&mut e, "an-em-<j"), "an-ẹm");
+}
+
+/// Option off: hyphen ends the word, Backspace is a plain delete
+#[test]
+fn hyphen_hard_boundary_by_default() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "nha-<f"), "nhaf");
+}
+
+/// Shortcuts starting with '-' still work after a segment
+#[test]
+fn hyphen_soft_boundary_keeps_arrow_shortcut() {
+    let mut e = hyphen_engine();
+    e.shortcuts_mut().add(Shortcut::immediate("->", "→"));
+    assert_eq!(type_word(&mut e, "ab->"), "ab→");
+}
+
+// ============================================================
+// URL / EMAIL / PATH PASSTHROUGH
+// ============================================================
+
+#[rstest]
+#[case("www.google.com", "www.google.com")]
+#[case("Www.Google.com", "Www.Google.com")]
+#[case("https://github.com/rust-lang", "https://github.com/rust-lang")]
+#[case("user@gmail.com", "user@gmail.com")]
+#[case("/usr/bin/vim", "/usr/bin/vim")]
+#[case("~/docs/tieeng", "~/docs/tieeng")]
+#[case("C:\\Users\\dduwowng", "C:\\Users\\dduwowng")]
+#[case("../users", "../users")]
+fn url_passthrough(#[case] input: &str, #[case] expected: &str) {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, input), expected);
+}
+
+/// Transforms resume after whitespace
+#[test]
+fn url_passthrough_ends_at_space() {
+    let mut e = Engine::new();
+    assert_eq!(
+        type_word(&mut e, "xem www.vnexpress.net nhes"),
+        "xem www.vnexpress.net nhé"
+    );
+}
+
+/// Slash between words is not a path
+#[test]
+fn url_passthrough_ignores_word_slash() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "cos/khoong"), "có/không");
+}
+
+#[test]
+fn url_passthrough_disabled() {
+    let mut e = Engine::new();
+    e.set_url_passthrough(false);
+    assert_eq!(type_word(&mut e, "/usr"), "/ủ");
+}
+
+// ============================================================
+// CODE-CONTEXT MODE
+// ============================================================
+
+fn code_engine(method: u8) -> Engine {
+    let mut e = Engine::new();
+    e.set_method(method);
+    e.set_code_context(true);
+    e
+}
+
+#[rstest]
+#[case("get_user", "get_user")]
+#[case("_private", "_private")]
+#[case("getUser", "getUser")]
+#[case("userName", "userName")]
+#[case("utf8", "utf8")]
+#[case("h264", "h264")]
+#[case("3dee", "3dee")]
+#[case("vieejt getUser tieengs", "việt getUser tiếng")]
+#[case("Vieejt Nam", "Việt Nam")]
+fn code_context_telex(#[case] input: &str, #[case] expected: &str) {
+    let mut e = code_engine(0);
+    assert_eq!(type_word(&mut e, input), expected);
+}
+
+/// Digits are VNI tone keys, not identifier markers
+#[test]
+fn code_context_vni_keeps_digit_tones() {
+    let mut e = code_engine(1);
+    assert_eq!(type_word(&mut e, "vie65t user_id"), "việt user_id");
+}
+
+#[test]
+fn code_context_off_by_default() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "userName"), "uẻName");
+}
+
+// ============================================================
+// DIGIT POLICY
+// ============================================================
+
+#[rstest]
+#[case("q1nhaf", "q1nhaf", "q1nhà")]
+#[case("k3anhs", "k3anhs", "k3ánh")]
+#[case("abc123s", "abc123s", "abc123s")]
+#[case("70000 ddoongf", "70000 đồng", "70000 đồng")]
+fn digit_policy_telex(#[case] input: &str, #[case] in_word: &str, #[case] boundary: &str) {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, input), in_word);
+    let mut e = Engine::new();
+    e.set_digit_policy(DigitPolicy::Boundary);
+    assert_eq!(type_word(&mut e, input), boundary);
+}
+
+/// VNI digits stay tone keys under either policy
+#[test]
+fn digit_policy_vni_unaffected() {
+    let mut e = Engine::new();
+    e.set_method(1);
+    e.set_digit_policy(DigitPolicy::Boundary);
+    assert_eq!(type_word(&mut e, "vie65t"), "việt");
+}
+
+// ============================================================
+// SYMBOL PACK
+// ============================================================
+
+fn symbol_engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_symbol_pack(true);
+    e
+}
+
+#[rstest]
+#[case("->", "→")]
+#[case("=>", "⇒")]
+#[case(">=", "≥")]
+#[case("!=", "≠")]
+#[case("+-", "±")]
+#[case("~=", "≈")]
+#[case("a->b", "a→b")]
+fn symbol_pack(#[case] input: &str, #[case] expected: &str) {
+    let mut e = symbol_engine();
+    assert_eq!(type_word(&mut e, input), expected);
+}
+
+/// '<' is Backspace in type_word, so type "<=" as keys
+#[test]
+fn symbol_pack_less_equal() {
+    let mut e = symbol_engine();
+    e.on_key_ext(keys::COMMA, false, false, true);
+    let r = e.on_key_ext(keys::EQUAL, false, false, false);
+    assert!(r.key_consumed());
+    assert_eq!((r.backspace, r.count), (1, 1));
+    assert_eq!(char::from_u32(r.chars[0]), Some('≤'));
+}
+
+#[test]
+fn symbol_pack_off_by_default() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "->"), "->");
+}
+
+/// Operators stay literal in code-context mode
+#[test]
+fn symbol_pack_not_in_code_context() {
+    let mut e = symbol_engine();
+    e.set_code_context(true);
+    assert_eq!(type_word(&mut e, "x->y"), "x->y");
+}
+
+/// A user shortcut with the same trigger wins
+#[test]
+fn symbol_pack_user_shortcut_wins() {
+    let mut e = symbol_engine();
+    e.shortcuts_mut().add(Shortcut::immediate("->", "⟶"));
+    assert_eq!(type_word(&mut e, "->"), "⟶");
+}
+
+// ============================================================
+// UNICODE CODE-POINT INPUT
+// ============================================================
+
+fn unicode_engine(method: u8) -> Engine {
+    let mut e = Engine::new();
+    e.set_method(method);
+    e.set_unicode_input(true);
+    e
+}
+
+#[rstest]
+#[case("u+1ec7 ", "ệ")]
+#[case("U+1EC7 ", "ệ")]
+#[case("u+41 ", "A")]
+#[case("u+1f600 ", "😀")]
+#[case("xem u+2192 ddi", "xem →đi")]
+#[case("u+d800 ", "u+d800 ")]
+#[case("u+1ec7x", "u+1ec7x")]
+#[case("u+1ec7<8 ", "Ỉ")]
+fn unicode_input_telex(#[case] input: &str, #[case] expected: &str) {
+    let mut e = unicode_engine(0);
+    assert_eq!(type_word(&mut e, input), expected);
+}
+
+/// VNI digits in the code point are hex, not tone keys
+#[test]
+fn unicode_input_vni() {
+    let mut e = unicode_engine(1);
+    assert_eq!(type_word(&mut e, "u+1e61 "), "ṡ");
+}
+
+#[test]
+fn unicode_input_off_by_default() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "u+41 "), "u+41 ");
+}
+
+// ============================================================
+// SHORTCUT PLACEHOLDERS
+// ============================================================
+
+#[test]
+fn shortcut_placeholder_clipboard() {
+    let mut e = Engine::new();
+    e.shortcuts_mut()
+        .add(Shortcut::new("dc", "địa chỉ: %clipboard%"));
+    e.set_clipboard_text(Some("12 Tràng Tiền"));
+    assert_eq!(type_word(&mut e, "dc "), "địa chỉ: 12 Tràng Tiền ");
+    e.set_clipboard_text(None);
+    assert_eq!(type_word(&mut e, "dc "), "địa chỉ:  ");
+}
+
+#[test]
+fn shortcut_placeholder_date() {
+    let mut e = Engine::new();
+    e.set_utc_offset(7 * 60);
+    e.shortcuts_mut().add(Shortcut::new("nay", "%date%"));
+    let date = type_word(&mut e, "nay ");
+    let bytes = date.as_bytes();
+    assert_eq!(date.len(), 11, "dd/mm/yyyy + space: {}", date);
+    assert_eq!((bytes[2], bytes[5]), (b'/', b'/'));
+}
+
+/// MatchCase uppercases the text around the placeholder, not its value
+#[test]
+fn shortcut_placeholder_match_case() {
+    let mut e = Engine::new();
+    e.shortcuts_mut()
+        .add(Shortcut::new("cb", "ghi: %clipboard%"));
+    e.set_clipboard_text(Some("abc"));
+    assert_eq!(type_word(&mut e, "CB "), "GHI: abc ");
+}
+
+// ============================================================
+// SHORTCUT CURSOR MARKER
+// ============================================================
+
+/// Type `trigger`, then Space; returns the Space result and cursor offset
+fn expand_with_space(e: &mut Engine, trigger: &str) -> (String, bool, u8) {
+    type_word(e, trigger);
+    let r = e.on_key_ext(keys::SPACE, false, false, false);
+    let output: String = (0..r.count as usize)
+        .filter_map(|i| char::from_u32(r.chars[i]))
+        .collect();
+    (output, r.cursor_moved(), e.cursor_offset())
+}
+
+#[test]
+fn shortcut_cursor_marker_pipe() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("kg", "Kính gửi |,"));
+    // Caret lands before ", " (the trigger space follows the replacement)
+    assert_eq!(
+        expand_with_space(&mut e, "kg"),
+        ("Kính gửi , ".to_string(), true, 2)
+    );
+}
+
+#[test]
+fn shortcut_cursor_marker_named() {
+    let mut e = Engine::new();
+    e.shortcuts_mut()
+        .add(Shortcut::new("ht", "<b>%cursor%</b> | x"));
+    assert_eq!(
+        expand_with_space(&mut e, "ht"),
+        ("<b></b> | x ".to_string(), true, 9)
+    );
+}
+
+/// Two `|` are literal text: no marker, caret stays at the end
+#[test]
+fn shortcut_cursor_marker_literal_pipes() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("hl", "a || b"));
+    assert_eq!(
+        expand_with_space(&mut e, "hl"),
+        ("a || b ".to_string(), false, 0)
+    );
+}
+
+/// The caret moved, so the next word starts fresh
+#[test]
+fn shortcut_cursor_marker_resets_word() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("kg", "Kính gửi |,"));
+    expand_with_space(&mut e, "kg");
+    assert_eq!(type_word(&mut e, "anh"), "anh");
+    let r = e.on_key_ext(keys::DELETE, false, false, false);
+    assert!(!r.cursor_moved());
+}
+
+// ============================================================
+// SNIPPET FIELDS
+// ============================================================
+
+/// Apply a result to `screen` (host side of `type_word`)
+fn apply(screen: &mut String, r: &gonhanh_core::engine::Result) {
+    for _ in 0..r.backspace {
+        screen.pop();
+    }
+    screen.extend((0..r.count as usize).filter_map(|i| char::from_u32(r.chars[i])));
+}
+
+#[test]
+fn snippet_fields_walk() {
+    let mut e = Engine::new();
+    e.shortcuts_mut()
+        .add(Shortcut::new("kg", "Kính gửi ${1:anh}, ngày ${2}."));
+    type_word(&mut e, "kg");
+    let mut screen = "kg".to_string();
+    let r = e.on_key_ext(keys::SPACE, false, false, false);
+    apply(&mut screen, &r);
+    assert_eq!(screen, "Kính gửi anh, ngày . ");
+    assert!(r.snippet_started());
+
+    // Field 1: "anh" selected, 12 chars left of the caret
+    let jump = e.snippet_next().unwrap();
+    assert_eq!((jump.caret, jump.select), (-12, 3));
+    // Typing "chij" replaces the selection with "chị"
+    assert_eq!(type_word(&mut e, "chij"), "chị");
+    assert!(e.snippet_active());
+
+    // Field 2, then the end of the expansion
+    let jump = e.snippet_next().unwrap();
+    assert_eq!((jump.caret, jump.select), (7, 0));
+    type_word(&mut e, "5");
+    // End of the expansion is before the trigger space
+    let jump = e.snippet_next().unwrap();
+    assert_eq!((jump.caret, jump.select), (1, 0));
+    assert!(!e.snippet_active());
+    assert!(e.snippet_next().is_none());
+}
+
+#[test]
+fn snippet_ends_on_cursor_move() {
+    let mut e = Engine::new();
+    e.shortcuts_mut()
+        .add(Shortcut::new("dk", "${1:a} và ${2:b}"));
+    type_word(&mut e, "dk ");
+    e.snippet_next();
+    e.on_key_ext(keys::LEFT, false, false, false);
+    assert!(!e.snippet_active());
+
+    type_word(&mut e, " dk ");
+    e.snippet_next();
+    e.clear_all();
+    assert!(!e.snippet_active());
+}
+
+// ============================================================
+// ENGLISH EXCEPTION WORDS
+// ============================================================
+
+#[test]
+fn english_word_left_as_typed_without_auto_restore() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "maps "), "máp ");
+
+    let mut e = Engine::new();
+    e.add_english_word("Maps");
+    assert_eq!(type_word(&mut e, "maps "), "maps ");
+    assert_eq!(type_word(&mut e, "Maps,"), "Maps,");
+    // Other words are still Vietnamese
+    assert_eq!(type_word(&mut e, "mas "), "má ");
+}
+
+#[test]
+fn english_word_overrides_keep_decision() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    assert_eq!(type_word(&mut e, "box "), "bõ ");
+    e.add_english_word("box");
+    assert_eq!(type_word(&mut e, "box "), "box ");
+}
+
+#[test]
+fn english_word_remove_and_clear() {
+    let mut e = Engine::new();
+    e.add_english_word("maps");
+    e.add_english_word("box");
+    assert!(e.remove_english_word("MAPS"));
+    assert!(!e.remove_english_word("maps"));
+    assert_eq!(type_word(&mut e, "maps "), "máp ");
+    e.clear_english_words();
+    assert!(!e.has_english_word("box"));
+}
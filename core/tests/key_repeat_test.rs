@@ -0,0 +1,60 @@
+//! Auto-repeat: a held key ends the word for the IME and its repeats are
+//! typed as-is (`RepeatPolicy::Literal`, default), or each repeat is a
+//! separate press (`RepeatPolicy::Press`)
+
+use gonhanh_core::engine::{Engine, RepeatPolicy};
+use gonhanh_core::testkit::{keystroke, Keystroke, Screen};
+
+/// Type `input` on an empty screen: '*' repeats the previous key, '<' is
+/// Backspace
+fn type_held(e: &mut Engine, input: &str) -> String {
+    let mut screen = Screen::new();
+    let mut last: Option<Keystroke> = None;
+    for c in input.chars() {
+        match last.filter(|_| c == '*') {
+            Some(k) => {
+                screen.repeat(e, k.key, k.caps, false, k.shift);
+            }
+            None => {
+                screen.type_text(e, &c.to_string());
+                last = keystroke(c);
+            }
+        }
+    }
+    screen.text()
+}
+
+#[test]
+fn literal_repeats_are_typed_as_is() {
+    for (input, expected) in [
+        ("a***", "aaaa"),
+        ("as***", "ásss"),
+        ("dd**", "đdd"),
+        ("vieejt***", "việtttt"),
+        ("noo****", "nôoooo"),
+    ] {
+        let mut e = Engine::new();
+        assert_eq!(type_held(&mut e, input), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn literal_word_ends_at_boundary() {
+    let mut e = Engine::new();
+    assert_eq!(type_held(&mut e, "hmm** vieetj"), "hmmmm việt");
+}
+
+#[test]
+fn held_backspace_keeps_editing() {
+    let mut e = Engine::new();
+    // "việt", Backspace held over "tệ", then a tone on what is left
+    assert_eq!(type_held(&mut e, "vieetj<*s"), "ví");
+}
+
+#[test]
+fn press_policy_repeats_every_key() {
+    let mut e = Engine::new();
+    e.set_repeat_policy(RepeatPolicy::Press);
+    assert_eq!(type_held(&mut e, "a***"), "aaa");
+    assert_eq!(e.option("repeat_policy").as_deref(), Some("1"));
+}
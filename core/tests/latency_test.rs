@@ -0,0 +1,52 @@
+//! Latency metrics: keys and the stages inside them are timed only while
+//! metrics are on, and repeated words are answered from the lookup caches.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::latency::{self, Stage};
+use gonhanh_core::utils::type_word;
+
+#[test]
+fn times_keys_and_stages_while_enabled() {
+    let mut e = Engine::new();
+    latency::reset();
+    type_word(&mut e, "hoaf ");
+    assert_eq!(latency::summary(Stage::Key), None);
+    assert_eq!(latency::lookups(Stage::Validation), (0, 0));
+
+    latency::set_enabled(true);
+    type_word(&mut e, "vieetj ");
+    latency::set_enabled(false);
+
+    let key = latency::summary(Stage::Key).unwrap();
+    assert_eq!(key.count, 7);
+    assert!(key.p50_ns <= key.p99_ns && key.p99_ns <= key.max_ns);
+    // Only keys that reached a stage count for it
+    let rebuild = latency::summary(Stage::Rebuild).unwrap();
+    assert!(rebuild.count > 0 && rebuild.count < 7);
+    assert!(latency::summary(Stage::Validation).is_some());
+    assert!(latency::summary(Stage::Dictionary).is_some());
+    let (hits, misses) = latency::lookups(Stage::Validation);
+    assert!(misses > 0);
+
+    let json = latency::to_json();
+    assert!(
+        json.starts_with("{\"enabled\":false,\"window\":1024,\"stages\":{\"key\":{\"count\":7,")
+    );
+    assert!(json.contains(",\"cache\":{\"validation\":{\"hits\":"));
+
+    // The same word again is answered from the cache
+    latency::set_enabled(true);
+    type_word(&mut e, "vieetj ");
+    latency::set_enabled(false);
+    let (hits_again, misses_again) = latency::lookups(Stage::Validation);
+    assert!(hits_again > hits);
+    assert_eq!(misses_again, misses);
+    assert_eq!(latency::summary(Stage::Key).unwrap().count, 14);
+
+    latency::reset();
+    assert_eq!(latency::lookups(Stage::Validation), (0, 0));
+    assert_eq!(
+        latency::to_json(),
+        "{\"enabled\":false,\"window\":1024,\"stages\":{},\"cache\":{}}"
+    );
+}
@@ -0,0 +1,79 @@
+//! Learning from auto-restore corrections
+//!
+//! With learning on, undoing the auto-restore of a word twice keeps it
+//! Vietnamese from then on, and restoring a kept word twice with ESC makes
+//! auto-restore restore it.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine(learning: bool) -> Engine {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_backspace_undo_restore(true);
+    e.set_esc_restore(true);
+    e.learning_mut().set_enabled(learning);
+    e
+}
+
+#[test]
+fn repeated_undo_keeps_vietnamese() {
+    let mut e = engine(true);
+    assert_eq!(type_word(&mut e, "user < "), "uẻ ");
+    // One correction is not a preference yet
+    assert_eq!(type_word(&mut e, "user "), "user ");
+    assert_eq!(type_word(&mut e, "user < "), "uẻ ");
+    assert_eq!(type_word(&mut e, "user < "), "uẻ ");
+    assert_eq!(type_word(&mut e, "user "), "uẻ ");
+    assert_eq!(type_word(&mut e, "User,"), "Uẻ,");
+}
+
+#[test]
+fn undo_last_counts_as_correction() {
+    let mut e = engine(true);
+    for _ in 0..2 {
+        type_word(&mut e, "user ");
+        e.undo_last();
+        e.clear_all();
+    }
+    assert_eq!(type_word(&mut e, "user "), "uẻ ");
+}
+
+#[test]
+fn repeated_esc_restore_restores_english() {
+    let mut e = engine(true);
+    assert_eq!(type_word(&mut e, "box "), "bõ ");
+    assert_eq!(type_word(&mut e, "box\x1b "), "box ");
+    assert_eq!(type_word(&mut e, "box\x1b "), "box ");
+    assert_eq!(type_word(&mut e, "box "), "box ");
+    // Other words are unaffected
+    assert_eq!(type_word(&mut e, "mix "), "mĩ ");
+}
+
+#[test]
+fn nothing_learned_when_disabled() {
+    let mut e = engine(false);
+    for _ in 0..3 {
+        type_word(&mut e, "user < ");
+        type_word(&mut e, "box\x1b ");
+    }
+    assert!(e.learning().is_empty());
+    assert_eq!(type_word(&mut e, "user "), "user ");
+    assert_eq!(type_word(&mut e, "box "), "bõ ");
+}
+
+#[test]
+fn learned_words_survive_save_and_load() {
+    let mut e = engine(true);
+    for _ in 0..2 {
+        type_word(&mut e, "user < ");
+    }
+    let path = std::env::temp_dir().join(format!("gonhanh-learning-{}.txt", std::process::id()));
+    let path = path.to_str().unwrap();
+    assert!(e.learning().save(path));
+
+    let mut e = engine(true);
+    assert!(e.learning_mut().load(path));
+    let _ = std::fs::remove_file(path);
+    assert_eq!(type_word(&mut e, "user "), "uẻ ");
+}
@@ -0,0 +1,76 @@
+//! Macro recording: the text typed between start and stop becomes a
+//! shortcut under a trigger chosen afterwards
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+/// Record "Chào anh,⏎Thân ái" typed in Telex
+fn record_template(e: &mut Engine) -> Option<String> {
+    e.start_macro();
+    type_word(e, "Chaof anh,");
+    e.on_key(keys::RETURN, false, false);
+    type_word(e, "Thaan ais");
+    e.stop_macro()
+}
+
+#[test]
+fn records_text_as_it_ends_up_in_the_app() {
+    let mut e = Engine::new();
+    assert!(!e.macro_recording());
+    assert_eq!(
+        record_template(&mut e).as_deref(),
+        Some("Chào anh,\nThân ái")
+    );
+    assert!(!e.macro_recording());
+    // Stopping again has nothing to return
+    assert_eq!(e.stop_macro(), None);
+}
+
+#[test]
+fn backspace_is_applied_to_the_recording() {
+    let mut e = Engine::new();
+    e.start_macro();
+    type_word(&mut e, "xin chaof<<o");
+    assert_eq!(e.stop_macro().as_deref(), Some("xin cho"));
+}
+
+#[test]
+fn keys_before_start_are_not_recorded() {
+    let mut e = Engine::new();
+    type_word(&mut e, "truowcs ");
+    e.start_macro();
+    type_word(&mut e, "sau");
+    assert_eq!(e.stop_macro().as_deref(), Some("sau"));
+}
+
+#[test]
+fn pasted_text_is_recorded() {
+    let mut e = Engine::new();
+    e.start_macro();
+    type_word(&mut e, "Link: ");
+    e.on_paste(Some("https://gonhanh.org"));
+    e.on_paste(None);
+    assert_eq!(e.stop_macro().as_deref(), Some("Link: https://gonhanh.org"));
+}
+
+#[test]
+fn saved_macro_expands_like_a_shortcut() {
+    let mut e = Engine::new();
+    let text = record_template(&mut e).unwrap();
+    assert!(e.save_macro("ky"));
+    // After the recorded text, the trigger starts a new word
+    assert_eq!(type_word(&mut e, " ky "), format!(" {text} "));
+}
+
+#[test]
+fn save_needs_a_recording_and_a_trigger() {
+    let mut e = Engine::new();
+    assert!(!e.save_macro("ky"));
+    e.start_macro();
+    assert_eq!(e.stop_macro().as_deref(), Some(""));
+    assert!(!e.save_macro("ky"));
+    record_template(&mut e);
+    assert!(!e.save_macro("  "));
+    assert!(e.save_macro("ky"));
+}
@@ -0,0 +1,73 @@
+//! Managed config: an administrator's file overrides and locks options and
+//! can turn off network features.
+//!
+//! The managed config is process-wide, so everything runs in one test.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::hotkey::{Hotkey, HotkeyAction};
+use gonhanh_core::engine::settings::{SettingsError, HEADER};
+use gonhanh_core::engine::{Engine, ToneStyle};
+use gonhanh_core::managed::{self, Managed};
+use gonhanh_core::updater::{self, CheckError};
+use gonhanh_core::utils::type_word;
+
+#[test]
+fn locked_options_cannot_change() {
+    let text = format!(
+        "{}\ntone_style 0\nspell_check true\nnetwork false\n",
+        managed::HEADER
+    );
+    managed::set(Some(Managed::parse(&text).unwrap()));
+
+    let mut e = Engine::new();
+    e.set_spell_check(false);
+    e.apply_managed();
+    assert_eq!(e.tone_style(), ToneStyle::TraditionalStyle);
+    assert_eq!(e.option("spell_check").as_deref(), Some("true"));
+    assert_eq!(type_word(&mut e, "hoaf"), "hòa");
+
+    assert_eq!(
+        e.set_option("tone_style", "1"),
+        Err(SettingsError::Locked("tone_style".into()))
+    );
+    assert!(e.set_option("method", "1").is_ok());
+
+    // Bundles and profiles keep the locked values, the rest applies
+    let bundle = format!("{}\n[options]\ntone_style 1\nfree_tone true\n", HEADER);
+    e.import_settings(&bundle).unwrap();
+    assert_eq!(e.tone_style(), ToneStyle::TraditionalStyle);
+    assert_eq!(e.option("free_tone").as_deref(), Some("true"));
+
+    assert!(!managed::network_allowed());
+    assert_eq!(
+        updater::check("owner/repo").err(),
+        Some(CheckError::Disabled)
+    );
+
+    // Nor can the engine change them by itself: hotkeys, detection
+    let text = format!("{}\nmethod 0\ntone_style 0\n", managed::HEADER);
+    managed::set(Some(Managed::parse(&text).unwrap()));
+    let mut e = Engine::new();
+    e.apply_managed();
+    e.set_hotkey(
+        HotkeyAction::CycleMethod,
+        Some(Hotkey::new(keys::M, true, true)),
+    );
+    assert!(e.on_key_ext(keys::M, true, true, true).key_consumed());
+    assert_eq!(e.cycle_method(), 0);
+    e.set_auto_detect_method(true);
+    type_word(&mut e, "To6i la2 ngu7o72i ");
+    e.set_auto_tone_style(true);
+    for word in ["hoaf", "khoer", "thuyr"] {
+        type_word(&mut e, word);
+        e.select_candidate(0);
+        type_word(&mut e, " ");
+    }
+    assert_eq!(e.method(), 0);
+    assert_eq!(e.tone_style(), ToneStyle::TraditionalStyle);
+    assert!(e.take_events().is_empty());
+
+    managed::set(None);
+    assert!(managed::network_allowed());
+    assert!(e.set_option("tone_style", "1").is_ok());
+}
@@ -0,0 +1,66 @@
+//! Method cycle hotkey: the engine swallows the bound key, switches
+//! Telex → VNI → Telex and reports the change.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::hotkey::{Hotkey, HotkeyAction};
+use gonhanh_core::engine::{Engine, Event};
+use gonhanh_core::utils::type_word;
+
+fn engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_hotkey(
+        HotkeyAction::CycleMethod,
+        Some(Hotkey::new(keys::M, true, true)),
+    );
+    e
+}
+
+fn press_hotkey(e: &mut Engine) {
+    let r = e.on_key_ext(keys::M, true, true, true);
+    assert!(r.key_consumed());
+    assert_eq!(r.count, 0);
+}
+
+#[test]
+fn cycles_telex_vni() {
+    let mut e = engine();
+    press_hotkey(&mut e);
+    assert_eq!(e.method(), 1);
+    assert_eq!(e.take_events(), [Event::MethodChanged(1)]);
+    assert_eq!(type_word(&mut e, "a1 "), "á ");
+
+    press_hotkey(&mut e);
+    assert_eq!(e.method(), 0);
+    assert_eq!(e.take_events()[0].text(), "telex");
+    assert_eq!(type_word(&mut e, "as "), "á ");
+}
+
+#[test]
+fn other_methods_go_to_telex() {
+    let mut e = engine();
+    e.set_method(3);
+    assert_eq!(e.cycle_method(), 0);
+    e.set_method(2);
+    assert_eq!(e.cycle_method(), 0);
+}
+
+#[test]
+fn hotkey_ends_word() {
+    let mut e = engine();
+    type_word(&mut e, "vie");
+    press_hotkey(&mut e);
+    assert_eq!(e.get_buffer_string(), "");
+}
+
+#[test]
+fn other_combinations_pass_through() {
+    let mut e = engine();
+    let r = e.on_key_ext(keys::M, true, true, false);
+    assert!(!r.key_consumed());
+    assert_eq!(e.method(), 0);
+
+    e.set_hotkey(HotkeyAction::CycleMethod, None);
+    let r = e.on_key_ext(keys::M, true, true, true);
+    assert!(!r.key_consumed());
+    assert!(e.take_events().is_empty());
+}
@@ -0,0 +1,47 @@
+//! Telex/VNI auto-detection from the first words typed
+
+use gonhanh_core::engine::{Engine, Event};
+use gonhanh_core::utils::type_word;
+
+fn engine(method: u8) -> Engine {
+    let mut e = Engine::new();
+    e.set_method(method);
+    e.set_auto_detect_method(true);
+    e
+}
+
+#[test]
+fn switches_telex_to_vni() {
+    let mut e = engine(0);
+    assert_eq!(type_word(&mut e, "To6i "), "To6i ");
+    assert_eq!(type_word(&mut e, "la2 "), "la2 ");
+    assert_eq!(e.method(), 1);
+    assert_eq!(e.take_events(), [Event::MethodChanged(1)]);
+    assert_eq!(type_word(&mut e, "ngu7o72i Vie65t."), "người Việt.");
+    assert!(e.take_events().is_empty());
+}
+
+#[test]
+fn switches_vni_to_telex() {
+    let mut e = engine(1);
+    type_word(&mut e, "xin chaof caacs banj ");
+    assert_eq!(e.method(), 0);
+    assert_eq!(e.take_events(), [Event::MethodChanged(0)]);
+    assert_eq!(Event::MethodChanged(0).text(), "telex");
+}
+
+#[test]
+fn ignores_english_and_plain_words() {
+    let mut e = engine(0);
+    type_word(&mut e, "test see mp3 nha ");
+    assert_eq!(e.method(), 0);
+    assert!(e.take_events().is_empty());
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    type_word(&mut e, "To6i la2 ngu7o72i ");
+    assert_eq!(e.method(), 0);
+    assert!(e.take_events().is_empty());
+}
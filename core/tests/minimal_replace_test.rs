@@ -0,0 +1,39 @@
+//! Minimal replacements: results delete and retype only the part of the
+//! word that changes, not characters the app already shows.
+
+use gonhanh_core::engine::{Engine, Result};
+use gonhanh_core::utils::{char_to_key, type_word};
+
+fn type_keys(e: &mut Engine, input: &str) -> Result {
+    let mut last = Result::none();
+    for c in input.chars() {
+        last = e.on_key_ext(char_to_key(c), false, false, false);
+    }
+    last
+}
+
+fn text(r: &Result) -> String {
+    r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect()
+}
+
+#[test]
+fn revert_keeps_the_unchanged_start() {
+    // "nât" + t reverts to raw "natat": the "n" stays on screen
+    let mut e = Engine::new();
+    let r = type_keys(&mut e, "natat");
+    assert_eq!((r.backspace, text(&r).as_str()), (2, "atat"));
+
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "natat camah "), "natat camah ");
+}
+
+#[test]
+fn rewrite_to_the_same_text_sends_nothing() {
+    // The second "s" renders "ứnuo" again
+    let mut e = Engine::new();
+    let r = type_keys(&mut e, "wnusos");
+    assert_eq!((r.action, r.backspace, r.count), (1, 0, 0));
+}
@@ -0,0 +1,48 @@
+//! Native messaging host: the browser-facing binary, driven through
+//! length-prefixed stdin/stdout messages like Chrome does.
+
+use gonhanh_core::config;
+use gonhanh_core::daemon::{read_native_message, write_native_message};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn answers_framed_requests_with_user_settings() {
+    let dir = std::env::temp_dir().join(format!("gonhanh-native-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join(config::SETTINGS_FILE),
+        "# gonhanh settings 1\n[options]\nmethod 1\n",
+    )
+    .unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gonhanh-native-host"))
+        .arg("chrome-extension://abcdefghijklmnop/")
+        .env(config::ENV_VAR, &dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    for request in [
+        r#"{"id":1,"cmd":"get","key":"method"}"#,
+        r#"{"id":2,"cmd":"key","key":0}"#,
+        r#"{"id":3,"cmd":"key","key":18}"#,
+    ] {
+        write_native_message(&mut stdin, request).unwrap();
+    }
+    stdin.flush().unwrap();
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let mut reader = &output.stdout[..];
+    let mut next = || read_native_message(&mut reader).unwrap().unwrap();
+    assert_eq!(next(), r#"{"id":1,"value":"1"}"#);
+    assert!(next().starts_with(r#"{"id":2,"action":0,"#));
+    assert!(next().starts_with(r#"{"id":3,"action":1,"backspace":1,"chars":"á","#));
+    assert!(read_native_message(&mut reader).unwrap().is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,82 @@
+//! Named profiles: options and shortcuts kept apart per profile, switched
+//! by name or hotkey and saved under one directory.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::hotkey::{Hotkey, HotkeyAction};
+use gonhanh_core::engine::profiles::DEFAULT_PROFILE;
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::{Engine, Event};
+use gonhanh_core::utils::type_word;
+use std::fs;
+
+/// "default" with Telex and "ko", "work" with VNI and "kg"
+fn two_profiles() -> Engine {
+    let mut e = Engine::new();
+    e.set_hotkey(
+        HotkeyAction::NextProfile,
+        Some(Hotkey::new(keys::P, true, true)),
+    );
+    e.shortcuts_mut().add(Shortcut::new("ko", "không"));
+    e.switch_profile("work").unwrap();
+    e.set_method(1);
+    e.shortcuts_mut().clear();
+    e.shortcuts_mut().add(Shortcut::new("kg", "kính gửi"));
+    e.take_events();
+    e
+}
+
+#[test]
+fn each_profile_has_its_own_shortcuts() {
+    let mut e = two_profiles();
+    assert_eq!(type_word(&mut e, "kg a1 ko "), "kính gửi á ko ");
+
+    e.switch_profile(DEFAULT_PROFILE).unwrap();
+    e.clear();
+    assert_eq!(type_word(&mut e, "kg as ko "), "kg á không ");
+}
+
+#[test]
+fn hotkey_cycles_profiles() {
+    let mut e = two_profiles();
+    let r = e.on_key_ext(keys::P, true, true, true);
+    assert!(r.key_consumed());
+    assert_eq!(e.profile(), DEFAULT_PROFILE);
+    assert_eq!(e.method(), 0);
+    let events = e.take_events();
+    assert_eq!(events, [Event::ProfileChanged("default".into())]);
+    assert_eq!(events[0].code(), 3);
+
+    // Hotkeys are shared, so the same key goes back
+    e.on_key_ext(keys::P, true, true, true);
+    assert_eq!(e.profile(), "work");
+}
+
+#[test]
+fn save_and_load_round_trip() {
+    let dir = std::env::temp_dir().join(format!("gonhanh-profiles-{}", std::process::id()));
+    let mut e = two_profiles();
+    e.save_profiles(&dir).unwrap();
+    assert!(dir.join("default.txt").is_file());
+    assert!(dir.join("work.txt").is_file());
+
+    let mut loaded = Engine::new();
+    loaded.load_profiles(&dir).unwrap();
+    assert_eq!(loaded.profile(), "work");
+    assert_eq!(loaded.profile_names(), ["default", "work"]);
+    assert_eq!(loaded.method(), 1);
+    assert_eq!(type_word(&mut loaded, "kg "), "kính gửi ");
+
+    // Deleted profiles lose their file on the next save
+    e.switch_profile(DEFAULT_PROFILE).unwrap();
+    assert!(e.delete_profile("work"));
+    e.save_profiles(&dir).unwrap();
+    assert!(!dir.join("work.txt").exists());
+
+    // A broken profile rejects the whole load
+    fs::write(dir.join("broken.txt"), "not settings").unwrap();
+    assert!(loaded.load_profiles(&dir).is_err());
+    assert_eq!(loaded.profile(), "work");
+
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(loaded.load_profiles(&dir).is_ok());
+}
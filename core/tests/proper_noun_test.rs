@@ -0,0 +1,67 @@
+//! Proper-noun capitalization
+//!
+//! Known place names (and names added at runtime) are capitalized at the
+//! word boundary: "hà nội" + Space → "Hà Nội ". Only words separated by a
+//! single space count as one name.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_proper_noun_capitalize(true);
+    e
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "haf nooij "), "hà nội ");
+}
+
+#[test]
+fn capitalizes_place_names() {
+    let cases = [
+        ("haf nooij ", "Hà Nội "),
+        ("toi owr haf nooij ", "toi ở Hà Nội "),
+        ("vieetj nam", "việt nam"),
+        ("vieetj nam ", "Việt Nam "),
+        ("thanhf phoos hoof chis minh ", "Thành phố Hồ Chí Minh "),
+        ("ddaf nawngx,", "Đà Nẵng,"),
+        ("khanhs hoaf ", "Khánh Hoà "),
+        ("Haf Nooij ", "Hà Nội "),
+    ];
+    for (input, expected) in cases {
+        let mut e = engine();
+        assert_eq!(type_word(&mut e, input), expected, "'{}'", input);
+    }
+}
+
+#[test]
+fn leaves_common_phrases() {
+    let mut e = engine();
+    assert_eq!(type_word(&mut e, "hoaf binhf "), "hoà bình ");
+}
+
+#[test]
+fn words_must_be_adjacent() {
+    for input in ["haf  nooij ", "haf, nooij ", "haf\x1bnooij "] {
+        let mut e = engine();
+        assert!(
+            !type_word(&mut e, input).contains('N'),
+            "'{}'",
+            input.escape_debug()
+        );
+    }
+}
+
+#[test]
+fn added_names() {
+    let mut e = engine();
+    assert!(e.add_proper_noun("Nguyễn Văn An"));
+    assert!(!e.add_proper_noun(""));
+    assert_eq!(
+        type_word(&mut e, "chaof nguyeenx vawn an "),
+        "chào Nguyễn Văn An "
+    );
+}
@@ -0,0 +1,54 @@
+//! App quirk table: embedded strategies, replaced through the word list
+//! update channel.
+//!
+//! The table is process-wide, so everything runs in one test.
+
+use gonhanh_core::data::dictionary::{stamp, LoadError};
+use gonhanh_core::data::quirks::{self, BackspaceMode, SendMethod, QUIRK_BATCH};
+
+#[test]
+fn downloaded_table_replaces_embedded() {
+    assert_eq!(quirks::version(), 0);
+    assert_eq!(quirks::lookup("warp").unwrap().method, SendMethod::Paste);
+
+    let lines = [
+        "warp: atomic backspace=unicode",
+        "newapp.exe: slow batch delay=8",
+    ];
+    assert_eq!(quirks::load_override_text(&stamp(&lines, 3)), Ok(3));
+    assert_eq!(quirks::version(), 3);
+
+    let warp = quirks::lookup("Warp.exe").unwrap();
+    assert_eq!(warp.method, SendMethod::Atomic);
+    assert_eq!(warp.backspace, BackspaceMode::Unicode);
+    let newapp = quirks::lookup("newapp").unwrap();
+    assert!(newapp.has(QUIRK_BATCH));
+    assert_eq!((newapp.delay_ms, newapp.settle_ms), (8, 20));
+    // The whole table is replaced
+    assert_eq!(quirks::lookup("firefox"), None);
+
+    // Older, broken or unparsable tables keep the current one
+    assert_eq!(
+        quirks::load_override_text(&stamp(&lines, 2)),
+        Err(LoadError::NotNewer)
+    );
+    let mut tampered = stamp(&lines, 4);
+    tampered.push_str("x: fast\n");
+    assert_eq!(
+        quirks::load_override_text(&tampered),
+        Err(LoadError::Checksum)
+    );
+    assert_eq!(
+        quirks::load_override_text(&stamp(&["warp: teleport"], 5)),
+        Err(LoadError::Malformed)
+    );
+    assert!(matches!(
+        quirks::load_override("/nonexistent/quirks.txt"),
+        Err(LoadError::Io(_))
+    ));
+    assert_eq!(quirks::version(), 3);
+
+    quirks::reset_override();
+    assert_eq!(quirks::version(), 0);
+    assert!(quirks::lookup("firefox").is_some());
+}
@@ -0,0 +1,60 @@
+//! Remove key: the key that strips diacritics from the current word
+//! (z in Telex, 0 in VNI by default) can be moved or turned off.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn check(e: &mut Engine, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        e.clear();
+        assert_eq!(type_word(e, input), *expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn defaults() {
+    let mut e = Engine::new();
+    check(&mut e, &[("asz", "a"), ("vieejtz", "viêt")]);
+    e.set_method(1);
+    check(&mut e, &[("a10", "a"), ("vie65t0", "viêt")]);
+}
+
+#[test]
+fn telex_remove_off() {
+    let mut e = Engine::new();
+    e.set_remove_key(0, None);
+    check(&mut e, &[("asz", "áz"), ("jazz", "jazz"), ("quiz", "quiz")]);
+}
+
+#[test]
+fn telex_custom_key() {
+    let mut e = Engine::new();
+    e.set_remove_key(0, Some(keys::N0));
+    check(&mut e, &[("as0", "a"), ("asz", "áz"), ("a0", "a0")]);
+}
+
+#[test]
+fn vni_remove_off() {
+    let mut e = Engine::new();
+    e.set_method(1);
+    e.set_remove_key(1, None);
+    check(&mut e, &[("a10", "á0")]);
+}
+
+#[test]
+fn telex_vni_uses_both_keys() {
+    let mut e = Engine::new();
+    e.set_method(3);
+    check(&mut e, &[("asz", "a"), ("a10", "a")]);
+    e.set_remove_key(0, None);
+    check(&mut e, &[("asz", "áz"), ("a10", "a")]);
+}
+
+#[test]
+fn simple_telex_uses_telex_key() {
+    let mut e = Engine::new();
+    e.set_method(2);
+    e.set_remove_key(0, None);
+    check(&mut e, &[("asz", "áz")]);
+}
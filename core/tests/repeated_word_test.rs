@@ -0,0 +1,53 @@
+//! Repeated-word detection ("của của")
+
+use gonhanh_core::engine::{Engine, Event};
+use gonhanh_core::utils::type_word;
+
+fn engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_repeated_word_check(true);
+    e
+}
+
+fn repeated(input: &str) -> Vec<String> {
+    let mut e = engine();
+    type_word(&mut e, input);
+    e.take_events()
+        .into_iter()
+        .filter_map(|ev| match ev {
+            Event::RepeatedWord(word) => Some(word),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn flags_word_typed_twice() {
+    assert_eq!(repeated("cuar cuar tooi "), ["của"]);
+    assert_eq!(repeated("laf Laf "), ["Là"]);
+    // Committed by punctuation
+    assert_eq!(repeated("ddi ddi."), ["đi"]);
+    // Restored English words too
+    assert_eq!(repeated("the the "), ["the"]);
+}
+
+#[test]
+fn ignores_separated_or_intended_repeats() {
+    assert!(repeated("cuar, cuar ").is_empty());
+    assert!(repeated("cuar  cuar ").is_empty());
+    assert!(repeated("tuwf tuwf ").is_empty());
+    assert!(repeated("ngayf ngayf ").is_empty());
+    assert!(repeated("1 1 ").is_empty());
+}
+
+#[test]
+fn off_by_default_and_drained() {
+    let mut e = Engine::new();
+    type_word(&mut e, "cuar cuar ");
+    assert!(e.take_events().is_empty());
+
+    let mut e = engine();
+    type_word(&mut e, "cuar cuar ");
+    assert_eq!(e.take_events().len(), 1);
+    assert!(e.take_events().is_empty());
+}
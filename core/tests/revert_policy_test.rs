@@ -0,0 +1,80 @@
+//! Revert policy: what a modifier key does when pressed again after its
+//! revert ("aaa" → "aa"). Literal types it as a letter, Cycle re-applies
+//! the transform.
+
+use gonhanh_core::engine::{Engine, RevertPolicy};
+use gonhanh_core::utils::type_word;
+
+fn check(policy: RevertPolicy, method: u8, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(method);
+        e.set_revert_policy(policy);
+        assert_eq!(type_word(&mut e, input), *expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn literal_is_default() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "aaaa"), "aaa");
+}
+
+#[test]
+fn literal_passes_keys_through() {
+    check(
+        RevertPolicy::Literal,
+        0,
+        &[
+            ("aaa", "aa"),
+            ("aaaa", "aaa"),
+            ("asss", "ass"),
+            ("dddd", "ddd"),
+            ("awww", "aww"),
+            ("ufff", "uff"),
+        ],
+    );
+    check(
+        RevertPolicy::Literal,
+        1,
+        &[
+            ("a666", "a66"),
+            ("a111", "a11"),
+            ("d99", "d9"),
+            ("d999", "d99"),
+            ("D999", "D99"),
+            ("da999", "da99"),
+        ],
+    );
+}
+
+#[test]
+fn cycle_reapplies_transform() {
+    check(
+        RevertPolicy::Cycle,
+        0,
+        &[
+            ("aaa", "aa"),
+            ("aaaa", "â"),
+            ("aaaaa", "aa"),
+            ("asss", "á"),
+            ("assss", "as"),
+            ("dddd", "đ"),
+            ("awww", "ă"),
+            ("ufff", "ù"),
+            ("tieeee", "tiê"),
+            ("tieeees", "tiế"),
+            ("daddd", "đa"),
+        ],
+    );
+    check(
+        RevertPolicy::Cycle,
+        1,
+        &[("a666", "â"), ("a111", "á"), ("d999", "đ"), ("da999", "đa")],
+    );
+}
+
+#[test]
+fn cycle_only_right_after_revert() {
+    check(RevertPolicy::Cycle, 0, &[("asss ", "á "), ("bass", "bas")]);
+}
@@ -0,0 +1,69 @@
+//! Selection replace: hosts with a text API get "select N characters and
+//! type over them" instead of simulated backspaces.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::{Action, Engine, Result};
+use gonhanh_core::utils::char_to_key;
+
+fn type_keys(e: &mut Engine, input: &str) -> Result {
+    let mut last = Result::none();
+    for c in input.chars() {
+        last = e.on_key_ext(char_to_key(c), false, false, false);
+    }
+    last
+}
+
+fn text(r: &Result) -> String {
+    r.chars[..r.count as usize]
+        .iter()
+        .filter_map(|&c| char::from_u32(c))
+        .collect()
+}
+
+#[test]
+fn deleting_results_become_replace() {
+    let mut e = Engine::new();
+    e.set_selection_replace(true);
+
+    let r = type_keys(&mut e, "as");
+    assert_eq!(r.action, Action::Replace as u8);
+    assert_eq!((r.backspace, text(&r).as_str()), (1, "á"));
+
+    // Nothing to select: still a plain Send
+    e.clear();
+    let r = type_keys(&mut e, "w");
+    assert_eq!(r.action, Action::Send as u8);
+    assert_eq!((r.backspace, text(&r).as_str()), (0, "ư"));
+
+    e.shortcuts_mut().add(Shortcut::new("ko", "không"));
+    e.clear();
+    let r = type_keys(&mut e, "ko ");
+    assert_eq!(r.action, Action::Replace as u8);
+    assert_eq!((r.backspace, text(&r).as_str()), (2, "không "));
+}
+
+#[test]
+fn restore_keeps_its_key() {
+    let mut e = Engine::new();
+    e.set_esc_restore(true);
+    e.set_selection_replace(true);
+    type_keys(&mut e, "vieetj");
+    let r = e.on_key_ext(keys::ESC, false, false, false);
+    assert_eq!(r.action, Action::Replace as u8);
+    assert!(r.key_consumed());
+    assert_eq!((r.backspace, text(&r).as_str()), (4, "vieetj"));
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    let r = type_keys(&mut e, "as");
+    assert_eq!(r.action, Action::Send as u8);
+
+    e.set_selection_replace(true);
+    e.set_selection_replace(false);
+    e.clear();
+    let r = type_keys(&mut e, "as");
+    assert_eq!(r.action, Action::Send as u8);
+}
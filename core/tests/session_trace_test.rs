@@ -0,0 +1,79 @@
+//! Session traces: what a user records replays to the same text
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::settings::SettingsError;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::Screen;
+
+/// Events of a trace, without the settings
+fn events(trace: &str) -> Vec<&str> {
+    let start = trace.find("[trace]\n").unwrap() + "[trace]\n".len();
+    trace[start..].lines().collect()
+}
+
+#[test]
+fn tracing_is_off_by_default() {
+    let mut e = Engine::new();
+    assert!(!e.session_tracing());
+    assert_eq!(e.stop_session_trace(), None);
+}
+
+#[test]
+fn replay_gives_the_text_typed() {
+    let mut e = Engine::new();
+    e.set_method(1);
+    e.start_session_trace();
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "Vie65t Nam, to6i<<o6i ye6u\n");
+    screen.paste(&mut e, Some("xin chào "));
+    screen.type_text(&mut e, "ba5n");
+    let trace = e.stop_session_trace().unwrap();
+
+    // A Telex engine: the method comes from the trace
+    let replayed = Engine::new().replay(&trace).unwrap();
+    assert_eq!(replayed, screen.text());
+    assert_eq!(replayed, "Việt Nam, tôi yêu\nxin chào bạn");
+}
+
+#[test]
+fn only_host_calls_are_recorded() {
+    let mut e = Engine::new();
+    e.start_session_trace();
+    // Space clears the word inside the engine: not a host call
+    Screen::new().type_text(&mut e, "as ");
+    e.clear_all();
+    e.set_active_app(Some("firefox"));
+    let trace = e.stop_session_trace().unwrap();
+    assert_eq!(
+        events(&trace),
+        ["key 0", "key 1", "key 49", "clear_all", "app firefox"]
+    );
+}
+
+#[test]
+fn attached_trace_becomes_a_test() {
+    let trace = include_str!("data/traces/neeus-auto-restore.trace");
+    assert_eq!(Engine::new().replay(trace).unwrap(), "nếu ");
+}
+
+#[test]
+fn modifiers_and_undo_replay() {
+    let mut e = Engine::new();
+    e.start_session_trace();
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "Hi");
+    screen.press(&mut e, keys::SPACE, false, false, true);
+    screen.undo(&mut e);
+    let trace = e.stop_session_trace().unwrap();
+    assert!(events(&trace).contains(&"key 49 shift"));
+    assert!(events(&trace).contains(&"undo"));
+    assert_eq!(Engine::new().replay(&trace).unwrap(), screen.text());
+}
+
+#[test]
+fn bad_lines_are_reported() {
+    let mut e = Engine::new();
+    assert_eq!(e.replay("key 0\n"), Err(SettingsError::NotSettings));
+    let trace = "# gonhanh settings 1\n[options]\n[trace]\nkey 0\nkey x\n";
+    assert_eq!(e.replay(trace), Err(SettingsError::Malformed(5)));
+}
@@ -0,0 +1,100 @@
+//! Settings bundle: export everything configured on one engine and import
+//! it into another, as when moving to a new machine.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::capitalize::CapitalizeRule;
+use gonhanh_core::engine::hotkey::{Hotkey, HotkeyAction};
+use gonhanh_core::engine::settings::{SettingsError, HEADER};
+use gonhanh_core::engine::shortcut::{AppScope, Shortcut};
+use gonhanh_core::engine::{Engine, ToneStyle};
+use gonhanh_core::utils::type_word;
+
+fn configured() -> Engine {
+    let mut e = Engine::new();
+    e.set_method(1);
+    e.set_tone_style(ToneStyle::TraditionalStyle);
+    e.set_remove_key(0, None);
+    e.set_auto_capitalize(true);
+    e.set_capitalize_rule(';', CapitalizeRule::EndSentence);
+    e.set_hotkey(
+        HotkeyAction::CycleMethod,
+        Some(Hotkey::new(keys::M, true, true)),
+    );
+    e.shortcuts_mut().add(Shortcut::new("ko", "không"));
+    e.shortcuts_mut().add(
+        Shortcut::new("kg", "Kính gửi\n\tAnh|").in_apps(AppScope::from_lists("outlook.exe", "")),
+    );
+    e.shortcuts_mut().add(Shortcut::immediate("->", "→"));
+    e.add_english_word("expect");
+    e.set_proper_noun_capitalize(true);
+    e.add_proper_noun("Nguyễn Văn An");
+    e.learning_mut().set_enabled(true);
+    e
+}
+
+#[test]
+fn round_trip_keeps_everything() {
+    let exported = configured().export_settings();
+    assert!(exported.starts_with(HEADER));
+
+    let mut e = Engine::new();
+    e.import_settings(&exported).unwrap();
+    assert_eq!(e.export_settings(), exported);
+
+    assert_eq!(e.method(), 1);
+    assert_eq!(e.tone_style(), ToneStyle::TraditionalStyle);
+    assert_eq!(type_word(&mut e, "hoa2"), "hòa");
+    e.clear();
+    assert_eq!(type_word(&mut e, "ko "), "không ");
+    assert!(e.has_english_word("expect"));
+    assert_eq!(e.shortcuts().len(), 3);
+    assert!(e.learning().enabled());
+}
+
+#[test]
+fn import_replaces_tables_in_file() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("hn", "Hà Nội"));
+    e.add_english_word("deadline");
+    let text = format!("{}\n[shortcuts]\nko\tkhông\n", HEADER);
+    e.import_settings(&text).unwrap();
+    // Shortcuts replaced, English words untouched (no section)
+    assert_eq!(e.shortcuts().len(), 1);
+    assert!(e.has_english_word("deadline"));
+}
+
+#[test]
+fn newer_files_still_load() {
+    let mut e = Engine::new();
+    let text = format!(
+        "{}\n[options]\nmethod 1\nfuture_option 7\n[themes]\ndark\n",
+        HEADER
+    );
+    e.import_settings(&text).unwrap();
+    assert_eq!(e.method(), 1);
+}
+
+#[test]
+fn invalid_file_changes_nothing() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("hn", "Hà Nội"));
+    let text = format!(
+        "{}\n[options]\nmethod 1\n[shortcuts]\nko\tkhông\n[hotkeys]\ncycle_method M\n",
+        HEADER
+    );
+    assert_eq!(e.import_settings(&text), Err(SettingsError::Malformed(7)));
+    assert_eq!(e.method(), 0);
+    assert_eq!(e.shortcuts().len(), 1);
+}
+
+#[test]
+fn file_round_trip() {
+    let path = std::env::temp_dir().join(format!("gonhanh-settings-{}", std::process::id()));
+    let path = path.to_str().unwrap();
+    configured().save_settings(path).unwrap();
+    let mut e = Engine::new();
+    e.load_settings(path).unwrap();
+    assert_eq!(e.method(), 1);
+    std::fs::remove_file(path).unwrap();
+    assert!(matches!(e.load_settings(path), Err(SettingsError::Io(_))));
+}
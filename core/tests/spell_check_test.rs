@@ -0,0 +1,49 @@
+//! Spell check toggle: refusing invalid Vietnamese while typing is
+//! separate from English auto-restore at the word boundary.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine(spell_check: bool, auto_restore: bool) -> Engine {
+    let mut e = Engine::new();
+    e.set_spell_check(spell_check);
+    e.set_english_auto_restore(auto_restore);
+    e
+}
+
+fn check(e: &mut Engine, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        e.clear();
+        assert_eq!(type_word(e, input), *expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn spell_check_on_blocks_while_typing() {
+    let mut e = engine(true, false);
+    check(&mut e, &[("hocf", "hocf"), ("thingf", "thingf")]);
+}
+
+#[test]
+fn spell_check_off_never_blocks() {
+    let mut e = engine(false, false);
+    check(
+        &mut e,
+        &[("hocf", "hòc"), ("thingf", "thìng"), ("text ", "tẽt ")],
+    );
+}
+
+#[test]
+fn auto_restore_mid_word_with_spell_check() {
+    let mut e = engine(true, true);
+    check(&mut e, &[("text", "text"), ("text ", "text ")]);
+}
+
+#[test]
+fn spell_check_off_restores_only_at_boundary() {
+    let mut e = engine(false, true);
+    check(
+        &mut e,
+        &[("text", "tẽt"), ("text ", "text "), ("vieejt ", "việt ")],
+    );
+}
@@ -0,0 +1,58 @@
+//! Spelling auto-fix
+//!
+//! With the option on, a word that breaks only a c/k, g/gh or ng/ngh rule is
+//! respelled at the word boundary when the result is a Vietnamese word.
+
+use gonhanh_core::engine::validation::Strictness;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_spelling_autofix(true);
+    e
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    assert_eq!(type_word(&mut e, "nge "), "nge ");
+}
+
+#[test]
+fn respells_initial() {
+    let cases = [
+        ("nge ", "nghe "),
+        ("NGE ", "NGHE "),
+        ("ngeef ", "nghề "),
+        ("cees ", "kế "),
+        ("kaf ", "cà "),
+        ("ghas ", "gá "),
+        // Break keys are typed by the host after the fixed word
+        ("ngif,", "nghì,"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(type_word(&mut engine(), input), expected, "'{}'", input);
+    }
+}
+
+#[test]
+fn leaves_english_and_dictionary_words() {
+    for word in ["get ", "cent ", "gem ", "city ", "gen "] {
+        assert_eq!(type_word(&mut engine(), word), word);
+    }
+}
+
+#[test]
+fn fixed_word_is_kept_for_editing() {
+    let mut e = engine();
+    // Backspace over the space brings back the fixed word
+    assert_eq!(type_word(&mut e, "nge <s"), "nghé");
+}
+
+#[test]
+fn relaxed_profile_keeps_spelling() {
+    let mut e = engine();
+    e.set_strictness(Strictness::Relaxed);
+    assert_eq!(type_word(&mut e, "kos "), "kó ");
+}
@@ -0,0 +1,83 @@
+//! Regional and loanword spellings
+//!
+//! Off by default. Southern turns on z/dz initials ("dzô", "zậy"); every
+//! selected list keeps its words from being auto-restored.
+
+use gonhanh_core::data::dictionary::{
+    VARIANT_ALL, VARIANT_LOANWORDS, VARIANT_NORTHERN, VARIANT_SOUTHERN,
+};
+use gonhanh_core::engine::validation::Strictness;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn check(variants: u8, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_english_auto_restore(true);
+        e.set_spelling_variants(variants);
+        assert_eq!(
+            type_word(&mut e, input),
+            *expected,
+            "variants {:#x} '{}'",
+            variants,
+            input
+        );
+    }
+}
+
+#[test]
+fn off_by_default() {
+    assert_eq!(Engine::new().spelling_variants(), 0);
+    check(
+        0,
+        &[("dzoo ", "dzoo "), ("zaajy ", "zaajy "), ("zoo ", "zoo ")],
+    );
+}
+
+#[test]
+fn southern_z_and_dz_initials() {
+    check(
+        VARIANT_SOUTHERN,
+        &[
+            ("dzoo ", "dzô "),
+            ("dzaajy ", "dzậy "),
+            ("dzif ", "dzì "),
+            ("zaajy ", "zậy "),
+            // English "zoo" gives way to the southern spelling
+            ("zoo ", "zô "),
+        ],
+    );
+}
+
+#[test]
+fn other_lists_do_not_allow_z() {
+    check(VARIANT_NORTHERN | VARIANT_LOANWORDS, &[("dzoo ", "dzoo ")]);
+}
+
+#[test]
+fn strict_profile_refuses_z() {
+    let mut e = Engine::new();
+    e.set_spelling_variants(VARIANT_SOUTHERN);
+    e.set_strictness(Strictness::Strict);
+    assert_eq!(type_word(&mut e, "dzoo "), "dzoo ");
+}
+
+#[test]
+fn variant_words_stay() {
+    check(
+        VARIANT_ALL,
+        &[
+            ("layf ", "lày "),
+            ("chaary ", "chẩy "),
+            ("poost ", "pốt "),
+            ("sipj ", "sịp "),
+        ],
+    );
+}
+
+#[test]
+fn unknown_bits_are_dropped() {
+    let mut e = Engine::new();
+    e.set_spelling_variants(0xFF);
+    assert_eq!(e.spelling_variants(), VARIANT_ALL);
+}
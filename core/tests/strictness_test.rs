@@ -0,0 +1,94 @@
+//! Validation strictness profiles
+//!
+//! Standard applies every rule. Strict refuses foreign initials and final k
+//! even with allow-foreign on. Relaxed accepts chat spellings ("kó", "ngì")
+//! and foreign initials without the option.
+
+use gonhanh_core::engine::validation::Strictness;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine(strictness: Strictness, allow_foreign: bool) -> Engine {
+    let mut e = Engine::new();
+    e.set_allow_foreign_consonants(allow_foreign);
+    e.set_strictness(strictness);
+    e
+}
+
+fn check(strictness: Strictness, allow_foreign: bool, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = engine(strictness, allow_foreign);
+        assert_eq!(
+            type_word(&mut e, input),
+            *expected,
+            "{:?} '{}'",
+            strictness,
+            input
+        );
+    }
+}
+
+#[test]
+fn standard_is_default() {
+    assert_eq!(Engine::new().strictness(), Strictness::Standard);
+    check(
+        Strictness::Standard,
+        false,
+        &[
+            ("kos", "kos"),
+            ("ngif", "ngif"),
+            ("zas", "zas"),
+            ("laks", "lák"),
+        ],
+    );
+    check(Strictness::Standard, true, &[("zas", "zá")]);
+}
+
+#[test]
+fn strict_refuses_foreign_initials_and_final_k() {
+    check(
+        Strictness::Strict,
+        true,
+        &[("zas", "zas"), ("laks", "laks"), ("hocj", "học")],
+    );
+}
+
+#[test]
+fn strict_refuses_final_k_after_stroke_and_breve() {
+    check(
+        Strictness::Strict,
+        false,
+        &[
+            ("ddaks", "đaks"),
+            ("ddawks", "đăks"),
+            ("tawks", "tăks"),
+            ("dduwowcj", "được"),
+            ("ddawngs", "đắng"),
+        ],
+    );
+    check(
+        Strictness::Standard,
+        false,
+        &[("ddaks", "đák"), ("ddawks", "đắk")],
+    );
+
+    let mut e = engine(Strictness::Strict, false);
+    e.set_method(1);
+    assert_eq!(type_word(&mut e, "d9a8k1"), "đăk1");
+
+    // No ethnic minority exception when restoring English at space
+    for (input, expected) in [("lask ", "lask "), ("lawsk ", "lawsk ")] {
+        let mut e = engine(Strictness::Strict, false);
+        e.set_english_auto_restore(true);
+        assert_eq!(type_word(&mut e, input), expected);
+    }
+}
+
+#[test]
+fn relaxed_accepts_chat_spellings() {
+    check(
+        Strictness::Relaxed,
+        false,
+        &[("kos", "kó"), ("ngif", "ngì"), ("gef", "gè"), ("zas", "zá")],
+    );
+}
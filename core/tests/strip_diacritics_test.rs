@@ -0,0 +1,29 @@
+//! Diacritic stripping (Vietnamese → ASCII)
+
+use gonhanh_core::utils::strip_diacritics;
+
+#[test]
+fn strips_tones_and_modifiers() {
+    let cases = [
+        ("Nguyễn Văn A", "Nguyen Van A"),
+        ("Đà Nẵng", "Da Nang"),
+        ("ĐƯỢC ĐI", "DUOC DI"),
+        ("trường học, ồ!", "truong hoc, o!"),
+        ("hoà hòa", "hoa hoa"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(strip_diacritics(input), expected, "'{}'", input);
+    }
+}
+
+#[test]
+fn keeps_other_text() {
+    assert_eq!(strip_diacritics("café → 100% ok"), "cafe → 100% ok");
+    assert_eq!(strip_diacritics("日本"), "日本");
+}
+
+#[test]
+fn drops_decomposed_marks() {
+    // "Việt" with combining circumflex and dot below
+    assert_eq!(strip_diacritics("Vie\u{302}\u{323}t"), "Viet");
+}
@@ -0,0 +1,93 @@
+//! Teencode passthrough: chat abbreviations are committed as typed
+
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_teencode_passthrough(true);
+    e
+}
+
+fn check(e: &mut Engine, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        e.clear();
+        assert_eq!(type_word(e, input), *expected, "'{}'", input);
+    }
+}
+
+#[test]
+fn off_by_default() {
+    let mut e = Engine::new();
+    assert!(!e.teencode_passthrough());
+    check(&mut e, &[("wa ", "ưa "), ("hix ", "hĩ ")]);
+}
+
+#[test]
+fn abbreviations_are_not_transformed() {
+    check(
+        &mut engine(),
+        &[
+            ("wa ", "wa "),
+            ("hix ", "hix "),
+            ("ko ", "ko "),
+            ("dc ", "dc "),
+            ("wa,", "wa,"),
+        ],
+    );
+}
+
+#[test]
+fn other_words_still_transform() {
+    check(
+        &mut engine(),
+        &[
+            ("was ", "ứa "),
+            ("hixx ", "hix "),
+            ("ddc ", "đc "),
+            ("vieetj ", "việt "),
+        ],
+    );
+}
+
+#[test]
+fn abbreviations_are_not_respelled() {
+    let mut e = engine();
+    e.set_spelling_autofix(true);
+    check(&mut e, &[("ko ", "ko "), ("kos ", "có ")]);
+}
+
+#[test]
+fn shortcuts_win() {
+    let mut e = engine();
+    e.shortcuts_mut().add(Shortcut::new("ko", "không"));
+    check(&mut e, &[("ko ", "không "), ("wa ", "wa ")]);
+}
+
+#[test]
+fn added_abbreviations() {
+    let mut e = engine();
+    check(&mut e, &[("ax ", "ã ")]);
+    assert!(e.add_teencode("AX"));
+    assert!(!e.add_teencode("a x"));
+    check(&mut e, &[("ax ", "ax ")]);
+    e.clear_teencode();
+    check(&mut e, &[("ax ", "ã ")]);
+}
+
+#[test]
+fn abbreviations_survive_settings_round_trip() {
+    let mut e = engine();
+    e.add_teencode("vcl");
+    let text = e.export_settings();
+    assert!(text.contains("teencode_passthrough true\n"));
+    assert!(text.contains("[teencode]\nvcl\n"));
+
+    let mut copy = Engine::new();
+    copy.import_settings(&text).unwrap();
+    assert!(copy.teencode_passthrough());
+    assert!(copy.add_teencode("vcl"));
+    assert!(copy.export_settings().contains("[teencode]\nvcl\n"));
+}
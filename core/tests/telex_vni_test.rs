@@ -0,0 +1,62 @@
+//! Telex + VNI input method: both key sets at once, and reverts by
+//! either method's key for the same transform
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn check(cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(3);
+        assert_eq!(type_word(&mut e, input), *expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn accepts_both_key_sets() {
+    check(&[
+        ("vieetj", "việt"),
+        ("vie6t5", "việt"),
+        ("vie6tj", "việt"),
+        ("vieet5", "việt"),
+        ("nguwowif", "người"),
+        ("nguoi72", "người"),
+        ("ddwowngf", "đường"),
+        ("duong9", "đuong"),
+        ("tieng61 Vieetj Nam", "tiếng Việt Nam"),
+        ("a8", "ă"),
+        ("asz", "a"),
+    ]);
+}
+
+#[test]
+fn either_key_reverts() {
+    check(&[
+        ("ass", "as"),
+        ("a11", "a1"),
+        ("as1", "a1"),
+        ("a1s", "as"),
+        ("aa6", "a6"),
+        ("ddd", "dd"),
+        ("dd9", "d9"),
+        // Different transform: replaces instead of reverting
+        ("a12", "à"),
+    ]);
+}
+
+#[test]
+fn circumflex_by_six_reverts_on_its_own_vowel() {
+    check(&[
+        ("to6o", "too"),
+        ("a6a", "aa"),
+        ("to6a", "tôa"),
+        ("to6e", "tôe"),
+    ]);
+}
+
+#[test]
+fn method_reported() {
+    let mut e = Engine::new();
+    e.set_method(3);
+    assert_eq!(e.method(), 3);
+}
@@ -0,0 +1,123 @@
+//! Terminal-safe mode: words reach terminals whole, without backspaces,
+//! or the engine stays out of the app entirely.
+
+use gonhanh_core::engine::terminal::TerminalMode;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::Screen;
+
+/// Type `input` ('<' = Backspace) into a simulated app
+///
+/// Returns the app's text and how many backspaces it received.
+fn type_into_app(e: &mut Engine, input: &str) -> (String, usize) {
+    let mut screen = Screen::new();
+    screen.type_text(e, input);
+    (screen.text(), screen.backspaces())
+}
+
+#[test]
+fn compose_sends_finished_words() {
+    let mut e = Engine::new();
+    e.set_terminal_mode(TerminalMode::Compose);
+
+    assert_eq!(type_into_app(&mut e, "vieetj"), (String::new(), 0));
+    assert_eq!(e.composing(), "việt");
+    assert_eq!(type_into_app(&mut e, " nam."), ("việt nam.".into(), 0));
+    assert_eq!(e.composing(), "");
+
+    // Backspace edits the held word, not the app
+    assert_eq!(type_into_app(&mut e, "dduwowngf<< "), ("đườ ".into(), 0));
+}
+
+#[test]
+fn mode_follows_the_active_app() {
+    let mut e = Engine::new();
+    e.set_app_terminal_mode("WindowsTerminal.exe", Some(TerminalMode::Compose));
+    e.set_app_terminal_mode("parsecd", Some(TerminalMode::Passthrough));
+
+    e.shortcuts_mut()
+        .set_active_app(Some("windowsterminal.exe"));
+    assert_eq!(e.terminal_mode(), TerminalMode::Compose);
+    assert_eq!(type_into_app(&mut e, "tieengs "), ("tiếng ".into(), 0));
+
+    e.shortcuts_mut().set_active_app(Some("notepad.exe"));
+    let (text, backspaces) = type_into_app(&mut e, "tieengs ");
+    assert_eq!(text, "tiếng ");
+    assert!(backspaces > 0);
+
+    e.shortcuts_mut().set_active_app(Some("parsecd.exe"));
+    assert_eq!(type_into_app(&mut e, "tieengs "), ("tieengs ".into(), 0));
+
+    e.set_app_terminal_mode("parsecd", None);
+    assert_eq!(e.terminal_mode(), TerminalMode::Off);
+}
+
+#[test]
+fn held_word_can_be_taken() {
+    let mut e = Engine::new();
+    e.set_terminal_mode(TerminalMode::Compose);
+    type_into_app(&mut e, "xin chaof");
+    assert_eq!(e.take_composing(), "chào");
+    assert_eq!(e.composing(), "");
+}
+
+#[test]
+fn terminal_apps_round_trip_in_settings() {
+    let mut e = Engine::new();
+    e.set_terminal_mode(TerminalMode::Compose);
+    e.set_app_terminal_mode("Claude Code", Some(TerminalMode::Off));
+    e.set_app_terminal_mode("wezterm", Some(TerminalMode::Passthrough));
+    let text = e.export_settings();
+    assert!(text.contains("[terminal_apps]\n0 claude code\n2 wezterm\n"));
+
+    let mut copy = Engine::new();
+    copy.import_settings(&text).unwrap();
+    assert_eq!(copy.option("terminal_mode").as_deref(), Some("1"));
+    let apps: Vec<_> = copy.terminal_apps().collect();
+    assert_eq!(
+        apps,
+        [
+            ("claude code", TerminalMode::Off),
+            ("wezterm", TerminalMode::Passthrough)
+        ]
+    );
+
+    let bad = text.replace("2 wezterm", "7 wezterm");
+    assert!(copy.import_settings(&bad).is_err());
+}
+
+#[test]
+fn compose_counts_decomposed_backspaces_in_the_held_word() {
+    let mut e = Engine::new();
+    e.set_terminal_mode(TerminalMode::Compose);
+    e.set_decomposed_backspace(true);
+    assert_eq!(type_into_app(&mut e, "vieetj "), ("việt ".into(), 0));
+    assert_eq!(type_into_app(&mut e, "oso "), ("ố ".into(), 0));
+}
+
+#[test]
+fn compose_undo_edits_the_held_word() {
+    let mut e = Engine::new();
+    e.set_terminal_mode(TerminalMode::Compose);
+    e.set_auto_capitalize(true);
+    assert_eq!(type_into_app(&mut e, "ok. v"), ("ok. ".into(), 0));
+    assert_eq!(e.composing(), "V");
+    assert!(e.undo_last().key_consumed());
+    assert_eq!(e.composing(), "v");
+    assert_eq!(type_into_app(&mut e, "n "), ("vn ".into(), 0));
+}
+
+#[test]
+fn compose_word_delete_takes_the_held_word() {
+    use gonhanh_core::data::keys;
+
+    let mut e = Engine::new();
+    e.set_terminal_mode(TerminalMode::Compose);
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "xin chaof");
+    let r = screen.press(&mut e, keys::DELETE, false, true, false);
+    assert!(r.key_consumed());
+    assert_eq!(e.composing(), "");
+    // The app still has "xin ": Backspace brings the word back
+    screen.type_text(&mut e, "<f ");
+    assert_eq!(screen.text(), "xìn ");
+}
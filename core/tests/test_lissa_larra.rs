@@ -1,36 +1,5 @@
 use gonhanh_core::engine::Engine;
-
-fn char_to_key(c: char) -> u16 {
-    match c.to_ascii_lowercase() {
-        'a' => 0,
-        's' => 1,
-        'd' => 2,
-        'f' => 3,
-        'h' => 4,
-        'g' => 5,
-        'z' => 6,
-        'x' => 7,
-        'c' => 8,
-        'v' => 9,
-        'b' => 11,
-        'q' => 12,
-        'w' => 13,
-        'e' => 14,
-        'r' => 15,
-        'y' => 16,
-        't' => 17,
-        'o' => 31,
-        'u' => 32,
-        'i' => 34,
-        'p' => 35,
-        'l' => 37,
-        'j' => 38,
-        'k' => 40,
-        'n' => 45,
-        'm' => 46,
-        _ => 255,
-    }
-}
+use gonhanh_core::testkit::Screen;
 
 fn type_word_with_space(engine: &mut Engine, word: &str) -> String {
     type_word_with_space_debug(engine, word, false)
@@ -38,52 +7,14 @@ fn type_word_with_space(engine: &mut Engine, word: &str) -> String {
 
 fn type_word_with_space_debug(engine: &mut Engine, word: &str, debug: bool) -> String {
     engine.clear();
-    let mut output = String::new();
-
-    for ch in word.chars() {
-        let key = char_to_key(ch);
-        if key == 255 {
-            output.push(ch);
-            continue;
-        }
-        let result = engine.on_key(key, ch.is_uppercase(), false);
-        if result.action == 1 {
-            let bs = result.backspace as usize;
-            for _ in 0..bs.min(output.len()) {
-                output.pop();
-            }
-            for i in 0..result.count as usize {
-                if let Some(c) = char::from_u32(result.chars[i]) {
-                    output.push(c);
-                }
-            }
-        } else {
-            output.push(ch);
-        }
+    let mut screen = Screen::new();
+    for ch in word.chars().chain([' ']) {
+        screen.type_text(engine, &ch.to_string());
         if debug {
-            println!("  after '{}': output = '{}'", ch, output);
+            println!("  after {:?}: output = '{}'", ch, screen.text());
         }
     }
-
-    // Type space
-    let result = engine.on_key(49, false, false);
-    if result.action == 1 {
-        let bs = result.backspace as usize;
-        for _ in 0..bs.min(output.len()) {
-            output.pop();
-        }
-        for i in 0..result.count as usize {
-            if let Some(c) = char::from_u32(result.chars[i]) {
-                output.push(c);
-            }
-        }
-    } else {
-        output.push(' ');
-    }
-    if debug {
-        println!("  after space: output = '{}'", output);
-    }
-    output
+    screen.text()
 }
 
 #[test]
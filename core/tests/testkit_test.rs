@@ -0,0 +1,56 @@
+//! Typing simulator: the app side of every key, for golden tests
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::testkit::{keystroke, type_text, Screen};
+
+#[test]
+fn types_text_as_an_app_shows_it() {
+    let mut e = Engine::new();
+    assert_eq!(
+        type_text(&mut e, "Vieetj Nam, 2024!\n"),
+        "Việt Nam, 2024!\n"
+    );
+    let mut e = Engine::new();
+    assert_eq!(type_text(&mut e, "chaof<<o"), "cho");
+}
+
+#[test]
+fn uppercase_is_typed_with_shift() {
+    let k = keystroke('A').unwrap();
+    assert_eq!((k.key, k.caps, k.shift), (keys::A, true, true));
+    let k = keystroke('@').unwrap();
+    assert_eq!((k.key, k.caps, k.shift), (keys::N2, false, true));
+}
+
+#[test]
+fn cursor_marker_moves_the_caret() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("ngoac", "(|)"));
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "ngoac ");
+    assert_eq!((screen.text().as_str(), screen.caret()), ("() ", 1));
+    // Typing continues at the caret
+    screen.type_text(&mut e, "a");
+    assert_eq!(screen.text(), "(a) ");
+}
+
+#[test]
+fn existing_text_is_kept() {
+    let mut e = Engine::new();
+    let mut screen = Screen::with_text("Xin chào ");
+    screen.type_text(&mut e, "bajn");
+    assert_eq!(screen.text(), "Xin chào bạn");
+    screen.press(&mut e, keys::LEFT, false, false, false);
+    assert_eq!(screen.caret(), 11);
+}
+
+#[test]
+fn counts_backspaces_the_app_receives() {
+    let mut e = Engine::new();
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "as<");
+    // One to put the mark on "a", one typed
+    assert_eq!((screen.text().as_str(), screen.backspaces()), ("", 2));
+}
@@ -0,0 +1,69 @@
+//! Select-all, paste and text-change notifications
+//!
+//! The host reports Cmd/Ctrl+A, Cmd/Ctrl+V and text the app changed by
+//! itself; the engine then drops the word in progress and any pending undo
+//! instead of guessing from the next key.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::utils::type_word;
+
+#[test]
+fn select_all_drops_word_in_progress() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vie");
+    e.on_select_all();
+    // "ej" types over the selection: nothing left to put a mark on
+    assert_eq!(type_word(&mut e, "ej"), "ẹ");
+}
+
+#[test]
+fn select_all_drops_pending_undo() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_backspace_undo_restore(true);
+    assert_eq!(type_word(&mut e, "user "), "user ");
+    e.on_select_all();
+    assert_eq!(e.undo_last().action, Action::None as u8);
+    // Backspace deletes the selection, it does not bring back "uẻ"
+    assert_eq!(
+        e.on_key(keys::DELETE, false, false).action,
+        Action::None as u8
+    );
+}
+
+#[test]
+fn paste_starts_a_new_word() {
+    let mut e = Engine::new();
+    type_word(&mut e, "a");
+    e.on_paste(Some("xin chào"));
+    let r = e.on_key(keys::A, false, false);
+    assert_eq!(r.action, Action::None as u8);
+}
+
+#[test]
+fn paste_text_tail_is_token_context() {
+    // "get" + "Vieejt" is a camelCase identifier
+    let mut e = Engine::new();
+    e.set_code_context(true);
+    e.on_paste(Some("get"));
+    assert_eq!(type_word(&mut e, "Vieejt"), "Vieejt");
+
+    // Unknown or whitespace-ended paste: a plain word
+    for text in [None, Some("get ")] {
+        let mut e = Engine::new();
+        e.set_code_context(true);
+        e.on_paste(text);
+        assert_eq!(type_word(&mut e, "Vieejt"), "Việt");
+    }
+}
+
+#[test]
+fn text_changed_forgets_previous_word() {
+    let mut e = Engine::new();
+    type_word(&mut e, "xin ");
+    e.on_text_changed();
+    // Backspace over the space no longer brings "xin" back for editing
+    e.on_key(keys::DELETE, false, false);
+    assert_eq!(e.on_key(keys::F, false, false).action, Action::None as u8);
+}
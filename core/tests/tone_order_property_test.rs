@@ -0,0 +1,289 @@
+//! Tone-order invariance properties
+//!
+//! Engine guarantee: within one syllable, the position of the tone key (and
+//! of the circumflex/horn key) does not change the result once the base vowel
+//! is typed. `mias`, `misa` → mía; `kiasn`, `kisan`, `kisna` → kían.
+//!
+//! Syllables are generated from initial × nucleus × final × tone tables with a
+//! seeded PRNG (no proptest: dev-dependencies stay minimal), for both Telex
+//! and VNI. Every reordering of the modifier keys must match the canonical
+//! (modifiers-last) spelling. Failures list every offending input.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+/// Initials valid before every nucleus below (no c/k/q/g spelling rules)
+const INITIALS: &[&str] = &[
+    "", "b", "ch", "d", "h", "kh", "l", "m", "n", "nh", "ph", "s", "t", "th", "tr", "v", "x",
+];
+
+/// Nucleus: plain vowel keys, optional modifier key, allowed finals
+struct Nucleus {
+    vowels: &'static str,
+    modifier: Option<char>,
+    finals: &'static [&'static str],
+}
+
+const NUCLEI: &[Nucleus] = &[
+    Nucleus {
+        vowels: "a",
+        modifier: None,
+        finals: &["", "c", "m", "n", "ng", "nh", "p", "t", "i", "o", "u", "y"],
+    },
+    Nucleus {
+        vowels: "a",
+        modifier: Some('a'),
+        finals: &["c", "m", "n", "ng", "p", "t", "u", "y"],
+    },
+    Nucleus {
+        vowels: "a",
+        modifier: Some('w'),
+        finals: &["c", "m", "n", "ng", "p", "t"],
+    },
+    Nucleus {
+        vowels: "e",
+        modifier: None,
+        finals: &["", "c", "m", "n", "ng", "p", "t", "o"],
+    },
+    Nucleus {
+        vowels: "e",
+        modifier: Some('e'),
+        finals: &["", "c", "ch", "m", "n", "nh", "p", "t", "u"],
+    },
+    Nucleus {
+        vowels: "i",
+        modifier: None,
+        finals: &["", "c", "ch", "m", "n", "nh", "p", "t", "u"],
+    },
+    Nucleus {
+        vowels: "o",
+        modifier: None,
+        finals: &["", "c", "m", "n", "ng", "p", "t", "i"],
+    },
+    Nucleus {
+        vowels: "o",
+        modifier: Some('o'),
+        finals: &["", "c", "m", "n", "ng", "p", "t", "i"],
+    },
+    Nucleus {
+        vowels: "o",
+        modifier: Some('w'),
+        finals: &["", "m", "n", "p", "t", "i"],
+    },
+    Nucleus {
+        vowels: "u",
+        modifier: None,
+        finals: &["", "c", "m", "n", "ng", "p", "t", "i"],
+    },
+    Nucleus {
+        vowels: "u",
+        modifier: Some('w'),
+        finals: &["", "c", "ng", "t", "i", "u"],
+    },
+    Nucleus {
+        vowels: "ia",
+        modifier: None,
+        finals: &[""],
+    },
+    Nucleus {
+        vowels: "ie",
+        modifier: Some('e'),
+        finals: &["c", "m", "n", "ng", "p", "t", "u"],
+    },
+    Nucleus {
+        vowels: "ua",
+        modifier: None,
+        finals: &[""],
+    },
+    Nucleus {
+        vowels: "uo",
+        modifier: Some('o'),
+        finals: &["c", "m", "n", "ng", "p", "t", "i"],
+    },
+    Nucleus {
+        vowels: "uo",
+        modifier: Some('w'),
+        finals: &["c", "m", "n", "ng", "p", "t", "i", "u"],
+    },
+];
+
+/// Stop finals only take sắc or nặng
+const STOP_FINALS: &[&str] = &["c", "ch", "p", "t"];
+
+/// xorshift64 - reproducible case generation
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.next() as usize % items.len()]
+    }
+}
+
+/// A generated syllable split into its key groups
+struct Syllable {
+    initial: &'static str,
+    vowels: &'static str,
+    modifier: Option<char>,
+    fin: &'static str,
+    tone: char,
+    vni: bool,
+}
+
+impl Syllable {
+    fn generate(rng: &mut Rng, vni: bool) -> Self {
+        let nucleus = &NUCLEI[rng.next() as usize % NUCLEI.len()];
+        let fin = rng.pick(nucleus.finals);
+        let tones: &[char] = if STOP_FINALS.contains(&fin) {
+            &['s', 'j']
+        } else {
+            &['s', 'f', 'r', 'x', 'j']
+        };
+        Syllable {
+            initial: rng.pick(INITIALS),
+            vowels: nucleus.vowels,
+            modifier: nucleus.modifier,
+            fin,
+            tone: rng.pick(tones),
+            vni,
+        }
+    }
+
+    /// Tone key for the syllable's input method
+    fn tone_key(&self) -> char {
+        if !self.vni {
+            return self.tone;
+        }
+        match self.tone {
+            's' => '1',
+            'f' => '2',
+            'r' => '3',
+            'x' => '4',
+            _ => '5',
+        }
+    }
+
+    /// Circumflex/horn/breve key for the syllable's input method
+    fn modifier_key(&self) -> Option<char> {
+        let modifier = self.modifier?;
+        if !self.vni {
+            return Some(modifier);
+        }
+        Some(match (modifier, self.vowels) {
+            ('w', "a") => '8',
+            ('w', _) => '7',
+            _ => '6',
+        })
+    }
+
+    /// Modifiers typed after everything else
+    fn canonical(&self) -> String {
+        let mut s = format!("{}{}{}", self.initial, self.vowels, self.fin);
+        s.extend(self.modifier_key());
+        s.push(self.tone_key());
+        s
+    }
+
+    /// Every placement of the tone key after the first vowel
+    fn tone_orders(&self) -> Vec<String> {
+        let mut base = format!("{}{}{}", self.initial, self.vowels, self.fin);
+        base.extend(self.modifier_key());
+        let first_vowel = self.initial.len() + 1;
+        (first_vowel..=base.len())
+            .map(|i| {
+                let mut s = base.clone();
+                s.insert(i, self.tone_key());
+                s
+            })
+            .collect()
+    }
+
+    /// Every placement of the modifier key after its base vowel (tone last)
+    fn modifier_orders(&self) -> Vec<String> {
+        let Some(modifier) = self.modifier_key() else {
+            return Vec::new();
+        };
+        let base = format!("{}{}{}", self.initial, self.vowels, self.fin);
+        // Horn on uo and circumflex on ie/uo attach to the last nucleus vowel
+        let after_base = self.initial.len() + self.vowels.len();
+        (after_base..=base.len())
+            .map(|i| {
+                let mut s = base.clone();
+                s.insert(i, modifier);
+                s.push(self.tone_key());
+                s
+            })
+            .collect()
+    }
+}
+
+fn type_with(method: u8, input: &str) -> String {
+    let mut e = Engine::new();
+    e.set_method(method);
+    type_word(&mut e, input)
+}
+
+fn telex(input: &str) -> String {
+    type_with(0, input)
+}
+
+/// Run `cases` syllables through `orders`, collecting mismatches
+fn check(method: u8, cases: usize, seed: u64, orders: fn(&Syllable) -> Vec<String>) {
+    let mut rng = Rng(seed);
+    let mut failures = Vec::new();
+    for _ in 0..cases {
+        let syllable = Syllable::generate(&mut rng, method == 1);
+        let canonical = syllable.canonical();
+        let expected = type_with(method, &canonical);
+        for input in orders(&syllable) {
+            let actual = type_with(method, &input);
+            if actual != expected {
+                failures.push(format!(
+                    "{} → {} (expected {} from {})",
+                    input, actual, expected, canonical
+                ));
+            }
+        }
+    }
+    failures.sort();
+    failures.dedup();
+    assert!(
+        failures.is_empty(),
+        "{} order-dependent results:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn telex_tone_position_does_not_change_result() {
+    check(0, 3000, 0x2545_f491_4f6c_dd1d, Syllable::tone_orders);
+}
+
+#[test]
+fn telex_modifier_position_does_not_change_result() {
+    check(0, 3000, 0x9e37_79b9_7f4a_7c15, Syllable::modifier_orders);
+}
+
+#[test]
+fn vni_tone_position_does_not_change_result() {
+    check(1, 3000, 0x2545_f491_4f6c_dd1d, Syllable::tone_orders);
+}
+
+#[test]
+fn vni_modifier_position_does_not_change_result() {
+    check(1, 3000, 0x9e37_79b9_7f4a_7c15, Syllable::modifier_orders);
+}
+
+#[test]
+fn examples_from_spec() {
+    assert_eq!(telex("mias"), telex("misa"));
+    assert_eq!(telex("kiasn"), telex("kisna"));
+    assert_eq!(telex("kiasn"), "kían");
+}
@@ -0,0 +1,99 @@
+//! Tone placement styles (hòa ↔ hoà): golden corpora and normalization
+//!
+//! `data/tone_style_{traditional,new}.txt` hold Telex input and the expected
+//! output for each style.
+
+use gonhanh_core::convert::{normalize_tone, type_text};
+use gonhanh_core::engine::{Engine, ToneStyle};
+
+const NEW: ToneStyle = ToneStyle::NewStyle;
+const TRADITIONAL: ToneStyle = ToneStyle::TraditionalStyle;
+
+/// (input, expected) pairs, skipping `#` comments
+fn corpus(content: &str) -> Vec<(&str, &str)> {
+    content
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.split_once('\t').expect("tab-separated line"))
+        .collect()
+}
+
+fn check_corpus(style: ToneStyle, content: &str) {
+    let mut e = Engine::new();
+    e.set_tone_style(style);
+    for (input, expected) in corpus(content) {
+        assert_eq!(
+            type_text(&mut e, input),
+            expected,
+            "{:?} '{}'",
+            style,
+            input
+        );
+    }
+}
+
+#[test]
+fn golden_traditional_style() {
+    check_corpus(TRADITIONAL, include_str!("data/tone_style_traditional.txt"));
+}
+
+#[test]
+fn golden_new_style() {
+    check_corpus(NEW, include_str!("data/tone_style_new.txt"));
+}
+
+#[test]
+fn golden_corpora_normalize_into_each_other() {
+    let traditional = corpus(include_str!("data/tone_style_traditional.txt"));
+    let new = corpus(include_str!("data/tone_style_new.txt"));
+    assert_eq!(traditional.len(), new.len());
+    for ((input, old), (_, modern)) in traditional.iter().zip(&new) {
+        assert_eq!(normalize_tone(old, NEW), *modern, "'{}'", input);
+        assert_eq!(normalize_tone(modern, TRADITIONAL), *old, "'{}'", input);
+    }
+}
+
+#[test]
+fn modern_flag_maps_to_style() {
+    let mut e = Engine::new();
+    assert_eq!(e.tone_style(), NEW);
+    e.set_modern_tone(false);
+    assert_eq!(e.tone_style(), TRADITIONAL);
+    assert_eq!(ToneStyle::from_u8(0), TRADITIONAL);
+    assert_eq!(ToneStyle::from_u8(7), NEW);
+}
+
+#[test]
+fn mixed_document_in_one_pass() {
+    let text = "Khánh Hòa, Thanh Hoá; THỦY và thuỷ, khỏe khoẻ.";
+    assert_eq!(
+        normalize_tone(text, NEW),
+        "Khánh Hoà, Thanh Hoá; THUỶ và thuỷ, khoẻ khoẻ."
+    );
+    assert_eq!(
+        normalize_tone(text, TRADITIONAL),
+        "Khánh Hòa, Thanh Hóa; THỦY và thủy, khỏe khỏe."
+    );
+}
+
+#[test]
+fn same_placement_as_typing() {
+    let words = "hoaf thuyr khoer hoaj quyf giaf muaf nguyeenx truowngf hoanf tuaan";
+    let typed = |style| {
+        let mut e = Engine::new();
+        e.set_tone_style(style);
+        type_text(&mut e, words)
+    };
+    let (modern, traditional) = (typed(NEW), typed(TRADITIONAL));
+    assert_eq!(normalize_tone(&traditional, NEW), modern);
+    assert_eq!(normalize_tone(&modern, TRADITIONAL), traditional);
+}
+
+#[test]
+fn leaves_other_words() {
+    let text = "café naïve 日本 hòaa http://x.vn/hòa";
+    assert_eq!(
+        normalize_tone(text, NEW),
+        "café naïve 日本 hòaa http://x.vn/hoà"
+    );
+}
@@ -1434,9 +1434,10 @@ const TELEX_ENGLISH_AW_WORDS: &[(&str, &str)] = &[
     ("xepse ", "xếp "), // post-tone: e → p → s → e
     // xếp with different tones (post-tone circumflex)
     // Note: 'r' doesn't work after consonant (treated as consonant, not tone modifier)
-    ("xepfe ", "xềp "), // huyền tone
-    ("xepxe ", "xễp "), // ngã tone
-    ("xepje ", "xệp "), // nặng tone
+    // Stop final -p only takes sắc/nặng: huyền and ngã are refused
+    ("xepfe ", "xepfe "), // huyền tone
+    ("xepxe ", "xepxe "), // ngã tone
+    ("xepje ", "xệp "),   // nặng tone
     // tấm pattern (â) - all 3 typing orders
     ("taams ", "tấm "), // standard: aa → m → s
     ("tamas ", "tấm "), // delayed circumflex: a → m → a → s
@@ -1704,3 +1705,38 @@ fn telex_traditional_tone_placement() {
 fn vni_traditional_tone_placement() {
     vni_traditional(VNI_TRADITIONAL_TONE);
 }
+
+// ============================================================
+// TONE + STOP FINAL (p, t, c, ch take only sắc or nặng)
+// ============================================================
+
+const TELEX_STOP_FINAL_TONE: &[(&str, &str)] = &[
+    ("hocs", "hóc"),
+    ("hocj", "học"),
+    ("thichs", "thích"),
+    // huyền/hỏi/ngã after the final are refused: the key is a letter
+    ("hocf", "hocf"),
+    ("matr", "matr"),
+    ("thichx", "thichx"),
+];
+
+const VNI_STOP_FINAL_TONE: &[(&str, &str)] =
+    &[("hoc5", "học"), ("hoc2", "hoc2"), ("thap3", "thap3")];
+
+/// Tone typed before the final is rejected at the word boundary
+const TELEX_STOP_FINAL_RESTORE: &[(&str, &str)] = &[("hoafp ", "hoafp "), ("hofc ", "hofc ")];
+
+#[test]
+fn telex_stop_final_tone() {
+    telex(TELEX_STOP_FINAL_TONE);
+}
+
+#[test]
+fn vni_stop_final_tone() {
+    vni(VNI_STOP_FINAL_TONE);
+}
+
+#[test]
+fn telex_stop_final_tone_auto_restore() {
+    telex_auto_restore(TELEX_STOP_FINAL_RESTORE);
+}
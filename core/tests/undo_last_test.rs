@@ -0,0 +1,137 @@
+//! Undo of the last automatic action
+//!
+//! `Engine::undo_last` (or its hotkey) covers auto-restore, shortcut
+//! expansion and auto-capitalize: undo right after the action puts back
+//! what was on screen before it. With `set_backspace_undo_restore`, a
+//! single Backspace right after an auto-restore also undoes it.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::hotkey::{Hotkey, HotkeyAction};
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::utils::type_word;
+
+/// Type `input`, call `undo_last`, then type `after`; returns the screen
+fn type_undo(e: &mut Engine, input: &str, after: &str) -> String {
+    let mut screen = type_word(e, input);
+    let r = e.undo_last();
+    if r.action == Action::Send as u8 {
+        for _ in 0..r.backspace {
+            screen.pop();
+        }
+        screen.extend((0..r.count as usize).filter_map(|i| char::from_u32(r.chars[i])));
+    }
+    screen + &type_word(e, after)
+}
+
+#[test]
+fn undo_auto_restore_on_space() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    assert_eq!(type_word(&mut e, "user "), "user ");
+
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    assert_eq!(type_undo(&mut e, "user ", ""), "uẻ ");
+}
+
+#[test]
+fn undo_auto_restore_on_punctuation() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    assert_eq!(type_undo(&mut e, "user,", ""), "uẻ,");
+}
+
+#[test]
+fn undo_shortcut_expansion() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("vn", "Việt Nam"));
+    assert_eq!(type_undo(&mut e, "vn ", "ok"), "vn ok");
+
+    // Immediate symbol shortcut
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::immediate("->", "→"));
+    assert_eq!(type_undo(&mut e, "->", ""), "->");
+}
+
+#[test]
+fn undo_auto_capitalize() {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    assert_eq!(type_undo(&mut e, "ok. b", "awn"), "ok. băn");
+}
+
+#[test]
+fn undo_hotkey() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_hotkey(
+        HotkeyAction::UndoLast,
+        Some(Hotkey::new(keys::Z, true, true)),
+    );
+    type_word(&mut e, "user ");
+    let r = e.on_key_ext(keys::Z, true, true, true);
+    assert!(r.key_consumed());
+    assert_eq!(r.action, Action::Send as u8);
+    let text: String = (0..r.count as usize)
+        .filter_map(|i| char::from_u32(r.chars[i]))
+        .collect();
+    assert_eq!((r.backspace, text.as_str()), (5, "uẻ "));
+
+    // Nothing left to undo: the key is still swallowed
+    let r = e.on_key_ext(keys::Z, true, true, true);
+    assert!(r.key_consumed());
+    assert_eq!(r.action, Action::None as u8);
+}
+
+#[test]
+fn undo_only_right_after_action() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    type_word(&mut e, "user a");
+    assert_eq!(e.undo_last().action, Action::None as u8);
+
+    // Nothing automatic happened
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj");
+    assert_eq!(e.undo_last().action, Action::None as u8);
+}
+
+// ============================================================
+// BACKSPACE RIGHT AFTER AUTO-RESTORE
+// ============================================================
+
+fn auto_restore(input: &str) -> String {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_backspace_undo_restore(true);
+    type_word(&mut e, input)
+}
+
+#[test]
+fn backspace_keeps_restore_when_disabled() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    assert_eq!(type_word(&mut e, "user <"), "user");
+}
+
+#[test]
+fn backspace_undoes_auto_restore() {
+    // Backspace removes the space and brings back the Vietnamese rendering
+    assert_eq!(auto_restore("user <"), "uẻ");
+    assert_eq!(auto_restore("user,<"), "uẻ");
+    assert_eq!(auto_restore("nurses <"), "nues");
+}
+
+#[test]
+fn undone_word_is_not_restored_again() {
+    assert_eq!(auto_restore("user < "), "uẻ ");
+    assert_eq!(auto_restore("user <. ok"), "uẻ. ok");
+}
+
+#[test]
+fn backspace_undo_is_one_keystroke_only() {
+    // Second backspace edits normally; typing after the space clears the snapshot
+    assert_eq!(auto_restore("user <<"), "u");
+    assert_eq!(auto_restore("user a<"), "user ");
+}
@@ -0,0 +1,54 @@
+//! User dictionaries: `*.dic` files in a folder count as valid Vietnamese
+
+use gonhanh_core::data::dictionary::{
+    is_user_word, is_vietnamese, load_user_dir, reset_user, user_word_count,
+};
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+use std::fs;
+
+fn typed(input: &str) -> String {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    type_word(&mut e, input)
+}
+
+// User words are process-wide, so everything runs in one test
+#[test]
+fn user_words_join_the_vietnamese_list() {
+    let dir = std::env::temp_dir().join(format!("gonhanh-dictionaries-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("loan.dic"), "# từ mượn\nhẻ\nSịp\n").unwrap();
+    fs::write(dir.join("notes.txt"), "qoan\n").unwrap();
+
+    assert_eq!(typed("her "), "her ");
+    assert!(!is_vietnamese("sịp", false));
+
+    assert_eq!(load_user_dir(&dir), Ok(2));
+    assert_eq!(user_word_count(), 2);
+    // A syllable the user listed is not taken for English "her"
+    assert!(is_user_word("Hẻ"));
+    assert_eq!(typed("her "), "hẻ ");
+    assert!(is_vietnamese("Sịp", false));
+    // Only *.dic files are read
+    assert!(!is_vietnamese("qoan", false));
+
+    // Reloading replaces the words loaded before
+    fs::remove_file(dir.join("loan.dic")).unwrap();
+    fs::write(dir.join("y_khoa.dic"), "2\nxét nghiệm\n").unwrap();
+    assert_eq!(load_user_dir(&dir), Ok(2));
+    assert!(!is_user_word("hẻ") && is_user_word("nghiệm"));
+    assert_eq!(typed("her "), "her ");
+
+    // A missing folder loads nothing
+    assert_eq!(load_user_dir(&dir.join("missing")), Ok(0));
+    assert_eq!(user_word_count(), 0);
+
+    fs::write(dir.join("loan.dic"), "hẻ\n").unwrap();
+    assert_eq!(load_user_dir(&dir), Ok(3));
+    reset_user();
+    assert_eq!(user_word_count(), 0);
+    assert_eq!(typed("her "), "her ");
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,191 @@
+//! Word hooks: a host script sees committed words and may rewrite them
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::hooks::{WordHook, TIME_BUDGET};
+use gonhanh_core::engine::shortcut::{Shortcut, MAX_REPLACEMENT_LEN};
+use gonhanh_core::engine::{Action, Engine, Result};
+use gonhanh_core::testkit::{type_text, Screen};
+
+/// Rewrites "kg" to "kilôgam" and keeps every word it saw
+#[derive(Debug, Default)]
+struct Units {
+    seen: Arc<Mutex<Vec<String>>>,
+}
+
+impl WordHook for Units {
+    fn on_commit(&mut self, word: &str) -> Option<String> {
+        self.seen.lock().unwrap().push(word.to_string());
+        (word == "kg").then(|| "kilôgam".to_string())
+    }
+}
+
+/// Takes longer than the engine allows
+#[derive(Debug)]
+struct Slow;
+
+impl WordHook for Slow {
+    fn on_commit(&mut self, _word: &str) -> Option<String> {
+        thread::sleep(TIME_BUDGET * 2);
+        Some("chậm".to_string())
+    }
+}
+
+/// Rewrites every word to the same text
+#[derive(Debug)]
+struct Fixed(String);
+
+impl WordHook for Fixed {
+    fn on_commit(&mut self, _word: &str) -> Option<String> {
+        Some(self.0.clone())
+    }
+}
+
+fn engine_with_units() -> (Engine, Arc<Mutex<Vec<String>>>) {
+    let mut e = Engine::new();
+    let hook = Units::default();
+    let seen = hook.seen.clone();
+    e.set_word_hook(Some(Box::new(hook)));
+    (e, seen)
+}
+
+#[test]
+fn hooks_are_off_by_default() {
+    let (mut e, seen) = engine_with_units();
+    assert!(!e.word_hooks());
+    assert_eq!(type_text(&mut e, "kg x"), "kg x");
+    assert!(seen.lock().unwrap().is_empty());
+}
+
+#[test]
+fn hook_sees_committed_words() {
+    let (mut e, seen) = engine_with_units();
+    e.set_word_hooks(true);
+    assert_eq!(type_text(&mut e, "xin chaof ban "), "xin chào ban ");
+    assert_eq!(*seen.lock().unwrap(), ["xin", "chào", "ban"]);
+}
+
+#[test]
+fn rewrite_goes_out_with_the_boundary_key() {
+    let (mut e, _) = engine_with_units();
+    e.set_word_hooks(true);
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "5 kg ");
+    assert_eq!(screen.text(), "5 kilôgam ");
+
+    let (mut e, _) = engine_with_units();
+    e.set_word_hooks(true);
+    assert_eq!(type_text(&mut e, "kg,"), "kilôgam,");
+    let (mut e, _) = engine_with_units();
+    e.set_word_hooks(true);
+    assert_eq!(type_text(&mut e, "kg ."), "kilôgam .");
+}
+
+#[test]
+fn rewrite_stays_when_a_non_text_key_follows() {
+    for (input, expected) in [("kg <x", "kilôgamx"), ("kg\nx", "kg\nx")] {
+        let (mut e, _) = engine_with_units();
+        e.set_word_hooks(true);
+        assert_eq!(type_text(&mut e, input), expected, "{:?}", input);
+    }
+    let (mut e, _) = engine_with_units();
+    e.set_word_hooks(true);
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "kg ");
+    screen.press(&mut e, keys::LEFT, false, false, false);
+    screen.press(&mut e, keys::C, false, true, false);
+    screen.type_text(&mut e, "x");
+    assert_eq!(screen.text(), "kilôgamx ");
+}
+
+#[test]
+fn hook_rewrite_can_be_undone() {
+    let (mut e, _) = engine_with_units();
+    e.set_word_hooks(true);
+    let mut screen = Screen::new();
+    screen.type_text(&mut e, "kg ");
+    assert_eq!(screen.text(), "kilôgam ");
+    screen.undo(&mut e);
+    assert_eq!(screen.text(), "kg ");
+}
+
+#[test]
+fn hook_runs_with_the_engine_free() {
+    let (mut e, seen) = engine_with_units();
+    e.set_word_hooks(true);
+    let mut screen = Screen::new();
+    for key in [keys::K, keys::G, keys::SPACE] {
+        let r = e.on_key(key, false, false);
+        screen.apply(&e, &r, key, false, false, false);
+    }
+    let mut call = e.take_word_hook_call().unwrap();
+    assert_eq!(call.word(), "kg");
+    // The engine keeps working while the hook runs elsewhere
+    call.run();
+    let mut r = screen.press(&mut e, keys::X, false, false, false);
+    e.finish_word_hook_call(call, &mut r);
+    // A key came in meanwhile: too late for the rewrite
+    assert_eq!(r.action, 0);
+    screen.type_text(&mut e, "y");
+    assert_eq!(screen.text(), "kg xy");
+    assert_eq!(*seen.lock().unwrap(), ["kg"]);
+}
+
+#[test]
+fn rewrite_must_fit_after_the_sent_text() {
+    let prefix = ['x'; 10];
+    // The key's result sends the prefix, then the word and its boundary
+    let key_result = || {
+        let mut chars = prefix.to_vec();
+        chars.extend("kg ".chars());
+        Result::send(0, &chars)
+    };
+    let fits = MAX_REPLACEMENT_LEN - prefix.len() - 1;
+    for (len, merged) in [
+        (fits, true),
+        (fits + 1, false),
+        (MAX_REPLACEMENT_LEN, false),
+    ] {
+        let mut e = Engine::new();
+        e.set_word_hook(Some(Box::new(Fixed("y".repeat(len)))));
+        e.set_word_hooks(true);
+        for key in [keys::K, keys::G, keys::SPACE] {
+            e.on_key(key, false, false);
+        }
+        let mut call = e.take_word_hook_call().unwrap();
+        call.run();
+        let mut r = key_result();
+        e.finish_word_hook_call(call, &mut r);
+        assert_eq!(r.action, Action::Send as u8);
+        let text: String = (0..r.count as usize)
+            .filter_map(|i| char::from_u32(r.chars[i]))
+            .collect();
+        let expected = if merged {
+            format!("xxxxxxxxxx{} ", "y".repeat(len))
+        } else {
+            "xxxxxxxxxxkg ".to_string()
+        };
+        assert_eq!(text, expected, "rewrite of {} chars", len);
+    }
+}
+
+#[test]
+fn shortcuts_come_first() {
+    let (mut e, seen) = engine_with_units();
+    e.set_word_hooks(true);
+    e.shortcuts_mut().add(Shortcut::new("kg", "ki lô"));
+    assert_eq!(type_text(&mut e, "kg x"), "ki lô x");
+    assert!(seen.lock().unwrap().is_empty());
+}
+
+#[test]
+fn slow_hook_turns_hooks_off() {
+    let mut e = Engine::new();
+    e.set_word_hook(Some(Box::new(Slow)));
+    e.set_word_hooks(true);
+    // The late rewrite is dropped
+    assert_eq!(type_text(&mut e, "nhanh x"), "nhanh x");
+    assert!(!e.word_hooks());
+}
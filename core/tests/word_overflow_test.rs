@@ -0,0 +1,65 @@
+//! Words longer than `MAX_WORD_KEYS` keys (pasted identifiers, German
+//! compounds) are restored to what was typed and the rest of the token
+//! passes through, so backspace counts never outgrow the result
+
+use gonhanh_core::engine::{Engine, Result, MAX_WORD_KEYS};
+use gonhanh_core::utils::type_word;
+
+fn typed(input: &str, auto_restore: bool) -> String {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(auto_restore);
+    type_word(&mut e, input)
+}
+
+#[test]
+fn boundary() {
+    let tail = MAX_WORD_KEYS - "vieetj".len();
+    let at_limit = format!("vieetj{}", "k".repeat(tail));
+    assert_eq!(typed(&at_limit, false), format!("việt{}", "k".repeat(tail)));
+
+    let past_limit = format!("{}k", at_limit);
+    assert_eq!(typed(&past_limit, false), past_limit);
+}
+
+#[test]
+fn rest_of_token_passes_through() {
+    let input = format!("vieetj{}aas", "k".repeat(MAX_WORD_KEYS));
+    assert_eq!(typed(&input, false), input);
+    let input = "tieengs".repeat(12);
+    for auto_restore in [false, true] {
+        assert_eq!(typed(&input, auto_restore), input);
+    }
+}
+
+#[test]
+fn german_compound() {
+    let word = "Rindfleischetikettierungsueberwachungsaufgabenuebertragungsgesetzes";
+    assert!(word.len() > MAX_WORD_KEYS);
+    assert_eq!(typed(&format!("{} ", word), true), format!("{} ", word));
+}
+
+#[test]
+fn next_word_starts_fresh() {
+    let token = "x".repeat(300);
+    for auto_restore in [false, true] {
+        assert_eq!(
+            typed(&format!("{} vieetj ", token), auto_restore),
+            format!("{} việt ", token)
+        );
+    }
+}
+
+#[test]
+fn backspace_inside_long_token() {
+    let token = format!("tieengs{}", "k".repeat(MAX_WORD_KEYS));
+    assert_eq!(
+        typed(&format!("{}<<<aa vieetj", token), true),
+        format!("{}aa việt", &token[..token.len() - 3])
+    );
+}
+
+#[test]
+fn result_count_fits_u8() {
+    let r = Result::send(0, &['a'; 300]);
+    assert_eq!(r.count, u8::MAX);
+}
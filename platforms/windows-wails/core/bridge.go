@@ -48,25 +48,31 @@ type Bridge struct {
 	mu  sync.Mutex
 
 	// Proc addresses
-	pImeInit               *syscall.Proc
-	pImeClear              *syscall.Proc
-	pImeFree               *syscall.Proc
-	pImeMethod             *syscall.Proc
-	pImeEnabled            *syscall.Proc
-	pImeModern             *syscall.Proc
-	pImeKeyExt             *syscall.Proc
-	pImeSkipWShortcut      *syscall.Proc
-	pImeBracketShortcut    *syscall.Proc
-	pImeEscRestore         *syscall.Proc
-	pImeFreeTone           *syscall.Proc
-	pImeEnglishAutoRestore *syscall.Proc
-	pImeAutoCapitalize     *syscall.Proc
-	pImeClearAll           *syscall.Proc
-	pImeGetBuffer          *syscall.Proc
-	pImeRestoreWord        *syscall.Proc
-	pImeAddShortcut        *syscall.Proc
-	pImeRemoveShortcut     *syscall.Proc
-	pImeClearShortcuts     *syscall.Proc
+	pImeInit                *syscall.Proc
+	pImeClear               *syscall.Proc
+	pImeFree                *syscall.Proc
+	pImeMethod              *syscall.Proc
+	pImeEnabled             *syscall.Proc
+	pImeModern              *syscall.Proc
+	pImeKeyExt              *syscall.Proc
+	pImeSkipWShortcut       *syscall.Proc
+	pImeBracketShortcut     *syscall.Proc
+	pImeEscRestore          *syscall.Proc
+	pImeRestoreKey          *syscall.Proc
+	pImeFreeTone            *syscall.Proc
+	pImeEnglishAutoRestore  *syscall.Proc
+	pImeAutoRestoreTriggers *syscall.Proc
+	pImeCodeContext         *syscall.Proc
+	pImeAutoCapitalize      *syscall.Proc
+	pImeClearAll            *syscall.Proc
+	pImeGetBuffer           *syscall.Proc
+	pImeRestoreWord         *syscall.Proc
+	pImeAddShortcut         *syscall.Proc
+	pImeRemoveShortcut      *syscall.Proc
+	pImeClearShortcuts      *syscall.Proc
+	pImeSetShortcutApps     *syscall.Proc
+	pImeSetActiveApp        *syscall.Proc
+	pImeNotifyPaste         *syscall.Proc
 }
 
 // Global bridge instance
@@ -129,8 +135,11 @@ loaded:
 	b.pImeSkipWShortcut, _ = dll.FindProc("ime_skip_w_shortcut")
 	b.pImeBracketShortcut, _ = dll.FindProc("ime_bracket_shortcut")
 	b.pImeEscRestore, _ = dll.FindProc("ime_esc_restore")
+	b.pImeRestoreKey, _ = dll.FindProc("ime_restore_key")
 	b.pImeFreeTone, _ = dll.FindProc("ime_free_tone")
 	b.pImeEnglishAutoRestore, _ = dll.FindProc("ime_english_auto_restore")
+	b.pImeAutoRestoreTriggers, _ = dll.FindProc("ime_auto_restore_triggers")
+	b.pImeCodeContext, _ = dll.FindProc("ime_code_context")
 	b.pImeAutoCapitalize, _ = dll.FindProc("ime_auto_capitalize")
 	b.pImeClearAll, _ = dll.FindProc("ime_clear_all")
 	b.pImeGetBuffer, _ = dll.FindProc("ime_get_buffer")
@@ -138,6 +147,9 @@ loaded:
 	b.pImeAddShortcut, _ = dll.FindProc("ime_add_shortcut")
 	b.pImeRemoveShortcut, _ = dll.FindProc("ime_remove_shortcut")
 	b.pImeClearShortcuts, _ = dll.FindProc("ime_clear_shortcuts")
+	b.pImeSetShortcutApps, _ = dll.FindProc("ime_set_shortcut_apps")
+	b.pImeSetActiveApp, _ = dll.FindProc("ime_set_active_app")
+	b.pImeNotifyPaste, _ = dll.FindProc("ime_notify_paste")
 
 	return b, nil
 }
@@ -193,7 +205,7 @@ func (b *Bridge) SetEnabled(enabled bool) {
 	}
 }
 
-// SetModernTone sets tone style (modern=true: hòa, old=false: hoà)
+// SetModernTone sets tone style (modern=true: hoà, traditional=false: hòa)
 func (b *Bridge) SetModernTone(modern bool) {
 	if b.pImeModern != nil {
 		b.pImeModern.Call(boolToUintptr(modern))
@@ -221,6 +233,13 @@ func (b *Bridge) SetEscRestore(enabled bool) {
 	}
 }
 
+// SetRestoreKey sets the macOS keycode that restores raw input (default ESC)
+func (b *Bridge) SetRestoreKey(keycode uint16) {
+	if b.pImeRestoreKey != nil {
+		b.pImeRestoreKey.Call(uintptr(keycode))
+	}
+}
+
 // SetFreeTone sets whether to enable free tone placement
 func (b *Bridge) SetFreeTone(enabled bool) {
 	if b.pImeFreeTone != nil {
@@ -235,6 +254,22 @@ func (b *Bridge) SetEnglishAutoRestore(enabled bool) {
 	}
 }
 
+// SetAutoRestoreTriggers sets which word boundaries trigger auto-restore
+// (bitmask: 0x01 Space, 0x02 Tab, 0x04 Enter, 0x08 punctuation)
+func (b *Bridge) SetAutoRestoreTriggers(triggers uint8) {
+	if b.pImeAutoRestoreTriggers != nil {
+		b.pImeAutoRestoreTriggers.Call(uintptr(triggers))
+	}
+}
+
+// SetCodeContext sets whether identifiers (snake_case, camelCase, utf8) are
+// typed without transforms; call on focus change for IDEs and terminals
+func (b *Bridge) SetCodeContext(enabled bool) {
+	if b.pImeCodeContext != nil {
+		b.pImeCodeContext.Call(boolToUintptr(enabled))
+	}
+}
+
 // SetAutoCapitalize sets whether to auto-capitalize after sentence-ending punctuation
 func (b *Bridge) SetAutoCapitalize(enabled bool) {
 	if b.pImeAutoCapitalize != nil {
@@ -323,6 +358,50 @@ func (b *Bridge) ClearShortcuts() {
 	}
 }
 
+// SetShortcutApps limits a shortcut to some apps (only) or keeps it out of
+// others (never). Both are comma-separated process names, e.g. "outlook.exe".
+func (b *Bridge) SetShortcutApps(trigger, only, never string) {
+	if b.pImeSetShortcutApps == nil {
+		return
+	}
+
+	triggerBytes := append([]byte(trigger), 0)
+	onlyBytes := append([]byte(only), 0)
+	neverBytes := append([]byte(never), 0)
+
+	b.pImeSetShortcutApps.Call(
+		uintptr(unsafe.Pointer(&triggerBytes[0])),
+		uintptr(unsafe.Pointer(&onlyBytes[0])),
+		uintptr(unsafe.Pointer(&neverBytes[0])),
+	)
+}
+
+// SetActiveApp tells the engine which app is in the foreground (process name)
+func (b *Bridge) SetActiveApp(processName string) {
+	if b.pImeSetActiveApp == nil {
+		return
+	}
+
+	nameBytes := append([]byte(processName), 0)
+	b.pImeSetActiveApp.Call(uintptr(unsafe.Pointer(&nameBytes[0])))
+}
+
+// NotifyPaste tells the engine text was inserted at the caret
+// (empty text = unknown). Falls back to ClearAll with older DLLs.
+func (b *Bridge) NotifyPaste(text string) {
+	if b.pImeNotifyPaste == nil {
+		b.ClearAll()
+		return
+	}
+
+	if text == "" {
+		b.pImeNotifyPaste.Call(0)
+		return
+	}
+	textBytes := append([]byte(text), 0)
+	b.pImeNotifyPaste.Call(uintptr(unsafe.Pointer(&textBytes[0])))
+}
+
 // RestoreWord restores a word to the buffer for continued editing
 func (b *Bridge) RestoreWord(word string) {
 	if b.pImeRestoreWord == nil {
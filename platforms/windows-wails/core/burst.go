@@ -0,0 +1,51 @@
+package core
+
+// Burst detection: keys arriving faster than anyone types
+//
+// Auto-typers, password managers and apps that paste by sending keystrokes
+// deliver keys a few milliseconds apart. Fed to the IME, a pasted "aa"
+// becomes "â" and the pasted content is corrupted, so keys of a burst pass
+// through untouched.
+//
+// Two rolled physical keys can land within a few ms of each other, so one
+// short gap proves nothing; a burst needs BurstMinRun short gaps in a row.
+// The keys before that run is complete still go through the IME.
+
+// BurstIntervalUs is the largest gap (µs) between two keys of a burst.
+// Synthetic senders typically pace keys 0-16ms apart.
+const BurstIntervalUs = 20_000
+
+// BurstMinRun is how many short gaps in a row start a burst
+const BurstMinRun = 3
+
+// BurstDetector tracks key timestamps (µs, from QueryPerformanceCounter:
+// KBDLLHOOKSTRUCT.Time only has the ~15.6ms tick resolution)
+type BurstDetector struct {
+	lastTime int64
+	hasLast  bool
+	run      int
+	inBurst  bool
+}
+
+// Observe records a key down at timeUs.
+// inBurst: the key belongs to a burst and should pass through.
+// changed: a burst started or ended with this key, so the engine no longer
+// knows the text around the caret.
+func (d *BurstDetector) Observe(timeUs int64) (inBurst, changed bool) {
+	if d.hasLast && timeUs-d.lastTime <= BurstIntervalUs {
+		d.run++
+	} else {
+		d.run = 0
+	}
+	inBurst = d.run >= BurstMinRun
+	changed = inBurst != d.inBurst
+	d.lastTime = timeUs
+	d.hasLast = true
+	d.inBurst = inBurst
+	return inBurst, changed
+}
+
+// Reset forgets the last key (e.g. after a focus change)
+func (d *BurstDetector) Reset() {
+	*d = BurstDetector{}
+}
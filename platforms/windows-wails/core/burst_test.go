@@ -0,0 +1,89 @@
+package core
+
+import "testing"
+
+// observeAll feeds key times (ms) to a fresh detector and returns inBurst per key
+func observeAll(times []int64) []bool {
+	var d BurstDetector
+	got := make([]bool, len(times))
+	for i, ms := range times {
+		got[i], _ = d.Observe(ms * 1000)
+	}
+	return got
+}
+
+func TestBurstDetector_Observe(t *testing.T) {
+	tests := []struct {
+		name     string
+		times    []int64
+		expected []bool
+	}{
+		{
+			name:     "human typing",
+			times:    []int64{0, 120, 230, 310, 450},
+			expected: []bool{false, false, false, false, false},
+		},
+		{
+			name:     "two rolled keys in the same tick",
+			times:    []int64{0, 0, 150, 160, 300},
+			expected: []bool{false, false, false, false, false},
+		},
+		{
+			name:     "two short gaps are not enough",
+			times:    []int64{0, 5, 10, 200},
+			expected: []bool{false, false, false, false},
+		},
+		{
+			name:     "synthetic keys 1ms apart",
+			times:    []int64{0, 1, 2, 3, 4, 5},
+			expected: []bool{false, false, false, true, true, true},
+		},
+		{
+			name:     "synthetic keys 16ms apart",
+			times:    []int64{0, 16, 32, 48, 64},
+			expected: []bool{false, false, false, true, true},
+		},
+		{
+			name:     "long gap ends the burst",
+			times:    []int64{0, 1, 2, 3, 500, 501},
+			expected: []bool{false, false, false, true, false, false},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got := observeAll(tt.times)
+			for i := range got {
+				if got[i] != tt.expected[i] {
+					t.Errorf("key %d: inBurst = %v, want %v (all: %v)", i, got[i], tt.expected[i], got)
+					break
+				}
+			}
+		})
+	}
+}
+
+func TestBurstDetector_Changed(t *testing.T) {
+	var d BurstDetector
+	var changes []int
+	for i, ms := range []int64{0, 1, 2, 3, 4, 300} {
+		if _, changed := d.Observe(ms * 1000); changed {
+			changes = append(changes, i)
+		}
+	}
+	// Burst starts on the 4th key and ends on the key after the long gap
+	if len(changes) != 2 || changes[0] != 3 || changes[1] != 5 {
+		t.Errorf("changes at keys %v, want [3 5]", changes)
+	}
+}
+
+func TestBurstDetector_Reset(t *testing.T) {
+	var d BurstDetector
+	for _, ms := range []int64{0, 1, 2, 3} {
+		d.Observe(ms * 1000)
+	}
+	d.Reset()
+	if inBurst, changed := d.Observe(4000); inBurst || changed {
+		t.Errorf("after Reset: inBurst=%v changed=%v, want false false", inBurst, changed)
+	}
+}
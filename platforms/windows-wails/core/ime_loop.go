@@ -187,6 +187,7 @@ func (l *ImeLoop) processKey(keyCode uint16, shift, capsLock bool) bool {
 	// Check if foreground app changed - if so, clear buffer and invalidate caches
 	if AppChanged() {
 		l.bridge.Clear()
+		l.bridge.SetActiveApp(GetCurrentProcessName())
 		l.coalescer.Flush()
 		InvalidateSmartProfileCache()
 	}
@@ -69,8 +69,28 @@ var (
 	procGetKeyState         = user32.NewProc("GetKeyState")
 	procGetAsyncKeyState    = user32.NewProc("GetAsyncKeyState")
 	procMessageBeep         = user32.NewProc("MessageBeep")
+
+	procQueryPerformanceCounter   = kernel32.NewProc("QueryPerformanceCounter")
+	procQueryPerformanceFrequency = kernel32.NewProc("QueryPerformanceFrequency")
 )
 
+// qpcFrequency is the performance counter's ticks per second (fixed at boot)
+var qpcFrequency = func() int64 {
+	var freq int64
+	procQueryPerformanceFrequency.Call(uintptr(unsafe.Pointer(&freq)))
+	return freq
+}()
+
+// nowMicros returns a monotonic timestamp in µs from QueryPerformanceCounter
+func nowMicros() int64 {
+	var count int64
+	procQueryPerformanceCounter.Call(uintptr(unsafe.Pointer(&count)))
+	if qpcFrequency <= 0 {
+		return 0
+	}
+	return count/qpcFrequency*1_000_000 + count%qpcFrequency*1_000_000/qpcFrequency
+}
+
 // InjectedKeyMarker identifies keys we injected (to skip processing)
 // "FKEY" in hex: 0x464B4559
 var InjectedKeyMarker = uintptr(0x464B4559)
@@ -93,6 +113,9 @@ type KeyboardHook struct {
 	consumedMu sync.Mutex
 	consumed   map[uint16]bool
 
+	// Synthetic key bursts (paste-as-keystrokes) bypass the IME
+	burst BurstDetector
+
 	// Callbacks
 	OnKeyPressed func(keyCode uint16, shift, capsLock bool) bool // returns true if handled
 	OnHotkey     func()
@@ -404,6 +427,20 @@ func (h *KeyboardHook) hookCallback(nCode int, wParam uintptr, lParam uintptr) u
 				return ret
 			}
 
+			// Keys of a burst pass through untouched; the engine is reset at
+			// both ends since it never sees the burst's text
+			inBurst, changed := h.burst.Observe(nowMicros())
+			if changed {
+				bridge, _ := GetBridge()
+				if bridge != nil {
+					bridge.NotifyPaste("")
+				}
+			}
+			if inBurst {
+				ret, _, _ := procCallNextHookEx.Call(h.hookID, uintptr(nCode), wParam, lParam)
+				return ret
+			}
+
 			// Process the key through IME callback
 			if h.OnKeyPressed != nil {
 				h.mu.Lock()
@@ -780,3 +780,34 @@ func TestKeyboardHookConsumedMap(t *testing.T) {
 	// The hook should be created successfully with consumed map initialized
 	// We can't directly test the map since it's private, but we verify hook creation works
 }
+
+func TestBurstDetector(t *testing.T) {
+	var d core.BurstDetector
+	steps := []struct {
+		time    uint32
+		inBurst bool
+		changed bool
+	}{
+		{1000, false, false}, // first key
+		{1150, false, false}, // human typing
+		{1152, true, true},   // burst starts
+		{1153, true, false},
+		{1153 + core.BurstIntervalMs, true, false},
+		{1300, false, true}, // burst over
+		{1450, false, false},
+	}
+	for i, s := range steps {
+		inBurst, changed := d.Observe(s.time)
+		if inBurst != s.inBurst || changed != s.changed {
+			t.Errorf("step %d: Observe(%d) = (%v, %v), want (%v, %v)",
+				i, s.time, inBurst, changed, s.inBurst, s.changed)
+		}
+	}
+
+	// Tick count wrapping around
+	d.Reset()
+	d.Observe(0xFFFFFFFE)
+	if inBurst, _ := d.Observe(1); !inBurst {
+		t.Error("burst across tick wraparound not detected")
+	}
+}